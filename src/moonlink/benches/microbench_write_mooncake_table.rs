@@ -57,6 +57,7 @@ fn bench_write_mooncake_table(c: &mut Criterion) {
         filesystem_config: moonlink::FileSystemConfig::FileSystem {
             root_directory: warehouse_location.clone(),
         },
+        ..Default::default()
     };
     let rt = Runtime::new().unwrap();
     let table_config = MooncakeTableConfig::new(temp_dir.path().to_str().unwrap().to_string());
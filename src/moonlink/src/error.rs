@@ -48,6 +48,12 @@ pub enum Error {
 
     #[error("JSON serialization/deserialization error: {source}")]
     Json { source: Arc<serde_json::Error> },
+
+    #[error("Unsupported schema change: {0}")]
+    UnsupportedSchemaChange(String),
+
+    #[error("Table failed: {0}")]
+    TableFailed(String),
 }
 
 pub type Result<T> = result::Result<T, Error>;
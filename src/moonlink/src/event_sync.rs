@@ -1,4 +1,5 @@
 /// This module contains sender and receiver for table events synchronization.
+use std::collections::HashSet;
 use tokio::sync::{broadcast, oneshot, watch};
 
 use crate::Result;
@@ -13,6 +14,12 @@ pub struct EventSyncReceiver {
     pub force_snapshot_completion_rx: watch::Receiver<Option<Result<u64>>>,
     /// Used to create notifier when force table maintenance operation completes.
     pub table_maintenance_completion_tx: broadcast::Sender<Result<()>>,
+    /// Get notified when finished initial copy has been persisted.
+    pub initial_copy_completion_rx: watch::Receiver<bool>,
+    /// Get notified when the table fails, with the error which stops its replication.
+    pub table_error_rx: watch::Receiver<Option<String>>,
+    /// Get notified with GIDs of prepared transactions, which have been persisted into table WAL.
+    pub prepared_gids_rx: watch::Receiver<HashSet<String>>,
 }
 
 /// Contains a few senders, which notifies after certain iceberg events completion.
@@ -29,6 +36,12 @@ pub struct EventSyncSender {
     pub force_snapshot_completion_tx: watch::Sender<Option<Result<u64>>>,
     /// Notifies when force table maintenance operation completes.
     pub table_maintenance_completion_tx: broadcast::Sender<Result<()>>,
+    /// Notifies when finished initial copy has been persisted.
+    pub initial_copy_completion_tx: watch::Sender<bool>,
+    /// Notifies when the table fails, with the error which stops its replication.
+    pub table_error_tx: watch::Sender<Option<String>>,
+    /// Notifies GIDs of prepared transactions, which have been persisted into table WAL.
+    pub prepared_gids_tx: watch::Sender<HashSet<String>>,
 }
 
 /// Create table event manager sender and receiver.
@@ -37,17 +50,26 @@ pub fn create_table_event_syncer() -> (EventSyncSender, EventSyncReceiver) {
     let (flush_lsn_tx, flush_lsn_rx) = watch::channel(0u64);
     let (force_snapshot_completion_tx, force_snapshot_completion_rx) = watch::channel(None);
     let (table_maintenance_completion_tx, _) = broadcast::channel(64usize);
+    let (initial_copy_completion_tx, initial_copy_completion_rx) = watch::channel(false);
+    let (table_error_tx, table_error_rx) = watch::channel(None);
+    let (prepared_gids_tx, prepared_gids_rx) = watch::channel(HashSet::new());
     let event_sync_sender = EventSyncSender {
         drop_table_completion_tx,
         flush_lsn_tx,
         force_snapshot_completion_tx: force_snapshot_completion_tx.clone(),
         table_maintenance_completion_tx: table_maintenance_completion_tx.clone(),
+        initial_copy_completion_tx,
+        table_error_tx,
+        prepared_gids_tx,
     };
     let event_sync_receiver = EventSyncReceiver {
         drop_table_completion_rx,
         flush_lsn_rx,
         force_snapshot_completion_rx,
         table_maintenance_completion_tx,
+        initial_copy_completion_rx,
+        table_error_rx,
+        prepared_gids_rx,
     };
     (event_sync_sender, event_sync_receiver)
 }
@@ -11,10 +11,16 @@ pub use event_sync::EventSyncSender;
 pub use storage::storage_utils::create_data_file;
 pub(crate) use storage::NonEvictableHandle;
 pub use storage::{
-    EventSyncReceiver, FileSystemAccessor, FileSystemConfig, IcebergTableConfig,
-    IcebergTableManager, MooncakeTable, MooncakeTableConfig, MoonlinkSecretType,
-    MoonlinkTableConfig, MoonlinkTableSecret, ObjectStorageCache, ObjectStorageCacheConfig,
-    SnapshotReadOutput, TableEventManager, TableManager, TableStatus, TableStatusReader,
+    AlterTableRequest, CopiedKeyRange, EventSyncReceiver, FileSystemAccessor, FileSystemConfig,
+    IcebergCatalogConfig, IcebergDeleteFileFormat, IcebergTableConfig, IcebergTableManager,
+    InitialCopyPosition, InitialCopyProgress, ManifestCompactionConfig, MooncakeTable,
+    MooncakeTableConfig, MoonlinkSecretType, MoonlinkTableConfig, MoonlinkTableSecret,
+    ObjectStorageCache, ObjectStorageCacheConfig, ParquetBloomFilterConfig,
+    ParquetCompressionCodec, ParquetSortField, ParquetSortStrategy, ParquetStatisticsLevel,
+    ParquetWriterConfig, PartitionField, PartitionSpec, PartitionTransform,
+    ReplicationConnectionStatus, ReplicationConnectionUsage, ReplicationSlotStatus,
+    SnapshotReadOutput, SnapshotRetentionConfig, TableEventManager, TableManager, TableStatus,
+    TableStatusReader,
 };
 pub use table_handler::TableHandler;
 pub use table_notify::TableEvent;
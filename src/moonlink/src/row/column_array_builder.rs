@@ -4,10 +4,12 @@ use arrow::array::builder::{
     BinaryBuilder, BooleanBuilder, NullBufferBuilder, PrimitiveBuilder, StringBuilder,
 };
 use arrow::array::types::{Decimal128Type, Float32Type, Float64Type, Int32Type, Int64Type};
-use arrow::array::{ArrayBuilder, ArrayRef, FixedSizeBinaryBuilder, ListArray};
+use arrow::array::{
+    ArrayBuilder, ArrayRef, FixedSizeBinaryBuilder, ListArray, MapArray, StructArray,
+};
 use arrow::buffer::OffsetBuffer;
 use arrow::compute::kernels::cast;
-use arrow::datatypes::DataType;
+use arrow::datatypes::{DataType, FieldRef, Fields};
 use std::mem::take;
 use std::sync::Arc;
 
@@ -21,9 +23,9 @@ impl ArrayBuilderHelper {
         self.offset_builder.push(value);
         self.null_builder.append_non_null();
     }
-    fn push_null(&mut self) {
-        self.offset_builder
-            .push(self.offset_builder.last().copied().unwrap_or(0));
+    /// Null list starts at the current end of values, so it doesn't take items from the previous list.
+    fn push_null(&mut self, value: i32) {
+        self.offset_builder.push(value);
         self.null_builder.append_null();
     }
 }
@@ -38,6 +40,23 @@ pub(crate) enum ColumnArrayBuilder {
     Utf8(StringBuilder, Option<ArrayBuilderHelper>),
     FixedSizeBinary(FixedSizeBinaryBuilder, Option<ArrayBuilderHelper>),
     Binary(BinaryBuilder, Option<ArrayBuilderHelper>),
+    /// Struct value is represented as [`RowValue::Struct`], with one value for each field.
+    Struct(Vec<ColumnArrayBuilder>, NullBufferBuilder),
+    /// Map value is represented as [`RowValue::Array`] of entries, each entry is a [`RowValue::Struct`] of key and value.
+    Map {
+        keys: Box<ColumnArrayBuilder>,
+        values: Box<ColumnArrayBuilder>,
+        num_entries: i32,
+        array_helper: ArrayBuilderHelper,
+    },
+}
+
+/// Get key and value fields for the given map entries field.
+fn get_map_key_value_fields(entries: &FieldRef) -> (&FieldRef, &FieldRef) {
+    match entries.data_type() {
+        DataType::Struct(fields) if fields.len() == 2 => (&fields[0], &fields[1]),
+        _ => panic!("Map entries should be a struct of key and value"),
+    }
 }
 
 impl ColumnArrayBuilder {
@@ -95,6 +114,37 @@ impl ColumnArrayBuilder {
                 array_builder,
             ),
             DataType::List(inner) => ColumnArrayBuilder::new(inner.data_type(), capacity, true),
+            DataType::Struct(fields) => {
+                assert!(!is_list, "List of struct is not supported");
+                ColumnArrayBuilder::Struct(
+                    fields
+                        .iter()
+                        .map(|field| ColumnArrayBuilder::new(field.data_type(), capacity, false))
+                        .collect(),
+                    NullBufferBuilder::new(capacity),
+                )
+            }
+            DataType::Map(entries, _) => {
+                assert!(!is_list, "List of map is not supported");
+                let (key_field, value_field) = get_map_key_value_fields(entries);
+                ColumnArrayBuilder::Map {
+                    keys: Box::new(ColumnArrayBuilder::new(
+                        key_field.data_type(),
+                        capacity,
+                        false,
+                    )),
+                    values: Box::new(ColumnArrayBuilder::new(
+                        value_field.data_type(),
+                        capacity,
+                        false,
+                    )),
+                    num_entries: 0,
+                    array_helper: ArrayBuilderHelper {
+                        offset_builder: Vec::with_capacity(capacity),
+                        null_builder: NullBufferBuilder::new(capacity),
+                    },
+                }
+            }
             _ => panic!("data type: {data_type:?}"),
         }
     }
@@ -116,7 +166,7 @@ impl ColumnArrayBuilder {
                     }
                     RowValue::Null => {
                         if let Some(helper) = array_helper.as_mut() {
-                            helper.push_null();
+                            helper.push_null(builder.len() as i32);
                         } else {
                             builder.append_null();
                         }
@@ -140,7 +190,7 @@ impl ColumnArrayBuilder {
                     }
                     RowValue::Null => {
                         if let Some(helper) = array_helper.as_mut() {
-                            helper.push_null();
+                            helper.push_null(builder.len() as i32);
                         } else {
                             builder.append_null();
                         }
@@ -164,7 +214,7 @@ impl ColumnArrayBuilder {
                     }
                     RowValue::Null => {
                         if let Some(helper) = array_helper.as_mut() {
-                            helper.push_null();
+                            helper.push_null(builder.len() as i32);
                         } else {
                             builder.append_null();
                         }
@@ -188,7 +238,7 @@ impl ColumnArrayBuilder {
                     }
                     RowValue::Null => {
                         if let Some(helper) = array_helper.as_mut() {
-                            helper.push_null();
+                            helper.push_null(builder.len() as i32);
                         } else {
                             builder.append_null();
                         }
@@ -212,7 +262,7 @@ impl ColumnArrayBuilder {
                     }
                     RowValue::Null => {
                         if let Some(helper) = array_helper.as_mut() {
-                            helper.push_null();
+                            helper.push_null(builder.len() as i32);
                         } else {
                             builder.append_null();
                         }
@@ -236,7 +286,7 @@ impl ColumnArrayBuilder {
                     }
                     RowValue::Null => {
                         if let Some(helper) = array_helper.as_mut() {
-                            helper.push_null();
+                            helper.push_null(builder.len() as i32);
                         } else {
                             builder.append_null();
                         }
@@ -263,7 +313,7 @@ impl ColumnArrayBuilder {
                     }
                     RowValue::Null => {
                         if let Some(helper) = array_helper.as_mut() {
-                            helper.push_null();
+                            helper.push_null(builder.len() as i32);
                         } else {
                             builder.append_null();
                         }
@@ -289,7 +339,7 @@ impl ColumnArrayBuilder {
                     }
                     RowValue::Null => {
                         if let Some(helper) = array_helper.as_mut() {
-                            helper.push_null();
+                            helper.push_null(builder.len() as i32);
                         } else {
                             builder.append_null();
                         }
@@ -313,7 +363,7 @@ impl ColumnArrayBuilder {
                     }
                     RowValue::Null => {
                         if let Some(helper) = array_helper.as_mut() {
-                            helper.push_null();
+                            helper.push_null(builder.len() as i32);
                         } else {
                             builder.append_null();
                         }
@@ -322,6 +372,50 @@ impl ColumnArrayBuilder {
                 };
                 Ok(())
             }
+            ColumnArrayBuilder::Struct(builders, null_builder) => {
+                match value {
+                    RowValue::Struct(v) => {
+                        assert_eq!(v.len(), builders.len());
+                        for (builder, v) in builders.iter_mut().zip(v.iter()) {
+                            builder.append_value(v)?;
+                        }
+                        null_builder.append_non_null();
+                    }
+                    RowValue::Null => {
+                        for builder in builders.iter_mut() {
+                            builder.append_value(&RowValue::Null)?;
+                        }
+                        null_builder.append_null();
+                    }
+                    _ => unreachable!("Struct expected from well-typed input"),
+                };
+                Ok(())
+            }
+            ColumnArrayBuilder::Map {
+                keys,
+                values,
+                num_entries,
+                array_helper,
+            } => {
+                match value {
+                    RowValue::Array(v) => {
+                        array_helper.push(*num_entries);
+                        for entry in v.iter() {
+                            match entry {
+                                RowValue::Struct(entry) if entry.len() == 2 => {
+                                    keys.append_value(&entry[0])?;
+                                    values.append_value(&entry[1])?;
+                                }
+                                _ => unreachable!("Map entry expected from well-typed input"),
+                            }
+                        }
+                        *num_entries += v.len() as i32;
+                    }
+                    RowValue::Null => array_helper.push_null(*num_entries),
+                    _ => unreachable!("Map expected from well-typed input"),
+                };
+                Ok(())
+            }
         }
     }
     /// Finish building and return the array
@@ -354,6 +448,52 @@ impl ColumnArrayBuilder {
             ColumnArrayBuilder::Binary(builder, array_helper) => {
                 (Arc::new(builder.finish()), array_helper)
             }
+            ColumnArrayBuilder::Struct(builders, null_builder) => {
+                let fields = match logical_type {
+                    DataType::Struct(fields) => fields,
+                    _ => panic!("Struct expected from well-typed input"),
+                };
+                let columns = builders
+                    .iter_mut()
+                    .zip(fields.iter())
+                    .map(|(builder, field)| builder.finish(field.data_type()))
+                    .collect();
+                return Arc::new(StructArray::new(
+                    fields.clone(),
+                    columns,
+                    null_builder.finish(),
+                ));
+            }
+            ColumnArrayBuilder::Map {
+                keys,
+                values,
+                num_entries,
+                array_helper,
+            } => {
+                let (entries_field, sorted) = match logical_type {
+                    DataType::Map(entries_field, sorted) => (entries_field, *sorted),
+                    _ => panic!("Map expected from well-typed input"),
+                };
+                let (key_field, value_field) = get_map_key_value_fields(entries_field);
+                let entries = StructArray::new(
+                    Fields::from(vec![key_field.clone(), value_field.clone()]),
+                    vec![
+                        keys.finish(key_field.data_type()),
+                        values.finish(value_field.data_type()),
+                    ],
+                    None,
+                );
+                let mut offset_array = take(&mut array_helper.offset_builder);
+                offset_array.push(*num_entries);
+                *num_entries = 0;
+                return Arc::new(MapArray::new(
+                    entries_field.clone(),
+                    OffsetBuffer::new(offset_array.into()),
+                    entries,
+                    array_helper.null_builder.finish(),
+                    sorted,
+                ));
+            }
         };
         if let Some(helper) = array_helper.as_mut() {
             let mut offset_array = take(&mut helper.offset_builder);
@@ -363,6 +503,13 @@ impl ColumnArrayBuilder {
                 DataType::List(inner) => inner,
                 _ => panic!("List expected from well-typed input"),
             };
+            // Items are built with a wider physical type for certain types (i.e. int16), cast to the declared one.
+            let array = cast(&array, inner_field.data_type()).unwrap_or_else(|_| {
+                panic!(
+                    "Fail to cast to correct type in ColumnArrayBuilder::finish for {:?}",
+                    inner_field.data_type()
+                )
+            });
             let list_array = ListArray::new(
                 inner_field.clone(),
                 OffsetBuffer::new(offset_array.into()),
@@ -384,8 +531,8 @@ impl ColumnArrayBuilder {
 mod tests {
     use super::*;
     use arrow::array::{
-        Array, BooleanArray, FixedSizeBinaryArray, Float32Array, Float64Array, Int32Array,
-        Int64Array, StringArray,
+        Array, BooleanArray, FixedSizeBinaryArray, Float32Array, Float64Array, Int16Array,
+        Int32Array, Int64Array, StringArray,
     };
     use arrow::datatypes::DataType;
     #[test]
@@ -560,4 +707,119 @@ mod tests {
         assert_eq!(second_int_array.value(0), 4);
         assert_eq!(second_int_array.value(1), 5);
     }
+
+    #[test]
+    fn test_column_array_builder_int16_list() {
+        let list_type = DataType::List(Arc::new(arrow::datatypes::Field::new(
+            "item",
+            DataType::Int16,
+            true,
+        )));
+        let mut builder = ColumnArrayBuilder::new(&list_type, 2, true);
+        builder
+            .append_value(&RowValue::Array(vec![RowValue::Int32(1), RowValue::Null]))
+            .unwrap();
+        builder.append_value(&RowValue::Null).unwrap();
+        let array = builder.finish(&list_type);
+        assert_eq!(array.data_type(), &list_type);
+        let list_array = array.as_any().downcast_ref::<ListArray>().unwrap();
+        let first_list = list_array.value(0);
+        let first_int_array = first_list.as_any().downcast_ref::<Int16Array>().unwrap();
+        assert_eq!(first_int_array.value(0), 1);
+        assert!(first_int_array.is_null(1));
+        assert!(list_array.is_null(1));
+    }
+
+    #[test]
+    fn test_column_array_builder_struct() {
+        let struct_type = DataType::Struct(Fields::from(vec![
+            arrow::datatypes::Field::new("lower", DataType::Int32, true),
+            arrow::datatypes::Field::new("upper", DataType::Int32, true),
+        ]));
+        let mut builder = ColumnArrayBuilder::new(&struct_type, 3, false);
+        builder
+            .append_value(&RowValue::Struct(vec![RowValue::Int32(1), RowValue::Null]))
+            .unwrap();
+        builder.append_value(&RowValue::Null).unwrap();
+        builder
+            .append_value(&RowValue::Struct(vec![
+                RowValue::Int32(3),
+                RowValue::Int32(4),
+            ]))
+            .unwrap();
+        let array = builder.finish(&struct_type);
+        let struct_array = array.as_any().downcast_ref::<StructArray>().unwrap();
+        assert_eq!(struct_array.len(), 3);
+        assert!(struct_array.is_null(1));
+        let lower = struct_array
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        let upper = struct_array
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(lower.value(0), 1);
+        assert!(upper.is_null(0));
+        assert_eq!(lower.value(2), 3);
+        assert_eq!(upper.value(2), 4);
+    }
+
+    #[test]
+    fn test_column_array_builder_map() {
+        let map_type = DataType::Map(
+            Arc::new(arrow::datatypes::Field::new(
+                "entries",
+                DataType::Struct(Fields::from(vec![
+                    arrow::datatypes::Field::new("key", DataType::Utf8, false),
+                    arrow::datatypes::Field::new("value", DataType::Utf8, true),
+                ])),
+                false,
+            )),
+            false,
+        );
+        let entry = |key: &str, value: Option<&str>| {
+            RowValue::Struct(vec![
+                RowValue::ByteArray(key.as_bytes().to_vec()),
+                value
+                    .map(|value| RowValue::ByteArray(value.as_bytes().to_vec()))
+                    .unwrap_or(RowValue::Null),
+            ])
+        };
+        // Builders are reused after finish, so check offsets get reset.
+        let mut builder = ColumnArrayBuilder::new(&map_type, 3, false);
+        for _ in 0..2 {
+            builder
+                .append_value(&RowValue::Array(vec![
+                    entry("a", Some("1")),
+                    entry("b", None),
+                ]))
+                .unwrap();
+            builder.append_value(&RowValue::Null).unwrap();
+            builder
+                .append_value(&RowValue::Array(vec![entry("c", Some("3"))]))
+                .unwrap();
+            let array = builder.finish(&map_type);
+            let map_array = array.as_any().downcast_ref::<MapArray>().unwrap();
+            assert_eq!(map_array.len(), 3);
+            assert_eq!(map_array.value_offsets(), &[0, 2, 2, 3]);
+            assert!(map_array.is_null(1));
+            let keys = map_array
+                .keys()
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            let values = map_array
+                .values()
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            assert_eq!(keys.value(0), "a");
+            assert_eq!(values.value(0), "1");
+            assert!(values.is_null(1));
+            assert_eq!(keys.value(2), "c");
+        }
+    }
 }
@@ -1,5 +1,6 @@
 pub(crate) mod async_bitwriter;
 pub(crate) mod cache;
+pub(crate) mod clustering_utils;
 pub(crate) mod compaction;
 pub(crate) mod filesystem;
 mod iceberg;
@@ -7,6 +8,7 @@ pub(crate) mod index;
 pub(crate) mod io_utils;
 pub(crate) mod mooncake_table;
 pub(crate) mod parquet_utils;
+pub(crate) mod parquet_writer_config;
 pub(crate) mod path_utils;
 pub(crate) mod storage_utils;
 #[allow(dead_code)]
@@ -18,19 +20,33 @@ pub(crate) use cache::object_storage::cache_handle::NonEvictableHandle;
 pub use cache::object_storage::object_storage_cache::ObjectStorageCache;
 pub use filesystem::accessor::filesystem_accessor::FileSystemAccessor;
 pub use filesystem::filesystem_config::FileSystemConfig;
-pub use iceberg::iceberg_table_config::IcebergTableConfig;
+pub use iceberg::iceberg_table_config::{
+    IcebergCatalogConfig, IcebergDeleteFileFormat, IcebergTableConfig,
+};
 pub use iceberg::iceberg_table_manager::IcebergTableManager;
+pub use iceberg::manifest_compaction_config::ManifestCompactionConfig;
+pub use iceberg::snapshot_retention_config::SnapshotRetentionConfig;
 pub use iceberg::table_event_manager::TableEventManager;
+pub(crate) use iceberg::table_manager::ExpireSnapshotsResult;
 pub use iceberg::table_manager::TableManager;
+pub use mooncake_table::initial_copy_position::{CopiedKeyRange, InitialCopyPosition};
+pub use mooncake_table::partition_spec::{PartitionField, PartitionSpec, PartitionTransform};
 pub use mooncake_table::table_config::TableConfig as MoonlinkTableConfig;
 pub use mooncake_table::table_secret::{
     SecretEntry as MoonlinkTableSecret, SecretType as MoonlinkSecretType,
 };
-pub use mooncake_table::table_status::TableStatus;
+pub use mooncake_table::table_status::{
+    InitialCopyProgress, ReplicationConnectionStatus, ReplicationConnectionUsage,
+    ReplicationSlotStatus, TableStatus,
+};
 pub use mooncake_table::table_status_reader::TableStatusReader;
 pub use mooncake_table::SnapshotReadOutput;
-pub use mooncake_table::{MooncakeTable, MooncakeTableConfig};
+pub use mooncake_table::{AlterTableRequest, MooncakeTable, MooncakeTableConfig};
 pub(crate) use mooncake_table::{PuffinDeletionBlobAtRead, SnapshotTableState};
+pub use parquet_writer_config::{
+    ParquetBloomFilterConfig, ParquetCompressionCodec, ParquetSortField, ParquetSortStrategy,
+    ParquetStatisticsLevel, ParquetWriterConfig,
+};
 
 #[cfg(test)]
 pub(crate) use iceberg::puffin_utils::*;
@@ -344,6 +344,15 @@ impl ObjectStorageCache {
         })
     }
 
+    /// Return whether the given file is pinned as cache file, which happens when remote file is used as local cache with local filesystem optimization.
+    pub(crate) async fn is_cache_filepath_pinned(&self, filepath: &str) -> bool {
+        let guard = self.cache.read().await;
+        guard
+            .non_evictable_cache
+            .values()
+            .any(|cache_entry| cache_entry.cache_entry.cache_filepath == filepath)
+    }
+
     /// ================================
     /// Test/bench util functions
     /// ================================
@@ -0,0 +1,210 @@
+/// This module contains utils to cluster rows along space-filling curves over multiple columns.
+///
+/// Each column is mapped to its rank within the record batch, so columns of any sortable type contribute equally;
+/// ranks are then combined into one curve key per row, and rows are ordered by the key.
+use crate::storage::parquet_writer_config::{ParquetSortField, ParquetSortStrategy};
+use crate::Result;
+
+use arrow::compute::{rank, SortOptions};
+use arrow_array::RecordBatch;
+use parquet::errors::ParquetError;
+
+/// Number of bits in a curve key.
+const CURVE_KEY_BITS: u32 = 128;
+/// Max number of bits taken by each column, which is enough to hold any row rank.
+const MAX_BITS_PER_COLUMN: u32 = 32;
+
+/// Interleave bits of all coordinates into one key, from the most significant bit.
+fn interleave_bits(coordinates: &[u32], bits: u32) -> u128 {
+    let mut key: u128 = 0;
+    for bit in (0..bits).rev() {
+        for coordinate in coordinates.iter() {
+            key = (key << 1) | ((*coordinate >> bit) & 1) as u128;
+        }
+    }
+    key
+}
+
+/// Get Z-order key for the given coordinates, each of which takes [`bits`] bits.
+pub(crate) fn get_z_order_key(coordinates: &[u32], bits: u32) -> u128 {
+    interleave_bits(coordinates, bits)
+}
+
+/// Get Hilbert key for the given coordinates, each of which takes [`bits`] bits.
+///
+/// Coordinates are transposed into Hilbert index in place, following John Skilling, "Programming the Hilbert curve".
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn get_hilbert_key(coordinates: &[u32], bits: u32) -> u128 {
+    let mut x = coordinates.to_vec();
+    let n = x.len();
+    let m = 1u32 << (bits - 1);
+
+    // Inverse undo excess work.
+    let mut q = m;
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..n {
+            if x[i] & q != 0 {
+                x[0] ^= p;
+            } else {
+                let t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+
+    // Gray encode.
+    for i in 1..n {
+        x[i] ^= x[i - 1];
+    }
+    let mut t = 0;
+    let mut q = m;
+    while q > 1 {
+        if x[n - 1] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    for cur_x in x.iter_mut() {
+        *cur_x ^= t;
+    }
+
+    interleave_bits(&x, bits)
+}
+
+/// Get curve keys for all rows in the given record batch.
+pub(crate) fn get_curve_keys(
+    record_batch: &RecordBatch,
+    sort_order: &[ParquetSortField],
+    sort_strategy: ParquetSortStrategy,
+) -> Result<Vec<u128>> {
+    assert_ne!(sort_strategy, ParquetSortStrategy::Linear);
+    let column_count = sort_order.len() as u32;
+    if column_count == 0 || column_count > CURVE_KEY_BITS {
+        return Err(ParquetError::General(format!(
+            "{sort_strategy:?} clustering takes 1 to {CURVE_KEY_BITS} columns, but {column_count} are given"
+        ))
+        .into());
+    }
+    let bits = (CURVE_KEY_BITS / column_count).min(MAX_BITS_PER_COLUMN);
+
+    // Get rank for each column, and keep the most significant bits which fit into the curve key.
+    let mut ranks_by_column = Vec::with_capacity(sort_order.len());
+    for sort_field in sort_order.iter() {
+        let values = record_batch
+            .column_by_name(&sort_field.column)
+            .ok_or_else(|| {
+                ParquetError::General(format!("Sort column {} not found", sort_field.column))
+            })?;
+        let mut ranks = rank(
+            values.as_ref(),
+            Some(SortOptions {
+                descending: sort_field.descending,
+                nulls_first: sort_field.nulls_first,
+            }),
+        )?;
+        let max_rank = ranks.iter().copied().max().unwrap_or(0);
+        let rank_bits = u32::BITS - max_rank.leading_zeros();
+        let shift = rank_bits.saturating_sub(bits);
+        for cur_rank in ranks.iter_mut() {
+            *cur_rank >>= shift;
+        }
+        ranks_by_column.push(ranks);
+    }
+
+    let mut coordinates = vec![0; sort_order.len()];
+    let mut keys = Vec::with_capacity(record_batch.num_rows());
+    for row_idx in 0..record_batch.num_rows() {
+        for (column_idx, ranks) in ranks_by_column.iter().enumerate() {
+            coordinates[column_idx] = ranks[row_idx];
+        }
+        let key = match sort_strategy {
+            ParquetSortStrategy::ZOrder => get_z_order_key(&coordinates, bits),
+            ParquetSortStrategy::Hilbert => get_hilbert_key(&coordinates, bits),
+            ParquetSortStrategy::Linear => unreachable!(),
+        };
+        keys.push(key);
+    }
+    Ok(keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use arrow_array::{Int32Array, StringArray};
+    use arrow_schema::{DataType, Field, Schema};
+
+    #[test]
+    fn test_z_order_key() {
+        // 2x2 grid is visited in "Z" shape.
+        let keys = [[0, 0], [0, 1], [1, 0], [1, 1]]
+            .iter()
+            .map(|coordinates| get_z_order_key(coordinates, /*bits=*/ 1))
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec![0, 1, 2, 3]);
+        assert_eq!(get_z_order_key(&[0b10, 0b01], /*bits=*/ 2), 0b1001);
+    }
+
+    #[test]
+    fn test_hilbert_key() {
+        // 4x4 grid is visited by one continuous path, where adjacent keys are adjacent cells.
+        let bits = 2;
+        let mut cells = vec![];
+        for x in 0..4u32 {
+            for y in 0..4u32 {
+                cells.push((get_hilbert_key(&[x, y], bits), x, y));
+            }
+        }
+        cells.sort();
+        for (idx, (key, _, _)) in cells.iter().enumerate() {
+            assert_eq!(*key, idx as u128);
+        }
+        for window in cells.windows(2) {
+            let (_, x1, y1) = window[0];
+            let (_, x2, y2) = window[1];
+            assert_eq!(x1.abs_diff(x2) + y1.abs_diff(y2), 1);
+        }
+    }
+
+    #[test]
+    fn test_get_curve_keys() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let record_batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![1, 100, 1, 100])),
+                Arc::new(StringArray::from(vec!["a", "a", "z", "z"])),
+            ],
+        )
+        .unwrap();
+        let sort_order = vec![
+            ParquetSortField {
+                column: "id".to_string(),
+                descending: false,
+                nulls_first: false,
+            },
+            ParquetSortField {
+                column: "name".to_string(),
+                descending: false,
+                nulls_first: false,
+            },
+        ];
+        let keys = get_curve_keys(&record_batch, &sort_order, ParquetSortStrategy::ZOrder).unwrap();
+        // Rows with the smallest values on both columns come first, and the largest ones come last.
+        assert!(keys[0] < keys[1] && keys[0] < keys[2]);
+        assert!(keys[3] > keys[1] && keys[3] > keys[2]);
+
+        let keys =
+            get_curve_keys(&record_batch, &sort_order, ParquetSortStrategy::Hilbert).unwrap();
+        assert_eq!(keys.len(), 4);
+        assert!(get_curve_keys(&record_batch, &[], ParquetSortStrategy::ZOrder).is_err());
+    }
+}
@@ -3,6 +3,7 @@
 
 use std::collections::{HashMap, HashSet};
 
+use arrow::compute::{concat_batches, take_record_batch};
 use arrow_array::RecordBatch;
 use arrow_schema::SchemaRef;
 use futures::TryStreamExt;
@@ -10,6 +11,7 @@ use more_asserts as ma;
 use parquet::arrow::async_reader::ParquetRecordBatchStreamBuilder;
 use parquet::arrow::AsyncArrowWriter;
 
+use crate::row::IdentityProp;
 use crate::storage::cache::object_storage::base_cache::CacheTrait;
 use crate::storage::compaction::table_compaction::{
     CompactedDataEntry, DataCompactionPayload, DataCompactionResult, RemappedRecordLocation,
@@ -19,6 +21,9 @@ use crate::storage::iceberg::puffin_utils;
 use crate::storage::index::persisted_bucket_hash_map::GlobalIndexBuilder;
 use crate::storage::index::FileIndex;
 use crate::storage::mooncake_table::delete_vector::BatchDeletionVector;
+use crate::storage::mooncake_table::partition_spec::{PartitionKey, PartitionSpec};
+use crate::storage::mooncake_table::schema_evolution;
+use crate::storage::parquet_writer_config::ParquetWriterConfig;
 use crate::storage::storage_utils::RecordLocation;
 use crate::storage::storage_utils::{
     get_random_file_name_in_dir, get_unique_file_id_for_flush, MooncakeDataFileRef,
@@ -35,6 +40,24 @@ pub(crate) struct CompactionFileParams {
     pub(crate) table_auto_incr_ids: std::ops::Range<u32>,
     /// Final size for compacted data files.
     pub(crate) data_file_final_size: u64,
+    /// Partition spec for compacted data files, each of them only contains rows of one partition.
+    pub(crate) partition_spec: Option<PartitionSpec>,
+    /// Config for compacted parquet data files.
+    pub(crate) parquet_writer_config: ParquetWriterConfig,
+    /// Identity property of the table, whose key columns get parquet bloom filters.
+    pub(crate) identity: IdentityProp,
+}
+
+/// Ongoing compacted data file for one partition.
+struct CompactedFileWriter {
+    /// Async arrow writer for the new data file.
+    arrow_writer: AsyncArrowWriter<tokio::fs::File>,
+    /// New compacted data file.
+    new_data_file: MooncakeDataFileRef,
+    /// Row number for the new compacted data file.
+    row_num: usize,
+    /// Index of the new data file among all compacted data files, which follows file id order.
+    data_file_index: u64,
 }
 
 pub(crate) struct CompactionBuilder {
@@ -48,14 +71,27 @@ pub(crate) struct CompactionBuilder {
     new_data_files: Vec<(MooncakeDataFileRef, CompactedDataEntry)>,
     /// ===== Current ongoing compaction operation =====
     ///
-    /// Current active async arrow writer, which is initialized in a lazy style.
-    cur_arrow_writer: Option<AsyncArrowWriter<tokio::fs::File>>,
-    /// Current new data file.
-    cur_new_data_file: Option<MooncakeDataFileRef>,
-    /// Current row number for the new compaction file.
-    cur_row_num: usize,
+    /// Current active writers keyed by partition, which are initialized in a lazy style; unpartitioned tables have at most one.
+    cur_writers: HashMap<PartitionKey, CompactedFileWriter>,
     /// Current compacted file count, including new compacted data files and index block files.
     compacted_file_count: u64,
+    /// ===== Rows pending to sort, only used when sort order is configured =====
+    ///
+    /// Partitions with pending rows, in the order they're first seen.
+    pending_partition_keys: Vec<PartitionKey>,
+    /// Surviving rows keyed by partition, which are sorted and written once they reach target compacted data file size.
+    pending_rows: HashMap<PartitionKey, PendingRows>,
+}
+
+/// Surviving rows pending to sort for one partition.
+#[derive(Default)]
+struct PendingRows {
+    /// Record batches for surviving rows.
+    batches: Vec<RecordBatch>,
+    /// Record locations before compaction for all surviving rows.
+    old_record_locations: Vec<RecordLocation>,
+    /// Memory size for all record batches.
+    memory_size: usize,
 }
 
 /// Result for data file compaction.
@@ -90,10 +126,10 @@ impl CompactionBuilder {
             file_params,
             new_data_files: Vec::new(),
             // Current ongoing compaction operation
-            cur_arrow_writer: None,
-            cur_new_data_file: None,
-            cur_row_num: 0,
+            cur_writers: HashMap::new(),
             compacted_file_count: 0,
+            pending_partition_keys: Vec::new(),
+            pending_rows: HashMap::new(),
         }
     }
 
@@ -114,55 +150,190 @@ impl CompactionBuilder {
 
     /// Util function to create a new data file.
     fn create_new_data_file(&self) -> MooncakeDataFileRef {
-        assert!(self.cur_new_data_file.is_none());
         let next_file_id = self.get_next_file_id();
         let file_path = get_random_file_name_in_dir(self.file_params.dir_path.as_path());
         create_data_file(next_file_id, file_path)
     }
 
-    /// Initialize arrow writer for once.
-    async fn initialize_arrow_writer_if_not(&mut self) -> Result<()> {
+    /// Initialize arrow writer for the given partition for once.
+    async fn initialize_arrow_writer_if_not(&mut self, partition_key: &PartitionKey) -> Result<()> {
         // If we create multiple data files during compaction, simply increment file id and recreate a new one.
-        if self.cur_arrow_writer.is_some() {
-            assert!(self.cur_new_data_file.is_some());
+        if self.cur_writers.contains_key(partition_key) {
             return Ok(());
         }
 
-        self.cur_new_data_file = Some(self.create_new_data_file());
-        let write_file =
-            tokio::fs::File::create(self.cur_new_data_file.as_ref().unwrap().file_path()).await?;
-        let properties = parquet_utils::get_default_parquet_properties();
-        let writer: AsyncArrowWriter<tokio::fs::File> =
+        let new_data_file = self.create_new_data_file();
+        let write_file = tokio::fs::File::create(new_data_file.file_path()).await?;
+        let bloom_filter_columns = parquet_utils::get_bloom_filter_columns(
+            &self.file_params.parquet_writer_config,
+            &self.schema,
+            &self.file_params.identity,
+        )?;
+        // Without sort order, compacted data files concatenate rows of input data files, so they're not declared as sorted.
+        let sorting_columns = parquet_utils::get_sorting_columns(
+            &self.schema,
+            &self.file_params.parquet_writer_config,
+        )?;
+        let properties = parquet_utils::get_parquet_properties(
+            &self.file_params.parquet_writer_config,
+            &bloom_filter_columns,
+            sorting_columns,
+        )?;
+        let arrow_writer: AsyncArrowWriter<tokio::fs::File> =
             AsyncArrowWriter::try_new(write_file, self.schema.clone(), Some(properties))?;
-        self.cur_arrow_writer = Some(writer);
+        let writer = CompactedFileWriter {
+            arrow_writer,
+            new_data_file,
+            row_num: 0,
+            data_file_index: self.compacted_file_count,
+        };
+        self.cur_writers.insert(partition_key.clone(), writer);
+        self.compacted_file_count += 1;
 
         Ok(())
     }
 
-    /// Util function to flush current arrow write and re-initialize related states.
-    async fn flush_arrow_writer(&mut self) -> Result<()> {
-        self.cur_arrow_writer.as_mut().unwrap().finish().await?;
-        let file_size = self.cur_arrow_writer.as_ref().unwrap().bytes_written();
+    /// Util function to flush arrow writer for the given partition.
+    async fn flush_arrow_writer(&mut self, partition_key: &PartitionKey) -> Result<()> {
+        let mut writer = self.cur_writers.remove(partition_key).unwrap();
+        writer.arrow_writer.finish().await?;
+        let file_size = writer.arrow_writer.bytes_written();
         ma::assert_gt!(file_size, 0);
-        ma::assert_gt!(self.cur_row_num, 0);
+        ma::assert_gt!(writer.row_num, 0);
         let compacted_data_entry = CompactedDataEntry {
-            num_rows: self.cur_row_num,
+            num_rows: writer.row_num,
             file_size,
         };
-        let new_data_file = std::mem::take(&mut self.cur_new_data_file).unwrap();
         self.new_data_files
-            .push((new_data_file, compacted_data_entry));
+            .push((writer.new_data_file, compacted_data_entry));
 
-        // Reinitialize states related to current new compacted data file.
-        self.cur_arrow_writer = None;
-        self.cur_new_data_file = None;
-        self.cur_row_num = 0;
-        self.compacted_file_count += 1;
+        Ok(())
+    }
 
+    /// Util function to flush arrow writers which already reached target compacted data file size.
+    async fn flush_full_arrow_writers(&mut self) -> Result<()> {
+        let partition_keys_to_flush = self
+            .cur_writers
+            .iter()
+            .filter(|(_, writer)| {
+                writer.arrow_writer.memory_size() >= self.file_params.data_file_final_size as usize
+            })
+            .map(|(partition_key, _)| partition_key.clone())
+            .collect::<Vec<_>>();
+        for partition_key in partition_keys_to_flush.iter() {
+            self.flush_arrow_writer(partition_key).await?;
+        }
         Ok(())
     }
 
+    /// Util function to write the given record batch to the data file for the given partition.
+    /// [`old_record_locations`] are record locations before compaction for all rows in the record batch.
+    async fn write_partitioned_batch(
+        &mut self,
+        partition_key: &PartitionKey,
+        record_batch: &RecordBatch,
+        old_record_locations: Vec<RecordLocation>,
+        old_to_new_remap: &mut DataFileRemap,
+        record_loc_to_data_file_index_map: &mut HashMap<RecordLocation, u64>,
+    ) -> Result<()> {
+        assert_eq!(record_batch.num_rows(), old_record_locations.len());
+        self.initialize_arrow_writer_if_not(partition_key).await?;
+        let writer = self.cur_writers.get_mut(partition_key).unwrap();
+        writer.arrow_writer.write(record_batch).await?;
+
+        for old_record_location in old_record_locations.into_iter() {
+            let new_record_location =
+                RecordLocation::DiskFile(writer.new_data_file.file_id(), writer.row_num);
+            // Precondition: data files are compacted before file indices, and new data files are ordered by file id.
+            record_loc_to_data_file_index_map
+                .insert(new_record_location.clone(), writer.data_file_index);
+            let remapped_record_location = RemappedRecordLocation {
+                record_location: new_record_location,
+                new_data_file: writer.new_data_file.clone(),
+            };
+            let old_entry = old_to_new_remap.insert(old_record_location, remapped_record_location);
+            assert!(old_entry.is_none());
+            writer.row_num += 1;
+        }
+        Ok(())
+    }
+
+    /// Util function to sort pending rows for the given partition, and write them to compacted data files.
+    /// Data files are closed afterwards, so each of them only contains one sorted run.
+    async fn write_sorted_rows_for_partition(
+        &mut self,
+        partition_key: &PartitionKey,
+        old_to_new_remap: &mut DataFileRemap,
+        record_loc_to_data_file_index_map: &mut HashMap<RecordLocation, u64>,
+    ) -> Result<()> {
+        let pending_rows = self.pending_rows.remove(partition_key).unwrap();
+        self.pending_partition_keys
+            .retain(|key| key != partition_key);
+        let old_record_locations = pending_rows.old_record_locations;
+        let batches = pending_rows.batches;
+        // Keep record batches written no larger than input ones.
+        let max_batch_rows = batches
+            .iter()
+            .map(|batch| batch.num_rows())
+            .max()
+            .unwrap_or(0);
+        let batch = concat_batches(&self.schema, &batches)?;
+        drop(batches);
+        let sorted_indices =
+            parquet_utils::get_sorted_indices(&batch, &self.file_params.parquet_writer_config)?;
+        let sorted_batch = take_record_batch(&batch, &sorted_indices)?;
+        drop(batch);
+
+        let mut offset = 0;
+        while offset < sorted_batch.num_rows() {
+            let len = max_batch_rows.min(sorted_batch.num_rows() - offset);
+            let sorted_record_locations = sorted_indices.values()[offset..offset + len]
+                .iter()
+                .map(|idx| old_record_locations[*idx as usize].clone())
+                .collect::<Vec<_>>();
+            self.write_partitioned_batch(
+                partition_key,
+                &sorted_batch.slice(offset, len),
+                sorted_record_locations,
+                old_to_new_remap,
+                record_loc_to_data_file_index_map,
+            )
+            .await?;
+            offset += len;
+
+            // Sorted rows are split into multiple data files once they reach target file size, each of which covers a disjoint range.
+            self.flush_full_arrow_writers().await?;
+        }
+
+        if self.cur_writers.contains_key(partition_key) {
+            self.flush_arrow_writer(partition_key).await?;
+        }
+        Ok(())
+    }
+
+    /// Util function to sort all pending rows for each partition, and write them to compacted data files.
+    /// Return the data file mapping and data file index mapping for all pending rows.
+    #[tracing::instrument(name = "write_sorted_rows", skip_all)]
+    async fn write_sorted_rows(&mut self) -> Result<(DataFileRemap, HashMap<RecordLocation, u64>)> {
+        let mut old_to_new_remap = HashMap::new();
+        let mut record_loc_to_data_file_index_map = HashMap::new();
+
+        let partition_keys = self.pending_partition_keys.clone();
+        for partition_key in partition_keys.iter() {
+            self.write_sorted_rows_for_partition(
+                partition_key,
+                &mut old_to_new_remap,
+                &mut record_loc_to_data_file_index_map,
+            )
+            .await?;
+        }
+
+        Ok((old_to_new_remap, record_loc_to_data_file_index_map))
+    }
+
     /// Util function to read the given parquet file, apply the corresponding deletion vector, and write it to the given arrow writer.
+    /// If sort order is configured, surviving rows are buffered, and sorted and written once they reach target compacted data file size
+    /// or all data files are read; so memory consumption is bounded by target file size for each partition.
     /// Return the data file mapping, and cache evicted data files to delete.
     #[tracing::instrument(name = "apply_deletion_vec", skip_all)]
     async fn apply_deletion_vector_and_write(
@@ -196,6 +367,8 @@ impl CompactionBuilder {
         let batch_deletion_vector =
             if let Some(puffin_blob_ref) = data_file_to_compact.deletion_vector {
                 puffin_utils::load_deletion_vector_from_blob(&puffin_blob_ref).await?
+            } else if let Some(position_deletes) = data_file_to_compact.position_deletes {
+                position_deletes
             } else {
                 BatchDeletionVector::new(/*max_rows=*/ 0)
             };
@@ -209,63 +382,87 @@ impl CompactionBuilder {
                 .unwrap()
         };
 
+        let sorted = !self.file_params.parquet_writer_config.sort_order.is_empty();
         let mut old_start_row_idx = 0;
         let mut old_to_new_remap = HashMap::new();
         let mut record_loc_to_data_file_index_map = HashMap::new();
         while let Some(cur_record_batch) = reader.try_next().await? {
             // If all rows have been deleted for the old data file, do nothing.
             let cur_num_rows = cur_record_batch.num_rows();
+            // Data files written before schema change are read with the current schema.
+            let cur_record_batch =
+                schema_evolution::adapt_record_batch(&cur_record_batch, self.schema.clone())?;
             let filtered_record_batch =
                 get_filtered_record_batch(cur_record_batch, old_start_row_idx);
             if filtered_record_batch.num_rows() == 0 {
                 continue;
             }
 
-            self.initialize_arrow_writer_if_not().await?;
-            self.cur_arrow_writer
-                .as_mut()
-                .unwrap()
-                .write(&filtered_record_batch)
-                .await?;
+            // Old row indices for rows within the filtered record batch.
+            let old_row_indices = (old_start_row_idx..(old_start_row_idx + cur_num_rows))
+                .filter(|old_row_idx| !batch_deletion_vector.is_deleted(*old_row_idx))
+                .collect::<Vec<_>>();
+            let partitioned_batches = match &self.file_params.partition_spec {
+                Some(partition_spec) => partition_spec.split_batch(&filtered_record_batch)?,
+                None => {
+                    let row_indices = (0..filtered_record_batch.num_rows()).collect();
+                    vec![(vec![], filtered_record_batch, row_indices)]
+                }
+            };
 
             // Construct old data file to new one mapping on-the-fly.
             old_to_new_remap.reserve(old_to_new_remap.len() + cur_num_rows);
             record_loc_to_data_file_index_map
                 .reserve(record_loc_to_data_file_index_map.len() + cur_num_rows);
 
-            for old_row_idx in old_start_row_idx..(old_start_row_idx + cur_num_rows) {
-                if batch_deletion_vector.is_deleted(old_row_idx) {
+            for (partition_key, partitioned_batch, row_indices) in partitioned_batches {
+                let old_record_locations = row_indices
+                    .into_iter()
+                    .map(|row_idx| {
+                        RecordLocation::DiskFile(
+                            data_file_to_compact.file_id.file_id,
+                            old_row_indices[row_idx],
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                if sorted {
+                    let pending_rows = self
+                        .pending_rows
+                        .entry(partition_key.clone())
+                        .or_insert_with(|| {
+                            self.pending_partition_keys.push(partition_key.clone());
+                            PendingRows::default()
+                        });
+                    pending_rows.memory_size += partitioned_batch.get_array_memory_size();
+                    pending_rows.batches.push(partitioned_batch);
+                    pending_rows
+                        .old_record_locations
+                        .extend(old_record_locations);
+                    if pending_rows.memory_size >= self.file_params.data_file_final_size as usize {
+                        self.write_sorted_rows_for_partition(
+                            &partition_key,
+                            &mut old_to_new_remap,
+                            &mut record_loc_to_data_file_index_map,
+                        )
+                        .await?;
+                    }
                     continue;
                 }
-                let old_record_location =
-                    RecordLocation::DiskFile(data_file_to_compact.file_id.file_id, old_row_idx);
-                let new_record_location = RecordLocation::DiskFile(
-                    self.cur_new_data_file.as_ref().unwrap().file_id(),
-                    self.cur_row_num,
-                );
-                // Precondition: data files are compacted before file indices, so [`self.compacted_file_count`] indicates the index of already compacted data files.
-                record_loc_to_data_file_index_map
-                    .insert(new_record_location.clone(), self.compacted_file_count);
-                let remapped_record_location = RemappedRecordLocation {
-                    record_location: new_record_location,
-                    new_data_file: self.cur_new_data_file.as_ref().unwrap().clone(),
-                };
-                let old_entry =
-                    old_to_new_remap.insert(old_record_location, remapped_record_location);
-                assert!(old_entry.is_none());
-                self.cur_row_num += 1;
+                self.write_partitioned_batch(
+                    &partition_key,
+                    &partitioned_batch,
+                    old_record_locations,
+                    &mut old_to_new_remap,
+                    &mut record_loc_to_data_file_index_map,
+                )
+                .await?;
             }
 
             old_start_row_idx += cur_num_rows;
         }
 
         // Bytes to write already reached target compacted data file size, flush and close.
-        if self.cur_arrow_writer.is_some()
-            && self.cur_arrow_writer.as_ref().unwrap().memory_size()
-                >= self.file_params.data_file_final_size as usize
-        {
-            self.flush_arrow_writer().await?;
-        }
+        self.flush_full_arrow_writers().await?;
 
         // Unpin cache handle after usage, if necessary.
         // TODO(hjiang): Better error propagation, cache handle should be always unpinned whether success or failure.
@@ -300,6 +497,12 @@ impl CompactionBuilder {
             record_loc_to_data_file_index_map
                 .extend(data_file_compaction_result.record_loc_to_data_file_index);
         }
+        if !self.pending_partition_keys.is_empty() {
+            let (sorted_remap, sorted_record_loc_to_data_file_index) =
+                self.write_sorted_rows().await?;
+            old_to_new_remap.extend(sorted_remap);
+            record_loc_to_data_file_index_map.extend(sorted_record_loc_to_data_file_index);
+        }
 
         let data_file_compaction_result = DataFileCompactionResult {
             data_file_remap: old_to_new_remap,
@@ -396,10 +599,14 @@ impl CompactionBuilder {
             });
         }
 
-        // Flush and close the compacted data file.
-        if self.cur_arrow_writer.is_some() {
-            self.flush_arrow_writer().await?;
+        // Flush and close the compacted data files.
+        let partition_keys_to_flush = self.cur_writers.keys().cloned().collect::<Vec<_>>();
+        for partition_key in partition_keys_to_flush.iter() {
+            self.flush_arrow_writer(partition_key).await?;
         }
+        // Partitioned writers are flushed out of order, order new data files by file id so their indices match allocation order.
+        self.new_data_files
+            .sort_by_key(|(new_data_file, _)| new_data_file.file_id().0);
 
         // Perform compaction on file indices.
         let new_file_indices = self
@@ -1,6 +1,7 @@
 use crate::storage::filesystem::accessor::base_filesystem_accessor::BaseFileSystemAccess;
 use crate::storage::iceberg::puffin_utils::PuffinBlobRef;
 use crate::storage::index::FileIndex;
+use crate::storage::mooncake_table::delete_vector::BatchDeletionVector;
 use crate::storage::storage_utils::MooncakeDataFileRef;
 use crate::storage::storage_utils::RecordLocation;
 use crate::storage::storage_utils::TableUniqueFileId;
@@ -20,6 +21,8 @@ pub struct SingleFileToCompact {
     /// Deletion vector.
     /// If assigned, the puffin file has been pinned so later accesses are valid.
     pub(crate) deletion_vector: Option<PuffinBlobRef>,
+    /// Persisted deletion records, if they're persisted as iceberg position delete files instead of puffin blob.
+    pub(crate) position_deletes: Option<BatchDeletionVector>,
 }
 
 /// Payload to trigger a compaction operation.
@@ -1,9 +1,19 @@
+use crate::row::IdentityProp;
 use crate::storage::compaction::compactor::{CompactionBuilder, CompactionFileParams};
-use crate::storage::compaction::table_compaction::{DataCompactionPayload, SingleFileToCompact};
+use crate::storage::compaction::table_compaction::{
+    DataCompactionPayload, DataCompactionResult, SingleFileToCompact,
+};
 use crate::storage::compaction::test_utils;
 use crate::storage::compaction::test_utils::get_record_location_mapping;
 use crate::storage::mooncake_table::delete_vector::BatchDeletionVector;
+use crate::storage::mooncake_table::partition_spec::{
+    PartitionField, PartitionSpec, PartitionTransform,
+};
 use crate::storage::mooncake_table::table_creation_test_utils::*;
+use crate::storage::parquet_writer_config::{
+    ParquetBloomFilterConfig, ParquetCompressionCodec, ParquetSortField, ParquetSortStrategy,
+    ParquetWriterConfig,
+};
 use crate::storage::storage_utils::{
     self, get_unique_file_id_for_flush, MooncakeDataFileRef, TableId, TableUniqueFileId,
 };
@@ -11,7 +21,16 @@ use crate::storage::storage_utils::{FileId, RecordLocation};
 use crate::storage::PuffinBlobRef;
 use crate::{create_data_file, FileSystemAccessor, ObjectStorageCache};
 
+use arrow_array::{Array, Int32Array, Int64Array};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::basic::Compression;
+use parquet::file::metadata::SortingColumn;
+use parquet::file::properties::ReaderProperties;
+use parquet::file::reader::{FileReader, RowGroupReader, SerializedFileReader};
+use parquet::file::serialized_reader::ReadOptionsBuilder;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Single compacted file size.
 const SINGLE_COMPACTED_DATA_FILE_SIZE: u64 = u64::MAX;
@@ -33,6 +52,7 @@ fn get_single_file_to_compact(
         },
         filepath: file.file_path().clone(),
         deletion_vector,
+        position_deletes: None,
     }
 }
 
@@ -79,6 +99,9 @@ async fn test_data_file_compaction_1() {
         dir_path: std::path::PathBuf::from(temp_dir.path()),
         table_auto_incr_ids: (table_auto_incr_id as u32)..(table_auto_incr_id as u32 + 1),
         data_file_final_size: SINGLE_COMPACTED_DATA_FILE_SIZE,
+        partition_spec: None,
+        parquet_writer_config: ParquetWriterConfig::default(),
+        identity: IdentityProp::SinglePrimitiveKey(0),
     };
 
     // Perform compaction.
@@ -164,6 +187,9 @@ async fn test_data_file_compaction_2() {
         dir_path: std::path::PathBuf::from(temp_dir.path()),
         table_auto_incr_ids: (table_auto_incr_id as u32)..(table_auto_incr_id as u32 + 1),
         data_file_final_size: SINGLE_COMPACTED_DATA_FILE_SIZE,
+        partition_spec: None,
+        parquet_writer_config: ParquetWriterConfig::default(),
+        identity: IdentityProp::SinglePrimitiveKey(0),
     };
 
     // Perform compaction.
@@ -252,6 +278,9 @@ async fn test_data_file_compaction_3() {
         dir_path: std::path::PathBuf::from(temp_dir.path()),
         table_auto_incr_ids: (table_auto_incr_id as u32)..(table_auto_incr_id as u32 + 1),
         data_file_final_size: SINGLE_COMPACTED_DATA_FILE_SIZE,
+        partition_spec: None,
+        parquet_writer_config: ParquetWriterConfig::default(),
+        identity: IdentityProp::SinglePrimitiveKey(0),
     };
 
     // Check compaction results.
@@ -329,6 +358,9 @@ async fn test_data_file_compaction_4() {
         dir_path: std::path::PathBuf::from(temp_dir.path()),
         table_auto_incr_ids: (table_auto_incr_id as u32)..(table_auto_incr_id as u32 + 1),
         data_file_final_size: SINGLE_COMPACTED_DATA_FILE_SIZE,
+        partition_spec: None,
+        parquet_writer_config: ParquetWriterConfig::default(),
+        identity: IdentityProp::SinglePrimitiveKey(0),
     };
 
     // Perform compaction.
@@ -445,6 +477,9 @@ async fn test_data_file_compaction_5() {
         dir_path: std::path::PathBuf::from(temp_dir.path()),
         table_auto_incr_ids: (table_auto_incr_id as u32)..(table_auto_incr_id as u32 + 1),
         data_file_final_size: SINGLE_COMPACTED_DATA_FILE_SIZE,
+        partition_spec: None,
+        parquet_writer_config: ParquetWriterConfig::default(),
+        identity: IdentityProp::SinglePrimitiveKey(0),
     };
 
     // Perform compaction.
@@ -568,6 +603,9 @@ async fn test_data_file_compaction_6() {
         dir_path: std::path::PathBuf::from(temp_dir.path()),
         table_auto_incr_ids: (table_auto_incr_id as u32)..(table_auto_incr_id as u32 + 1),
         data_file_final_size: SINGLE_COMPACTED_DATA_FILE_SIZE,
+        partition_spec: None,
+        parquet_writer_config: ParquetWriterConfig::default(),
+        identity: IdentityProp::SinglePrimitiveKey(0),
     };
 
     // Check compaction results.
@@ -679,6 +717,9 @@ async fn test_multiple_compacted_data_files_1() {
         dir_path: std::path::PathBuf::from(temp_dir.path()),
         table_auto_incr_ids: (table_auto_incr_id as u32)..(table_auto_incr_id as u32 + 4),
         data_file_final_size: MULTI_COMPACTED_DATA_FILE_SIZE,
+        partition_spec: None,
+        parquet_writer_config: ParquetWriterConfig::default(),
+        identity: IdentityProp::SinglePrimitiveKey(0),
     };
 
     // Perform compaction.
@@ -815,6 +856,9 @@ async fn test_multiple_compacted_data_files_2() {
         dir_path: std::path::PathBuf::from(temp_dir.path()),
         table_auto_incr_ids: (table_auto_incr_id as u32)..(table_auto_incr_id as u32 + 4),
         data_file_final_size: MULTI_COMPACTED_DATA_FILE_SIZE,
+        partition_spec: None,
+        parquet_writer_config: ParquetWriterConfig::default(),
+        identity: IdentityProp::SinglePrimitiveKey(0),
     };
 
     // Perform compaction.
@@ -824,6 +868,124 @@ async fn test_multiple_compacted_data_files_2() {
     assert!(compaction_result.remapped_data_files.is_empty());
 }
 
+/// Testing scenario: compacted data files are split by partition, and a partition could span over multiple old data files.
+#[tokio::test]
+async fn test_partitioned_data_file_compaction() {
+    // Create data files and file indices.
+    let temp_dir = tempfile::tempdir().unwrap();
+    let data_file_1 = temp_dir.path().join("test-1.parquet");
+    let data_file_2 = temp_dir.path().join("test-2.parquet");
+
+    let data_file_1 = create_data_file(
+        /*file_id=*/ 0,
+        data_file_1.to_str().unwrap().to_string(),
+    );
+    let data_file_2 = create_data_file(
+        /*file_id=*/ 1,
+        data_file_2.to_str().unwrap().to_string(),
+    );
+    let record_batch_1 = test_utils::create_test_batch_1();
+    let record_batch_2 = test_utils::create_test_batch_2();
+    test_utils::dump_arrow_record_batches(vec![record_batch_1], data_file_1.clone()).await;
+    test_utils::dump_arrow_record_batches(vec![record_batch_2], data_file_2.clone()).await;
+
+    let file_index_1 = test_utils::create_file_index_1(
+        temp_dir.path().to_path_buf(),
+        data_file_1.clone(),
+        /*start_file_id=*/ 2,
+    )
+    .await;
+    let file_index_2 = test_utils::create_file_index_2(
+        temp_dir.path().to_path_buf(),
+        data_file_2.clone(),
+        /*start_file_id=*/ 3,
+    )
+    .await;
+
+    // Prepare compaction payload.
+    let payload = DataCompactionPayload {
+        uuid: uuid::Uuid::new_v4(),
+        object_storage_cache: ObjectStorageCache::default_for_test(&temp_dir),
+        filesystem_accessor: FileSystemAccessor::default_for_test(&temp_dir),
+        disk_files: vec![
+            get_single_file_to_compact(&data_file_1, /*deletion_vector=*/ None),
+            get_single_file_to_compact(&data_file_2, /*deletion_vector=*/ None),
+        ],
+        file_indices: vec![file_index_1.clone(), file_index_2.clone()],
+    };
+    // Ids [1, 2, 3, 4, 5, 6] fall into partitions [0, 0, 3, 3, 3, 6].
+    let table_auto_incr_id: u64 = 4;
+    let file_params = CompactionFileParams {
+        dir_path: std::path::PathBuf::from(temp_dir.path()),
+        table_auto_incr_ids: (table_auto_incr_id as u32)..(table_auto_incr_id as u32 + 1),
+        data_file_final_size: SINGLE_COMPACTED_DATA_FILE_SIZE,
+        partition_spec: Some(PartitionSpec {
+            fields: vec![PartitionField {
+                source_column: "id".to_string(),
+                transform: PartitionTransform::Truncate(3),
+            }],
+        }),
+        parquet_writer_config: ParquetWriterConfig::default(),
+        identity: IdentityProp::SinglePrimitiveKey(0),
+    };
+
+    // Perform compaction.
+    let builder = CompactionBuilder::new(payload, create_test_arrow_schema(), file_params);
+    let compaction_result = builder.build().await.unwrap();
+
+    // Check new data files, which are ordered by file id.
+    let compacted_file_ids = (0..3)
+        .map(|file_idx| FileId(get_unique_file_id_for_flush(table_auto_incr_id, file_idx)))
+        .collect::<Vec<_>>();
+    let actual_new_data_files = compaction_result
+        .new_data_files
+        .iter()
+        .map(|(file, entry)| (file.file_id(), entry.num_rows))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        actual_new_data_files,
+        vec![
+            (compacted_file_ids[0], 2),
+            (compacted_file_ids[1], 3),
+            (compacted_file_ids[2], 1),
+        ]
+    );
+
+    // Check remap results and file indices compaction.
+    let expected_record_locations = vec![
+        (compacted_file_ids[0], 0),
+        (compacted_file_ids[0], 1),
+        (compacted_file_ids[1], 0),
+        (compacted_file_ids[1], 1),
+        (compacted_file_ids[1], 2),
+        (compacted_file_ids[2], 0),
+    ];
+    let actual_remap = get_record_location_mapping(&compaction_result.remapped_data_files);
+    let old_record_locations = [
+        RecordLocation::DiskFile(FileId(0), 0),
+        RecordLocation::DiskFile(FileId(0), 1),
+        RecordLocation::DiskFile(FileId(0), 2),
+        RecordLocation::DiskFile(FileId(1), 0),
+        RecordLocation::DiskFile(FileId(1), 1),
+        RecordLocation::DiskFile(FileId(1), 2),
+    ];
+    for (old_record_location, (file_id, row_idx)) in old_record_locations
+        .iter()
+        .zip(expected_record_locations.iter())
+    {
+        assert_eq!(
+            actual_remap[old_record_location],
+            RecordLocation::DiskFile(*file_id, *row_idx)
+        );
+    }
+    test_utils::check_file_indices_compaction_for_multiple_compacted_files(
+        compaction_result.new_file_indices.as_slice(),
+        expected_record_locations,
+        /*old_row_indices=*/ (0..6).collect(),
+    )
+    .await;
+}
+
 /// Testing scenario: new compacted data files are larger than max flush count.
 /// For more details, please refer to https://github.com/Mooncake-Labs/moonlink/issues/641
 #[tokio::test]
@@ -876,6 +1038,9 @@ async fn test_large_number_of_data_files() {
         dir_path: std::path::PathBuf::from(temp_dir.path()),
         table_auto_incr_ids: start_table_auto_incr_id..end_table_auto_incr_id,
         data_file_final_size: 1, // Dump each data file into its own file.
+        partition_spec: None,
+        parquet_writer_config: ParquetWriterConfig::default(),
+        identity: IdentityProp::SinglePrimitiveKey(0),
     };
 
     // Perform compaction.
@@ -899,3 +1064,401 @@ async fn test_large_number_of_data_files() {
     );
     assert_eq!(compaction_result.new_file_indices.len(), 1);
 }
+
+/// Testing scenario: compacted data files are written with parquet writer properties from table config, including bloom filters for identity columns.
+#[tokio::test]
+async fn test_data_file_compaction_with_parquet_writer_config() {
+    // Create data file and corresponding file indices.
+    let temp_dir = tempfile::tempdir().unwrap();
+    let data_file = temp_dir.path().join("test-1.parquet");
+    let data_file = create_data_file(/*file_id=*/ 0, data_file.to_str().unwrap().to_string());
+    let record_batch = test_utils::create_test_batch_1();
+    test_utils::dump_arrow_record_batches(vec![record_batch], data_file.clone()).await;
+    let file_index = test_utils::create_file_index_1(
+        temp_dir.path().to_path_buf(),
+        data_file.clone(),
+        /*start_file_id=*/ 1,
+    )
+    .await;
+
+    // Prepare compaction payload.
+    let payload = DataCompactionPayload {
+        uuid: uuid::Uuid::new_v4(),
+        object_storage_cache: ObjectStorageCache::default_for_test(&temp_dir),
+        filesystem_accessor: FileSystemAccessor::default_for_test(&temp_dir),
+        disk_files: vec![get_single_file_to_compact(
+            &data_file, /*deletion_vector=*/ None,
+        )],
+        file_indices: vec![file_index],
+    };
+    let table_auto_incr_id: u64 = 2;
+    let file_params = CompactionFileParams {
+        dir_path: std::path::PathBuf::from(temp_dir.path()),
+        table_auto_incr_ids: (table_auto_incr_id as u32)..(table_auto_incr_id as u32 + 1),
+        data_file_final_size: SINGLE_COMPACTED_DATA_FILE_SIZE,
+        partition_spec: None,
+        parquet_writer_config: ParquetWriterConfig {
+            compression: ParquetCompressionCodec::Zstd,
+            compression_level: Some(3),
+            max_row_group_size: 2,
+            bloom_filter_config: ParquetBloomFilterConfig {
+                identity_columns_enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        identity: IdentityProp::SinglePrimitiveKey(0),
+    };
+
+    // Perform compaction.
+    let builder = CompactionBuilder::new(payload, create_test_arrow_schema(), file_params);
+    let compaction_result = builder.build().await.unwrap();
+    assert_eq!(compaction_result.new_data_files.len(), 1);
+
+    // Check parquet metadata of the compacted data file.
+    let file = std::fs::File::open(compaction_result.new_data_files[0].0.file_path()).unwrap();
+    let read_options = ReadOptionsBuilder::new()
+        .with_reader_properties(
+            ReaderProperties::builder()
+                .set_read_bloom_filter(true)
+                .build(),
+        )
+        .build();
+    let reader = SerializedFileReader::new_with_options(file, read_options).unwrap();
+    let parquet_metadata = reader.metadata();
+    assert_eq!(parquet_metadata.num_row_groups(), 2);
+    for row_group in parquet_metadata.row_groups() {
+        for column in row_group.columns() {
+            assert!(matches!(column.compression(), Compression::ZSTD(_)));
+        }
+    }
+
+    // Bloom filter is written for the identity column.
+    for row_group_idx in 0..parquet_metadata.num_row_groups() {
+        let row_group_reader = reader.get_row_group(row_group_idx).unwrap();
+        assert!(row_group_reader.get_column_bloom_filter(0).is_some());
+        assert!(row_group_reader.get_column_bloom_filter(1).is_none());
+    }
+}
+
+/// Test util function to compact the two test data files with the given parquet writer config and target file size, the first of which has its second row deleted.
+async fn compact_two_files_with_parquet_writer_config(
+    temp_dir: &tempfile::TempDir,
+    parquet_writer_config: ParquetWriterConfig,
+    data_file_final_size: u64,
+) -> DataCompactionResult {
+    let object_storage_cache = ObjectStorageCache::default_for_test(temp_dir);
+    let filesystem_accessor = FileSystemAccessor::default_for_test(temp_dir);
+    let data_file_1 = create_data_file(
+        /*file_id=*/ 0,
+        temp_dir
+            .path()
+            .join("test-1.parquet")
+            .to_str()
+            .unwrap()
+            .to_string(),
+    );
+    let data_file_2 = create_data_file(
+        /*file_id=*/ 1,
+        temp_dir
+            .path()
+            .join("test-2.parquet")
+            .to_str()
+            .unwrap()
+            .to_string(),
+    );
+    test_utils::dump_arrow_record_batches(
+        vec![test_utils::create_test_batch_1()],
+        data_file_1.clone(),
+    )
+    .await;
+    test_utils::dump_arrow_record_batches(
+        vec![test_utils::create_test_batch_2()],
+        data_file_2.clone(),
+    )
+    .await;
+    let file_index_1 = test_utils::create_file_index_1(
+        temp_dir.path().to_path_buf(),
+        data_file_1.clone(),
+        /*start_file_id=*/ 2,
+    )
+    .await;
+    let file_index_2 = test_utils::create_file_index_2(
+        temp_dir.path().to_path_buf(),
+        data_file_2.clone(),
+        /*start_file_id=*/ 3,
+    )
+    .await;
+
+    // Create deletion vector puffin file for the first data file.
+    let puffin_filepath = temp_dir.path().join("deletion-vector-1.bin");
+    let mut batch_deletion_vector = BatchDeletionVector::new(/*max_rows=*/ 3);
+    batch_deletion_vector.delete_row(1);
+    let puffin_blob_ref = test_utils::dump_deletion_vector_puffin(
+        data_file_1.file_path().clone(),
+        puffin_filepath.to_str().unwrap().to_string(),
+        batch_deletion_vector,
+        object_storage_cache.clone(),
+        filesystem_accessor.as_ref(),
+        get_table_unique_table_id(/*file_id=*/ 4),
+    )
+    .await;
+
+    let payload = DataCompactionPayload {
+        uuid: uuid::Uuid::new_v4(),
+        object_storage_cache,
+        filesystem_accessor,
+        disk_files: vec![
+            get_single_file_to_compact(&data_file_1, Some(puffin_blob_ref)),
+            get_single_file_to_compact(&data_file_2, /*deletion_vector=*/ None),
+        ],
+        file_indices: vec![file_index_1, file_index_2],
+    };
+    let table_auto_incr_id: u64 = 5;
+    let file_params = CompactionFileParams {
+        dir_path: std::path::PathBuf::from(temp_dir.path()),
+        table_auto_incr_ids: (table_auto_incr_id as u32)..(table_auto_incr_id as u32 + 2),
+        data_file_final_size,
+        partition_spec: None,
+        parquet_writer_config,
+        identity: IdentityProp::SinglePrimitiveKey(0),
+    };
+    let builder = CompactionBuilder::new(payload, create_test_arrow_schema(), file_params);
+    builder.build().await.unwrap()
+}
+
+/// Test util function to get the id column of the given data file, and its declared sorting columns.
+fn read_ids_and_sorting_columns(
+    data_file: &MooncakeDataFileRef,
+) -> (Vec<i32>, Option<Vec<SortingColumn>>) {
+    let file = std::fs::File::open(data_file.file_path()).unwrap();
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+    let sorting_columns = builder.metadata().row_group(0).sorting_columns().cloned();
+    let mut ids = vec![];
+    for record_batch in builder.build().unwrap() {
+        let record_batch = record_batch.unwrap();
+        let id_column = record_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        ids.extend(id_column.values().iter().copied());
+    }
+    (ids, sorting_columns)
+}
+
+/// Testing scenario: compacted data files are sorted by the configured linear sort order, and declared as sorted.
+#[tokio::test]
+async fn test_data_file_compaction_with_linear_sort_order() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let parquet_writer_config = ParquetWriterConfig {
+        sort_order: vec![ParquetSortField {
+            column: "age".to_string(),
+            descending: true,
+            nulls_first: false,
+        }],
+        ..Default::default()
+    };
+    let compaction_result = compact_two_files_with_parquet_writer_config(
+        &temp_dir,
+        parquet_writer_config,
+        SINGLE_COMPACTED_DATA_FILE_SIZE,
+    )
+    .await;
+    assert_eq!(compaction_result.new_data_files.len(), 1);
+
+    // Rows across input data files are sorted together.
+    let (new_data_file, compacted_data_entry) = &compaction_result.new_data_files[0];
+    assert_eq!(compacted_data_entry.num_rows, 5);
+    let (ids, sorting_columns) = read_ids_and_sorting_columns(new_data_file);
+    assert_eq!(ids, vec![6, 5, 4, 3, 1]);
+    assert_eq!(
+        sorting_columns,
+        Some(vec![SortingColumn {
+            column_idx: 2,
+            descending: true,
+            nulls_first: false,
+        }])
+    );
+
+    // Old record locations are remapped to their sorted positions.
+    let compacted_file_id = new_data_file.file_id();
+    let expected_remap = HashMap::from([
+        (
+            RecordLocation::DiskFile(FileId(0), 0),
+            RecordLocation::DiskFile(compacted_file_id, 4),
+        ),
+        (
+            RecordLocation::DiskFile(FileId(0), 2),
+            RecordLocation::DiskFile(compacted_file_id, 3),
+        ),
+        (
+            RecordLocation::DiskFile(FileId(1), 0),
+            RecordLocation::DiskFile(compacted_file_id, 2),
+        ),
+        (
+            RecordLocation::DiskFile(FileId(1), 1),
+            RecordLocation::DiskFile(compacted_file_id, 1),
+        ),
+        (
+            RecordLocation::DiskFile(FileId(1), 2),
+            RecordLocation::DiskFile(compacted_file_id, 0),
+        ),
+    ]);
+    let actual_remap = get_record_location_mapping(&compaction_result.remapped_data_files);
+    assert_eq!(actual_remap, expected_remap);
+
+    // File indices point to sorted positions as well.
+    test_utils::check_file_indices_compaction(
+        compaction_result.new_file_indices.as_slice(),
+        /*expected_file_id=*/ Some(compacted_file_id),
+        /*old_row_indices=*/ vec![0, 2, 3, 4, 5],
+    )
+    .await;
+}
+
+/// Testing scenario: rows pending to sort are written once they reach target file size, so each compacted data file contains one sorted run.
+#[tokio::test]
+async fn test_data_file_compaction_with_sort_order_and_small_file_size() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let parquet_writer_config = ParquetWriterConfig {
+        sort_order: vec![ParquetSortField {
+            column: "age".to_string(),
+            descending: true,
+            nulls_first: false,
+        }],
+        ..Default::default()
+    };
+    let compaction_result = compact_two_files_with_parquet_writer_config(
+        &temp_dir,
+        parquet_writer_config,
+        /*data_file_final_size=*/ 1,
+    )
+    .await;
+    assert_eq!(compaction_result.new_data_files.len(), 2);
+
+    // Rows are sorted within each input data file, instead of across all of them.
+    let (first_data_file, first_data_entry) = &compaction_result.new_data_files[0];
+    assert_eq!(first_data_entry.num_rows, 2);
+    let (ids, sorting_columns) = read_ids_and_sorting_columns(first_data_file);
+    assert_eq!(ids, vec![3, 1]);
+    assert!(sorting_columns.is_some());
+    let (second_data_file, second_data_entry) = &compaction_result.new_data_files[1];
+    assert_eq!(second_data_entry.num_rows, 3);
+    let (ids, sorting_columns) = read_ids_and_sorting_columns(second_data_file);
+    assert_eq!(ids, vec![6, 5, 4]);
+    assert!(sorting_columns.is_some());
+    assert_eq!(compaction_result.remapped_data_files.len(), 5);
+}
+
+/// Testing scenario: compacted data files are clustered along space-filling curves, and not declared as sorted.
+#[tokio::test]
+async fn test_data_file_compaction_with_curve_sort_strategy() {
+    for sort_strategy in [ParquetSortStrategy::ZOrder, ParquetSortStrategy::Hilbert] {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let parquet_writer_config = ParquetWriterConfig {
+            sort_order: vec![
+                ParquetSortField {
+                    column: "id".to_string(),
+                    descending: true,
+                    nulls_first: false,
+                },
+                ParquetSortField {
+                    column: "age".to_string(),
+                    descending: true,
+                    nulls_first: false,
+                },
+            ],
+            sort_strategy,
+            ..Default::default()
+        };
+        let compaction_result = compact_two_files_with_parquet_writer_config(
+            &temp_dir,
+            parquet_writer_config,
+            SINGLE_COMPACTED_DATA_FILE_SIZE,
+        )
+        .await;
+        assert_eq!(compaction_result.new_data_files.len(), 1);
+
+        // Both columns grow together, so clustered rows are in the same order as either column.
+        let (new_data_file, _) = &compaction_result.new_data_files[0];
+        let (ids, sorting_columns) = read_ids_and_sorting_columns(new_data_file);
+        assert_eq!(ids, vec![6, 5, 4, 3, 1]);
+        assert!(sorting_columns.is_none());
+        assert_eq!(compaction_result.remapped_data_files.len(), 5);
+        test_utils::check_file_indices_compaction(
+            compaction_result.new_file_indices.as_slice(),
+            /*expected_file_id=*/ Some(new_data_file.file_id()),
+            /*old_row_indices=*/ vec![0, 2, 3, 4, 5],
+        )
+        .await;
+    }
+}
+
+/// Testing scenario: data files written before schema change are compacted with the current schema, with added columns filled with nulls and widened columns promoted.
+#[tokio::test]
+async fn test_data_file_compaction_with_schema_evolution() {
+    // Create data file with the old schema, and corresponding file indices.
+    let temp_dir = tempfile::tempdir().unwrap();
+    let data_file = temp_dir.path().join("test-1.parquet");
+    let data_file = create_data_file(/*file_id=*/ 0, data_file.to_str().unwrap().to_string());
+    let record_batch = test_utils::create_test_batch_1();
+    test_utils::dump_arrow_record_batches(vec![record_batch], data_file.clone()).await;
+    let file_index = test_utils::create_file_index_1(
+        temp_dir.path().to_path_buf(),
+        data_file.clone(),
+        /*start_file_id=*/ 1,
+    )
+    .await;
+
+    // Prepare compaction payload.
+    let payload = DataCompactionPayload {
+        uuid: uuid::Uuid::new_v4(),
+        object_storage_cache: ObjectStorageCache::default_for_test(&temp_dir),
+        filesystem_accessor: FileSystemAccessor::default_for_test(&temp_dir),
+        disk_files: vec![get_single_file_to_compact(
+            &data_file, /*deletion_vector=*/ None,
+        )],
+        file_indices: vec![file_index],
+    };
+    let table_auto_incr_id: u64 = 2;
+    let file_params = CompactionFileParams {
+        dir_path: std::path::PathBuf::from(temp_dir.path()),
+        table_auto_incr_ids: (table_auto_incr_id as u32)..(table_auto_incr_id as u32 + 1),
+        data_file_final_size: SINGLE_COMPACTED_DATA_FILE_SIZE,
+        partition_spec: None,
+        parquet_writer_config: ParquetWriterConfig::default(),
+        identity: IdentityProp::SinglePrimitiveKey(0),
+    };
+
+    // Current schema widens "age" column and adds "email" column.
+    let mut fields = create_test_arrow_schema().fields().to_vec();
+    fields[2] = Arc::new(fields[2].as_ref().clone().with_data_type(DataType::Int64));
+    fields.push(Arc::new(
+        Field::new("email", DataType::Utf8, true).with_metadata(HashMap::from([(
+            "PARQUET:field_id".to_string(),
+            "3".to_string(),
+        )])),
+    ));
+    let schema = Arc::new(Schema::new(fields));
+
+    // Perform compaction.
+    let builder = CompactionBuilder::new(payload, schema, file_params);
+    let compaction_result = builder.build().await.unwrap();
+    assert_eq!(compaction_result.new_data_files.len(), 1);
+
+    // Check data file compaction.
+    let file = std::fs::File::open(compaction_result.new_data_files[0].0.file_path()).unwrap();
+    let mut reader = ParquetRecordBatchReaderBuilder::try_new(file)
+        .unwrap()
+        .build()
+        .unwrap();
+    let record_batch = reader.next().unwrap().unwrap();
+    let age_column = record_batch
+        .column(2)
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(age_column.values().to_vec(), vec![10, 20, 30]);
+    assert_eq!(record_batch.column(3).null_count(), 3);
+}
@@ -24,6 +24,10 @@ pub trait BaseFileSystemAccess: std::fmt::Debug + Send + Sync {
     /// For example, we have directory "a", "a/b", "a/b/c", listing direct subdirectories for "a" will return "a/b".
     async fn list_direct_subdirectories(&self, folder: &str) -> Result<Vec<String>>;
 
+    /// List all objects under the given directory recursively, along with their last modified timestamp in milliseconds.
+    /// Returned object paths are prefixed with root directory (or bucket), in the same way as filepaths written to iceberg metadata.
+    async fn list_objects_recursively(&self, folder: &str) -> Result<Vec<(String, i64)>>;
+
     /// Remove the whole directory recursively.
     async fn remove_directory(&self, directory: &str) -> Result<()>;
 
@@ -143,6 +143,42 @@ impl BaseFileSystemAccess for FileSystemAccessor {
         Ok(dirs)
     }
 
+    async fn list_objects_recursively(&self, folder: &str) -> Result<Vec<(String, i64)>> {
+        let sanitized_folder = self.sanitize_path(folder);
+        let prefix = format!("{}/", sanitized_folder.trim_end_matches('/'));
+        let operator = self.get_operator().await?;
+        let entries = operator.list_with(&prefix).recursive(true).await?;
+
+        let mut objects = Vec::with_capacity(entries.len());
+        for cur_entry in entries.into_iter() {
+            if cur_entry.path().ends_with('/') {
+                continue;
+            }
+            // Not all services return last modified timestamp at listing, i.e. local filesystem.
+            let last_modified = match cur_entry.metadata().last_modified() {
+                Some(last_modified) => last_modified,
+                None => operator
+                    .stat(cur_entry.path())
+                    .await?
+                    .last_modified()
+                    .ok_or_else(|| {
+                        std::io::Error::other(format!(
+                            "Last modified timestamp is unavailable for {}",
+                            cur_entry.path()
+                        ))
+                    })?,
+            };
+            let object = format!(
+                "{}/{}",
+                self.root_path.trim_end_matches('/'),
+                cur_entry.path().trim_start_matches('/')
+            );
+            objects.push((object, last_modified.timestamp_millis()));
+        }
+
+        Ok(objects)
+    }
+
     /// TODO(hjiang): Check whether we could unify the implementation with [`remove_directory`].
     #[cfg(feature = "storage-gcs")]
     async fn remove_directory(&self, directory: &str) -> Result<()> {
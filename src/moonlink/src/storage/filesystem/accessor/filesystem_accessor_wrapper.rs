@@ -104,6 +104,11 @@ impl BaseFileSystemAccess for FileSystemWrapper {
         self.inner.list_direct_subdirectories(folder).await
     }
 
+    async fn list_objects_recursively(&self, folder: &str) -> Result<Vec<(String, i64)>> {
+        self.perform_wrapper_function().await?;
+        self.inner.list_objects_recursively(folder).await
+    }
+
     async fn remove_directory(&self, directory: &str) -> Result<()> {
         self.perform_wrapper_function().await?;
         self.inner.remove_directory(directory).await
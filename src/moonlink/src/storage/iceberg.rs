@@ -2,20 +2,27 @@ pub(super) mod catalog_utils;
 pub(super) mod deletion_vector;
 pub(super) mod file_catalog;
 mod iceberg_schema_manager;
+mod iceberg_snapshot_expirer;
 pub(super) mod iceberg_table_config;
 mod iceberg_table_loader;
 pub(super) mod iceberg_table_manager;
 mod iceberg_table_syncer;
 pub(super) mod index;
 pub(super) mod io_utils;
+pub(super) mod manifest_compaction_config;
 pub(super) mod moonlink_catalog;
 pub(super) mod parquet_metadata_utils;
 pub(super) mod parquet_stats_utils;
 pub(super) mod parquet_utils;
+pub(super) mod position_delete;
 pub(super) mod puffin_utils;
 pub(super) mod puffin_writer_proxy;
+pub(super) mod rest_catalog;
 mod schema_utils;
+pub(super) mod snapshot_retention_config;
 mod snapshot_utils;
+#[cfg(feature = "catalog-sql")]
+pub(super) mod sql_catalog;
 mod table_commit_proxy;
 pub(super) mod table_event_manager;
 pub(super) mod table_manager;
@@ -52,5 +59,15 @@ mod file_catalog_test_utils;
 #[cfg(test)]
 mod file_catalog_test;
 
+#[cfg(test)]
+mod rest_catalog_test_utils;
+
+#[cfg(test)]
+mod rest_catalog_test;
+
+#[cfg(feature = "catalog-sql")]
+#[cfg(test)]
+mod sql_catalog_test;
+
 #[cfg(test)]
 mod mock_filesystem_test;
@@ -2,23 +2,52 @@
 use crate::storage::filesystem::accessor::base_filesystem_accessor::BaseFileSystemAccess;
 use crate::storage::filesystem::filesystem_config::FileSystemConfig;
 use crate::storage::iceberg::file_catalog::FileCatalog;
+use crate::storage::iceberg::iceberg_table_config::IcebergCatalogConfig;
 use crate::storage::iceberg::moonlink_catalog::MoonlinkCatalog;
+use crate::storage::iceberg::rest_catalog::RestCatalog;
+#[cfg(feature = "catalog-sql")]
+use crate::storage::iceberg::sql_catalog::SqlCatalog;
+use crate::storage::iceberg::table_property;
 
-use iceberg::spec::Schema as IcebergSchema;
-use iceberg::Result as IcebergResult;
+use iceberg::spec::{
+    FormatVersion, Schema as IcebergSchema, SortOrder, TableMetadata, TableMetadataBuilder,
+    UnboundPartitionSpec,
+};
+use iceberg::{Error as IcebergError, ErrorKind, Result as IcebergResult, TableCreation};
+use iceberg::{TableRequirement, TableUpdate};
 
 /// Create a catelog based on the provided type.
 ///
 /// It's worth noting catalog and warehouse uri are not 1-1 mapping; for example, rest catalog could handle warehouse.
-/// Here we simply deduce catalog type from warehouse because both filesystem and object storage catalog are only able to handle certain scheme.
+/// For file catalog, we simply deduce catalog type from warehouse because both filesystem and object storage catalog are only able to handle certain scheme.
 pub fn create_catalog(
     filesystem_config: FileSystemConfig,
+    catalog_config: IcebergCatalogConfig,
     iceberg_schema: IcebergSchema,
 ) -> IcebergResult<Box<dyn MoonlinkCatalog>> {
-    Ok(Box::new(FileCatalog::new(
-        filesystem_config,
-        iceberg_schema,
-    )?))
+    match catalog_config {
+        IcebergCatalogConfig::File => Ok(Box::new(FileCatalog::new(
+            filesystem_config,
+            iceberg_schema,
+        )?)),
+        IcebergCatalogConfig::Rest {
+            uri,
+            warehouse,
+            props,
+        } => Ok(Box::new(RestCatalog::new(
+            uri,
+            warehouse,
+            props,
+            iceberg_schema,
+        ))),
+        #[cfg(feature = "catalog-sql")]
+        IcebergCatalogConfig::Sql { uri, catalog_name } => Ok(Box::new(SqlCatalog::new(
+            filesystem_config,
+            &uri,
+            catalog_name,
+            iceberg_schema,
+        )?)),
+    }
 }
 
 /// Test util function to create catalog with provided filesystem accessor.
@@ -32,3 +61,96 @@ pub fn create_catalog_with_filesystem_accessor(
         iceberg_schema,
     )?))
 }
+
+/// Get table metadata builder for a new table.
+/// Unlike [`TableMetadataBuilder::from_table_creation`], the format version is taken from the reserved table property
+/// if given, which is not stored as a table property.
+pub(crate) fn new_table_metadata_builder(
+    mut creation: TableCreation,
+) -> IcebergResult<TableMetadataBuilder> {
+    let format_version = match creation.properties.remove(table_property::FORMAT_VERSION) {
+        Some(format_version) => {
+            serde_json::from_str::<FormatVersion>(&format_version).map_err(|e| {
+                IcebergError::new(
+                    ErrorKind::DataInvalid,
+                    format!("invalid table format version {format_version}: {e}"),
+                )
+            })?
+        }
+        None => table_property::FORMAT_VERSION_DEFAULT,
+    };
+    let location = creation.location.ok_or_else(|| {
+        IcebergError::new(
+            ErrorKind::DataInvalid,
+            "table location is required at creation",
+        )
+    })?;
+    TableMetadataBuilder::new(
+        creation.schema,
+        creation
+            .partition_spec
+            .unwrap_or_else(|| UnboundPartitionSpec::builder().build()),
+        creation
+            .sort_order
+            .unwrap_or_else(SortOrder::unsorted_order),
+        location,
+        format_version,
+        creation.properties,
+    )
+}
+
+/// Validate table commit requirements.
+pub(crate) fn validate_table_requirements(
+    table_requirements: Vec<TableRequirement>,
+    table_metadata: &TableMetadata,
+) -> IcebergResult<()> {
+    for cur_requirment in table_requirements.into_iter() {
+        cur_requirment.check(Some(table_metadata))?;
+    }
+    Ok(())
+}
+
+/// Reflect table updates to table metadata builder.
+pub(crate) fn reflect_table_updates(
+    mut builder: TableMetadataBuilder,
+    table_updates: Vec<TableUpdate>,
+) -> IcebergResult<TableMetadataBuilder> {
+    for update in &table_updates {
+        match update {
+            TableUpdate::AddSnapshot { snapshot } => {
+                builder = builder.add_snapshot(snapshot.clone())?;
+            }
+            TableUpdate::SetSnapshotRef {
+                ref_name,
+                reference,
+            } => {
+                builder = builder.set_ref(ref_name, reference.clone())?;
+            }
+            TableUpdate::SetProperties { updates } => {
+                builder = builder.set_properties(updates.clone())?;
+            }
+            TableUpdate::RemoveProperties { removals } => {
+                builder = builder.remove_properties(removals)?;
+            }
+            TableUpdate::AddSchema { schema } => {
+                builder = builder.add_schema(schema.clone());
+            }
+            TableUpdate::SetCurrentSchema { schema_id } => {
+                builder = builder.set_current_schema(*schema_id)?;
+            }
+            TableUpdate::RemoveSnapshots { snapshot_ids } => {
+                builder = builder.remove_snapshots(snapshot_ids);
+            }
+            TableUpdate::AddSortOrder { sort_order } => {
+                builder = builder.add_sort_order(sort_order.clone())?;
+            }
+            TableUpdate::SetDefaultSortOrder { sort_order_id } => {
+                builder = builder.set_default_sort_order(*sort_order_id)?;
+            }
+            _ => {
+                unreachable!("Unimplemented table update: {:?}", update);
+            }
+        }
+    }
+    Ok(builder)
+}
@@ -2,8 +2,10 @@ use super::puffin_writer_proxy::append_puffin_metadata_and_rewrite;
 use crate::storage::filesystem::accessor::base_filesystem_accessor::BaseFileSystemAccess;
 use crate::storage::filesystem::accessor::filesystem_accessor::FileSystemAccessor;
 use crate::storage::filesystem::filesystem_config::FileSystemConfig;
+use crate::storage::iceberg::catalog_utils;
 use crate::storage::iceberg::io_utils as iceberg_io_utils;
 use crate::storage::iceberg::moonlink_catalog::{PuffinWrite, SchemaUpdate};
+use crate::storage::iceberg::position_delete::PositionDeleteFileMetadata;
 use crate::storage::iceberg::puffin_writer_proxy::{
     get_puffin_metadata_and_close, PuffinBlobMetadataProxy,
 };
@@ -48,11 +50,9 @@ use iceberg::spec::{
     Schema as IcebergSchema, TableMetadata, TableMetadataBuildResult, TableMetadataBuilder,
 };
 use iceberg::table::Table;
+use iceberg::Error as IcebergError;
 use iceberg::Result as IcebergResult;
-use iceberg::{
-    Catalog, Namespace, NamespaceIdent, TableCommit, TableCreation, TableIdent, TableUpdate,
-};
-use iceberg::{Error as IcebergError, TableRequirement};
+use iceberg::{Catalog, Namespace, NamespaceIdent, TableCommit, TableCreation, TableIdent};
 
 /// Object storage usually doesn't have "folder" concept, when creating a new namespace, we create an indicator file under certain folder.
 pub(super) const NAMESPACE_INDICATOR_OBJECT_NAME: &str = "indicator.text";
@@ -77,8 +77,12 @@ pub struct FileCatalog {
     puffin_blobs_to_add: HashMap<String, Vec<PuffinBlobMetadataProxy>>,
     /// A vector of "puffin filepath"s.
     puffin_blobs_to_remove: HashSet<String>,
+    /// Used to record position delete files in one transaction, and cleaned up after transaction commits.
+    position_deletes_to_add: Vec<PositionDeleteFileMetadata>,
     /// A set of data files to remove, along with their corresponding deletion vectors and file indices.
     data_files_to_remove: HashSet<String>,
+    /// Whether to compact all manifest files at the next commit.
+    compact_manifests: bool,
 }
 
 impl FileCatalog {
@@ -93,7 +97,9 @@ impl FileCatalog {
             iceberg_schema,
             puffin_blobs_to_add: HashMap::new(),
             puffin_blobs_to_remove: HashSet::new(),
+            position_deletes_to_add: Vec::new(),
             data_files_to_remove: HashSet::new(),
+            compact_manifests: false,
         })
     }
 
@@ -112,7 +118,9 @@ impl FileCatalog {
             warehouse_location: String::new(),
             puffin_blobs_to_add: HashMap::new(),
             puffin_blobs_to_remove: HashSet::new(),
+            position_deletes_to_add: Vec::new(),
             data_files_to_remove: HashSet::new(),
+            compact_manifests: false,
         })
     }
 
@@ -186,53 +194,6 @@ impl FileCatalog {
         Ok((metadata_filepath, metadata))
     }
 
-    /// Validate table commit requirements.
-    fn validate_table_requirements(
-        table_requirements: Vec<TableRequirement>,
-        table_metadata: &TableMetadata,
-    ) -> IcebergResult<()> {
-        for cur_requirment in table_requirements.into_iter() {
-            cur_requirment.check(Some(table_metadata))?;
-        }
-        Ok(())
-    }
-
-    /// Reflect table updates to table metadata builder.
-    fn reflect_table_updates(
-        mut builder: TableMetadataBuilder,
-        table_updates: Vec<TableUpdate>,
-    ) -> IcebergResult<TableMetadataBuilder> {
-        for update in &table_updates {
-            match update {
-                TableUpdate::AddSnapshot { snapshot } => {
-                    builder = builder.add_snapshot(snapshot.clone())?;
-                }
-                TableUpdate::SetSnapshotRef {
-                    ref_name,
-                    reference,
-                } => {
-                    builder = builder.set_ref(ref_name, reference.clone())?;
-                }
-                TableUpdate::SetProperties { updates } => {
-                    builder = builder.set_properties(updates.clone())?;
-                }
-                TableUpdate::RemoveProperties { removals } => {
-                    builder = builder.remove_properties(removals)?;
-                }
-                TableUpdate::AddSchema { schema } => {
-                    builder = builder.add_schema(schema.clone());
-                }
-                TableUpdate::SetCurrentSchema { schema_id } => {
-                    builder = builder.set_current_schema(*schema_id)?;
-                }
-                _ => {
-                    unreachable!("Unimplemented table update: {:?}", update);
-                }
-            }
-        }
-        Ok(builder)
-    }
-
     /// This is a hack function to work-around iceberg-rust.
     /// iceberg-rust somehow reassign field id at table creation, which means it leads to inconsistency between iceberg table metadata and parquet metadata; query engines is possible to suffer schema inconsistency error.
     /// Here we overwrite iceberg schema with correctly populated field id.
@@ -271,10 +232,20 @@ impl PuffinWrite for FileCatalog {
         self.puffin_blobs_to_remove = puffin_filepaths;
     }
 
+    fn record_position_delete_file(&mut self, position_delete: PositionDeleteFileMetadata) {
+        self.position_deletes_to_add.push(position_delete);
+    }
+
+    fn set_compact_manifests(&mut self) {
+        self.compact_manifests = true;
+    }
+
     fn clear_puffin_metadata(&mut self) {
         self.puffin_blobs_to_add.clear();
         self.puffin_blobs_to_remove.clear();
+        self.position_deletes_to_add.clear();
         self.data_files_to_remove.clear();
+        self.compact_manifests = false;
     }
 }
 
@@ -510,7 +481,7 @@ impl Catalog for FileCatalog {
             creation.name.clone()
         );
 
-        let table_metadata = TableMetadataBuilder::from_table_creation(creation)?.build()?;
+        let table_metadata = catalog_utils::new_table_metadata_builder(creation)?.build()?;
         let metadata = self.get_iceberg_table_metadata(table_metadata)?;
         let metadata_json = serde_json::to_vec(&metadata)?;
         self.filesystem_accessor
@@ -600,11 +571,11 @@ impl Catalog for FileCatalog {
         );
 
         // Validate existing table metadata with requirements.
-        Self::validate_table_requirements(commit.take_requirements(), &metadata)?;
+        catalog_utils::validate_table_requirements(commit.take_requirements(), &metadata)?;
 
         // Construct new metadata with updates.
         let updates = commit.take_updates();
-        let builder = Self::reflect_table_updates(builder, updates)?;
+        let builder = catalog_utils::reflect_table_updates(builder, updates)?;
         let metadata = builder.build()?.metadata;
 
         // Write metadata file.
@@ -635,6 +606,8 @@ impl Catalog for FileCatalog {
             &self.data_files_to_remove,
             &self.puffin_blobs_to_add,
             &self.puffin_blobs_to_remove,
+            &self.position_deletes_to_add,
+            self.compact_manifests,
         )
         .await?;
 
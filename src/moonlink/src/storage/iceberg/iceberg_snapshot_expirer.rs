@@ -0,0 +1,284 @@
+use crate::storage::iceberg::file_catalog::VERSION_HINT_FILENAME;
+use crate::storage::iceberg::iceberg_table_config::IcebergCatalogConfig;
+use crate::storage::iceberg::iceberg_table_manager::*;
+use crate::storage::iceberg::snapshot_retention_config::SnapshotRetentionConfig;
+use crate::storage::iceberg::table_commit_proxy::TableCommitProxy;
+use crate::storage::iceberg::table_manager::ExpireSnapshotsResult;
+
+use std::collections::{HashMap, HashSet};
+
+use iceberg::io::FileIO;
+use iceberg::spec::{SnapshotRef, TableMetadata, MAIN_BRANCH};
+use iceberg::Error as IcebergError;
+use iceberg::Result as IcebergResult;
+use iceberg::{TableRequirement, TableUpdate};
+
+/// Suffix for iceberg table metadata files.
+const METADATA_FILE_SUFFIX: &str = ".metadata.json";
+
+/// Get object path relative to root directory (or bucket), so filepaths in iceberg metadata and listed objects are comparable.
+fn get_relative_path<'a>(filepath: &'a str, root_path: &str) -> &'a str {
+    filepath
+        .strip_prefix(root_path)
+        .unwrap_or(filepath)
+        .trim_start_matches('/')
+}
+
+/// This module contains snapshot expiration for iceberg table manager.
+///
+/// Every iceberg persistence creates a new snapshot, and files replaced by compaction, index merge and deletion vector updates are still referenced by old snapshots.
+/// Snapshot expiration removes snapshots out of retention from table metadata, and deletes files which are only referenced by expired snapshots.
+/// Files left by failed commits are never referenced by any snapshot, which are deleted as orphan files after a grace period; old metadata files are pruned as well.
+impl IcebergTableManager {
+    /// Split all snapshots into (retained snapshots, expired snapshots) based on the given retention config.
+    /// Current snapshot is always retained, since it's the one moonlink reads and writes on.
+    fn split_snapshots_by_retention(
+        table_metadata: &TableMetadata,
+        retention_config: &SnapshotRetentionConfig,
+    ) -> (Vec<SnapshotRef>, Vec<SnapshotRef>) {
+        let current_snapshot_id = table_metadata.current_snapshot_id();
+        let mut snapshots = table_metadata.snapshots().cloned().collect::<Vec<_>>();
+        snapshots.sort_by_key(|snapshot| {
+            std::cmp::Reverse((snapshot.timestamp_ms(), snapshot.sequence_number()))
+        });
+
+        let max_snapshot_age_ms =
+            i64::try_from(retention_config.max_snapshot_age_ms).unwrap_or(i64::MAX);
+        let expire_before_ms = chrono::Utc::now()
+            .timestamp_millis()
+            .saturating_sub(max_snapshot_age_ms);
+        let mut retained_snapshots = vec![];
+        let mut expired_snapshots = vec![];
+        for (idx, cur_snapshot) in snapshots.into_iter().enumerate() {
+            if Some(cur_snapshot.snapshot_id()) == current_snapshot_id
+                || idx < retention_config.min_snapshots_to_keep
+                || cur_snapshot.timestamp_ms() >= expire_before_ms
+            {
+                retained_snapshots.push(cur_snapshot);
+            } else {
+                expired_snapshots.push(cur_snapshot);
+            }
+        }
+        (retained_snapshots, expired_snapshots)
+    }
+
+    /// Get all files referenced by the given snapshots, including manifest lists, manifest files, data files and puffin files.
+    ///
+    /// Manifest files are possibly shared by multiple snapshots, their entries are cached by manifest filepath to avoid repeated IO.
+    async fn get_files_referenced_by_snapshots(
+        snapshots: &[SnapshotRef],
+        table_metadata: &TableMetadata,
+        file_io: &FileIO,
+        manifest_entries_cache: &mut HashMap<String, Vec<String>>,
+    ) -> IcebergResult<HashSet<String>> {
+        let mut referenced_files = HashSet::new();
+        for cur_snapshot in snapshots.iter() {
+            referenced_files.insert(cur_snapshot.manifest_list().to_string());
+            let manifest_list = cur_snapshot
+                .load_manifest_list(file_io, table_metadata)
+                .await?;
+            for manifest_file in manifest_list.entries().iter() {
+                if !manifest_entries_cache.contains_key(&manifest_file.manifest_path) {
+                    let manifest = manifest_file.load_manifest(file_io).await?;
+                    let filepaths = manifest
+                        .entries()
+                        .iter()
+                        .map(|entry| entry.data_file().file_path().to_string())
+                        .collect::<Vec<_>>();
+                    manifest_entries_cache.insert(manifest_file.manifest_path.clone(), filepaths);
+                }
+                referenced_files.insert(manifest_file.manifest_path.clone());
+                referenced_files.extend(
+                    manifest_entries_cache[&manifest_file.manifest_path]
+                        .iter()
+                        .cloned(),
+                );
+            }
+        }
+        Ok(referenced_files)
+    }
+
+    /// Remove expired snapshots from table metadata, and return expired snapshot ids, files which are only referenced by expired snapshots,
+    /// and files referenced by retained snapshots.
+    async fn commit_snapshot_expiration(
+        &mut self,
+        retention_config: &SnapshotRetentionConfig,
+    ) -> IcebergResult<(Vec<i64>, HashSet<String>, HashSet<String>)> {
+        // Nothing to expire if iceberg table hasn't been created.
+        let iceberg_table = match self.iceberg_table.clone() {
+            Some(iceberg_table) => iceberg_table,
+            None => return Ok((vec![], HashSet::new(), HashSet::new())),
+        };
+        let table_metadata = iceberg_table.metadata();
+        let (retained_snapshots, expired_snapshots) =
+            Self::split_snapshots_by_retention(table_metadata, retention_config);
+
+        // Get referenced files before commit, since manifest lists for expired snapshots are no longer reachable afterwards.
+        let mut manifest_entries_cache = HashMap::new();
+        let retained_files = Self::get_files_referenced_by_snapshots(
+            &retained_snapshots,
+            table_metadata,
+            iceberg_table.file_io(),
+            &mut manifest_entries_cache,
+        )
+        .await?;
+        if expired_snapshots.is_empty() {
+            return Ok((vec![], HashSet::new(), retained_files));
+        }
+        let expired_files = Self::get_files_referenced_by_snapshots(
+            &expired_snapshots,
+            table_metadata,
+            iceberg_table.file_io(),
+            &mut manifest_entries_cache,
+        )
+        .await?;
+
+        // Remove expired snapshots, with current snapshot checked to avoid concurrent updates.
+        let snapshot_ids = expired_snapshots
+            .iter()
+            .map(|snapshot| snapshot.snapshot_id())
+            .collect::<Vec<_>>();
+        let table_commit_proxy = TableCommitProxy {
+            ident: self.get_table_ident(),
+            requirements: vec![TableRequirement::RefSnapshotIdMatch {
+                r#ref: MAIN_BRANCH.to_string(),
+                snapshot_id: table_metadata.current_snapshot_id(),
+            }],
+            updates: vec![TableUpdate::RemoveSnapshots {
+                snapshot_ids: snapshot_ids.clone(),
+            }],
+        };
+        let updated_iceberg_table = self
+            .catalog
+            .update_table(table_commit_proxy.take_as_table_commit())
+            .await?;
+        self.iceberg_table = Some(updated_iceberg_table);
+
+        let files_to_delete = expired_files
+            .difference(&retained_files)
+            .cloned()
+            .collect::<HashSet<_>>();
+        Ok((snapshot_ids, files_to_delete, retained_files))
+    }
+
+    /// Get files under table location which are not referenced by any retained snapshot, including
+    /// - orphan files older than grace period, i.e. left by failed commits;
+    /// - metadata files beyond the max number of previous ones to keep.
+    async fn get_unreferenced_files(
+        &self,
+        retention_config: &SnapshotRetentionConfig,
+        referenced_files: &HashSet<String>,
+    ) -> IcebergResult<Vec<String>> {
+        let iceberg_table = match self.iceberg_table.as_ref() {
+            Some(iceberg_table) => iceberg_table,
+            None => return Ok(vec![]),
+        };
+        let table_location = iceberg_table.metadata().location();
+        let root_path = self.config.filesystem_config.get_root_path();
+        let referenced_files = referenced_files
+            .iter()
+            .map(|filepath| get_relative_path(filepath, &root_path))
+            .collect::<HashSet<_>>();
+        let current_metadata_file = iceberg_table
+            .metadata_location()
+            .map(|filepath| get_relative_path(filepath, &root_path));
+        let objects = self
+            .filesystem_accessor
+            .list_objects_recursively(table_location)
+            .await
+            .map_err(|e| {
+                IcebergError::new(
+                    iceberg::ErrorKind::Unexpected,
+                    format!("Failed to list files under table location {table_location}: {e:?}"),
+                )
+                .with_retryable(true)
+            })?;
+
+        let orphan_file_grace_period_ms =
+            i64::try_from(retention_config.orphan_file_grace_period_ms).unwrap_or(i64::MAX);
+        let orphan_before_ms = chrono::Utc::now()
+            .timestamp_millis()
+            .saturating_sub(orphan_file_grace_period_ms);
+        let mut unreferenced_files = vec![];
+        let mut metadata_files = vec![];
+        for (cur_object, last_modified_ms) in objects.into_iter() {
+            let relative_path = get_relative_path(&cur_object, &root_path);
+            if referenced_files.contains(relative_path)
+                || relative_path.ends_with(VERSION_HINT_FILENAME)
+            {
+                continue;
+            }
+            if relative_path.ends_with(METADATA_FILE_SUFFIX) {
+                if Some(relative_path) != current_metadata_file {
+                    metadata_files.push((last_modified_ms, cur_object));
+                }
+                continue;
+            }
+            if last_modified_ms < orphan_before_ms {
+                unreferenced_files.push(cur_object);
+            }
+        }
+
+        // Metadata files for REST catalog are managed by catalog server.
+        if matches!(self.config.catalog, IcebergCatalogConfig::Rest { .. }) {
+            return Ok(unreferenced_files);
+        }
+        // Current metadata file is always kept and excluded above, keep the latest previous ones.
+        metadata_files.sort_by(|lhs, rhs| rhs.cmp(lhs));
+        unreferenced_files.extend(
+            metadata_files
+                .into_iter()
+                .skip(retention_config.max_previous_metadata_files)
+                .map(|(_, filepath)| filepath),
+        );
+        Ok(unreferenced_files)
+    }
+
+    pub(crate) async fn expire_snapshots_impl(
+        &mut self,
+        retention_config: &SnapshotRetentionConfig,
+    ) -> IcebergResult<ExpireSnapshotsResult> {
+        let (expired_snapshot_ids, new_files_to_delete, retained_files) =
+            self.commit_snapshot_expiration(retention_config).await?;
+        let unreferenced_files = self
+            .get_unreferenced_files(retention_config, &retained_files)
+            .await?;
+
+        // Retry deletion for files pinned at previous expirations.
+        let mut files_to_delete = std::mem::take(&mut self.files_pending_deletion)
+            .into_iter()
+            .chain(new_files_to_delete)
+            .chain(unreferenced_files)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        // Files used by in-flight reads are pinned in object storage cache; for remote storage, reads happen on local cache files so remote ones are safe to delete.
+        let mut deleted_files = Vec::with_capacity(files_to_delete.len());
+        while let Some(cur_file) = files_to_delete.pop() {
+            if self
+                .object_storage_cache
+                .is_cache_filepath_pinned(&cur_file)
+                .await
+            {
+                self.files_pending_deletion.insert(cur_file);
+                continue;
+            }
+            if let Err(e) = self.filesystem_accessor.delete_object(&cur_file).await {
+                self.files_pending_deletion.insert(cur_file.clone());
+                self.files_pending_deletion.extend(files_to_delete);
+                return Err(IcebergError::new(
+                    iceberg::ErrorKind::Unexpected,
+                    format!("Failed to delete file {cur_file} at snapshot expiration: {e:?}"),
+                )
+                .with_retryable(true));
+            }
+            deleted_files.push(cur_file);
+        }
+
+        Ok(ExpireSnapshotsResult {
+            expired_snapshot_ids,
+            deleted_files,
+        })
+    }
+}
@@ -1,5 +1,67 @@
 use crate::FileSystemConfig;
 
+use std::collections::HashMap;
+
+/// Catalog which tracks iceberg table metadata.
+/// WARNING: REST catalog properties usually carry credentials, they're persisted as table secrets and not logged.
+#[derive(Clone, Default, PartialEq)]
+pub enum IcebergCatalogConfig {
+    /// Catalog on top of the warehouse filesystem, which tracks metadata with version hint files.
+    #[default]
+    File,
+    /// Iceberg REST catalog, i.e. Polaris, Lakekeeper, Nessie.
+    Rest {
+        /// Uri of the REST catalog server.
+        uri: String,
+        /// Warehouse identifier passed to the REST catalog server, if any.
+        warehouse: Option<String>,
+        /// Additional properties for the REST catalog client, i.e. credentials.
+        props: HashMap<String, String>,
+    },
+    /// Iceberg SQL catalog, which follows iceberg JDBC catalog schema, so it could share the database with metadata store.
+    #[cfg(feature = "catalog-sql")]
+    Sql {
+        /// Database uri, i.e. `sqlite:///path/to/database.sqlite`, `postgres://user@host/database`.
+        uri: String,
+        /// Catalog name, which is used by query engines with JDBC catalog to discover tables.
+        catalog_name: String,
+    },
+}
+
+impl std::fmt::Debug for IcebergCatalogConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::File => f.write_str("File"),
+            Self::Rest {
+                uri,
+                warehouse,
+                props,
+            } => f
+                .debug_struct("Rest")
+                .field("uri", uri)
+                .field("warehouse", warehouse)
+                .field("props", &props.keys().collect::<Vec<_>>())
+                .finish(),
+            #[cfg(feature = "catalog-sql")]
+            Self::Sql { uri, catalog_name } => f
+                .debug_struct("Sql")
+                .field("uri", uri)
+                .field("catalog_name", catalog_name)
+                .finish(),
+        }
+    }
+}
+
+/// File format used to persist row deletions into iceberg table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IcebergDeleteFileFormat {
+    /// Deletion vector puffin blobs, which are introduced in iceberg v3.
+    #[default]
+    DeletionVector,
+    /// Position delete parquet files, for query engines which only support iceberg v2.
+    PositionDelete,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct IcebergTableConfig {
     /// Namespace for the iceberg table.
@@ -8,6 +70,10 @@ pub struct IcebergTableConfig {
     pub table_name: String,
     // Filesystem config.
     pub filesystem_config: FileSystemConfig,
+    /// Catalog config.
+    pub catalog: IcebergCatalogConfig,
+    /// File format for row deletions.
+    pub delete_file_format: IcebergDeleteFileFormat,
 }
 
 impl IcebergTableConfig {
@@ -24,6 +90,8 @@ impl Default for IcebergTableConfig {
             filesystem_config: FileSystemConfig::FileSystem {
                 root_directory: Self::DEFAULT_WAREHOUSE_URI.to_string(),
             },
+            catalog: IcebergCatalogConfig::default(),
+            delete_file_format: IcebergDeleteFileFormat::default(),
         }
     }
 }
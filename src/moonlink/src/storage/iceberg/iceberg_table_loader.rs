@@ -2,6 +2,7 @@ use crate::storage::cache::object_storage::base_cache::CacheTrait;
 use crate::storage::iceberg::deletion_vector::DeletionVector;
 use crate::storage::iceberg::iceberg_table_manager::*;
 use crate::storage::iceberg::index::FileIndexBlob;
+use crate::storage::iceberg::position_delete;
 use crate::storage::iceberg::puffin_utils::PuffinBlobRef;
 #[cfg(any(test, debug_assertions))]
 use crate::storage::iceberg::schema_utils;
@@ -12,6 +13,7 @@ use crate::storage::index::{FileIndex as MooncakeFileIndex, MooncakeIndex};
 use crate::storage::io_utils;
 use crate::storage::mooncake_table::delete_vector::BatchDeletionVector;
 use crate::storage::mooncake_table::DiskFileEntry;
+use crate::storage::mooncake_table::InitialCopyPosition;
 use crate::storage::mooncake_table::Snapshot as MooncakeSnapshot;
 use crate::storage::storage_utils::{create_data_file, FileId, TableId, TableUniqueFileId};
 use crate::storage::wal::wal_persistence_metadata::WalPersistenceMetadata;
@@ -186,6 +188,102 @@ impl IcebergTableManager {
         Ok(())
     }
 
+    /// Load position delete file into table manager from the current manifest entry.
+    /// Unlike deletion vector puffin, position delete file is only read at recovery, so it's not kept pinned in the cache.
+    /// One position delete file could contain deleted rows for multiple data files, which are grouped by the data file path column.
+    async fn load_position_deletes_from_manifest_entry(
+        &mut self,
+        entry: &ManifestEntry,
+        next_file_id: &mut u64,
+    ) -> IcebergResult<()> {
+        // Skip data files, file indices and deletion vectors.
+        if !utils::is_position_delete_entry(entry) {
+            return Ok(());
+        }
+
+        let data_file = entry.data_file();
+
+        // Load remote position delete file to local cache.
+        let cur_file_id = *next_file_id;
+        *next_file_id += 1;
+        let unique_file_id = TableUniqueFileId {
+            table_id: TableId(self.mooncake_table_metadata.table_id),
+            file_id: FileId(cur_file_id),
+        };
+        let (cache_handle, mut evicted_files_to_delete) = self
+            .object_storage_cache
+            .get_cache_entry(
+                unique_file_id,
+                data_file.file_path(),
+                self.filesystem_accessor.as_ref(),
+            )
+            .await
+            .map_err(|e| {
+                IcebergError::new(
+                    iceberg::ErrorKind::Unexpected,
+                    format!(
+                        "Failed to get cache entry for {}: {:?}",
+                        data_file.file_path(),
+                        e
+                    ),
+                )
+                .with_retryable(true)
+            })?;
+        let local_filepath = if let Some(cache_handle) = &cache_handle {
+            cache_handle.get_cache_filepath()
+        } else {
+            data_file.file_path()
+        };
+
+        let load_result = position_delete::load_position_deletes_from_file(local_filepath).await;
+
+        // Position delete file is not needed after load, unreference and delete it from cache.
+        if let Some(mut cache_handle) = cache_handle {
+            let cur_evicted_files = cache_handle.unreference_and_delete().await;
+            evicted_files_to_delete.extend(cur_evicted_files);
+        }
+        io_utils::delete_local_files(&evicted_files_to_delete)
+            .await
+            .map_err(|e| {
+                IcebergError::new(
+                    iceberg::ErrorKind::Unexpected,
+                    format!("Failed to delete files for {evicted_files_to_delete:?}: {e:?}"),
+                )
+                .with_retryable(true)
+            })?;
+
+        for (referenced_data_file, deleted_rows) in load_result?.into_iter() {
+            let file_id = self
+                .remote_data_file_to_file_id
+                .get(&referenced_data_file)
+                .ok_or_else(|| {
+                    IcebergError::new(
+                        iceberg::ErrorKind::DataInvalid,
+                        format!(
+                            "Position delete file {} refers to data file {} not in the current snapshot",
+                            data_file.file_path(),
+                            referenced_data_file
+                        ),
+                    )
+                })?;
+            let data_file_entry = self.persisted_data_files.get_mut(file_id).unwrap();
+
+            // Max number of rows for batch deletion vector is the number of rows in the referenced data file; deleted rows from multiple position delete files are merged.
+            let max_rows = data_file_entry.data_file.record_count() as usize;
+            if data_file_entry.deletion_vector.get_max_rows() != max_rows {
+                data_file_entry.deletion_vector = BatchDeletionVector::new(max_rows);
+            }
+            position_delete::apply_position_deletes(
+                &mut data_file_entry.deletion_vector,
+                &referenced_data_file,
+                deleted_rows,
+            )?;
+            data_file_entry.persisted_deletion_vector = None;
+        }
+
+        Ok(())
+    }
+
     /// -------- Transformation util functions ---------
     ///
     /// Util function to transform iceberg table status to mooncake table snapshot, assign file id uniquely to all data files.
@@ -194,6 +292,7 @@ impl IcebergTableManager {
         persisted_file_indices: Vec<MooncakeFileIndex>,
         flush_lsn: Option<u64>,
         wal_metadata: Option<WalPersistenceMetadata>,
+        initial_copy_position: Option<InitialCopyPosition>,
     ) -> MooncakeSnapshot {
         let mut mooncake_snapshot = MooncakeSnapshot::new(self.mooncake_table_metadata.clone());
 
@@ -235,6 +334,8 @@ impl IcebergTableManager {
         mooncake_snapshot.data_file_flush_lsn = flush_lsn;
         // Fill in wal persistence metadata.
         mooncake_snapshot.wal_persistence_metadata = wal_metadata;
+        // Fill in initial copy position.
+        mooncake_snapshot.initial_copy_position = initial_copy_position.unwrap_or_default();
 
         mooncake_snapshot
     }
@@ -338,6 +439,10 @@ impl IcebergTableManager {
                     &mut next_file_id,
                 )
                 .await?;
+
+                // Load position delete file.
+                self.load_position_deletes_from_manifest_entry(entry.as_ref(), &mut next_file_id)
+                    .await?;
             }
         }
 
@@ -345,6 +450,7 @@ impl IcebergTableManager {
             loaded_file_indices,
             snapshot_property.flush_lsn,
             snapshot_property.wal_persisted_metadata,
+            snapshot_property.initial_copy_position,
         );
         Ok((next_file_id as u32, mooncake_snapshot))
     }
@@ -2,19 +2,23 @@ use crate::storage::filesystem::accessor::base_filesystem_accessor::BaseFileSyst
 use crate::storage::iceberg::catalog_utils;
 use crate::storage::iceberg::moonlink_catalog::MoonlinkCatalog;
 use crate::storage::iceberg::puffin_utils::PuffinBlobRef;
+use crate::storage::iceberg::snapshot_retention_config::SnapshotRetentionConfig;
+use crate::storage::iceberg::table_commit_proxy::TableCommitProxy;
 use crate::storage::iceberg::table_manager::{
-    PersistenceFileParams, PersistenceResult, TableManager,
+    ExpireSnapshotsResult, PersistenceFileParams, PersistenceResult, TableManager,
 };
+use crate::storage::iceberg::table_property;
 use crate::storage::iceberg::utils;
 use crate::storage::index::FileIndex as MooncakeFileIndex;
 use crate::storage::mooncake_table::delete_vector::BatchDeletionVector;
+use crate::storage::mooncake_table::partition_spec;
 use crate::storage::mooncake_table::IcebergSnapshotPayload;
 use crate::storage::mooncake_table::Snapshot as MooncakeSnapshot;
 use crate::storage::mooncake_table::TableMetadata as MooncakeTableMetadata;
 use crate::storage::storage_utils::FileId;
 use crate::{IcebergTableConfig, ObjectStorageCache};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -22,15 +26,12 @@ use iceberg::spec::DataFile;
 use iceberg::table::Table as IcebergTable;
 use iceberg::writer::file_writer::location_generator::DefaultLocationGenerator;
 use iceberg::writer::file_writer::location_generator::LocationGenerator;
-use iceberg::{NamespaceIdent, Result as IcebergResult, TableIdent};
+use iceberg::{
+    Error as IcebergError, ErrorKind, NamespaceIdent, Result as IcebergResult, TableIdent,
+    TableRequirement, TableUpdate,
+};
 use uuid::Uuid;
 
-/// TODO(hjiang): store snapshot property in snapshot summary, instead of table property.
-///
-/// Key for iceberg snapshot property, to record flush lsn.
-pub(super) const MOONCAKE_TABLE_FLUSH_LSN: &str = "moonlink.table-flush-lsn";
-/// Key for iceberg snapshot property, to record WAL persistence metadata.
-pub(super) const MOONCAKE_WAL_METADATA: &str = "moonlink.wal-metadata";
 /// Used to represent uninitialized deletion vector.
 /// TODO(hjiang): Consider using `Option<>` to represent uninitialized, which is more rust-idiometic.
 pub(super) const UNINITIALIZED_BATCH_DELETION_VECTOR_MAX_ROW: usize = 0;
@@ -76,6 +77,13 @@ pub struct IcebergTableManager {
 
     /// Maps from remote data file path to its file id.
     pub(crate) remote_data_file_to_file_id: HashMap<String, FileId>,
+
+    /// Files only referenced by expired snapshots, but pinned by in-flight reads at expiration, whose deletion is retried at the next expiration.
+    pub(crate) files_pending_deletion: HashSet<String>,
+
+    /// Number of manifest files in the current snapshot, which is loaded lazily and tracked locally afterwards, to avoid loading manifest list at every persistence.
+    /// Unassigned if it's unknown, i.e. manifest files get rewritten at the last persistence.
+    pub(crate) manifest_file_count: Option<usize>,
 }
 
 impl IcebergTableManager {
@@ -87,8 +95,11 @@ impl IcebergTableManager {
     ) -> IcebergResult<IcebergTableManager> {
         let iceberg_schema =
             iceberg::arrow::arrow_schema_to_schema(mooncake_table_metadata.schema.as_ref())?;
-        let catalog =
-            catalog_utils::create_catalog(config.filesystem_config.clone(), iceberg_schema)?;
+        let catalog = catalog_utils::create_catalog(
+            config.filesystem_config.clone(),
+            config.catalog.clone(),
+            iceberg_schema,
+        )?;
         Ok(Self {
             snapshot_loaded: false,
             config,
@@ -100,6 +111,8 @@ impl IcebergTableManager {
             persisted_data_files: HashMap::new(),
             persisted_file_indices: HashMap::new(),
             remote_data_file_to_file_id: HashMap::new(),
+            files_pending_deletion: HashSet::new(),
+            manifest_file_count: None,
         })
     }
 
@@ -127,6 +140,8 @@ impl IcebergTableManager {
             persisted_data_files: HashMap::new(),
             persisted_file_indices: HashMap::new(),
             remote_data_file_to_file_id: HashMap::new(),
+            files_pending_deletion: HashSet::new(),
+            manifest_file_count: None,
         })
     }
 
@@ -146,6 +161,13 @@ impl IcebergTableManager {
         location_generator
             .generate_location(&format!("{}-deletion-vector-v1-puffin.bin", Uuid::now_v7()))
     }
+    /// Get a unique position delete filepath under table warehouse uri.
+    pub(super) fn get_unique_position_delete_filepath(&self) -> String {
+        let location_generator =
+            DefaultLocationGenerator::new(self.iceberg_table.as_ref().unwrap().metadata().clone())
+                .unwrap();
+        location_generator.generate_location(&format!("{}-position-delete.parquet", Uuid::now_v7()))
+    }
     pub(super) fn get_unique_hash_index_v1_filepath(&self) -> String {
         let location_generator =
             DefaultLocationGenerator::new(self.iceberg_table.as_ref().unwrap().metadata().clone())
@@ -167,9 +189,15 @@ impl IcebergTableManager {
                 &self.config.namespace,
                 &self.config.table_name,
                 self.mooncake_table_metadata.schema.as_ref(),
+                self.mooncake_table_metadata.config.partition_spec.as_ref(),
+                self.config.delete_file_format,
+                &self.mooncake_table_metadata.config.parquet_writer_config,
             )
             .await?;
+            self.validate_delete_file_format(&table)?;
+            self.validate_partition_spec(&table)?;
             self.iceberg_table = Some(table);
+            self.update_sort_order_if_changed().await?;
         }
         Ok(())
     }
@@ -183,8 +211,77 @@ impl IcebergTableManager {
             &self.config.table_name,
         )
         .await?;
+        if let Some(table) = &self.iceberg_table {
+            self.validate_delete_file_format(table)?;
+            self.validate_partition_spec(table)?;
+            self.update_sort_order_if_changed().await?;
+        }
         Ok(())
     }
+
+    /// Update default sort order of the existing iceberg table, if parquet writer config has changed since table creation.
+    /// Data files written before are not rewritten, which is allowed since sort order only applies to newly written ones.
+    async fn update_sort_order_if_changed(&mut self) -> IcebergResult<()> {
+        let table_metadata = self.iceberg_table.as_ref().unwrap().metadata();
+        let sort_order = utils::get_iceberg_sort_order(
+            &self.mooncake_table_metadata.config.parquet_writer_config,
+            table_metadata.current_schema(),
+        )?;
+        if sort_order.fields == table_metadata.default_sort_order().fields {
+            return Ok(());
+        }
+
+        let table_commit_proxy = TableCommitProxy {
+            ident: self.get_table_ident(),
+            requirements: vec![TableRequirement::DefaultSortOrderIdMatch {
+                default_sort_order_id: table_metadata.default_sort_order_id(),
+            }],
+            updates: vec![
+                TableUpdate::AddSortOrder { sort_order },
+                // -1 refers to the last added sort order.
+                TableUpdate::SetDefaultSortOrder { sort_order_id: -1 },
+            ],
+        };
+        let updated_iceberg_table = self
+            .catalog
+            .update_table(table_commit_proxy.take_as_table_commit())
+            .await?;
+        self.iceberg_table = Some(updated_iceberg_table);
+        Ok(())
+    }
+
+    /// Validate that the iceberg table is written with the configured delete file format, since persisted deletions can't be
+    /// converted between formats. Tables created before the delete file format is recorded are not validated.
+    fn validate_delete_file_format(&self, table: &IcebergTable) -> IcebergResult<()> {
+        let expected_delete_file_format =
+            table_property::get_delete_file_format_name(self.config.delete_file_format);
+        if let Some(delete_file_format) = table
+            .metadata()
+            .properties()
+            .get(table_property::MOONLINK_DELETE_FILE_FORMAT)
+        {
+            if delete_file_format != expected_delete_file_format {
+                return Err(IcebergError::new(
+                    ErrorKind::FeatureUnsupported,
+                    format!(
+                        "iceberg table is written with delete file format {delete_file_format}, which doesn't match configured {expected_delete_file_format}"
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate that data files are partitioned the same way as the existing iceberg table, since the partition spec in
+    /// the table config is derived from the source table on every registration.
+    fn validate_partition_spec(&self, table: &IcebergTable) -> IcebergResult<()> {
+        let table_metadata = table.metadata();
+        partition_spec::validate_iceberg_partition_spec(
+            self.mooncake_table_metadata.config.partition_spec.as_ref(),
+            table_metadata.default_partition_spec(),
+            table_metadata.current_schema(),
+        )
+    }
 }
 
 /// TODO(hjiang): Parallelize all IO operations.
@@ -212,6 +309,13 @@ impl TableManager for IcebergTableManager {
         self.load_snapshot_from_table_impl().await
     }
 
+    async fn expire_snapshots(
+        &mut self,
+        retention_config: &SnapshotRetentionConfig,
+    ) -> IcebergResult<ExpireSnapshotsResult> {
+        self.expire_snapshots_impl(retention_config).await
+    }
+
     async fn drop_table(&mut self) -> IcebergResult<()> {
         let table_ident = TableIdent::new(
             NamespaceIdent::from_strs(&self.config.namespace).unwrap(),
@@ -4,12 +4,15 @@ use crate::storage::iceberg::deletion_vector::{
     DELETION_VECTOR_CADINALITY, DELETION_VECTOR_REFERENCED_DATA_FILE,
     MOONCAKE_DELETION_VECTOR_NUM_ROWS,
 };
+use crate::storage::iceberg::iceberg_table_config::IcebergDeleteFileFormat;
 use crate::storage::iceberg::iceberg_table_manager::*;
 use crate::storage::iceberg::index::FileIndexBlob;
 use crate::storage::iceberg::io_utils as iceberg_io_utils;
+use crate::storage::iceberg::position_delete::{self, PositionDeleteFileMetadata};
 use crate::storage::iceberg::puffin_utils;
 use crate::storage::iceberg::puffin_utils::PuffinBlobRef;
 use crate::storage::iceberg::schema_utils;
+use crate::storage::iceberg::snapshot_utils;
 use crate::storage::iceberg::table_manager::{PersistenceFileParams, PersistenceResult};
 use crate::storage::index::FileIndex as MooncakeFileIndex;
 use crate::storage::mooncake_table::delete_vector::BatchDeletionVector;
@@ -156,6 +159,45 @@ impl IcebergTableManager {
         Ok(puffin_blob_ref)
     }
 
+    /// Write deletion records to position delete parquet file, which is readable by iceberg v2 query engines.
+    /// Precondition: batch deletion vector is not empty.
+    ///
+    /// Position delete file is not referenced by mooncake snapshot, since in-memory batch deletion vector already contains all deleted rows.
+    async fn write_position_deletes(
+        &mut self,
+        data_file: String,
+        deletion_vector: &BatchDeletionVector,
+    ) -> IcebergResult<()> {
+        let deleted_rows = deletion_vector.collect_deleted_rows();
+        assert!(!deleted_rows.is_empty());
+
+        let record_count = deleted_rows.len() as u64;
+        let content = position_delete::serialize_position_deletes(&data_file, deleted_rows)?;
+        let file_size_in_bytes = content.len() as u64;
+        let position_delete_filepath = self.get_unique_position_delete_filepath();
+        self.filesystem_accessor
+            .write_object(&position_delete_filepath, content)
+            .await
+            .map_err(|e| {
+                IcebergError::new(
+                    iceberg::ErrorKind::Unexpected,
+                    format!(
+                        "Failed to write position delete file {position_delete_filepath}: {e:?}"
+                    ),
+                )
+                .with_retryable(true)
+            })?;
+
+        self.catalog
+            .record_position_delete_file(PositionDeleteFileMetadata {
+                file_path: position_delete_filepath,
+                referenced_data_file: data_file,
+                record_count,
+                file_size_in_bytes,
+            });
+        Ok(())
+    }
+
     /// Dump local data files into iceberg table.
     /// Return new iceberg data files for append transaction, and local data filepath to remote data filepath for index block remapping.
     async fn sync_data_files(
@@ -247,6 +289,7 @@ impl IcebergTableManager {
     }
 
     /// Dump committed deletion logs into iceberg table, only the changed part will be persisted.
+    /// Return puffin blobs for deletion vectors, which are empty if position delete files are written instead.
     async fn sync_deletion_vector(
         &mut self,
         new_deletion_logs: HashMap<MooncakeDataFileRef, BatchDeletionVector>,
@@ -269,18 +312,26 @@ impl IcebergTableManager {
             entry.deletion_vector.merge_with(&new_deletion_vector);
 
             // Data filepath in iceberg table.
-            let iceberg_data_file = entry.data_file.file_path();
-            let puffin_blob = self
-                .write_deletion_vector(
-                    iceberg_data_file.to_string(),
-                    entry.deletion_vector.clone(),
-                    file_params,
-                    puffin_index as u64,
-                )
-                .await?;
+            let iceberg_data_file = entry.data_file.file_path().to_string();
+            match self.config.delete_file_format {
+                IcebergDeleteFileFormat::DeletionVector => {
+                    let puffin_blob = self
+                        .write_deletion_vector(
+                            iceberg_data_file,
+                            entry.deletion_vector.clone(),
+                            file_params,
+                            puffin_index as u64,
+                        )
+                        .await?;
+                    puffin_deletion_blobs.insert(data_file.file_id(), puffin_blob);
+                }
+                IcebergDeleteFileFormat::PositionDelete => {
+                    self.write_position_deletes(iceberg_data_file, &entry.deletion_vector)
+                        .await?;
+                }
+            }
             let old_entry = self.persisted_data_files.insert(data_file.file_id(), entry);
             assert!(old_entry.is_some());
-            puffin_deletion_blobs.insert(data_file.file_id(), puffin_blob);
         }
         Ok(puffin_deletion_blobs)
     }
@@ -435,6 +486,37 @@ impl IcebergTableManager {
         Ok(remote_file_indices)
     }
 
+    /// Return whether manifest files should be compacted at the next commit, which is decided by the number of manifest files in the current snapshot.
+    /// Manifest list is only loaded when the number of manifest files is unknown.
+    async fn should_compact_manifests(&mut self) -> IcebergResult<bool> {
+        let manifest_files_to_compact = self
+            .mooncake_table_metadata
+            .config
+            .manifest_compaction_config
+            .manifest_files_to_compact;
+        // Skip manifest list load if manifest compaction is disabled.
+        if manifest_files_to_compact == u32::MAX {
+            return Ok(false);
+        }
+        let manifest_file_count = match self.manifest_file_count {
+            Some(manifest_file_count) => manifest_file_count,
+            None => {
+                let iceberg_table = self.iceberg_table.as_ref().unwrap();
+                let table_metadata = iceberg_table.metadata();
+                let cur_snapshot = match table_metadata.current_snapshot() {
+                    Some(cur_snapshot) => cur_snapshot,
+                    None => return Ok(false),
+                };
+                let manifest_list = cur_snapshot
+                    .load_manifest_list(iceberg_table.file_io(), table_metadata)
+                    .await?;
+                self.manifest_file_count = Some(manifest_list.entries().len());
+                manifest_list.entries().len()
+            }
+        };
+        Ok(manifest_file_count >= manifest_files_to_compact as usize)
+    }
+
     pub(crate) async fn sync_snapshot_impl(
         &mut self,
         mut snapshot_payload: IcebergSnapshotPayload,
@@ -449,6 +531,7 @@ impl IcebergTableManager {
 
         let new_data_files = take_data_files_to_import(&mut snapshot_payload);
         let old_data_files = take_data_files_to_remove(&mut snapshot_payload);
+        let has_data_files_to_remove = !old_data_files.is_empty();
         let new_file_indices = take_file_indices_to_import(&mut snapshot_payload);
         let old_file_indices = take_file_indices_to_remove(&mut snapshot_payload);
 
@@ -477,21 +560,23 @@ impl IcebergTableManager {
             .await?;
 
         // Update snapshot summary properties.
-        let mut snapshot_properties = HashMap::<String, String>::from([(
-            MOONCAKE_TABLE_FLUSH_LSN.to_string(),
-            snapshot_payload.flush_lsn.to_string(),
-        )]);
-        if let Some(wal_metadata) = snapshot_payload.wal_persistence_metadata {
-            snapshot_properties.insert(
-                MOONCAKE_WAL_METADATA.to_string(),
-                serde_json::to_string(&wal_metadata).unwrap(),
-            );
+        let snapshot_properties = snapshot_utils::create_snapshot_properties(
+            snapshot_payload.flush_lsn,
+            snapshot_payload.wal_persistence_metadata.as_ref(),
+            &snapshot_payload.initial_copy_position,
+        );
+
+        // Compact manifest files at the current commit, if there're too many of them.
+        let compact_manifests = self.should_compact_manifests().await?;
+        if compact_manifests {
+            self.catalog.set_compact_manifests();
         }
 
         let mut txn = Transaction::new(self.iceberg_table.as_ref().unwrap());
         let action = txn.fast_append();
+        let has_new_data_files = !data_file_import_result.new_iceberg_data_files.is_empty();
         // Only start append action when there're new data files.
-        if !data_file_import_result.new_iceberg_data_files.is_empty() {
+        if has_new_data_files {
             let action = action.add_data_files(data_file_import_result.new_iceberg_data_files);
             let action = action.set_snapshot_properties(snapshot_properties);
             txn = action.apply(txn)?;
@@ -506,6 +591,15 @@ impl IcebergTableManager {
         let updated_iceberg_table = txn.commit(&*self.catalog).await?;
         self.iceberg_table = Some(updated_iceberg_table);
 
+        // Data file manifests are rewritten at manifest compaction and data file removal; otherwise fast append adds at most one data file manifest,
+        // while deletion vectors and file indices are always rewritten into one manifest file for each.
+        self.manifest_file_count = match self.manifest_file_count {
+            Some(manifest_file_count) if !compact_manifests && !has_data_files_to_remove => {
+                Some(manifest_file_count + usize::from(has_new_data_files))
+            }
+            _ => None,
+        };
+
         self.catalog.clear_puffin_metadata();
 
         // NOTICE: persisted data files and file indices are returned in the order of (1) newly imported ones; (2) index merge ones; (3) data compacted ones.
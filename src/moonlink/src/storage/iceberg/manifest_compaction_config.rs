@@ -0,0 +1,23 @@
+use typed_builder::TypedBuilder;
+
+/// Configurations for iceberg manifest compaction.
+///
+/// Each iceberg persistence appends a new data file manifest, manifest files are compacted into at most one per entry type (data files, deletion vectors and file indices) at the next persistence, when there're too many of them.
+#[derive(Clone, Debug, PartialEq, TypedBuilder)]
+pub struct ManifestCompactionConfig {
+    /// Number of manifest files in the current snapshot to trigger a manifest compaction.
+    pub manifest_files_to_compact: u32,
+}
+
+impl ManifestCompactionConfig {
+    /// Default value is aligned with iceberg table property `commit.manifest.min-count-to-merge`.
+    pub const DEFAULT_MANIFEST_FILES_TO_COMPACT: u32 = 100;
+}
+
+impl Default for ManifestCompactionConfig {
+    fn default() -> Self {
+        Self {
+            manifest_files_to_compact: Self::DEFAULT_MANIFEST_FILES_TO_COMPACT,
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::storage::iceberg::position_delete::PositionDeleteFileMetadata;
+
 use async_trait::async_trait;
 /// A trait which defines deletion vector write related interfaces.
 use iceberg::puffin::PuffinWriter;
@@ -24,6 +26,12 @@ pub trait PuffinWrite {
     /// Set puffin file to remove.
     fn set_puffin_files_to_remove(&mut self, puffin_filepaths: HashSet<String>);
 
+    /// Add position delete file, which overwrites existing deletion records for its referenced data file.
+    fn record_position_delete_file(&mut self, position_delete: PositionDeleteFileMetadata);
+
+    /// Request to compact all manifest files at the next commit.
+    fn set_compact_manifests(&mut self);
+
     /// After transaction commits, puffin metadata should be cleared for next puffin write.
     fn clear_puffin_metadata(&mut self);
 }
@@ -21,8 +21,8 @@ use iceberg::arrow::DEFAULT_MAP_FIELD_NAME;
 use iceberg::io::FileIOBuilder;
 use iceberg::spec::{
     visit_schema, DataContentType, DataFile, DataFileBuilder, DataFileFormat, ListType, MapType,
-    NestedFieldRef, PrimitiveType, Schema, SchemaRef, SchemaVisitor, Struct, StructType,
-    TableMetadata,
+    NestedFieldRef, PartitionSpec, PrimitiveType, Schema, SchemaRef, SchemaVisitor, Struct,
+    StructType, TableMetadata,
 };
 use iceberg::Result as IcebergResult;
 use iceberg::{Error as IcebergError, ErrorKind};
@@ -31,6 +31,7 @@ use parquet::file::metadata::ParquetMetaData;
 
 use crate::storage::iceberg::parquet_metadata_utils;
 use crate::storage::iceberg::parquet_stats_utils::MinMaxColAggregator;
+use crate::storage::mooncake_table::partition_spec;
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -166,13 +167,14 @@ impl SchemaVisitor for IndexByParquetPathName {
 // parquet_to_data_file_builder
 // ================================
 //
-// `ParquetMetadata` to data file builder
+// `ParquetMetadata` to data file builder, partition value is derived from column lower bounds if partition spec given.
 fn parquet_to_data_file_builder(
     schema: SchemaRef,
     metadata: Arc<ParquetMetaData>,
     written_size: usize,
     file_path: String,
     nan_value_counts: HashMap<i32, u64>,
+    partition_spec: Option<&PartitionSpec>,
 ) -> IcebergResult<DataFileBuilder> {
     let index_by_parquet_path = {
         let mut visitor = IndexByParquetPathName::new();
@@ -184,7 +186,7 @@ fn parquet_to_data_file_builder(
         let mut per_col_size: HashMap<i32, u64> = HashMap::new();
         let mut per_col_val_num: HashMap<i32, u64> = HashMap::new();
         let mut per_col_null_val_num: HashMap<i32, u64> = HashMap::new();
-        let mut min_max_agg = MinMaxColAggregator::new(schema);
+        let mut min_max_agg = MinMaxColAggregator::new(schema.clone());
 
         for row_group in metadata.row_groups() {
             for column_chunk_metadata in row_group.columns() {
@@ -216,12 +218,19 @@ fn parquet_to_data_file_builder(
         )
     };
 
+    let partition = match partition_spec {
+        Some(partition_spec) => {
+            partition_spec::get_iceberg_partition_value(partition_spec, &schema, &lower_bounds)?
+        }
+        None => Struct::empty(),
+    };
+
     let mut builder = DataFileBuilder::default();
     builder
         .content(DataContentType::Data)
         .file_path(file_path)
         .file_format(DataFileFormat::Parquet)
-        .partition(Struct::empty())
+        .partition(partition)
         .record_count(metadata.file_metadata().num_rows() as u64)
         .file_size_in_bytes(written_size as u64)
         .column_sizes(column_sizes)
@@ -280,6 +289,7 @@ pub(crate) async fn get_data_file_from_local_parquet_file(
         remote_parquet_file,
         // TODO: Implement nan_value_counts here
         HashMap::new(),
+        Some(table_metadata.default_partition_spec().as_ref()),
     )?;
     builder.partition_spec_id(table_metadata.default_partition_spec_id());
 
@@ -298,6 +308,8 @@ mod tests {
     use arrow::record_batch::RecordBatch;
     use arrow_array::{Int32Array, Int64Array};
     use iceberg::arrow as IcebergArrow;
+    use iceberg::spec::Datum;
+    use parquet::schema::types::ColumnPath;
     use parquet::{arrow::AsyncArrowWriter, file::properties::WriterProperties};
     use tempfile::tempdir;
 
@@ -329,8 +341,10 @@ mod tests {
         let local_filepath = tmp_dir.path().join("src.parquet");
         let remote_filepath = tmp_dir.path().join("dst.parquet");
         let file = tokio::fs::File::create(&local_filepath).await.unwrap();
+        // Bloom filter is written before footer, which shouldn't affect column statistics.
         let props = WriterProperties::builder()
             .set_compression(parquet::basic::Compression::UNCOMPRESSED)
+            .set_column_bloom_filter_enabled(ColumnPath::from("id"), true)
             .build();
 
         let mut writer = AsyncArrowWriter::try_new(file, schema.clone(), Some(props)).unwrap();
@@ -348,6 +362,7 @@ mod tests {
             file_size,
             remote_filepath.to_str().unwrap().to_string(),
             /*nan_value_counts=*/ HashMap::new(),
+            /*partition_spec=*/ None,
         )
         .unwrap();
         data_file_builder.partition_spec_id(0);
@@ -357,5 +372,9 @@ mod tests {
         assert_eq!(data_file.file_path(), remote_filepath.to_str().unwrap());
         assert_eq!(data_file.file_format(), DataFileFormat::Parquet);
         assert_eq!(data_file.record_count(), 4);
+        assert_eq!(data_file.lower_bounds().get(&1).unwrap(), &Datum::int(1));
+        assert_eq!(data_file.upper_bounds().get(&1).unwrap(), &Datum::int(4));
+        assert_eq!(data_file.lower_bounds().get(&2).unwrap(), &Datum::long(10));
+        assert_eq!(data_file.upper_bounds().get(&2).unwrap(), &Datum::long(40));
     }
 }
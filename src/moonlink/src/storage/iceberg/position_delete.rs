@@ -0,0 +1,208 @@
+/// Iceberg position delete file is the iceberg v2 compatible persistent format of in-memory BatchDeletionVector.
+/// On persistence stage, deleted rows of a data file are written into one parquet file with position delete schema;
+/// at recovery, batch deletion vector is constructed back by reading all positions in the parquet file.
+///
+/// Position delete file spec: https://iceberg.apache.org/spec/#position-delete-files
+use crate::storage::mooncake_table::delete_vector::BatchDeletionVector;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow_array::{Array, Int64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema as ArrowSchema};
+use futures::TryStreamExt;
+use iceberg::{Error as IcebergError, Result as IcebergResult};
+use parquet::arrow::{ArrowWriter, ParquetRecordBatchStreamBuilder, PARQUET_FIELD_ID_META_KEY};
+
+/// Reserved field id and name for the data file path column, as defined in iceberg spec.
+const POSITION_DELETE_FILE_PATH_FIELD_ID: i32 = 2147483546;
+const POSITION_DELETE_FILE_PATH_FIELD_NAME: &str = "file_path";
+/// Reserved field id and name for the row position column, as defined in iceberg spec.
+const POSITION_DELETE_POS_FIELD_ID: i32 = 2147483545;
+const POSITION_DELETE_POS_FIELD_NAME: &str = "pos";
+
+/// Metadata for position delete file, which is recorded at catalog and appended to manifest file before transaction commits.
+#[derive(Clone, Debug)]
+pub(crate) struct PositionDeleteFileMetadata {
+    /// Position delete filepath in iceberg table.
+    pub(crate) file_path: String,
+    /// Data file all positions refer to, moonlink places deletions for one data file within one position delete file.
+    pub(crate) referenced_data_file: String,
+    /// Number of deleted rows.
+    pub(crate) record_count: u64,
+    /// Position delete file size.
+    pub(crate) file_size_in_bytes: u64,
+}
+
+/// Get arrow schema for position delete file.
+fn get_position_delete_arrow_schema() -> ArrowSchema {
+    let get_field = |name: &str, data_type: DataType, field_id: i32| {
+        Field::new(name, data_type, /*nullable=*/ false).with_metadata(HashMap::from([(
+            PARQUET_FIELD_ID_META_KEY.to_string(),
+            field_id.to_string(),
+        )]))
+    };
+    ArrowSchema::new(vec![
+        get_field(
+            POSITION_DELETE_FILE_PATH_FIELD_NAME,
+            DataType::Utf8,
+            POSITION_DELETE_FILE_PATH_FIELD_ID,
+        ),
+        get_field(
+            POSITION_DELETE_POS_FIELD_NAME,
+            DataType::Int64,
+            POSITION_DELETE_POS_FIELD_ID,
+        ),
+    ])
+}
+
+/// Serialize deleted rows of the given data file into parquet bytes, with position delete schema.
+/// Precondition: deleted rows are sorted in ascending order, as required by iceberg spec.
+pub(crate) fn serialize_position_deletes(
+    referenced_data_file: &str,
+    deleted_rows: Vec<u64>,
+) -> IcebergResult<Vec<u8>> {
+    let to_iceberg_error = |e: parquet::errors::ParquetError| {
+        IcebergError::new(
+            iceberg::ErrorKind::Unexpected,
+            format!("Failed to write position deletes for {referenced_data_file}: {e:?}"),
+        )
+    };
+
+    let schema = Arc::new(get_position_delete_arrow_schema());
+    let file_paths = StringArray::from(vec![referenced_data_file; deleted_rows.len()]);
+    let positions = Int64Array::from_iter_values(deleted_rows.into_iter().map(|row| row as i64));
+    let record_batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![Arc::new(file_paths), Arc::new(positions)],
+    )
+    .map_err(|e| {
+        IcebergError::new(
+            iceberg::ErrorKind::DataInvalid,
+            format!("Failed to create position delete record batch: {e:?}"),
+        )
+    })?;
+
+    let mut writer =
+        ArrowWriter::try_new(Vec::new(), schema, /*props=*/ None).map_err(to_iceberg_error)?;
+    writer.write(&record_batch).map_err(to_iceberg_error)?;
+    writer.into_inner().map_err(to_iceberg_error)
+}
+
+/// Load deleted rows from the local position delete parquet file, grouped by the data files they refer to.
+pub(crate) async fn load_position_deletes_from_file(
+    local_filepath: &str,
+) -> IcebergResult<HashMap<String /*data file*/, Vec<u64> /*deleted rows*/>> {
+    let to_iceberg_error = |e: parquet::errors::ParquetError| {
+        IcebergError::new(
+            iceberg::ErrorKind::DataInvalid,
+            format!("Failed to read position delete file {local_filepath}: {e:?}"),
+        )
+    };
+
+    let file = tokio::fs::File::open(local_filepath).await.map_err(|e| {
+        IcebergError::new(
+            iceberg::ErrorKind::Unexpected,
+            format!("Failed to open position delete file {local_filepath}: {e:?}"),
+        )
+        .with_retryable(true)
+    })?;
+    let mut reader = ParquetRecordBatchStreamBuilder::new(file)
+        .await
+        .map_err(to_iceberg_error)?
+        .build()
+        .map_err(to_iceberg_error)?;
+
+    let mut deleted_rows: HashMap<String, Vec<u64>> = HashMap::new();
+    while let Some(record_batch) = reader.try_next().await.map_err(to_iceberg_error)? {
+        let file_paths = record_batch
+            .column_by_name(POSITION_DELETE_FILE_PATH_FIELD_NAME)
+            .and_then(|col| col.as_any().downcast_ref::<StringArray>());
+        let positions = record_batch
+            .column_by_name(POSITION_DELETE_POS_FIELD_NAME)
+            .and_then(|col| col.as_any().downcast_ref::<Int64Array>());
+        let (Some(file_paths), Some(positions)) = (file_paths, positions) else {
+            return Err(IcebergError::new(
+                iceberg::ErrorKind::DataInvalid,
+                format!(
+                    "Position delete file {local_filepath} doesn't follow position delete schema"
+                ),
+            ));
+        };
+        for idx in 0..record_batch.num_rows() {
+            if file_paths.is_null(idx) || positions.is_null(idx) || positions.value(idx) < 0 {
+                return Err(IcebergError::new(
+                    iceberg::ErrorKind::DataInvalid,
+                    format!(
+                        "Position delete file {local_filepath} contains invalid entry at row {idx}"
+                    ),
+                ));
+            }
+            deleted_rows
+                .entry(file_paths.value(idx).to_string())
+                .or_default()
+                .push(positions.value(idx) as u64);
+        }
+    }
+    Ok(deleted_rows)
+}
+
+/// Apply deleted rows to the batch deletion vector of a data file with the given number of rows.
+pub(crate) fn apply_position_deletes(
+    batch_deletion_vector: &mut BatchDeletionVector,
+    referenced_data_file: &str,
+    deleted_rows: Vec<u64>,
+) -> IcebergResult<()> {
+    let max_rows = batch_deletion_vector.get_max_rows();
+    for row_idx in deleted_rows.into_iter() {
+        if row_idx as usize >= max_rows {
+            return Err(IcebergError::new(
+                iceberg::ErrorKind::DataInvalid,
+                format!("Position {row_idx} out of range for data file {referenced_data_file} with {max_rows} rows"),
+            ));
+        }
+        batch_deletion_vector.delete_row(row_idx as usize);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_position_deletes_serde() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let filepath = temp_dir.path().join("position_deletes.parquet");
+        let filepath = filepath.to_str().unwrap();
+        let referenced_data_file = "/tmp/iceberg/data/filename";
+
+        let deleted_rows = vec![0, 3, 5, 1999];
+        let content =
+            serialize_position_deletes(referenced_data_file, deleted_rows.clone()).unwrap();
+        tokio::fs::write(filepath, content).await.unwrap();
+
+        let mut loaded_deletes = load_position_deletes_from_file(filepath).await.unwrap();
+        assert_eq!(loaded_deletes.len(), 1);
+        let loaded_rows = loaded_deletes.remove(referenced_data_file).unwrap();
+        assert_eq!(loaded_rows, deleted_rows);
+
+        let mut batch_deletion_vector = BatchDeletionVector::new(/*max_rows=*/ 2000);
+        apply_position_deletes(
+            &mut batch_deletion_vector,
+            referenced_data_file,
+            loaded_rows.clone(),
+        )
+        .unwrap();
+        assert_eq!(batch_deletion_vector.collect_deleted_rows(), deleted_rows);
+
+        // Positions out of data file range are rejected.
+        let mut batch_deletion_vector = BatchDeletionVector::new(/*max_rows=*/ 1000);
+        assert!(apply_position_deletes(
+            &mut batch_deletion_vector,
+            referenced_data_file,
+            loaded_rows,
+        )
+        .is_err());
+    }
+}
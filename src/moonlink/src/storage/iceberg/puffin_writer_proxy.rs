@@ -15,13 +15,14 @@ use crate::storage::iceberg::deletion_vector::{
     DELETION_VECTOR_CADINALITY, DELETION_VECTOR_REFERENCED_DATA_FILE,
 };
 use crate::storage::iceberg::index::{MOONCAKE_HASH_INDEX_V1, MOONCAKE_HASH_INDEX_V1_CARDINALITY};
+use crate::storage::iceberg::position_delete::PositionDeleteFileMetadata;
 
 use std::collections::{HashMap, HashSet};
 
 use iceberg::io::FileIO;
 use iceberg::puffin::{CompressionCodec, PuffinWriter, DELETION_VECTOR_V1};
 use iceberg::spec::{
-    DataContentType, DataFile, DataFileFormat, Datum, FormatVersion, ManifestContentType,
+    DataContentType, DataFile, DataFileFormat, Datum, FormatVersion, Literal, ManifestContentType,
     ManifestListWriter, ManifestWriter, ManifestWriterBuilder, Snapshot, Struct, TableMetadata,
 };
 use iceberg::Result as IcebergResult;
@@ -208,16 +209,19 @@ pub(crate) async fn get_puffin_metadata_and_close(
 }
 
 /// Util function to get `DataFileProxy` for new file index puffin blob.
+/// File index covers data files of all partitions, so it's placed under null partition.
 fn get_data_file_for_file_index(
     puffin_filepath: &str,
     blob_metadata: &PuffinBlobMetadataProxy,
+    null_partition: Struct,
+    partition_spec_id: i32,
 ) -> DataFile {
     assert_eq!(blob_metadata.r#type, MOONCAKE_HASH_INDEX_V1);
     let data_file_proxy = DataFileProxy {
         content: DataContentType::Data,
         file_path: puffin_filepath.to_string(),
         file_format: DataFileFormat::Puffin,
-        partition: Struct::empty(),
+        partition: null_partition,
         record_count: blob_metadata
             .properties
             .get(MOONCAKE_HASH_INDEX_V1_CARDINALITY)
@@ -236,7 +240,7 @@ fn get_data_file_for_file_index(
         equality_ids: Vec::new(),
         sort_order_id: None,
         first_row_id: None,
-        partition_spec_id: 0,
+        partition_spec_id,
         referenced_data_file: None,
         content_offset: None,
         content_size_in_bytes: None,
@@ -245,9 +249,13 @@ fn get_data_file_for_file_index(
 }
 
 /// Util function to get `DataFileProxy` for deletion vector puffin blob.
+/// Deletion vector is placed under the same partition as its referenced data file.
 fn get_data_file_for_deletion_vector(
     puffin_filepath: &str,
     blob_metadata: &PuffinBlobMetadataProxy,
+    data_file_partitions: &HashMap<String, Struct>,
+    null_partition: &Struct,
+    partition_spec_id: i32,
 ) -> (String /*referenced_data_filepath*/, DataFile) {
     assert_eq!(blob_metadata.r#type, DELETION_VECTOR_V1);
     let referenced_data_filepath = blob_metadata
@@ -255,12 +263,16 @@ fn get_data_file_for_deletion_vector(
         .get(DELETION_VECTOR_REFERENCED_DATA_FILE)
         .unwrap()
         .clone();
+    let partition = data_file_partitions
+        .get(&referenced_data_filepath)
+        .cloned()
+        .unwrap_or_else(|| null_partition.clone());
 
     let data_file_proxy = DataFileProxy {
         content: DataContentType::PositionDeletes,
         file_path: puffin_filepath.to_string(),
         file_format: DataFileFormat::Puffin,
-        partition: Struct::empty(),
+        partition,
         record_count: blob_metadata
             .properties
             .get(DELETION_VECTOR_CADINALITY)
@@ -279,7 +291,7 @@ fn get_data_file_for_deletion_vector(
         equality_ids: Vec::new(),
         sort_order_id: None,
         first_row_id: None,
-        partition_spec_id: 0,
+        partition_spec_id,
         referenced_data_file: Some(referenced_data_filepath.clone()),
         content_offset: Some(blob_metadata.offset as i64),
         content_size_in_bytes: Some(blob_metadata.length as i64),
@@ -288,6 +300,45 @@ fn get_data_file_for_deletion_vector(
     (referenced_data_filepath, data_file)
 }
 
+/// Util function to get `DataFileProxy` for position delete parquet file.
+/// Position delete file is placed under the same partition as its referenced data file.
+fn get_data_file_for_position_delete(
+    position_delete: &PositionDeleteFileMetadata,
+    data_file_partitions: &HashMap<String, Struct>,
+    null_partition: &Struct,
+    partition_spec_id: i32,
+) -> DataFile {
+    let partition = data_file_partitions
+        .get(&position_delete.referenced_data_file)
+        .cloned()
+        .unwrap_or_else(|| null_partition.clone());
+
+    let data_file_proxy = DataFileProxy {
+        content: DataContentType::PositionDeletes,
+        file_path: position_delete.file_path.clone(),
+        file_format: DataFileFormat::Parquet,
+        partition,
+        record_count: position_delete.record_count,
+        file_size_in_bytes: position_delete.file_size_in_bytes,
+        column_sizes: HashMap::new(),
+        value_counts: HashMap::new(),
+        null_value_counts: HashMap::new(),
+        nan_value_counts: HashMap::new(),
+        lower_bounds: HashMap::new(),
+        upper_bounds: HashMap::new(),
+        key_metadata: None,
+        split_offsets: Vec::new(),
+        equality_ids: Vec::new(),
+        sort_order_id: None,
+        first_row_id: None,
+        partition_spec_id,
+        referenced_data_file: Some(position_delete.referenced_data_file.clone()),
+        content_offset: None,
+        content_size_in_bytes: None,
+    };
+    unsafe { std::mem::transmute::<DataFileProxy, DataFile>(data_file_proxy) }
+}
+
 /// Util function to create manifest list writer and delete current one.
 async fn create_new_manifest_list_writer(
     table_metadata: &TableMetadata,
@@ -336,8 +387,11 @@ fn create_manifest_writer_builder(
 /// Get all manifest files and entries,
 /// - Data file entries: retain all entries except those marked for removal due to compaction.
 /// - Deletion vector entries: remove entries referencing data files to be removed, and merge retained deletion vectors with the provided puffin deletion vector blob.
+/// - Position delete entries: handled the same way as deletion vectors, a new position delete file overwrites the existing delete entry for the same data file.
 /// - File indices entries: retain all entries except those marked for removal due to index merging or data file compaction.
 ///
+/// If [`compact_manifests`] is requested, data file entries are merged into one manifest file as well, so manifest list is compacted to at most one manifest file per entry type.
+///
 /// For more details, please refer to https://docs.google.com/document/d/1fIvrRfEHWBephsX0Br2G-Ils_30JIkmGkcdbFbovQjI/edit?usp=sharing
 ///
 /// Note: this function should be called before catalog transaction commit.
@@ -351,10 +405,14 @@ pub(crate) async fn append_puffin_metadata_and_rewrite(
     data_files_to_remove: &HashSet<String>,
     puffin_blobs_to_add: &HashMap<String, Vec<PuffinBlobMetadataProxy>>,
     puffin_blobs_to_remove: &HashSet<String>,
+    position_deletes_to_add: &[PositionDeleteFileMetadata],
+    compact_manifests: bool,
 ) -> IcebergResult<()> {
     if data_files_to_remove.is_empty()
         && puffin_blobs_to_add.is_empty()
+        && position_deletes_to_add.is_empty()
         && puffin_blobs_to_remove.is_empty()
+        && !compact_manifests
     {
         return Ok(());
     }
@@ -412,10 +470,16 @@ pub(crate) async fn append_puffin_metadata_and_rewrite(
 
     // Map from referenced data file to deletion vector manifest entry.
     let mut existing_deletion_vector_entries = HashMap::new();
+    // Map from data file to its partition, which new deletion vectors inherit.
+    let mut data_file_partitions = HashMap::new();
+    let partition_spec = table_metadata.default_partition_spec();
+    let partition_spec_id = table_metadata.default_partition_spec_id();
+    let null_partition = Struct::from_iter(partition_spec.fields().iter().map(|_| None::<Literal>));
 
     // How to tell different manifest entry types:
     // - Data file: manifest content type `Data`, manifest entry file format `Parquet`
     // - Deletion vector: manifest content type `Deletes`, manifest entry file format `Puffin`
+    // - Position delete: manifest content type `Deletes`, manifest entry file format `Parquet`
     // - File indices: manifest content type `Data`, manifest entry file format `Puffin`
     for cur_manifest_file in manifest_list.entries() {
         let manifest = cur_manifest_file.load_manifest(file_io).await?;
@@ -424,10 +488,24 @@ pub(crate) async fn append_puffin_metadata_and_rewrite(
         // Assumption: we store all data file manifest entries in one manifest file.
         assert!(!manifest_entries.is_empty());
 
-        // For data file manifest entries, if nothing to remove we simply append the manifest file and do nothing.
+        if !partition_spec.is_unpartitioned() {
+            for cur_manifest_entry in manifest_entries.iter() {
+                if cur_manifest_entry.data_file().content_type() == DataContentType::Data
+                    && cur_manifest_entry.file_format() == DataFileFormat::Parquet
+                {
+                    data_file_partitions.insert(
+                        cur_manifest_entry.data_file().file_path().to_string(),
+                        cur_manifest_entry.data_file().partition().clone(),
+                    );
+                }
+            }
+        }
+
+        // For data file manifest entries, if nothing to remove nor compact we simply append the manifest file and do nothing.
         if *manifest_metadata.content() == ManifestContentType::Data
             && manifest_entries.first().as_ref().unwrap().file_format() == DataFileFormat::Parquet
             && data_files_to_remove.is_empty()
+            && !compact_manifests
         {
             manifest_list_writer.add_manifests([cur_manifest_file.clone()].into_iter())?;
             continue;
@@ -440,8 +518,9 @@ pub(crate) async fn append_puffin_metadata_and_rewrite(
             // ============================
             //
             // Process data files, remove those been merged; and compact all data file entries into one manifest file.
-            if cur_manifest_entry.file_format() == DataFileFormat::Parquet {
-                assert_eq!(*manifest_metadata.content(), ManifestContentType::Data);
+            if *manifest_metadata.content() == ManifestContentType::Data
+                && cur_manifest_entry.file_format() == DataFileFormat::Parquet
+            {
                 if data_files_to_remove.contains(cur_manifest_entry.data_file().file_path()) {
                     continue;
                 }
@@ -458,8 +537,8 @@ pub(crate) async fn append_puffin_metadata_and_rewrite(
             // ============================
             //
             // Process file indices: skip those requested to remove, and keep those un-mentioned.
-            assert_eq!(cur_manifest_entry.file_format(), DataFileFormat::Puffin);
             if *manifest_metadata.content() == ManifestContentType::Data {
+                assert_eq!(cur_manifest_entry.file_format(), DataFileFormat::Puffin);
                 // Skip file indices which are requested to remove (due to index merge and data file compaction).
                 if puffin_blobs_to_remove.contains(cur_manifest_entry.data_file().file_path()) {
                     continue;
@@ -475,10 +554,10 @@ pub(crate) async fn append_puffin_metadata_and_rewrite(
             }
 
             // ============================
-            // Deletion vector entries
+            // Deletion vector and position delete entries
             // ============================
             //
-            // Process deletion vectors.
+            // Process deletion vectors and position deletes.
             assert_eq!(*manifest_metadata.content(), ManifestContentType::Deletes);
 
             // Skip deletion vectors which are requested to remove (due to compaction).
@@ -510,7 +589,12 @@ pub(crate) async fn append_puffin_metadata_and_rewrite(
         for cur_blob_metadata in blob_metadata.iter() {
             // Handle mooncake hash index v1.
             if cur_blob_metadata.r#type == MOONCAKE_HASH_INDEX_V1 {
-                let data_file = get_data_file_for_file_index(puffin_filepath, cur_blob_metadata);
+                let data_file = get_data_file_for_file_index(
+                    puffin_filepath,
+                    cur_blob_metadata,
+                    null_partition.clone(),
+                    partition_spec_id,
+                );
                 init_file_index_manifest_writer(&mut file_index_manifest_writer)?;
                 file_index_manifest_writer
                     .as_mut()
@@ -520,8 +604,13 @@ pub(crate) async fn append_puffin_metadata_and_rewrite(
             }
 
             // Handle deletion vectors.
-            let (referenced_data_filepath, data_file) =
-                get_data_file_for_deletion_vector(puffin_filepath, cur_blob_metadata);
+            let (referenced_data_filepath, data_file) = get_data_file_for_deletion_vector(
+                puffin_filepath,
+                cur_blob_metadata,
+                &data_file_partitions,
+                &null_partition,
+                partition_spec_id,
+            );
             existing_deletion_vector_entries.remove(&referenced_data_filepath);
             init_deletion_vector_manifest_writer_for_once(&mut deletion_vector_manifest_writer)?;
             deletion_vector_manifest_writer
@@ -531,6 +620,22 @@ pub(crate) async fn append_puffin_metadata_and_rewrite(
         }
     }
 
+    // Append position delete files, which overwrite existing delete entries for the same data files.
+    for cur_position_delete in position_deletes_to_add.iter() {
+        let data_file = get_data_file_for_position_delete(
+            cur_position_delete,
+            &data_file_partitions,
+            &null_partition,
+            partition_spec_id,
+        );
+        existing_deletion_vector_entries.remove(&cur_position_delete.referenced_data_file);
+        init_deletion_vector_manifest_writer_for_once(&mut deletion_vector_manifest_writer)?;
+        deletion_vector_manifest_writer
+            .as_mut()
+            .unwrap()
+            .add_file(data_file, table_metadata.last_sequence_number())?;
+    }
+
     // Add old deletion vector entries which doesn't get overwritten.
     for (_, cur_manifest_entry) in existing_deletion_vector_entries.drain() {
         init_deletion_vector_manifest_writer_for_once(&mut deletion_vector_manifest_writer)?;
@@ -0,0 +1,284 @@
+use crate::storage::iceberg::catalog_utils;
+use crate::storage::iceberg::moonlink_catalog::{PuffinWrite, SchemaUpdate};
+use crate::storage::iceberg::position_delete::PositionDeleteFileMetadata;
+use crate::storage::iceberg::puffin_writer_proxy::{
+    append_puffin_metadata_and_rewrite, get_puffin_metadata_and_close, PuffinBlobMetadataProxy,
+};
+use crate::storage::iceberg::table_commit_proxy::TableCommitProxy;
+
+/// This module contains the REST catalog implementation, which delegates table metadata management to an iceberg REST catalog server (i.e. Polaris, Lakekeeper, Nessie).
+///
+/// Manifest files and manifest lists are still written by moonlink into the table location, catalog server only tracks metadata; so deletion vectors are
+/// appended to manifests before the commit request is sent, and table commits are applied atomically by the catalog server with requirements checked.
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+use iceberg::puffin::PuffinWriter;
+use iceberg::spec::{Schema as IcebergSchema, TableMetadataBuilder};
+use iceberg::table::Table;
+use iceberg::Result as IcebergResult;
+use iceberg::{
+    Catalog, Namespace, NamespaceIdent, TableCommit, TableCreation, TableIdent, TableRequirement,
+};
+use iceberg_catalog_rest::{RestCatalog as IcebergRestCatalog, RestCatalogConfig};
+
+#[derive(Debug)]
+pub struct RestCatalog {
+    /// REST catalog client.
+    catalog: IcebergRestCatalog,
+    /// Used to overwrite iceberg metadata at table creation.
+    iceberg_schema: IcebergSchema,
+    /// Used to record puffin blob metadata in one transaction, and cleaned up after transaction commits.
+    ///
+    /// Maps from "puffin filepath" to "puffin blob metadata".
+    puffin_blobs_to_add: HashMap<String, Vec<PuffinBlobMetadataProxy>>,
+    /// A vector of "puffin filepath"s.
+    puffin_blobs_to_remove: HashSet<String>,
+    /// Used to record position delete files in one transaction, and cleaned up after transaction commits.
+    position_deletes_to_add: Vec<PositionDeleteFileMetadata>,
+    /// A set of data files to remove, along with their corresponding deletion vectors and file indices.
+    data_files_to_remove: HashSet<String>,
+    /// Whether to compact all manifest files at the next commit.
+    compact_manifests: bool,
+}
+
+impl RestCatalog {
+    /// Create a REST catalog, connection to catalog server is established lazily.
+    pub fn new(
+        uri: String,
+        warehouse: Option<String>,
+        props: HashMap<String, String>,
+        iceberg_schema: IcebergSchema,
+    ) -> Self {
+        let config = match warehouse {
+            Some(warehouse) => RestCatalogConfig::builder()
+                .uri(uri)
+                .warehouse(warehouse)
+                .props(props)
+                .build(),
+            None => RestCatalogConfig::builder().uri(uri).props(props).build(),
+        };
+        Self {
+            catalog: IcebergRestCatalog::new(config),
+            iceberg_schema,
+            puffin_blobs_to_add: HashMap::new(),
+            puffin_blobs_to_remove: HashSet::new(),
+            position_deletes_to_add: Vec::new(),
+            data_files_to_remove: HashSet::new(),
+            compact_manifests: false,
+        }
+    }
+
+    /// Commit the given schema as current schema, with current schema id checked at catalog server.
+    async fn commit_current_schema(
+        &self,
+        table: &Table,
+        new_schema: IcebergSchema,
+    ) -> IcebergResult<Table> {
+        let metadata = table.metadata().clone();
+        let current_schema_id = metadata.current_schema_id();
+        let metadata_builder_result = metadata
+            .into_builder(
+                table
+                    .metadata_location()
+                    .map(|location| location.to_string()),
+            )
+            .add_current_schema(new_schema)?
+            .build()?;
+
+        let table_commit_proxy = TableCommitProxy {
+            ident: table.identifier().clone(),
+            requirements: vec![TableRequirement::CurrentSchemaIdMatch { current_schema_id }],
+            updates: metadata_builder_result.changes,
+        };
+        self.catalog
+            .update_table(table_commit_proxy.take_as_table_commit())
+            .await
+    }
+}
+
+#[async_trait]
+impl PuffinWrite for RestCatalog {
+    async fn record_puffin_metadata_and_close(
+        &mut self,
+        puffin_filepath: String,
+        puffin_writer: PuffinWriter,
+    ) -> IcebergResult<()> {
+        let puffin_metadata = get_puffin_metadata_and_close(puffin_writer).await?;
+        self.puffin_blobs_to_add
+            .insert(puffin_filepath, puffin_metadata);
+        Ok(())
+    }
+
+    fn set_data_files_to_remove(&mut self, data_files: HashSet<String>) {
+        assert!(self.data_files_to_remove.is_empty());
+        self.data_files_to_remove = data_files;
+    }
+
+    fn set_puffin_files_to_remove(&mut self, puffin_filepaths: HashSet<String>) {
+        assert!(self.puffin_blobs_to_remove.is_empty());
+        self.puffin_blobs_to_remove = puffin_filepaths;
+    }
+
+    fn record_position_delete_file(&mut self, position_delete: PositionDeleteFileMetadata) {
+        self.position_deletes_to_add.push(position_delete);
+    }
+
+    fn set_compact_manifests(&mut self) {
+        self.compact_manifests = true;
+    }
+
+    fn clear_puffin_metadata(&mut self) {
+        self.puffin_blobs_to_add.clear();
+        self.puffin_blobs_to_remove.clear();
+        self.position_deletes_to_add.clear();
+        self.data_files_to_remove.clear();
+        self.compact_manifests = false;
+    }
+}
+
+#[async_trait]
+impl Catalog for RestCatalog {
+    async fn list_namespaces(
+        &self,
+        parent: Option<&NamespaceIdent>,
+    ) -> IcebergResult<Vec<NamespaceIdent>> {
+        self.catalog.list_namespaces(parent).await
+    }
+
+    async fn create_namespace(
+        &self,
+        namespace_ident: &NamespaceIdent,
+        properties: HashMap<String, String>,
+    ) -> IcebergResult<Namespace> {
+        self.catalog
+            .create_namespace(namespace_ident, properties)
+            .await
+    }
+
+    async fn get_namespace(&self, namespace_ident: &NamespaceIdent) -> IcebergResult<Namespace> {
+        self.catalog.get_namespace(namespace_ident).await
+    }
+
+    async fn namespace_exists(&self, namespace_ident: &NamespaceIdent) -> IcebergResult<bool> {
+        self.catalog.namespace_exists(namespace_ident).await
+    }
+
+    async fn drop_namespace(&self, namespace_ident: &NamespaceIdent) -> IcebergResult<()> {
+        self.catalog.drop_namespace(namespace_ident).await
+    }
+
+    async fn list_tables(
+        &self,
+        namespace_ident: &NamespaceIdent,
+    ) -> IcebergResult<Vec<TableIdent>> {
+        self.catalog.list_tables(namespace_ident).await
+    }
+
+    async fn update_namespace(
+        &self,
+        namespace_ident: &NamespaceIdent,
+        properties: HashMap<String, String>,
+    ) -> IcebergResult<()> {
+        self.catalog
+            .update_namespace(namespace_ident, properties)
+            .await
+    }
+
+    /// Create a new table inside the namespace.
+    ///
+    /// Similar to file catalog, field ids are possibly reassigned at table creation, so we overwrite iceberg schema with correctly populated field id.
+    async fn create_table(
+        &self,
+        namespace_ident: &NamespaceIdent,
+        creation: TableCreation,
+    ) -> IcebergResult<Table> {
+        let table = self.catalog.create_table(namespace_ident, creation).await?;
+        if table.metadata().current_schema().as_struct() == self.iceberg_schema.as_struct() {
+            return Ok(table);
+        }
+        self.commit_current_schema(&table, self.iceberg_schema.clone())
+            .await
+    }
+
+    async fn load_table(&self, table_ident: &TableIdent) -> IcebergResult<Table> {
+        self.catalog.load_table(table_ident).await
+    }
+
+    async fn drop_table(&self, table_ident: &TableIdent) -> IcebergResult<()> {
+        self.catalog.drop_table(table_ident).await
+    }
+
+    async fn table_exists(&self, table_ident: &TableIdent) -> IcebergResult<bool> {
+        self.catalog.table_exists(table_ident).await
+    }
+
+    async fn rename_table(&self, src: &TableIdent, dest: &TableIdent) -> IcebergResult<()> {
+        self.catalog.rename_table(src, dest).await
+    }
+
+    /// Update a table to the catalog.
+    ///
+    /// Manifest files and manifest list for the new snapshot have persisted into storage, they're rewritten with puffin blobs before the commit
+    /// request is sent, so the new snapshot becomes visible along with its deletion vectors atomically.
+    async fn update_table(&self, mut commit: TableCommit) -> IcebergResult<Table> {
+        let table = self.catalog.load_table(commit.identifier()).await?;
+        let requirements = commit.take_requirements();
+        let updates = commit.take_updates();
+
+        // Validate existing table metadata with requirements, which will be checked again at catalog server.
+        catalog_utils::validate_table_requirements(requirements.clone(), table.metadata())?;
+
+        // Construct new metadata with updates, to locate manifest list for the new snapshot.
+        let builder = TableMetadataBuilder::new_from_metadata(
+            table.metadata().clone(),
+            /*current_file_location=*/
+            table
+                .metadata_location()
+                .map(|location| location.to_string()),
+        );
+        let builder = catalog_utils::reflect_table_updates(builder, updates.clone())?;
+        let metadata = builder.build()?.metadata;
+        append_puffin_metadata_and_rewrite(
+            &metadata,
+            table.file_io(),
+            &self.data_files_to_remove,
+            &self.puffin_blobs_to_add,
+            &self.puffin_blobs_to_remove,
+            &self.position_deletes_to_add,
+            self.compact_manifests,
+        )
+        .await?;
+
+        let table_commit_proxy = TableCommitProxy {
+            ident: commit.identifier().clone(),
+            requirements,
+            updates,
+        };
+        self.catalog
+            .update_table(table_commit_proxy.take_as_table_commit())
+            .await
+    }
+
+    async fn register_table(
+        &self,
+        table_ident: &TableIdent,
+        metadata_location: String,
+    ) -> IcebergResult<Table> {
+        self.catalog
+            .register_table(table_ident, metadata_location)
+            .await
+    }
+}
+
+#[async_trait]
+impl SchemaUpdate for RestCatalog {
+    async fn update_table_schema(
+        &mut self,
+        new_schema: IcebergSchema,
+        table_ident: TableIdent,
+    ) -> IcebergResult<Table> {
+        let table = self.catalog.load_table(&table_ident).await?;
+        self.commit_current_schema(&table, new_schema).await
+    }
+}
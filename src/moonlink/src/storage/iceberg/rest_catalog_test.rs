@@ -0,0 +1,121 @@
+use crate::storage::iceberg::file_catalog_test_utils::*;
+use crate::storage::iceberg::moonlink_catalog::SchemaUpdate;
+use crate::storage::iceberg::rest_catalog::RestCatalog;
+use crate::storage::iceberg::rest_catalog_test_utils::RestCatalogTestServer;
+use crate::storage::iceberg::table_commit_proxy::TableCommitProxy;
+
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+use iceberg::spec::Schema;
+use iceberg::{Catalog, NamespaceIdent, TableCreation, TableIdent, TableRequirement};
+use uuid::Uuid;
+
+/// Test util function to create a REST catalog connected to the given test server.
+fn create_test_rest_catalog(server: &RestCatalogTestServer, iceberg_schema: Schema) -> RestCatalog {
+    RestCatalog::new(
+        server.uri().to_string(),
+        /*warehouse=*/ None,
+        /*props=*/ HashMap::new(),
+        iceberg_schema,
+    )
+}
+
+// Test util function to create a new table.
+async fn create_test_table(
+    catalog: &RestCatalog,
+    temp_dir: &TempDir,
+    iceberg_schema: Schema,
+) -> TableIdent {
+    let namespace = NamespaceIdent::from_strs(["default"]).unwrap();
+    let table_name = "test_table".to_string();
+
+    let table_creation = TableCreation::builder()
+        .name(table_name.clone())
+        .location(format!(
+            "{}/{}/{}",
+            temp_dir.path().to_str().unwrap(),
+            namespace.to_url_string(),
+            table_name
+        ))
+        .schema(iceberg_schema)
+        .build();
+    catalog
+        .create_namespace(&namespace, /*properties=*/ HashMap::new())
+        .await
+        .unwrap();
+    catalog
+        .create_table(&namespace, table_creation)
+        .await
+        .unwrap();
+
+    TableIdent::new(namespace, table_name)
+}
+
+#[tokio::test]
+async fn test_rest_catalog_table_operations() {
+    let server = RestCatalogTestServer::new().await;
+    let temp_dir = TempDir::new().unwrap();
+    let catalog = create_test_rest_catalog(&server, get_updated_test_schema());
+
+    let namespace = NamespaceIdent::from_strs(["default"]).unwrap();
+    assert!(!catalog.namespace_exists(&namespace).await.unwrap());
+    let table_ident = create_test_table(&catalog, &temp_dir, get_updated_test_schema()).await;
+    assert!(catalog.namespace_exists(&namespace).await.unwrap());
+    assert!(catalog.table_exists(&table_ident).await.unwrap());
+    assert_eq!(
+        catalog.list_tables(&namespace).await.unwrap(),
+        vec![table_ident.clone()]
+    );
+
+    // Field ids are reassigned by catalog server at creation, check they're overwritten.
+    let table = catalog.load_table(&table_ident).await.unwrap();
+    assert_eq!(
+        table.metadata().current_schema().as_struct(),
+        get_updated_test_schema().as_struct()
+    );
+
+    // Drop the table and check.
+    catalog.drop_table(&table_ident).await.unwrap();
+    assert!(!catalog.table_exists(&table_ident).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_rest_catalog_update_schema() {
+    let server = RestCatalogTestServer::new().await;
+    let temp_dir = TempDir::new().unwrap();
+    let mut catalog = create_test_rest_catalog(&server, get_test_schema());
+    let table_ident = create_test_table(&catalog, &temp_dir, get_test_schema()).await;
+
+    let new_schema = get_updated_test_schema();
+    catalog
+        .update_table_schema(new_schema.clone(), table_ident.clone())
+        .await
+        .unwrap();
+
+    let table = catalog.load_table(&table_ident).await.unwrap();
+    assert_eq!(
+        table.metadata().current_schema().as_struct(),
+        new_schema.as_struct()
+    );
+}
+
+#[tokio::test]
+async fn test_rest_catalog_update_table_with_requirement_check_failed() {
+    let server = RestCatalogTestServer::new().await;
+    let temp_dir = TempDir::new().unwrap();
+    let catalog = create_test_rest_catalog(&server, get_test_schema());
+    let table_ident = create_test_table(&catalog, &temp_dir, get_test_schema()).await;
+
+    let table_commit_proxy = TableCommitProxy {
+        ident: table_ident,
+        requirements: vec![TableRequirement::UuidMatch {
+            uuid: Uuid::new_v4(),
+        }],
+        updates: vec![],
+    };
+    let res = catalog
+        .update_table(table_commit_proxy.take_as_table_commit())
+        .await;
+    assert!(res.is_err());
+}
@@ -0,0 +1,432 @@
+/// This module contains a minimal iceberg REST catalog server, which keeps table metadata in memory and serves as a stand-in for catalog servers in unit tests.
+///
+/// Only endpoints used by moonlink are supported, and each connection serves exactly one request.
+use crate::storage::iceberg::catalog_utils;
+use crate::storage::iceberg::iceberg_table_config::IcebergCatalogConfig;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use iceberg::spec::{TableMetadata, TableMetadataBuilder, UnboundPartitionSpec};
+use iceberg::{TableCreation, TableRequirement, TableUpdate};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// Separator for multi-level namespace in REST request path.
+const NAMESPACE_SEPARATOR: char = '\u{1f}';
+
+/// Table metadata tracked by the test catalog server.
+struct TableEntry {
+    /// Version of the latest metadata.
+    version: u64,
+    /// Table metadata.
+    metadata: TableMetadata,
+}
+
+impl TableEntry {
+    fn metadata_location(&self) -> String {
+        format!(
+            "{}/metadata/v{}.metadata.json",
+            self.metadata.location(),
+            self.version
+        )
+    }
+
+    fn to_load_table_response(&self) -> Value {
+        json!({
+            "metadata-location": self.metadata_location(),
+            "metadata": self.metadata,
+            "config": {},
+        })
+    }
+}
+
+#[derive(Default)]
+struct CatalogState {
+    /// All existing namespaces.
+    namespaces: HashSet<Vec<String>>,
+    /// Maps from table identifier to table metadata.
+    tables: HashMap<(Vec<String>, String), TableEntry>,
+}
+
+/// HTTP response, which consists of status code and optional json body.
+type Response = (u16, Option<Value>);
+
+fn error_response(code: u16, error_type: &str, message: String) -> Response {
+    (
+        code,
+        Some(json!({
+            "error": {
+                "message": message,
+                "type": error_type,
+                "code": code,
+            }
+        })),
+    )
+}
+
+fn bad_request(message: String) -> Response {
+    error_response(400, "BadRequestException", message)
+}
+
+/// Decode percent-encoded url component.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%' && idx + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[idx + 1..idx + 3]).unwrap_or_default();
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                idx += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[idx]);
+        idx += 1;
+    }
+    String::from_utf8(decoded).unwrap()
+}
+
+fn parse_namespace(s: &str) -> Vec<String> {
+    percent_decode(s)
+        .split(NAMESPACE_SEPARATOR)
+        .map(|level| level.to_string())
+        .collect()
+}
+
+impl CatalogState {
+    fn handle(&mut self, method: &str, target: &str, body: &[u8]) -> Response {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let segments = path
+            .trim_start_matches("/v1/")
+            .split('/')
+            .collect::<Vec<_>>();
+        let body = if body.is_empty() {
+            Value::Null
+        } else {
+            match serde_json::from_slice(body) {
+                Ok(body) => body,
+                Err(e) => return bad_request(format!("Invalid request body: {e}")),
+            }
+        };
+
+        match (method, segments.as_slice()) {
+            ("GET", ["config"]) => (200, Some(json!({ "defaults": {}, "overrides": {} }))),
+            ("GET", ["namespaces"]) => {
+                let parent = query
+                    .split('&')
+                    .find_map(|param| param.strip_prefix("parent="))
+                    .map(parse_namespace);
+                self.list_namespaces(parent)
+            }
+            ("POST", ["namespaces"]) => self.create_namespace(body),
+            ("HEAD", ["namespaces", namespace]) => {
+                let namespace = parse_namespace(namespace);
+                if self.namespaces.contains(&namespace) {
+                    (204, None)
+                } else {
+                    (404, None)
+                }
+            }
+            ("GET", ["namespaces", namespace]) => {
+                let namespace = parse_namespace(namespace);
+                if !self.namespaces.contains(&namespace) {
+                    return error_response(
+                        404,
+                        "NoSuchNamespaceException",
+                        format!("Namespace {namespace:?} doesn't exist"),
+                    );
+                }
+                (
+                    200,
+                    Some(json!({ "namespace": namespace, "properties": {} })),
+                )
+            }
+            ("DELETE", ["namespaces", namespace]) => {
+                self.namespaces.remove(&parse_namespace(namespace));
+                (204, None)
+            }
+            ("GET", ["namespaces", namespace, "tables"]) => {
+                let namespace = parse_namespace(namespace);
+                let identifiers = self
+                    .tables
+                    .keys()
+                    .filter(|(cur_namespace, _)| *cur_namespace == namespace)
+                    .map(
+                        |(cur_namespace, name)| json!({ "namespace": cur_namespace, "name": name }),
+                    )
+                    .collect::<Vec<_>>();
+                (200, Some(json!({ "identifiers": identifiers })))
+            }
+            ("POST", ["namespaces", namespace, "tables"]) => {
+                self.create_table(parse_namespace(namespace), body)
+            }
+            ("HEAD", ["namespaces", namespace, "tables", table]) => {
+                let key = (parse_namespace(namespace), percent_decode(table));
+                if self.tables.contains_key(&key) {
+                    (204, None)
+                } else {
+                    (404, None)
+                }
+            }
+            ("GET", ["namespaces", namespace, "tables", table]) => {
+                let key = (parse_namespace(namespace), percent_decode(table));
+                match self.tables.get(&key) {
+                    Some(entry) => (200, Some(entry.to_load_table_response())),
+                    None => error_response(
+                        404,
+                        "NoSuchTableException",
+                        format!("Table {key:?} doesn't exist"),
+                    ),
+                }
+            }
+            ("POST", ["namespaces", namespace, "tables", table]) => {
+                let key = (parse_namespace(namespace), percent_decode(table));
+                self.commit_table(key, body)
+            }
+            ("DELETE", ["namespaces", namespace, "tables", table]) => {
+                let key = (parse_namespace(namespace), percent_decode(table));
+                match self.tables.remove(&key) {
+                    Some(_) => (204, None),
+                    None => error_response(
+                        404,
+                        "NoSuchTableException",
+                        format!("Table {key:?} doesn't exist"),
+                    ),
+                }
+            }
+            _ => bad_request(format!("Unsupported request {method} {target}")),
+        }
+    }
+
+    fn list_namespaces(&self, parent: Option<Vec<String>>) -> Response {
+        let namespaces = self
+            .namespaces
+            .iter()
+            .filter(|namespace| match &parent {
+                Some(parent) => {
+                    namespace.len() == parent.len() + 1 && namespace.starts_with(parent)
+                }
+                None => namespace.len() == 1,
+            })
+            .collect::<Vec<_>>();
+        (200, Some(json!({ "namespaces": namespaces })))
+    }
+
+    fn create_namespace(&mut self, body: Value) -> Response {
+        let namespace: Vec<String> = match serde_json::from_value(body["namespace"].clone()) {
+            Ok(namespace) => namespace,
+            Err(e) => return bad_request(format!("Invalid namespace: {e}")),
+        };
+        if !self.namespaces.insert(namespace.clone()) {
+            return error_response(
+                409,
+                "AlreadyExistsException",
+                format!("Namespace {namespace:?} already exists"),
+            );
+        }
+        (
+            200,
+            Some(json!({ "namespace": namespace, "properties": {} })),
+        )
+    }
+
+    fn create_table(&mut self, namespace: Vec<String>, body: Value) -> Response {
+        if !self.namespaces.contains(&namespace) {
+            return error_response(
+                404,
+                "NoSuchNamespaceException",
+                format!("Namespace {namespace:?} doesn't exist"),
+            );
+        }
+        let name = body["name"].as_str().unwrap_or_default().to_string();
+        let key = (namespace, name.clone());
+        if self.tables.contains_key(&key) {
+            return error_response(
+                409,
+                "AlreadyExistsException",
+                format!("Table {key:?} already exists"),
+            );
+        }
+
+        let schema = match serde_json::from_value(body["schema"].clone()) {
+            Ok(schema) => schema,
+            Err(e) => return bad_request(format!("Invalid schema: {e}")),
+        };
+        let partition_spec: Option<UnboundPartitionSpec> = match body.get("partition-spec") {
+            Some(partition_spec) if !partition_spec.is_null() => {
+                match serde_json::from_value(partition_spec.clone()) {
+                    Ok(partition_spec) => Some(partition_spec),
+                    Err(e) => return bad_request(format!("Invalid partition spec: {e}")),
+                }
+            }
+            _ => None,
+        };
+        let properties = serde_json::from_value(body["properties"].clone()).unwrap_or_default();
+        let location = body["location"].as_str().unwrap_or_default().to_string();
+
+        let mut creation = TableCreation::builder()
+            .name(name)
+            .location(location)
+            .schema(schema)
+            .properties(properties)
+            .build();
+        creation.partition_spec = partition_spec;
+        let metadata = match catalog_utils::new_table_metadata_builder(creation)
+            .and_then(|builder| builder.build())
+        {
+            Ok(build_result) => build_result.metadata,
+            Err(e) => return bad_request(format!("Failed to create table: {e}")),
+        };
+
+        let entry = TableEntry {
+            version: 0,
+            metadata,
+        };
+        let response = entry.to_load_table_response();
+        self.tables.insert(key, entry);
+        (200, Some(response))
+    }
+
+    fn commit_table(&mut self, key: (Vec<String>, String), body: Value) -> Response {
+        let Some(entry) = self.tables.get_mut(&key) else {
+            return error_response(
+                404,
+                "NoSuchTableException",
+                format!("Table {key:?} doesn't exist"),
+            );
+        };
+        let requirements: Vec<TableRequirement> =
+            match serde_json::from_value(body["requirements"].clone()) {
+                Ok(requirements) => requirements,
+                Err(e) => return bad_request(format!("Invalid requirements: {e}")),
+            };
+        let updates: Vec<TableUpdate> = match serde_json::from_value(body["updates"].clone()) {
+            Ok(updates) => updates,
+            Err(e) => return bad_request(format!("Invalid updates: {e}")),
+        };
+
+        if let Err(e) = catalog_utils::validate_table_requirements(requirements, &entry.metadata) {
+            return error_response(409, "CommitFailedException", e.to_string());
+        }
+        let builder = TableMetadataBuilder::new_from_metadata(
+            entry.metadata.clone(),
+            /*current_file_location=*/ Some(entry.metadata_location()),
+        );
+        let metadata = match catalog_utils::reflect_table_updates(builder, updates)
+            .and_then(|builder| builder.build())
+        {
+            Ok(build_result) => build_result.metadata,
+            Err(e) => return bad_request(format!("Failed to apply updates: {e}")),
+        };
+
+        entry.version += 1;
+        entry.metadata = metadata;
+        (
+            200,
+            Some(json!({
+                "metadata-location": entry.metadata_location(),
+                "metadata": entry.metadata,
+            })),
+        )
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    state: Arc<Mutex<CatalogState>>,
+) -> std::io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    // Parse request line and headers.
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut request_parts = request_line.split_whitespace();
+    let method = request_parts.next().unwrap_or_default().to_string();
+    let target = request_parts.next().unwrap_or_default().to_string();
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).await?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+
+    let (status, response_body) = state.lock().unwrap().handle(&method, &target, &body);
+    let response_body = response_body
+        .map(|body| serde_json::to_vec(&body).unwrap())
+        .unwrap_or_default();
+    let header = format!(
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        if status < 300 { "OK" } else { "Error" },
+        response_body.len(),
+    );
+    writer.write_all(header.as_bytes()).await?;
+    if method != "HEAD" {
+        writer.write_all(&response_body).await?;
+    }
+    writer.shutdown().await?;
+    Ok(())
+}
+
+/// Test REST catalog server, which listens on a random local port and gets shutdown on drop.
+pub(crate) struct RestCatalogTestServer {
+    /// Uri for catalog server.
+    uri: String,
+    /// Handle for the background server task.
+    handle: JoinHandle<()>,
+}
+
+impl RestCatalogTestServer {
+    pub(crate) async fn new() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let uri = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(CatalogState::default()));
+        let handle = tokio::spawn(async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    return;
+                };
+                let state = state.clone();
+                tokio::spawn(async move {
+                    let _ = handle_connection(stream, state).await;
+                });
+            }
+        });
+        Self { uri, handle }
+    }
+
+    /// Get uri for the test server.
+    pub(crate) fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Get catalog config to connect to the test server.
+    pub(crate) fn catalog_config(&self) -> IcebergCatalogConfig {
+        IcebergCatalogConfig::Rest {
+            uri: self.uri.clone(),
+            warehouse: None,
+            props: HashMap::new(),
+        }
+    }
+}
+
+impl Drop for RestCatalogTestServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
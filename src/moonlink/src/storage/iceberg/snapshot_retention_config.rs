@@ -0,0 +1,43 @@
+use typed_builder::TypedBuilder;
+
+/// Configurations for iceberg snapshot expiration.
+///
+/// A snapshot is expired when it's older than the retention window, and isn't among the latest snapshots to keep; current snapshot is always retained.
+/// Files only referenced by expired snapshots are deleted after expiration, along with orphan files under table location and old metadata files.
+#[derive(Clone, Debug, PartialEq, TypedBuilder)]
+pub struct SnapshotRetentionConfig {
+    /// Max age in milliseconds for a snapshot to retain.
+    pub max_snapshot_age_ms: u64,
+    /// Min number of latest snapshots to retain, whatever age they're of.
+    pub min_snapshots_to_keep: usize,
+    /// Interval in seconds for background snapshot expiration.
+    pub expiration_interval_secs: u64,
+    /// Min age in milliseconds for a file under table location, which isn't referenced by any snapshot, to be deleted as orphan file.
+    /// Files written by ongoing commits are unreferenced as well, so it should be long enough for a commit to finish.
+    pub orphan_file_grace_period_ms: u64,
+    /// Max number of previous metadata files to keep, older ones are deleted.
+    /// Only applies to catalogs which moonlink writes metadata files for, metadata files for REST catalog are managed by the catalog server.
+    pub max_previous_metadata_files: usize,
+}
+
+impl SnapshotRetentionConfig {
+    /// Default values are aligned with iceberg table properties `history.expire.max-snapshot-age-ms` and `history.expire.min-snapshots-to-keep`.
+    pub const DEFAULT_MAX_SNAPSHOT_AGE_MS: u64 = 5 * 24 * 60 * 60 * 1000; // 5 days
+    pub const DEFAULT_MIN_SNAPSHOTS_TO_KEEP: usize = 1;
+    pub const DEFAULT_EXPIRATION_INTERVAL_SECS: u64 = 60 * 60; // 1 hour
+    /// Default values are aligned with iceberg `remove_orphan_files` procedure and table property `write.metadata.previous-versions-max`.
+    pub const DEFAULT_ORPHAN_FILE_GRACE_PERIOD_MS: u64 = 3 * 24 * 60 * 60 * 1000; // 3 days
+    pub const DEFAULT_MAX_PREVIOUS_METADATA_FILES: usize = 100;
+}
+
+impl Default for SnapshotRetentionConfig {
+    fn default() -> Self {
+        Self {
+            max_snapshot_age_ms: Self::DEFAULT_MAX_SNAPSHOT_AGE_MS,
+            min_snapshots_to_keep: Self::DEFAULT_MIN_SNAPSHOTS_TO_KEEP,
+            expiration_interval_secs: Self::DEFAULT_EXPIRATION_INTERVAL_SECS,
+            orphan_file_grace_period_ms: Self::DEFAULT_ORPHAN_FILE_GRACE_PERIOD_MS,
+            max_previous_metadata_files: Self::DEFAULT_MAX_PREVIOUS_METADATA_FILES,
+        }
+    }
+}
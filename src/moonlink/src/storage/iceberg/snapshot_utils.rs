@@ -1,44 +1,113 @@
+use std::collections::HashMap;
+
 use iceberg::spec::TableMetadata;
 
-use crate::storage::iceberg::iceberg_table_manager::{
-    MOONCAKE_TABLE_FLUSH_LSN, MOONCAKE_WAL_METADATA,
-};
+use crate::storage::mooncake_table::InitialCopyPosition;
 use crate::storage::wal::wal_persistence_metadata::WalPersistenceMetadata;
 use iceberg::Error as IcebergError;
 use iceberg::Result as IcebergResult;
 
 /// This file contains util functions on iceberg snapshot.
 ///
+/// Moonlink state is recorded in the summary of every snapshot it commits, so each historical snapshot carries its own state,
+/// and it's not affected by other writers updating table properties.
+///
+/// Key for iceberg snapshot property, to record flush lsn.
+pub(super) const MOONCAKE_TABLE_FLUSH_LSN: &str = "moonlink.table-flush-lsn";
+/// Key for iceberg snapshot property, to record WAL persistence metadata.
+pub(super) const MOONCAKE_WAL_METADATA: &str = "moonlink.wal-metadata";
+/// Key for iceberg snapshot property, to record initial copy position.
+pub(super) const MOONLINK_INITIAL_COPY_POSITION: &str = "moonlink.initial-copy-position";
+/// Key for iceberg snapshot property, to record moonlink format version.
+pub(super) const MOONLINK_FORMAT_VERSION: &str = "moonlink.format-version";
+/// Current moonlink format version, bumped whenever moonlink snapshot properties change incompatibly.
+/// Version 0 refers to the legacy layout, whose snapshot summary doesn't record format version; it already records flush LSN
+/// and WAL metadata in the same keys, so tables written by older versions keep their state without reset or resync.
+pub(super) const CURRENT_MOONLINK_FORMAT_VERSION: u32 = 1;
+
 /// Moonlink snapshot properties.
 pub(super) struct SnapshotProperty {
     /// Iceberg flush LSN.
     pub(super) flush_lsn: Option<u64>,
     /// WAL persisted metadata.
     pub(super) wal_persisted_metadata: Option<WalPersistenceMetadata>,
+    /// Initial copy position.
+    pub(super) initial_copy_position: Option<InitialCopyPosition>,
 }
 
-/// Get moonlink customized snapshot
+/// Get moonlink customized snapshot properties to record in the summary of a new snapshot.
+pub(super) fn create_snapshot_properties(
+    flush_lsn: u64,
+    wal_persisted_metadata: Option<&WalPersistenceMetadata>,
+    initial_copy_position: &InitialCopyPosition,
+) -> HashMap<String, String> {
+    let mut snapshot_properties = HashMap::<String, String>::from([
+        (MOONCAKE_TABLE_FLUSH_LSN.to_string(), flush_lsn.to_string()),
+        (
+            MOONLINK_FORMAT_VERSION.to_string(),
+            CURRENT_MOONLINK_FORMAT_VERSION.to_string(),
+        ),
+    ]);
+    if let Some(wal_metadata) = wal_persisted_metadata {
+        snapshot_properties.insert(
+            MOONCAKE_WAL_METADATA.to_string(),
+            serde_json::to_string(wal_metadata).unwrap(),
+        );
+    }
+    // Only record initial copy position for tables which have been copied.
+    if *initial_copy_position != InitialCopyPosition::default() {
+        snapshot_properties.insert(
+            MOONLINK_INITIAL_COPY_POSITION.to_string(),
+            serde_json::to_string(initial_copy_position).unwrap(),
+        );
+    }
+    snapshot_properties
+}
+
+/// Get moonlink customized snapshot properties from the summary of the current snapshot.
+/// Table properties are not read, since they could be updated by other writers.
 pub(super) fn get_snapshot_properties(
     table_metadata: &TableMetadata,
 ) -> IcebergResult<SnapshotProperty> {
-    let current_snapshot = table_metadata.current_snapshot().unwrap();
-    let snapshot_summary = current_snapshot.summary();
+    let empty_summary = HashMap::new();
+    let snapshot_summary = table_metadata
+        .current_snapshot()
+        .map(|snapshot| &snapshot.summary().additional_properties)
+        .unwrap_or(&empty_summary);
+
+    // Validate moonlink format version, summary without one is written in legacy layout.
+    let mut format_version = 0;
+    if let Some(version) = snapshot_summary.get(MOONLINK_FORMAT_VERSION) {
+        format_version = version.parse().map_err(|e| {
+            IcebergError::new(
+                iceberg::ErrorKind::DataInvalid,
+                format!("failed to parse moonlink format version {version}: {e:?}"),
+            )
+        })?;
+    }
+    if format_version > CURRENT_MOONLINK_FORMAT_VERSION {
+        return Err(IcebergError::new(
+            iceberg::ErrorKind::FeatureUnsupported,
+            format!(
+                "moonlink format version {format_version} is newer than supported version {CURRENT_MOONLINK_FORMAT_VERSION}"
+            ),
+        ));
+    }
 
     // Extract flush LSN.
     let mut flush_lsn: Option<u64> = None;
-    if let Some(lsn) = snapshot_summary
-        .additional_properties
-        .get(MOONCAKE_TABLE_FLUSH_LSN)
-    {
-        flush_lsn = Some(lsn.parse().unwrap());
+    if let Some(lsn) = snapshot_summary.get(MOONCAKE_TABLE_FLUSH_LSN) {
+        flush_lsn = Some(lsn.parse().map_err(|e| {
+            IcebergError::new(
+                iceberg::ErrorKind::DataInvalid,
+                format!("failed to parse flush LSN {lsn}: {e:?}"),
+            )
+        })?);
     }
 
     // Extract WAL persisted metadata.
     let mut wal_persisted_metadata: Option<WalPersistenceMetadata> = None;
-    if let Some(wal) = snapshot_summary
-        .additional_properties
-        .get(MOONCAKE_WAL_METADATA)
-    {
+    if let Some(wal) = snapshot_summary.get(MOONCAKE_WAL_METADATA) {
         let parsed_wal = serde_json::from_str(wal).map_err(|e| {
             IcebergError::new(
                 iceberg::ErrorKind::DataInvalid,
@@ -48,8 +117,143 @@ pub(super) fn get_snapshot_properties(
         wal_persisted_metadata = Some(parsed_wal);
     }
 
+    // Extract initial copy position.
+    let mut initial_copy_position: Option<InitialCopyPosition> = None;
+    if let Some(position) = snapshot_summary.get(MOONLINK_INITIAL_COPY_POSITION) {
+        let parsed_position = serde_json::from_str(position).map_err(|e| {
+            IcebergError::new(
+                iceberg::ErrorKind::DataInvalid,
+                format!("failed to parse initial copy position {position}: {e:?}"),
+            )
+        })?;
+        initial_copy_position = Some(parsed_position);
+    }
+
     Ok(SnapshotProperty {
         flush_lsn,
         wal_persisted_metadata,
+        initial_copy_position,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use iceberg::spec::{
+        FormatVersion, NestedField, Operation, PrimitiveType, Schema, Snapshot, SnapshotReference,
+        SnapshotRetention, SortOrder, Summary, TableMetadataBuilder, Type, UnboundPartitionSpec,
+        MAIN_BRANCH,
+    };
+
+    /// Test util function to create table metadata with the given table properties, and a current snapshot if summary given.
+    fn create_table_metadata(
+        table_properties: HashMap<String, String>,
+        snapshot_summary: Option<HashMap<String, String>>,
+    ) -> TableMetadata {
+        let schema = Schema::builder()
+            .with_fields(vec![NestedField::required(
+                1,
+                "id",
+                Type::Primitive(PrimitiveType::Int),
+            )
+            .into()])
+            .build()
+            .unwrap();
+        let mut builder = TableMetadataBuilder::new(
+            schema,
+            UnboundPartitionSpec::builder().build(),
+            SortOrder::unsorted_order(),
+            "/tmp/test_table".to_string(),
+            FormatVersion::V2,
+            table_properties,
+        )
+        .unwrap();
+        if let Some(additional_properties) = snapshot_summary {
+            let snapshot = Snapshot::builder()
+                .with_snapshot_id(1)
+                .with_sequence_number(1)
+                .with_timestamp_ms(chrono::Utc::now().timestamp_millis())
+                .with_schema_id(0)
+                .with_manifest_list("/tmp/test_table/metadata/snap-1.avro")
+                .with_summary(Summary {
+                    operation: Operation::Append,
+                    additional_properties,
+                })
+                .build();
+            builder = builder
+                .add_snapshot(snapshot)
+                .unwrap()
+                .set_ref(
+                    MAIN_BRANCH,
+                    SnapshotReference {
+                        snapshot_id: 1,
+                        retention: SnapshotRetention::Branch {
+                            min_snapshots_to_keep: None,
+                            max_snapshot_age_ms: None,
+                            max_ref_age_ms: None,
+                        },
+                    },
+                )
+                .unwrap();
+        }
+        builder.build().unwrap().metadata
+    }
+
+    #[test]
+    fn test_snapshot_properties() {
+        let wal_metadata = WalPersistenceMetadata {
+            persisted_file_num: 3,
+        };
+        let mut initial_copy_position = InitialCopyPosition::default();
+        initial_copy_position.advance(
+            /*last_key=*/ 100, /*snapshot_lsn=*/ 20, /*rows_copied=*/ 100,
+        );
+        let summary = create_snapshot_properties(
+            /*flush_lsn=*/ 30,
+            Some(&wal_metadata),
+            &initial_copy_position,
+        );
+        assert_eq!(
+            summary.get(MOONLINK_FORMAT_VERSION).unwrap(),
+            &CURRENT_MOONLINK_FORMAT_VERSION.to_string()
+        );
+        // Table properties changed by other writers don't affect snapshot state.
+        let table_properties =
+            HashMap::from([(MOONCAKE_TABLE_FLUSH_LSN.to_string(), "10".to_string())]);
+        let table_metadata = create_table_metadata(table_properties, Some(summary));
+        let snapshot_property = get_snapshot_properties(&table_metadata).unwrap();
+        assert_eq!(snapshot_property.flush_lsn, Some(30));
+        assert_eq!(snapshot_property.wal_persisted_metadata, Some(wal_metadata));
+        assert_eq!(
+            snapshot_property.initial_copy_position,
+            Some(initial_copy_position)
+        );
+
+        // Summary without format version is read as legacy layout, and table properties are ignored.
+        let summary = HashMap::from([(MOONCAKE_TABLE_FLUSH_LSN.to_string(), "20".to_string())]);
+        let table_properties =
+            HashMap::from([(MOONCAKE_TABLE_FLUSH_LSN.to_string(), "10".to_string())]);
+        let table_metadata = create_table_metadata(table_properties.clone(), Some(summary));
+        let snapshot_property = get_snapshot_properties(&table_metadata).unwrap();
+        assert_eq!(snapshot_property.flush_lsn, Some(20));
+        let table_metadata = create_table_metadata(table_properties, Some(HashMap::new()));
+        let snapshot_property = get_snapshot_properties(&table_metadata).unwrap();
+        assert!(snapshot_property.flush_lsn.is_none());
+
+        // Table without snapshot.
+        let table_metadata = create_table_metadata(HashMap::new(), /*snapshot_summary=*/ None);
+        let snapshot_property = get_snapshot_properties(&table_metadata).unwrap();
+        assert!(snapshot_property.flush_lsn.is_none());
+        assert!(snapshot_property.wal_persisted_metadata.is_none());
+        assert!(snapshot_property.initial_copy_position.is_none());
+
+        // Newer format version is rejected.
+        let summary = HashMap::from([(
+            MOONLINK_FORMAT_VERSION.to_string(),
+            (CURRENT_MOONLINK_FORMAT_VERSION + 1).to_string(),
+        )]);
+        let table_metadata = create_table_metadata(HashMap::new(), Some(summary));
+        assert!(get_snapshot_properties(&table_metadata).is_err());
+    }
+}
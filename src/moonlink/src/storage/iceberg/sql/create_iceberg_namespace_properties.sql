@@ -0,0 +1,8 @@
+-- SQL statement to store iceberg namespaces and their properties, which follows iceberg JDBC catalog schema.
+CREATE TABLE IF NOT EXISTS iceberg_namespace_properties (
+    catalog_name VARCHAR(255) NOT NULL,         -- catalog name
+    namespace VARCHAR(255) NOT NULL,            -- namespace levels joined by dot
+    property_key VARCHAR(255),                  -- namespace property key
+    property_value VARCHAR(1000),               -- namespace property value
+    PRIMARY KEY (catalog_name, namespace, property_key)
+);
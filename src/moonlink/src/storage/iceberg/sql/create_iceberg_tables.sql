@@ -0,0 +1,10 @@
+-- SQL statement to store iceberg table pointers, which follows iceberg JDBC catalog schema.
+CREATE TABLE IF NOT EXISTS iceberg_tables (
+    catalog_name VARCHAR(255) NOT NULL,         -- catalog name
+    table_namespace VARCHAR(255) NOT NULL,      -- namespace levels joined by dot
+    table_name VARCHAR(255) NOT NULL,           -- iceberg table name
+    metadata_location VARCHAR(1000),            -- current metadata file location
+    previous_metadata_location VARCHAR(1000),   -- previous metadata file location
+    iceberg_type VARCHAR(5),                    -- one of (TABLE, VIEW)
+    PRIMARY KEY (catalog_name, table_namespace, table_name)
+);
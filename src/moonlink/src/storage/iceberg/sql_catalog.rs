@@ -0,0 +1,756 @@
+use crate::storage::filesystem::accessor::base_filesystem_accessor::BaseFileSystemAccess;
+use crate::storage::filesystem::accessor::filesystem_accessor::FileSystemAccessor;
+use crate::storage::filesystem::filesystem_config::FileSystemConfig;
+use crate::storage::iceberg::catalog_utils;
+use crate::storage::iceberg::io_utils as iceberg_io_utils;
+use crate::storage::iceberg::moonlink_catalog::{PuffinWrite, SchemaUpdate};
+use crate::storage::iceberg::position_delete::PositionDeleteFileMetadata;
+use crate::storage::iceberg::puffin_writer_proxy::{
+    append_puffin_metadata_and_rewrite, get_puffin_metadata_and_close, PuffinBlobMetadataProxy,
+};
+use crate::storage::iceberg::table_commit_proxy::TableCommitProxy;
+
+/// This module contains the SQL catalog implementation, which stores table pointers in a SQLite or Postgres database, following iceberg JDBC catalog schema.
+/// Compared with file catalog, current metadata pointer is swapped with a single conditional `UPDATE ... WHERE metadata_location = <expected>`
+/// (compare-and-swap, no explicit transaction), instead of overwriting version hint file, which isn't atomic on object storage.
+/// Query engines with JDBC catalog (i.e. Spark, Trino) could discover tables with the same database and catalog name.
+///
+/// Database schema:
+/// - iceberg_tables
+///   + one row for each table, which points to its current metadata file
+/// - iceberg_namespace_properties
+///   + one row for each namespace property, namespaces without properties have an `exists` property
+///
+/// Metadata files are written to `<table-location>/metadata/<version>-<uuid>.metadata.json`.
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use iceberg::io::FileIO;
+use iceberg::puffin::PuffinWriter;
+use iceberg::spec::{Schema as IcebergSchema, TableMetadata, TableMetadataBuilder};
+use iceberg::table::Table;
+use iceberg::Result as IcebergResult;
+use iceberg::{
+    Catalog, Namespace, NamespaceIdent, TableCommit, TableCreation, TableIdent, TableRequirement,
+};
+use iceberg::{Error as IcebergError, ErrorKind};
+use sqlx::any::{AnyPool, AnyPoolOptions};
+use sqlx::Row;
+use tokio::sync::OnceCell;
+
+/// SQL statements for iceberg tables schema.
+const CREATE_ICEBERG_TABLES_SQL: &str = include_str!("sql/create_iceberg_tables.sql");
+/// SQL statements for iceberg namespace properties schema.
+const CREATE_NAMESPACE_PROPERTIES_SQL: &str =
+    include_str!("sql/create_iceberg_namespace_properties.sql");
+/// Namespace property which indicates the namespace exists, used when no property is provided at creation.
+const NAMESPACE_EXISTS_PROPERTY: &str = "exists";
+/// Record type for iceberg tables, views are not supported.
+const ICEBERG_TABLE_TYPE: &str = "TABLE";
+
+/// Convert sqlx error to iceberg error.
+fn to_iceberg_error(e: sqlx::Error, operation: &str) -> IcebergError {
+    IcebergError::new(
+        ErrorKind::Unexpected,
+        format!("Failed to {operation} in sql catalog: {e}"),
+    )
+    .with_retryable(true)
+}
+
+/// Namespace is persisted as its levels joined by dot.
+fn namespace_to_string(namespace_ident: &NamespaceIdent) -> String {
+    namespace_ident.as_ref().join(".")
+}
+
+/// Get metadata version from metadata file location.
+fn get_metadata_version(metadata_location: &str) -> IcebergResult<u64> {
+    metadata_location
+        .rsplit('/')
+        .next()
+        .and_then(|filename| filename.split('-').next())
+        .and_then(|version| version.parse().ok())
+        .ok_or_else(|| {
+            IcebergError::new(
+                ErrorKind::DataInvalid,
+                format!("Invalid metadata location {metadata_location}"),
+            )
+        })
+}
+
+/// Get a new metadata file location for the given version.
+fn get_metadata_location(table_location: &str, version: u64) -> String {
+    format!(
+        "{}/metadata/{version:05}-{}.metadata.json",
+        table_location.trim_end_matches('/'),
+        uuid::Uuid::new_v4(),
+    )
+}
+
+#[derive(Debug)]
+pub struct SqlCatalog {
+    /// Connection pool to catalog database, connections are established lazily.
+    pool: AnyPool,
+    /// Whether catalog tables have been created.
+    initialized: OnceCell<()>,
+    /// Catalog name, which allows multiple catalogs to share the same database.
+    catalog_name: String,
+    /// Filesystem operator.
+    filesystem_accessor: Arc<dyn BaseFileSystemAccess>,
+    /// Used to read and write metadata files.
+    file_io: FileIO,
+    /// Used to overwrite iceberg metadata at table creation.
+    iceberg_schema: IcebergSchema,
+    /// Used to record puffin blob metadata in one transaction, and cleaned up after transaction commits.
+    ///
+    /// Maps from "puffin filepath" to "puffin blob metadata".
+    puffin_blobs_to_add: HashMap<String, Vec<PuffinBlobMetadataProxy>>,
+    /// A vector of "puffin filepath"s.
+    puffin_blobs_to_remove: HashSet<String>,
+    /// Used to record position delete files in one transaction, and cleaned up after transaction commits.
+    position_deletes_to_add: Vec<PositionDeleteFileMetadata>,
+    /// A set of data files to remove, along with their corresponding deletion vectors and file indices.
+    data_files_to_remove: HashSet<String>,
+    /// Whether to compact all manifest files at the next commit.
+    compact_manifests: bool,
+}
+
+impl SqlCatalog {
+    /// Create a SQL catalog, which gets initialized lazily.
+    pub fn new(
+        config: FileSystemConfig,
+        uri: &str,
+        catalog_name: String,
+        iceberg_schema: IcebergSchema,
+    ) -> IcebergResult<Self> {
+        sqlx::any::install_default_drivers();
+        let pool = AnyPoolOptions::new()
+            .connect_lazy(uri)
+            .map_err(|e| to_iceberg_error(e, "connect to database"))?;
+        let file_io = iceberg_io_utils::create_file_io(&config)?;
+        Ok(Self {
+            pool,
+            initialized: OnceCell::new(),
+            catalog_name,
+            filesystem_accessor: Arc::new(FileSystemAccessor::new(config)),
+            file_io,
+            iceberg_schema,
+            puffin_blobs_to_add: HashMap::new(),
+            puffin_blobs_to_remove: HashSet::new(),
+            position_deletes_to_add: Vec::new(),
+            data_files_to_remove: HashSet::new(),
+            compact_manifests: false,
+        })
+    }
+
+    /// Get connection pool, catalog tables are created at first access if they don't exist.
+    async fn get_pool(&self) -> IcebergResult<&AnyPool> {
+        self.initialized
+            .get_or_try_init(|| async {
+                for statement in [CREATE_ICEBERG_TABLES_SQL, CREATE_NAMESPACE_PROPERTIES_SQL] {
+                    sqlx::query(statement)
+                        .execute(&self.pool)
+                        .await
+                        .map_err(|e| to_iceberg_error(e, "create catalog tables"))?;
+                }
+                Ok::<(), IcebergError>(())
+            })
+            .await?;
+        Ok(&self.pool)
+    }
+
+    /// Get current metadata location for the given table, return [`None`] if table doesn't exist.
+    async fn get_current_metadata_location(
+        &self,
+        table_ident: &TableIdent,
+    ) -> IcebergResult<Option<String>> {
+        let row = sqlx::query(
+            "SELECT metadata_location FROM iceberg_tables WHERE catalog_name = $1 AND table_namespace = $2 AND table_name = $3",
+        )
+        .bind(self.catalog_name.as_str())
+        .bind(namespace_to_string(table_ident.namespace()))
+        .bind(table_ident.name())
+        .fetch_optional(self.get_pool().await?)
+        .await
+        .map_err(|e| to_iceberg_error(e, "get metadata location"))?;
+        Ok(row.and_then(|row| row.get::<Option<String>, _>("metadata_location")))
+    }
+
+    /// Load metadata and its location for the given table.
+    pub(super) async fn load_metadata(
+        &self,
+        table_ident: &TableIdent,
+    ) -> IcebergResult<(String /*metadata_location*/, TableMetadata)> {
+        let metadata_location = self
+            .get_current_metadata_location(table_ident)
+            .await?
+            .ok_or_else(|| {
+                IcebergError::new(
+                    ErrorKind::TableNotFound,
+                    format!("Table {table_ident:?} doesn't exist"),
+                )
+            })?;
+        let metadata = self.read_metadata(&metadata_location).await?;
+        Ok((metadata_location, metadata))
+    }
+
+    async fn read_metadata(&self, metadata_location: &str) -> IcebergResult<TableMetadata> {
+        let metadata_bytes = self.file_io.new_input(metadata_location)?.read().await?;
+        let metadata = serde_json::from_slice::<TableMetadata>(&metadata_bytes)
+            .map_err(|e| IcebergError::new(ErrorKind::DataInvalid, e.to_string()))?;
+        Ok(metadata)
+    }
+
+    async fn write_metadata(
+        &self,
+        metadata_location: &str,
+        metadata: &TableMetadata,
+    ) -> IcebergResult<()> {
+        let metadata_json = serde_json::to_vec(metadata)?;
+        self.file_io
+            .new_output(metadata_location)?
+            .write(metadata_json.into())
+            .await
+    }
+
+    /// Insert table pointer, which fails if the table already exists.
+    async fn insert_table(
+        &self,
+        table_ident: &TableIdent,
+        metadata_location: &str,
+    ) -> IcebergResult<()> {
+        sqlx::query(
+            "INSERT INTO iceberg_tables (catalog_name, table_namespace, table_name, metadata_location, iceberg_type) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(self.catalog_name.as_str())
+        .bind(namespace_to_string(table_ident.namespace()))
+        .bind(table_ident.name())
+        .bind(metadata_location)
+        .bind(ICEBERG_TABLE_TYPE)
+        .execute(self.get_pool().await?)
+        .await
+        .map_err(|e| to_iceberg_error(e, "insert table"))?;
+        Ok(())
+    }
+
+    /// Swap table pointer to the new metadata location with a single conditional update, which only succeeds if the current
+    /// metadata location is still the expected one; otherwise no row is updated and commit conflict is returned.
+    pub(super) async fn swap_metadata_location(
+        &self,
+        table_ident: &TableIdent,
+        expected_metadata_location: &str,
+        new_metadata_location: &str,
+    ) -> IcebergResult<()> {
+        let rows_affected = sqlx::query(
+            "UPDATE iceberg_tables SET metadata_location = $1, previous_metadata_location = $2 \
+             WHERE catalog_name = $3 AND table_namespace = $4 AND table_name = $5 AND metadata_location = $6",
+        )
+        .bind(new_metadata_location)
+        .bind(expected_metadata_location)
+        .bind(self.catalog_name.as_str())
+        .bind(namespace_to_string(table_ident.namespace()))
+        .bind(table_ident.name())
+        .bind(expected_metadata_location)
+        .execute(self.get_pool().await?)
+        .await
+        .map_err(|e| to_iceberg_error(e, "update metadata location"))?
+        .rows_affected();
+        if rows_affected == 0 {
+            return Err(IcebergError::new(
+                ErrorKind::CatalogCommitConflicts,
+                format!(
+                    "Failed to commit table {table_ident:?}, metadata location {expected_metadata_location} has been updated concurrently"
+                ),
+            )
+            .with_retryable(true));
+        }
+        Ok(())
+    }
+
+    /// Overwrite all properties for the given namespace.
+    async fn set_namespace_properties(
+        &self,
+        namespace_ident: &NamespaceIdent,
+        mut properties: HashMap<String, String>,
+    ) -> IcebergResult<HashMap<String, String>> {
+        if properties.is_empty() {
+            properties.insert(NAMESPACE_EXISTS_PROPERTY.to_string(), "true".to_string());
+        }
+        let namespace = namespace_to_string(namespace_ident);
+        let mut tx = self
+            .get_pool()
+            .await?
+            .begin()
+            .await
+            .map_err(|e| to_iceberg_error(e, "begin transaction"))?;
+        sqlx::query(
+            "DELETE FROM iceberg_namespace_properties WHERE catalog_name = $1 AND namespace = $2",
+        )
+        .bind(self.catalog_name.as_str())
+        .bind(namespace.as_str())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| to_iceberg_error(e, "delete namespace properties"))?;
+        for (key, value) in properties.iter() {
+            sqlx::query(
+                "INSERT INTO iceberg_namespace_properties (catalog_name, namespace, property_key, property_value) VALUES ($1, $2, $3, $4)",
+            )
+            .bind(self.catalog_name.as_str())
+            .bind(namespace.as_str())
+            .bind(key.as_str())
+            .bind(value.as_str())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| to_iceberg_error(e, "insert namespace properties"))?;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| to_iceberg_error(e, "commit transaction"))?;
+        Ok(properties)
+    }
+
+    /// Get all namespaces, including those only implied by tables.
+    async fn get_all_namespaces(&self) -> IcebergResult<Vec<String>> {
+        let rows = sqlx::query(
+            "SELECT DISTINCT table_namespace AS namespace FROM iceberg_tables WHERE catalog_name = $1 \
+             UNION SELECT DISTINCT namespace FROM iceberg_namespace_properties WHERE catalog_name = $2",
+        )
+        .bind(self.catalog_name.as_str())
+        .bind(self.catalog_name.as_str())
+        .fetch_all(self.get_pool().await?)
+        .await
+        .map_err(|e| to_iceberg_error(e, "list namespaces"))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| row.get::<String, _>("namespace"))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl PuffinWrite for SqlCatalog {
+    async fn record_puffin_metadata_and_close(
+        &mut self,
+        puffin_filepath: String,
+        puffin_writer: PuffinWriter,
+    ) -> IcebergResult<()> {
+        let puffin_metadata = get_puffin_metadata_and_close(puffin_writer).await?;
+        self.puffin_blobs_to_add
+            .insert(puffin_filepath, puffin_metadata);
+        Ok(())
+    }
+
+    fn set_data_files_to_remove(&mut self, data_files: HashSet<String>) {
+        assert!(self.data_files_to_remove.is_empty());
+        self.data_files_to_remove = data_files;
+    }
+
+    fn set_puffin_files_to_remove(&mut self, puffin_filepaths: HashSet<String>) {
+        assert!(self.puffin_blobs_to_remove.is_empty());
+        self.puffin_blobs_to_remove = puffin_filepaths;
+    }
+
+    fn record_position_delete_file(&mut self, position_delete: PositionDeleteFileMetadata) {
+        self.position_deletes_to_add.push(position_delete);
+    }
+
+    fn set_compact_manifests(&mut self) {
+        self.compact_manifests = true;
+    }
+
+    fn clear_puffin_metadata(&mut self) {
+        self.puffin_blobs_to_add.clear();
+        self.puffin_blobs_to_remove.clear();
+        self.position_deletes_to_add.clear();
+        self.data_files_to_remove.clear();
+        self.compact_manifests = false;
+    }
+}
+
+#[async_trait]
+impl Catalog for SqlCatalog {
+    /// List direct children namespaces under the given parent, or top-level namespaces if parent is not provided.
+    async fn list_namespaces(
+        &self,
+        parent: Option<&NamespaceIdent>,
+    ) -> IcebergResult<Vec<NamespaceIdent>> {
+        if let Some(namespace_ident) = parent {
+            if !self.namespace_exists(namespace_ident).await? {
+                return Err(IcebergError::new(
+                    ErrorKind::NamespaceNotFound,
+                    format!(
+                        "When list namespace, parent namespace {namespace_ident:?} doesn't exist."
+                    ),
+                ));
+            }
+        }
+
+        let parent_levels = parent
+            .map(|namespace_ident| namespace_ident.as_ref().clone())
+            .unwrap_or_default();
+        let mut res: Vec<NamespaceIdent> = Vec::new();
+        for namespace in self.get_all_namespaces().await? {
+            let levels = namespace.split('.').collect::<Vec<_>>();
+            if levels.len() <= parent_levels.len()
+                || !levels
+                    .iter()
+                    .zip(parent_levels.iter())
+                    .all(|(level, parent_level)| *level == parent_level.as_str())
+            {
+                continue;
+            }
+            let cur_namespace_ident = NamespaceIdent::from_strs(&levels[..=parent_levels.len()])?;
+            if !res.contains(&cur_namespace_ident) {
+                res.push(cur_namespace_ident);
+            }
+        }
+        Ok(res)
+    }
+
+    /// Create a new namespace inside the catalog, return error if namespace already exists.
+    async fn create_namespace(
+        &self,
+        namespace_ident: &NamespaceIdent,
+        properties: HashMap<String, String>,
+    ) -> IcebergResult<Namespace> {
+        if self.namespace_exists(namespace_ident).await? {
+            return Err(IcebergError::new(
+                ErrorKind::NamespaceAlreadyExists,
+                format!("Namespace {namespace_ident:?} already exists"),
+            ));
+        }
+        let properties = self
+            .set_namespace_properties(namespace_ident, properties)
+            .await?;
+        Ok(Namespace::with_properties(
+            namespace_ident.clone(),
+            properties,
+        ))
+    }
+
+    /// Get a namespace information from the catalog, return error if requested namespace doesn't exist.
+    async fn get_namespace(&self, namespace_ident: &NamespaceIdent) -> IcebergResult<Namespace> {
+        if !self.namespace_exists(namespace_ident).await? {
+            return Err(IcebergError::new(
+                ErrorKind::NamespaceNotFound,
+                format!("Namespace {namespace_ident:?} does not exist"),
+            ));
+        }
+        let rows = sqlx::query(
+            "SELECT property_key, property_value FROM iceberg_namespace_properties WHERE catalog_name = $1 AND namespace = $2",
+        )
+        .bind(self.catalog_name.as_str())
+        .bind(namespace_to_string(namespace_ident))
+        .fetch_all(self.get_pool().await?)
+        .await
+        .map_err(|e| to_iceberg_error(e, "get namespace properties"))?;
+        let properties = rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.get::<String, _>("property_key"),
+                    row.get::<Option<String>, _>("property_value")
+                        .unwrap_or_default(),
+                )
+            })
+            .collect();
+        Ok(Namespace::with_properties(
+            namespace_ident.clone(),
+            properties,
+        ))
+    }
+
+    /// Check if namespace exists in catalog, a namespace exists if it has properties or contains tables.
+    async fn namespace_exists(&self, namespace_ident: &NamespaceIdent) -> IcebergResult<bool> {
+        let namespace = namespace_to_string(namespace_ident);
+        let row = sqlx::query(
+            "SELECT namespace FROM iceberg_namespace_properties WHERE catalog_name = $1 AND namespace = $2 \
+             UNION SELECT table_namespace FROM iceberg_tables WHERE catalog_name = $3 AND table_namespace = $4 LIMIT 1",
+        )
+        .bind(self.catalog_name.as_str())
+        .bind(namespace.as_str())
+        .bind(self.catalog_name.as_str())
+        .bind(namespace.as_str())
+        .fetch_optional(self.get_pool().await?)
+        .await
+        .map_err(|e| to_iceberg_error(e, "check namespace existence"))?;
+        Ok(row.is_some())
+    }
+
+    /// Drop a namespace from the catalog, return error if there're still tables within.
+    async fn drop_namespace(&self, namespace_ident: &NamespaceIdent) -> IcebergResult<()> {
+        let tables = self.list_tables(namespace_ident).await?;
+        if !tables.is_empty() {
+            return Err(IcebergError::new(
+                ErrorKind::Unexpected,
+                format!("Namespace {namespace_ident:?} is not empty, contains tables {tables:?}"),
+            ));
+        }
+        sqlx::query(
+            "DELETE FROM iceberg_namespace_properties WHERE catalog_name = $1 AND namespace = $2",
+        )
+        .bind(self.catalog_name.as_str())
+        .bind(namespace_to_string(namespace_ident))
+        .execute(self.get_pool().await?)
+        .await
+        .map_err(|e| to_iceberg_error(e, "drop namespace"))?;
+        Ok(())
+    }
+
+    /// List tables from namespace, return error if the given namespace doesn't exist.
+    async fn list_tables(
+        &self,
+        namespace_ident: &NamespaceIdent,
+    ) -> IcebergResult<Vec<TableIdent>> {
+        if !self.namespace_exists(namespace_ident).await? {
+            return Err(IcebergError::new(
+                ErrorKind::NamespaceNotFound,
+                format!("Namespace {namespace_ident:?} doesn't exist when list tables within."),
+            ));
+        }
+        let rows = sqlx::query(
+            "SELECT table_name FROM iceberg_tables WHERE catalog_name = $1 AND table_namespace = $2",
+        )
+        .bind(self.catalog_name.as_str())
+        .bind(namespace_to_string(namespace_ident))
+        .fetch_all(self.get_pool().await?)
+        .await
+        .map_err(|e| to_iceberg_error(e, "list tables"))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| TableIdent::new(namespace_ident.clone(), row.get("table_name")))
+            .collect())
+    }
+
+    async fn update_namespace(
+        &self,
+        namespace_ident: &NamespaceIdent,
+        properties: HashMap<String, String>,
+    ) -> IcebergResult<()> {
+        if !self.namespace_exists(namespace_ident).await? {
+            return Err(IcebergError::new(
+                ErrorKind::NamespaceNotFound,
+                format!("Namespace {namespace_ident:?} does not exist"),
+            ));
+        }
+        self.set_namespace_properties(namespace_ident, properties)
+            .await?;
+        Ok(())
+    }
+
+    /// Create a new table inside the namespace.
+    async fn create_table(
+        &self,
+        namespace_ident: &NamespaceIdent,
+        creation: TableCreation,
+    ) -> IcebergResult<Table> {
+        let table_ident = TableIdent::new(namespace_ident.clone(), creation.name.clone());
+        if self.table_exists(&table_ident).await? {
+            return Err(IcebergError::new(
+                ErrorKind::TableAlreadyExists,
+                format!("Table {table_ident:?} already exists"),
+            ));
+        }
+        let table_location = creation.location.clone().ok_or_else(|| {
+            IcebergError::new(
+                ErrorKind::DataInvalid,
+                format!("Table location is required at creation for {table_ident:?}"),
+            )
+        })?;
+
+        // iceberg-rust reassigns field id at table creation, overwrite iceberg schema with correctly populated field id.
+        let table_metadata = catalog_utils::new_table_metadata_builder(creation)?.build()?;
+        let metadata = table_metadata
+            .metadata
+            .into_builder(/*current_file_location=*/ None)
+            .add_current_schema(self.iceberg_schema.clone())?
+            .build()?
+            .metadata;
+
+        let metadata_location = get_metadata_location(&table_location, /*version=*/ 0);
+        self.write_metadata(&metadata_location, &metadata).await?;
+        self.insert_table(&table_ident, &metadata_location).await?;
+
+        Table::builder()
+            .metadata_location(metadata_location)
+            .metadata(metadata)
+            .identifier(table_ident)
+            .file_io(self.file_io.clone())
+            .build()
+    }
+
+    /// Load table from the catalog.
+    async fn load_table(&self, table_ident: &TableIdent) -> IcebergResult<Table> {
+        let (metadata_location, metadata) = self.load_metadata(table_ident).await?;
+        Table::builder()
+            .metadata_location(metadata_location)
+            .metadata(metadata)
+            .identifier(table_ident.clone())
+            .file_io(self.file_io.clone())
+            .build()
+    }
+
+    /// Drop a table from the catalog, and delete all its files.
+    async fn drop_table(&self, table: &TableIdent) -> IcebergResult<()> {
+        let rows_affected = sqlx::query(
+            "DELETE FROM iceberg_tables WHERE catalog_name = $1 AND table_namespace = $2 AND table_name = $3",
+        )
+        .bind(self.catalog_name.as_str())
+        .bind(namespace_to_string(table.namespace()))
+        .bind(table.name())
+        .execute(self.get_pool().await?)
+        .await
+        .map_err(|e| to_iceberg_error(e, "drop table"))?
+        .rows_affected();
+        if rows_affected == 0 {
+            return Err(IcebergError::new(
+                ErrorKind::TableNotFound,
+                format!("Table {table:?} doesn't exist"),
+            ));
+        }
+
+        let directory = format!("{}/{}", table.namespace().to_url_string(), table.name());
+        self.filesystem_accessor
+            .remove_directory(&directory)
+            .await
+            .map_err(|e| {
+                IcebergError::new(
+                    ErrorKind::Unexpected,
+                    format!("Failed to delete directory {directory}: {e:?}"),
+                )
+                .with_retryable(true)
+            })?;
+        Ok(())
+    }
+
+    /// Check if a table exists in the catalog.
+    async fn table_exists(&self, table: &TableIdent) -> IcebergResult<bool> {
+        Ok(self.get_current_metadata_location(table).await?.is_some())
+    }
+
+    /// Rename a table in the catalog, table files are not moved.
+    async fn rename_table(&self, src: &TableIdent, dest: &TableIdent) -> IcebergResult<()> {
+        if self.table_exists(dest).await? {
+            return Err(IcebergError::new(
+                ErrorKind::TableAlreadyExists,
+                format!("Table {dest:?} already exists"),
+            ));
+        }
+        let rows_affected = sqlx::query(
+            "UPDATE iceberg_tables SET table_namespace = $1, table_name = $2 WHERE catalog_name = $3 AND table_namespace = $4 AND table_name = $5",
+        )
+        .bind(namespace_to_string(dest.namespace()))
+        .bind(dest.name())
+        .bind(self.catalog_name.as_str())
+        .bind(namespace_to_string(src.namespace()))
+        .bind(src.name())
+        .execute(self.get_pool().await?)
+        .await
+        .map_err(|e| to_iceberg_error(e, "rename table"))?
+        .rows_affected();
+        if rows_affected == 0 {
+            return Err(IcebergError::new(
+                ErrorKind::TableNotFound,
+                format!("Table {src:?} doesn't exist"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Update a table to the catalog, which writes a new metadata file and swaps metadata pointer.
+    /// The swap is a single conditional update on the metadata location loaded at the beginning, so a concurrent commit leads
+    /// to no row updated, which is reported as commit conflict.
+    async fn update_table(&self, mut commit: TableCommit) -> IcebergResult<Table> {
+        let (metadata_location, metadata) = self.load_metadata(commit.identifier()).await?;
+        let version = get_metadata_version(&metadata_location)? + 1;
+        let builder = TableMetadataBuilder::new_from_metadata(
+            metadata.clone(),
+            /*current_file_location=*/ Some(metadata_location.clone()),
+        );
+
+        // Validate existing table metadata with requirements.
+        catalog_utils::validate_table_requirements(commit.take_requirements(), &metadata)?;
+
+        // Construct new metadata with updates.
+        let updates = commit.take_updates();
+        let builder = catalog_utils::reflect_table_updates(builder, updates)?;
+        let metadata = builder.build()?.metadata;
+
+        // Write metadata file.
+        let new_metadata_location = get_metadata_location(metadata.location(), version);
+        self.write_metadata(&new_metadata_location, &metadata)
+            .await?;
+
+        // Manifest files and manifest list has persisted into storage, make modifications based on puffin blobs.
+        append_puffin_metadata_and_rewrite(
+            &metadata,
+            &self.file_io,
+            &self.data_files_to_remove,
+            &self.puffin_blobs_to_add,
+            &self.puffin_blobs_to_remove,
+            &self.position_deletes_to_add,
+            self.compact_manifests,
+        )
+        .await?;
+
+        // Swap metadata pointer, which fails if there's concurrent commit since metadata load.
+        self.swap_metadata_location(
+            commit.identifier(),
+            &metadata_location,
+            &new_metadata_location,
+        )
+        .await?;
+
+        Table::builder()
+            .identifier(commit.identifier().clone())
+            .file_io(self.file_io.clone())
+            .metadata(metadata)
+            .metadata_location(new_metadata_location)
+            .build()
+    }
+
+    /// Register an existing table with its metadata file.
+    async fn register_table(
+        &self,
+        table: &TableIdent,
+        metadata_location: String,
+    ) -> IcebergResult<Table> {
+        if self.table_exists(table).await? {
+            return Err(IcebergError::new(
+                ErrorKind::TableAlreadyExists,
+                format!("Table {table:?} already exists"),
+            ));
+        }
+        let metadata = self.read_metadata(&metadata_location).await?;
+        self.insert_table(table, &metadata_location).await?;
+        Table::builder()
+            .metadata_location(metadata_location)
+            .metadata(metadata)
+            .identifier(table.clone())
+            .file_io(self.file_io.clone())
+            .build()
+    }
+}
+
+#[async_trait]
+impl SchemaUpdate for SqlCatalog {
+    async fn update_table_schema(
+        &mut self,
+        new_schema: IcebergSchema,
+        table_ident: TableIdent,
+    ) -> IcebergResult<Table> {
+        let (_, old_metadata) = self.load_metadata(&table_ident).await?;
+        let current_schema_id = old_metadata.current_schema_id();
+        let mut metadata_builder = old_metadata.into_builder(/*current_file_location=*/ None);
+        metadata_builder = metadata_builder.add_current_schema(new_schema)?;
+        let metadata_builder_result = metadata_builder.build()?;
+
+        let table_commit_proxy = TableCommitProxy {
+            ident: table_ident,
+            updates: metadata_builder_result.changes,
+            requirements: vec![TableRequirement::CurrentSchemaIdMatch { current_schema_id }],
+        };
+        let table_commit = table_commit_proxy.take_as_table_commit();
+        self.update_table(table_commit).await
+    }
+}
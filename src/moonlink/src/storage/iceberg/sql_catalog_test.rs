@@ -0,0 +1,253 @@
+use crate::storage::filesystem::filesystem_config::FileSystemConfig;
+use crate::storage::iceberg::file_catalog_test_utils::*;
+use crate::storage::iceberg::moonlink_catalog::SchemaUpdate;
+use crate::storage::iceberg::sql_catalog::SqlCatalog;
+use crate::storage::iceberg::table_commit_proxy::TableCommitProxy;
+
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+use iceberg::spec::Schema;
+use iceberg::{Catalog, ErrorKind, NamespaceIdent, TableCreation, TableIdent, TableRequirement};
+use sqlx::Row;
+
+/// Test catalog name.
+const TEST_CATALOG_NAME: &str = "moonlink";
+
+/// Test util function to get sqlite database uri under the given directory.
+pub(crate) fn get_test_sqlite_uri(temp_dir: &TempDir) -> String {
+    format!(
+        "sqlite://{}?mode=rwc",
+        temp_dir.path().join("catalog.sqlite").to_str().unwrap()
+    )
+}
+
+/// Test util function to create a SQL catalog backed by sqlite.
+fn create_test_sql_catalog(temp_dir: &TempDir, iceberg_schema: Schema) -> SqlCatalog {
+    SqlCatalog::new(
+        FileSystemConfig::FileSystem {
+            root_directory: temp_dir.path().to_str().unwrap().to_string(),
+        },
+        &get_test_sqlite_uri(temp_dir),
+        TEST_CATALOG_NAME.to_string(),
+        iceberg_schema,
+    )
+    .unwrap()
+}
+
+// Test util function to create a new table.
+async fn create_test_table(catalog: &SqlCatalog, temp_dir: &TempDir) -> TableIdent {
+    let namespace = NamespaceIdent::from_strs(["default"]).unwrap();
+    let table_name = "test_table".to_string();
+
+    let table_creation = TableCreation::builder()
+        .name(table_name.clone())
+        .location(format!(
+            "{}/{}/{}",
+            temp_dir.path().to_str().unwrap(),
+            namespace.to_url_string(),
+            table_name
+        ))
+        .schema(get_test_schema())
+        .build();
+    catalog
+        .create_namespace(&namespace, /*properties=*/ HashMap::new())
+        .await
+        .unwrap();
+    catalog
+        .create_table(&namespace, table_creation)
+        .await
+        .unwrap();
+
+    TableIdent::new(namespace, table_name)
+}
+
+/// Test util function to get metadata locations from `iceberg_tables`.
+async fn get_metadata_locations(
+    temp_dir: &TempDir,
+    table_ident: &TableIdent,
+) -> (String, Option<String>) {
+    sqlx::any::install_default_drivers();
+    let pool = sqlx::AnyPool::connect(&get_test_sqlite_uri(temp_dir))
+        .await
+        .unwrap();
+    let row = sqlx::query(
+        "SELECT metadata_location, previous_metadata_location FROM iceberg_tables WHERE catalog_name = $1 AND table_namespace = $2 AND table_name = $3",
+    )
+    .bind(TEST_CATALOG_NAME)
+    .bind("default")
+    .bind(table_ident.name())
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    (
+        row.get("metadata_location"),
+        row.get("previous_metadata_location"),
+    )
+}
+
+#[tokio::test]
+async fn test_sql_catalog_namespace_operations() {
+    let temp_dir = TempDir::new().unwrap();
+    let catalog = create_test_sql_catalog(&temp_dir, get_test_schema());
+    let parent_namespace = NamespaceIdent::from_strs(["default"]).unwrap();
+    let namespace = NamespaceIdent::from_strs(["default", "ns"]).unwrap();
+
+    // Create namespaces and check.
+    assert!(!catalog.namespace_exists(&namespace).await.unwrap());
+    catalog
+        .create_namespace(&parent_namespace, /*properties=*/ HashMap::new())
+        .await
+        .unwrap();
+    let properties = HashMap::from([("owner".to_string(), "moonlink".to_string())]);
+    catalog
+        .create_namespace(&namespace, properties.clone())
+        .await
+        .unwrap();
+    assert!(catalog.namespace_exists(&namespace).await.unwrap());
+    assert!(catalog
+        .create_namespace(&namespace, /*properties=*/ HashMap::new())
+        .await
+        .is_err());
+
+    // Get namespace and check properties.
+    let ns = catalog.get_namespace(&namespace).await.unwrap();
+    assert_eq!(ns.name(), &namespace);
+    assert_eq!(ns.properties(), &properties);
+
+    // List namespaces and check.
+    let res = catalog.list_namespaces(/*parent=*/ None).await.unwrap();
+    assert_eq!(res, vec![parent_namespace.clone()]);
+    let res = catalog
+        .list_namespaces(Some(&parent_namespace))
+        .await
+        .unwrap();
+    assert_eq!(res, vec![namespace.clone()]);
+
+    // Drop namespace and check.
+    catalog.drop_namespace(&namespace).await.unwrap();
+    assert!(!catalog.namespace_exists(&namespace).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_sql_catalog_table_operations() {
+    let temp_dir = TempDir::new().unwrap();
+    let catalog = create_test_sql_catalog(&temp_dir, get_test_schema());
+    let table_ident = create_test_table(&catalog, &temp_dir).await;
+    assert!(catalog.table_exists(&table_ident).await.unwrap());
+    assert_eq!(
+        catalog.list_tables(table_ident.namespace()).await.unwrap(),
+        vec![table_ident.clone()]
+    );
+
+    // Load table and check.
+    let table = catalog.load_table(&table_ident).await.unwrap();
+    assert_eq!(**table.metadata().current_schema(), get_test_schema());
+    let (metadata_location, previous_metadata_location) =
+        get_metadata_locations(&temp_dir, &table_ident).await;
+    assert_eq!(table.metadata_location(), Some(metadata_location.as_str()));
+    assert!(previous_metadata_location.is_none());
+
+    // Rename table and check.
+    let new_table_ident = TableIdent::new(table_ident.namespace().clone(), "new_table".to_string());
+    catalog
+        .rename_table(&table_ident, &new_table_ident)
+        .await
+        .unwrap();
+    assert!(!catalog.table_exists(&table_ident).await.unwrap());
+    assert!(catalog.table_exists(&new_table_ident).await.unwrap());
+
+    // Drop the table and check.
+    catalog.drop_table(&new_table_ident).await.unwrap();
+    assert!(!catalog.table_exists(&new_table_ident).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_sql_catalog_update_schema() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut catalog = create_test_sql_catalog(&temp_dir, get_test_schema());
+    let table_ident = create_test_table(&catalog, &temp_dir).await;
+    let (old_metadata_location, _) = get_metadata_locations(&temp_dir, &table_ident).await;
+
+    let new_schema = get_updated_test_schema();
+    catalog
+        .update_table_schema(new_schema.clone(), table_ident.clone())
+        .await
+        .unwrap();
+
+    // Check metadata pointer has been swapped.
+    let (metadata_location, previous_metadata_location) =
+        get_metadata_locations(&temp_dir, &table_ident).await;
+    assert_ne!(metadata_location, old_metadata_location);
+    assert_eq!(previous_metadata_location, Some(old_metadata_location));
+
+    // Load table metadata to check schema.
+    let (_, table_metadata) = catalog.load_metadata(&table_ident).await.unwrap();
+    assert_eq!(table_metadata.current_schema_id(), new_schema.schema_id());
+    assert_eq!(**table_metadata.current_schema(), new_schema);
+}
+
+#[tokio::test]
+async fn test_sql_catalog_update_table_with_requirement_check_failed() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut catalog = create_test_sql_catalog(&temp_dir, get_test_schema());
+    let table_ident = create_test_table(&catalog, &temp_dir).await;
+    let (_, table_metadata) = catalog.load_metadata(&table_ident).await.unwrap();
+    let old_schema_id = table_metadata.current_schema_id();
+
+    // Update schema, so commits based on the old schema fail.
+    catalog
+        .update_table_schema(get_updated_test_schema(), table_ident.clone())
+        .await
+        .unwrap();
+    let (metadata_location, _) = get_metadata_locations(&temp_dir, &table_ident).await;
+
+    let table_commit_proxy = TableCommitProxy {
+        ident: table_ident.clone(),
+        requirements: vec![TableRequirement::CurrentSchemaIdMatch {
+            current_schema_id: old_schema_id,
+        }],
+        updates: vec![],
+    };
+    let res = catalog
+        .update_table(table_commit_proxy.take_as_table_commit())
+        .await;
+    assert!(res.is_err());
+
+    // Metadata pointer keeps unchanged on commit failure.
+    let (new_metadata_location, _) = get_metadata_locations(&temp_dir, &table_ident).await;
+    assert_eq!(new_metadata_location, metadata_location);
+}
+
+#[tokio::test]
+async fn test_sql_catalog_swap_stale_metadata_location() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut catalog = create_test_sql_catalog(&temp_dir, get_test_schema());
+    let table_ident = create_test_table(&catalog, &temp_dir).await;
+    let (stale_metadata_location, _) = get_metadata_locations(&temp_dir, &table_ident).await;
+
+    // Commit a new metadata file, so the loaded metadata pointer becomes stale.
+    catalog
+        .update_table_schema(get_updated_test_schema(), table_ident.clone())
+        .await
+        .unwrap();
+    let (metadata_location, previous_metadata_location) =
+        get_metadata_locations(&temp_dir, &table_ident).await;
+
+    // Swap based on the stale pointer updates no row, and reports commit conflict.
+    let err = catalog
+        .swap_metadata_location(
+            &table_ident,
+            &stale_metadata_location,
+            &format!("{stale_metadata_location}.stale"),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::CatalogCommitConflicts);
+
+    // Metadata pointer keeps unchanged on commit conflict.
+    assert_eq!(
+        get_metadata_locations(&temp_dir, &table_ident).await,
+        (metadata_location, previous_metadata_location)
+    );
+}
@@ -1,4 +1,5 @@
 /// This module interacts with iceberg snapshot status, which corresponds to one mooncake table.
+use std::collections::HashSet;
 use tokio::sync::{broadcast, mpsc, oneshot, watch};
 
 use crate::event_sync::EventSyncReceiver;
@@ -17,6 +18,12 @@ pub struct TableEventManager {
     force_snapshot_completion_rx: watch::Receiver<Option<Result<u64>>>,
     /// Sender which is used to create notification at latest data compaction completion.
     table_maintenance_completion_tx: broadcast::Sender<Result<()>>,
+    /// Channel to observe whether finished initial copy has been persisted.
+    initial_copy_completion_rx: watch::Receiver<bool>,
+    /// Channel to observe the error which stops replication for the table, if it fails.
+    table_error_rx: watch::Receiver<Option<String>>,
+    /// Channel to observe GIDs of prepared transactions persisted into table WAL.
+    prepared_gids_rx: watch::Receiver<HashSet<String>>,
 }
 
 impl TableEventManager {
@@ -30,6 +37,9 @@ impl TableEventManager {
             flush_lsn_rx: table_event_sync_rx.flush_lsn_rx,
             force_snapshot_completion_rx: table_event_sync_rx.force_snapshot_completion_rx,
             table_maintenance_completion_tx: table_event_sync_rx.table_maintenance_completion_tx,
+            initial_copy_completion_rx: table_event_sync_rx.initial_copy_completion_rx,
+            table_error_rx: table_event_sync_rx.table_error_rx,
+            prepared_gids_rx: table_event_sync_rx.prepared_gids_rx,
         }
     }
 
@@ -38,6 +48,21 @@ impl TableEventManager {
         self.flush_lsn_rx.clone()
     }
 
+    /// Subscribe to persistence of finished initial copy.
+    pub fn subscribe_initial_copy_completion(&self) -> watch::Receiver<bool> {
+        self.initial_copy_completion_rx.clone()
+    }
+
+    /// Subscribe to the table failure.
+    pub fn subscribe_table_error(&self) -> watch::Receiver<Option<String>> {
+        self.table_error_rx.clone()
+    }
+
+    /// Subscribe to prepared transactions persisted into table WAL.
+    pub fn subscribe_prepared_gids(&self) -> watch::Receiver<HashSet<String>> {
+        self.prepared_gids_rx.clone()
+    }
+
     /// Initiate an iceberg snapshot event, return the channel for synchronization.
     pub async fn initiate_snapshot(&mut self, lsn: u64) -> watch::Receiver<Option<Result<u64>>> {
         self.table_event_tx
@@ -111,6 +136,16 @@ impl TableEventManager {
         subscriber
     }
 
+    /// Initiate snapshot expiration event, return the channel for synchronization.
+    pub async fn initiate_snapshot_expiration(&mut self) -> broadcast::Receiver<Result<()>> {
+        let subscriber = self.table_maintenance_completion_tx.subscribe();
+        self.table_event_tx
+            .send(TableEvent::ForceSnapshotExpiration)
+            .await
+            .unwrap();
+        subscriber
+    }
+
     /// Drop a mooncake table.
     /// Each table event manager correspond to one mooncake table, so this function should be called at most once.
     pub async fn drop_table(&mut self) -> Result<()> {
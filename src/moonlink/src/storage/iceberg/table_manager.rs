@@ -2,6 +2,7 @@
 use std::collections::HashMap;
 
 use crate::storage::iceberg::puffin_utils::PuffinBlobRef;
+use crate::storage::iceberg::snapshot_retention_config::SnapshotRetentionConfig;
 use crate::storage::index::FileIndex;
 use crate::storage::mooncake_table::IcebergSnapshotPayload;
 use crate::storage::mooncake_table::Snapshot as MooncakeSnapshot;
@@ -33,6 +34,16 @@ pub struct PersistenceResult {
     pub(crate) puffin_blob_ref: HashMap<FileId, PuffinBlobRef>,
 }
 
+/// Iceberg snapshot expiration results.
+#[derive(Clone, Default, Debug)]
+pub struct ExpireSnapshotsResult {
+    /// Ids for expired snapshots.
+    pub(crate) expired_snapshot_ids: Vec<i64>,
+    /// Files which are not referenced by retained snapshots, and have been deleted; including files only referenced by expired snapshots,
+    /// orphan files and previous metadata files.
+    pub(crate) deleted_files: Vec<String>,
+}
+
 #[async_trait]
 #[cfg_attr(test, automock)]
 pub trait TableManager: Send {
@@ -57,6 +68,14 @@ pub trait TableManager: Send {
         &mut self,
     ) -> IcebergResult<(u32 /*next file id*/, MooncakeSnapshot)>;
 
+    /// Expire snapshots out of retention, and delete files which are not referenced by any retained snapshot.
+    /// Files still pinned by in-flight reads are skipped, and retried at the next expiration.
+    #[allow(async_fn_in_trait)]
+    async fn expire_snapshots(
+        &mut self,
+        retention_config: &SnapshotRetentionConfig,
+    ) -> IcebergResult<ExpireSnapshotsResult>;
+
     /// Drop the current iceberg table.
     #[allow(async_fn_in_trait)]
     async fn drop_table(&mut self) -> IcebergResult<()>;
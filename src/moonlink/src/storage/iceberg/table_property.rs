@@ -1,15 +1,41 @@
 /// This module defines a few iceberg table property related constants and utils.
 /// Reference: https://iceberg.apache.org/docs/latest/configuration/#table-properties
+use crate::storage::iceberg::iceberg_table_config::IcebergDeleteFileFormat;
+use crate::storage::parquet_writer_config::{
+    ParquetCompressionCodec, ParquetStatisticsLevel, ParquetWriterConfig,
+};
+
 use std::collections::HashMap;
 
-/// Compression codec for parquet files.
+use iceberg::spec::FormatVersion;
+
+/// Reserved property for the table format version, which catalogs take as the format version of newly created tables instead of storing it.
+/// Both delete file formats are committed into v2 tables.
+pub(crate) const FORMAT_VERSION: &str = "format-version";
+pub(crate) const FORMAT_VERSION_DEFAULT: FormatVersion = FormatVersion::V2;
+
+/// Delete file format the table is written with, which can't be switched for an existing table.
+pub(crate) const MOONLINK_DELETE_FILE_FORMAT: &str = "moonlink.delete-file-format";
+
+/// Compression codec and level for parquet files.
 pub(crate) const PARQUET_COMPRESSION: &str = "write.parquet.compression-codec";
-pub(crate) const PARQUET_COMPRESSION_DEFAULT: &str = "snappy";
+pub(crate) const PARQUET_COMPRESSION_LEVEL: &str = "write.parquet.compression-level";
+
+/// Page size for parquet files.
+pub(crate) const PARQUET_PAGE_SIZE_BYTES: &str = "write.parquet.page-size-bytes";
+
+/// Column metrics mode, which is only set when parquet files are written without statistics.
+pub(crate) const METRICS_DEFAULT_MODE: &str = "write.metadata.metrics.default";
+pub(crate) const METRICS_MODE_COUNTS: &str = "counts";
 
 /// Compression codec for metadata.
 pub(crate) const METADATA_COMPRESSION: &str = "write.metadata.compression-codec";
 pub(crate) const METADATA_COMPRESSION_DEFAULT: &str = "none";
 
+/// File format for position delete files, which is only set when deletion records are written as position delete files.
+pub(crate) const DELETE_DEFAULT_FILE_FORMAT: &str = "write.delete.format.default";
+pub(crate) const DELETE_DEFAULT_FILE_FORMAT_PARQUET: &str = "parquet";
+
 /// Retry properties.
 pub(crate) const TABLE_COMMIT_RETRY_NUM: &str = "commit.retry.num-retries";
 pub(crate) const TABLE_COMMIT_RETRY_NUM_DEFAULT: u64 = 5;
@@ -23,18 +49,65 @@ pub(crate) const TABLE_COMMIT_RETRY_MAX_MS_DEFAULT: u64 = 30000; // 30 second
 pub(crate) const TABLE_COMMIT_RETRY_TIMEOUT_MS: &str = "commit.retry.total-timeout-ms";
 pub(crate) const TABLE_COMMIT_RETRY_TIMEOUT_MS_DEFAULT: u64 = 120000; // 2 min
 
+/// Get property value for the given delete file format.
+pub(crate) fn get_delete_file_format_name(
+    delete_file_format: IcebergDeleteFileFormat,
+) -> &'static str {
+    match delete_file_format {
+        IcebergDeleteFileFormat::DeletionVector => "deletion-vector",
+        IcebergDeleteFileFormat::PositionDelete => "position-delete",
+    }
+}
+
+/// Get iceberg compression codec name for the given parquet compression codec.
+fn get_compression_codec_name(codec: ParquetCompressionCodec) -> &'static str {
+    match codec {
+        ParquetCompressionCodec::Uncompressed => "uncompressed",
+        ParquetCompressionCodec::Snappy => "snappy",
+        ParquetCompressionCodec::Gzip => "gzip",
+        ParquetCompressionCodec::Lz4 => "lz4",
+        ParquetCompressionCodec::Zstd => "zstd",
+    }
+}
+
 // Create iceberg table properties from table config.
-pub(crate) fn create_iceberg_table_properties() -> HashMap<String, String> {
-    let mut props = HashMap::with_capacity(6);
+//
+// Notice row group size is not recorded, since moonlink limits row groups by row count while iceberg property is in bytes.
+pub(crate) fn create_iceberg_table_properties(
+    delete_file_format: IcebergDeleteFileFormat,
+    parquet_writer_config: &ParquetWriterConfig,
+) -> HashMap<String, String> {
+    let mut props = HashMap::with_capacity(12);
+    props.insert(
+        FORMAT_VERSION.to_string(),
+        (FORMAT_VERSION_DEFAULT as u8).to_string(),
+    );
     // Compression properties.
     props.insert(
         PARQUET_COMPRESSION.to_string(),
-        PARQUET_COMPRESSION_DEFAULT.to_string(),
+        get_compression_codec_name(parquet_writer_config.compression).to_string(),
     );
+    if let Some(compression_level) = parquet_writer_config.compression_level {
+        props.insert(
+            PARQUET_COMPRESSION_LEVEL.to_string(),
+            compression_level.to_string(),
+        );
+    }
     props.insert(
         METADATA_COMPRESSION.to_string(),
         METADATA_COMPRESSION_DEFAULT.to_string(),
     );
+    // Parquet layout properties.
+    props.insert(
+        PARQUET_PAGE_SIZE_BYTES.to_string(),
+        parquet_writer_config.data_page_size.to_string(),
+    );
+    if parquet_writer_config.statistics_level == ParquetStatisticsLevel::None {
+        props.insert(
+            METRICS_DEFAULT_MODE.to_string(),
+            METRICS_MODE_COUNTS.to_string(),
+        );
+    }
     // Commit retry properties.
     props.insert(
         TABLE_COMMIT_RETRY_NUM.to_string(),
@@ -52,5 +125,16 @@ pub(crate) fn create_iceberg_table_properties() -> HashMap<String, String> {
         TABLE_COMMIT_RETRY_TIMEOUT_MS.to_string(),
         TABLE_COMMIT_RETRY_TIMEOUT_MS_DEFAULT.to_string(),
     );
+    // Delete file properties.
+    props.insert(
+        MOONLINK_DELETE_FILE_FORMAT.to_string(),
+        get_delete_file_format_name(delete_file_format).to_string(),
+    );
+    if delete_file_format == IcebergDeleteFileFormat::PositionDelete {
+        props.insert(
+            DELETE_DEFAULT_FILE_FORMAT.to_string(),
+            DELETE_DEFAULT_FILE_FORMAT_PARQUET.to_string(),
+        );
+    }
     props
 }
@@ -1,4 +1,5 @@
 /// This module contain tests which are not covered by state-machine based test, including complex operations, object storage based tests, etc.
+use crate::row::IdentityProp;
 use crate::row::MoonlinkRow;
 use crate::row::RowValue;
 #[cfg(feature = "storage-gcs")]
@@ -11,11 +12,19 @@ use crate::storage::filesystem::s3::s3_test_utils;
 use crate::storage::filesystem::s3::test_guard::TestGuard as S3TestGuard;
 use crate::storage::iceberg::file_catalog::METADATA_DIRECTORY;
 use crate::storage::iceberg::file_catalog::VERSION_HINT_FILENAME;
-use crate::storage::iceberg::iceberg_table_config::IcebergTableConfig;
+use crate::storage::iceberg::iceberg_table_config::{
+    IcebergCatalogConfig, IcebergDeleteFileFormat, IcebergTableConfig,
+};
 use crate::storage::iceberg::iceberg_table_manager::IcebergTableManager;
+use crate::storage::iceberg::manifest_compaction_config::ManifestCompactionConfig;
+use crate::storage::iceberg::rest_catalog_test_utils::RestCatalogTestServer;
 use crate::storage::iceberg::schema_utils::*;
+use crate::storage::iceberg::snapshot_utils;
+#[cfg(feature = "catalog-sql")]
+use crate::storage::iceberg::sql_catalog_test::get_test_sqlite_uri;
 use crate::storage::iceberg::table_manager::PersistenceFileParams;
 use crate::storage::iceberg::table_manager::TableManager;
+use crate::storage::iceberg::table_property;
 use crate::storage::iceberg::test_utils::*;
 use crate::storage::index::persisted_bucket_hash_map::GlobalIndex;
 use crate::storage::index::MooncakeIndex;
@@ -25,6 +34,7 @@ use crate::storage::mooncake_table::table_operation_test_utils::*;
 use crate::storage::mooncake_table::validation_test_utils::*;
 use crate::storage::mooncake_table::IcebergPersistenceConfig;
 use crate::storage::mooncake_table::IcebergSnapshotPayload;
+use crate::storage::mooncake_table::InitialCopyPosition;
 use crate::storage::mooncake_table::MaintenanceOption;
 use crate::storage::mooncake_table::MooncakeTableConfig;
 use crate::storage::mooncake_table::SnapshotOption;
@@ -32,6 +42,9 @@ use crate::storage::mooncake_table::{
     IcebergSnapshotDataCompactionPayload, IcebergSnapshotImportPayload,
     IcebergSnapshotIndexMergePayload,
 };
+use crate::storage::parquet_writer_config::{
+    ParquetCompressionCodec, ParquetSortField, ParquetWriterConfig,
+};
 use crate::storage::storage_utils;
 use crate::storage::storage_utils::create_data_file;
 use crate::storage::storage_utils::FileId;
@@ -49,9 +62,14 @@ use std::sync::Arc;
 use arrow::datatypes::Schema as ArrowSchema;
 use arrow_array::{Int32Array, RecordBatch, StringArray};
 use iceberg::arrow::arrow_schema_to_schema;
+use iceberg::spec::{
+    DataContentType, DataFileFormat, FormatVersion, NullOrder, SortDirection, Transform,
+};
 use iceberg::NamespaceIdent;
 use iceberg::TableIdent;
 use parquet::arrow::AsyncArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::reader::{FileReader, SerializedFileReader};
 use tempfile::tempdir;
 use tokio::sync::mpsc;
 
@@ -332,6 +350,7 @@ async fn test_store_and_load_snapshot_impl(iceberg_table_config: IcebergTableCon
         uuid: uuid::Uuid::new_v4(),
         flush_lsn: 0,
         wal_persistence_metadata: None,
+        initial_copy_position: InitialCopyPosition::default(),
         new_table_schema: None,
         committed_deletion_logs: test_committed_deletion_logs_to_persist_1(data_file_1.clone()),
         import_payload: IcebergSnapshotImportPayload {
@@ -386,6 +405,7 @@ async fn test_store_and_load_snapshot_impl(iceberg_table_config: IcebergTableCon
         uuid: uuid::Uuid::new_v4(),
         flush_lsn: 1,
         wal_persistence_metadata: None,
+        initial_copy_position: InitialCopyPosition::default(),
         new_table_schema: None,
         committed_deletion_logs: test_committed_deletion_logs_to_persist_2(data_file_2.clone()),
         import_payload: IcebergSnapshotImportPayload {
@@ -466,6 +486,7 @@ async fn test_store_and_load_snapshot_impl(iceberg_table_config: IcebergTableCon
         wal_persistence_metadata: Some(WalPersistenceMetadata {
             persisted_file_num: 10,
         }),
+        initial_copy_position: InitialCopyPosition::default(),
         new_table_schema: None,
         committed_deletion_logs: HashSet::new(),
         import_payload: IcebergSnapshotImportPayload {
@@ -556,6 +577,7 @@ async fn test_store_and_load_snapshot_impl(iceberg_table_config: IcebergTableCon
         uuid: uuid::Uuid::new_v4(),
         flush_lsn: 3,
         wal_persistence_metadata: None,
+        initial_copy_position: InitialCopyPosition::default(),
         new_table_schema: None,
         committed_deletion_logs: HashSet::new(),
         import_payload: IcebergSnapshotImportPayload {
@@ -627,6 +649,7 @@ async fn test_store_and_load_snapshot_impl(iceberg_table_config: IcebergTableCon
         uuid: uuid::Uuid::new_v4(),
         flush_lsn: 4,
         wal_persistence_metadata: None,
+        initial_copy_position: InitialCopyPosition::default(),
         new_table_schema: None,
         committed_deletion_logs: HashSet::new(),
         import_payload: IcebergSnapshotImportPayload {
@@ -688,6 +711,37 @@ async fn test_sync_snapshots() {
     test_store_and_load_snapshot_impl(iceberg_table_config).await;
 }
 
+/// Iceberg snapshot sync and load test with REST catalog, which tracks table metadata at catalog server.
+#[tokio::test]
+async fn test_sync_snapshots_with_rest_catalog() {
+    let rest_catalog_server = RestCatalogTestServer::new().await;
+    let iceberg_temp_dir = tempdir().unwrap();
+    let iceberg_table_config = IcebergTableConfig {
+        catalog: rest_catalog_server.catalog_config(),
+        ..get_iceberg_table_config(&iceberg_temp_dir)
+    };
+
+    // Common testing logic.
+    test_store_and_load_snapshot_impl(iceberg_table_config).await;
+}
+
+/// Iceberg snapshot sync and load test with SQL catalog, which tracks metadata pointer in sqlite.
+#[tokio::test]
+#[cfg(feature = "catalog-sql")]
+async fn test_sync_snapshots_with_sql_catalog() {
+    let iceberg_temp_dir = tempdir().unwrap();
+    let iceberg_table_config = IcebergTableConfig {
+        catalog: IcebergCatalogConfig::Sql {
+            uri: get_test_sqlite_uri(&iceberg_temp_dir),
+            catalog_name: "moonlink".to_string(),
+        },
+        ..get_iceberg_table_config(&iceberg_temp_dir)
+    };
+
+    // Common testing logic.
+    test_store_and_load_snapshot_impl(iceberg_table_config).await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 #[cfg(feature = "storage-s3")]
 async fn test_sync_snapshot_with_s3() {
@@ -1008,6 +1062,7 @@ async fn test_empty_content_snapshot_creation_impl(iceberg_table_config: Iceberg
         uuid: uuid::Uuid::new_v4(),
         flush_lsn: 0,
         wal_persistence_metadata: None,
+        initial_copy_position: InitialCopyPosition::default(),
         new_table_schema: None,
         committed_deletion_logs: HashSet::new(),
         import_payload: IcebergSnapshotImportPayload::default(),
@@ -2064,7 +2119,11 @@ async fn test_schema_update_impl(iceberg_table_config: IcebergTableConfig) {
     // Local filesystem to store write-through cache.
     let table_temp_dir = tempdir().unwrap();
     let local_table_directory = table_temp_dir.path().to_str().unwrap().to_string();
-    let mooncake_table_metadata = create_test_table_metadata(local_table_directory.clone());
+    // Full row identity table can't drop columns once it has rows, so index rows by primary key.
+    let mooncake_table_metadata = create_test_table_metadata_with_identity(
+        local_table_directory.clone(),
+        IdentityProp::Keys(vec![0]),
+    );
 
     // Local filesystem to store read-through cache.
     let cache_temp_dir = tempdir().unwrap();
@@ -2233,3 +2292,578 @@ async fn test_persist_snapshot_property() {
         wal_persistence_metadata
     );
 }
+
+/// ================================
+/// Test manifest compaction
+/// ================================
+///
+/// Testing scenario: each iceberg persistence appends a new data file manifest, which are compacted when reaching the threshold.
+#[tokio::test]
+async fn test_manifest_compaction() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let filesystem_accessor = FileSystemAccessor::default_for_test(&temp_dir);
+    let object_storage_cache = ObjectStorageCache::default_for_test(&temp_dir);
+    let path = temp_dir.path().to_path_buf();
+    let warehouse_uri = path.clone().to_str().unwrap().to_string();
+    let mooncake_table_metadata =
+        create_test_table_metadata(temp_dir.path().to_str().unwrap().to_string());
+    let identity_property = mooncake_table_metadata.identity.clone();
+
+    let iceberg_table_config = create_iceberg_table_config(warehouse_uri.clone());
+    let schema = create_test_arrow_schema();
+    let mooncake_table_config = MooncakeTableConfig {
+        manifest_compaction_config: ManifestCompactionConfig {
+            manifest_files_to_compact: 3,
+        },
+        ..Default::default()
+    };
+    let mut table = MooncakeTable::new(
+        schema.as_ref().clone(),
+        "test_table".to_string(),
+        /*table_id=*/ 1,
+        path,
+        identity_property,
+        iceberg_table_config.clone(),
+        mooncake_table_config,
+        object_storage_cache.clone(),
+        create_test_filesystem_accessor(&iceberg_table_config),
+    )
+    .await
+    .unwrap();
+    let (notify_tx, mut notify_rx) = mpsc::channel(100);
+    table.register_table_notify(notify_tx).await;
+
+    // Persist one data file at each iceberg snapshot, which appends a new data file manifest; manifest file count is tracked across multiple compactions.
+    for id in 1..=7 {
+        let row = MoonlinkRow::new(vec![
+            RowValue::Int32(id),
+            RowValue::ByteArray("John".as_bytes().to_vec()),
+            RowValue::Int32(10 * id),
+        ]);
+        table.append(row).unwrap();
+        table.commit(/*lsn=*/ id as u64);
+        flush_table_and_sync(&mut table, &mut notify_rx, /*lsn=*/ id as u64)
+            .await
+            .unwrap();
+        create_mooncake_and_persist_for_test(&mut table, &mut notify_rx).await;
+    }
+
+    let mut iceberg_table_manager = IcebergTableManager::new(
+        mooncake_table_metadata.clone(),
+        object_storage_cache.clone(),
+        create_test_filesystem_accessor(&iceberg_table_config),
+        iceberg_table_config.clone(),
+    )
+    .unwrap();
+    let (_, snapshot) = iceberg_table_manager
+        .load_snapshot_from_table()
+        .await
+        .unwrap();
+    assert_eq!(snapshot.disk_files.len(), 7);
+    assert_eq!(snapshot.data_file_flush_lsn.unwrap(), 7);
+    validate_recovered_snapshot(&snapshot, &warehouse_uri, filesystem_accessor.as_ref()).await;
+
+    // Without compaction, there would be seven data file manifests and one file index manifest.
+    let iceberg_table = iceberg_table_manager.iceberg_table.as_ref().unwrap();
+    let manifest_list = iceberg_table
+        .metadata()
+        .current_snapshot()
+        .unwrap()
+        .load_manifest_list(iceberg_table.file_io(), iceberg_table.metadata())
+        .await
+        .unwrap();
+    assert!(manifest_list.entries().len() <= 3);
+}
+
+#[tokio::test]
+async fn test_position_delete_compatibility_mode() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_temp_dir = tempfile::tempdir().unwrap();
+    let filesystem_accessor = FileSystemAccessor::default_for_test(&temp_dir);
+    let warehouse_uri = temp_dir.path().to_str().unwrap().to_string();
+    let mooncake_table_metadata =
+        create_test_table_metadata(temp_dir.path().to_str().unwrap().to_string());
+
+    let iceberg_table_config = IcebergTableConfig {
+        delete_file_format: IcebergDeleteFileFormat::PositionDelete,
+        ..create_iceberg_table_config(warehouse_uri.clone())
+    };
+    let (mut table, mut notify_rx) = create_mooncake_table_and_notify(
+        mooncake_table_metadata.clone(),
+        iceberg_table_config.clone(),
+        ObjectStorageCache::default_for_test(&cache_temp_dir),
+    )
+    .await;
+
+    // Persist one data file with two rows.
+    let row1 = test_row_1();
+    let row2 = test_row_2();
+    table.append(row1.clone()).unwrap();
+    table.append(row2.clone()).unwrap();
+    table.commit(/*lsn=*/ 100);
+    flush_table_and_sync(&mut table, &mut notify_rx, /*lsn=*/ 100)
+        .await
+        .unwrap();
+    create_mooncake_and_persist_for_test(&mut table, &mut notify_rx).await;
+
+    // Delete the first row, which has been persisted.
+    table.delete(row1.clone(), /*lsn=*/ 100).await;
+    table.commit(/*lsn=*/ 200);
+    flush_table_and_sync(&mut table, &mut notify_rx, /*lsn=*/ 200)
+        .await
+        .unwrap();
+    create_mooncake_and_persist_for_test(&mut table, &mut notify_rx).await;
+
+    // Deletion record is loaded back from position delete file.
+    let mut iceberg_table_manager = IcebergTableManager::new(
+        mooncake_table_metadata.clone(),
+        ObjectStorageCache::default_for_test(&cache_temp_dir),
+        create_test_filesystem_accessor(&iceberg_table_config),
+        iceberg_table_config.clone(),
+    )
+    .unwrap();
+    let (_, snapshot) = iceberg_table_manager
+        .load_snapshot_from_table()
+        .await
+        .unwrap();
+    assert_eq!(snapshot.disk_files.len(), 1);
+    assert_eq!(snapshot.data_file_flush_lsn.unwrap(), 200);
+    let disk_file_entry = snapshot.disk_files.values().next().unwrap();
+    assert!(disk_file_entry.puffin_deletion_blob.is_none());
+    assert_eq!(
+        disk_file_entry.batch_deletion_vector.collect_deleted_rows(),
+        vec![0]
+    );
+    validate_recovered_snapshot(&snapshot, &warehouse_uri, filesystem_accessor.as_ref()).await;
+
+    // Iceberg table only contains position delete parquet files, no deletion vector puffin blobs.
+    let iceberg_table = iceberg_table_manager.iceberg_table.as_ref().unwrap();
+    assert_eq!(
+        iceberg_table
+            .metadata()
+            .properties()
+            .get(table_property::DELETE_DEFAULT_FILE_FORMAT)
+            .unwrap(),
+        table_property::DELETE_DEFAULT_FILE_FORMAT_PARQUET
+    );
+    let manifest_list = iceberg_table
+        .metadata()
+        .current_snapshot()
+        .unwrap()
+        .load_manifest_list(iceberg_table.file_io(), iceberg_table.metadata())
+        .await
+        .unwrap();
+    let mut delete_file_formats = vec![];
+    for manifest_file in manifest_list.entries() {
+        let manifest = manifest_file
+            .load_manifest(iceberg_table.file_io())
+            .await
+            .unwrap();
+        for entry in manifest.entries() {
+            if entry.data_file().content_type() == DataContentType::PositionDeletes {
+                delete_file_formats.push(entry.data_file().file_format());
+            }
+        }
+    }
+    assert_eq!(delete_file_formats, vec![DataFileFormat::Parquet]);
+}
+
+/// Testing scenario: iceberg tables are created as v2 tables for both delete file formats, and record the delete file format.
+#[tokio::test]
+async fn test_table_format_version() {
+    for delete_file_format in [
+        IcebergDeleteFileFormat::DeletionVector,
+        IcebergDeleteFileFormat::PositionDelete,
+    ] {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let warehouse_uri = temp_dir.path().to_str().unwrap().to_string();
+        let mooncake_table_metadata = create_test_table_metadata(warehouse_uri.clone());
+        let iceberg_table_config = IcebergTableConfig {
+            delete_file_format,
+            ..create_iceberg_table_config(warehouse_uri)
+        };
+        let mut iceberg_table_manager = IcebergTableManager::new(
+            mooncake_table_metadata,
+            ObjectStorageCache::default_for_test(&temp_dir),
+            create_test_filesystem_accessor(&iceberg_table_config),
+            iceberg_table_config,
+        )
+        .unwrap();
+        iceberg_table_manager
+            .initialize_iceberg_table_for_once()
+            .await
+            .unwrap();
+
+        let table_metadata = iceberg_table_manager
+            .iceberg_table
+            .as_ref()
+            .unwrap()
+            .metadata();
+        assert_eq!(table_metadata.format_version(), FormatVersion::V2);
+        // Format version is not stored as a table property.
+        assert!(!table_metadata
+            .properties()
+            .contains_key(table_property::FORMAT_VERSION));
+        assert_eq!(
+            table_metadata
+                .properties()
+                .get(table_property::MOONLINK_DELETE_FILE_FORMAT)
+                .unwrap(),
+            table_property::get_delete_file_format_name(delete_file_format)
+        );
+    }
+}
+
+/// Testing scenario: existing iceberg table can't be loaded with a different delete file format than it's written with.
+#[tokio::test]
+async fn test_reject_mismatched_delete_file_format() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let warehouse_uri = temp_dir.path().to_str().unwrap().to_string();
+    let mooncake_table_metadata = create_test_table_metadata(warehouse_uri.clone());
+    let create_iceberg_table_manager = |delete_file_format: IcebergDeleteFileFormat| {
+        let iceberg_table_config = IcebergTableConfig {
+            delete_file_format,
+            ..create_iceberg_table_config(warehouse_uri.clone())
+        };
+        IcebergTableManager::new(
+            mooncake_table_metadata.clone(),
+            ObjectStorageCache::default_for_test(&temp_dir),
+            create_test_filesystem_accessor(&iceberg_table_config),
+            iceberg_table_config,
+        )
+        .unwrap()
+    };
+
+    // Create the iceberg table with deletion vectors.
+    let mut iceberg_table_manager =
+        create_iceberg_table_manager(IcebergDeleteFileFormat::DeletionVector);
+    iceberg_table_manager
+        .initialize_iceberg_table_for_once()
+        .await
+        .unwrap();
+
+    // Load the existing table in position delete mode.
+    let mut iceberg_table_manager =
+        create_iceberg_table_manager(IcebergDeleteFileFormat::PositionDelete);
+    assert!(iceberg_table_manager
+        .initialize_iceberg_table_for_once()
+        .await
+        .is_err());
+    assert!(iceberg_table_manager.iceberg_table.is_none());
+    let mut iceberg_table_manager =
+        create_iceberg_table_manager(IcebergDeleteFileFormat::PositionDelete);
+    assert!(iceberg_table_manager
+        .load_snapshot_from_table()
+        .await
+        .is_err());
+
+    // Load the existing table with the same delete file format.
+    let mut iceberg_table_manager =
+        create_iceberg_table_manager(IcebergDeleteFileFormat::DeletionVector);
+    iceberg_table_manager
+        .initialize_iceberg_table_for_once()
+        .await
+        .unwrap();
+}
+
+/// Testing scenario: parquet writer config applies to flushed data files, and is recorded in iceberg table properties.
+#[tokio::test]
+async fn test_parquet_writer_config() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let object_storage_cache = ObjectStorageCache::default_for_test(&temp_dir);
+    let path = temp_dir.path().to_path_buf();
+    let warehouse_uri = path.clone().to_str().unwrap().to_string();
+    let mooncake_table_metadata =
+        create_test_table_metadata(temp_dir.path().to_str().unwrap().to_string());
+    let identity_property = mooncake_table_metadata.identity.clone();
+
+    let iceberg_table_config = create_iceberg_table_config(warehouse_uri.clone());
+    let schema = create_test_arrow_schema();
+    let mooncake_table_config = MooncakeTableConfig {
+        parquet_writer_config: ParquetWriterConfig {
+            compression: ParquetCompressionCodec::Zstd,
+            compression_level: Some(3),
+            data_page_size: 4096,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut table = MooncakeTable::new(
+        schema.as_ref().clone(),
+        "test_table".to_string(),
+        /*table_id=*/ 1,
+        path,
+        identity_property,
+        iceberg_table_config.clone(),
+        mooncake_table_config,
+        object_storage_cache.clone(),
+        create_test_filesystem_accessor(&iceberg_table_config),
+    )
+    .await
+    .unwrap();
+    let (notify_tx, mut notify_rx) = mpsc::channel(100);
+    table.register_table_notify(notify_tx).await;
+
+    table.append(test_row_1()).unwrap();
+    table.commit(/*lsn=*/ 1);
+    flush_table_and_sync(&mut table, &mut notify_rx, /*lsn=*/ 1)
+        .await
+        .unwrap();
+    create_mooncake_and_persist_for_test(&mut table, &mut notify_rx).await;
+
+    let mut iceberg_table_manager = IcebergTableManager::new(
+        mooncake_table_metadata.clone(),
+        object_storage_cache.clone(),
+        create_test_filesystem_accessor(&iceberg_table_config),
+        iceberg_table_config.clone(),
+    )
+    .unwrap();
+    let (_, snapshot) = iceberg_table_manager
+        .load_snapshot_from_table()
+        .await
+        .unwrap();
+    assert_eq!(snapshot.disk_files.len(), 1);
+
+    // Check table properties.
+    let iceberg_table = iceberg_table_manager.iceberg_table.as_ref().unwrap();
+    let properties = iceberg_table.metadata().properties();
+    assert_eq!(
+        properties.get(table_property::PARQUET_COMPRESSION).unwrap(),
+        "zstd"
+    );
+    assert_eq!(
+        properties
+            .get(table_property::PARQUET_COMPRESSION_LEVEL)
+            .unwrap(),
+        "3"
+    );
+    assert_eq!(
+        properties
+            .get(table_property::PARQUET_PAGE_SIZE_BYTES)
+            .unwrap(),
+        "4096"
+    );
+
+    // Check compression of the persisted data file, which is placed at local filesystem.
+    let data_file = snapshot.disk_files.keys().next().unwrap();
+    let file = std::fs::File::open(data_file.file_path()).unwrap();
+    let reader = SerializedFileReader::new(file).unwrap();
+    let parquet_metadata = reader.metadata();
+    for row_group in parquet_metadata.row_groups() {
+        for column in row_group.columns() {
+            assert!(matches!(column.compression(), Compression::ZSTD(_)));
+        }
+    }
+}
+
+/// Testing scenario: linear sort order in parquet writer config is recorded as iceberg table sort order, and updated when config changes.
+#[tokio::test]
+async fn test_parquet_sort_order() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let object_storage_cache = ObjectStorageCache::default_for_test(&temp_dir);
+    let path = temp_dir.path().to_path_buf();
+    let warehouse_uri = path.clone().to_str().unwrap().to_string();
+    let unsorted_mooncake_table_metadata =
+        create_test_table_metadata(temp_dir.path().to_str().unwrap().to_string());
+    let identity_property = unsorted_mooncake_table_metadata.identity.clone();
+
+    let iceberg_table_config = create_iceberg_table_config(warehouse_uri.clone());
+    let schema = create_test_arrow_schema();
+    let mooncake_table_config = MooncakeTableConfig {
+        parquet_writer_config: ParquetWriterConfig {
+            sort_order: vec![
+                ParquetSortField {
+                    column: "age".to_string(),
+                    descending: true,
+                    nulls_first: false,
+                },
+                ParquetSortField {
+                    column: "id".to_string(),
+                    descending: false,
+                    nulls_first: true,
+                },
+            ],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mooncake_table_metadata = create_test_table_metadata_with_config(
+        temp_dir.path().to_str().unwrap().to_string(),
+        mooncake_table_config.clone(),
+    );
+    let mut table = MooncakeTable::new(
+        schema.as_ref().clone(),
+        "test_table".to_string(),
+        /*table_id=*/ 1,
+        path,
+        identity_property,
+        iceberg_table_config.clone(),
+        mooncake_table_config,
+        object_storage_cache.clone(),
+        create_test_filesystem_accessor(&iceberg_table_config),
+    )
+    .await
+    .unwrap();
+    let (notify_tx, mut notify_rx) = mpsc::channel(100);
+    table.register_table_notify(notify_tx).await;
+
+    table.append(test_row_1()).unwrap();
+    table.commit(/*lsn=*/ 1);
+    flush_table_and_sync(&mut table, &mut notify_rx, /*lsn=*/ 1)
+        .await
+        .unwrap();
+    create_mooncake_and_persist_for_test(&mut table, &mut notify_rx).await;
+
+    let mut iceberg_table_manager = IcebergTableManager::new(
+        mooncake_table_metadata.clone(),
+        object_storage_cache.clone(),
+        create_test_filesystem_accessor(&iceberg_table_config),
+        iceberg_table_config.clone(),
+    )
+    .unwrap();
+    iceberg_table_manager
+        .load_snapshot_from_table()
+        .await
+        .unwrap();
+
+    // Check iceberg sort order.
+    let iceberg_table = iceberg_table_manager.iceberg_table.as_ref().unwrap();
+    let table_metadata = iceberg_table.metadata();
+    let iceberg_schema = table_metadata.current_schema();
+    let sort_order = table_metadata.default_sort_order();
+    assert!(!sort_order.is_unsorted());
+    assert_eq!(sort_order.fields.len(), 2);
+    let age_field = &sort_order.fields[0];
+    assert_eq!(
+        age_field.source_id,
+        iceberg_schema.field_by_name("age").unwrap().id
+    );
+    assert_eq!(age_field.transform, Transform::Identity);
+    assert_eq!(age_field.direction, SortDirection::Descending);
+    assert_eq!(age_field.null_order, NullOrder::Last);
+    let id_field = &sort_order.fields[1];
+    assert_eq!(
+        id_field.source_id,
+        iceberg_schema.field_by_name("id").unwrap().id
+    );
+    assert_eq!(id_field.direction, SortDirection::Ascending);
+    assert_eq!(id_field.null_order, NullOrder::First);
+
+    // Load the existing iceberg table with sort order removed from config, and check iceberg sort order gets updated.
+    let mut iceberg_table_manager = IcebergTableManager::new(
+        unsorted_mooncake_table_metadata.clone(),
+        object_storage_cache.clone(),
+        create_test_filesystem_accessor(&iceberg_table_config),
+        iceberg_table_config.clone(),
+    )
+    .unwrap();
+    iceberg_table_manager
+        .load_snapshot_from_table()
+        .await
+        .unwrap();
+    let iceberg_table = iceberg_table_manager.iceberg_table.as_ref().unwrap();
+    assert!(iceberg_table.metadata().default_sort_order().is_unsorted());
+
+    // Updated sort order is persisted in the catalog.
+    let iceberg_table_manager = IcebergTableManager::new(
+        unsorted_mooncake_table_metadata,
+        object_storage_cache.clone(),
+        create_test_filesystem_accessor(&iceberg_table_config),
+        iceberg_table_config.clone(),
+    )
+    .unwrap();
+    let table = iceberg_table_manager
+        .catalog
+        .load_table(&iceberg_table_manager.get_table_ident())
+        .await
+        .unwrap();
+    assert!(table.metadata().default_sort_order().is_unsorted());
+}
+
+/// Testing scenario: moonlink state is recorded in the summary of each iceberg snapshot, instead of table properties.
+#[tokio::test]
+async fn test_snapshot_summary_properties() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let object_storage_cache = ObjectStorageCache::default_for_test(&temp_dir);
+    let path = temp_dir.path().to_path_buf();
+    let warehouse_uri = path.clone().to_str().unwrap().to_string();
+    let mooncake_table_metadata =
+        create_test_table_metadata(temp_dir.path().to_str().unwrap().to_string());
+    let identity_property = mooncake_table_metadata.identity.clone();
+
+    let iceberg_table_config = create_iceberg_table_config(warehouse_uri.clone());
+    let schema = create_test_arrow_schema();
+    let mut table = MooncakeTable::new(
+        schema.as_ref().clone(),
+        "test_table".to_string(),
+        /*table_id=*/ 1,
+        path,
+        identity_property,
+        iceberg_table_config.clone(),
+        MooncakeTableConfig::default(),
+        object_storage_cache.clone(),
+        create_test_filesystem_accessor(&iceberg_table_config),
+    )
+    .await
+    .unwrap();
+    let (notify_tx, mut notify_rx) = mpsc::channel(100);
+    table.register_table_notify(notify_tx).await;
+
+    // Persist two iceberg snapshots at different LSNs.
+    table.append(test_row_1()).unwrap();
+    table.commit(/*lsn=*/ 1);
+    flush_table_and_sync(&mut table, &mut notify_rx, /*lsn=*/ 1)
+        .await
+        .unwrap();
+    create_mooncake_and_persist_for_test(&mut table, &mut notify_rx).await;
+    table.append(test_row_2()).unwrap();
+    table.commit(/*lsn=*/ 2);
+    flush_table_and_sync(&mut table, &mut notify_rx, /*lsn=*/ 2)
+        .await
+        .unwrap();
+    create_mooncake_and_persist_for_test(&mut table, &mut notify_rx).await;
+
+    let mut iceberg_table_manager = IcebergTableManager::new(
+        mooncake_table_metadata.clone(),
+        object_storage_cache.clone(),
+        create_test_filesystem_accessor(&iceberg_table_config),
+        iceberg_table_config.clone(),
+    )
+    .unwrap();
+    let (_, snapshot) = iceberg_table_manager
+        .load_snapshot_from_table()
+        .await
+        .unwrap();
+    assert_eq!(snapshot.data_file_flush_lsn, Some(2));
+
+    // Each snapshot carries its own flush LSN, and none is kept in table properties.
+    let table_metadata = iceberg_table_manager
+        .iceberg_table
+        .as_ref()
+        .unwrap()
+        .metadata();
+    let mut flush_lsns = table_metadata
+        .snapshots()
+        .map(|snapshot| {
+            let summary = &snapshot.summary().additional_properties;
+            assert_eq!(
+                summary
+                    .get(snapshot_utils::MOONLINK_FORMAT_VERSION)
+                    .unwrap(),
+                &snapshot_utils::CURRENT_MOONLINK_FORMAT_VERSION.to_string()
+            );
+            summary
+                .get(snapshot_utils::MOONCAKE_TABLE_FLUSH_LSN)
+                .unwrap()
+                .parse::<u64>()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    flush_lsns.sort();
+    flush_lsns.dedup();
+    assert!(flush_lsns.contains(&1));
+    assert_eq!(*flush_lsns.last().unwrap(), 2);
+    assert!(!table_metadata
+        .properties()
+        .contains_key(snapshot_utils::MOONCAKE_TABLE_FLUSH_LSN));
+}
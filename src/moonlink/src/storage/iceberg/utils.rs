@@ -1,13 +1,22 @@
+use crate::storage::iceberg::iceberg_table_config::IcebergDeleteFileFormat;
 use crate::storage::iceberg::moonlink_catalog::MoonlinkCatalog;
 use crate::storage::iceberg::table_property;
+use crate::storage::mooncake_table::partition_spec::PartitionSpec;
+use crate::storage::parquet_writer_config::{ParquetSortStrategy, ParquetWriterConfig};
 
 use std::collections::HashMap;
 
 use arrow_schema::Schema as ArrowSchema;
 use iceberg::arrow as IcebergArrow;
-use iceberg::spec::{DataContentType, DataFileFormat, ManifestEntry};
+use iceberg::spec::{
+    DataContentType, DataFileFormat, ManifestEntry, NullOrder, Schema as IcebergSchema,
+    SortDirection, SortField, SortOrder, Transform, UnboundPartitionSpec,
+};
 use iceberg::table::Table as IcebergTable;
-use iceberg::{NamespaceIdent, Result as IcebergResult, TableCreation, TableIdent};
+use iceberg::{
+    Error as IcebergError, ErrorKind, NamespaceIdent, Result as IcebergResult, TableCreation,
+    TableIdent,
+};
 
 /// Return whether the given manifest entry represents data files.
 pub fn is_data_file_entry(entry: &ManifestEntry) -> bool {
@@ -26,17 +35,25 @@ pub fn is_data_file_entry(entry: &ManifestEntry) -> bool {
 /// Return whether the given manifest entry represents deletion vector.
 pub fn is_deletion_vector_entry(entry: &ManifestEntry) -> bool {
     let f = entry.data_file();
-    let is_deletion_vector = f.content_type() == DataContentType::PositionDeletes;
+    let is_deletion_vector = f.content_type() == DataContentType::PositionDeletes
+        && f.file_format() == DataFileFormat::Puffin;
     if !is_deletion_vector {
         return false;
     }
-    assert_eq!(f.file_format(), DataFileFormat::Puffin);
     assert!(f.referenced_data_file().is_some());
     assert!(f.content_offset().is_some());
     assert!(f.content_size_in_bytes().is_some());
     true
 }
 
+/// Return whether the given manifest entry represents position delete file.
+/// Position delete files written by other writers could contain deletes for multiple data files, so referenced data file is not required.
+pub fn is_position_delete_entry(entry: &ManifestEntry) -> bool {
+    let f = entry.data_file();
+    f.content_type() == DataContentType::PositionDeletes
+        && f.file_format() == DataFileFormat::Parquet
+}
+
 /// Return whether the given manifest entry represents file index.
 pub fn is_file_index(entry: &ManifestEntry) -> bool {
     let f = entry.data_file();
@@ -51,14 +68,58 @@ pub fn is_file_index(entry: &ManifestEntry) -> bool {
     true
 }
 
+/// Get iceberg sort order for data files written with the given parquet writer config.
+/// Rows clustered along a curve are not sorted by any column, so only linear sort order is recorded.
+pub(crate) fn get_iceberg_sort_order(
+    parquet_writer_config: &ParquetWriterConfig,
+    schema: &IcebergSchema,
+) -> IcebergResult<SortOrder> {
+    if parquet_writer_config.sort_order.is_empty()
+        || parquet_writer_config.sort_strategy != ParquetSortStrategy::Linear
+    {
+        return Ok(SortOrder::unsorted_order());
+    }
+    let mut builder = SortOrder::builder();
+    builder.with_order_id(1);
+    for sort_field in parquet_writer_config.sort_order.iter() {
+        let source_field = schema.field_by_name(&sort_field.column).ok_or_else(|| {
+            IcebergError::new(
+                ErrorKind::DataInvalid,
+                format!("sort column {} not found", sort_field.column),
+            )
+        })?;
+        builder.with_sort_field(
+            SortField::builder()
+                .source_id(source_field.id)
+                .transform(Transform::Identity)
+                .direction(if sort_field.descending {
+                    SortDirection::Descending
+                } else {
+                    SortDirection::Ascending
+                })
+                .null_order(if sort_field.nulls_first {
+                    NullOrder::First
+                } else {
+                    NullOrder::Last
+                })
+                .build(),
+        );
+    }
+    builder.build(schema)
+}
+
 /// Create an iceberg table in the given catalog from the given namespace and table name.
 /// Precondition: table doesn't exist in the given catalog.
+#[allow(clippy::too_many_arguments)]
 async fn create_iceberg_table<C: MoonlinkCatalog + ?Sized>(
     catalog: &C,
     warehouse_uri: &str,
     table_name: &str,
     namespace_ident: NamespaceIdent,
     arrow_schema: &ArrowSchema,
+    partition_spec: Option<&PartitionSpec>,
+    delete_file_format: IcebergDeleteFileFormat,
+    parquet_writer_config: &ParquetWriterConfig,
 ) -> IcebergResult<IcebergTable> {
     let namespace_already_exists = catalog.namespace_exists(&namespace_ident).await?;
     if !namespace_already_exists {
@@ -68,6 +129,11 @@ async fn create_iceberg_table<C: MoonlinkCatalog + ?Sized>(
     }
 
     let iceberg_schema = IcebergArrow::arrow_schema_to_schema(arrow_schema)?;
+    let iceberg_partition_spec = match partition_spec {
+        Some(partition_spec) => partition_spec.to_iceberg_partition_spec(&iceberg_schema)?,
+        None => UnboundPartitionSpec::builder().build(),
+    };
+    let iceberg_sort_order = get_iceberg_sort_order(parquet_writer_config, &iceberg_schema)?;
     let tbl_creation = TableCreation::builder()
         .name(table_name.to_string())
         .location(format!(
@@ -77,24 +143,35 @@ async fn create_iceberg_table<C: MoonlinkCatalog + ?Sized>(
             table_name
         ))
         .schema(iceberg_schema)
-        .properties(table_property::create_iceberg_table_properties())
+        .partition_spec(iceberg_partition_spec)
+        .sort_order(iceberg_sort_order)
+        .properties(table_property::create_iceberg_table_properties(
+            delete_file_format,
+            parquet_writer_config,
+        ))
         .build();
     let table = catalog.create_table(&namespace_ident, tbl_creation).await?;
     Ok(table)
 }
 
 /// Get or create an iceberg table in the given catalog from the given namespace and table name.
+/// The partition spec only applies to newly created tables, existing tables keep their own one; callers validate it
+/// matches the given one.
 ///
 /// There're several options:
 /// - If the table doesn't exist, create a new one
 /// - If the table already exists, and [drop_if_exists] true (overwrite use case), delete the table and re-create
 /// - If already exists and not requested to drop (recovery use case), do nothing and return the table directly
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn get_or_create_iceberg_table<C: MoonlinkCatalog + ?Sized>(
     catalog: &C,
     warehouse_uri: &str,
     namespace: &Vec<String>,
     table_name: &str,
     arrow_schema: &ArrowSchema,
+    partition_spec: Option<&PartitionSpec>,
+    delete_file_format: IcebergDeleteFileFormat,
+    parquet_writer_config: &ParquetWriterConfig,
 ) -> IcebergResult<IcebergTable> {
     let namespace_ident = NamespaceIdent::from_strs(namespace).unwrap();
     let table_ident = TableIdent::new(namespace_ident.clone(), table_name.to_string());
@@ -112,6 +189,9 @@ pub(crate) async fn get_or_create_iceberg_table<C: MoonlinkCatalog + ?Sized>(
             table_name,
             namespace_ident,
             arrow_schema,
+            partition_spec,
+            delete_file_format,
+            parquet_writer_config,
         )
         .await
     } else {
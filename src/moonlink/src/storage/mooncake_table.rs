@@ -2,8 +2,11 @@ mod batch_id_counter;
 mod data_batches;
 pub(crate) mod delete_vector;
 mod disk_slice;
+pub mod initial_copy_position;
 mod mem_slice;
+pub mod partition_spec;
 mod persistence_buffer;
+pub(crate) mod schema_evolution;
 mod shared_array;
 mod snapshot;
 mod snapshot_cache_utils;
@@ -23,7 +26,7 @@ use super::iceberg::puffin_utils::PuffinBlobRef;
 use super::index::index_merge_config::FileIndexMergeConfig;
 use super::index::{FileIndex, MemIndex, MooncakeIndex};
 use super::storage_utils::{MooncakeDataFileRef, RawDeletionRecord, RecordLocation};
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::row::{IdentityProp, MoonlinkRow};
 use crate::storage::cache::object_storage::object_storage_cache::ObjectStorageCache;
 use crate::storage::compaction::compaction_config::DataCompactionConfig;
@@ -32,11 +35,16 @@ pub(crate) use crate::storage::compaction::table_compaction::{
     DataCompactionPayload, DataCompactionResult,
 };
 use crate::storage::filesystem::accessor::base_filesystem_accessor::BaseFileSystemAccess;
-use crate::storage::iceberg::iceberg_table_config::IcebergTableConfig;
+use crate::storage::iceberg::iceberg_table_config::{IcebergDeleteFileFormat, IcebergTableConfig};
 use crate::storage::iceberg::iceberg_table_manager::IcebergTableManager;
-use crate::storage::iceberg::table_manager::{PersistenceFileParams, TableManager};
+use crate::storage::iceberg::manifest_compaction_config::ManifestCompactionConfig;
+use crate::storage::iceberg::snapshot_retention_config::SnapshotRetentionConfig;
+use crate::storage::iceberg::table_manager::{
+    ExpireSnapshotsResult, PersistenceFileParams, TableManager,
+};
 use crate::storage::index::persisted_bucket_hash_map::GlobalIndexBuilder;
 use crate::storage::mooncake_table::batch_id_counter::BatchIdCounter;
+pub use crate::storage::mooncake_table::initial_copy_position::InitialCopyPosition;
 use crate::storage::mooncake_table::shared_array::SharedRowBufferSnapshot;
 pub use crate::storage::mooncake_table::snapshot_read_output::ReadOutput as SnapshotReadOutput;
 #[cfg(test)]
@@ -46,7 +54,10 @@ pub(crate) use crate::storage::mooncake_table::table_snapshot::{
     take_file_indices_to_remove, FileIndiceMergePayload, FileIndiceMergeResult,
     IcebergSnapshotDataCompactionResult, IcebergSnapshotImportPayload,
     IcebergSnapshotIndexMergePayload, IcebergSnapshotPayload, IcebergSnapshotResult,
+    SnapshotExpirationResult,
 };
+use crate::storage::parquet_utils;
+use crate::storage::parquet_writer_config::ParquetWriterConfig;
 use crate::storage::storage_utils::{FileId, TableId};
 use crate::storage::wal::wal_persistence_metadata::WalPersistenceMetadata;
 use crate::table_notify::TableEvent;
@@ -56,6 +67,7 @@ use arrow_schema::Schema;
 use delete_vector::BatchDeletionVector;
 pub(crate) use disk_slice::DiskSliceWriter;
 use mem_slice::MemSlice;
+use partition_spec::PartitionSpec;
 pub(crate) use snapshot::{PuffinDeletionBlobAtRead, SnapshotTableState};
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
@@ -116,8 +128,16 @@ pub struct MooncakeTableConfig {
     pub data_compaction_config: DataCompactionConfig,
     /// Config for index merge.
     pub file_index_config: FileIndexMergeConfig,
+    /// Config for iceberg snapshot expiration.
+    pub snapshot_retention_config: SnapshotRetentionConfig,
+    /// Config for iceberg manifest compaction.
+    pub manifest_compaction_config: ManifestCompactionConfig,
     /// Filesystem directory to store temporary files, used for union read.
     pub temp_files_directory: String,
+    /// Partition spec for data files, unpartitioned if unassigned.
+    pub partition_spec: Option<PartitionSpec>,
+    /// Config for parquet data files.
+    pub parquet_writer_config: ParquetWriterConfig,
 }
 
 impl Default for MooncakeTableConfig {
@@ -157,7 +177,11 @@ impl MooncakeTableConfig {
             persistence_config: IcebergPersistenceConfig::default(),
             data_compaction_config: DataCompactionConfig::default(),
             file_index_config: FileIndexMergeConfig::default(),
+            snapshot_retention_config: SnapshotRetentionConfig::default(),
+            manifest_compaction_config: ManifestCompactionConfig::default(),
             temp_files_directory,
+            partition_spec: None,
+            parquet_writer_config: ParquetWriterConfig::default(),
         }
     }
     pub fn batch_size(&self) -> usize {
@@ -189,34 +213,64 @@ pub struct TableMetadata {
     /// function to get lookup key from row
     pub(crate) identity: IdentityProp,
 }
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct AlterTableRequest {
-    pub(crate) new_columns: Vec<arrow_schema::FieldRef>,
-    pub(crate) dropped_columns: Vec<String>,
+    /// Columns to add, which are appended to the end of the schema with new field ids.
+    pub new_columns: Vec<arrow_schema::FieldRef>,
+    /// Names of the columns to drop.
+    pub dropped_columns: Vec<String>,
+    /// Columns to rename, as (old name, new name) pairs; field ids are kept.
+    pub renamed_columns: Vec<(String, String)>,
+    /// Columns whose type changes in place, identified by name after rename.
+    /// Only type promotions are supported, which keep the field id; other type changes are rejected, since existing values can't be converted.
+    pub altered_columns: Vec<arrow_schema::FieldRef>,
+}
+
+impl AlterTableRequest {
+    pub fn is_empty(&self) -> bool {
+        self.new_columns.is_empty()
+            && self.dropped_columns.is_empty()
+            && self.renamed_columns.is_empty()
+            && self.altered_columns.is_empty()
+    }
+
+    /// Return whether the schema change adds or drops columns of a table with full row identity, whose rows are indexed by all of their column values.
+    /// Existing rows, either persisted or buffered, could no longer be located by deletes in that case, since lookup keys are hashed from all columns.
+    pub fn changes_full_row_identity(&self, identity: &IdentityProp) -> bool {
+        *identity == IdentityProp::FullRow
+            && (!self.new_columns.is_empty() || !self.dropped_columns.is_empty())
+    }
+
+    /// Validate the schema change could be applied to a table with the given schema and identity.
+    pub fn validate(&self, schema: &Schema, identity: &IdentityProp) -> Result<()> {
+        let mut last_field_id = schema_evolution::get_highest_field_id(schema.fields.iter());
+        schema_evolution::evolve_schema(schema, identity, self, &mut last_field_id)?;
+        Ok(())
+    }
 }
 
 impl TableMetadata {
+    /// Create table metadata after schema change, new field ids are assigned after `last_field_id`.
+    /// Return error if the schema change can't be applied without losing data, which requires a resync; `last_field_id` is left unchanged in that case.
     pub fn new_for_alter_table(
         previous_metadata: Arc<TableMetadata>,
         alter_table_request: AlterTableRequest,
-    ) -> Self {
-        let mut new_columns = vec![];
-        for field in previous_metadata.schema.fields.iter() {
-            if !alter_table_request.dropped_columns.contains(field.name()) {
-                new_columns.push(field.clone());
-            }
-        }
-        new_columns.extend(alter_table_request.new_columns);
-        let new_schema =
-            Schema::new_with_metadata(new_columns, previous_metadata.schema.metadata.clone());
-        Self {
+        last_field_id: &mut i32,
+    ) -> Result<Self> {
+        let (new_schema, identity) = schema_evolution::evolve_schema(
+            &previous_metadata.schema,
+            &previous_metadata.identity,
+            &alter_table_request,
+            last_field_id,
+        )?;
+        Ok(Self {
             name: previous_metadata.name.clone(),
             table_id: previous_metadata.table_id,
             schema: Arc::new(new_schema),
             config: previous_metadata.config.clone(),
             path: previous_metadata.path.clone(),
-            identity: previous_metadata.identity.clone(),
-        }
+            identity,
+        })
     }
 }
 #[derive(Clone, Debug)]
@@ -258,6 +312,8 @@ pub struct Snapshot {
     pub(crate) data_file_flush_lsn: Option<u64>,
     /// WAL persistence metadata.
     pub(crate) wal_persistence_metadata: Option<WalPersistenceMetadata>,
+    /// Initial copy position.
+    pub(crate) initial_copy_position: InitialCopyPosition,
     /// indices
     pub(crate) indices: MooncakeIndex,
 }
@@ -270,6 +326,7 @@ impl Snapshot {
             snapshot_version: 0,
             data_file_flush_lsn: None,
             wal_persistence_metadata: None,
+            initial_copy_position: InitialCopyPosition::default(),
             indices: MooncakeIndex::new(),
         }
     }
@@ -375,6 +432,9 @@ pub struct SnapshotTask {
     /// --- States related to WAL operation ---
     new_wal_persistence_metadata: Option<WalPersistenceMetadata>,
 
+    /// --- States related to initial copy ---
+    new_initial_copy_position: Option<InitialCopyPosition>,
+
     /// --- States related to read operation ---
     read_cache_handles: Vec<NonEvictableHandle>,
 
@@ -410,6 +470,8 @@ impl SnapshotTask {
             committed_deletion_logs: HashSet::new(),
             // WAL related fields.
             new_wal_persistence_metadata: None,
+            // Initial copy related fields.
+            new_initial_copy_position: None,
             // Read request related fields.
             read_cache_handles: Vec::new(),
             // Index merge related fields.
@@ -437,6 +499,9 @@ impl SnapshotTask {
         if !self.new_streaming_xact.is_empty() {
             return false;
         }
+        if self.new_initial_copy_position.is_some() {
+            return false;
+        }
         if !self.read_cache_handles.is_empty() {
             return false;
         }
@@ -456,6 +521,8 @@ impl SnapshotTask {
         // If mooncake has new transaction commits.
         self.new_commit_lsn > 0
             || self.force_empty_iceberg_payload
+        // If initial copy commits copied rows, which are committed at LSN 0.
+            || self.new_initial_copy_position.is_some()
         // If mooncake table accumulated large enough writes.
             || !self.new_disk_slices.is_empty()
             || self.new_deletions.len()
@@ -598,6 +665,10 @@ pub struct MooncakeTable {
     /// Stream state per transaction, keyed by xact-id.
     transaction_stream_states: HashMap<u32, TransactionStreamState>,
 
+    /// Prepared two-phase transactions, maps from GID to the xact-id of its transaction stream.
+    /// Source confirms its replication position past PREPARE, so they're recovered from the table handler's WAL after restart.
+    prepared_transactions: HashMap<String, u32>,
+
     /// Auto increment id for generating unique file ids.
     /// Note, these ids is only used locally, and not persisted.
     next_file_id: u32,
@@ -615,8 +686,17 @@ pub struct MooncakeTable {
     /// Metadata for latest WAL persistence.
     last_wal_persisted_metadata: Option<WalPersistenceMetadata>,
 
+    /// Initial copy position loaded from iceberg, unassigned if there's no iceberg snapshot.
+    persisted_initial_copy_position: Option<InitialCopyPosition>,
+
     /// Table notifier, which is used to sent multiple types of event completion information.
     table_notify: Option<Sender<TableEvent>>,
+
+    /// Highest field id ever assigned, field ids of dropped columns are not reused.
+    last_field_id: i32,
+
+    /// Whether any row has been loaded into or appended to the table, including rows later deleted or aborted.
+    has_rows: bool,
 }
 
 impl MooncakeTable {
@@ -635,6 +715,10 @@ impl MooncakeTable {
         object_storage_cache: ObjectStorageCache,
         filesystem_accessor: Arc<dyn BaseFileSystemAccess>,
     ) -> Result<Self> {
+        parquet_utils::validate_parquet_writer_config(
+            &table_config.parquet_writer_config,
+            &schema,
+        )?;
         let metadata = Arc::new(TableMetadata {
             name,
             table_id,
@@ -643,6 +727,7 @@ impl MooncakeTable {
             path: base_path,
             identity,
         });
+        let delete_file_format = iceberg_table_config.delete_file_format;
         let iceberg_table_manager = Box::new(IcebergTableManager::new(
             metadata.clone(),
             object_storage_cache.clone(),
@@ -652,6 +737,7 @@ impl MooncakeTable {
         Self::new_with_table_manager(
             metadata,
             iceberg_table_manager,
+            delete_file_format,
             object_storage_cache,
             filesystem_accessor,
         )
@@ -661,13 +747,17 @@ impl MooncakeTable {
     pub(crate) async fn new_with_table_manager(
         table_metadata: Arc<TableMetadata>,
         mut table_manager: Box<dyn TableManager>,
+        delete_file_format: IcebergDeleteFileFormat,
         object_storage_cache: ObjectStorageCache,
         filesystem_accessor: Arc<dyn BaseFileSystemAccess>,
     ) -> Result<Self> {
         let (table_snapshot_watch_sender, table_snapshot_watch_receiver) = watch::channel(u64::MAX);
         let (next_file_id, current_snapshot) = table_manager.load_snapshot_from_table().await?;
+        let has_rows = !current_snapshot.disk_files.is_empty();
         let last_iceberg_snapshot_lsn = current_snapshot.data_file_flush_lsn;
         let last_wal_persisted_metadata = current_snapshot.wal_persistence_metadata.clone();
+        let persisted_initial_copy_position =
+            last_iceberg_snapshot_lsn.map(|_| current_snapshot.initial_copy_position.clone());
         if let Some(persistence_lsn) = last_iceberg_snapshot_lsn {
             table_snapshot_watch_sender.send(persistence_lsn).unwrap();
         }
@@ -686,6 +776,7 @@ impl MooncakeTable {
             snapshot: Arc::new(RwLock::new(
                 SnapshotTableState::new(
                     table_metadata.clone(),
+                    delete_file_format,
                     object_storage_cache,
                     filesystem_accessor,
                     current_snapshot,
@@ -696,6 +787,7 @@ impl MooncakeTable {
             mooncake_snapshot_ongoing: false,
             next_snapshot_task: SnapshotTask::new(table_metadata.as_ref().config.clone()),
             transaction_stream_states: HashMap::new(),
+            prepared_transactions: HashMap::new(),
             table_snapshot_watch_sender,
             table_snapshot_watch_receiver,
             next_file_id,
@@ -704,14 +796,37 @@ impl MooncakeTable {
             iceberg_table_manager: Some(table_manager),
             last_iceberg_snapshot_lsn,
             last_wal_persisted_metadata,
+            persisted_initial_copy_position,
             table_notify: None,
+            last_field_id: schema_evolution::get_highest_field_id(table_metadata.schema.fields()),
+            has_rows,
         })
     }
 
+    /// Validate the schema change could be applied to the current table schema.
+    pub(crate) fn validate_alter_table(
+        &self,
+        alter_table_request: &AlterTableRequest,
+    ) -> Result<()> {
+        alter_table_request.validate(&self.metadata.schema, &self.metadata.identity)?;
+        self.validate_alter_table_for_rows(alter_table_request)
+    }
+
+    /// Validate the schema change could be applied to existing rows, otherwise the table has to be resynced.
+    fn validate_alter_table_for_rows(&self, alter_table_request: &AlterTableRequest) -> Result<()> {
+        if alter_table_request.changes_full_row_identity(&self.metadata.identity) && self.has_rows {
+            return Err(Error::UnsupportedSchemaChange(
+                "columns cannot be added or dropped for full row identity table with existing rows"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     pub(crate) fn alter_table(
         &mut self,
         alter_table_request: AlterTableRequest,
-    ) -> Arc<TableMetadata> {
+    ) -> Result<Arc<TableMetadata>> {
         assert!(
             self.mem_slice.is_empty(),
             "Cannot alter table with non-empty mem slice"
@@ -721,18 +836,37 @@ impl MooncakeTable {
             "Cannot alter table with pending snapshot task"
         );
 
-        // Create new table metadata.
+        // Validate and create new table metadata, before any table state gets changed.
+        self.validate_alter_table_for_rows(&alter_table_request)?;
+        let mut last_field_id = self.last_field_id;
         let new_metadata = Arc::new(TableMetadata::new_for_alter_table(
             self.metadata.clone(),
             alter_table_request,
-        ));
+            &mut last_field_id,
+        )?);
+
+        // Ongoing streaming transactions have rows appended with the old schema, rebuild their mem slices with the new one.
+        // Finalized batches are casted for all transactions first, so failure leaves the table unchanged.
+        let adapted_stream_batches = self
+            .transaction_stream_states
+            .iter()
+            .map(|(xact_id, stream_state)| {
+                Ok((
+                    *xact_id,
+                    stream_state.adapt_batches(new_metadata.schema.clone())?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for (xact_id, adapted_batches) in adapted_stream_batches.into_iter() {
+            self.transaction_stream_states
+                .get_mut(&xact_id)
+                .unwrap()
+                .alter_schema(new_metadata.schema.clone(), adapted_batches);
+        }
+        self.last_field_id = last_field_id;
 
         let mut guard = self.snapshot.try_write().unwrap();
         guard.reset_for_alter(new_metadata.clone());
-        assert!(
-            self.metadata.schema.fields.len() != new_metadata.schema.fields.len(),
-            "Only support alter table with add/drop fields"
-        );
         self.mem_slice = MemSlice::new(
             new_metadata.schema.clone(),
             new_metadata.config.batch_size,
@@ -740,7 +874,7 @@ impl MooncakeTable {
             Arc::clone(&self.non_streaming_batch_id_counter),
         );
         self.metadata = new_metadata.clone();
-        new_metadata
+        Ok(new_metadata)
     }
 
     /// Register event completion notifier.
@@ -866,6 +1000,27 @@ impl MooncakeTable {
         self.next_snapshot_task.data_compaction_result = data_compaction_res;
     }
 
+    /// Get index of the key column, if rows are identified by a single primitive key.
+    pub(crate) fn get_single_key_index(&self) -> Option<usize> {
+        match self.metadata.identity {
+            IdentityProp::SinglePrimitiveKey(key_index) => Some(key_index),
+            _ => None,
+        }
+    }
+
+    /// Update initial copy position, which is persisted along with rows committed before.
+    pub(crate) fn update_initial_copy_position(
+        &mut self,
+        initial_copy_position: InitialCopyPosition,
+    ) {
+        self.next_snapshot_task.new_initial_copy_position = Some(initial_copy_position);
+    }
+
+    /// Get initial copy position loaded from iceberg at table creation, return `None` if there's no iceberg snapshot.
+    pub fn get_persisted_initial_copy_position(&self) -> Option<&InitialCopyPosition> {
+        self.persisted_initial_copy_position.as_ref()
+    }
+
     /// Get iceberg snapshot flush LSN.
     pub(crate) fn get_iceberg_snapshot_lsn(&self) -> Option<u64> {
         self.last_iceberg_snapshot_lsn
@@ -887,6 +1042,7 @@ impl MooncakeTable {
     }
 
     pub fn append(&mut self, row: MoonlinkRow) -> Result<()> {
+        self.has_rows = true;
         let lookup_key = self.metadata.identity.get_lookup_key(&row);
         let identity_for_key = self.metadata.identity.extract_identity_for_key(&row);
         if let Some(batch) = self.mem_slice.append(lookup_key, row, identity_for_key)? {
@@ -948,11 +1104,12 @@ impl MooncakeTable {
     /// that they can be included in the next snapshot.  The `lsn` parameter
     /// specifies the commit LSN for the flushed data.  When `lsn` is `None` the
     /// caller is responsible for setting the final LSN on the returned
-    /// `DiskSliceWriter`.
+    /// `DiskSliceWriter`.  `next_file_id` is advanced past the file ids taken
+    /// by the flush.
     async fn flush_mem_slice(
         mem_slice: &mut MemSlice,
         metadata: &Arc<TableMetadata>,
-        next_file_id: u32,
+        next_file_id: &mut u32,
         lsn: Option<u64>,
         snapshot_task: Option<&mut SnapshotTask>,
     ) -> Result<DiskSliceWriter> {
@@ -975,12 +1132,16 @@ impl MooncakeTable {
             path,
             batches,
             lsn,
-            next_file_id,
+            *next_file_id,
             index,
             parquet_flush_threshold_size,
+            metadata.config.partition_spec.clone(),
+            metadata.config.parquet_writer_config.clone(),
+            metadata.identity.clone(),
         );
 
         disk_slice.write().await?;
+        *next_file_id += disk_slice.table_auto_incr_id_count();
         Ok(disk_slice)
     }
 
@@ -1005,12 +1166,10 @@ impl MooncakeTable {
             return Ok(());
         }
 
-        let next_file_id = self.next_file_id;
-        self.next_file_id += 1;
         let disk_slice = Self::flush_mem_slice(
             &mut self.mem_slice,
             &self.metadata,
-            next_file_id,
+            &mut self.next_file_id,
             Some(lsn),
             Some(&mut self.next_snapshot_task),
         )
@@ -1105,6 +1264,9 @@ impl MooncakeTable {
                 .config
                 .data_compaction_config
                 .data_file_final_size,
+            partition_spec: self.metadata.config.partition_spec.clone(),
+            parquet_writer_config: self.metadata.config.parquet_writer_config.clone(),
+            identity: self.metadata.identity.clone(),
         };
         let schema_ref = self.metadata.schema.clone();
         let table_notify_tx_copy = self.table_notify.as_ref().unwrap().clone();
@@ -1151,6 +1313,7 @@ impl MooncakeTable {
         let uuid = snapshot_payload.uuid;
         let flush_lsn = snapshot_payload.flush_lsn;
         let wal_persisted_metadata = snapshot_payload.wal_persistence_metadata.clone();
+        let initial_copy_position = snapshot_payload.initial_copy_position.clone();
         let new_table_schema = snapshot_payload.new_table_schema.clone();
         let committed_deletion_logs = snapshot_payload.committed_deletion_logs.clone();
 
@@ -1229,6 +1392,7 @@ impl MooncakeTable {
             table_manager: Some(iceberg_table_manager),
             flush_lsn,
             wal_persisted_metadata,
+            initial_copy_position,
             new_table_schema,
             committed_deletion_logs,
             import_result: IcebergSnapshotImportResult {
@@ -1285,6 +1449,50 @@ impl MooncakeTable {
         );
     }
 
+    /// Get snapshot retention config, which decides background snapshot expiration interval.
+    pub(crate) fn get_snapshot_retention_config(&self) -> &SnapshotRetentionConfig {
+        &self.metadata.config.snapshot_retention_config
+    }
+
+    /// Expire iceberg snapshots out of retention, whose completion will be notified separately in async style.
+    pub(crate) fn perform_snapshot_expiration(&mut self, uuid: uuid::Uuid) {
+        // Check invariant: iceberg table manager is not used by iceberg snapshot.
+        let mut iceberg_table_manager = self.iceberg_table_manager.take().unwrap();
+        let retention_config = self.metadata.config.snapshot_retention_config.clone();
+        let table_notify_tx_copy = self.table_notify.as_ref().unwrap().clone();
+
+        // Create a detached task, whose completion will be notified separately.
+        tokio::task::spawn(
+            async move {
+                let expire_snapshots_result = iceberg_table_manager
+                    .expire_snapshots(&retention_config)
+                    .await
+                    .map_err(|e| e.into());
+                table_notify_tx_copy
+                    .send(TableEvent::SnapshotExpirationResult {
+                        snapshot_expiration_result: SnapshotExpirationResult {
+                            uuid,
+                            table_manager: Some(iceberg_table_manager),
+                            expire_snapshots_result,
+                        },
+                    })
+                    .await
+                    .unwrap();
+            }
+            .instrument(info_span!("snapshot_expiration")),
+        );
+    }
+
+    /// Set snapshot expiration result, which takes back iceberg table manager.
+    pub(crate) fn set_snapshot_expiration_res(
+        &mut self,
+        snapshot_expiration_res: SnapshotExpirationResult,
+    ) -> Result<ExpireSnapshotsResult> {
+        assert!(self.iceberg_table_manager.is_none());
+        self.iceberg_table_manager = Some(snapshot_expiration_res.table_manager.unwrap());
+        snapshot_expiration_res.expire_snapshots_result
+    }
+
     /// Drop a mooncake table.
     pub(crate) async fn drop_mooncake_table(&mut self) -> Result<()> {
         tokio::fs::remove_dir_all(&self.metadata.path).await?;
@@ -1341,6 +1549,7 @@ mod mooncake_tests {
             table_manager: None,
             flush_lsn: 1,
             wal_persisted_metadata: None,
+            initial_copy_position: InitialCopyPosition::default(),
             new_table_schema: None,
             committed_deletion_logs: HashSet::new(),
             import_result: IcebergSnapshotImportResult {
@@ -4,6 +4,7 @@ use crate::row::IdentityProp;
 use crate::row::MoonlinkRow;
 use crate::storage::mooncake_table::batch_id_counter::BatchIdCounter;
 use crate::storage::mooncake_table::delete_vector::BatchDeletionVector;
+use crate::storage::mooncake_table::schema_evolution;
 use crate::storage::mooncake_table::shared_array::SharedRowBuffer;
 use crate::storage::mooncake_table::shared_array::SharedRowBufferSnapshot;
 use crate::storage::storage_utils::{RawDeletionRecord, RecordLocation};
@@ -158,6 +159,65 @@ impl ColumnStoreBuffer {
         Ok(Some((next_batch_id - 1, batch)))
     }
 
+    /// Cast finalized batches to the new schema, without changing the buffer, so failure leaves it untouched.
+    pub(super) fn adapt_batches(
+        &self,
+        schema: Arc<Schema>,
+    ) -> Result<Vec<Option<Arc<RecordBatch>>>> {
+        self.in_memory_batches
+            .iter()
+            .map(|entry| {
+                entry
+                    .batch
+                    .data
+                    .as_ref()
+                    .map(|data| {
+                        Ok(Arc::new(schema_evolution::adapt_record_batch(
+                            data,
+                            schema.clone(),
+                        )?))
+                    })
+                    .transpose()
+            })
+            .collect()
+    }
+
+    /// Rebuild the buffer with the new schema, for data appended with the old one.
+    /// Finalized batches are replaced with ones casted by [`Self::adapt_batches`], and current rows are re-appended with new column builders.
+    pub(super) fn alter_schema(
+        &mut self,
+        schema: Arc<Schema>,
+        adapted_batches: Vec<Option<Arc<RecordBatch>>>,
+    ) {
+        assert_eq!(adapted_batches.len(), self.in_memory_batches.len());
+        for (entry, adapted_batch) in self.in_memory_batches.iter_mut().zip(adapted_batches) {
+            entry.batch.data = adapted_batch;
+        }
+
+        let column_mapping = schema_evolution::get_column_mapping(&self.schema, &schema);
+        let old_rows = std::mem::replace(
+            &mut self.current_rows,
+            SharedRowBuffer::new(self.max_rows_per_buffer),
+        )
+        .get_snapshot();
+        self.current_batch_builder = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                ColumnArrayBuilder::new(field.data_type(), self.max_rows_per_buffer, false)
+            })
+            .collect();
+        for row in old_rows.get_buffer(self.current_row_count) {
+            let row = schema_evolution::adapt_row(row, &column_mapping, &schema);
+            row.values.iter().enumerate().for_each(|(i, cell)| {
+                let _res = self.current_batch_builder[i].append_value(cell);
+                assert!(_res.is_ok());
+            });
+            self.current_rows.push(row);
+        }
+        self.schema = schema;
+    }
+
     #[inline]
     pub fn check_identity(
         &self,
@@ -322,7 +382,7 @@ mod tests {
     use super::*;
     use crate::row::RowValue;
     use arrow::datatypes::{DataType, Field};
-    use arrow_array::{Int32Array, StringArray, TimestampMicrosecondArray};
+    use arrow_array::{Int32Array, Int64Array, StringArray, TimestampMicrosecondArray};
     use std::collections::HashMap;
 
     // TODO(hjiang): Add unit test for ColumnStoreBuffer with deletion, and check record batch content.
@@ -465,4 +525,73 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_column_store_buffer_alter_schema() -> Result<()> {
+        let field_with_id = |name: &str, data_type: DataType, nullable: bool, field_id: &str| {
+            Field::new(name, data_type, nullable).with_metadata(HashMap::from([(
+                "PARQUET:field_id".to_string(),
+                field_id.to_string(),
+            )]))
+        };
+        let schema = Schema::new(vec![
+            field_with_id("id", DataType::Int32, false, "1"),
+            field_with_id("age", DataType::Int32, false, "2"),
+        ]);
+        // Widen "age" column and add "email" column.
+        let new_schema = Arc::new(Schema::new(vec![
+            field_with_id("id", DataType::Int32, false, "1"),
+            field_with_id("age", DataType::Int64, false, "2"),
+            field_with_id("email", DataType::Utf8, true, "3"),
+        ]));
+
+        let mut buffer =
+            ColumnStoreBuffer::new(Arc::new(schema), 2, Arc::new(BatchIdCounter::new(false)));
+        // The first batch is finalized and the third row is buffered, before schema change.
+        for (id, age) in [(1, 10), (2, 20), (3, 30)] {
+            buffer.append_row(MoonlinkRow::new(vec![
+                RowValue::Int32(id),
+                RowValue::Int32(age),
+            ]))?;
+        }
+        let adapted_batches = buffer.adapt_batches(new_schema.clone())?;
+        buffer.alter_schema(new_schema.clone(), adapted_batches);
+        buffer.append_row(MoonlinkRow::new(vec![
+            RowValue::Int32(4),
+            RowValue::Int64(40),
+            RowValue::ByteArray("d".as_bytes().to_vec()),
+        ]))?;
+        buffer.finalize_current_batch()?;
+
+        let batches = buffer.drain();
+        assert_eq!(batches.len(), 2);
+        let expected_record_batches = [
+            RecordBatch::try_new(
+                new_schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from(vec![1, 2])),
+                    Arc::new(Int64Array::from(vec![10, 20])),
+                    Arc::new(StringArray::from(vec![None::<&str>, None])),
+                ],
+            )
+            .unwrap(),
+            RecordBatch::try_new(
+                new_schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from(vec![3, 4])),
+                    Arc::new(Int64Array::from(vec![30, 40])),
+                    Arc::new(StringArray::from(vec![None, Some("d")])),
+                ],
+            )
+            .unwrap(),
+        ];
+        for (batch, expected_record_batch) in batches.iter().zip(expected_record_batches) {
+            assert_eq!(
+                *batch.batch.data.as_ref().unwrap().as_ref(),
+                expected_record_batch
+            );
+        }
+
+        Ok(())
+    }
 }
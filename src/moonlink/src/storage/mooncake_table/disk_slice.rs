@@ -1,13 +1,17 @@
 use super::data_batches::BatchEntry;
+use super::partition_spec::{PartitionKey, PartitionSpec};
 use crate::error::{Error, Result};
+use crate::row::IdentityProp;
 use crate::storage::index::persisted_bucket_hash_map::GlobalIndexBuilder;
 use crate::storage::index::{cache_utils as index_cache_utils, FileIndex, MemIndex};
 use crate::storage::parquet_utils;
+use crate::storage::parquet_writer_config::ParquetWriterConfig;
 use crate::storage::storage_utils::{
     create_data_file, get_random_file_name_in_dir, get_unique_file_id_for_flush,
-    MooncakeDataFileRef, ProcessedDeletionRecord, RecordLocation, TableId,
+    MooncakeDataFileRef, ProcessedDeletionRecord, RecordLocation, TableId, NUM_FILES_PER_FLUSH,
 };
 use crate::ObjectStorageCache;
+use arrow::compute::{concat_batches, take_record_batch};
 use arrow_array::RecordBatch;
 use arrow_schema::Schema;
 use parquet::arrow::AsyncArrowWriter;
@@ -15,12 +19,24 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+/// Record batch of one partition, along with (batch id, row index) of each row among input batches.
+type PartitionedBatch = (PartitionKey, RecordBatch, Vec<(usize, usize)>);
+
 /// Attributes for disk files.
 pub(crate) struct DiskFileAttrs {
     pub(crate) file_size: usize,
     pub(crate) row_num: usize,
 }
 
+/// Ongoing parquet file writer for one partition.
+struct PartitionFileWriter {
+    /// Index of the data file within the disk slice.
+    file_idx: usize,
+    data_file: MooncakeDataFileRef,
+    writer: AsyncArrowWriter<tokio::fs::File>,
+    row_num: usize,
+}
+
 pub(crate) struct DiskSliceWriter {
     /// The schema of the DiskSlice.
     ///
@@ -40,6 +56,15 @@ pub(crate) struct DiskSliceWriter {
     /// Parquet file flush threshold size.
     parquet_flush_threshold_size: usize,
 
+    /// Partition spec for data files, each data file only contains rows of one partition.
+    partition_spec: Option<PartitionSpec>,
+
+    /// Config for parquet data files.
+    parquet_writer_config: ParquetWriterConfig,
+
+    /// Identity property of the table, whose key columns get parquet bloom filters.
+    identity: IdentityProp,
+
     // a mapping of old record locations to new record locations
     // this is used to remap deletions on the disk slice
     batch_id_to_idx: HashMap<u64, usize>,
@@ -52,6 +77,7 @@ pub(crate) struct DiskSliceWriter {
 }
 
 impl DiskSliceWriter {
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
         schema: Arc<Schema>,
         dir_path: PathBuf,
@@ -60,6 +86,9 @@ impl DiskSliceWriter {
         table_auto_incr_id: u32,
         old_index: Arc<MemIndex>,
         parquet_flush_threshold_size: usize,
+        partition_spec: Option<PartitionSpec>,
+        parquet_writer_config: ParquetWriterConfig,
+        identity: IdentityProp,
     ) -> Self {
         Self {
             schema,
@@ -73,9 +102,28 @@ impl DiskSliceWriter {
             old_index,
             new_index: None,
             parquet_flush_threshold_size,
+            partition_spec,
+            parquet_writer_config,
+            identity,
         }
     }
 
+    /// Get file id for the given file index, data files and the index block file are allocated in order.
+    /// A partitioned disk slice could write more files than one table auto-increment id is able to hold, so file ids span over consecutive ones.
+    fn get_file_id(&self, file_idx: usize) -> u64 {
+        let file_idx = file_idx as u64;
+        get_unique_file_id_for_flush(
+            self.table_auto_incr_id as u64 + file_idx / NUM_FILES_PER_FLUSH,
+            file_idx % NUM_FILES_PER_FLUSH,
+        )
+    }
+
+    /// Get the number of table auto-increment ids taken by the disk slice, including data files and the index block file.
+    pub(super) fn table_auto_incr_id_count(&self) -> u32 {
+        let file_count = self.files.len() as u64 + 1;
+        file_count.div_ceil(NUM_FILES_PER_FLUSH) as u32
+    }
+
     /// Apply deletion vector to in-memory batches, write to parquet files and remap index.
     #[tracing::instrument(name = "disk_slice_write", skip_all)]
     pub(super) async fn write(&mut self) -> Result<()> {
@@ -146,7 +194,127 @@ impl DiskSliceWriter {
         &self.old_index
     }
 
+    /// Create a new parquet file writer, whose data file takes the given file index.
+    async fn create_partition_file_writer(&self, file_idx: usize) -> Result<PartitionFileWriter> {
+        let file_id = self.get_file_id(file_idx);
+        let file_path = get_random_file_name_in_dir(&self.dir_path);
+        let data_file = create_data_file(file_id, file_path);
+        let file = tokio::fs::File::create(self.dir_path.join(data_file.file_path()))
+            .await
+            .map_err(Into::<Error>::into)?;
+        let sorting_columns =
+            parquet_utils::get_sorting_columns(&self.schema, &self.parquet_writer_config)?;
+        let bloom_filter_columns = parquet_utils::get_bloom_filter_columns(
+            &self.parquet_writer_config,
+            &self.schema,
+            &self.identity,
+        )?;
+        let properties = parquet_utils::get_parquet_properties(
+            &self.parquet_writer_config,
+            &bloom_filter_columns,
+            sorting_columns,
+        )?;
+        let writer = AsyncArrowWriter::try_new(file, self.schema.clone(), Some(properties))?;
+        Ok(PartitionFileWriter {
+            file_idx,
+            data_file,
+            writer,
+            row_num: 0,
+        })
+    }
+
+    /// Finalize the given parquet file writer, and record the data file at its file index.
+    async fn finish_partition_file_writer(
+        files: &mut [Option<(MooncakeDataFileRef, DiskFileAttrs)>],
+        mut file_writer: PartitionFileWriter,
+    ) -> Result<()> {
+        file_writer.writer.finish().await?;
+        let file_size = file_writer.writer.bytes_written();
+        files[file_writer.file_idx] = Some((
+            file_writer.data_file,
+            DiskFileAttrs {
+                file_size,
+                row_num: file_writer.row_num,
+            },
+        ));
+        Ok(())
+    }
+
+    /// Split record batches by partition, each partitioned batch carries (batch id, row index) of its rows; rows keep their input order.
+    fn split_batches_by_partition(
+        &self,
+        record_batches: &[(usize, RecordBatch, Vec<usize>)],
+    ) -> Result<Vec<PartitionedBatch>> {
+        let mut partitioned_batches = vec![];
+        for (batch_id, batch, row_indices) in record_batches {
+            let cur_partitioned_batches = match &self.partition_spec {
+                Some(partition_spec) => partition_spec.split_batch(batch)?,
+                None => vec![(vec![], batch.clone(), (0..batch.num_rows()).collect())],
+            };
+            for (partition_key, partitioned_batch, batch_row_indices) in cur_partitioned_batches {
+                let row_locations = batch_row_indices
+                    .into_iter()
+                    .map(|batch_row_idx| (*batch_id, row_indices[batch_row_idx]))
+                    .collect();
+                partitioned_batches.push((partition_key, partitioned_batch, row_locations));
+            }
+        }
+        Ok(partitioned_batches)
+    }
+
+    /// Sort rows of each partition by the configured sort order, partitions are placed in the order they first show up.
+    /// Sorted rows are sliced into batches no larger than input ones, so file size threshold is checked at the same granularity.
+    fn sort_partitioned_batches(
+        &self,
+        partitioned_batches: Vec<PartitionedBatch>,
+    ) -> Result<Vec<PartitionedBatch>> {
+        let mut partition_keys = vec![];
+        let mut batches_by_partition: HashMap<
+            PartitionKey,
+            (Vec<RecordBatch>, Vec<(usize, usize)>),
+        > = HashMap::new();
+        let mut max_batch_rows = 0;
+        for (partition_key, partitioned_batch, row_locations) in partitioned_batches {
+            max_batch_rows = max_batch_rows.max(partitioned_batch.num_rows());
+            let (batches, locations) = batches_by_partition
+                .entry(partition_key.clone())
+                .or_insert_with(|| {
+                    partition_keys.push(partition_key);
+                    (vec![], vec![])
+                });
+            batches.push(partitioned_batch);
+            locations.extend(row_locations);
+        }
+
+        let mut sorted_batches = vec![];
+        for partition_key in partition_keys.into_iter() {
+            let (batches, row_locations) = batches_by_partition.remove(&partition_key).unwrap();
+            let batch = concat_batches(&self.schema, &batches)?;
+            let sorted_indices =
+                parquet_utils::get_sorted_indices(&batch, &self.parquet_writer_config)?;
+            let sorted_batch = take_record_batch(&batch, &sorted_indices)?;
+            let sorted_row_locations = sorted_indices
+                .values()
+                .iter()
+                .map(|idx| row_locations[*idx as usize])
+                .collect::<Vec<_>>();
+            let mut offset = 0;
+            while offset < sorted_batch.num_rows() {
+                let len = max_batch_rows.min(sorted_batch.num_rows() - offset);
+                sorted_batches.push((
+                    partition_key.clone(),
+                    sorted_batch.slice(offset, len),
+                    sorted_row_locations[offset..offset + len].to_vec(),
+                ));
+                offset += len;
+            }
+        }
+        Ok(sorted_batches)
+    }
+
     /// Write record batches to parquet files in synchronous mode.
+    /// For partitioned tables, one parquet file is kept open for each partition.
+    /// If a sort order is configured, rows within each data file are written in that order.
     /// TODO(hjiang): Parallelize the parquet file write operations.
     #[tracing::instrument(name = "write_parquet_batches", skip_all)]
     async fn write_batch_to_parquet(
@@ -154,71 +322,38 @@ impl DiskSliceWriter {
         record_batches: &Vec<(usize, RecordBatch, Vec<usize>)>,
     ) -> Result<()> {
         let mut files = Vec::new();
-        let mut writer = None;
-        let mut out_file_idx = 0;
-        let mut out_row_idx = 0;
-        let dir_path = &self.dir_path;
-        let mut data_file = None;
-        for (batch_id, batch, row_indices) in record_batches {
-            if writer.is_none() {
-                // Generate a unique file name
-                // Create the file
-                out_file_idx = files.len();
-                let file_id = get_unique_file_id_for_flush(
-                    self.table_auto_incr_id as u64,
-                    out_file_idx as u64,
-                );
-                let file_path = get_random_file_name_in_dir(dir_path);
-                data_file = Some(create_data_file(file_id, file_path));
-                let file =
-                    tokio::fs::File::create(dir_path.join(data_file.as_ref().unwrap().file_path()))
-                        .await
-                        .map_err(Into::<Error>::into)?;
-                let properties = parquet_utils::get_default_parquet_properties();
-                writer = Some(AsyncArrowWriter::try_new(
-                    file,
-                    self.schema.clone(),
-                    Some(properties),
-                )?);
-                out_row_idx = 0;
+        let mut writers: HashMap<PartitionKey, PartitionFileWriter> = HashMap::new();
+        let mut partitioned_batches = self.split_batches_by_partition(record_batches)?;
+        if !self.parquet_writer_config.sort_order.is_empty() {
+            partitioned_batches = self.sort_partitioned_batches(partitioned_batches)?;
+        }
+        for (partition_key, partitioned_batch, row_locations) in partitioned_batches {
+            if !writers.contains_key(&partition_key) {
+                // Generate a unique file name, and create the file.
+                let file_writer = self.create_partition_file_writer(files.len()).await?;
+                files.push(None);
+                writers.insert(partition_key.clone(), file_writer);
             }
-            for row_idx in row_indices {
-                self.row_offset_mapping[*batch_id][*row_idx] = Some((out_file_idx, out_row_idx));
-                out_row_idx += 1;
+            let file_writer = writers.get_mut(&partition_key).unwrap();
+            for (batch_id, row_idx) in row_locations {
+                self.row_offset_mapping[batch_id][row_idx] =
+                    Some((file_writer.file_idx, file_writer.row_num));
+                file_writer.row_num += 1;
             }
             // Write the batch
-            writer.as_mut().unwrap().write(batch).await?;
-            let estimated_total_size = {
-                let cur_writer = writer.as_ref().unwrap();
-                cur_writer.in_progress_size() + cur_writer.bytes_written()
-            };
+            file_writer.writer.write(&partitioned_batch).await?;
+            let estimated_total_size =
+                file_writer.writer.in_progress_size() + file_writer.writer.bytes_written();
             if estimated_total_size > self.parquet_flush_threshold_size {
                 // Finalize the writer
-                writer.as_mut().unwrap().finish().await?;
-                let file_size = writer.as_ref().unwrap().bytes_written();
-                writer = None;
-                files.push((
-                    data_file.unwrap(),
-                    DiskFileAttrs {
-                        file_size,
-                        row_num: out_row_idx,
-                    },
-                ));
-                data_file = None;
+                let file_writer = writers.remove(&partition_key).unwrap();
+                Self::finish_partition_file_writer(&mut files, file_writer).await?;
             }
         }
-        if let Some(mut writer) = writer {
-            writer.finish().await?;
-            let file_size = writer.bytes_written();
-            files.push((
-                data_file.unwrap(),
-                DiskFileAttrs {
-                    file_size,
-                    row_num: out_row_idx,
-                },
-            ));
+        for (_, file_writer) in writers.into_iter() {
+            Self::finish_partition_file_writer(&mut files, file_writer).await?;
         }
-        self.files = files;
+        self.files = files.into_iter().map(|file| file.unwrap()).collect();
         Ok(())
     }
 
@@ -230,8 +365,7 @@ impl DiskSliceWriter {
         let list = self
             .old_index
             .remap_into_vec(&self.batch_id_to_idx, &self.row_offset_mapping);
-        let file_id =
-            get_unique_file_id_for_flush(self.table_auto_incr_id as u64, self.files.len() as u64);
+        let file_id = self.get_file_id(self.files.len());
         let mut index_builder = GlobalIndexBuilder::new();
         index_builder.set_files(self.files.iter().map(|(file, _)| file.clone()).collect());
         index_builder.set_directory(self.dir_path.clone());
@@ -266,12 +400,19 @@ mod tests {
     use crate::row::{IdentityProp, MoonlinkRow, RowValue};
     use crate::storage::index::persisted_bucket_hash_map::test_get_hashes_for_index;
     use crate::storage::mooncake_table::mem_slice::MemSlice;
+    use crate::storage::mooncake_table::partition_spec::{PartitionField, PartitionTransform};
     use crate::storage::mooncake_table::{BatchIdCounter, MooncakeTableConfig};
+    use crate::storage::parquet_writer_config::{ParquetBloomFilterConfig, ParquetSortField};
     use crate::storage::storage_utils::RawDeletionRecord;
     use arrow::datatypes::{DataType, Field};
     use arrow_array::{Int32Array, StringArray};
     use arrow_schema::Schema;
+    use futures::TryStreamExt;
     use parquet::arrow::async_reader::ParquetRecordBatchStreamBuilder;
+    use parquet::file::metadata::SortingColumn;
+    use parquet::file::properties::ReaderProperties;
+    use parquet::file::reader::{FileReader, RowGroupReader, SerializedFileReader};
+    use parquet::file::serialized_reader::ReadOptionsBuilder;
     use tempfile::tempdir;
 
     /// Util function to create test schema.
@@ -326,6 +467,9 @@ mod tests {
             /*table_auto_incr_id=*/ 0,
             Arc::new(old_index),
             MooncakeTableConfig::DEFAULT_DISK_SLICE_PARQUET_FILE_SIZE,
+            /*partition_spec=*/ None,
+            ParquetWriterConfig::default(),
+            IdentityProp::SinglePrimitiveKey(0),
         );
         disk_slice.write().await?;
 
@@ -444,6 +588,9 @@ mod tests {
             0,
             Arc::new(index),
             MooncakeTableConfig::DEFAULT_DISK_SLICE_PARQUET_FILE_SIZE,
+            /*partition_spec=*/ None,
+            ParquetWriterConfig::default(),
+            IdentityProp::SinglePrimitiveKey(0),
         );
 
         // Write the disk slice
@@ -489,4 +636,230 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_partitioned_disk_slice() -> Result<()> {
+        let temp_dir = tempdir().map_err(Into::<Error>::into)?;
+        let schema = get_test_schema();
+        let identity = IdentityProp::SinglePrimitiveKey(0);
+        let mut mem_slice = MemSlice::new(
+            schema.clone(),
+            100,
+            identity,
+            Arc::new(BatchIdCounter::new(false)),
+        );
+        for id in [1, 12, 3, 15, 25] {
+            let row = MoonlinkRow::new(vec![
+                RowValue::Int32(id),
+                RowValue::ByteArray(format!("name-{id}").into_bytes()),
+            ]);
+            mem_slice.append(id as u64, row, None)?;
+        }
+        let (_new_batch, entries, index) = mem_slice.drain().unwrap();
+
+        // Rows are partitioned by ids truncated to 10.
+        let partition_spec = PartitionSpec {
+            fields: vec![PartitionField {
+                source_column: "id".to_string(),
+                transform: PartitionTransform::Truncate(10),
+            }],
+        };
+        let mut disk_slice = DiskSliceWriter::new(
+            schema.clone(),
+            temp_dir.path().to_path_buf(),
+            entries,
+            Some(1),
+            /*table_auto_incr_id=*/ 0,
+            Arc::new(index),
+            MooncakeTableConfig::DEFAULT_DISK_SLICE_PARQUET_FILE_SIZE,
+            Some(partition_spec),
+            ParquetWriterConfig::default(),
+            IdentityProp::SinglePrimitiveKey(0),
+        );
+        disk_slice.write().await?;
+        assert_eq!(disk_slice.table_auto_incr_id_count(), 1);
+
+        // Each partition is written to its own data file, in the order partitions first show up.
+        let mut actual_ids = vec![];
+        for (file, attrs) in disk_slice.output_files() {
+            let file = tokio::fs::File::open(temp_dir.path().join(file.file_path())).await?;
+            let reader = ParquetRecordBatchStreamBuilder::new(file).await?.build()?;
+            let batches = reader.try_collect::<Vec<_>>().await?;
+            let ids = batches
+                .iter()
+                .flat_map(|batch| {
+                    batch
+                        .column(0)
+                        .as_any()
+                        .downcast_ref::<Int32Array>()
+                        .unwrap()
+                        .values()
+                        .to_vec()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(ids.len(), attrs.row_num);
+            actual_ids.push(ids);
+        }
+        assert_eq!(actual_ids, vec![vec![1, 3], vec![12, 15], vec![25]]);
+
+        // Index points to row locations within partitioned data files.
+        let new_index = disk_slice.take_index().unwrap();
+        let results = new_index
+            .search_values(&test_get_hashes_for_index(&[15]))
+            .await;
+        let expected_file_id = disk_slice.output_files()[1].0.file_id();
+        assert_eq!(
+            results.into_iter().map(|(_, loc)| loc).collect::<Vec<_>>(),
+            vec![RecordLocation::DiskFile(expected_file_id, 1)]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sorted_disk_slice() -> Result<()> {
+        let temp_dir = tempdir().map_err(Into::<Error>::into)?;
+        let schema = get_test_schema();
+        let identity = IdentityProp::SinglePrimitiveKey(0);
+        // Each record batch holds two rows, so rows are sorted across batches.
+        let mut mem_slice = MemSlice::new(
+            schema.clone(),
+            /*max_rows_per_buffer=*/ 2,
+            identity,
+            Arc::new(BatchIdCounter::new(false)),
+        );
+        for id in [5, 3, 9, 1, 7] {
+            let row = MoonlinkRow::new(vec![
+                RowValue::Int32(id),
+                RowValue::ByteArray(format!("name-{id}").into_bytes()),
+            ]);
+            mem_slice.append(id as u64, row, None)?;
+        }
+        let (_new_batch, entries, index) = mem_slice.drain().unwrap();
+
+        let parquet_writer_config = ParquetWriterConfig {
+            sort_order: vec![ParquetSortField {
+                column: "id".to_string(),
+                descending: false,
+                nulls_first: false,
+            }],
+            ..Default::default()
+        };
+        let mut disk_slice = DiskSliceWriter::new(
+            schema.clone(),
+            temp_dir.path().to_path_buf(),
+            entries,
+            Some(1),
+            /*table_auto_incr_id=*/ 0,
+            Arc::new(index),
+            MooncakeTableConfig::DEFAULT_DISK_SLICE_PARQUET_FILE_SIZE,
+            /*partition_spec=*/ None,
+            parquet_writer_config,
+            IdentityProp::SinglePrimitiveKey(0),
+        );
+        disk_slice.write().await?;
+        assert_eq!(disk_slice.output_files().len(), 1);
+
+        // Rows are written in sort order, which is recorded in parquet metadata.
+        let (data_file, _) = &disk_slice.output_files()[0];
+        let file = tokio::fs::File::open(temp_dir.path().join(data_file.file_path())).await?;
+        let builder = ParquetRecordBatchStreamBuilder::new(file).await?;
+        let sorting_columns = builder.metadata().row_group(0).sorting_columns().cloned();
+        assert_eq!(
+            sorting_columns,
+            Some(vec![SortingColumn {
+                column_idx: 0,
+                descending: false,
+                nulls_first: false,
+            }])
+        );
+        let batches = builder.build()?.try_collect::<Vec<_>>().await?;
+        let ids = batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap()
+                    .values()
+                    .to_vec()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![1, 3, 5, 7, 9]);
+
+        // Index points to row locations after sort.
+        let new_index = disk_slice.take_index().unwrap();
+        let results = new_index
+            .search_values(&test_get_hashes_for_index(&[9]))
+            .await;
+        assert_eq!(
+            results.into_iter().map(|(_, loc)| loc).collect::<Vec<_>>(),
+            vec![RecordLocation::DiskFile(data_file.file_id(), 4)]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_disk_slice_bloom_filter() -> Result<()> {
+        let temp_dir = tempdir().map_err(Into::<Error>::into)?;
+        let schema = get_test_schema();
+        let identity = IdentityProp::SinglePrimitiveKey(0);
+        let mut mem_slice = MemSlice::new(
+            schema.clone(),
+            100,
+            identity.clone(),
+            Arc::new(BatchIdCounter::new(false)),
+        );
+        for id in [1, 2, 3] {
+            let row = MoonlinkRow::new(vec![
+                RowValue::Int32(id),
+                RowValue::ByteArray(format!("name-{id}").into_bytes()),
+            ]);
+            mem_slice.append(id as u64, row, None)?;
+        }
+        let (_new_batch, entries, index) = mem_slice.drain().unwrap();
+
+        let mut disk_slice = DiskSliceWriter::new(
+            schema.clone(),
+            temp_dir.path().to_path_buf(),
+            entries,
+            Some(1),
+            /*table_auto_incr_id=*/ 0,
+            Arc::new(index),
+            MooncakeTableConfig::DEFAULT_DISK_SLICE_PARQUET_FILE_SIZE,
+            /*partition_spec=*/ None,
+            ParquetWriterConfig {
+                bloom_filter_config: ParquetBloomFilterConfig {
+                    identity_columns_enabled: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            identity,
+        );
+        disk_slice.write().await?;
+        assert_eq!(disk_slice.output_files().len(), 1);
+
+        // Only the identity column has bloom filter.
+        let (data_file, _) = &disk_slice.output_files()[0];
+        let file = std::fs::File::open(temp_dir.path().join(data_file.file_path()))?;
+        let read_options = ReadOptionsBuilder::new()
+            .with_reader_properties(
+                ReaderProperties::builder()
+                    .set_read_bloom_filter(true)
+                    .build(),
+            )
+            .build();
+        let reader = SerializedFileReader::new_with_options(file, read_options)?;
+        let row_group_reader = reader.get_row_group(0)?;
+        let id_bloom_filter = row_group_reader.get_column_bloom_filter(0).unwrap();
+        for id in [1, 2, 3] {
+            assert!(id_bloom_filter.check(&id));
+        }
+        assert!(row_group_reader.get_column_bloom_filter(1).is_none());
+
+        Ok(())
+    }
 }
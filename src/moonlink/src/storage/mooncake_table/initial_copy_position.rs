@@ -0,0 +1,116 @@
+//! Position of an initial copy, which is persisted into iceberg snapshot property together with copied rows.
+use crate::row::{MoonlinkRow, RowValue};
+use serde::{Deserialize, Serialize};
+
+/// Keys which have been copied from one source snapshot.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CopiedKeyRange {
+    /// Largest key copied, the range starts right after the previous range.
+    pub last_key: i64,
+    /// LSN of the source snapshot which keys are copied from.
+    pub snapshot_lsn: u64,
+}
+
+/// Initial copy position.
+///
+/// Tables with a single integer primary key are copied in key order, and copied rows are committed periodically; so an
+/// interrupted copy resumes after the last copied key, from a new source snapshot. Changes to a key are reflected by the
+/// copy up to the snapshot LSN of the range it's copied in, and should only be applied after that.
+///
+/// WARNING: it's supposed to be small, there's one range per copy attempt.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct InitialCopyPosition {
+    /// Number of rows copied and committed.
+    pub rows_copied: u64,
+    /// Copied key ranges, ordered by key.
+    pub copied_ranges: Vec<CopiedKeyRange>,
+    /// LSN of the source snapshot for the last copy attempt, which covers all keys after copied ranges.
+    /// Only assigned when the copy finishes.
+    pub finish_lsn: Option<u64>,
+}
+
+impl InitialCopyPosition {
+    /// Get the copy key of the given row, return `None` if the key column doesn't hold an integer.
+    pub fn get_key(row: &MoonlinkRow, key_index: usize) -> Option<i64> {
+        match row.values.get(key_index)? {
+            RowValue::Int32(value) => Some(*value as i64),
+            RowValue::Int64(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get the largest copied key.
+    pub fn last_key(&self) -> Option<i64> {
+        self.copied_ranges.last().map(|range| range.last_key)
+    }
+
+    /// Return whether the copy has finished.
+    pub fn is_finished(&self) -> bool {
+        self.finish_lsn.is_some()
+    }
+
+    /// Record keys up to `last_key` copied from the source snapshot at `snapshot_lsn`.
+    pub fn advance(&mut self, last_key: i64, snapshot_lsn: u64, rows_copied: u64) {
+        match self.copied_ranges.last_mut() {
+            Some(range) if range.snapshot_lsn == snapshot_lsn => range.last_key = last_key,
+            _ => self.copied_ranges.push(CopiedKeyRange {
+                last_key,
+                snapshot_lsn,
+            }),
+        }
+        self.rows_copied = rows_copied;
+    }
+
+    /// Get the snapshot LSN which changes up to are reflected by the copy.
+    /// If `key` is unassigned, get the smallest one among all keys.
+    pub(crate) fn get_snapshot_lsn(&self, key: Option<i64>) -> Option<u64> {
+        match key {
+            Some(key) => self
+                .copied_ranges
+                .iter()
+                .find(|range| key <= range.last_key)
+                .map(|range| range.snapshot_lsn)
+                .or(self.finish_lsn),
+            None => self
+                .copied_ranges
+                .iter()
+                .map(|range| range.snapshot_lsn)
+                .chain(self.finish_lsn)
+                .min(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initial_copy_position() {
+        let mut position = InitialCopyPosition::default();
+        assert_eq!(position.last_key(), None);
+        assert_eq!(position.get_snapshot_lsn(Some(1)), None);
+
+        // First copy attempt, interrupted after key 200.
+        position.advance(
+            /*last_key=*/ 100, /*snapshot_lsn=*/ 10, /*rows_copied=*/ 100,
+        );
+        position.advance(
+            /*last_key=*/ 200, /*snapshot_lsn=*/ 10, /*rows_copied=*/ 200,
+        );
+        // Second copy attempt, which finishes.
+        position.advance(
+            /*last_key=*/ 300, /*snapshot_lsn=*/ 20, /*rows_copied=*/ 300,
+        );
+        position.finish_lsn = Some(20);
+
+        assert_eq!(position.last_key(), Some(300));
+        assert_eq!(position.copied_ranges.len(), 2);
+        assert_eq!(position.rows_copied, 300);
+        assert_eq!(position.get_snapshot_lsn(Some(-5)), Some(10));
+        assert_eq!(position.get_snapshot_lsn(Some(200)), Some(10));
+        assert_eq!(position.get_snapshot_lsn(Some(201)), Some(20));
+        assert_eq!(position.get_snapshot_lsn(Some(1000)), Some(20));
+        assert_eq!(position.get_snapshot_lsn(None), Some(10));
+    }
+}
@@ -40,6 +40,23 @@ impl MemSlice {
         }
     }
 
+    /// Cast finalized batches to the new schema, without changing the mem slice.
+    pub(super) fn adapt_batches(
+        &self,
+        schema: Arc<Schema>,
+    ) -> Result<Vec<Option<Arc<RecordBatch>>>> {
+        self.column_store.adapt_batches(schema)
+    }
+
+    /// Rebuild rows appended with the old schema in the new schema, with finalized batches casted by [`Self::adapt_batches`].
+    pub(super) fn alter_schema(
+        &mut self,
+        schema: Arc<Schema>,
+        adapted_batches: Vec<Option<Arc<RecordBatch>>>,
+    ) {
+        self.column_store.alter_schema(schema, adapted_batches)
+    }
+
     /// Return whether slice is empty.
     pub fn is_empty(&self) -> bool {
         self.column_store.get_num_rows() == 0
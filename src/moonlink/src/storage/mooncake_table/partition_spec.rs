@@ -0,0 +1,558 @@
+/// This module contains partition spec for mooncake tables, which decides how rows are grouped into data files.
+/// Transforms follow iceberg partition transform semantics, so each data file maps to exactly one iceberg partition.
+use crate::error::Result;
+
+use std::collections::HashMap;
+
+use arrow::compute::take_record_batch;
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Date32Type, Int16Type, Int32Type, Int64Type, TimestampMicrosecondType};
+use arrow_array::{Array, ArrayRef, RecordBatch, UInt32Array};
+use arrow_schema::{ArrowError, DataType, TimeUnit};
+use chrono::{DateTime, Datelike};
+use iceberg::spec::{
+    Datum, Literal, PartitionSpec as IcebergPartitionSpec, PrimitiveLiteral, PrimitiveType,
+    Schema as IcebergSchema, Struct, Transform, UnboundPartitionSpec,
+};
+use iceberg::Result as IcebergResult;
+use iceberg::{Error as IcebergError, ErrorKind};
+
+const MICROS_PER_HOUR: i64 = 3_600_000_000;
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Transform applied to the source column to get partition value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PartitionTransform {
+    /// Source value itself.
+    Identity,
+    /// Years since 1970, applies to date and timestamp columns.
+    Year,
+    /// Months since 1970-01, applies to date and timestamp columns.
+    Month,
+    /// Days since 1970-01-01, applies to date and timestamp columns.
+    Day,
+    /// Hours since 1970-01-01 00:00:00, applies to timestamp columns.
+    Hour,
+    /// Source value truncated to the given width, applies to integer columns.
+    Truncate(u32),
+}
+
+/// A single partition field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartitionField {
+    /// Name of the source column.
+    pub source_column: String,
+    /// Transform applied to the source column.
+    pub transform: PartitionTransform,
+}
+
+/// Partition spec for a mooncake table, all rows within one data file belong to the same partition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartitionSpec {
+    pub fields: Vec<PartitionField>,
+}
+
+/// Partition value for each partition field, `None` for null.
+pub(crate) type PartitionKey = Vec<Option<i64>>;
+
+/// Physical representation of source values, all of them are stored as 64-bit integers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SourceUnit {
+    /// Integer value.
+    Integer,
+    /// Days since epoch.
+    Days,
+    /// Microseconds since epoch.
+    Micros,
+}
+
+impl SourceUnit {
+    fn from_arrow(data_type: &DataType) -> Option<Self> {
+        match data_type {
+            DataType::Int16 | DataType::Int32 | DataType::Int64 => Some(Self::Integer),
+            DataType::Date32 => Some(Self::Days),
+            DataType::Timestamp(TimeUnit::Microsecond, _) => Some(Self::Micros),
+            _ => None,
+        }
+    }
+
+    fn from_iceberg(data_type: &PrimitiveType) -> Option<Self> {
+        match data_type {
+            PrimitiveType::Int | PrimitiveType::Long => Some(Self::Integer),
+            PrimitiveType::Date => Some(Self::Days),
+            PrimitiveType::Timestamp | PrimitiveType::Timestamptz => Some(Self::Micros),
+            _ => None,
+        }
+    }
+}
+
+impl PartitionTransform {
+    /// Return whether the transform applies to source values of the given unit.
+    fn supports(self, unit: SourceUnit) -> bool {
+        match self {
+            PartitionTransform::Identity => true,
+            PartitionTransform::Truncate(width) => width > 0 && unit == SourceUnit::Integer,
+            PartitionTransform::Year | PartitionTransform::Month | PartitionTransform::Day => {
+                unit != SourceUnit::Integer
+            }
+            PartitionTransform::Hour => unit == SourceUnit::Micros,
+        }
+    }
+
+    /// Apply the transform to the given source value, return `None` if the result is out of range, for example a date
+    /// beyond the supported calendar range.
+    /// Precondition: the transform supports the source unit.
+    fn apply(self, value: i64, unit: SourceUnit) -> Option<i64> {
+        let days = || match unit {
+            SourceUnit::Micros => value.div_euclid(MICROS_PER_DAY),
+            _ => value,
+        };
+        match self {
+            PartitionTransform::Identity => Some(value),
+            PartitionTransform::Truncate(width) => {
+                value.checked_sub(value.rem_euclid(width as i64))
+            }
+            PartitionTransform::Hour => Some(value.div_euclid(MICROS_PER_HOUR)),
+            PartitionTransform::Day => Some(days()),
+            PartitionTransform::Year | PartitionTransform::Month => {
+                let date = days()
+                    .checked_mul(SECONDS_PER_DAY)
+                    .and_then(|seconds| DateTime::from_timestamp(seconds, 0))?
+                    .date_naive();
+                let years = (date.year() - 1970) as i64;
+                if self == PartitionTransform::Year {
+                    Some(years)
+                } else {
+                    Some(years * 12 + date.month0() as i64)
+                }
+            }
+        }
+    }
+
+    /// Get the corresponding iceberg transform.
+    pub(crate) fn to_iceberg(self) -> Transform {
+        match self {
+            PartitionTransform::Identity => Transform::Identity,
+            PartitionTransform::Year => Transform::Year,
+            PartitionTransform::Month => Transform::Month,
+            PartitionTransform::Day => Transform::Day,
+            PartitionTransform::Hour => Transform::Hour,
+            PartitionTransform::Truncate(width) => Transform::Truncate(width),
+        }
+    }
+
+    /// Get transform from the iceberg one, return `None` if not supported.
+    pub(crate) fn from_iceberg(transform: Transform) -> Option<Self> {
+        match transform {
+            Transform::Identity => Some(PartitionTransform::Identity),
+            Transform::Year => Some(PartitionTransform::Year),
+            Transform::Month => Some(PartitionTransform::Month),
+            Transform::Day => Some(PartitionTransform::Day),
+            Transform::Hour => Some(PartitionTransform::Hour),
+            Transform::Truncate(width) => Some(PartitionTransform::Truncate(width)),
+            _ => None,
+        }
+    }
+
+    /// Get iceberg partition field name for the given source column.
+    fn get_partition_field_name(self, source_column: &str) -> String {
+        match self {
+            PartitionTransform::Identity => source_column.to_string(),
+            PartitionTransform::Year => format!("{source_column}_year"),
+            PartitionTransform::Month => format!("{source_column}_month"),
+            PartitionTransform::Day => format!("{source_column}_day"),
+            PartitionTransform::Hour => format!("{source_column}_hour"),
+            PartitionTransform::Truncate(_) => format!("{source_column}_trunc"),
+        }
+    }
+}
+
+/// Get source values of the given arrow array as 64-bit integers.
+fn get_source_values(array: &ArrayRef) -> Result<(SourceUnit, Vec<Option<i64>>)> {
+    let unit = SourceUnit::from_arrow(array.data_type()).ok_or_else(|| {
+        ArrowError::InvalidArgumentError(format!(
+            "partition source column of type {} is not supported",
+            array.data_type()
+        ))
+    })?;
+    let values = match array.data_type() {
+        DataType::Int16 => array
+            .as_primitive::<Int16Type>()
+            .iter()
+            .map(|v| v.map(i64::from))
+            .collect(),
+        DataType::Int32 => array
+            .as_primitive::<Int32Type>()
+            .iter()
+            .map(|v| v.map(i64::from))
+            .collect(),
+        DataType::Int64 => array.as_primitive::<Int64Type>().iter().collect(),
+        DataType::Date32 => array
+            .as_primitive::<Date32Type>()
+            .iter()
+            .map(|v| v.map(i64::from))
+            .collect(),
+        DataType::Timestamp(TimeUnit::Microsecond, _) => array
+            .as_primitive::<TimestampMicrosecondType>()
+            .iter()
+            .collect(),
+        _ => unreachable!(),
+    };
+    Ok((unit, values))
+}
+
+impl PartitionSpec {
+    /// Get partition key for each row of the given record batch.
+    pub(crate) fn get_partition_keys(&self, batch: &RecordBatch) -> Result<Vec<PartitionKey>> {
+        let mut keys = vec![Vec::with_capacity(self.fields.len()); batch.num_rows()];
+        for field in self.fields.iter() {
+            let array = batch.column_by_name(&field.source_column).ok_or_else(|| {
+                ArrowError::SchemaError(format!(
+                    "partition source column {} not found",
+                    field.source_column
+                ))
+            })?;
+            let (unit, values) = get_source_values(array)?;
+            if !field.transform.supports(unit) {
+                return Err(ArrowError::InvalidArgumentError(format!(
+                    "partition transform {:?} doesn't apply to column {} of type {}",
+                    field.transform,
+                    field.source_column,
+                    array.data_type()
+                ))
+                .into());
+            }
+            for (key, value) in keys.iter_mut().zip(values) {
+                let partition_value = match value {
+                    Some(value) => Some(field.transform.apply(value, unit).ok_or_else(|| {
+                        ArrowError::ComputeError(format!(
+                            "partition transform {:?} overflows for value {value} of column {}",
+                            field.transform, field.source_column
+                        ))
+                    })?),
+                    None => None,
+                };
+                key.push(partition_value);
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Split the given record batch by partition, in the order each partition first shows up.
+    /// Row indices within the given record batch are returned along with each partitioned batch.
+    pub(crate) fn split_batch(
+        &self,
+        batch: &RecordBatch,
+    ) -> Result<Vec<(PartitionKey, RecordBatch, Vec<usize>)>> {
+        let keys = self.get_partition_keys(batch)?;
+        let mut partition_indices: HashMap<PartitionKey, usize> = HashMap::new();
+        let mut partitions: Vec<(PartitionKey, Vec<usize>)> = vec![];
+        for (row_idx, key) in keys.into_iter().enumerate() {
+            match partition_indices.get(&key) {
+                Some(idx) => partitions[*idx].1.push(row_idx),
+                None => {
+                    partition_indices.insert(key.clone(), partitions.len());
+                    partitions.push((key, vec![row_idx]));
+                }
+            }
+        }
+
+        // Fast path: all rows belong to the same partition.
+        if partitions.len() == 1 {
+            let (key, row_indices) = partitions.pop().unwrap();
+            return Ok(vec![(key, batch.clone(), row_indices)]);
+        }
+
+        let mut split_batches = Vec::with_capacity(partitions.len());
+        for (key, row_indices) in partitions.into_iter() {
+            let indices = UInt32Array::from_iter_values(row_indices.iter().map(|idx| *idx as u32));
+            let partitioned_batch = take_record_batch(batch, &indices)?;
+            split_batches.push((key, partitioned_batch, row_indices));
+        }
+        Ok(split_batches)
+    }
+
+    /// Get the corresponding iceberg partition spec, source columns are resolved by name in the given iceberg schema.
+    pub(crate) fn to_iceberg_partition_spec(
+        &self,
+        schema: &IcebergSchema,
+    ) -> IcebergResult<UnboundPartitionSpec> {
+        let mut builder = UnboundPartitionSpec::builder();
+        for field in self.fields.iter() {
+            let source_field = schema.field_by_name(&field.source_column).ok_or_else(|| {
+                IcebergError::new(
+                    ErrorKind::DataInvalid,
+                    format!("partition source column {} not found", field.source_column),
+                )
+            })?;
+            builder = builder.add_partition_field(
+                source_field.id,
+                field
+                    .transform
+                    .get_partition_field_name(&field.source_column),
+                field.transform.to_iceberg(),
+            )?;
+        }
+        Ok(builder.build())
+    }
+}
+
+/// Check that the given partition spec matches the default partition spec of an existing iceberg table, i.e. it has the
+/// same transforms on the same source columns.
+/// Data files are partitioned with the former while their partition values are recorded with the latter, so a table whose
+/// source partitioning changed has to be recreated.
+pub(crate) fn validate_iceberg_partition_spec(
+    partition_spec: Option<&PartitionSpec>,
+    iceberg_partition_spec: &IcebergPartitionSpec,
+    schema: &IcebergSchema,
+) -> IcebergResult<()> {
+    let expected_fields = match partition_spec {
+        Some(partition_spec) => partition_spec
+            .to_iceberg_partition_spec(schema)?
+            .fields()
+            .iter()
+            .map(|field| (field.source_id, field.transform))
+            .collect(),
+        None => vec![],
+    };
+    let existing_fields = iceberg_partition_spec
+        .fields()
+        .iter()
+        .map(|field| (field.source_id, field.transform))
+        .collect::<Vec<_>>();
+    if expected_fields != existing_fields {
+        return Err(IcebergError::new(
+            ErrorKind::DataInvalid,
+            format!(
+                "partition spec {partition_spec:?} doesn't match the one of the existing iceberg table {iceberg_partition_spec:?}"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Get partition value of a data file under the given iceberg partition spec, based on lower bounds of its columns.
+/// Precondition: all rows within the data file belong to the same partition, which holds for files written by moonlink.
+pub(crate) fn get_iceberg_partition_value(
+    partition_spec: &IcebergPartitionSpec,
+    schema: &IcebergSchema,
+    lower_bounds: &HashMap<i32, Datum>,
+) -> IcebergResult<Struct> {
+    let mut partition_values = Vec::with_capacity(partition_spec.fields().len());
+    for field in partition_spec.fields() {
+        let unsupported = || {
+            IcebergError::new(
+                ErrorKind::FeatureUnsupported,
+                format!("partition field {} is not supported", field.name),
+            )
+        };
+        let transform =
+            PartitionTransform::from_iceberg(field.transform).ok_or_else(unsupported)?;
+        let source_type = schema
+            .field_by_id(field.source_id)
+            .and_then(|source_field| source_field.field_type.as_primitive_type().cloned())
+            .ok_or_else(unsupported)?;
+        let unit = SourceUnit::from_iceberg(&source_type).ok_or_else(unsupported)?;
+
+        // Null values are ordered last, so a missing lower bound means all values are null.
+        let Some(lower_bound) = lower_bounds.get(&field.source_id) else {
+            partition_values.push(None);
+            continue;
+        };
+        let source_value = match lower_bound.literal() {
+            PrimitiveLiteral::Int(v) => *v as i64,
+            PrimitiveLiteral::Long(v) => *v,
+            _ => return Err(unsupported()),
+        };
+        let value = transform.apply(source_value, unit).ok_or_else(|| {
+            IcebergError::new(
+                ErrorKind::DataInvalid,
+                format!(
+                    "partition field {} overflows for value {source_value}",
+                    field.name
+                ),
+            )
+        })?;
+        // Identity and truncate keep source type, other transforms produce 32-bit values.
+        let is_long = matches!(
+            transform,
+            PartitionTransform::Identity | PartitionTransform::Truncate(_)
+        ) && matches!(
+            source_type,
+            PrimitiveType::Long | PrimitiveType::Timestamp | PrimitiveType::Timestamptz
+        );
+        let literal = if is_long {
+            PrimitiveLiteral::Long(value)
+        } else {
+            PrimitiveLiteral::Int(value as i32)
+        };
+        partition_values.push(Some(Literal::Primitive(literal)));
+    }
+    Ok(Struct::from_iter(partition_values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow_array::{Date32Array, Int32Array};
+    use arrow_schema::{Field, Schema};
+    use iceberg::spec::{NestedField, Type};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_partition_transform() {
+        // 2024-03-15 = 19797 days since epoch.
+        let days = 19797;
+        let micros = days * MICROS_PER_DAY + 5 * MICROS_PER_HOUR;
+        assert_eq!(
+            PartitionTransform::Year.apply(days, SourceUnit::Days),
+            Some(2024 - 1970)
+        );
+        assert_eq!(
+            PartitionTransform::Month.apply(micros, SourceUnit::Micros),
+            Some((2024 - 1970) * 12 + 2)
+        );
+        assert_eq!(
+            PartitionTransform::Day.apply(micros, SourceUnit::Micros),
+            Some(days)
+        );
+        assert_eq!(
+            PartitionTransform::Hour.apply(micros, SourceUnit::Micros),
+            Some(days * 24 + 5)
+        );
+        assert_eq!(
+            PartitionTransform::Truncate(10).apply(-1, SourceUnit::Integer),
+            Some(-10)
+        );
+        assert_eq!(
+            PartitionTransform::Truncate(10).apply(19, SourceUnit::Integer),
+            Some(10)
+        );
+        // Dates before epoch.
+        assert_eq!(
+            PartitionTransform::Year.apply(-1, SourceUnit::Days),
+            Some(-1)
+        );
+        assert_eq!(
+            PartitionTransform::Month.apply(-1, SourceUnit::Days),
+            Some(-1)
+        );
+        // Out of calendar range.
+        assert_eq!(
+            PartitionTransform::Year.apply(i32::MAX as i64, SourceUnit::Days),
+            None
+        );
+        assert_eq!(
+            PartitionTransform::Truncate(10).apply(i64::MIN, SourceUnit::Integer),
+            None
+        );
+    }
+
+    #[test]
+    fn test_split_batch() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("created", DataType::Date32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+                // 2024-01-01, 2024-02-01, null, 2024-01-31
+                Arc::new(Date32Array::from(vec![
+                    Some(19723),
+                    Some(19754),
+                    None,
+                    Some(19753),
+                ])),
+            ],
+        )
+        .unwrap();
+        let partition_spec = PartitionSpec {
+            fields: vec![PartitionField {
+                source_column: "created".to_string(),
+                transform: PartitionTransform::Month,
+            }],
+        };
+        let split_batches = partition_spec.split_batch(&batch).unwrap();
+        let summary = split_batches
+            .iter()
+            .map(|(key, batch, row_indices)| (key.clone(), batch.num_rows(), row_indices.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (vec![Some(648)], 2, vec![0, 3]),
+                (vec![Some(649)], 1, vec![1]),
+                (vec![None], 1, vec![2]),
+            ]
+        );
+
+        // Transform doesn't apply to integer columns.
+        let partition_spec = PartitionSpec {
+            fields: vec![PartitionField {
+                source_column: "id".to_string(),
+                transform: PartitionTransform::Month,
+            }],
+        };
+        assert!(partition_spec.split_batch(&batch).is_err());
+
+        // Date out of calendar range fails rather than panics.
+        let batch = RecordBatch::try_new(
+            batch.schema(),
+            vec![
+                Arc::new(Int32Array::from(vec![1])),
+                Arc::new(Date32Array::from(vec![i32::MAX])),
+            ],
+        )
+        .unwrap();
+        let partition_spec = PartitionSpec {
+            fields: vec![PartitionField {
+                source_column: "created".to_string(),
+                transform: PartitionTransform::Year,
+            }],
+        };
+        assert!(partition_spec.split_batch(&batch).is_err());
+    }
+
+    #[test]
+    fn test_validate_iceberg_partition_spec() {
+        let schema = IcebergSchema::builder()
+            .with_fields(vec![
+                NestedField::required(1, "id", Type::Primitive(PrimitiveType::Int)).into(),
+                NestedField::optional(2, "created", Type::Primitive(PrimitiveType::Date)).into(),
+            ])
+            .build()
+            .unwrap();
+        let partition_spec = PartitionSpec {
+            fields: vec![PartitionField {
+                source_column: "created".to_string(),
+                transform: PartitionTransform::Month,
+            }],
+        };
+        let iceberg_partition_spec = partition_spec
+            .to_iceberg_partition_spec(&schema)
+            .unwrap()
+            .bind(schema.clone())
+            .unwrap();
+        validate_iceberg_partition_spec(Some(&partition_spec), &iceberg_partition_spec, &schema)
+            .unwrap();
+
+        // Source partitioning changed after the iceberg table was created.
+        let day_partition_spec = PartitionSpec {
+            fields: vec![PartitionField {
+                source_column: "created".to_string(),
+                transform: PartitionTransform::Day,
+            }],
+        };
+        assert!(validate_iceberg_partition_spec(
+            Some(&day_partition_spec),
+            &iceberg_partition_spec,
+            &schema
+        )
+        .is_err());
+        assert!(validate_iceberg_partition_spec(None, &iceberg_partition_spec, &schema).is_err());
+    }
+}
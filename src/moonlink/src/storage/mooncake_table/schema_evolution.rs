@@ -0,0 +1,501 @@
+use crate::error::{Error, Result};
+use crate::row::{IdentityProp, MoonlinkRow, RowValue};
+use crate::storage::mooncake_table::AlterTableRequest;
+
+use arrow::array::{new_null_array, ArrayRef, RecordBatch};
+use arrow::compute::cast;
+use arrow_schema::{DataType, Field, FieldRef, Fields, Schema, SchemaRef};
+use std::sync::Arc;
+
+/// Arrow field metadata key for iceberg field id.
+const FIELD_ID_METADATA_KEY: &str = "PARQUET:field_id";
+
+fn get_field_id(field: &Field) -> Option<i32> {
+    field
+        .metadata()
+        .get(FIELD_ID_METADATA_KEY)
+        .and_then(|id| id.parse().ok())
+}
+
+fn get_highest_field_id_for_field(field: &Field) -> i32 {
+    let nested_highest_field_id = match field.data_type() {
+        DataType::List(item) | DataType::LargeList(item) => get_highest_field_id_for_field(item),
+        DataType::Struct(fields) => get_highest_field_id(fields.iter()),
+        DataType::Map(entries, _) => get_highest_field_id_for_field(entries),
+        _ => -1,
+    };
+    nested_highest_field_id.max(get_field_id(field).unwrap_or(-1))
+}
+
+/// Get the highest field id of the given fields, including nested fields; return -1 if none assigned.
+pub(crate) fn get_highest_field_id<'a>(fields: impl IntoIterator<Item = &'a FieldRef>) -> i32 {
+    fields
+        .into_iter()
+        .map(|field| get_highest_field_id_for_field(field))
+        .max()
+        .unwrap_or(-1)
+}
+
+/// Assign new field ids to the given field and its nested fields, after `last_field_id`.
+/// Field ids are never reused, even for dropped columns, so old data files are never interpreted with new columns.
+pub(crate) fn assign_field_ids(field: &Field, last_field_id: &mut i32) -> Field {
+    let data_type = match field.data_type() {
+        DataType::List(item) => DataType::List(Arc::new(assign_field_ids(item, last_field_id))),
+        DataType::LargeList(item) => {
+            DataType::LargeList(Arc::new(assign_field_ids(item, last_field_id)))
+        }
+        DataType::Struct(fields) => DataType::Struct(
+            fields
+                .iter()
+                .map(|field| assign_field_ids(field, last_field_id))
+                .collect::<Fields>(),
+        ),
+        // Only key and value fields carry field ids, the entries field is not an iceberg field.
+        DataType::Map(entries, sorted) => {
+            let DataType::Struct(fields) = entries.data_type() else {
+                panic!("Map entries should be a struct of key and value");
+            };
+            let fields = fields
+                .iter()
+                .map(|field| assign_field_ids(field, last_field_id))
+                .collect::<Fields>();
+            DataType::Map(
+                Arc::new(
+                    entries
+                        .as_ref()
+                        .clone()
+                        .with_data_type(DataType::Struct(fields)),
+                ),
+                *sorted,
+            )
+        }
+        data_type => data_type.clone(),
+    };
+    *last_field_id += 1;
+    let mut metadata = field.metadata().clone();
+    metadata.insert(FIELD_ID_METADATA_KEY.to_string(), last_field_id.to_string());
+    field
+        .clone()
+        .with_data_type(data_type)
+        .with_metadata(metadata)
+}
+
+/// Return whether changing a column type from `old` to `new` is allowed as an iceberg type promotion, which keeps the field id.
+/// Reference: [https://iceberg.apache.org/spec/#schema-evolution]
+pub(crate) fn is_type_promotion(old: &DataType, new: &DataType) -> bool {
+    match (old, new) {
+        (old, new) if old == new => true,
+        (DataType::Int16, DataType::Int32 | DataType::Int64) => true,
+        (DataType::Int32, DataType::Int64) => true,
+        (DataType::Float32, DataType::Float64) => true,
+        (
+            DataType::Decimal128(old_precision, old_scale),
+            DataType::Decimal128(new_precision, new_scale),
+        ) => old_scale == new_scale && new_precision >= old_precision,
+        _ => false,
+    }
+}
+
+/// Return whether promoting a column type from `old` to `new` changes its row values, see [`promote_row_value`].
+fn is_row_value_promotion(old: &DataType, new: &DataType) -> bool {
+    matches!(
+        (old, new),
+        (DataType::Int16 | DataType::Int32, DataType::Int64)
+            | (DataType::Float32, DataType::Float64)
+    )
+}
+
+/// Return whether promoting the given column changes lookup keys of existing rows, which are hashed from identity column values.
+/// Single primitive keys are converted by value, so only floating point promotion changes them.
+fn changes_lookup_key(
+    identity: &IdentityProp,
+    column_index: usize,
+    old: &DataType,
+    new: &DataType,
+) -> bool {
+    match identity {
+        IdentityProp::SinglePrimitiveKey(index) => {
+            *index == column_index && matches!((old, new), (DataType::Float32, DataType::Float64))
+        }
+        IdentityProp::Keys(indices) => {
+            indices.contains(&column_index) && is_row_value_promotion(old, new)
+        }
+        IdentityProp::FullRow => is_row_value_promotion(old, new),
+    }
+}
+
+/// Get identity property for the new schema, with identity columns located by name.
+/// Return error if any identity column is dropped, since rows could no longer be located by their identity.
+pub(crate) fn remap_identity(
+    identity: &IdentityProp,
+    old_schema: &Schema,
+    new_schema_fields: &[FieldRef],
+    renamed_columns: &[(String, String)],
+) -> Result<IdentityProp> {
+    let remap_index = |old_index: usize| {
+        let old_name = old_schema.field(old_index).name();
+        let new_name = renamed_columns
+            .iter()
+            .find(|(old, _)| old == old_name)
+            .map(|(_, new)| new)
+            .unwrap_or(old_name);
+        new_schema_fields
+            .iter()
+            .position(|field| field.name() == new_name)
+            .ok_or_else(|| {
+                Error::UnsupportedSchemaChange(format!(
+                    "identity column {old_name} cannot be dropped"
+                ))
+            })
+    };
+    let identity = match identity {
+        IdentityProp::SinglePrimitiveKey(index) => {
+            IdentityProp::SinglePrimitiveKey(remap_index(*index)?)
+        }
+        IdentityProp::Keys(indices) => IdentityProp::Keys(
+            indices
+                .iter()
+                .map(|index| remap_index(*index))
+                .collect::<Result<Vec<_>>>()?,
+        ),
+        IdentityProp::FullRow => IdentityProp::FullRow,
+    };
+    Ok(identity)
+}
+
+/// Get the schema and identity property after the given schema change, new field ids are assigned after `last_field_id`.
+/// Return error for changes which can't be applied without losing data, and leave `last_field_id` unchanged.
+pub(crate) fn evolve_schema(
+    schema: &Schema,
+    identity: &IdentityProp,
+    alter_table_request: &AlterTableRequest,
+    last_field_id: &mut i32,
+) -> Result<(Schema, IdentityProp)> {
+    let mut new_last_field_id = *last_field_id;
+    let mut new_columns = vec![];
+    for (column_index, field) in schema.fields.iter().enumerate() {
+        if alter_table_request.dropped_columns.contains(field.name()) {
+            continue;
+        }
+        let new_name = alter_table_request
+            .renamed_columns
+            .iter()
+            .find(|(old_name, _)| old_name == field.name())
+            .map(|(_, new_name)| new_name)
+            .unwrap_or(field.name());
+        let mut new_field = field.as_ref().clone().with_name(new_name);
+        if let Some(altered_field) = alter_table_request
+            .altered_columns
+            .iter()
+            .find(|altered_field| altered_field.name() == new_name)
+        {
+            new_field = if field.data_type().equals_datatype(altered_field.data_type()) {
+                // Nested field ids are kept when only nullability changes.
+                new_field.with_nullable(field.is_nullable() || altered_field.is_nullable())
+            } else if is_type_promotion(field.data_type(), altered_field.data_type()) {
+                // Rows written before are indexed by their old identity values, and could no longer be located by deletes, so the table has to be resynced.
+                if changes_lookup_key(
+                    identity,
+                    column_index,
+                    field.data_type(),
+                    altered_field.data_type(),
+                ) {
+                    return Err(Error::UnsupportedSchemaChange(format!(
+                        "identity column {new_name} cannot be changed from {} to {}",
+                        field.data_type(),
+                        altered_field.data_type()
+                    )));
+                }
+                new_field
+                    .with_data_type(altered_field.data_type().clone())
+                    .with_nullable(field.is_nullable() || altered_field.is_nullable())
+            } else {
+                // Existing values can't be converted to the new type, so the table has to be resynced.
+                return Err(Error::UnsupportedSchemaChange(format!(
+                    "column {new_name} cannot be changed from {} to {}",
+                    field.data_type(),
+                    altered_field.data_type()
+                )));
+            };
+        }
+        new_columns.push(Arc::new(new_field));
+    }
+    // Added columns are nullable, since rows written before have no value for them; iceberg also disallows adding required columns.
+    for field in alter_table_request.new_columns.iter() {
+        new_columns.push(Arc::new(
+            assign_field_ids(field, &mut new_last_field_id).with_nullable(true),
+        ));
+    }
+    let new_identity = remap_identity(
+        identity,
+        schema,
+        &new_columns,
+        &alter_table_request.renamed_columns,
+    )?;
+    *last_field_id = new_last_field_id;
+    Ok((
+        Schema::new_with_metadata(new_columns, schema.metadata.clone()),
+        new_identity,
+    ))
+}
+
+/// Get the column index in the old schema for each column in the new schema, matched by field id, or by name if field id not assigned.
+/// Return `None` for columns which don't exist in the old schema, i.e. added columns.
+pub(crate) fn get_column_mapping(old_schema: &Schema, new_schema: &Schema) -> Vec<Option<usize>> {
+    new_schema
+        .fields()
+        .iter()
+        .map(|new_field| match get_field_id(new_field) {
+            Some(field_id) => old_schema
+                .fields()
+                .iter()
+                .position(|old_field| get_field_id(old_field) == Some(field_id)),
+            None => old_schema.index_of(new_field.name()).ok(),
+        })
+        .collect()
+}
+
+/// Adapt a record batch written with an older schema to the given schema.
+/// Columns not in the old schema are filled with nulls, and promoted columns are casted to the new type.
+pub(crate) fn adapt_record_batch(
+    record_batch: &RecordBatch,
+    schema: SchemaRef,
+) -> Result<RecordBatch> {
+    if record_batch.schema_ref().fields() == schema.fields() {
+        return Ok(record_batch.clone());
+    }
+    let column_mapping = get_column_mapping(record_batch.schema_ref(), &schema);
+    let columns = schema
+        .fields()
+        .iter()
+        .zip(column_mapping)
+        .map(|(field, old_index)| {
+            let Some(old_index) = old_index else {
+                return Ok(new_null_array(field.data_type(), record_batch.num_rows()));
+            };
+            let column = record_batch.column(old_index);
+            if column.data_type() == field.data_type() {
+                return Ok(column.clone());
+            }
+            Ok(cast(column, field.data_type())?)
+        })
+        .collect::<Result<Vec<ArrayRef>>>()?;
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+/// Promote a row value to the given column type.
+fn promote_row_value(value: &RowValue, data_type: &DataType) -> RowValue {
+    match (value, data_type) {
+        (RowValue::Int32(value), DataType::Int64) => RowValue::Int64(*value as i64),
+        (RowValue::Float32(value), DataType::Float64) => RowValue::Float64(*value as f64),
+        // Decimal values are stored as i128 regardless of precision.
+        (value, _) => value.clone(),
+    }
+}
+
+/// Adapt a row appended with an older schema to the new schema, with column mapping from [`get_column_mapping`].
+pub(crate) fn adapt_row(
+    row: &MoonlinkRow,
+    column_mapping: &[Option<usize>],
+    schema: &Schema,
+) -> MoonlinkRow {
+    let values = column_mapping
+        .iter()
+        .zip(schema.fields())
+        .map(|(old_index, field)| match old_index {
+            Some(old_index) => promote_row_value(&row.values[*old_index], field.data_type()),
+            None => RowValue::Null,
+        })
+        .collect();
+    MoonlinkRow::new(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow_array::{Int32Array, Int64Array, StringArray};
+    use std::collections::HashMap;
+
+    fn field_with_id(name: &str, data_type: DataType, field_id: i32) -> Field {
+        Field::new(name, data_type, /*nullable=*/ true).with_metadata(HashMap::from([(
+            FIELD_ID_METADATA_KEY.to_string(),
+            field_id.to_string(),
+        )]))
+    }
+
+    #[test]
+    fn test_assign_nested_field_ids() {
+        let item = field_with_id("item", DataType::Int32, 0);
+        let list = field_with_id("list", DataType::List(Arc::new(item)), 1);
+        let mut last_field_id = 5;
+        let list = assign_field_ids(&list, &mut last_field_id);
+        assert_eq!(last_field_id, 7);
+        assert_eq!(get_field_id(&list), Some(7));
+        let DataType::List(item) = list.data_type() else {
+            panic!("Expects list type");
+        };
+        assert_eq!(get_field_id(item), Some(6));
+        assert_eq!(get_highest_field_id([&Arc::new(list)]), 7);
+    }
+
+    #[test]
+    fn test_type_promotion() {
+        assert!(is_type_promotion(&DataType::Int32, &DataType::Int64));
+        assert!(is_type_promotion(&DataType::Float32, &DataType::Float64));
+        assert!(is_type_promotion(
+            &DataType::Decimal128(10, 2),
+            &DataType::Decimal128(12, 2)
+        ));
+        assert!(!is_type_promotion(
+            &DataType::Decimal128(10, 2),
+            &DataType::Decimal128(12, 3)
+        ));
+        assert!(!is_type_promotion(&DataType::Int64, &DataType::Int32));
+        assert!(!is_type_promotion(&DataType::Int32, &DataType::Utf8));
+    }
+
+    #[test]
+    fn test_changes_lookup_key() {
+        let single_key = IdentityProp::SinglePrimitiveKey(0);
+        assert!(!changes_lookup_key(
+            &single_key,
+            0,
+            &DataType::Int32,
+            &DataType::Int64
+        ));
+        assert!(changes_lookup_key(
+            &single_key,
+            0,
+            &DataType::Float32,
+            &DataType::Float64
+        ));
+
+        let keys = IdentityProp::Keys(vec![0]);
+        assert!(changes_lookup_key(
+            &keys,
+            0,
+            &DataType::Int32,
+            &DataType::Int64
+        ));
+        assert!(!changes_lookup_key(
+            &keys,
+            1,
+            &DataType::Int32,
+            &DataType::Int64
+        ));
+        assert!(!changes_lookup_key(
+            &keys,
+            0,
+            &DataType::Decimal128(10, 2),
+            &DataType::Decimal128(12, 2)
+        ));
+
+        assert!(changes_lookup_key(
+            &IdentityProp::FullRow,
+            1,
+            &DataType::Float32,
+            &DataType::Float64
+        ));
+    }
+
+    #[test]
+    fn test_adapt_record_batch() {
+        let old_schema = Arc::new(Schema::new(vec![
+            field_with_id("id", DataType::Int32, 0),
+            field_with_id("age", DataType::Int32, 1),
+            field_with_id("name", DataType::Utf8, 2),
+        ]));
+        let new_schema = Arc::new(Schema::new(vec![
+            field_with_id("id", DataType::Int32, 0),
+            field_with_id("age", DataType::Int64, 1),
+            field_with_id("email", DataType::Utf8, 3),
+        ]));
+        assert_eq!(
+            get_column_mapping(&old_schema, &new_schema),
+            vec![Some(0), Some(1), None]
+        );
+
+        let record_batch = RecordBatch::try_new(
+            old_schema,
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(Int32Array::from(vec![10, 20])),
+                Arc::new(StringArray::from(vec!["a", "b"])),
+            ],
+        )
+        .unwrap();
+        let adapted_batch = adapt_record_batch(&record_batch, new_schema.clone()).unwrap();
+        let expected_batch = RecordBatch::try_new(
+            new_schema,
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(Int64Array::from(vec![10, 20])),
+                Arc::new(StringArray::from(vec![None::<&str>, None])),
+            ],
+        )
+        .unwrap();
+        assert_eq!(adapted_batch, expected_batch);
+    }
+
+    #[test]
+    fn test_adapt_row() {
+        let old_schema = Schema::new(vec![
+            field_with_id("id", DataType::Int32, 0),
+            field_with_id("score", DataType::Float32, 1),
+            field_with_id("name", DataType::Utf8, 2),
+        ]);
+        let new_schema = Schema::new(vec![
+            field_with_id("id", DataType::Int64, 0),
+            field_with_id("score", DataType::Float64, 1),
+            field_with_id("full_name", DataType::Utf8, 2),
+            field_with_id("email", DataType::Utf8, 3),
+        ]);
+        let column_mapping = get_column_mapping(&old_schema, &new_schema);
+        let row = MoonlinkRow::new(vec![
+            RowValue::Int32(1),
+            RowValue::Float32(1.5),
+            RowValue::ByteArray(b"a".to_vec()),
+        ]);
+        let adapted_row = adapt_row(&row, &column_mapping, &new_schema);
+        assert_eq!(
+            adapted_row,
+            MoonlinkRow::new(vec![
+                RowValue::Int64(1),
+                RowValue::Float64(1.5),
+                RowValue::ByteArray(b"a".to_vec()),
+                RowValue::Null,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_remap_identity() {
+        let old_schema = Schema::new(vec![
+            field_with_id("a", DataType::Int32, 0),
+            field_with_id("b", DataType::Int32, 1),
+            field_with_id("c", DataType::Int32, 2),
+        ]);
+        let new_fields: Vec<FieldRef> = vec![
+            Arc::new(field_with_id("d", DataType::Int32, 2)),
+            Arc::new(field_with_id("b", DataType::Int32, 1)),
+        ];
+        let identity = remap_identity(
+            &IdentityProp::Keys(vec![1, 2]),
+            &old_schema,
+            &new_fields,
+            &[("c".to_string(), "d".to_string())],
+        )
+        .unwrap();
+        assert_eq!(identity, IdentityProp::Keys(vec![1, 0]));
+
+        // Dropping an identity column is rejected.
+        let res = remap_identity(
+            &IdentityProp::Keys(vec![0, 1]),
+            &old_schema,
+            &new_fields,
+            &[("c".to_string(), "d".to_string())],
+        );
+        assert!(matches!(res, Err(Error::UnsupportedSchemaChange(_))));
+    }
+}
@@ -11,6 +11,7 @@ use crate::storage::cache::object_storage::base_cache::{
 use crate::storage::cache::object_storage::object_storage_cache::ObjectStorageCache;
 use crate::storage::compaction::table_compaction::{CompactedDataEntry, RemappedRecordLocation};
 use crate::storage::filesystem::accessor::base_filesystem_accessor::BaseFileSystemAccess;
+use crate::storage::iceberg::iceberg_table_config::IcebergDeleteFileFormat;
 use crate::storage::index::{cache_utils as index_cache_utils, FileIndex};
 use crate::storage::mooncake_table::persistence_buffer::UnpersistedRecords;
 use crate::storage::mooncake_table::shared_array::SharedRowBufferSnapshot;
@@ -34,6 +35,9 @@ pub(crate) struct SnapshotTableState {
     /// Mooncake table metadata.
     pub(super) mooncake_table_metadata: Arc<MooncakeTableMetadata>,
 
+    /// File format for persisted row deletions, which decides where persisted deletion records live in the snapshot.
+    pub(super) delete_file_format: IcebergDeleteFileFormat,
+
     /// Current snapshot
     pub(super) current_snapshot: Snapshot,
 
@@ -123,6 +127,7 @@ impl CommittedDeletionToPersist {
 impl SnapshotTableState {
     pub(super) async fn new(
         metadata: Arc<MooncakeTableMetadata>,
+        delete_file_format: IcebergDeleteFileFormat,
         object_storage_cache: ObjectStorageCache,
         filesystem_accessor: Arc<dyn BaseFileSystemAccess>,
         current_snapshot: Snapshot,
@@ -139,6 +144,7 @@ impl SnapshotTableState {
         let table_config = metadata.config.clone();
         Ok(Self {
             mooncake_table_metadata: metadata.clone(),
+            delete_file_format,
             current_snapshot,
             batches,
             rows: None,
@@ -494,7 +500,11 @@ impl SnapshotTableState {
         // Assert and update flush LSN.
         if let Some(new_flush_lsn) = task.new_flush_lsn {
             // Assert flush LSN doesn't regress, if not force snapshot.
-            if self.current_snapshot.data_file_flush_lsn.is_some() && !opt.force_create {
+            // Initial copy commits copied rows at LSN 0 on every checkpoint.
+            if self.current_snapshot.data_file_flush_lsn.is_some()
+                && !opt.force_create
+                && task.new_initial_copy_position.is_none()
+            {
                 ma::assert_lt!(
                     self.current_snapshot.data_file_flush_lsn.unwrap(),
                     new_flush_lsn
@@ -509,6 +519,11 @@ impl SnapshotTableState {
             self.current_snapshot.wal_persistence_metadata = Some(wal_persistence_metadata);
         }
 
+        // Update initial copy position, which is committed together with copied rows.
+        if let Some(initial_copy_position) = task.new_initial_copy_position {
+            self.current_snapshot.initial_copy_position = initial_copy_position;
+        }
+
         if task.new_commit_lsn != 0 {
             self.current_snapshot.snapshot_version = task.new_commit_lsn;
         }
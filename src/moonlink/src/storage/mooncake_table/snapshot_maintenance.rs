@@ -2,11 +2,15 @@ use std::collections::HashSet;
 
 /// This file contains maintaince related features for mooncake snapshot.
 use crate::storage::compaction::table_compaction::SingleFileToCompact;
+use crate::storage::iceberg::iceberg_table_config::IcebergDeleteFileFormat;
+use crate::storage::mooncake_table::delete_vector::BatchDeletionVector;
 use crate::storage::mooncake_table::snapshot::SnapshotTableState;
 use crate::storage::mooncake_table::{
-    DataCompactionPayload, FileIndiceMergePayload, MaintenanceOption, SnapshotTask,
+    DataCompactionPayload, DiskFileEntry, FileIndiceMergePayload, MaintenanceOption, SnapshotTask,
+};
+use crate::storage::storage_utils::{
+    FileId, ProcessedDeletionRecord, RecordLocation, TableId, TableUniqueFileId,
 };
-use crate::storage::storage_utils::{ProcessedDeletionRecord, TableId, TableUniqueFileId};
 use crate::table_notify::{DataCompactionMaintenanceStatus, IndexMergeMaintenanceStatus};
 
 /// Remap single record location after compaction.
@@ -31,6 +35,30 @@ fn remap_record_location_after_compaction(
     true
 }
 
+/// Get persisted deletion records for data file without puffin deletion blob, which happens when deletion records are persisted as iceberg position delete files.
+/// Return `None` if there's no persisted deletion records.
+fn get_persisted_position_deletes(
+    file_id: FileId,
+    disk_file_entry: &DiskFileEntry,
+    unpersisted_deletions: &HashSet<(FileId, usize)>,
+) -> Option<BatchDeletionVector> {
+    if disk_file_entry.puffin_deletion_blob.is_some() {
+        return None;
+    }
+
+    let batch_deletion_vector = &disk_file_entry.batch_deletion_vector;
+    let mut position_deletes = BatchDeletionVector::new(batch_deletion_vector.get_max_rows());
+    for row_idx in batch_deletion_vector.collect_deleted_rows() {
+        if !unpersisted_deletions.contains(&(file_id, row_idx as usize)) {
+            position_deletes.delete_row(row_idx as usize);
+        }
+    }
+    if position_deletes.is_empty() {
+        return None;
+    }
+    Some(position_deletes)
+}
+
 impl SnapshotTableState {
     /// ===============================
     /// Get maintence payload
@@ -77,6 +105,21 @@ impl SnapshotTableState {
 
         // To simplify state management, only compact data files which have been persisted into iceberg table.
         let unpersisted_data_files = self.unpersisted_records.get_unpersisted_data_files_set();
+
+        // Committed but unpersisted deletion records for disk files, which are excluded from persisted position deletes to apply at compaction.
+        let has_position_deletes =
+            self.delete_file_format == IcebergDeleteFileFormat::PositionDelete;
+        let unpersisted_deletions = if has_position_deletes {
+            self.committed_deletion_log
+                .iter()
+                .filter_map(|cur_deletion_log| match &cur_deletion_log.pos {
+                    RecordLocation::DiskFile(file_id, row_idx) => Some((*file_id, *row_idx)),
+                    RecordLocation::MemoryBatch(_, _) => None,
+                })
+                .collect::<HashSet<_>>()
+        } else {
+            HashSet::new()
+        };
         let mut tentative_data_files_to_compact = vec![];
 
         // Number of data files rejected to merge due to unpersistence.
@@ -105,6 +148,15 @@ impl SnapshotTableState {
                 },
                 filepath: cur_data_file.file_path().to_string(),
                 deletion_vector: disk_file_entry.puffin_deletion_blob.clone(),
+                position_deletes: if has_position_deletes {
+                    get_persisted_position_deletes(
+                        cur_data_file.file_id(),
+                        disk_file_entry,
+                        &unpersisted_deletions,
+                    )
+                } else {
+                    None
+                },
             };
             tentative_data_files_to_compact.push(single_file_to_compact);
         }
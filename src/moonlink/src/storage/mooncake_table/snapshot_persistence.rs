@@ -38,6 +38,7 @@ impl SnapshotTableState {
             uuid: uuid::Uuid::new_v4(),
             flush_lsn,
             wal_persistence_metadata,
+            initial_copy_position: self.current_snapshot.initial_copy_position.clone(),
             new_table_schema: None,
             committed_deletion_logs: committed_deletion_to_persist.committed_deletion_logs,
             import_payload: IcebergSnapshotImportPayload {
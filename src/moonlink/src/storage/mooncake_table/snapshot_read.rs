@@ -1,6 +1,7 @@
 use super::data_batches::create_batch_from_rows;
 use crate::error::Result;
 use crate::storage::cache::object_storage::base_cache::CacheTrait;
+use crate::storage::iceberg::iceberg_table_config::IcebergDeleteFileFormat;
 use crate::storage::mooncake_table::snapshot::SnapshotTableState;
 use crate::storage::mooncake_table::snapshot_read_output::{
     DataFileForRead, ReadOutput as SnapshotReadOutput,
@@ -69,9 +70,22 @@ impl SnapshotTableState {
         // Get puffin blobs for deletion vector.
         let mut puffin_cache_handles = vec![];
         let mut deletion_vector_blob_at_read = vec![];
+        let mut ret = Vec::new();
+        // Deletion records persisted as iceberg position delete files only live in batch deletion vector, which also contains all committed deletion records.
+        let read_position_deletes =
+            self.delete_file_format == IcebergDeleteFileFormat::PositionDelete;
         for (idx, (_, disk_deletion_vector)) in self.current_snapshot.disk_files.iter().enumerate()
         {
             if disk_deletion_vector.puffin_deletion_blob.is_none() {
+                if read_position_deletes {
+                    ret.extend(
+                        disk_deletion_vector
+                            .batch_deletion_vector
+                            .collect_deleted_rows()
+                            .into_iter()
+                            .map(|row_idx| (idx as u32, row_idx as u32)),
+                    );
+                }
                 continue;
             }
             let puffin_deletion_blob = disk_deletion_vector.puffin_deletion_blob.as_ref().unwrap();
@@ -100,11 +114,18 @@ impl SnapshotTableState {
         }
 
         // Get committed but un-persisted deletion vector.
-        let mut ret = Vec::new();
         for deletion in self.committed_deletion_log.iter() {
             if let RecordLocation::DiskFile(file_id, row_id) = &deletion.pos {
-                for (id, (file, _)) in self.current_snapshot.disk_files.iter().enumerate() {
+                for (id, (file, disk_deletion_vector)) in
+                    self.current_snapshot.disk_files.iter().enumerate()
+                {
                     if file.file_id() == *file_id {
+                        // Already read from batch deletion vector.
+                        if read_position_deletes
+                            && disk_deletion_vector.puffin_deletion_blob.is_none()
+                        {
+                            break;
+                        }
                         ret.push((id as u32, *row_id as u32));
                         break;
                     }
@@ -33,6 +33,7 @@ pub(crate) fn get_iceberg_table_config(temp_dir: &TempDir) -> IcebergTableConfig
         namespace: vec![ICEBERG_TEST_NAMESPACE.to_string()],
         table_name: ICEBERG_TEST_TABLE.to_string(),
         filesystem_config: FileSystemConfig::FileSystem { root_directory },
+        ..Default::default()
     }
 }
 
@@ -132,6 +133,21 @@ pub(crate) fn create_test_table_metadata_with_config(
     })
 }
 
+/// Test util function to create mooncake table metadata with the given row identity.
+pub(crate) fn create_test_table_metadata_with_identity(
+    local_table_directory: String,
+    identity: RowIdentity,
+) -> Arc<MooncakeTableMetadata> {
+    Arc::new(MooncakeTableMetadata {
+        name: ICEBERG_TEST_TABLE.to_string(),
+        table_id: 0,
+        schema: create_test_arrow_schema(),
+        config: MooncakeTableConfig::new(local_table_directory.clone()),
+        path: std::path::PathBuf::from(local_table_directory),
+        identity,
+    })
+}
+
 /// Test util function to create mooncake table metadata with index merge enable whenever there're two index blocks.
 pub(crate) fn create_test_table_metadata_with_index_merge(
     local_table_directory: String,
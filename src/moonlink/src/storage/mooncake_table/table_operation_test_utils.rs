@@ -470,10 +470,10 @@ pub(crate) async fn alter_table_and_persist_to_iceberg(
         create_mooncake_snapshot_for_test(table, notify_rx).await;
     if let Some(mut iceberg_snapshot_payload) = iceberg_snapshot_payload {
         let alter_table_request = AlterTableRequest {
-            new_columns: vec![],
             dropped_columns: vec!["age".to_string()],
+            ..Default::default()
         };
-        let new_table_metadata = table.alter_table(alter_table_request);
+        let new_table_metadata = table.alter_table(alter_table_request).unwrap();
         iceberg_snapshot_payload.new_table_schema = Some(new_table_metadata.clone());
         let iceberg_snapshot_result =
             create_iceberg_snapshot(table, Some(iceberg_snapshot_payload), notify_rx).await;
@@ -486,8 +486,8 @@ pub(crate) async fn alter_table_and_persist_to_iceberg(
 
 pub(crate) async fn alter_table(table: &mut MooncakeTable) {
     let alter_table_request = AlterTableRequest {
-        new_columns: vec![],
         dropped_columns: vec!["age".to_string()],
+        ..Default::default()
     };
-    table.alter_table(alter_table_request);
+    table.alter_table(alter_table_request).unwrap();
 }
@@ -1,4 +1,4 @@
-/// Secret entry for object object storage access.
+/// Secret entry for object storage access, or iceberg catalog access.
 /// WARNING: Not expected to log anywhere!
 
 #[derive(Clone, Debug, PartialEq)]
@@ -9,6 +9,10 @@ pub enum SecretType {
     Gcs,
     #[cfg(feature = "storage-s3")]
     S3,
+    /// Properties of the iceberg REST catalog client, serialized as a JSON object in [`SecretEntry::secret`].
+    IcebergRestCatalog,
+    /// Connection string of the iceberg SQL catalog database, which could carry credentials, in [`SecretEntry::secret`].
+    IcebergSqlCatalog,
 }
 
 #[derive(Clone, PartialEq)]
@@ -43,11 +47,19 @@ impl SecretEntry {
             SecretType::Gcs => "gcs".to_string(),
             #[cfg(feature = "storage-s3")]
             SecretType::S3 => "s3".to_string(),
+            SecretType::IcebergRestCatalog => "iceberg_rest_catalog".to_string(),
+            SecretType::IcebergSqlCatalog => "iceberg_sql_catalog".to_string(),
         }
     }
 
     /// Convert secret type from string format.
     pub fn convert_secret_type(secret_type: &str) -> SecretType {
+        if secret_type == "iceberg_rest_catalog" {
+            return SecretType::IcebergRestCatalog;
+        }
+        if secret_type == "iceberg_sql_catalog" {
+            return SecretType::IcebergSqlCatalog;
+        }
         #[cfg(feature = "storage-gcs")]
         {
             if secret_type == "gcs" {
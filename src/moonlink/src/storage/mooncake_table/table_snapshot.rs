@@ -1,13 +1,16 @@
 use crate::storage::iceberg::puffin_utils::PuffinBlobRef;
+use crate::storage::iceberg::table_manager::ExpireSnapshotsResult;
 use crate::storage::index::persisted_bucket_hash_map::GlobalIndex;
 /// Items needed for iceberg snapshot.
 use crate::storage::index::FileIndex as MooncakeFileIndex;
 use crate::storage::mooncake_table::delete_vector::BatchDeletionVector;
+use crate::storage::mooncake_table::InitialCopyPosition;
 use crate::storage::mooncake_table::TableMetadata as MooncakeTableMetadata;
 use crate::storage::storage_utils::FileId;
 use crate::storage::storage_utils::MooncakeDataFileRef;
 use crate::storage::wal::wal_persistence_metadata::WalPersistenceMetadata;
 use crate::storage::TableManager;
+use crate::Result;
 
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -133,6 +136,8 @@ pub struct IcebergSnapshotPayload {
     pub(crate) flush_lsn: u64,
     /// WAL persistence metadata.
     pub(crate) wal_persistence_metadata: Option<WalPersistenceMetadata>,
+    /// Initial copy position.
+    pub(crate) initial_copy_position: InitialCopyPosition,
     /// Committed deletion logs included in the current iceberg snapshot persistence operation, which is used to prune after persistence completion.
     pub(crate) committed_deletion_logs: HashSet<(FileId, usize /*row idx*/)>,
     /// New mooncake table schema.
@@ -151,6 +156,7 @@ impl std::fmt::Debug for IcebergSnapshotPayload {
             .field("uuid", &self.uuid)
             .field("flush_lsn", &self.flush_lsn)
             .field("wal_persistence_metadata", &self.wal_persistence_metadata)
+            .field("initial_copy_position", &self.initial_copy_position)
             .field(
                 "committed deletion logs count",
                 &self.committed_deletion_logs.len(),
@@ -312,6 +318,8 @@ pub struct IcebergSnapshotResult {
     pub(crate) flush_lsn: u64,
     /// Iceberg WAL persistence.
     pub(crate) wal_persisted_metadata: Option<WalPersistenceMetadata>,
+    /// Initial copy position sync-ed to iceberg.
+    pub(crate) initial_copy_position: InitialCopyPosition,
     /// Mooncake schema sync-ed to iceberg.
     pub(crate) new_table_schema: Option<Arc<MooncakeTableMetadata>>,
     /// Committed deletion logs included in the current iceberg snapshot persistence operation, which is used to prune after persistence completion.
@@ -331,6 +339,7 @@ impl Clone for IcebergSnapshotResult {
             table_manager: None,
             flush_lsn: self.flush_lsn,
             wal_persisted_metadata: self.wal_persisted_metadata.clone(),
+            initial_copy_position: self.initial_copy_position.clone(),
             new_table_schema: self.new_table_schema.clone(),
             committed_deletion_logs: self.committed_deletion_logs.clone(),
             import_result: self.import_result.clone(),
@@ -421,6 +430,38 @@ impl std::fmt::Debug for FileIndiceMergeResult {
     }
 }
 
+////////////////////////////
+/// Snapshot expiration
+////////////////////////////
+///
+pub struct SnapshotExpirationResult {
+    /// UUID for current snapshot expiration operation, used for observability purpose.
+    pub(crate) uuid: uuid::Uuid,
+    /// Table manager is moved into snapshot expiration, and returned whether the operation succeeds or not.
+    pub(crate) table_manager: Option<Box<dyn TableManager>>,
+    /// Result for snapshot expiration.
+    pub(crate) expire_snapshots_result: Result<ExpireSnapshotsResult>,
+}
+
+impl Clone for SnapshotExpirationResult {
+    fn clone(&self) -> Self {
+        SnapshotExpirationResult {
+            uuid: self.uuid,
+            table_manager: None,
+            expire_snapshots_result: self.expire_snapshots_result.clone(),
+        }
+    }
+}
+
+impl std::fmt::Debug for SnapshotExpirationResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapshotExpirationResult")
+            .field("uuid", &self.uuid)
+            .field("expire_snapshots_result", &self.expire_snapshots_result)
+            .finish()
+    }
+}
+
 /// Util functions to take all data files to import.
 pub fn take_data_files_to_import(
     snapshot_payload: &mut IcebergSnapshotPayload,
@@ -20,4 +20,81 @@ pub struct TableStatus {
     pub flush_lsn: Option<u64>,
     /// Iceberg warehouse location.
     pub iceberg_warehouse_location: String,
+    /// Initial copy progress, only assigned if the table has gone through an initial copy.
+    pub initial_copy_progress: Option<InitialCopyProgress>,
+    /// Status of the replication connection for the table, only assigned for replicated tables.
+    pub replication_status: Option<ReplicationConnectionStatus>,
+    /// Error which stops replication for the table, the table has to be dropped and added again once assigned.
+    pub error: Option<String>,
+}
+
+/// Progress of the initial copy for a table.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct InitialCopyProgress {
+    /// Number of rows copied so far.
+    pub rows_copied: u64,
+    /// Number of bytes read from source so far.
+    pub bytes_copied: u64,
+    /// Estimated total number of rows to copy, taken from row count at copy start.
+    pub estimated_total_rows: Option<u64>,
+    /// Estimated remaining time in seconds, unassigned if not enough information.
+    pub eta_secs: Option<u64>,
+    /// Whether the initial copy has finished.
+    pub finished: bool,
+    /// Error of the last failed copy attempt, failed copies are retried a few times before the table fails.
+    pub last_error: Option<String>,
+}
+
+/// Status of the replication connection which a table is replicated through.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ReplicationConnectionStatus {
+    /// Whether the replication connection is currently established.
+    pub connected: bool,
+    /// Number of failed reconnect attempts since the connection was lost.
+    pub reconnect_attempts: u64,
+    /// Number of times the connection has been re-established.
+    pub reconnect_count: u64,
+    /// Error which caused the last disconnection.
+    pub last_error: Option<String>,
+    /// Health of the replication slot, only assigned after the slot has been checked.
+    pub slot_status: Option<ReplicationSlotStatus>,
+    /// Tables and databases replicated through the connection.
+    pub usage: ReplicationConnectionUsage,
+}
+
+/// Usage of a replication connection, which could be shared by tables of multiple databases.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ReplicationConnectionUsage {
+    /// Replication slot used by the connection.
+    pub slot_name: String,
+    /// Publication used by the connection.
+    pub publication: String,
+    /// Number of tables replicated through the connection.
+    pub table_count: u64,
+    /// Ids of databases which have tables replicated through the connection, in ascending order.
+    pub database_ids: Vec<u32>,
+}
+
+/// Health of the replication slot on the source, as reported by `pg_replication_slots`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ReplicationSlotStatus {
+    /// Replication slot name.
+    pub slot_name: String,
+    /// Whether the slot exists on the source.
+    pub exists: bool,
+    /// Availability of WAL required by the slot, one of `reserved`, `extended`, `unreserved` and `lost`.
+    pub wal_status: Option<String>,
+    /// Oldest LSN which is still required by the slot.
+    pub restart_lsn: Option<u64>,
+    /// LSN up to which the slot has been confirmed by moonlink.
+    pub confirmed_flush_lsn: Option<u64>,
+    /// Number of WAL bytes retained on the source for the slot.
+    pub retained_wal_bytes: Option<u64>,
+}
+
+impl ReplicationSlotStatus {
+    /// Whether the slot has been dropped or invalidated, so replication can no longer resume from it.
+    pub fn is_lost(&self) -> bool {
+        !self.exists || self.wal_status.as_deref() == Some("lost")
+    }
 }
@@ -1,14 +1,16 @@
 /// Table state reader is a class, which fetches current table status.
 use std::sync::Arc;
 
-use crate::storage::mooncake_table::table_status::TableStatus;
+use crate::storage::mooncake_table::table_status::{
+    InitialCopyProgress, ReplicationConnectionStatus, TableStatus,
+};
 use crate::storage::IcebergTableConfig;
 use crate::storage::MooncakeTable;
 use crate::storage::SnapshotTableState;
 use crate::Result;
 
 use arrow_schema::Schema;
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
 
 pub struct TableStatusReader {
     /// Database id.
@@ -19,6 +21,12 @@ pub struct TableStatusReader {
     iceberg_warehouse_location: String,
     /// Table snapshot.
     table_snapshot: Arc<RwLock<SnapshotTableState>>,
+    /// Initial copy progress, only assigned if the table performs initial copy.
+    initial_copy_progress_rx: Option<watch::Receiver<Option<InitialCopyProgress>>>,
+    /// Replication connection status, only assigned for replicated tables.
+    replication_status_rx: Option<watch::Receiver<ReplicationConnectionStatus>>,
+    /// Error which stops replication for the table, only assigned for replicated tables.
+    table_error_rx: Option<watch::Receiver<Option<String>>>,
 }
 
 impl TableStatusReader {
@@ -34,9 +42,36 @@ impl TableStatusReader {
             table_id,
             iceberg_warehouse_location: iceberg_table_config.filesystem_config.get_root_path(),
             table_snapshot,
+            initial_copy_progress_rx: None,
+            replication_status_rx: None,
+            table_error_rx: None,
         }
     }
 
+    /// Register a receiver for initial copy progress, which will be reported as part of table status.
+    pub fn with_initial_copy_progress(
+        mut self,
+        initial_copy_progress_rx: watch::Receiver<Option<InitialCopyProgress>>,
+    ) -> Self {
+        self.initial_copy_progress_rx = Some(initial_copy_progress_rx);
+        self
+    }
+
+    /// Register a receiver for replication connection status, which will be reported as part of table status.
+    pub fn with_replication_status(
+        mut self,
+        replication_status_rx: watch::Receiver<ReplicationConnectionStatus>,
+    ) -> Self {
+        self.replication_status_rx = Some(replication_status_rx);
+        self
+    }
+
+    /// Register a receiver for table failure, which will be reported as part of table status.
+    pub fn with_table_error(mut self, table_error_rx: watch::Receiver<Option<String>>) -> Self {
+        self.table_error_rx = Some(table_error_rx);
+        self
+    }
+
     /// Get current table state.
     pub async fn get_current_table_state(&self) -> Result<TableStatus> {
         let table_snapshot_state = {
@@ -49,6 +84,18 @@ impl TableStatusReader {
             commit_lsn: table_snapshot_state.commit_lsn,
             flush_lsn: table_snapshot_state.flush_lsn,
             iceberg_warehouse_location: self.iceberg_warehouse_location.clone(),
+            initial_copy_progress: self
+                .initial_copy_progress_rx
+                .as_ref()
+                .and_then(|rx| rx.borrow().clone()),
+            replication_status: self
+                .replication_status_rx
+                .as_ref()
+                .map(|rx| rx.borrow().clone()),
+            error: self
+                .table_error_rx
+                .as_ref()
+                .and_then(|rx| rx.borrow().clone()),
         })
     }
 
@@ -70,6 +117,9 @@ mod tests {
     use crate::row::RowValue;
     use crate::storage::mooncake_table::table_creation_test_utils::*;
     use crate::storage::mooncake_table::table_operation_test_utils::*;
+    use crate::storage::mooncake_table::table_status::{
+        ReplicationConnectionUsage, ReplicationSlotStatus,
+    };
 
     /// Fake mooncake database and table id.
     const FAKE_DATABASE_ID: u32 = 0;
@@ -110,6 +160,9 @@ mod tests {
             iceberg_warehouse_location: iceberg_table_config.filesystem_config.get_root_path(),
             commit_lsn: 0,
             flush_lsn: None,
+            initial_copy_progress: None,
+            replication_status: None,
+            error: None,
         };
         assert_eq!(actual_table_state, expected_table_state);
     }
@@ -139,6 +192,9 @@ mod tests {
             iceberg_warehouse_location: iceberg_table_config.filesystem_config.get_root_path(),
             commit_lsn: 0,
             flush_lsn: None,
+            initial_copy_progress: None,
+            replication_status: None,
+            error: None,
         };
         assert_eq!(actual_table_state, expected_table_state);
     }
@@ -172,6 +228,9 @@ mod tests {
             iceberg_warehouse_location: iceberg_table_config.filesystem_config.get_root_path(),
             commit_lsn: 10,
             flush_lsn: None,
+            initial_copy_progress: None,
+            replication_status: None,
+            error: None,
         };
         assert_eq!(actual_table_state, expected_table_state);
     }
@@ -206,10 +265,120 @@ mod tests {
             iceberg_warehouse_location: iceberg_table_config.filesystem_config.get_root_path(),
             commit_lsn: 10,
             flush_lsn: Some(10),
+            initial_copy_progress: None,
+            replication_status: None,
+            error: None,
         };
         assert_eq!(actual_table_state, expected_table_state);
     }
 
+    /// Testing scenario: initial copy progress is reported through the registered receiver.
+    #[tokio::test]
+    async fn test_table_state_with_initial_copy_progress() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let iceberg_table_config = get_iceberg_table_config(&temp_dir);
+
+        let (table, _, _) = create_table_and_iceberg_manager(&temp_dir).await;
+        let (progress_tx, progress_rx) = watch::channel(None);
+        let table_state_reader = TableStatusReader::new(
+            FAKE_DATABASE_ID,
+            FAKE_TABLE_ID,
+            &iceberg_table_config,
+            &table,
+        )
+        .with_initial_copy_progress(progress_rx);
+
+        // No progress reported yet.
+        let actual_table_state = table_state_reader.get_current_table_state().await.unwrap();
+        assert!(actual_table_state.initial_copy_progress.is_none());
+
+        // Report progress and check.
+        let progress = InitialCopyProgress {
+            rows_copied: 10,
+            bytes_copied: 100,
+            estimated_total_rows: Some(20),
+            eta_secs: Some(1),
+            finished: false,
+            last_error: None,
+        };
+        progress_tx.send(Some(progress.clone())).unwrap();
+        let actual_table_state = table_state_reader.get_current_table_state().await.unwrap();
+        assert_eq!(actual_table_state.initial_copy_progress, Some(progress));
+    }
+
+    /// Testing scenario: replication connection status is reported through the registered receiver.
+    #[tokio::test]
+    async fn test_table_state_with_replication_status() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let iceberg_table_config = get_iceberg_table_config(&temp_dir);
+
+        let (table, _, _) = create_table_and_iceberg_manager(&temp_dir).await;
+        let (status_tx, status_rx) = watch::channel(ReplicationConnectionStatus::default());
+        let table_state_reader = TableStatusReader::new(
+            FAKE_DATABASE_ID,
+            FAKE_TABLE_ID,
+            &iceberg_table_config,
+            &table,
+        )
+        .with_replication_status(status_rx);
+
+        // Report a disconnection and check.
+        let status = ReplicationConnectionStatus {
+            connected: false,
+            reconnect_attempts: 2,
+            reconnect_count: 1,
+            last_error: Some("connection closed".to_string()),
+            slot_status: Some(ReplicationSlotStatus {
+                slot_name: "moonlink_slot".to_string(),
+                exists: true,
+                wal_status: Some("lost".to_string()),
+                restart_lsn: None,
+                confirmed_flush_lsn: Some(10),
+                retained_wal_bytes: None,
+            }),
+            usage: ReplicationConnectionUsage {
+                slot_name: "moonlink_slot".to_string(),
+                publication: "moonlink_pub".to_string(),
+                table_count: 2,
+                database_ids: vec![FAKE_DATABASE_ID, FAKE_DATABASE_ID + 1],
+            },
+        };
+        status_tx.send(status.clone()).unwrap();
+        let actual_table_state = table_state_reader.get_current_table_state().await.unwrap();
+        assert_eq!(actual_table_state.replication_status, Some(status));
+    }
+
+    /// Testing scenario: table failure is reported through the registered receiver.
+    #[tokio::test]
+    async fn test_table_state_with_table_error() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let iceberg_table_config = get_iceberg_table_config(&temp_dir);
+
+        let (table, _, _) = create_table_and_iceberg_manager(&temp_dir).await;
+        let (table_error_tx, table_error_rx) = watch::channel(None);
+        let table_state_reader = TableStatusReader::new(
+            FAKE_DATABASE_ID,
+            FAKE_TABLE_ID,
+            &iceberg_table_config,
+            &table,
+        )
+        .with_table_error(table_error_rx);
+
+        // No failure reported yet.
+        let actual_table_state = table_state_reader.get_current_table_state().await.unwrap();
+        assert!(actual_table_state.error.is_none());
+
+        // Report a failure and check.
+        table_error_tx
+            .send(Some("initial copy failed".to_string()))
+            .unwrap();
+        let actual_table_state = table_state_reader.get_current_table_state().await.unwrap();
+        assert_eq!(
+            actual_table_state.error,
+            Some("initial copy failed".to_string())
+        );
+    }
+
     /// =========================
     /// Read table schema
     /// =========================
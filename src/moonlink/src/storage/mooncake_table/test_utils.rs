@@ -50,6 +50,7 @@ pub fn test_iceberg_table_config(context: &TestContext, table_name: &str) -> Ice
         namespace: vec!["default".to_string()],
         table_name: table_name.to_string(),
         filesystem_config: FileSystemConfig::FileSystem { root_directory },
+        ..Default::default()
     }
 }
 
@@ -1,11 +1,16 @@
 use super::test_utils::*;
 use super::*;
+use crate::error::Error;
+use crate::row::RowValue;
 use crate::storage::iceberg::table_manager::MockTableManager;
 use crate::storage::mooncake_table::table_creation_test_utils::*;
 use crate::storage::mooncake_table::table_operation_test_utils::*;
 use crate::storage::mooncake_table::Snapshot as MooncakeSnapshot;
 use crate::FileSystemAccessor;
+use arrow_array::{Int32Array, Int64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field};
 use iceberg::{Error as IcebergError, ErrorKind};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use rstest::*;
 use rstest_reuse::{self, *};
 use tempfile::TempDir;
@@ -544,6 +549,7 @@ async fn test_snapshot_load_failure() {
     let table = MooncakeTable::new_with_table_manager(
         table_metadata,
         Box::new(mock_table_manager),
+        IcebergDeleteFileFormat::default(),
         ObjectStorageCache::default_for_test(&temp_dir),
         FileSystemAccessor::default_for_test(&temp_dir),
     )
@@ -592,6 +598,7 @@ async fn test_snapshot_store_failure() {
     let mut table = MooncakeTable::new_with_table_manager(
         table_metadata,
         Box::new(mock_table_manager),
+        IcebergDeleteFileFormat::default(),
         ObjectStorageCache::default_for_test(&temp_dir),
         FileSystemAccessor::default_for_test(&temp_dir),
     )
@@ -691,3 +698,379 @@ async fn test_alter_table_with_operations() {
     )
     .await;
 }
+
+/// Testing scenario: alter table with an ongoing streaming transaction, rows appended before and after schema change are committed with the new schema.
+#[tokio::test]
+async fn test_alter_table_mid_stream() {
+    let context = TestContext::new("alter_table_mid_stream");
+    let mut table = test_table(
+        &context,
+        "alter_table_mid_stream",
+        IdentityProp::Keys(vec![0]),
+    )
+    .await;
+    let (event_completion_tx, mut event_completion_rx) = mpsc::channel(100);
+    table.register_table_notify(event_completion_tx).await;
+
+    table
+        .append_in_stream_batch(test_row(1, "A", 20), /*xact_id=*/ 1)
+        .unwrap();
+
+    // Widen "age" column and add "email" column.
+    table
+        .alter_table(AlterTableRequest {
+            new_columns: vec![Arc::new(Field::new("email", DataType::Utf8, true))],
+            altered_columns: vec![Arc::new(Field::new("age", DataType::Int64, false))],
+            ..Default::default()
+        })
+        .unwrap();
+    let row_after = MoonlinkRow::new(vec![
+        RowValue::Int32(2),
+        RowValue::ByteArray("B".as_bytes().to_vec()),
+        RowValue::Int64(21),
+        RowValue::ByteArray("b@example.com".as_bytes().to_vec()),
+    ]);
+    table
+        .append_in_stream_batch(row_after, /*xact_id=*/ 1)
+        .unwrap();
+    table
+        .commit_transaction_stream(/*xact_id=*/ 1, /*lsn=*/ 1)
+        .await
+        .unwrap();
+    create_mooncake_snapshot_for_test(&mut table, &mut event_completion_rx).await;
+
+    // Check data file is written in the new schema.
+    let mut table_snapshot = table.snapshot.write().await;
+    let SnapshotReadOutput {
+        data_file_paths, ..
+    } = table_snapshot.request_read().await.unwrap();
+    assert_eq!(data_file_paths.len(), 1);
+    let file = std::fs::File::open(data_file_paths[0].get_file_path()).unwrap();
+    let mut reader = ParquetRecordBatchReaderBuilder::try_new(file)
+        .unwrap()
+        .build()
+        .unwrap();
+    let record_batch = reader.next().unwrap().unwrap();
+    let expected_record_batch = RecordBatch::try_new(
+        table.metadata.schema.clone(),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2])),
+            Arc::new(StringArray::from(vec!["A", "B"])),
+            Arc::new(Int64Array::from(vec![20, 21])),
+            Arc::new(StringArray::from(vec![None, Some("b@example.com")])),
+        ],
+    )
+    .unwrap();
+    assert_eq!(record_batch.columns(), expected_record_batch.columns());
+}
+
+/// Testing scenario: rows appended before schema change, either flushed to data files or buffered in streaming transactions, could still be deleted.
+#[tokio::test]
+async fn test_alter_table_delete_rows_before_alter() {
+    let context = TestContext::new("alter_table_delete_rows_before_alter");
+    let mut table = test_table(
+        &context,
+        "alter_table_delete_rows_before_alter",
+        IdentityProp::Keys(vec![0]),
+    )
+    .await;
+    let (event_completion_tx, mut event_completion_rx) = mpsc::channel(100);
+    table.register_table_notify(event_completion_tx).await;
+
+    // Flush one row to data file, and buffer another one in a streaming transaction.
+    table.append(test_row(1, "A", 20)).unwrap();
+    table.commit(/*lsn=*/ 1);
+    flush_table_and_sync(&mut table, &mut event_completion_rx, /*lsn=*/ 1)
+        .await
+        .unwrap();
+    create_mooncake_snapshot_for_test(&mut table, &mut event_completion_rx).await;
+    table
+        .append_in_stream_batch(test_row(2, "B", 21), /*xact_id=*/ 1)
+        .unwrap();
+
+    // Widen "age" column and add "email" column.
+    table
+        .alter_table(AlterTableRequest {
+            new_columns: vec![Arc::new(Field::new("email", DataType::Utf8, true))],
+            altered_columns: vec![Arc::new(Field::new("age", DataType::Int64, false))],
+            ..Default::default()
+        })
+        .unwrap();
+
+    // Delete both rows appended before schema change with their values in the new schema.
+    let new_schema_row = |id: i32, name: &str, age: i64| {
+        MoonlinkRow::new(vec![
+            RowValue::Int32(id),
+            RowValue::ByteArray(name.as_bytes().to_vec()),
+            RowValue::Int64(age),
+            RowValue::Null,
+        ])
+    };
+    table.delete(new_schema_row(1, "A", 20), /*lsn=*/ 2).await;
+    table.commit(/*lsn=*/ 2);
+    table
+        .delete_in_stream_batch(new_schema_row(2, "B", 21), /*xact_id=*/ 1)
+        .await;
+    table
+        .append_in_stream_batch(new_schema_row(3, "C", 22), /*xact_id=*/ 1)
+        .unwrap();
+    table
+        .commit_transaction_stream(/*xact_id=*/ 1, /*lsn=*/ 3)
+        .await
+        .unwrap();
+    create_mooncake_snapshot_for_test(&mut table, &mut event_completion_rx).await;
+
+    let mut table_snapshot = table.snapshot.write().await;
+    let SnapshotReadOutput {
+        data_file_paths,
+        puffin_cache_handles,
+        position_deletes,
+        deletion_vectors,
+        ..
+    } = table_snapshot.request_read().await.unwrap();
+    verify_files_and_deletions(
+        get_data_files_for_read(&data_file_paths).as_slice(),
+        get_deletion_puffin_files_for_read(&puffin_cache_handles).as_slice(),
+        position_deletes,
+        deletion_vectors,
+        &[3],
+    )
+    .await;
+}
+
+/// Testing scenario: schema changes which change lookup keys of existing rows are rejected, and the table is left unchanged.
+#[tokio::test]
+async fn test_alter_table_rejects_lookup_key_changes() {
+    // Widening key column changes lookup key hash.
+    let context = TestContext::new("alter_table_rejects_key_promotion");
+    let mut table = test_table(
+        &context,
+        "alter_table_rejects_key_promotion",
+        IdentityProp::Keys(vec![0]),
+    )
+    .await;
+    let res = table.alter_table(AlterTableRequest {
+        altered_columns: vec![Arc::new(Field::new("id", DataType::Int64, false))],
+        ..Default::default()
+    });
+    assert!(matches!(res, Err(Error::UnsupportedSchemaChange(_))));
+    assert_eq!(table.metadata.schema.field(0).data_type(), &DataType::Int32);
+
+    // Full row identity hashes all columns, so existing rows, including ones in ongoing streaming transactions, can't be located after columns are added.
+    let context = TestContext::new("alter_table_rejects_full_row_change");
+    let mut table = test_table(
+        &context,
+        "alter_table_rejects_full_row_change",
+        IdentityProp::FullRow,
+    )
+    .await;
+    table
+        .append_in_stream_batch(test_row(1, "A", 20), /*xact_id=*/ 1)
+        .unwrap();
+    let alter_table_request = AlterTableRequest {
+        new_columns: vec![Arc::new(Field::new("email", DataType::Utf8, true))],
+        ..Default::default()
+    };
+    assert!(matches!(
+        table.validate_alter_table(&alter_table_request),
+        Err(Error::UnsupportedSchemaChange(_))
+    ));
+    let res = table.alter_table(alter_table_request);
+    assert!(matches!(res, Err(Error::UnsupportedSchemaChange(_))));
+    assert_eq!(table.metadata.schema.fields().len(), 3);
+    let res = table.alter_table(AlterTableRequest {
+        altered_columns: vec![Arc::new(Field::new("age", DataType::Int64, false))],
+        ..Default::default()
+    });
+    assert!(matches!(res, Err(Error::UnsupportedSchemaChange(_))));
+}
+
+/// Testing scenario: full row identity table rejects adding or dropping columns once it has rows, so rows before the schema change
+/// could still be deleted; table without rows could add columns.
+#[tokio::test]
+async fn test_alter_table_full_row_identity() {
+    let context = TestContext::new("alter_table_full_row_identity");
+    let mut table = test_table(
+        &context,
+        "alter_table_full_row_identity",
+        IdentityProp::FullRow,
+    )
+    .await;
+    let (event_completion_tx, mut event_completion_rx) = mpsc::channel(100);
+    table.register_table_notify(event_completion_tx).await;
+
+    // Flush one row to data file.
+    table.append(test_row(1, "A", 20)).unwrap();
+    table.commit(/*lsn=*/ 1);
+    flush_table_and_sync(&mut table, &mut event_completion_rx, /*lsn=*/ 1)
+        .await
+        .unwrap();
+    create_mooncake_snapshot_for_test(&mut table, &mut event_completion_rx).await;
+
+    // Adding or dropping columns is rejected even without ongoing streaming transactions.
+    let add_column_request = AlterTableRequest {
+        new_columns: vec![Arc::new(Field::new("email", DataType::Utf8, true))],
+        ..Default::default()
+    };
+    assert!(matches!(
+        table.validate_alter_table(&add_column_request),
+        Err(Error::UnsupportedSchemaChange(_))
+    ));
+    let res = table.alter_table(add_column_request.clone());
+    assert!(matches!(res, Err(Error::UnsupportedSchemaChange(_))));
+    let res = table.alter_table(AlterTableRequest {
+        dropped_columns: vec!["age".to_string()],
+        ..Default::default()
+    });
+    assert!(matches!(res, Err(Error::UnsupportedSchemaChange(_))));
+    assert_eq!(table.metadata.schema.fields().len(), 3);
+
+    // Row before the schema change could still be located and deleted.
+    table.delete(test_row(1, "A", 20), /*lsn=*/ 2).await;
+    table.commit(/*lsn=*/ 2);
+    create_mooncake_snapshot_for_test(&mut table, &mut event_completion_rx).await;
+    {
+        let mut table_snapshot = table.snapshot.write().await;
+        let SnapshotReadOutput {
+            data_file_paths,
+            puffin_cache_handles,
+            position_deletes,
+            deletion_vectors,
+            ..
+        } = table_snapshot.request_read().await.unwrap();
+        verify_files_and_deletions(
+            get_data_files_for_read(&data_file_paths).as_slice(),
+            get_deletion_puffin_files_for_read(&puffin_cache_handles).as_slice(),
+            position_deletes,
+            deletion_vectors,
+            &[],
+        )
+        .await;
+    }
+
+    // Table without rows could add columns, and rows after the schema change are deleted with the new schema.
+    let context = TestContext::new("alter_table_full_row_identity_empty");
+    let mut table = test_table(
+        &context,
+        "alter_table_full_row_identity_empty",
+        IdentityProp::FullRow,
+    )
+    .await;
+    let (event_completion_tx, mut event_completion_rx) = mpsc::channel(100);
+    table.register_table_notify(event_completion_tx).await;
+    table.validate_alter_table(&add_column_request).unwrap();
+    table.alter_table(add_column_request).unwrap();
+    let new_schema_row = |id: i32, name: &str| {
+        MoonlinkRow::new(vec![
+            RowValue::Int32(id),
+            RowValue::ByteArray(name.as_bytes().to_vec()),
+            RowValue::Int32(20),
+            RowValue::Null,
+        ])
+    };
+    table.append(new_schema_row(1, "A")).unwrap();
+    table.append(new_schema_row(2, "B")).unwrap();
+    table.commit(/*lsn=*/ 1);
+    flush_table_and_sync(&mut table, &mut event_completion_rx, /*lsn=*/ 1)
+        .await
+        .unwrap();
+    table.delete(new_schema_row(1, "A"), /*lsn=*/ 2).await;
+    table.commit(/*lsn=*/ 2);
+    create_mooncake_snapshot_for_test(&mut table, &mut event_completion_rx).await;
+
+    let mut table_snapshot = table.snapshot.write().await;
+    let SnapshotReadOutput {
+        data_file_paths,
+        puffin_cache_handles,
+        position_deletes,
+        deletion_vectors,
+        ..
+    } = table_snapshot.request_read().await.unwrap();
+    verify_files_and_deletions(
+        get_data_files_for_read(&data_file_paths).as_slice(),
+        get_deletion_puffin_files_for_read(&puffin_cache_handles).as_slice(),
+        position_deletes,
+        deletion_vectors,
+        &[2],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_alter_table_keeps_field_ids() {
+    let context = TestContext::new("alter_table_field_ids");
+    let mut table = test_table(
+        &context,
+        "alter_table_field_ids",
+        IdentityProp::Keys(vec![0]),
+    )
+    .await;
+    let get_field_ids = |table: &MooncakeTable| -> Vec<(String, DataType, String)> {
+        table
+            .metadata
+            .schema
+            .fields
+            .iter()
+            .map(|f| {
+                (
+                    f.name().to_string(),
+                    f.data_type().clone(),
+                    f.metadata().get("PARQUET:field_id").unwrap().clone(),
+                )
+            })
+            .collect()
+    };
+
+    // Rename, widen and add columns in one request.
+    table
+        .alter_table(AlterTableRequest {
+            new_columns: vec![Arc::new(Field::new("email", DataType::Utf8, true))],
+            renamed_columns: vec![("name".to_string(), "full_name".to_string())],
+            altered_columns: vec![Arc::new(Field::new("age", DataType::Int64, false))],
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(
+        get_field_ids(&table),
+        vec![
+            ("id".to_string(), DataType::Int32, "0".to_string()),
+            ("full_name".to_string(), DataType::Utf8, "1".to_string()),
+            ("age".to_string(), DataType::Int64, "2".to_string()),
+            ("email".to_string(), DataType::Utf8, "3".to_string()),
+        ]
+    );
+
+    // Incompatible type change is rejected, and the table is left unchanged.
+    let res = table.alter_table(AlterTableRequest {
+        new_columns: vec![Arc::new(Field::new("phone", DataType::Utf8, true))],
+        altered_columns: vec![Arc::new(Field::new("age", DataType::Utf8, true))],
+        ..Default::default()
+    });
+    assert!(matches!(res, Err(Error::UnsupportedSchemaChange(_))));
+
+    // Dropping an identity column is rejected.
+    let res = table.alter_table(AlterTableRequest {
+        dropped_columns: vec!["id".to_string()],
+        ..Default::default()
+    });
+    assert!(matches!(res, Err(Error::UnsupportedSchemaChange(_))));
+
+    // Field ids of dropped columns are not reused.
+    table
+        .alter_table(AlterTableRequest {
+            new_columns: vec![Arc::new(Field::new("phone", DataType::Utf8, true))],
+            dropped_columns: vec!["email".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(
+        get_field_ids(&table),
+        vec![
+            ("id".to_string(), DataType::Int32, "0".to_string()),
+            ("full_name".to_string(), DataType::Utf8, "1".to_string()),
+            ("age".to_string(), DataType::Int64, "2".to_string()),
+            ("phone".to_string(), DataType::Utf8, "4".to_string()),
+        ]
+    );
+    assert_eq!(table.metadata.identity, IdentityProp::Keys(vec![0]));
+}
@@ -78,6 +78,23 @@ impl TransactionStreamState {
             flushed_files: hashbrown::HashMap::new(),
         }
     }
+
+    /// Cast finalized batches of the mem slice to the new schema on alter table, without changing the stream state.
+    pub(super) fn adapt_batches(
+        &self,
+        schema: Arc<Schema>,
+    ) -> Result<Vec<Option<Arc<RecordBatch>>>> {
+        self.mem_slice.adapt_batches(schema)
+    }
+
+    /// Rebuild the mem slice with the new schema on alter table, with finalized batches casted by [`Self::adapt_batches`].
+    pub(super) fn alter_schema(
+        &mut self,
+        schema: Arc<Schema>,
+        adapted_batches: Vec<Option<Arc<RecordBatch>>>,
+    ) {
+        self.mem_slice.alter_schema(schema, adapted_batches)
+    }
 }
 
 pub(crate) const LSN_START_FOR_STREAMING_XACT: u64 = 0xFFFF_FFFF_0000_0000;
@@ -115,6 +132,7 @@ impl MooncakeTable {
     }
 
     pub fn append_in_stream_batch(&mut self, row: MoonlinkRow, xact_id: u32) -> Result<()> {
+        self.has_rows = true;
         let lookup_key = self.metadata.identity.get_lookup_key(&row);
         let identity_for_key = self.metadata.identity.extract_identity_for_key(&row);
 
@@ -203,6 +221,11 @@ impl MooncakeTable {
         }
     }
 
+    /// Return whether there's an uncommitted transaction stream for the given xact-id.
+    pub(crate) fn has_transaction_stream(&self, xact_id: u32) -> bool {
+        self.transaction_stream_states.contains_key(&xact_id)
+    }
+
     pub fn abort_in_stream_batch(&mut self, xact_id: u32) {
         // Record abortion in snapshot task so we can remove any uncomitted deletions
         self.transaction_stream_states.remove(&xact_id);
@@ -213,12 +236,10 @@ impl MooncakeTable {
 
     pub async fn flush_transaction_stream(&mut self, xact_id: u32) -> Result<()> {
         if let Some(stream_state) = self.transaction_stream_states.get_mut(&xact_id) {
-            let next_file_id = self.next_file_id;
-            self.next_file_id += 1;
             let mut disk_slice = Self::flush_mem_slice(
                 &mut stream_state.mem_slice,
                 &self.metadata,
-                next_file_id,
+                &mut self.next_file_id,
                 None,
                 None,
             )
@@ -245,6 +266,28 @@ impl MooncakeTable {
         Ok(())
     }
 
+    /// Prepare a transaction stream for two-phase commit, its buffered rows are flushed to disk so large prepared transactions are staged ahead of commit.
+    /// The prepared transaction is later committed or rolled back by its GID.
+    pub async fn prepare_transaction_stream(&mut self, xact_id: u32, gid: String) -> Result<()> {
+        // Transaction might have no changes for the current table, still track it so commit and rollback are handled uniformly.
+        self.get_or_create_stream_state(xact_id);
+        self.flush_transaction_stream(xact_id).await?;
+        self.prepared_transactions.insert(gid, xact_id);
+        Ok(())
+    }
+
+    /// Whether the transaction stream with the given xact-id has been prepared.
+    pub(crate) fn is_prepared_transaction(&self, xact_id: u32) -> bool {
+        self.prepared_transactions
+            .values()
+            .any(|prepared_xact_id| *prepared_xact_id == xact_id)
+    }
+
+    /// Take the xact-id of a prepared transaction, return `None` if the GID is not prepared for the current table.
+    pub fn take_prepared_transaction(&mut self, gid: &str) -> Option<u32> {
+        self.prepared_transactions.remove(gid)
+    }
+
     /// Commit a transaction stream commit.
     /// This is used to commit a transaction stream commit that was buffered during initial copy.
     /// It will be applied to the table at the end of initial copy.
@@ -1,12 +1,507 @@
+use crate::row::IdentityProp;
 /// This module contains parquet related constants and utils.
-use parquet::{basic::Compression, file::properties::WriterProperties};
+use crate::storage::clustering_utils;
+use crate::storage::parquet_writer_config::{
+    ParquetCompressionCodec, ParquetSortStrategy, ParquetStatisticsLevel, ParquetWriterConfig,
+};
+use crate::Result;
 
-/// Default compression.
-const DEFAULT_COMPRESSION: Compression = parquet::basic::Compression::SNAPPY;
+use arrow::compute::{lexsort_to_indices, SortColumn, SortOptions};
+use arrow_array::{RecordBatch, UInt32Array};
+use arrow_schema::{DataType, Schema};
+use parquet::basic::{Compression, GzipLevel, ZstdLevel};
+use parquet::errors::ParquetError;
+use parquet::file::metadata::SortingColumn;
+use parquet::file::properties::{EnabledStatistics, WriterProperties};
+use parquet::schema::types::ColumnPath;
 
-pub(crate) fn get_default_parquet_properties() -> WriterProperties {
-    WriterProperties::builder()
-        .set_compression(DEFAULT_COMPRESSION)
-        .set_dictionary_enabled(true)
-        .build()
+/// Get the parquet compression for the given codec and level.
+fn get_compression(codec: ParquetCompressionCodec, level: Option<u32>) -> Result<Compression> {
+    let compression = match codec {
+        ParquetCompressionCodec::Uncompressed => Compression::UNCOMPRESSED,
+        ParquetCompressionCodec::Snappy => Compression::SNAPPY,
+        ParquetCompressionCodec::Lz4 => Compression::LZ4_RAW,
+        ParquetCompressionCodec::Gzip => match level {
+            Some(level) => Compression::GZIP(GzipLevel::try_new(level)?),
+            None => Compression::GZIP(GzipLevel::default()),
+        },
+        ParquetCompressionCodec::Zstd => match level {
+            Some(level) => Compression::ZSTD(ZstdLevel::try_new(level as i32)?),
+            None => Compression::ZSTD(ZstdLevel::default()),
+        },
+    };
+    Ok(compression)
+}
+
+/// Validate parquet writer config against the table schema, so invalid configs are rejected at table creation rather than at flush.
+pub(crate) fn validate_parquet_writer_config(
+    config: &ParquetWriterConfig,
+    schema: &Schema,
+) -> Result<()> {
+    get_compression(config.compression, config.compression_level)?;
+    for sort_field in config.sort_order.iter() {
+        if schema.column_with_name(&sort_field.column).is_none() {
+            return Err(ParquetError::General(format!(
+                "Sort column {} not found",
+                sort_field.column
+            ))
+            .into());
+        }
+    }
+
+    let bloom_filter_config = &config.bloom_filter_config;
+    for column in bloom_filter_config.columns.iter() {
+        if schema.column_with_name(column).is_none() {
+            return Err(
+                ParquetError::General(format!("Bloom filter column {column} not found")).into(),
+            );
+        }
+    }
+    if bloom_filter_config.identity_columns_enabled || !bloom_filter_config.columns.is_empty() {
+        validate_bloom_filter_fpp(bloom_filter_config.fpp)?;
+    }
+    Ok(())
+}
+
+/// Validate bloom filter false positive probability, since parquet writer panics on out-of-range values.
+fn validate_bloom_filter_fpp(fpp: f64) -> Result<()> {
+    if !(fpp > 0.0 && fpp < 1.0) {
+        return Err(ParquetError::General(format!(
+            "Bloom filter fpp should be within (0, 1), but {fpp} is given"
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Get top-level columns to write bloom filters for, which include identity columns (if enabled) and user-listed ones.
+pub(crate) fn get_bloom_filter_columns(
+    config: &ParquetWriterConfig,
+    schema: &Schema,
+    identity: &IdentityProp,
+) -> Result<Vec<String>> {
+    let bloom_filter_config = &config.bloom_filter_config;
+    let mut columns = vec![];
+    // Tables identified by full row have no key columns to look up by.
+    if bloom_filter_config.identity_columns_enabled && *identity != IdentityProp::FullRow {
+        for key_idx in identity.get_key_indices(schema.fields().len()) {
+            columns.push(schema.field(key_idx).name().clone());
+        }
+    }
+    for column in bloom_filter_config.columns.iter() {
+        if schema.column_with_name(column).is_none() {
+            return Err(
+                ParquetError::General(format!("Bloom filter column {column} not found")).into(),
+            );
+        }
+        if !columns.contains(column) {
+            columns.push(column.clone());
+        }
+    }
+    Ok(columns)
+}
+
+/// Get parquet writer properties for data files.
+/// [`sorting_columns`] is only recorded in parquet metadata, it's the caller's responsibility to write rows in the declared order.
+pub(crate) fn get_parquet_properties(
+    config: &ParquetWriterConfig,
+    bloom_filter_columns: &[String],
+    sorting_columns: Option<Vec<SortingColumn>>,
+) -> Result<WriterProperties> {
+    let statistics_enabled = match config.statistics_level {
+        ParquetStatisticsLevel::None => EnabledStatistics::None,
+        ParquetStatisticsLevel::Chunk => EnabledStatistics::Chunk,
+        ParquetStatisticsLevel::Page => EnabledStatistics::Page,
+    };
+    let mut builder = WriterProperties::builder()
+        .set_compression(get_compression(
+            config.compression,
+            config.compression_level,
+        )?)
+        .set_max_row_group_size(config.max_row_group_size)
+        .set_data_page_size_limit(config.data_page_size)
+        .set_dictionary_enabled(config.dictionary_enabled)
+        .set_statistics_enabled(statistics_enabled)
+        .set_sorting_columns(sorting_columns);
+    for (column, enabled) in config.column_dictionary_enabled.iter() {
+        builder =
+            builder.set_column_dictionary_enabled(ColumnPath::from(column.as_str()), *enabled);
+    }
+
+    let bloom_filter_config = &config.bloom_filter_config;
+    if !bloom_filter_columns.is_empty() {
+        validate_bloom_filter_fpp(bloom_filter_config.fpp)?;
+    }
+    for column in bloom_filter_columns.iter() {
+        let column_path = ColumnPath::from(column.as_str());
+        builder = builder
+            .set_column_bloom_filter_enabled(column_path.clone(), true)
+            .set_column_bloom_filter_fpp(column_path.clone(), bloom_filter_config.fpp)
+            .set_column_bloom_filter_ndv(column_path, bloom_filter_config.ndv);
+    }
+    Ok(builder.build())
+}
+
+/// Get the number of parquet leaf columns for the given arrow type.
+fn get_leaf_column_count(data_type: &DataType) -> usize {
+    match data_type {
+        DataType::Struct(fields) => fields
+            .iter()
+            .map(|field| get_leaf_column_count(field.data_type()))
+            .sum(),
+        DataType::List(field)
+        | DataType::LargeList(field)
+        | DataType::FixedSizeList(field, _)
+        | DataType::Map(field, _) => get_leaf_column_count(field.data_type()),
+        _ => 1,
+    }
+}
+
+/// Get parquet sorting columns for the configured sort order, or none if rows are not sorted lexicographically.
+pub(crate) fn get_sorting_columns(
+    schema: &Schema,
+    config: &ParquetWriterConfig,
+) -> Result<Option<Vec<SortingColumn>>> {
+    let sort_order = &config.sort_order;
+    // Rows clustered along a curve are not sorted by any column.
+    if sort_order.is_empty() || config.sort_strategy != ParquetSortStrategy::Linear {
+        return Ok(None);
+    }
+    let mut sorting_columns = Vec::with_capacity(sort_order.len());
+    for sort_field in sort_order.iter() {
+        let (field_idx, _) = schema.column_with_name(&sort_field.column).ok_or_else(|| {
+            ParquetError::General(format!("Sort column {} not found", sort_field.column))
+        })?;
+        // Parquet column index refers to leaf columns, which could differ from arrow field index with nested types.
+        let column_idx = schema.fields()[..field_idx]
+            .iter()
+            .map(|field| get_leaf_column_count(field.data_type()))
+            .sum::<usize>();
+        sorting_columns.push(SortingColumn {
+            column_idx: column_idx as i32,
+            descending: sort_field.descending,
+            nulls_first: sort_field.nulls_first,
+        });
+    }
+    Ok(Some(sorting_columns))
+}
+
+/// Get row indices of the given record batch in the configured sort order and strategy.
+pub(crate) fn get_sorted_indices(
+    record_batch: &RecordBatch,
+    config: &ParquetWriterConfig,
+) -> Result<UInt32Array> {
+    let sort_order = &config.sort_order;
+    if config.sort_strategy != ParquetSortStrategy::Linear {
+        let keys =
+            clustering_utils::get_curve_keys(record_batch, sort_order, config.sort_strategy)?;
+        let mut indices = (0..record_batch.num_rows() as u32).collect::<Vec<_>>();
+        indices.sort_by_key(|idx| keys[*idx as usize]);
+        return Ok(UInt32Array::from(indices));
+    }
+
+    let mut sort_columns = Vec::with_capacity(sort_order.len());
+    for sort_field in sort_order.iter() {
+        let values = record_batch
+            .column_by_name(&sort_field.column)
+            .ok_or_else(|| {
+                ParquetError::General(format!("Sort column {} not found", sort_field.column))
+            })?
+            .clone();
+        sort_columns.push(SortColumn {
+            values,
+            options: Some(SortOptions {
+                descending: sort_field.descending,
+                nulls_first: sort_field.nulls_first,
+            }),
+        });
+    }
+    Ok(lexsort_to_indices(&sort_columns, /*limit=*/ None)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::parquet_writer_config::{ParquetBloomFilterConfig, ParquetSortField};
+
+    use std::sync::Arc;
+
+    use arrow_array::{Int32Array, StringArray};
+    use arrow_schema::{Field, Fields};
+
+    #[test]
+    fn test_get_parquet_properties() {
+        let config = ParquetWriterConfig {
+            compression: ParquetCompressionCodec::Zstd,
+            compression_level: Some(5),
+            max_row_group_size: 1000,
+            data_page_size: 4096,
+            dictionary_enabled: true,
+            column_dictionary_enabled: [("name".to_string(), false)].into_iter().collect(),
+            statistics_level: ParquetStatisticsLevel::Chunk,
+            ..Default::default()
+        };
+        let props = get_parquet_properties(
+            &config,
+            /*bloom_filter_columns=*/ &[],
+            /*sorting_columns=*/ None,
+        )
+        .unwrap();
+        let name_column = ColumnPath::from("name");
+        let id_column = ColumnPath::from("id");
+        assert_eq!(
+            props.compression(&id_column),
+            Compression::ZSTD(ZstdLevel::try_new(5).unwrap())
+        );
+        assert_eq!(props.max_row_group_size(), 1000);
+        assert_eq!(props.data_page_size_limit(), 4096);
+        assert!(props.dictionary_enabled(&id_column));
+        assert!(!props.dictionary_enabled(&name_column));
+        assert_eq!(
+            props.statistics_enabled(&id_column),
+            EnabledStatistics::Chunk
+        );
+
+        // Invalid compression level.
+        let config = ParquetWriterConfig {
+            compression: ParquetCompressionCodec::Gzip,
+            compression_level: Some(100),
+            ..Default::default()
+        };
+        assert!(get_parquet_properties(
+            &config,
+            /*bloom_filter_columns=*/ &[],
+            /*sorting_columns=*/ None
+        )
+        .is_err());
+
+        // Default config.
+        let props = get_parquet_properties(
+            &ParquetWriterConfig::default(),
+            /*bloom_filter_columns=*/ &[],
+            /*sorting_columns=*/ None,
+        )
+        .unwrap();
+        assert_eq!(props.compression(&id_column), Compression::SNAPPY);
+    }
+
+    #[test]
+    fn test_validate_parquet_writer_config() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, true),
+        ]);
+        assert!(validate_parquet_writer_config(&ParquetWriterConfig::default(), &schema).is_ok());
+
+        // Out-of-range gzip level.
+        let config = ParquetWriterConfig {
+            compression: ParquetCompressionCodec::Gzip,
+            compression_level: Some(10),
+            ..Default::default()
+        };
+        assert!(validate_parquet_writer_config(&config, &schema).is_err());
+
+        // Unknown sort column.
+        let config = ParquetWriterConfig {
+            sort_order: vec![ParquetSortField {
+                column: "unknown".to_string(),
+                descending: false,
+                nulls_first: false,
+            }],
+            ..Default::default()
+        };
+        assert!(validate_parquet_writer_config(&config, &schema).is_err());
+
+        // Unknown bloom filter column.
+        let config = ParquetWriterConfig {
+            bloom_filter_config: ParquetBloomFilterConfig {
+                columns: vec!["unknown".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(validate_parquet_writer_config(&config, &schema).is_err());
+
+        // Invalid bloom filter false positive probability.
+        let config = ParquetWriterConfig {
+            bloom_filter_config: ParquetBloomFilterConfig {
+                identity_columns_enabled: true,
+                fpp: 0.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(validate_parquet_writer_config(&config, &schema).is_err());
+    }
+
+    #[test]
+    fn test_sort_order() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "nested",
+                DataType::Struct(Fields::from(vec![
+                    Field::new("a", DataType::Int32, true),
+                    Field::new("b", DataType::Int32, true),
+                ])),
+                true,
+            ),
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let mut config = ParquetWriterConfig {
+            sort_order: vec![
+                ParquetSortField {
+                    column: "name".to_string(),
+                    descending: false,
+                    nulls_first: true,
+                },
+                ParquetSortField {
+                    column: "id".to_string(),
+                    descending: true,
+                    nulls_first: false,
+                },
+            ],
+            ..Default::default()
+        };
+        let sorting_columns = get_sorting_columns(&schema, &config).unwrap().unwrap();
+        assert_eq!(
+            sorting_columns,
+            vec![
+                SortingColumn {
+                    column_idx: 3,
+                    descending: false,
+                    nulls_first: true,
+                },
+                SortingColumn {
+                    column_idx: 2,
+                    descending: true,
+                    nulls_first: false,
+                },
+            ]
+        );
+        assert!(
+            get_sorting_columns(&schema, &ParquetWriterConfig::default())
+                .unwrap()
+                .is_none()
+        );
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let record_batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+                Arc::new(StringArray::from(vec![
+                    Some("b"),
+                    None,
+                    Some("a"),
+                    Some("b"),
+                ])),
+            ],
+        )
+        .unwrap();
+        let indices = get_sorted_indices(&record_batch, &config).unwrap();
+        assert_eq!(indices.values(), &[1, 2, 3, 0]);
+
+        // Clustered rows are not declared as sorted.
+        config.sort_strategy = ParquetSortStrategy::ZOrder;
+        assert!(get_sorting_columns(&schema, &config).unwrap().is_none());
+        let indices = get_sorted_indices(&record_batch, &config).unwrap();
+        assert_eq!(indices.len(), 4);
+    }
+
+    #[test]
+    fn test_bloom_filter_properties() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("age", DataType::Int32, true),
+        ]);
+        let id_column = ColumnPath::from("id");
+        let name_column = ColumnPath::from("name");
+        let age_column = ColumnPath::from("age");
+
+        // No bloom filter by default.
+        let config = ParquetWriterConfig::default();
+        let columns =
+            get_bloom_filter_columns(&config, &schema, &IdentityProp::SinglePrimitiveKey(0))
+                .unwrap();
+        assert!(columns.is_empty());
+
+        // Identity column gets bloom filter once enabled.
+        let config = ParquetWriterConfig {
+            bloom_filter_config: ParquetBloomFilterConfig {
+                identity_columns_enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let columns =
+            get_bloom_filter_columns(&config, &schema, &IdentityProp::SinglePrimitiveKey(0))
+                .unwrap();
+        assert_eq!(columns, vec!["id".to_string()]);
+        let props = get_parquet_properties(&config, &columns, /*sorting_columns=*/ None).unwrap();
+        let bloom_filter_props = props.bloom_filter_properties(&id_column).unwrap();
+        assert_eq!(
+            bloom_filter_props.fpp,
+            ParquetBloomFilterConfig::DEFAULT_FPP
+        );
+        assert_eq!(
+            bloom_filter_props.ndv,
+            ParquetBloomFilterConfig::DEFAULT_NDV
+        );
+        assert!(props.bloom_filter_properties(&name_column).is_none());
+
+        // Tables identified by full row have no key columns for bloom filter.
+        let columns = get_bloom_filter_columns(&config, &schema, &IdentityProp::FullRow).unwrap();
+        assert!(columns.is_empty());
+
+        // User-listed columns are appended after identity columns without duplicates.
+        let config = ParquetWriterConfig {
+            bloom_filter_config: ParquetBloomFilterConfig {
+                identity_columns_enabled: true,
+                columns: vec!["age".to_string(), "id".to_string()],
+                fpp: 0.01,
+                ndv: 100,
+            },
+            ..Default::default()
+        };
+        let columns =
+            get_bloom_filter_columns(&config, &schema, &IdentityProp::Keys(vec![0, 1])).unwrap();
+        assert_eq!(
+            columns,
+            vec!["id".to_string(), "name".to_string(), "age".to_string()]
+        );
+        let props = get_parquet_properties(&config, &columns, /*sorting_columns=*/ None).unwrap();
+        let bloom_filter_props = props.bloom_filter_properties(&age_column).unwrap();
+        assert_eq!(bloom_filter_props.fpp, 0.01);
+        assert_eq!(bloom_filter_props.ndv, 100);
+
+        // Unknown column.
+        let config = ParquetWriterConfig {
+            bloom_filter_config: ParquetBloomFilterConfig {
+                columns: vec!["unknown".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(get_bloom_filter_columns(&config, &schema, &IdentityProp::FullRow).is_err());
+
+        // Invalid false positive probability.
+        let config = ParquetWriterConfig {
+            bloom_filter_config: ParquetBloomFilterConfig {
+                fpp: 1.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(get_parquet_properties(
+            &config,
+            &["id".to_string()],
+            /*sorting_columns=*/ None
+        )
+        .is_err());
+    }
 }
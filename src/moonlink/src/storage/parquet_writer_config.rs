@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use typed_builder::TypedBuilder;
+
+/// Compression codec for parquet data files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParquetCompressionCodec {
+    Uncompressed,
+    #[default]
+    Snappy,
+    Gzip,
+    Lz4,
+    Zstd,
+}
+
+/// Granularity of min/max statistics written into parquet data files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParquetStatisticsLevel {
+    /// No statistics are written, iceberg manifest entries carry no column bounds either.
+    None,
+    /// Statistics are written for each column chunk.
+    Chunk,
+    /// Statistics are written for each column chunk and each data page.
+    #[default]
+    Page,
+}
+
+/// Sort key for rows within one data file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParquetSortField {
+    /// Name of the top-level column to sort by.
+    pub column: String,
+    /// Whether to sort in descending order.
+    pub descending: bool,
+    /// Whether nulls come before non-null values.
+    pub nulls_first: bool,
+}
+
+/// Strategy to order rows by sort fields.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParquetSortStrategy {
+    /// Rows are sorted lexicographically by sort fields in order.
+    #[default]
+    Linear,
+    /// Rows are clustered along a Z-order curve over sort fields, which weighs all of them equally.
+    ZOrder,
+    /// Rows are clustered along a Hilbert curve over sort fields, which keeps better locality than Z-order.
+    Hilbert,
+}
+
+/// Configurations for parquet bloom filters, which let engines skip data files on point predicates.
+#[derive(Clone, Debug, PartialEq, TypedBuilder)]
+pub struct ParquetBloomFilterConfig {
+    /// Whether to write bloom filters for identity columns, which doesn't apply to tables identified by full row.
+    /// It's opt-in since bloom filters are sized by [`ndv`] regardless of the number of rows, which adds up for small data files.
+    #[builder(default)]
+    pub identity_columns_enabled: bool,
+    /// Extra top-level columns to write bloom filters for.
+    #[builder(default)]
+    pub columns: Vec<String>,
+    /// False positive probability, which should be within (0, 1).
+    #[builder(default = ParquetBloomFilterConfig::DEFAULT_FPP)]
+    pub fpp: f64,
+    /// Number of distinct values expected in a row group, which decides bloom filter size.
+    #[builder(default = ParquetBloomFilterConfig::DEFAULT_NDV)]
+    pub ndv: u64,
+}
+
+impl ParquetBloomFilterConfig {
+    pub const DEFAULT_FPP: f64 = 0.05;
+    /// Default value is aligned with [`ParquetWriterConfig::DEFAULT_MAX_ROW_GROUP_SIZE`], so a full row group of distinct values stays within the false positive probability.
+    pub const DEFAULT_NDV: u64 = ParquetWriterConfig::DEFAULT_MAX_ROW_GROUP_SIZE as u64;
+}
+
+impl Default for ParquetBloomFilterConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Configurations for parquet data files written by the table, which apply to both flush and compaction.
+#[derive(Clone, Debug, PartialEq, TypedBuilder)]
+pub struct ParquetWriterConfig {
+    /// Compression codec.
+    #[builder(default)]
+    pub compression: ParquetCompressionCodec,
+    /// Compression level, only applies to gzip and zstd; codec default level is used if unassigned.
+    #[builder(default)]
+    pub compression_level: Option<u32>,
+    /// Max number of rows in a row group.
+    #[builder(default = ParquetWriterConfig::DEFAULT_MAX_ROW_GROUP_SIZE)]
+    pub max_row_group_size: usize,
+    /// Best-effort max size of a data page in bytes.
+    #[builder(default = ParquetWriterConfig::DEFAULT_DATA_PAGE_SIZE)]
+    pub data_page_size: usize,
+    /// Whether dictionary encoding is enabled by default.
+    #[builder(default = true)]
+    pub dictionary_enabled: bool,
+    /// Per-column override for dictionary encoding, keyed by top-level column name.
+    #[builder(default)]
+    pub column_dictionary_enabled: HashMap<String, bool>,
+    /// Statistics level.
+    #[builder(default)]
+    pub statistics_level: ParquetStatisticsLevel,
+    /// Sort order for rows within each flushed or compacted data file, rows are written in arrival order if empty.
+    /// Linear sort order is also recorded as iceberg table sort order.
+    #[builder(default)]
+    pub sort_order: Vec<ParquetSortField>,
+    /// Strategy to order rows by [`sort_order`], direction and null order of sort fields apply to all strategies.
+    #[builder(default)]
+    pub sort_strategy: ParquetSortStrategy,
+    /// Config for bloom filters.
+    #[builder(default)]
+    pub bloom_filter_config: ParquetBloomFilterConfig,
+}
+
+impl ParquetWriterConfig {
+    pub const DEFAULT_MAX_ROW_GROUP_SIZE: usize = 1024 * 1024;
+    pub const DEFAULT_DATA_PAGE_SIZE: usize = 1024 * 1024;
+}
+
+impl Default for ParquetWriterConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
@@ -1,4 +1,5 @@
 pub mod wal_persistence_metadata;
+pub(crate) mod prepared_transaction_wal;
 use crate::row::MoonlinkRow;
 use crate::storage::filesystem::accessor::base_filesystem_accessor::BaseFileSystemAccess;
 use crate::storage::filesystem::accessor::filesystem_accessor::FileSystemAccessor;
@@ -32,6 +33,16 @@ enum WalEventEnum {
     StreamFlush {
         xact_id: u32,
     },
+    PrepareTransaction {
+        xact_id: u32,
+        gid: String,
+    },
+    CommitPrepared {
+        gid: String,
+    },
+    RollbackPrepared {
+        gid: String,
+    },
 }
 
 pub struct PersistAndTruncateResult {
@@ -76,6 +87,16 @@ impl WalEvent {
             TableEvent::Commit { xact_id, .. } => WalEventEnum::Commit { xact_id: *xact_id },
             TableEvent::StreamAbort { xact_id } => WalEventEnum::StreamAbort { xact_id: *xact_id },
             TableEvent::StreamFlush { xact_id } => WalEventEnum::StreamFlush { xact_id: *xact_id },
+            TableEvent::PrepareTransaction { xact_id, gid } => WalEventEnum::PrepareTransaction {
+                xact_id: *xact_id,
+                gid: gid.clone(),
+            },
+            TableEvent::CommitPrepared { gid, .. } => {
+                WalEventEnum::CommitPrepared { gid: gid.clone() }
+            }
+            TableEvent::RollbackPrepared { gid } => {
+                WalEventEnum::RollbackPrepared { gid: gid.clone() }
+            }
             _ => {
                 unimplemented!("Invalid table event for WAL: {:?}", table_event)
             }
@@ -107,6 +128,13 @@ impl WalEvent {
             },
             WalEventEnum::StreamAbort { xact_id } => TableEvent::StreamAbort { xact_id },
             WalEventEnum::StreamFlush { xact_id } => TableEvent::StreamFlush { xact_id },
+            WalEventEnum::PrepareTransaction { xact_id, gid } => {
+                TableEvent::PrepareTransaction { xact_id, gid }
+            }
+            WalEventEnum::CommitPrepared { gid } => {
+                TableEvent::CommitPrepared { gid, lsn: self.lsn }
+            }
+            WalEventEnum::RollbackPrepared { gid } => TableEvent::RollbackPrepared { gid },
         }
     }
 }
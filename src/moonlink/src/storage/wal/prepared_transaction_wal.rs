@@ -0,0 +1,276 @@
+/// Local WAL for transaction streams, which keeps prepared two-phase transactions across restarts.
+///
+/// The source confirms its replication position past PREPARE once the prepared transaction is persisted here, and
+/// doesn't replay it after restart; so its changes are recovered from WAL and staged again until COMMIT PREPARED.
+use crate::storage::filesystem::filesystem_config::FileSystemConfig;
+use crate::storage::wal::{PersistAndTruncateResult, WalEvent, WalManager};
+use crate::table_notify::TableEvent;
+use crate::Result;
+use futures::StreamExt;
+use std::collections::{HashMap, HashSet};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use tokio::sync::watch;
+
+/// Prefix of the directory holding WAL files of a transaction stream, followed by its xact-id.
+const XACT_DIRECTORY_PREFIX: &str = "xact_";
+
+/// Number of buffered events for a transaction stream, after which they're persisted before the transaction is prepared.
+#[cfg(debug_assertions)]
+pub(crate) const PERSIST_EVENT_THRESHOLD: usize = 16;
+#[cfg(not(debug_assertions))]
+pub(crate) const PERSIST_EVENT_THRESHOLD: usize = 4096;
+
+/// Records events of transaction streams, each into its own WAL directory.
+///
+/// Events are buffered in memory and persisted in chunks, a transaction is durable once its PREPARE is persisted.
+/// WAL of a transaction is deleted once it's resolved, except for committed prepared transactions, whose WAL is kept
+/// until the commit is persisted into iceberg.
+pub(crate) struct PreparedTransactionWal {
+    /// Local directory to place WAL of transaction streams.
+    directory: PathBuf,
+    /// WAL of unresolved transaction streams, keyed by xact-id.
+    stream_wals: HashMap<u32, WalManager>,
+    /// Prepared transactions which have been persisted, maps from GID to xact-id.
+    prepared_transactions: HashMap<String, u32>,
+    /// Committed prepared transactions whose commit hasn't been persisted into iceberg, as (commit LSN, xact-id).
+    committed_transactions: Vec<(u64, u32)>,
+    /// Notifies GIDs of prepared transactions which have been persisted.
+    prepared_gids_tx: watch::Sender<HashSet<String>>,
+}
+
+impl PreparedTransactionWal {
+    /// Recover prepared transactions from WAL under the given directory, and return their events to replay in order.
+    ///
+    /// WAL of unprepared transactions is deleted, since the source replays them; so is WAL of transactions whose commit
+    /// has been persisted into iceberg, i.e. at or before `iceberg_snapshot_lsn`.
+    pub(crate) async fn recover(
+        directory: PathBuf,
+        iceberg_snapshot_lsn: Option<u64>,
+        prepared_gids_tx: watch::Sender<HashSet<String>>,
+    ) -> Result<(Self, Vec<TableEvent>)> {
+        let mut wal = Self {
+            directory,
+            stream_wals: HashMap::new(),
+            prepared_transactions: HashMap::new(),
+            committed_transactions: Vec::new(),
+            prepared_gids_tx,
+        };
+        let mut events_to_replay = vec![];
+        let mut entries = match tokio::fs::read_dir(&wal.directory).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                wal.notify_prepared_gids();
+                return Ok((wal, events_to_replay));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut xact_ids = vec![];
+        while let Some(entry) = entries.next_entry().await? {
+            let xact_id = entry
+                .file_name()
+                .to_str()
+                .and_then(|file_name| file_name.strip_prefix(XACT_DIRECTORY_PREFIX))
+                .and_then(|xact_id| xact_id.parse::<u32>().ok());
+            if let Some(xact_id) = xact_id {
+                xact_ids.push(xact_id);
+            }
+        }
+        xact_ids.sort();
+
+        for xact_id in xact_ids {
+            let mut stream_wal = wal.create_stream_wal(xact_id);
+            let wal_files: Vec<Vec<TableEvent>> = WalManager::recover_flushed_wals(
+                stream_wal.file_system_accessor.clone(),
+                /*start_file_number=*/ 0,
+                /*begin_from_lsn=*/ 0,
+            )
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<_>>()?;
+            let file_count = wal_files.len() as u64;
+            let events: Vec<TableEvent> = wal_files.into_iter().flatten().collect();
+
+            let gid = events.iter().find_map(|event| match event {
+                TableEvent::PrepareTransaction { gid, .. } => Some(gid.clone()),
+                _ => None,
+            });
+            let commit_persisted = events.iter().any(|event| match event {
+                TableEvent::CommitPrepared { lsn, .. } => {
+                    iceberg_snapshot_lsn.is_some_and(|snapshot_lsn| *lsn <= snapshot_lsn)
+                }
+                _ => false,
+            });
+            let Some(gid) = gid.filter(|_| !commit_persisted) else {
+                wal.remove_stream_wal(xact_id).await?;
+                continue;
+            };
+
+            // Commit which hasn't been persisted is replayed by the source.
+            events_to_replay.extend(
+                events
+                    .into_iter()
+                    .filter(|event| !matches!(event, TableEvent::CommitPrepared { .. })),
+            );
+            stream_wal.curr_file_number = file_count;
+            wal.stream_wals.insert(xact_id, stream_wal);
+            wal.prepared_transactions.insert(gid, xact_id);
+        }
+        wal.notify_prepared_gids();
+        Ok((wal, events_to_replay))
+    }
+
+    /// Record the event if it belongs to a transaction stream.
+    pub(crate) async fn record(&mut self, event: &TableEvent) -> Result<()> {
+        match event {
+            TableEvent::Append {
+                xact_id: Some(xact_id),
+                is_copied: false,
+                ..
+            }
+            | TableEvent::Delete {
+                xact_id: Some(xact_id),
+                ..
+            } => {
+                // Prepared transactions take no further changes.
+                if self.is_prepared(*xact_id) {
+                    return Ok(());
+                }
+                let stream_wal = self.get_or_create_stream_wal(*xact_id);
+                // Stream events carry placeholder LSNs which aren't monotonic, commit LSN is only known at commit.
+                stream_wal.in_mem_wal.buf.push(WalEvent::new(event, 0));
+                if stream_wal.in_mem_wal.buf.len() >= PERSIST_EVENT_THRESHOLD {
+                    Self::persist(stream_wal).await?;
+                }
+            }
+            TableEvent::PrepareTransaction { xact_id, gid } => {
+                let stream_wal = self.get_or_create_stream_wal(*xact_id);
+                stream_wal.in_mem_wal.buf.push(WalEvent::new(event, 0));
+                if let Err(e) = Self::persist(stream_wal).await {
+                    // The transaction isn't durable, so it's replayed by the source after restart.
+                    self.remove_stream_wal(*xact_id).await?;
+                    return Err(e);
+                }
+                self.prepared_transactions.insert(gid.clone(), *xact_id);
+                self.notify_prepared_gids();
+            }
+            TableEvent::CommitPrepared { gid, lsn } => {
+                let Some(xact_id) = self.prepared_transactions.remove(gid) else {
+                    return Ok(());
+                };
+                self.notify_prepared_gids();
+                // Record the commit, so the transaction isn't recovered once the commit has been persisted.
+                if let Some(mut stream_wal) = self.stream_wals.remove(&xact_id) {
+                    stream_wal.in_mem_wal.buf.push(WalEvent::new(event, 0));
+                    Self::persist(&mut stream_wal).await?;
+                }
+                self.committed_transactions.push((*lsn, xact_id));
+            }
+            TableEvent::RollbackPrepared { gid } => {
+                if let Some(xact_id) = self.prepared_transactions.remove(gid) {
+                    self.notify_prepared_gids();
+                    self.remove_stream_wal(xact_id).await?;
+                }
+            }
+            TableEvent::Commit {
+                xact_id: Some(xact_id),
+                ..
+            }
+            | TableEvent::CommitFlush {
+                xact_id: Some(xact_id),
+                ..
+            }
+            | TableEvent::StreamAbort { xact_id } => {
+                if !self.is_prepared(*xact_id) {
+                    self.remove_stream_wal(*xact_id).await?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Delete WAL of committed prepared transactions, whose commit has been persisted into iceberg.
+    pub(crate) async fn truncate(&mut self, iceberg_flush_lsn: u64) -> Result<()> {
+        let (persisted, unpersisted) = std::mem::take(&mut self.committed_transactions)
+            .into_iter()
+            .partition(|(commit_lsn, _)| *commit_lsn <= iceberg_flush_lsn);
+        self.committed_transactions = unpersisted;
+        for (_, xact_id) in persisted {
+            self.remove_stream_wal(xact_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Delete all WAL, used when the table is dropped.
+    pub(crate) async fn drop_wal(self) -> Result<()> {
+        match tokio::fs::remove_dir_all(&self.directory).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn is_prepared(&self, xact_id: u32) -> bool {
+        self.prepared_transactions
+            .values()
+            .any(|prepared_xact_id| *prepared_xact_id == xact_id)
+    }
+
+    fn notify_prepared_gids(&self) {
+        self.prepared_gids_tx
+            .send_replace(self.prepared_transactions.keys().cloned().collect());
+    }
+
+    fn get_stream_directory(&self, xact_id: u32) -> PathBuf {
+        self.directory
+            .join(format!("{XACT_DIRECTORY_PREFIX}{xact_id}"))
+    }
+
+    fn create_stream_wal(&self, xact_id: u32) -> WalManager {
+        WalManager::new(FileSystemConfig::FileSystem {
+            root_directory: self
+                .get_stream_directory(xact_id)
+                .to_str()
+                .unwrap()
+                .to_string(),
+        })
+    }
+
+    fn get_or_create_stream_wal(&mut self, xact_id: u32) -> &mut WalManager {
+        if !self.stream_wals.contains_key(&xact_id) {
+            let stream_wal = self.create_stream_wal(xact_id);
+            self.stream_wals.insert(xact_id, stream_wal);
+        }
+        self.stream_wals.get_mut(&xact_id).unwrap()
+    }
+
+    /// Persist buffered events of a transaction stream into a new WAL file.
+    /// Events are kept in memory if persistence fails, so they're retried later.
+    async fn persist(stream_wal: &mut WalManager) -> Result<()> {
+        let wal_file_info = stream_wal.get_to_persist_wal_file_info();
+        WalManager::persist(
+            stream_wal.file_system_accessor.clone(),
+            &stream_wal.in_mem_wal.buf,
+            &wal_file_info,
+        )
+        .await?;
+        stream_wal.in_mem_wal.buf.clear();
+        stream_wal.handle_completed_persist_and_truncate(&PersistAndTruncateResult {
+            file_persisted: Some(wal_file_info),
+            highest_deleted_file: None,
+        });
+        Ok(())
+    }
+
+    async fn remove_stream_wal(&mut self, xact_id: u32) -> Result<()> {
+        self.stream_wals.remove(&xact_id);
+        match tokio::fs::remove_dir_all(self.get_stream_directory(xact_id)).await {
+            Ok(()) => Ok(()),
+            // WAL files are only created once events are persisted.
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
@@ -175,6 +175,41 @@ pub fn assert_ingestion_events_equal(actual: &TableEvent, expected: &TableEvent)
         ) => {
             assert_eq!(xact1, xact2, "StreamFlush events have different xact_ids");
         }
+        (
+            TableEvent::PrepareTransaction {
+                xact_id: xact1,
+                gid: gid1,
+            },
+            TableEvent::PrepareTransaction {
+                xact_id: xact2,
+                gid: gid2,
+            },
+        ) => {
+            assert_eq!(
+                xact1, xact2,
+                "PrepareTransaction events have different xact_ids"
+            );
+            assert_eq!(gid1, gid2, "PrepareTransaction events have different GIDs");
+        }
+        (
+            TableEvent::CommitPrepared {
+                gid: gid1,
+                lsn: lsn1,
+            },
+            TableEvent::CommitPrepared {
+                gid: gid2,
+                lsn: lsn2,
+            },
+        ) => {
+            assert_eq!(gid1, gid2, "CommitPrepared events have different GIDs");
+            assert_eq!(lsn1, lsn2, "CommitPrepared events have different LSNs");
+        }
+        (
+            TableEvent::RollbackPrepared { gid: gid1 },
+            TableEvent::RollbackPrepared { gid: gid2 },
+        ) => {
+            assert_eq!(gid1, gid2, "RollbackPrepared events have different GIDs");
+        }
         _ => {
             panic!("Event types don't match: {actual:?} vs {expected:?}");
         }
@@ -1,10 +1,15 @@
 use crate::storage::mooncake_table::test_utils::{test_row, TestContext};
+use crate::storage::wal::prepared_transaction_wal::{
+    PreparedTransactionWal, PERSIST_EVENT_THRESHOLD,
+};
 use crate::storage::wal::test_utils::*;
 use crate::storage::wal::WalManager;
 use crate::table_notify::TableEvent;
 use crate::FileSystemConfig;
 use futures::StreamExt;
+use std::collections::HashSet;
 use tokio::fs;
+use tokio::sync::watch;
 
 #[tokio::test]
 async fn test_wal_insert_persist_files() {
@@ -299,6 +304,21 @@ async fn test_wal_recovery_mixed_event_types() {
         },
         TableEvent::StreamAbort { xact_id: 1 },
         TableEvent::StreamFlush { xact_id: 2 },
+        TableEvent::PrepareTransaction {
+            xact_id: 2,
+            gid: "gid-2".to_string(),
+        },
+        TableEvent::CommitPrepared {
+            gid: "gid-2".to_string(),
+            lsn: 104,
+        },
+        TableEvent::PrepareTransaction {
+            xact_id: 3,
+            gid: "gid-3".to_string(),
+        },
+        TableEvent::RollbackPrepared {
+            gid: "gid-3".to_string(),
+        },
     ];
 
     for event in &events {
@@ -371,3 +391,166 @@ async fn test_wal_multiple_persist_truncate_recovery() {
         assert_ingestion_events_vectors_equal(&recovered_events, &expected_events);
     }
 }
+
+/// Number of rows for transaction streams, which are persisted in multiple chunks.
+const STREAM_ROW_COUNT: i32 = PERSIST_EVENT_THRESHOLD as i32 * 2 + 8;
+
+/// Create append events for the given transaction stream.
+fn create_stream_append_events(xact_id: u32, row_count: i32) -> Vec<TableEvent> {
+    (0..row_count)
+        .map(|id| TableEvent::Append {
+            row: test_row(id, "Alice", 30),
+            xact_id: Some(xact_id),
+            lsn: 0,
+            is_copied: false,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_prepared_transaction_wal_recovery() {
+    let context = TestContext::new("prepared_transaction_wal_recovery");
+    let (prepared_gids_tx, prepared_gids_rx) = watch::channel(HashSet::new());
+    let (mut wal, events) =
+        PreparedTransactionWal::recover(context.path(), None, prepared_gids_tx.clone())
+            .await
+            .unwrap();
+    assert!(events.is_empty());
+
+    // Prepared transaction, with more events than persisted at once.
+    let mut prepared_events = create_stream_append_events(/*xact_id=*/ 1, STREAM_ROW_COUNT);
+    prepared_events.push(TableEvent::PrepareTransaction {
+        xact_id: 1,
+        gid: "gid-1".to_string(),
+    });
+    // Unprepared transaction, which is replayed by the source after restart.
+    let unprepared_events = create_stream_append_events(/*xact_id=*/ 2, STREAM_ROW_COUNT);
+    // Committed transaction stream.
+    let mut committed_events = create_stream_append_events(/*xact_id=*/ 3, STREAM_ROW_COUNT);
+    committed_events.push(TableEvent::Commit {
+        lsn: 10,
+        xact_id: Some(3),
+    });
+    // Events are persisted in chunks, the last of which is kept in memory until the transaction is prepared.
+    for event in prepared_events
+        .iter()
+        .chain(unprepared_events.iter())
+        .chain(committed_events.iter())
+    {
+        wal.record(event).await.unwrap();
+    }
+    assert_eq!(
+        *prepared_gids_rx.borrow(),
+        HashSet::from(["gid-1".to_string()])
+    );
+    assert!(fs::try_exists(context.path().join("xact_2")).await.unwrap());
+    assert!(!fs::try_exists(context.path().join("xact_3")).await.unwrap());
+
+    // Only the prepared transaction is recovered, WAL of the unprepared one is deleted.
+    let (_, recovered_events) =
+        PreparedTransactionWal::recover(context.path(), None, prepared_gids_tx)
+            .await
+            .unwrap();
+    assert_ingestion_events_vectors_equal(&recovered_events, &prepared_events);
+    assert!(!fs::try_exists(context.path().join("xact_2")).await.unwrap());
+    assert_eq!(
+        *prepared_gids_rx.borrow(),
+        HashSet::from(["gid-1".to_string()])
+    );
+}
+
+#[tokio::test]
+async fn test_prepared_transaction_wal_resolution() {
+    let context = TestContext::new("prepared_transaction_wal_resolution");
+    let (prepared_gids_tx, prepared_gids_rx) = watch::channel(HashSet::new());
+    let (mut wal, _) =
+        PreparedTransactionWal::recover(context.path(), None, prepared_gids_tx.clone())
+            .await
+            .unwrap();
+
+    let mut prepared_events =
+        create_stream_append_events(/*xact_id=*/ 1, /*row_count=*/ 1);
+    prepared_events.push(TableEvent::PrepareTransaction {
+        xact_id: 1,
+        gid: "gid-1".to_string(),
+    });
+    let mut rollback_events =
+        create_stream_append_events(/*xact_id=*/ 2, /*row_count=*/ 1);
+    rollback_events.push(TableEvent::PrepareTransaction {
+        xact_id: 2,
+        gid: "gid-2".to_string(),
+    });
+    for event in prepared_events.iter().chain(rollback_events.iter()) {
+        wal.record(event).await.unwrap();
+    }
+
+    // WAL of rolled back transaction is deleted right away.
+    wal.record(&TableEvent::RollbackPrepared {
+        gid: "gid-2".to_string(),
+    })
+    .await
+    .unwrap();
+    assert!(!fs::try_exists(context.path().join("xact_2")).await.unwrap());
+    assert_eq!(
+        *prepared_gids_rx.borrow(),
+        HashSet::from(["gid-1".to_string()])
+    );
+
+    // WAL of committed transaction is kept until the commit is persisted.
+    let commit_prepared = TableEvent::CommitPrepared {
+        gid: "gid-1".to_string(),
+        lsn: 100,
+    };
+    wal.record(&commit_prepared).await.unwrap();
+    assert!(prepared_gids_rx.borrow().is_empty());
+    assert!(fs::try_exists(context.path().join("xact_1")).await.unwrap());
+
+    // Commit which hasn't been persisted is replayed by the source, so the transaction is recovered as prepared.
+    let (mut wal, recovered_events) =
+        PreparedTransactionWal::recover(context.path(), Some(50), prepared_gids_tx.clone())
+            .await
+            .unwrap();
+    assert_ingestion_events_vectors_equal(&recovered_events, &prepared_events);
+    assert_eq!(
+        *prepared_gids_rx.borrow(),
+        HashSet::from(["gid-1".to_string()])
+    );
+
+    // WAL is deleted once the replayed commit is persisted.
+    wal.record(&commit_prepared).await.unwrap();
+    wal.truncate(/*iceberg_flush_lsn=*/ 50).await.unwrap();
+    assert!(fs::try_exists(context.path().join("xact_1")).await.unwrap());
+    wal.truncate(/*iceberg_flush_lsn=*/ 100).await.unwrap();
+    assert!(local_dir_is_empty(&context.path()).await);
+}
+
+#[tokio::test]
+async fn test_prepared_transaction_wal_skips_persisted_commit() {
+    let context = TestContext::new("prepared_transaction_wal_persisted_commit");
+    let (prepared_gids_tx, prepared_gids_rx) = watch::channel(HashSet::new());
+    let (mut wal, _) =
+        PreparedTransactionWal::recover(context.path(), None, prepared_gids_tx.clone())
+            .await
+            .unwrap();
+    let mut events = create_stream_append_events(/*xact_id=*/ 1, /*row_count=*/ 1);
+    events.push(TableEvent::PrepareTransaction {
+        xact_id: 1,
+        gid: "gid-1".to_string(),
+    });
+    events.push(TableEvent::CommitPrepared {
+        gid: "gid-1".to_string(),
+        lsn: 100,
+    });
+    for event in events.iter() {
+        wal.record(event).await.unwrap();
+    }
+
+    // Restart after the commit has been persisted, but before its WAL is deleted.
+    let (_, recovered_events) =
+        PreparedTransactionWal::recover(context.path(), Some(100), prepared_gids_tx)
+            .await
+            .unwrap();
+    assert!(recovered_events.is_empty());
+    assert!(prepared_gids_rx.borrow().is_empty());
+    assert!(local_dir_is_empty(&context.path()).await);
+}
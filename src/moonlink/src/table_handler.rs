@@ -9,13 +9,14 @@
 /// - persisted table LSN: the largest LSN where all updates have been persisted into iceberg
 ///   Suppose we have two tables, table-A has persisted all updated into iceberg; with table-B taking new updates. persisted table LSN for table-A grows with table-B.
 use crate::event_sync::EventSyncSender;
-use crate::storage::mooncake_table::AlterTableRequest;
 use crate::storage::mooncake_table::MaintenanceOption;
 use crate::storage::mooncake_table::SnapshotOption;
 use crate::storage::mooncake_table::INITIAL_COPY_XACT_ID;
+use crate::storage::wal::prepared_transaction_wal::PreparedTransactionWal;
 use crate::storage::{io_utils, MooncakeTable};
 use crate::table_notify::TableEvent;
-use crate::Error;
+use crate::{Error, Result};
+use std::path::PathBuf;
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::sync::watch;
 use tokio::task::JoinHandle;
@@ -41,11 +42,14 @@ pub struct TableHandler {
 
 impl TableHandler {
     /// Create a new TableHandler for the given schema and table name
+    ///
+    /// * prepared_transaction_wal_directory: local directory to persist transaction streams, so prepared transactions are kept across restarts; they're only kept in memory if unassigned.
     pub async fn new(
         mut table: MooncakeTable,
         event_sync_sender: EventSyncSender,
         replication_lsn_rx: watch::Receiver<u64>,
         event_replay_tx: Option<mpsc::UnboundedSender<TableEvent>>,
+        prepared_transaction_wal_directory: Option<PathBuf>,
     ) -> Self {
         // Create channel for events
         let (event_sender, event_receiver) = mpsc::channel(100);
@@ -53,13 +57,33 @@ impl TableHandler {
         // Create channel for internal control events.
         table.register_table_notify(event_sender.clone()).await;
 
+        // Recover prepared transactions before the handler is returned, so the source learns they're persisted before replication starts.
+        let prepared_transaction_wal = match prepared_transaction_wal_directory {
+            Some(directory) => Some(
+                Self::recover_prepared_transactions(directory, &mut table, &event_sync_sender)
+                    .await,
+            ),
+            None => None,
+        };
+
         // Spawn the task to notify periodical events.
         let event_sender_for_periodical_snapshot = event_sender.clone();
         let event_sender_for_periodical_force_snapshot = event_sender.clone();
+        let event_sender_for_periodical_snapshot_expiration = event_sender.clone();
+        let snapshot_expiration_interval = Duration::from_secs(
+            table
+                .get_snapshot_retention_config()
+                .expiration_interval_secs,
+        );
         let periodic_event_handle = tokio::spawn(async move {
             let mut periodic_snapshot_interval = tokio::time::interval(Duration::from_millis(500));
             let mut periodic_force_snapshot_interval =
                 tokio::time::interval(Duration::from_secs(300));
+            // Skip the immediate first tick, there's nothing to expire at table start.
+            let mut periodic_snapshot_expiration_interval = tokio::time::interval_at(
+                tokio::time::Instant::now() + snapshot_expiration_interval,
+                snapshot_expiration_interval,
+            );
 
             loop {
                 tokio::select! {
@@ -74,6 +98,11 @@ impl TableHandler {
                             return;
                         }
                     }
+                    _ = periodic_snapshot_expiration_interval.tick() => {
+                        if event_sender_for_periodical_snapshot_expiration.send(TableEvent::PeriodicalSnapshotExpiration(uuid::Uuid::new_v4())).await.is_err() {
+                            return;
+                        }
+                    }
                     else => {
                         break;
                     }
@@ -90,6 +119,7 @@ impl TableHandler {
                     replication_lsn_rx,
                     event_replay_tx,
                     table,
+                    prepared_transaction_wal,
                 )
                 .await;
             }
@@ -117,6 +147,7 @@ impl TableHandler {
         replication_lsn_rx: watch::Receiver<u64>,
         event_replay_tx: Option<mpsc::UnboundedSender<TableEvent>>,
         mut table: MooncakeTable,
+        prepared_transaction_wal: Option<Result<PreparedTransactionWal>>,
     ) {
         let initial_persistence_lsn = table.get_iceberg_snapshot_lsn();
         let mut table_handler_state = TableHandlerState::new(
@@ -124,9 +155,31 @@ impl TableHandler {
             event_sync_sender.force_snapshot_completion_tx.clone(),
             initial_persistence_lsn,
         );
+        // Initial copy persisted before restart decides which cdc events have been reflected by the copy.
+        if let Some(initial_copy_position) = table.get_persisted_initial_copy_position() {
+            table_handler_state.initial_copy_position = initial_copy_position.clone();
+        }
+        table_handler_state.initial_copy_key_index = table.get_single_key_index();
+        match prepared_transaction_wal {
+            Some(Ok(prepared_transaction_wal)) => {
+                table_handler_state.prepared_transaction_wal = Some(prepared_transaction_wal);
+            }
+            // Prepared transactions might no longer be replayed by the source, so the table can't proceed without them.
+            Some(Err(e)) => {
+                Self::fail_table(
+                    format!("failed to recover prepared transactions: {e}"),
+                    &mut table,
+                    &mut table_handler_state,
+                    &event_sync_sender,
+                );
+            }
+            None => {}
+        }
 
         // Used to clean up mooncake table status, and send completion notification.
-        let drop_table = async |table: &mut MooncakeTable, event_sync_sender: EventSyncSender| {
+        let drop_table = async |table: &mut MooncakeTable,
+                                prepared_transaction_wal: Option<PreparedTransactionWal>,
+                                event_sync_sender: EventSyncSender| {
             // Step-1: shutdown the table, which unreferences and deletes all cache files.
             if let Err(e) = table.shutdown().await {
                 let _ = event_sync_sender.drop_table_completion_tx.send(Err(e));
@@ -145,7 +198,15 @@ impl TableHandler {
                 return;
             }
 
-            // Step-4: send back completion notification.
+            // Step-4: delete WAL of transaction streams.
+            if let Some(prepared_transaction_wal) = prepared_transaction_wal {
+                if let Err(e) = prepared_transaction_wal.drop_wal().await {
+                    let _ = event_sync_sender.drop_table_completion_tx.send(Err(e));
+                    return;
+                }
+            }
+
+            // Step-5: send back completion notification.
             let _ = event_sync_sender.drop_table_completion_tx.send(Ok(()));
         };
 
@@ -171,6 +232,11 @@ impl TableHandler {
                         replay_tx.send(event.clone()).unwrap();
                     }
 
+                    // Replication has stopped for a failed table.
+                    if table_handler_state.is_failed() && event.is_replication_event() {
+                        continue;
+                    }
+
                     table_handler_state.update_table_lsns(&event);
 
                     match event {
@@ -182,6 +248,14 @@ impl TableHandler {
                         // ==============================
                         //
                         TableEvent::ForceSnapshot { lsn } => {
+                            // Failed table no longer advances, so requested LSN may never be persisted.
+                            if let Some(table_error) = &table_handler_state.table_error {
+                                if lsn.is_some() {
+                                    let _ = table_handler_state.force_snapshot_completion_tx.send(Some(Err(Error::TableFailed(table_error.clone()))));
+                                }
+                                continue;
+                            }
+
                             let requested_lsn = if lsn.is_some() {
                                 lsn
                             } else if table_handler_state.latest_commit_lsn.is_some() {
@@ -246,44 +320,89 @@ impl TableHandler {
                             assert_eq!(table_handler_state.data_compaction_request_status, MaintenanceRequestStatus::Unrequested);
                             table_handler_state.data_compaction_request_status = MaintenanceRequestStatus::ForceFull;
                         }
+                        // Branch to trigger a force snapshot expiration request.
+                        TableEvent::ForceSnapshotExpiration => {
+                            if !table_handler_state.can_start_new_maintenance() {
+                                let _ = table_handler_state.table_maintenance_completion_tx.send(Ok(()));
+                                continue;
+                            }
+                            // Otherwise queue a request, which starts as soon as iceberg table manager is available.
+                            table_handler_state.snapshot_expiration_request_status = MaintenanceRequestStatus::ForceRegular;
+                            if table_handler_state.can_start_snapshot_expiration() {
+                                table_handler_state.snapshot_expiration_ongoing = true;
+                                table.perform_snapshot_expiration(uuid::Uuid::new_v4());
+                            }
+                        }
                         // Branch to drop the iceberg table and clear pinned data files from the global object storage cache, only used when the whole table requested to drop.
                         // So we block wait for asynchronous request completion.
                         TableEvent::DropTable => {
                             // Fast-path: no other concurrent events, directly clean up states and ack back.
                             if table_handler_state.can_drop_table_now() {
-                                drop_table(&mut table, event_sync_sender).await;
+                                drop_table(&mut table, table_handler_state.prepared_transaction_wal.take(), event_sync_sender).await;
                                 return;
                             }
 
                             // Otherwise, leave a drop marker to clean up states later.
                             table_handler_state.mark_drop_table();
                         }
-                        TableEvent::AlterTable { columns_to_drop } => {
-                            debug!("altering table, dropping columns: {:?}", columns_to_drop);
-                            let alter_table_request = AlterTableRequest {
-                                new_columns: vec![],
-                                dropped_columns: columns_to_drop,
-                            };
+                        TableEvent::AlterTable { alter_table_request } => {
+                            debug!("altering table: {:?}", alter_table_request);
+                            // Rows streamed afterwards are in the new schema, so the table can't proceed if the change is rejected.
+                            if let Err(e) = table.validate_alter_table(&alter_table_request) {
+                                Self::fail_table(format!("failed to alter table: {e}"), &mut table, &mut table_handler_state, &event_sync_sender);
+                                continue;
+                            }
                             table_handler_state.start_alter_table(alter_table_request);
                         }
                         TableEvent::StartInitialCopy => {
                             debug!("starting initial copy");
                             table_handler_state.start_initial_copy();
                         }
+                        TableEvent::CheckpointInitialCopy { position } => {
+                            debug!(rows_copied = position.rows_copied, "checkpointing initial copy");
+                            // Rows copied so far are persisted by later snapshots together with the copy position.
+                            if table.has_transaction_stream(INITIAL_COPY_XACT_ID) {
+                                // Checkpoint can't be recorded without its rows, so the copy can't resume from it.
+                                if let Err(e) = table.commit_transaction_stream(INITIAL_COPY_XACT_ID, 0).await {
+                                    Self::fail_table(format!("failed to checkpoint initial copy: {e}"), &mut table, &mut table_handler_state, &event_sync_sender);
+                                    continue;
+                                }
+                            }
+                            table_handler_state.initial_copy_position = position.clone();
+                            table.update_initial_copy_position(position);
+                        }
+                        TableEvent::AbortInitialCopy => {
+                            debug!("aborting initial copy attempt");
+                            // Rows committed by checkpoints are kept, which the next attempt resumes after.
+                            if table.has_transaction_stream(INITIAL_COPY_XACT_ID) {
+                                table.abort_in_stream_batch(INITIAL_COPY_XACT_ID);
+                            }
+                        }
                         TableEvent::FinishInitialCopy { start_lsn } => {
                             debug!("finishing initial copy");
-                            if let Err(e) = table.commit_transaction_stream(INITIAL_COPY_XACT_ID, 0).await {
-                                error!(error = %e, "failed to finish initial copy");
+                            // Rows could have all been committed by checkpoints.
+                            if table.has_transaction_stream(INITIAL_COPY_XACT_ID) {
+                                // Copy must not be recorded as finished, otherwise uncommitted rows are lost after restart.
+                                if let Err(e) = table.commit_transaction_stream(INITIAL_COPY_XACT_ID, 0).await {
+                                    Self::fail_table(format!("failed to finish initial copy: {e}"), &mut table, &mut table_handler_state, &event_sync_sender);
+                                    continue;
+                                }
+                            }
+                            // Record the copy as finished, which is persisted along with copied rows.
+                            table_handler_state.initial_copy_position.finish_lsn = Some(start_lsn);
+                            table.update_initial_copy_position(table_handler_state.initial_copy_position.clone());
+                            table_handler_state.initial_copy_persistence_pending = true;
+                            // Force create the snapshot with LSN 0, unless there's one ongoing, which will be followed by periodic ones.
+                            if !table_handler_state.mooncake_snapshot_ongoing {
+                                assert!(table.create_snapshot(SnapshotOption {
+                                    uuid: uuid::Uuid::new_v4(),
+                                    force_create: true,
+                                    skip_iceberg_snapshot: true,
+                                    index_merge_option: MaintenanceOption::Skip,
+                                    data_compaction_option: MaintenanceOption::Skip,
+                                }));
+                                table_handler_state.mooncake_snapshot_ongoing = true;
                             }
-                            // Force create the snapshot with LSN 0
-                            assert!(table.create_snapshot(SnapshotOption {
-                                uuid: uuid::Uuid::new_v4(),
-                                force_create: true,
-                                skip_iceberg_snapshot: true,
-                                index_merge_option: MaintenanceOption::Skip,
-                                data_compaction_option: MaintenanceOption::Skip,
-                            }));
-                            table_handler_state.mooncake_snapshot_ongoing = true;
                             table_handler_state.finish_initial_copy();
 
                             // Drop any events that have LSN less than the start LSN during apply.
@@ -291,6 +410,9 @@ impl TableHandler {
                             // Apply the buffered events.
                             Self::process_blocked_events(&mut table, &mut table_handler_state).await;
                         }
+                        TableEvent::FailTable { error } => {
+                            Self::fail_table(error, &mut table, &mut table_handler_state, &event_sync_sender);
+                        }
                         // ==============================
                         // Table internal events
                         // ==============================
@@ -317,7 +439,19 @@ impl TableHandler {
 
                             // Fallback to normal periodic snapshot.
                             table_handler_state.reset_iceberg_state_at_mooncake_snapshot();
-                            table_handler_state.mooncake_snapshot_ongoing = table.create_snapshot(table_handler_state.get_mooncake_snapshot_option(/*request_force=*/false, uuid));
+                            // Persist finished initial copy as soon as possible, whose completion is waited by the source.
+                            let persist_initial_copy = table_handler_state.initial_copy_persistence_pending && table_handler_state.can_initiate_iceberg_snapshot();
+                            if persist_initial_copy {
+                                table.force_empty_iceberg_payload();
+                            }
+                            table_handler_state.mooncake_snapshot_ongoing = table.create_snapshot(table_handler_state.get_mooncake_snapshot_option(/*request_force=*/persist_initial_copy, uuid));
+                        }
+                        TableEvent::PeriodicalSnapshotExpiration(uuid) => {
+                            // Best-effort, skip if iceberg table manager is in use.
+                            if table_handler_state.can_start_snapshot_expiration() {
+                                table_handler_state.snapshot_expiration_ongoing = true;
+                                table.perform_snapshot_expiration(uuid);
+                            }
                         }
                         TableEvent::MooncakeTableSnapshotResult { lsn, uuid: _, iceberg_snapshot_payload, data_compaction_payload, file_indice_merge_payload, evicted_data_files_to_delete } => {
                             // Spawn a detached best-effort task to delete evicted object storage cache.
@@ -329,7 +463,7 @@ impl TableHandler {
 
                             // Drop table if requested, and table at a clean state.
                             if table_handler_state.special_table_state == SpecialTableState::DropTable && table_handler_state.can_drop_table_now() {
-                                drop_table(&mut table, event_sync_sender).await;
+                                drop_table(&mut table, table_handler_state.prepared_transaction_wal.take(), event_sync_sender).await;
                                 return;
                             }
 
@@ -346,14 +480,22 @@ impl TableHandler {
                                     table_handler_state.iceberg_snapshot_ongoing = true;
                                     if table_handler_state.should_complete_alter_table(iceberg_snapshot_payload.flush_lsn) {
                                         if let SpecialTableState::AlterTable { ref mut alter_table_request, .. } = table_handler_state.special_table_state {
-                                            let new_table_metadata = table.alter_table(alter_table_request.take().unwrap());
-                                            iceberg_snapshot_payload.new_table_schema = Some(new_table_metadata);
+                                            match table.alter_table(alter_table_request.take().unwrap()) {
+                                                Ok(new_table_metadata) => {
+                                                    iceberg_snapshot_payload.new_table_schema = Some(new_table_metadata);
+                                                    table_handler_state.finish_alter_table();
+                                                    Self::process_blocked_events(&mut table, &mut table_handler_state).await;
+                                                }
+                                                // Buffered rows are in the new schema, which can't be applied to the table any more.
+                                                // Rows before the schema change are still persisted.
+                                                Err(e) => {
+                                                    Self::fail_table(format!("failed to alter table: {e}"), &mut table, &mut table_handler_state, &event_sync_sender);
+                                                }
+                                            }
                                         }
                                         else {
                                             unreachable!("alter table request is not set");
                                         }
-                                        table_handler_state.finish_alter_table();
-                                        Self::process_blocked_events(&mut table, &mut table_handler_state).await;
                                     }
                                     table.persist_iceberg_snapshot(iceberg_snapshot_payload);
                                 }
@@ -411,9 +553,21 @@ impl TableHandler {
                                         let _ = table_handler_state.table_maintenance_completion_tx.send(Ok(()));
                                     }
 
+                                    // Notify finished initial copy has been persisted.
+                                    if table_handler_state.initial_copy_persistence_pending && snapshot_res.initial_copy_position.is_finished() {
+                                        table_handler_state.initial_copy_persistence_pending = false;
+                                        let _ = event_sync_sender.initial_copy_completion_tx.send(true);
+                                    }
+
                                     // Buffer iceberg persistence result, which later will be reflected to mooncake snapshot.
                                     let iceberg_flush_lsn = snapshot_res.flush_lsn;
                                     event_sync_sender.flush_lsn_tx.send(iceberg_flush_lsn).unwrap();
+                                    // Prepared transactions committed at or before flush LSN are no longer recovered from WAL.
+                                    if let Some(prepared_transaction_wal) = &mut table_handler_state.prepared_transaction_wal {
+                                        if let Err(e) = prepared_transaction_wal.truncate(iceberg_flush_lsn).await {
+                                            error!(error = %e, "failed to truncate prepared transaction WAL");
+                                        }
+                                    }
                                     table.set_iceberg_snapshot_res(snapshot_res);
                                     table_handler_state.iceberg_snapshot_result_consumed = false;
 
@@ -441,9 +595,15 @@ impl TableHandler {
                                 }
                             }
 
+                            // Start pending force snapshot expiration, which waits for iceberg table manager.
+                            if table_handler_state.snapshot_expiration_request_status.is_force_request() && table_handler_state.can_start_snapshot_expiration() {
+                                table_handler_state.snapshot_expiration_ongoing = true;
+                                table.perform_snapshot_expiration(uuid::Uuid::new_v4());
+                            }
+
                             // Drop table if requested, and table at a clean state.
                             if table_handler_state.special_table_state == SpecialTableState::DropTable && table_handler_state.can_drop_table_now() {
-                                drop_table(&mut table, event_sync_sender).await;
+                                drop_table(&mut table, table_handler_state.prepared_transaction_wal.take(), event_sync_sender).await;
                                 return;
                             }
                         }
@@ -452,7 +612,7 @@ impl TableHandler {
                             table_handler_state.mark_index_merge_completed().await;
                             // Check whether need to drop table.
                             if table_handler_state.special_table_state == SpecialTableState::DropTable && table_handler_state.can_drop_table_now() {
-                                drop_table(&mut table, event_sync_sender).await;
+                                drop_table(&mut table, table_handler_state.prepared_transaction_wal.take(), event_sync_sender).await;
                                 return;
                             }
                         }
@@ -468,7 +628,23 @@ impl TableHandler {
                             }
                             // Check whether need to drop table.
                             if table_handler_state.special_table_state == SpecialTableState::DropTable && table_handler_state.can_drop_table_now() {
-                                drop_table(&mut table, event_sync_sender).await;
+                                drop_table(&mut table, table_handler_state.prepared_transaction_wal.take(), event_sync_sender).await;
+                                return;
+                            }
+                        }
+                        TableEvent::SnapshotExpirationResult { snapshot_expiration_result } => {
+                            let expire_snapshots_result = table.set_snapshot_expiration_res(snapshot_expiration_result);
+                            if let Ok(expire_snapshots_result) = &expire_snapshots_result {
+                                debug!(
+                                    expired_snapshots = expire_snapshots_result.expired_snapshot_ids.len(),
+                                    deleted_files = expire_snapshots_result.deleted_files.len(),
+                                    "iceberg snapshots expired"
+                                );
+                            }
+                            table_handler_state.mark_snapshot_expiration_completed(&expire_snapshots_result);
+                            // Check whether need to drop table.
+                            if table_handler_state.special_table_state == SpecialTableState::DropTable && table_handler_state.can_drop_table_now() {
+                                drop_table(&mut table, table_handler_state.prepared_transaction_wal.take(), event_sync_sender).await;
                                 return;
                             }
                         }
@@ -499,6 +675,60 @@ impl TableHandler {
         }
     }
 
+    /// Stop replication for the table with the given error, which is reported through table status.
+    fn fail_table(
+        error: String,
+        table: &mut MooncakeTable,
+        table_handler_state: &mut TableHandlerState,
+        event_sync_sender: &EventSyncSender,
+    ) {
+        error!(%error, "table failed, replication events are discarded until it's dropped");
+        // Copied rows which haven't been committed are discarded along with buffered events.
+        if table.has_transaction_stream(INITIAL_COPY_XACT_ID) {
+            table.abort_in_stream_batch(INITIAL_COPY_XACT_ID);
+        }
+        table_handler_state.fail_table(error.clone());
+        let _ = event_sync_sender.table_error_tx.send(Some(error));
+    }
+
+    /// Recover prepared transactions from WAL, and stage their changes again as prepared transaction streams.
+    async fn recover_prepared_transactions(
+        directory: PathBuf,
+        table: &mut MooncakeTable,
+        event_sync_sender: &EventSyncSender,
+    ) -> Result<PreparedTransactionWal> {
+        let (prepared_transaction_wal, events) = PreparedTransactionWal::recover(
+            directory,
+            table.get_iceberg_snapshot_lsn(),
+            event_sync_sender.prepared_gids_tx.clone(),
+        )
+        .await?;
+        for event in events {
+            match event {
+                TableEvent::Append {
+                    row,
+                    xact_id: Some(xact_id),
+                    ..
+                } => {
+                    table.append_in_stream_batch(row, xact_id)?;
+                    if table.should_transaction_flush(xact_id) {
+                        table.flush_transaction_stream(xact_id).await?;
+                    }
+                }
+                TableEvent::Delete {
+                    row,
+                    xact_id: Some(xact_id),
+                    ..
+                } => table.delete_in_stream_batch(row, xact_id).await,
+                TableEvent::PrepareTransaction { xact_id, gid } => {
+                    table.prepare_transaction_stream(xact_id, gid).await?
+                }
+                _ => unreachable!("unexpected prepared transaction event: {:?}", event),
+            }
+        }
+        Ok(prepared_transaction_wal)
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn process_cdc_table_event(
         event: TableEvent,
@@ -531,6 +761,25 @@ impl TableHandler {
             table_handler_state.special_table_state == SpecialTableState::InitialCopy
         );
 
+        // Prepared transactions take no further changes, changes replayed by the source after reconnect or restart are
+        // discarded, so are aborts of their replayed subtransactions.
+        let stream_xact_id = match &event {
+            TableEvent::Append { xact_id, .. } | TableEvent::Delete { xact_id, .. } => *xact_id,
+            TableEvent::StreamAbort { xact_id } | TableEvent::StreamFlush { xact_id } => {
+                Some(*xact_id)
+            }
+            _ => None,
+        };
+        if stream_xact_id.is_some_and(|xact_id| table.is_prepared_transaction(xact_id)) {
+            return;
+        }
+
+        if let Some(prepared_transaction_wal) = &mut table_handler_state.prepared_transaction_wal {
+            if let Err(e) = prepared_transaction_wal.record(&event).await {
+                error!(error = %e, "failed to record transaction stream event");
+            }
+        }
+
         match event {
             TableEvent::Append {
                 is_copied,
@@ -581,6 +830,29 @@ impl TableHandler {
             TableEvent::StreamAbort { xact_id } => {
                 table.abort_in_stream_batch(xact_id);
             }
+            TableEvent::PrepareTransaction { xact_id, gid } => {
+                if let Err(e) = table.prepare_transaction_stream(xact_id, gid).await {
+                    error!(error = %e, "failed to prepare transaction");
+                }
+            }
+            TableEvent::CommitPrepared { gid, lsn } => {
+                // Prepared transactions which don't touch the current table are not tracked.
+                if let Some(xact_id) = table.take_prepared_transaction(&gid) {
+                    Self::commit_and_attempt_flush(
+                        lsn,
+                        Some(xact_id),
+                        table_handler_state,
+                        table,
+                        /*force_flush_requested=*/ false,
+                    )
+                    .await;
+                }
+            }
+            TableEvent::RollbackPrepared { gid } => {
+                if let Some(xact_id) = table.take_prepared_transaction(&gid) {
+                    table.abort_in_stream_batch(xact_id);
+                }
+            }
             TableEvent::CommitFlush { lsn, xact_id } => {
                 Self::commit_and_attempt_flush(
                     lsn,
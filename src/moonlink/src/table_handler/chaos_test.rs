@@ -518,6 +518,7 @@ impl TestEnvironment {
             table_event_sync_sender,
             replication_lsn_rx.clone(),
             Some(event_replay_tx),
+            /*prepared_transaction_wal_directory=*/ None,
         )
         .await;
         let table_event_manager =
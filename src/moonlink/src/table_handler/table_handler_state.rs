@@ -1,8 +1,11 @@
 /// Table handler state manages table event process states.
 use crate::storage::mooncake_table::AlterTableRequest;
 use crate::storage::mooncake_table::DataCompactionResult;
+use crate::storage::mooncake_table::InitialCopyPosition;
 use crate::storage::mooncake_table::MaintenanceOption;
 use crate::storage::mooncake_table::SnapshotOption;
+use crate::storage::wal::prepared_transaction_wal::PreparedTransactionWal;
+use crate::storage::ExpireSnapshotsResult;
 use crate::table_notify::TableEvent;
 use crate::Result;
 use tokio::sync::{broadcast, watch};
@@ -75,6 +78,11 @@ pub(crate) struct TableHandlerState {
     // This also is used in the initial copy mode to discard cdc events that may be already included in the initial copy.
     // If we have already seen this LSN, we simply discard the event.
     pub(crate) initial_persistence_lsn: Option<u64>,
+    // Initial copy position, which could be copied in multiple attempts from different source snapshots.
+    // CDC events are discarded if they're already reflected by the snapshot their rows are copied from.
+    pub(crate) initial_copy_position: InitialCopyPosition,
+    // Index of the key column which initial copy is ordered by.
+    pub(crate) initial_copy_key_index: Option<usize>,
     // Record LSN if the last handled table event is a commit operation, which indicates mooncake table stays at a consistent view, so table could be flushed safely.
     pub(crate) table_consistent_view_lsn: Option<u64>,
     // Latest LSN of the table's latest commit.
@@ -103,6 +111,12 @@ pub(crate) struct TableHandlerState {
     pub(crate) special_table_state: SpecialTableState,
     // Buffered events during blocking operations: initial copy, alter table, drop table, etc.
     pub(crate) initial_copy_buffered_events: Vec<TableEvent>,
+    // Whether initial copy has finished, but not persisted into iceberg yet.
+    pub(crate) initial_copy_persistence_pending: bool,
+    // Error which stops replication for the table, replication events are discarded once assigned.
+    pub(crate) table_error: Option<String>,
+    // WAL of transaction streams, which keeps prepared transactions across restarts; unassigned if not persisted.
+    pub(crate) prepared_transaction_wal: Option<PreparedTransactionWal>,
 
     // ================================================
     // Table maintainence status
@@ -118,6 +132,17 @@ pub(crate) struct TableHandlerState {
     pub(crate) table_maintenance_process_status: MaintenanceProcessStatus,
     /// Notify when data compaction completes.
     pub(crate) table_maintenance_completion_tx: broadcast::Sender<Result<()>>,
+
+    // ================================================
+    // Snapshot expiration status
+    // ================================================
+    //
+    // Snapshot expiration takes iceberg table manager, so it doesn't run concurrently with iceberg snapshot.
+    //
+    /// Force snapshot expiration request status.
+    pub(crate) snapshot_expiration_request_status: MaintenanceRequestStatus,
+    /// Whether there's an ongoing snapshot expiration.
+    pub(crate) snapshot_expiration_ongoing: bool,
 }
 
 impl TableHandlerState {
@@ -131,6 +156,8 @@ impl TableHandlerState {
             iceberg_snapshot_ongoing: false,
             mooncake_snapshot_ongoing: false,
             initial_persistence_lsn,
+            initial_copy_position: InitialCopyPosition::default(),
+            initial_copy_key_index: None,
             latest_commit_lsn: None,
             special_table_state: SpecialTableState::Normal,
             // Force snapshot fields.
@@ -142,8 +169,14 @@ impl TableHandlerState {
             data_compaction_request_status: MaintenanceRequestStatus::Unrequested,
             table_maintenance_process_status: MaintenanceProcessStatus::Unrequested,
             table_maintenance_completion_tx,
+            // Snapshot expiration fields.
+            snapshot_expiration_request_status: MaintenanceRequestStatus::Unrequested,
+            snapshot_expiration_ongoing: false,
             // Initial copy fields.
             initial_copy_buffered_events: Vec::new(),
+            initial_copy_persistence_pending: false,
+            table_error: None,
+            prepared_transaction_wal: None,
         }
     }
 
@@ -155,7 +188,7 @@ impl TableHandlerState {
                     self.latest_commit_lsn = Some(*lsn);
                     self.table_consistent_view_lsn = Some(*lsn);
                 }
-                TableEvent::CommitFlush { lsn, .. } => {
+                TableEvent::CommitFlush { lsn, .. } | TableEvent::CommitPrepared { lsn, .. } => {
                     self.latest_commit_lsn = Some(*lsn);
                     self.table_consistent_view_lsn = Some(*lsn);
                 }
@@ -198,7 +231,8 @@ impl TableHandlerState {
         SnapshotOption {
             uuid,
             force_create,
-            skip_iceberg_snapshot: self.iceberg_snapshot_ongoing,
+            skip_iceberg_snapshot: self.iceberg_snapshot_ongoing
+                || self.snapshot_expiration_ongoing,
             index_merge_option: self.get_index_merge_maintenance_option(),
             data_compaction_option: self.get_data_compaction_maintenance_option(),
         }
@@ -213,8 +247,11 @@ impl TableHandlerState {
         if event.is_streaming_update() {
             return false;
         }
-        // For non-streaming events, discard if LSN is less than flush LSN.
-        let initial_persistence_lsn = self.initial_persistence_lsn.unwrap();
+        // For non-streaming events, discard if LSN is less than flush LSN, or the LSN initial copy reflects.
+        let mut initial_persistence_lsn = self.initial_persistence_lsn.unwrap();
+        if let Some(initial_copy_lsn) = self.get_initial_copy_lsn(event) {
+            initial_persistence_lsn = initial_persistence_lsn.max(initial_copy_lsn);
+        }
         if let Some(lsn) = event.get_lsn_for_ingest_event() {
             lsn <= initial_persistence_lsn
         } else {
@@ -222,6 +259,18 @@ impl TableHandlerState {
         }
     }
 
+    /// Get the LSN which initial copy reflects changes up to, for the row of the given event.
+    /// For events without a row, get the smallest one, so they're not discarded if any row could be applied.
+    fn get_initial_copy_lsn(&self, event: &TableEvent) -> Option<u64> {
+        let key = match event {
+            TableEvent::Append { row, .. } | TableEvent::Delete { row, .. } => self
+                .initial_copy_key_index
+                .and_then(|key_index| InitialCopyPosition::get_key(row, key_index)),
+            _ => None,
+        };
+        self.initial_copy_position.get_snapshot_lsn(key)
+    }
+
     pub(crate) fn is_in_blocking_state(&self) -> bool {
         self.special_table_state != SpecialTableState::Normal
     }
@@ -319,6 +368,9 @@ impl TableHandlerState {
         if self.table_maintenance_process_status != MaintenanceProcessStatus::Unrequested {
             return false;
         }
+        if self.snapshot_expiration_ongoing {
+            return false;
+        }
         true
     }
 
@@ -398,6 +450,28 @@ impl TableHandlerState {
         self.table_consistent_view_lsn = Some(0);
     }
 
+    /// ============================
+    /// Table failure
+    /// ============================
+    ///
+    /// Mark the table failed, buffered events are discarded, and the table leaves initial copy or alter table state,
+    /// which never completes for a failed table, so it could still be dropped.
+    pub(crate) fn fail_table(&mut self, error: String) {
+        self.table_error = Some(error);
+        self.initial_copy_buffered_events.clear();
+        if matches!(
+            self.special_table_state,
+            SpecialTableState::InitialCopy | SpecialTableState::AlterTable { .. }
+        ) {
+            self.special_table_state = SpecialTableState::Normal;
+        }
+    }
+
+    /// Return whether the table has failed.
+    pub(crate) fn is_failed(&self) -> bool {
+        self.table_error.is_some()
+    }
+
     /// ============================
     /// Iceberg snapshot
     /// ============================
@@ -407,7 +481,9 @@ impl TableHandlerState {
     /// We can only create a new iceberg snapshot when (1) there's no ongoing iceberg snapshot, (2) previous snapshot results have been acknowledged.
     ///
     pub(crate) fn can_initiate_iceberg_snapshot(&self) -> bool {
-        self.iceberg_snapshot_result_consumed && !self.iceberg_snapshot_ongoing
+        self.iceberg_snapshot_result_consumed
+            && !self.iceberg_snapshot_ongoing
+            && !self.snapshot_expiration_ongoing
     }
 
     pub(crate) fn reset_iceberg_state_at_mooncake_snapshot(&mut self) {
@@ -491,6 +567,39 @@ impl TableHandlerState {
         if self.data_compaction_request_status.is_requested() {
             return false;
         }
+        if self.snapshot_expiration_request_status.is_requested() {
+            return false;
+        }
         true
     }
+
+    /// ============================
+    /// Snapshot expiration
+    /// ============================
+    ///
+    /// Return whether a snapshot expiration could be started now, which requires iceberg table manager not in use.
+    pub(crate) fn can_start_snapshot_expiration(&self) -> bool {
+        !self.snapshot_expiration_ongoing
+            && !self.iceberg_snapshot_ongoing
+            && self.special_table_state != SpecialTableState::DropTable
+    }
+
+    /// Mark snapshot expiration completion, and notify force request if any.
+    pub(crate) fn mark_snapshot_expiration_completed(
+        &mut self,
+        expire_snapshots_result: &Result<ExpireSnapshotsResult>,
+    ) {
+        assert!(self.snapshot_expiration_ongoing);
+        self.snapshot_expiration_ongoing = false;
+        if let Err(err) = expire_snapshots_result {
+            error!(error = ?err, "failed to expire iceberg snapshots");
+        }
+        if self.snapshot_expiration_request_status.is_force_request() {
+            self.snapshot_expiration_request_status = MaintenanceRequestStatus::Unrequested;
+            // Table maintenance could come from table internal events, which doesn't have notification receiver.
+            let _ = self
+                .table_maintenance_completion_tx
+                .send(expire_snapshots_result.clone().map(|_| ()));
+        }
+    }
 }
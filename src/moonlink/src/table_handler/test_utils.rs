@@ -18,6 +18,9 @@ use std::sync::Arc;
 use tempfile::{tempdir, TempDir};
 use tokio::sync::{mpsc, watch};
 
+/// Directory under the test directory to persist transaction streams.
+pub(crate) const PREPARED_TRANSACTION_WAL_DIRECTORY: &str = "prepared_transactions";
+
 /// Creates a `MoonlinkRow` for testing purposes.
 pub fn create_row(id: i32, name: &str, age: i32) -> MoonlinkRow {
     MoonlinkRow::new(vec![
@@ -35,6 +38,7 @@ pub fn get_iceberg_manager_config(table_name: String, warehouse_uri: String) ->
         filesystem_config: FileSystemConfig::FileSystem {
             root_directory: warehouse_uri,
         },
+        ..Default::default()
     }
 }
 
@@ -93,6 +97,7 @@ impl TestEnvironment {
             table_event_sync_sender,
             replication_rx.clone(),
             /*event_replay_tx=*/ None,
+            Some(temp_dir.path().join(PREPARED_TRANSACTION_WAL_DIRECTORY)),
         )
         .await;
         let table_event_manager =
@@ -215,6 +220,29 @@ impl TestEnvironment {
         self.send_event(TableEvent::StreamAbort { xact_id }).await;
     }
 
+    pub async fn prepare_transaction(&self, xact_id: u32, gid: &str) {
+        self.send_event(TableEvent::PrepareTransaction {
+            xact_id,
+            gid: gid.to_string(),
+        })
+        .await;
+    }
+
+    pub async fn commit_prepared(&self, gid: &str, lsn: u64) {
+        self.send_event(TableEvent::CommitPrepared {
+            gid: gid.to_string(),
+            lsn,
+        })
+        .await;
+    }
+
+    pub async fn rollback_prepared(&self, gid: &str) {
+        self.send_event(TableEvent::RollbackPrepared {
+            gid: gid.to_string(),
+        })
+        .await;
+    }
+
     /// Force an index merge operation, and block wait its completion.
     pub async fn force_index_merge_and_sync(&mut self) -> Result<()> {
         let mut rx = self.table_event_manager.initiate_index_merge().await;
@@ -233,6 +261,15 @@ impl TestEnvironment {
         rx.recv().await.unwrap()
     }
 
+    /// Force a snapshot expiration operation, and block wait its completion.
+    pub async fn force_snapshot_expiration_and_sync(&mut self) -> Result<()> {
+        let mut rx = self
+            .table_event_manager
+            .initiate_snapshot_expiration()
+            .await;
+        rx.recv().await.unwrap()
+    }
+
     pub async fn flush_table_and_sync(&mut self, lsn: u64) {
         self.send_event(TableEvent::CommitFlush { lsn, xact_id: None })
             .await;
@@ -10,14 +10,20 @@ use crate::storage::filesystem::accessor::filesystem_accessor::FileSystemAccesso
 use crate::storage::index::index_merge_config::FileIndexMergeConfig;
 use crate::storage::mooncake_table::table_creation_test_utils::*;
 use crate::storage::mooncake_table::validation_test_utils::*;
+use crate::storage::mooncake_table::AlterTableRequest;
 use crate::storage::mooncake_table::IcebergPersistenceConfig;
 use crate::storage::mooncake_table::IcebergSnapshotPayload;
+use crate::storage::mooncake_table::InitialCopyPosition;
 use crate::storage::mooncake_table::MooncakeTableConfig;
 use crate::storage::mooncake_table::Snapshot as MooncakeSnapshot;
 use crate::storage::mooncake_table::TableMetadata as MooncakeTableMetadata;
+use crate::storage::IcebergDeleteFileFormat;
+use crate::storage::ManifestCompactionConfig;
 use crate::storage::MockTableManager;
 use crate::storage::MooncakeTable;
+use crate::storage::ParquetWriterConfig;
 use crate::storage::PersistenceResult;
+use crate::storage::SnapshotRetentionConfig;
 use crate::storage::TableManager;
 use crate::table_handler::table_handler_state::TableHandlerState;
 use crate::ObjectStorageCache;
@@ -72,8 +78,12 @@ async fn test_append_with_small_disk_slice() {
         mem_slice_size: 1000,
         snapshot_deletion_record_count: 1000,
         temp_files_directory: temp_dir.path().to_str().unwrap().to_string(),
+        partition_spec: None,
         data_compaction_config: DataCompactionConfig::default(),
         file_index_config: FileIndexMergeConfig::default(),
+        snapshot_retention_config: SnapshotRetentionConfig::default(),
+        manifest_compaction_config: ManifestCompactionConfig::default(),
+        parquet_writer_config: ParquetWriterConfig::default(),
         persistence_config: IcebergPersistenceConfig {
             new_data_file_count: 1000,
             new_committed_deletion_log: 1000,
@@ -181,6 +191,71 @@ async fn test_streaming_abort() {
     env.shutdown().await;
 }
 
+#[tokio::test]
+async fn test_two_phase_commit() {
+    let mut env = TestEnvironment::default().await;
+
+    // Prepared transaction to commit.
+    let commit_xact_id = 101;
+    env.append_row(1, "User-1", 20, /*lsn=*/ 50, Some(commit_xact_id))
+        .await;
+    env.prepare_transaction(commit_xact_id, "gid-commit").await;
+
+    // Prepared transaction to roll back.
+    let rollback_xact_id = 102;
+    env.append_row(2, "User-2", 30, /*lsn=*/ 60, Some(rollback_xact_id))
+        .await;
+    env.prepare_transaction(rollback_xact_id, "gid-rollback")
+        .await;
+
+    // Commit or rollback for a transaction which doesn't touch the table is ignored.
+    env.commit_prepared("gid-unknown", /*lsn=*/ 80).await;
+
+    env.rollback_prepared("gid-rollback").await;
+    env.commit_prepared("gid-commit", /*lsn=*/ 100).await;
+
+    env.set_readable_lsn(100);
+    env.verify_snapshot(100, &[1]).await;
+
+    env.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_prepared_transaction_recovery() {
+    let temp_dir = tempdir().unwrap();
+    let mooncake_table_config =
+        MooncakeTableConfig::new(temp_dir.path().to_str().unwrap().to_string());
+    let mut env = TestEnvironment::new(temp_dir, mooncake_table_config.clone()).await;
+    let mut prepared_gids_rx = env.table_event_manager.subscribe_prepared_gids();
+
+    let xact_id = 101;
+    env.append_row(1, "User-1", 20, /*lsn=*/ 0, Some(xact_id))
+        .await;
+    env.prepare_transaction(xact_id, "gid-1").await;
+    prepared_gids_rx
+        .wait_for(|gids| gids.contains("gid-1"))
+        .await
+        .unwrap();
+
+    // Restart the table handler without dropping the table, the source no longer replays the prepared transaction.
+    let temp_dir = std::mem::replace(&mut env.temp_dir, tempdir().unwrap());
+    env.handler._event_handle.take().unwrap().abort();
+    env.handler._periodic_event_handle.abort();
+    drop(env);
+    let mut env = TestEnvironment::new(temp_dir, mooncake_table_config).await;
+    assert!(env
+        .table_event_manager
+        .subscribe_prepared_gids()
+        .borrow()
+        .contains("gid-1"));
+
+    env.commit_prepared("gid-1", /*lsn=*/ 100).await;
+    env.set_readable_lsn(100);
+    env.verify_snapshot(100, &[1]).await;
+
+    env.shutdown().await;
+}
+
 #[tokio::test]
 async fn test_concurrent_streaming_transactions() {
     let mut env = TestEnvironment::default().await;
@@ -565,8 +640,12 @@ async fn test_iceberg_snapshot_creation_for_batch_write() {
         mem_slice_size: 1000,
         snapshot_deletion_record_count: 1000,
         temp_files_directory: temp_dir.path().to_str().unwrap().to_string(),
+        partition_spec: None,
         data_compaction_config: DataCompactionConfig::default(),
         file_index_config: FileIndexMergeConfig::default(),
+        snapshot_retention_config: SnapshotRetentionConfig::default(),
+        manifest_compaction_config: ManifestCompactionConfig::default(),
+        parquet_writer_config: ParquetWriterConfig::default(),
         persistence_config: IcebergPersistenceConfig {
             new_data_file_count: 1000,
             new_committed_deletion_log: 1000,
@@ -764,8 +843,12 @@ async fn test_iceberg_snapshot_creation_for_streaming_write() {
         mem_slice_size: 1000,
         snapshot_deletion_record_count: 1000,
         temp_files_directory: temp_dir.path().to_str().unwrap().to_string(),
+        partition_spec: None,
         data_compaction_config: DataCompactionConfig::default(),
         file_index_config: FileIndexMergeConfig::default(),
+        snapshot_retention_config: SnapshotRetentionConfig::default(),
+        manifest_compaction_config: ManifestCompactionConfig::default(),
+        parquet_writer_config: ParquetWriterConfig::default(),
         persistence_config: IcebergPersistenceConfig {
             new_data_file_count: 1000,
             new_committed_deletion_log: 1000,
@@ -994,8 +1077,12 @@ async fn test_multiple_snapshot_requests() {
         mem_slice_size: 1000,
         snapshot_deletion_record_count: 1000,
         temp_files_directory: temp_dir.path().to_str().unwrap().to_string(),
+        partition_spec: None,
         data_compaction_config: DataCompactionConfig::default(),
         file_index_config: FileIndexMergeConfig::default(),
+        snapshot_retention_config: SnapshotRetentionConfig::default(),
+        manifest_compaction_config: ManifestCompactionConfig::default(),
+        parquet_writer_config: ParquetWriterConfig::default(),
         persistence_config: IcebergPersistenceConfig {
             new_data_file_count: 1000,
             new_committed_deletion_log: 1000,
@@ -1277,6 +1364,267 @@ async fn test_initial_copy_basic() {
     env.shutdown().await;
 }
 
+#[tokio::test]
+async fn test_initial_copy_checkpoint() {
+    let mut env = TestEnvironment::default().await;
+    let sender = env.handler.get_event_sender();
+    let mut initial_copy_completion_rx =
+        env.table_event_manager.subscribe_initial_copy_completion();
+
+    sender
+        .send(TableEvent::StartInitialCopy)
+        .await
+        .expect("send start initial copy");
+    sender
+        .send(TableEvent::Append {
+            row: create_row(1, "Alice", 30),
+            xact_id: None,
+            lsn: 0,
+            is_copied: true,
+        })
+        .await
+        .expect("send copied row");
+
+    // Checkpoint commits rows copied so far, which are persisted together with the copy position.
+    let mut position = InitialCopyPosition::default();
+    position.advance(
+        /*last_key=*/ 1, /*snapshot_lsn=*/ 10, /*rows_copied=*/ 1,
+    );
+    sender
+        .send(TableEvent::CheckpointInitialCopy {
+            position: position.clone(),
+        })
+        .await
+        .expect("send initial copy checkpoint");
+
+    sender
+        .send(TableEvent::Append {
+            row: create_row(2, "Bob", 40),
+            xact_id: None,
+            lsn: 0,
+            is_copied: true,
+        })
+        .await
+        .expect("send copied row");
+    sender
+        .send(TableEvent::FinishInitialCopy { start_lsn: 10 })
+        .await
+        .expect("send finish initial copy");
+
+    // Finished initial copy is persisted by periodic snapshots.
+    initial_copy_completion_rx
+        .wait_for(|persisted| *persisted)
+        .await
+        .unwrap();
+    let mut iceberg_table_manager =
+        env.create_iceberg_table_manager(MooncakeTableConfig::default());
+    let (_, snapshot) = iceberg_table_manager
+        .load_snapshot_from_table()
+        .await
+        .unwrap();
+    position.finish_lsn = Some(10);
+    assert_eq!(snapshot.initial_copy_position, position);
+    assert!(!snapshot.disk_files.is_empty());
+
+    env.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_initial_copy_abort() {
+    let mut env = TestEnvironment::default().await;
+    let sender = env.handler.get_event_sender();
+
+    sender
+        .send(TableEvent::StartInitialCopy)
+        .await
+        .expect("send start initial copy");
+    sender
+        .send(TableEvent::Append {
+            row: create_row(1, "Alice", 30),
+            xact_id: None,
+            lsn: 0,
+            is_copied: true,
+        })
+        .await
+        .expect("send copied row");
+
+    // Rows of the failed attempt are discarded, and the retried copy delivers them again.
+    sender
+        .send(TableEvent::AbortInitialCopy)
+        .await
+        .expect("send abort initial copy");
+    for (id, name) in [(1, "Alice"), (2, "Bob")] {
+        sender
+            .send(TableEvent::Append {
+                row: create_row(id, name, 30),
+                xact_id: None,
+                lsn: 0,
+                is_copied: true,
+            })
+            .await
+            .expect("send copied row");
+    }
+    sender
+        .send(TableEvent::FinishInitialCopy { start_lsn: 0 })
+        .await
+        .expect("send finish initial copy");
+
+    env.commit(10).await;
+    env.flush_table(10).await;
+    env.set_table_commit_lsn(10);
+    env.set_replication_lsn(10);
+    env.verify_snapshot(10, &[1, 2]).await;
+
+    env.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_fail_table() {
+    let mut env = TestEnvironment::default().await;
+    let sender = env.handler.get_event_sender();
+    let mut table_error_rx = env.table_event_manager.subscribe_table_error();
+
+    env.append_row(1, "Alice", 30, /*lsn=*/ 5, None).await;
+    env.commit(10).await;
+    env.flush_table_and_sync(10).await;
+
+    sender
+        .send(TableEvent::FailTable {
+            error: "initial copy failed".to_string(),
+        })
+        .await
+        .unwrap();
+    table_error_rx
+        .wait_for(|error| error.is_some())
+        .await
+        .unwrap();
+    assert_eq!(
+        table_error_rx.borrow().as_deref(),
+        Some("initial copy failed")
+    );
+
+    // Replication events are discarded once the table failed.
+    env.append_row(2, "Bob", 40, /*lsn=*/ 15, None).await;
+    env.commit(20).await;
+
+    // Force snapshot reports the failure instead of blocking.
+    let rx = env.table_event_manager.initiate_snapshot(/*lsn=*/ 20).await;
+    let res =
+        TableEventManager::synchronize_force_snapshot_request(rx, /*requested_lsn=*/ 20).await;
+    assert!(res.is_err());
+
+    env.verify_snapshot(10, &[1]).await;
+
+    // Failed table could still be dropped.
+    env.drop_table().await.unwrap();
+}
+
+/// Copied rows which fail to commit fail the table, instead of recording the initial copy as finished.
+#[tokio::test]
+async fn test_initial_copy_commit_failure_fails_table() {
+    let temp_dir = tempdir().unwrap();
+    // Place data files under a separate directory, so they could fail to be written without affecting iceberg table.
+    let data_directory = temp_dir.path().join("data");
+    tokio::fs::create_dir_all(&data_directory).await.unwrap();
+    let table_name = "table_name";
+    let iceberg_table_config = get_iceberg_manager_config(
+        table_name.to_string(),
+        temp_dir.path().to_str().unwrap().to_string(),
+    );
+    let mooncake_table = MooncakeTable::new(
+        (*create_test_arrow_schema()).clone(),
+        table_name.to_string(),
+        /*table_id=*/ 1,
+        data_directory.clone(),
+        crate::row::IdentityProp::Keys(vec![0]),
+        iceberg_table_config.clone(),
+        MooncakeTableConfig::new(temp_dir.path().to_str().unwrap().to_string()),
+        ObjectStorageCache::default_for_test(&temp_dir),
+        create_test_filesystem_accessor(&iceberg_table_config),
+    )
+    .await
+    .unwrap();
+    let mut env = TestEnvironment::new_with_mooncake_table(temp_dir, mooncake_table).await;
+    let sender = env.handler.get_event_sender();
+    let mut table_error_rx = env.table_event_manager.subscribe_table_error();
+
+    sender
+        .send(TableEvent::StartInitialCopy)
+        .await
+        .expect("send start initial copy");
+    sender
+        .send(TableEvent::Append {
+            row: create_row(1, "Alice", 30),
+            xact_id: None,
+            lsn: 0,
+            is_copied: true,
+        })
+        .await
+        .expect("send copied row");
+
+    // Copied rows can't be flushed to disk once the data directory is gone.
+    tokio::fs::remove_dir_all(&data_directory).await.unwrap();
+    sender
+        .send(TableEvent::FinishInitialCopy { start_lsn: 10 })
+        .await
+        .expect("send finish initial copy");
+    table_error_rx
+        .wait_for(|error| error.is_some())
+        .await
+        .unwrap();
+    assert!(table_error_rx
+        .borrow()
+        .as_deref()
+        .unwrap()
+        .starts_with("failed to finish initial copy"));
+
+    // Initial copy is not recorded as finished.
+    let mut iceberg_table_manager =
+        env.create_iceberg_table_manager(MooncakeTableConfig::default());
+    let (_, snapshot) = iceberg_table_manager
+        .load_snapshot_from_table()
+        .await
+        .unwrap();
+    assert!(snapshot.initial_copy_position.finish_lsn.is_none());
+
+    env.shutdown().await;
+}
+
+/// Schema change which can't be applied without losing data fails the table, instead of holding back replication.
+#[tokio::test]
+async fn test_alter_table_rejection_fails_table() {
+    let mut env = TestEnvironment::default().await;
+    let sender = env.handler.get_event_sender();
+    let mut table_error_rx = env.table_event_manager.subscribe_table_error();
+
+    env.append_row(1, "Alice", 30, /*lsn=*/ 5, None).await;
+    env.commit(10).await;
+    env.flush_table_and_sync(10).await;
+
+    // Identity column can't be dropped.
+    sender
+        .send(TableEvent::AlterTable {
+            alter_table_request: AlterTableRequest {
+                dropped_columns: vec!["id".to_string()],
+                ..Default::default()
+            },
+        })
+        .await
+        .unwrap();
+    table_error_rx
+        .wait_for(|error| error.is_some())
+        .await
+        .unwrap();
+    assert!(table_error_rx
+        .borrow()
+        .as_deref()
+        .unwrap()
+        .starts_with("failed to alter table"));
+
+    env.verify_snapshot(10, &[1]).await;
+    env.drop_table().await.unwrap();
+}
+
 #[tokio::test]
 async fn test_periodical_force_snapshot_with_empty_table() {
     let env = TestEnvironment::default().await;
@@ -1557,6 +1905,199 @@ async fn test_full_maintenance_with_sufficient_data_files() {
     assert_eq!(snapshot.indices.file_indices.len(), 1); // one compacted file index
 }
 
+/// Testing scenario: snapshot expiration after full compaction deletes data files only referenced by expired snapshots.
+#[tokio::test]
+async fn test_snapshot_expiration_after_full_maintenance() {
+    let temp_dir = tempdir().unwrap();
+    // Setup mooncake config, which expires all snapshots except the current one.
+    let mooncake_table_config = MooncakeTableConfig {
+        snapshot_retention_config: SnapshotRetentionConfig {
+            max_snapshot_age_ms: 0,
+            min_snapshots_to_keep: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut env = TestEnvironment::new(temp_dir, mooncake_table_config).await;
+
+    // Force snapshot expiration when there's no iceberg snapshot.
+    env.force_snapshot_expiration_and_sync().await.unwrap();
+
+    // Append two rows to the table, and flush right afterwards.
+    env.append_row(
+        /*id=*/ 2, /*name=*/ "Bob", /*age=*/ 40, /*lsn=*/ 5,
+        /*xact_id=*/ None,
+    )
+    .await;
+    env.commit(10).await;
+    env.flush_table_and_sync(/*lsn=*/ 10).await;
+
+    env.append_row(
+        /*id=*/ 3, /*name=*/ "Tom", /*age=*/ 50, /*lsn=*/ 15,
+        /*xact_id=*/ None,
+    )
+    .await;
+    env.commit(20).await;
+    env.flush_table_and_sync(/*lsn=*/ 20).await;
+
+    // Get data files before compaction.
+    let mut iceberg_table_manager =
+        env.create_iceberg_table_manager(MooncakeTableConfig::default());
+    let (_, snapshot) = iceberg_table_manager
+        .load_snapshot_from_table()
+        .await
+        .unwrap();
+    let old_data_files = snapshot
+        .disk_files
+        .keys()
+        .map(|data_file| data_file.file_path().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(old_data_files.len(), 2);
+
+    // Compact all data files, and expire snapshots which reference old data files.
+    env.force_full_maintenance_and_sync().await.unwrap();
+    env.force_snapshot_expiration_and_sync().await.unwrap();
+    for cur_data_file in old_data_files.iter() {
+        assert!(!tokio::fs::try_exists(cur_data_file).await.unwrap());
+    }
+
+    // Check mooncake snapshot.
+    env.verify_snapshot(/*target_lsn=*/ 20, /*ids=*/ &[2, 3])
+        .await;
+
+    // Check iceberg snapshot result.
+    let mut iceberg_table_manager =
+        env.create_iceberg_table_manager(MooncakeTableConfig::default());
+    let (_, snapshot) = iceberg_table_manager
+        .load_snapshot_from_table()
+        .await
+        .unwrap();
+    assert_eq!(snapshot.data_file_flush_lsn.unwrap(), 20);
+    assert_eq!(snapshot.disk_files.len(), 1); // one compacted data file
+    assert_eq!(snapshot.indices.file_indices.len(), 1); // one compacted file index
+}
+
+/// Testing scenario: snapshot expiration deletes orphan files and previous metadata files, which aren't referenced by any snapshot.
+#[tokio::test]
+async fn test_snapshot_expiration_deletes_orphan_and_metadata_files() {
+    let temp_dir = tempdir().unwrap();
+    let table_location = temp_dir.path().join("default").join("table_name");
+    // Setup mooncake config, which deletes all unreferenced files right away.
+    let mooncake_table_config = MooncakeTableConfig {
+        snapshot_retention_config: SnapshotRetentionConfig {
+            max_snapshot_age_ms: 0,
+            min_snapshots_to_keep: 1,
+            orphan_file_grace_period_ms: 0,
+            max_previous_metadata_files: 0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut env = TestEnvironment::new(temp_dir, mooncake_table_config).await;
+
+    // Create multiple iceberg snapshots, each of which writes a new metadata file.
+    env.append_row(
+        /*id=*/ 2, /*name=*/ "Bob", /*age=*/ 40, /*lsn=*/ 5,
+        /*xact_id=*/ None,
+    )
+    .await;
+    env.commit(10).await;
+    env.flush_table_and_sync(/*lsn=*/ 10).await;
+
+    env.append_row(
+        /*id=*/ 3, /*name=*/ "Tom", /*age=*/ 50, /*lsn=*/ 15,
+        /*xact_id=*/ None,
+    )
+    .await;
+    env.commit(20).await;
+    env.flush_table_and_sync(/*lsn=*/ 20).await;
+
+    // Place an orphan file under table location, which is left by a failed commit.
+    let orphan_file = table_location.join("orphan.parquet");
+    tokio::fs::write(&orphan_file, b"orphan").await.unwrap();
+
+    env.force_snapshot_expiration_and_sync().await.unwrap();
+    assert!(!tokio::fs::try_exists(&orphan_file).await.unwrap());
+
+    // Only the current metadata file is kept.
+    assert_eq!(count_metadata_files(&table_location).await, 1);
+
+    // Check iceberg snapshot result, referenced files are kept.
+    let mut iceberg_table_manager =
+        env.create_iceberg_table_manager(MooncakeTableConfig::default());
+    let (_, snapshot) = iceberg_table_manager
+        .load_snapshot_from_table()
+        .await
+        .unwrap();
+    assert_eq!(snapshot.data_file_flush_lsn.unwrap(), 20);
+    assert_eq!(snapshot.disk_files.len(), 2);
+    for cur_data_file in snapshot.disk_files.keys() {
+        assert!(tokio::fs::try_exists(cur_data_file.file_path())
+            .await
+            .unwrap());
+    }
+    env.verify_snapshot(/*target_lsn=*/ 20, /*ids=*/ &[2, 3])
+        .await;
+}
+
+/// Testing scenario: snapshot expiration keeps exactly the configured number of previous metadata files, besides the current one.
+#[tokio::test]
+async fn test_snapshot_expiration_keeps_previous_metadata_files() {
+    let temp_dir = tempdir().unwrap();
+    let table_location = temp_dir.path().join("default").join("table_name");
+    let mooncake_table_config = MooncakeTableConfig {
+        snapshot_retention_config: SnapshotRetentionConfig {
+            max_snapshot_age_ms: 0,
+            min_snapshots_to_keep: 1,
+            orphan_file_grace_period_ms: 0,
+            max_previous_metadata_files: 2,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut env = TestEnvironment::new(temp_dir, mooncake_table_config).await;
+
+    // Create more iceberg snapshots than metadata files to keep, each of which writes a new metadata file.
+    for id in 1..=4 {
+        let lsn = id as u64 * 10;
+        env.append_row(
+            id,
+            /*name=*/ "Bob",
+            /*age=*/ 40,
+            /*lsn=*/ lsn - 5,
+            /*xact_id=*/ None,
+        )
+        .await;
+        env.commit(lsn).await;
+        env.flush_table_and_sync(lsn).await;
+    }
+    assert!(count_metadata_files(&table_location).await > 3);
+
+    env.force_snapshot_expiration_and_sync().await.unwrap();
+    assert_eq!(count_metadata_files(&table_location).await, 3);
+    env.verify_snapshot(/*target_lsn=*/ 40, /*ids=*/ &[1, 2, 3, 4])
+        .await;
+}
+
+/// Count iceberg metadata files under the given table location.
+async fn count_metadata_files(table_location: &std::path::Path) -> usize {
+    let mut metadata_file_count = 0;
+    let mut entries = tokio::fs::read_dir(table_location.join("metadata"))
+        .await
+        .unwrap();
+    while let Some(entry) = entries.next_entry().await.unwrap() {
+        if entry
+            .file_name()
+            .to_str()
+            .unwrap()
+            .ends_with(".metadata.json")
+        {
+            metadata_file_count += 1;
+        }
+    }
+    metadata_file_count
+}
+
 /// Testing scenario: write operations no later than persisted LSN shall be discarded.
 #[tokio::test]
 async fn test_discard_duplicate_writes() {
@@ -1601,6 +2142,7 @@ async fn test_discard_duplicate_writes() {
     let mooncake_table = MooncakeTable::new_with_table_manager(
         mooncake_table_metadata,
         Box::new(mock_table_manager),
+        IcebergDeleteFileFormat::default(),
         ObjectStorageCache::default_for_test(&temp_dir),
         FileSystemAccessor::default_for_test(&temp_dir),
     )
@@ -1696,3 +2238,48 @@ fn test_get_persisted_table_lsn() {
         assert_eq!(persisted_table_lsn, 2);
     }
 }
+
+#[test]
+fn test_discard_events_after_initial_copy() {
+    let (table_maintenance_completion_tx, _) = broadcast::channel(64usize);
+    let (force_snapshot_completion_tx, _) = watch::channel(None);
+    let mut table_handler_state = TableHandlerState::new(
+        table_maintenance_completion_tx,
+        force_snapshot_completion_tx,
+        /*initial_persistence_lsn=*/ Some(0),
+    );
+
+    // Keys up to 100 are copied at LSN 10 before restart, the rest are copied at LSN 20.
+    let mut initial_copy_position = InitialCopyPosition::default();
+    initial_copy_position.advance(
+        /*last_key=*/ 100, /*snapshot_lsn=*/ 10, /*rows_copied=*/ 100,
+    );
+    initial_copy_position.finish_lsn = Some(20);
+    table_handler_state.initial_copy_position = initial_copy_position;
+    table_handler_state.initial_copy_key_index = Some(0);
+
+    let append = |id: i32, lsn: u64| TableEvent::Append {
+        row: create_row(id, "Alice", 30),
+        xact_id: None,
+        lsn,
+        is_copied: false,
+    };
+    assert!(table_handler_state.should_discard_event(&append(50, 10)));
+    assert!(!table_handler_state.should_discard_event(&append(50, 15)));
+    assert!(table_handler_state.should_discard_event(&append(150, 15)));
+    assert!(!table_handler_state.should_discard_event(&append(150, 25)));
+
+    // Commit is kept as long as any row could be applied.
+    assert!(
+        table_handler_state.should_discard_event(&TableEvent::Commit {
+            lsn: 10,
+            xact_id: None,
+        })
+    );
+    assert!(
+        !table_handler_state.should_discard_event(&TableEvent::Commit {
+            lsn: 15,
+            xact_id: None,
+        })
+    );
+}
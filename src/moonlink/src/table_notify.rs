@@ -1,10 +1,13 @@
 use crate::row::MoonlinkRow;
+use crate::storage::mooncake_table::AlterTableRequest;
 use crate::storage::mooncake_table::DataCompactionPayload;
 use crate::storage::mooncake_table::DataCompactionResult;
 use crate::storage::mooncake_table::FileIndiceMergePayload;
 use crate::storage::mooncake_table::FileIndiceMergeResult;
 use crate::storage::mooncake_table::IcebergSnapshotPayload;
 use crate::storage::mooncake_table::IcebergSnapshotResult;
+use crate::storage::mooncake_table::InitialCopyPosition;
+use crate::storage::mooncake_table::SnapshotExpirationResult;
 
 use crate::NonEvictableHandle;
 use crate::Result;
@@ -66,6 +69,12 @@ pub enum TableEvent {
     Commit { lsn: u64, xact_id: Option<u32> },
     /// Abort current stream with given xact_id
     StreamAbort { xact_id: u32 },
+    /// Prepare the transaction stream with given xact_id for two-phase commit, it's committed or rolled back later by its GID.
+    PrepareTransaction { xact_id: u32, gid: String },
+    /// Commit a prepared transaction with the given LSN.
+    CommitPrepared { gid: String, lsn: u64 },
+    /// Roll back a prepared transaction.
+    RollbackPrepared { gid: String },
     /// ==============================
     /// Test events
     /// ==============================
@@ -89,22 +98,36 @@ pub enum TableEvent {
     ForceRegularDataCompaction,
     /// Force a full table maintenance operation.
     ForceFullMaintenance,
+    /// Force to expire iceberg snapshots out of retention, and delete files no longer referenced.
+    ForceSnapshotExpiration,
     /// Drop table.
     DropTable,
-    /// Alter table,
-    AlterTable { columns_to_drop: Vec<String> },
+    /// Alter table schema.
+    AlterTable {
+        alter_table_request: AlterTableRequest,
+    },
     /// Start initial table copy.
     /// `start_lsn` is the `pg_current_wal_lsn` when the initial copy starts.
     StartInitialCopy,
+    /// Commit rows copied so far, which are persisted together with the given copy position, so the copy could resume from it.
+    CheckpointInitialCopy { position: InitialCopyPosition },
+    /// Discard copied rows which haven't been committed by a checkpoint, after a failed copy attempt.
+    /// The table stays in initial copy, which is retried from the last checkpoint, or redone if there's none.
+    AbortInitialCopy,
     /// Finish initial table copy and merge buffered changes.
     /// `start_lsn` is the `pg_current_wal_lsn` when the initial copy starts. We want this in FinishInitialCopy so we can set the commit LSN correctly.
     FinishInitialCopy { start_lsn: u64 },
+    /// Stop replication for the table with the given error, for example the initial copy keeps failing.
+    /// Buffered and later replication events are discarded, the table has to be dropped and added again.
+    FailTable { error: String },
     /// ==============================
     /// Table internal events
     /// ==============================
     ///
     /// Periodical mooncake snapshot.
     PeriodicalMooncakeTableSnapshot(uuid::Uuid),
+    /// Periodical iceberg snapshot expiration.
+    PeriodicalSnapshotExpiration(uuid::Uuid),
     /// Mooncake snapshot completes.
     MooncakeTableSnapshotResult {
         /// Mooncake snapshot LSN.
@@ -135,6 +158,11 @@ pub enum TableEvent {
         /// Result for data compaction.
         data_compaction_result: Result<DataCompactionResult>,
     },
+    /// Snapshot expiration completes.
+    SnapshotExpirationResult {
+        /// Result for snapshot expiration.
+        snapshot_expiration_result: SnapshotExpirationResult,
+    },
     /// Read request completion.
     ReadRequestCompletion {
         /// Cache handles, which are pinned before query.
@@ -157,6 +185,9 @@ impl TableEvent {
                     | TableEvent::Delete { .. }
                     | TableEvent::Commit { .. }
                     | TableEvent::StreamAbort { .. }
+                    | TableEvent::PrepareTransaction { .. }
+                    | TableEvent::CommitPrepared { .. }
+                    | TableEvent::RollbackPrepared { .. }
                     | TableEvent::CommitFlush { .. }
                     | TableEvent::StreamFlush { .. }
             )
@@ -169,16 +200,37 @@ impl TableEvent {
                     | TableEvent::Delete { .. }
                     | TableEvent::Commit { .. }
                     | TableEvent::StreamAbort { .. }
+                    | TableEvent::PrepareTransaction { .. }
+                    | TableEvent::CommitPrepared { .. }
+                    | TableEvent::RollbackPrepared { .. }
             )
         }
     }
 
+    /// Whether the event comes from replication, i.e. ingest events and table changes from the source, which are
+    /// discarded once the table fails.
+    pub fn is_replication_event(&self) -> bool {
+        self.is_ingest_event()
+            || matches!(
+                self,
+                TableEvent::AlterTable { .. }
+                    | TableEvent::StartInitialCopy
+                    | TableEvent::CheckpointInitialCopy { .. }
+                    | TableEvent::AbortInitialCopy
+                    | TableEvent::FinishInitialCopy { .. }
+                    | TableEvent::FailTable { .. }
+            )
+    }
+
     /// Whether current table event indicates a streaming write transaction.
     pub fn is_streaming_update(&self) -> bool {
         match &self {
             TableEvent::Append { xact_id, .. } => xact_id.is_some(),
             TableEvent::Delete { xact_id, .. } => xact_id.is_some(),
             TableEvent::StreamAbort { .. } => true,
+            TableEvent::PrepareTransaction { .. } => true,
+            TableEvent::CommitPrepared { .. } => true,
+            TableEvent::RollbackPrepared { .. } => true,
             TableEvent::Commit { xact_id, .. } => xact_id.is_some(),
             TableEvent::CommitFlush { xact_id, .. } => xact_id.is_some(),
             TableEvent::StreamFlush { .. } => true,
@@ -192,6 +244,7 @@ impl TableEvent {
             TableEvent::Delete { lsn, .. } => Some(*lsn),
             TableEvent::Commit { lsn, .. } => Some(*lsn),
             TableEvent::StreamAbort { .. } => None,
+            TableEvent::CommitPrepared { lsn, .. } => Some(*lsn),
             TableEvent::CommitFlush { lsn, .. } => Some(*lsn),
             _ => None,
         }
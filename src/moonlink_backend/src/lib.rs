@@ -7,13 +7,21 @@ mod recovery_utils;
 use arrow_schema::Schema;
 pub use error::{Error, Result};
 use mooncake_table_id::MooncakeTableId;
-pub use moonlink::ReadState;
-use moonlink::{TableEventManager, TableStatus};
+pub use moonlink::{
+    IcebergCatalogConfig, IcebergDeleteFileFormat, ParquetBloomFilterConfig,
+    ParquetCompressionCodec, ParquetSortField, ParquetSortStrategy, ParquetStatisticsLevel,
+    ParquetWriterConfig, ReadState,
+};
+use moonlink::{ReplicationConnectionStatus, TableEventManager, TableStatus};
+pub use moonlink_connectors::LogicalMessage;
 use moonlink_connectors::ReplicationManager;
+pub use moonlink_connectors::{
+    ConversionErrorPolicy, DeadLetterRecord, RawValue, RowOperation, TableOptions,
+};
 use moonlink_metadata_store::base_metadata_store::MetadataStoreTrait;
 use std::hash::Hash;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 
 pub struct MoonlinkBackend<
     D: std::convert::From<u32> + Eq + Hash + Clone + std::fmt::Display,
@@ -78,13 +86,35 @@ where
 
     /// # Arguments
     ///
-    /// * src_uri: connection string for source database (row storage database).
+    /// * src_uri: connection string for source database (row storage database), which could carry replication options;
+    ///   for example, `allow_replica_identity_full=true` alters the source table's replica identity to FULL if it has no
+    ///   usable key.
     pub async fn create_table(
         &self,
         database_id: D,
         table_id: T,
         src_table_name: String,
         src_uri: String,
+    ) -> Result<()> {
+        self.create_table_with_options(
+            database_id,
+            table_id,
+            src_table_name,
+            src_uri,
+            TableOptions::default(),
+        )
+        .await
+    }
+
+    /// Same as [`Self::create_table`], with options to create the mooncake table with, i.e. catalog of the iceberg
+    /// table; options are persisted in metadata store so they're kept across recovery.
+    pub async fn create_table_with_options(
+        &self,
+        database_id: D,
+        table_id: T,
+        src_table_name: String,
+        src_uri: String,
+        table_options: TableOptions,
     ) -> Result<()> {
         let mooncake_table_id = MooncakeTableId {
             database_id: database_id.clone(),
@@ -96,7 +126,6 @@ where
         // Add mooncake table to replication, and create corresponding mooncake table.
         let moonlink_table_config = {
             let mut manager = self.replication_manager.write().await;
-            // TODO(hjiang): Should pass real secrets into the mooncake table.
             let table_config = manager
                 .add_table(
                     &src_uri,
@@ -104,7 +133,7 @@ where
                     database_id,
                     table_id,
                     &src_table_name,
-                    /*override_iceberg_filesystem_config=*/ None,
+                    table_options,
                     /*is_recovery=*/ false,
                 )
                 .await?;
@@ -126,6 +155,79 @@ where
         Ok(())
     }
 
+    /// Get config of iceberg SQL catalog which shares database with metadata store, so query engines with JDBC catalog
+    /// could discover iceberg tables from the same database.
+    #[cfg(feature = "catalog-sql")]
+    pub fn get_sql_catalog_config(&self, catalog_name: String) -> IcebergCatalogConfig {
+        IcebergCatalogConfig::Sql {
+            uri: self.metadata_store_accessor.get_database_uri().to_string(),
+            catalog_name,
+        }
+    }
+
+    /// Create tables replicated from the same source together, they're copied within a single snapshot of the source,
+    /// so reads across these tables are consistent once the initial copy finishes.
+    ///
+    /// # Arguments
+    ///
+    /// * tables: table id and source table name of each table to create.
+    pub async fn create_tables(
+        &self,
+        database_id: D,
+        tables: Vec<(T, String)>,
+        src_uri: String,
+    ) -> Result<()> {
+        let mut table_ids = Vec::with_capacity(tables.len());
+        let mut tables_to_add = Vec::with_capacity(tables.len());
+        for (table_id, src_table_name) in tables {
+            let mooncake_table_id = MooncakeTableId {
+                database_id: database_id.clone(),
+                table_id,
+            };
+            let table_id = mooncake_table_id.get_table_id_value();
+            table_ids.push((table_id, src_table_name.clone()));
+            tables_to_add.push((mooncake_table_id, table_id, src_table_name));
+        }
+        let Some(database_id) = tables_to_add
+            .first()
+            .map(|(mooncake_table_id, _, _)| mooncake_table_id.get_database_id_value())
+        else {
+            return Ok(());
+        };
+
+        // Add mooncake tables to replication, and create corresponding mooncake tables.
+        let moonlink_table_configs = {
+            let mut manager = self.replication_manager.write().await;
+            let table_configs = manager
+                .add_tables(
+                    &src_uri,
+                    database_id,
+                    tables_to_add,
+                    TableOptions::default(),
+                )
+                .await?;
+            manager.start_replication(&src_uri).await?;
+            table_configs
+        };
+
+        // Create metadata store entries.
+        for ((table_id, src_table_name), moonlink_table_config) in
+            table_ids.into_iter().zip(moonlink_table_configs)
+        {
+            self.metadata_store_accessor
+                .store_table_metadata(
+                    database_id,
+                    table_id,
+                    &src_table_name,
+                    &src_uri,
+                    moonlink_table_config,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
     pub async fn drop_table(&self, database_id: D, table_id: T) {
         let mooncake_table_id = MooncakeTableId {
             database_id: database_id.clone(),
@@ -176,10 +278,11 @@ where
     /// Perform a table maintaince operation based on requested mode, block wait until maintenance results have been persisted.
     /// Notice, it's only exposed for debugging, testing and admin usage.
     ///
-    /// There're currently three modes supported:
+    /// There're currently four modes supported:
     /// - "data": perform a data compaction, only data files smaller than a threshold, or with too many deleted rows will be compacted.
     /// - "index": perform an index merge operation, only index files smaller than a threshold, or with too many deleted rows will be merged.    
     /// - "full": perform a full compaction, which merges all data files and all index files, whatever file size they are of.
+    /// - "expire": expire iceberg snapshots out of retention, and delete files which are no longer referenced by any retained snapshot.
     pub async fn optimize_table(&self, database_id: D, table_id: T, mode: &str) -> Result<()> {
         let mut rx = {
             let mut manager = self.replication_manager.write().await;
@@ -193,9 +296,10 @@ where
                 "data" => writer.initiate_data_compaction().await,
                 "index" => writer.initiate_index_merge().await,
                 "full" => writer.initiate_full_compaction().await,
+                "expire" => writer.initiate_snapshot_expiration().await,
                 _ => {
                     return Err(Error::InvalidArgumentError(format!(
-                        "Unrecognizable table optimization mode `{mode}`, expected one of `data`, `index`, or `full`"
+                        "Unrecognizable table optimization mode `{mode}`, expected one of `data`, `index`, `full`, or `expire`"
                     )))
                 }
             }
@@ -205,6 +309,57 @@ where
         Ok(())
     }
 
+    /// Set policy for rows of the table which fail conversion in the replication stream, effective for subsequent changes.
+    ///
+    /// There're three policies supported:
+    /// - "fail": the default, fail the table, which stops its replication until it's dropped and added again.
+    /// - "skip": skip the row and log the error.
+    /// - "dead_letter": skip the row and persist it to the table's dead letter file, which could be read and replayed later.
+    pub async fn set_conversion_error_policy(
+        &self,
+        database_id: D,
+        table_id: T,
+        policy: &str,
+    ) -> Result<()> {
+        let policy = policy
+            .parse::<ConversionErrorPolicy>()
+            .map_err(Error::InvalidArgumentError)?;
+        let manager = self.replication_manager.read().await;
+        let mooncake_table_id = MooncakeTableId {
+            database_id,
+            table_id,
+        };
+        manager
+            .set_conversion_error_policy(&mooncake_table_id, policy)
+            .await?;
+        Ok(())
+    }
+
+    /// Read rows of the table which failed conversion and are kept in its dead letter file.
+    pub async fn read_dead_letters(
+        &self,
+        database_id: D,
+        table_id: T,
+    ) -> Result<Vec<DeadLetterRecord>> {
+        let manager = self.replication_manager.read().await;
+        let mooncake_table_id = MooncakeTableId {
+            database_id,
+            table_id,
+        };
+        Ok(manager.read_dead_letters(&mooncake_table_id).await?)
+    }
+
+    /// Replay dead letters of the table with its current schema, for example after the source column has been fixed.
+    /// Rows which still fail conversion are kept in the dead letter file; returns the number of replayed row events.
+    pub async fn replay_dead_letters(&self, database_id: D, table_id: T) -> Result<usize> {
+        let manager = self.replication_manager.read().await;
+        let mooncake_table_id = MooncakeTableId {
+            database_id,
+            table_id,
+        };
+        Ok(manager.replay_dead_letters(&mooncake_table_id).await?)
+    }
+
     pub async fn scan_table(
         &self,
         database_id: D,
@@ -224,6 +379,30 @@ where
         Ok(read_state.clone())
     }
 
+    /// Subscribe to logical decoding messages (emitted by `pg_logical_emit_message`) of the source identified by its URI.
+    /// Only messages decoded after subscription are received, the source must already be replicated by at least one table.
+    pub async fn subscribe_logical_messages(
+        &self,
+        src_uri: &str,
+    ) -> Result<broadcast::Receiver<LogicalMessage>> {
+        let manager = self.replication_manager.read().await;
+        manager.subscribe_logical_messages(src_uri).ok_or_else(|| {
+            Error::InvalidArgumentError(format!("Source {src_uri} is not being replicated"))
+        })
+    }
+
+    /// Get replication status of the source identified by its URI, including up-to-date health of its replication slot.
+    pub async fn get_source_status(&self, src_uri: &str) -> Result<ReplicationConnectionStatus> {
+        let manager = self.replication_manager.read().await;
+        let status = manager
+            .get_replication_status(src_uri)
+            .await
+            .ok_or_else(|| {
+                Error::InvalidArgumentError(format!("Source {src_uri} is not being replicated"))
+            })??;
+        Ok(status)
+    }
+
     /// Gracefully shutdown a replication connection identified by its URI.
     pub async fn shutdown_connection(&self, uri: &str) {
         let mut manager = self.replication_manager.write().await;
@@ -1,6 +1,6 @@
 use crate::error::Result;
 use crate::mooncake_table_id::MooncakeTableId;
-use moonlink_connectors::ReplicationManager;
+use moonlink_connectors::{ReplicationManager, TableOptions};
 use moonlink_metadata_store::base_metadata_store::{MetadataStoreTrait, TableMetadataEntry};
 
 use std::collections::HashSet;
@@ -19,6 +19,16 @@ where
         database_id: D::from(metadata_entry.database_id),
         table_id: T::from(metadata_entry.table_id),
     };
+    let iceberg_table_config = metadata_entry.moonlink_table_config.iceberg_table_config;
+    let table_options = TableOptions {
+        iceberg_filesystem_config: Some(iceberg_table_config.filesystem_config),
+        iceberg_catalog_config: iceberg_table_config.catalog,
+        iceberg_delete_file_format: iceberg_table_config.delete_file_format,
+        parquet_writer_config: metadata_entry
+            .moonlink_table_config
+            .mooncake_table_config
+            .parquet_writer_config,
+    };
     replication_manager
         .add_table(
             &metadata_entry.src_table_uri,
@@ -26,13 +36,7 @@ where
             metadata_entry.database_id,
             metadata_entry.table_id,
             &metadata_entry.src_table_name,
-            /*iceberg_filesystem_config=*/
-            Some(
-                metadata_entry
-                    .moonlink_table_config
-                    .iceberg_table_config
-                    .filesystem_config,
-            ),
+            table_options,
             /*is_recovery=*/ true,
         )
         .await?;
@@ -3,11 +3,13 @@ mod common;
 #[cfg(test)]
 mod tests {
     use super::common::{
-        current_wal_lsn, ids_from_state, smoke_create_and_insert, DatabaseId, TableId, TestGuard,
-        TestGuardMode, TABLE_ID,
+        current_wal_lsn, ids_from_state, ids_from_state_with_deletes, read_ids_from_parquet,
+        smoke_create_and_insert, DatabaseId, TableId, TestGuard, TestGuardMode, TABLE_ID,
+    };
+    use moonlink::{decode_read_state_for_testing, ReplicationConnectionStatus, TableStatus};
+    use moonlink_backend::{
+        IcebergDeleteFileFormat, MoonlinkBackend, RawValue, RowOperation, TableOptions,
     };
-    use moonlink::TableStatus;
-    use moonlink_backend::MoonlinkBackend;
     use moonlink_metadata_store::{base_metadata_store::MetadataStoreTrait, SqliteMetadataStore};
 
     use serial_test::serial;
@@ -115,6 +117,590 @@ mod tests {
         assert_eq!(ids, HashSet::from([1, 2]));
     }
 
+    /// Tables with a primary key keep their DEFAULT replica identity, and updates/deletes are replicated by key.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[serial]
+    async fn test_respect_existing_replica_identity() {
+        let (guard, client) = TestGuard::new(Some("ident_test")).await;
+        let backend = guard.backend();
+
+        let row = client
+            .query_one(
+                "SELECT relreplident::text FROM pg_class WHERE oid = 'public.ident_test'::regclass",
+                &[],
+            )
+            .await
+            .unwrap();
+        let replica_identity: String = row.get(0);
+        assert_eq!(replica_identity, "d");
+
+        client
+            .simple_query(
+                "INSERT INTO ident_test VALUES (1,'a'),(2,'b');
+                 UPDATE ident_test SET name = 'c' WHERE id = 1;
+                 DELETE FROM ident_test WHERE id = 2;",
+            )
+            .await
+            .unwrap();
+        let lsn = current_wal_lsn(&client).await;
+
+        let ids = ids_from_state_with_deletes(
+            &backend
+                .scan_table(guard.database_id, TABLE_ID, Some(lsn))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(ids, HashSet::from([1]));
+    }
+
+    /// Tables without a usable replica identity are rejected, instead of being altered to FULL implicitly.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[serial]
+    async fn test_reject_table_without_replica_identity_key() {
+        let (guard, client) = TestGuard::new(Some("ident_test")).await;
+        let backend = guard.backend();
+
+        client
+            .simple_query(
+                "DROP TABLE IF EXISTS ident_no_key_test;
+                 CREATE TABLE ident_no_key_test (id BIGINT, name TEXT);",
+            )
+            .await
+            .unwrap();
+        let res = backend
+            .create_table(
+                guard.database_id,
+                TABLE_ID + 1,
+                /*table_name=*/ "public.ident_no_key_test".to_string(),
+                SRC_URI.to_string(),
+            )
+            .await;
+        assert!(res.is_err());
+
+        let row = client
+            .query_one(
+                "SELECT relreplident::text FROM pg_class WHERE oid = 'public.ident_no_key_test'::regclass",
+                &[],
+            )
+            .await
+            .unwrap();
+        let replica_identity: String = row.get(0);
+        assert_eq!(replica_identity, "d");
+    }
+
+    /// Tables without a usable replica identity are altered to FULL, if opted in through the connection string.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[serial]
+    async fn test_opt_in_replica_identity_full() {
+        let (guard, client) = TestGuard::new(Some("ident_test")).await;
+        let backend = guard.backend();
+
+        client
+            .simple_query(
+                "DROP TABLE IF EXISTS ident_full_test;
+                 CREATE TABLE ident_full_test (id BIGINT, name TEXT);",
+            )
+            .await
+            .unwrap();
+        let src_uri =
+            format!("{SRC_URI}?slot_name=ident_full_slot&allow_replica_identity_full=true");
+        backend
+            .create_table(
+                guard.database_id,
+                TABLE_ID + 1,
+                /*table_name=*/ "public.ident_full_test".to_string(),
+                src_uri.clone(),
+            )
+            .await
+            .unwrap();
+
+        let row = client
+            .query_one(
+                "SELECT relreplident::text FROM pg_class WHERE oid = 'public.ident_full_test'::regclass",
+                &[],
+            )
+            .await
+            .unwrap();
+        let replica_identity: String = row.get(0);
+        assert_eq!(replica_identity, "f");
+
+        backend.drop_table(guard.database_id, TABLE_ID + 1).await;
+        backend.shutdown_connection(&src_uri).await;
+        let _ = client
+            .simple_query(
+                "SELECT pg_terminate_backend(active_pid) FROM pg_replication_slots WHERE slot_name = 'ident_full_slot';",
+            )
+            .await;
+        let _ = client
+            .simple_query("SELECT pg_drop_replication_slot('ident_full_slot');")
+            .await;
+    }
+
+    /// Replicate through a user-managed publication and slot, honoring its column list and row filter.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[serial]
+    async fn test_user_supplied_publication_and_slot() {
+        let (guard, client) = TestGuard::new(Some("pub_filter_test")).await;
+        let backend = guard.backend();
+
+        client
+            .simple_query(
+                "DROP TABLE IF EXISTS user_pub_test;
+                 CREATE TABLE user_pub_test (id BIGINT PRIMARY KEY, name TEXT, secret TEXT);
+                 INSERT INTO user_pub_test VALUES (1,'a','x'),(2,'b','y');
+                 DROP PUBLICATION IF EXISTS user_pub;
+                 CREATE PUBLICATION user_pub FOR TABLE user_pub_test (id, name) WHERE (id > 1);",
+            )
+            .await
+            .unwrap();
+        let src_uri = format!("{SRC_URI}?publication=user_pub&slot_name=user_slot");
+        backend
+            .create_table(
+                guard.database_id,
+                TABLE_ID + 1,
+                /*table_name=*/ "public.user_pub_test".to_string(),
+                src_uri.clone(),
+            )
+            .await
+            .unwrap();
+
+        client
+            .simple_query("INSERT INTO user_pub_test VALUES (0,'c','z'),(3,'d','w');")
+            .await
+            .unwrap();
+        let lsn = current_wal_lsn(&client).await;
+        let ids = ids_from_state(
+            &backend
+                .scan_table(guard.database_id, TABLE_ID + 1, Some(lsn))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(ids, HashSet::from([2, 3]));
+
+        backend.drop_table(guard.database_id, TABLE_ID + 1).await;
+
+        // User-managed publication is kept after moonlink stops replicating from it.
+        let row = client
+            .query_one(
+                "SELECT count(*) FROM pg_publication_tables WHERE pubname = 'user_pub'",
+                &[],
+            )
+            .await
+            .unwrap();
+        let published_tables: i64 = row.get(0);
+        assert_eq!(published_tables, 1);
+        client
+            .simple_query("DROP PUBLICATION user_pub;")
+            .await
+            .unwrap();
+        let _ = client
+            .simple_query(
+                "SELECT pg_terminate_backend(active_pid) FROM pg_replication_slots WHERE slot_name = 'user_slot';",
+            )
+            .await;
+        let _ = client
+            .simple_query("SELECT pg_drop_replication_slot('user_slot');")
+            .await;
+    }
+
+    /// Logical decoding messages are passed through, transactional ones only after commit with the commit LSN.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[serial]
+    async fn test_logical_message_passthrough() {
+        let (guard, client) = TestGuard::new(Some("message_test")).await;
+        let backend = guard.backend();
+        let mut message_rx = backend.subscribe_logical_messages(SRC_URI).await.unwrap();
+
+        client
+            .simple_query(
+                "BEGIN;
+                 INSERT INTO message_test VALUES (1,'a');
+                 SELECT pg_logical_emit_message(true, 'checkpoint', 'txn');
+                 COMMIT;
+                 BEGIN;
+                 SELECT pg_logical_emit_message(true, 'checkpoint', 'aborted');
+                 ROLLBACK;
+                 SELECT pg_logical_emit_message(false, 'heartbeat', 'non-txn');",
+            )
+            .await
+            .unwrap();
+
+        let message = message_rx.recv().await.unwrap();
+        assert_eq!(message.prefix, "checkpoint");
+        assert_eq!(message.content, b"txn".to_vec());
+        let commit_lsn = message.commit_lsn.unwrap();
+        assert!(commit_lsn > message.lsn);
+        let ids = ids_from_state(
+            &backend
+                .scan_table(guard.database_id, TABLE_ID, Some(commit_lsn))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(ids, HashSet::from([1]));
+
+        let message = message_rx.recv().await.unwrap();
+        assert_eq!(message.prefix, "heartbeat");
+        assert_eq!(message.content, b"non-txn".to_vec());
+        assert_eq!(message.commit_lsn, None);
+    }
+
+    /// Validates that source status reports health of the replication slot.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[serial]
+    async fn test_source_status_reports_slot_health() {
+        let (guard, _client) = TestGuard::new(Some("slot_health_test")).await;
+        let backend = guard.backend();
+
+        let status = backend.get_source_status(SRC_URI).await.unwrap();
+        let slot_status = status.slot_status.unwrap();
+        assert!(slot_status.exists);
+        assert!(!slot_status.is_lost());
+        assert!(slot_status.wal_status.is_some());
+        assert!(slot_status.restart_lsn.is_some());
+        assert!(slot_status.confirmed_flush_lsn.is_some());
+
+        assert!(backend
+            .get_source_status("postgresql://unknown")
+            .await
+            .is_err());
+    }
+
+    /// Rows which fail conversion are kept in the dead letter file under `dead_letter` policy, while replication continues.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[serial]
+    async fn test_dead_letter_conversion_failure() {
+        let (guard, client) = TestGuard::new(Some("dead_letter_base_test")).await;
+        let backend = guard.backend();
+
+        client
+            .simple_query(
+                "DROP TABLE IF EXISTS dead_letter_test;
+                 CREATE TABLE dead_letter_test (id BIGINT PRIMARY KEY, day DATE);",
+            )
+            .await
+            .unwrap();
+        backend
+            .create_table(
+                guard.database_id,
+                TABLE_ID + 1,
+                /*table_name=*/ "public.dead_letter_test".to_string(),
+                SRC_URI.to_string(),
+            )
+            .await
+            .unwrap();
+        assert!(backend
+            .set_conversion_error_policy(guard.database_id, TABLE_ID + 1, "unknown")
+            .await
+            .is_err());
+        backend
+            .set_conversion_error_policy(guard.database_id, TABLE_ID + 1, "dead_letter")
+            .await
+            .unwrap();
+
+        // Infinite date is not supported in conversion.
+        client
+            .simple_query("INSERT INTO dead_letter_test VALUES (1, 'infinity');")
+            .await
+            .unwrap();
+        client
+            .simple_query("INSERT INTO dead_letter_test VALUES (2, '2024-01-01');")
+            .await
+            .unwrap();
+        let lsn = current_wal_lsn(&client).await;
+        let ids = ids_from_state(
+            &backend
+                .scan_table(guard.database_id, TABLE_ID + 1, Some(lsn))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(ids, HashSet::from([2]));
+
+        let records = backend
+            .read_dead_letters(guard.database_id, TABLE_ID + 1)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].operation, RowOperation::Insert);
+        assert_eq!(
+            records[0].new_tuple,
+            Some(vec![
+                RawValue::Text(b"1".to_vec()),
+                RawValue::Text(b"infinity".to_vec())
+            ])
+        );
+
+        // Row still fails conversion, so it's kept.
+        let replayed_rows = backend
+            .replay_dead_letters(guard.database_id, TABLE_ID + 1)
+            .await
+            .unwrap();
+        assert_eq!(replayed_rows, 0);
+        assert_eq!(
+            backend
+                .read_dead_letters(guard.database_id, TABLE_ID + 1)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // Row changed since, its dead letter is dropped rather than replayed over the newer row.
+        client
+            .simple_query("UPDATE dead_letter_test SET day = '2024-02-01' WHERE id = 1;")
+            .await
+            .unwrap();
+        let lsn = current_wal_lsn(&client).await;
+        let ids = ids_from_state(
+            &backend
+                .scan_table(guard.database_id, TABLE_ID + 1, Some(lsn))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(ids, HashSet::from([1, 2]));
+        assert!(backend
+            .read_dead_letters(guard.database_id, TABLE_ID + 1)
+            .await
+            .unwrap()
+            .is_empty());
+
+        backend.drop_table(guard.database_id, TABLE_ID + 1).await;
+    }
+
+    /// A row which fails conversion under the default `fail` policy fails the table, while other tables keep replicating.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[serial]
+    async fn test_fail_conversion_failure() {
+        let (guard, client) = TestGuard::new(Some("fail_conversion_base_test")).await;
+        let backend = guard.backend();
+
+        client
+            .simple_query(
+                "DROP TABLE IF EXISTS fail_conversion_test;
+                 CREATE TABLE fail_conversion_test (id BIGINT PRIMARY KEY, day DATE);",
+            )
+            .await
+            .unwrap();
+        backend
+            .create_table(
+                guard.database_id,
+                TABLE_ID + 1,
+                /*table_name=*/ "public.fail_conversion_test".to_string(),
+                SRC_URI.to_string(),
+            )
+            .await
+            .unwrap();
+
+        // Infinite date is not supported in conversion.
+        client
+            .simple_query("INSERT INTO fail_conversion_test VALUES (1, 'infinity');")
+            .await
+            .unwrap();
+        let error = loop {
+            let table_statuses = backend.list_tables().await.unwrap();
+            let table_status = table_statuses
+                .iter()
+                .find(|table_status| table_status.table_id == TABLE_ID + 1)
+                .unwrap();
+            if let Some(error) = table_status.error.clone() {
+                break error;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        };
+        assert!(error.contains("row conversion failed"), "{error}");
+
+        // Replication isn't restarted, and keeps going for other tables.
+        client
+            .simple_query("INSERT INTO fail_conversion_base_test VALUES (1, 'a');")
+            .await
+            .unwrap();
+        let lsn = current_wal_lsn(&client).await;
+        let ids = ids_from_state(
+            &backend
+                .scan_table(guard.database_id, TABLE_ID, Some(lsn))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(ids, HashSet::from([1]));
+        let status = backend.get_source_status(SRC_URI).await.unwrap();
+        assert!(status.connected);
+        assert!(status.last_error.is_none());
+
+        backend.drop_table(guard.database_id, TABLE_ID + 1).await;
+    }
+
+    /// Tables of different databases share one replication connection, if their URIs resolve to the same source database with `share_slot`.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[serial]
+    async fn test_share_slot_across_databases() {
+        let (guard, client) = TestGuard::new(Some("share_slot_base_test")).await;
+        let backend = guard.backend();
+
+        client
+            .simple_query(
+                "DROP TABLE IF EXISTS share_slot_test_a;
+                 DROP TABLE IF EXISTS share_slot_test_b;
+                 CREATE TABLE share_slot_test_a (id BIGINT PRIMARY KEY, name TEXT);
+                 CREATE TABLE share_slot_test_b (id BIGINT PRIMARY KEY, name TEXT);
+                 DROP PUBLICATION IF EXISTS shared_pub;
+                 CREATE PUBLICATION shared_pub FOR TABLE share_slot_test_a, share_slot_test_b;",
+            )
+            .await
+            .unwrap();
+        let src_uri_a =
+            format!("{SRC_URI}?publication=shared_pub&slot_name=shared_slot&share_slot=true");
+        let src_uri_b = format!(
+            "{SRC_URI}?application_name=other&share_slot=true&slot_name=shared_slot&publication=shared_pub"
+        );
+        let other_database_id = guard.database_id + 1;
+        backend
+            .create_table(
+                guard.database_id,
+                TABLE_ID + 1,
+                /*table_name=*/ "public.share_slot_test_a".to_string(),
+                src_uri_a.clone(),
+            )
+            .await
+            .unwrap();
+        backend
+            .create_table(
+                other_database_id,
+                TABLE_ID + 1,
+                /*table_name=*/ "public.share_slot_test_b".to_string(),
+                src_uri_b.clone(),
+            )
+            .await
+            .unwrap();
+
+        let status = backend.get_source_status(&src_uri_b).await.unwrap();
+        assert_eq!(
+            status.usage,
+            backend.get_source_status(&src_uri_a).await.unwrap().usage
+        );
+        assert_eq!(status.usage.slot_name, "shared_slot");
+        assert_eq!(status.usage.publication, "shared_pub");
+        assert_eq!(status.usage.table_count, 2);
+        assert_eq!(
+            status.usage.database_ids,
+            vec![guard.database_id, other_database_id]
+        );
+
+        client
+            .simple_query(
+                "INSERT INTO share_slot_test_a VALUES (1,'a');
+                 INSERT INTO share_slot_test_b VALUES (2,'b');",
+            )
+            .await
+            .unwrap();
+        let lsn = current_wal_lsn(&client).await;
+        let ids_a = ids_from_state(
+            &backend
+                .scan_table(guard.database_id, TABLE_ID + 1, Some(lsn))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(ids_a, HashSet::from([1]));
+        let ids_b = ids_from_state(
+            &backend
+                .scan_table(other_database_id, TABLE_ID + 1, Some(lsn))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(ids_b, HashSet::from([2]));
+
+        // Connection is kept for the remaining table.
+        backend.drop_table(guard.database_id, TABLE_ID + 1).await;
+        let status = backend.get_source_status(&src_uri_a).await.unwrap();
+        assert_eq!(status.usage.table_count, 1);
+        assert_eq!(status.usage.database_ids, vec![other_database_id]);
+
+        backend.drop_table(other_database_id, TABLE_ID + 1).await;
+        client
+            .simple_query("DROP PUBLICATION shared_pub;")
+            .await
+            .unwrap();
+        let _ = client
+            .simple_query(
+                "SELECT pg_terminate_backend(active_pid) FROM pg_replication_slots WHERE slot_name = 'shared_slot';",
+            )
+            .await;
+        let _ = client
+            .simple_query("SELECT pg_drop_replication_slot('shared_slot');")
+            .await;
+    }
+
+    /// Replication reconnects with backoff while the source refuses connections, and resumes from the slot's
+    /// confirmed flush LSN once it's back, delivering changes made in between exactly once.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[serial]
+    async fn test_reconnect_with_backoff() {
+        let (guard, client) = TestGuard::new(Some("reconnect_test")).await;
+        let backend = guard.backend();
+
+        client
+            .simple_query("INSERT INTO reconnect_test VALUES (1,'a');")
+            .await
+            .unwrap();
+        let lsn = current_wal_lsn(&client).await;
+        backend
+            .scan_table(guard.database_id, TABLE_ID, Some(lsn))
+            .await
+            .unwrap();
+
+        // Refuse new connections and drop the replication connection, so reconnect attempts fail until allowed again.
+        client
+            .simple_query("ALTER DATABASE postgres ALLOW_CONNECTIONS false;")
+            .await
+            .unwrap();
+        client
+            .simple_query(
+                "SELECT pg_terminate_backend(active_pid) FROM pg_replication_slots WHERE slot_name = 'moonlink_slot_postgres';",
+            )
+            .await
+            .unwrap();
+        client
+            .simple_query("INSERT INTO reconnect_test VALUES (2,'b'),(3,'c');")
+            .await
+            .unwrap();
+        let mut replication_status = ReplicationConnectionStatus::default();
+        for _ in 0..100 {
+            let table_statuses = backend.list_tables().await.unwrap();
+            replication_status = table_statuses[0].replication_status.clone().unwrap();
+            if replication_status.reconnect_attempts >= 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        // Allow connections before any assertion, so failures don't leave the database inaccessible.
+        client
+            .simple_query("ALTER DATABASE postgres ALLOW_CONNECTIONS true;")
+            .await
+            .unwrap();
+        assert!(!replication_status.connected);
+        assert!(replication_status.reconnect_attempts >= 2);
+        assert!(replication_status.last_error.is_some());
+
+        // Rows before the disconnect are replayed from the confirmed flush LSN, but only delivered once.
+        let lsn = current_wal_lsn(&client).await;
+        let read_state = backend
+            .scan_table(guard.database_id, TABLE_ID, Some(lsn))
+            .await
+            .unwrap();
+        let (data_files, _, _, _) = decode_read_state_for_testing(&read_state);
+        let mut ids: Vec<i64> = data_files
+            .iter()
+            .flat_map(|data_file| read_ids_from_parquet(data_file).into_iter().flatten())
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        let table_statuses = backend.list_tables().await.unwrap();
+        let replication_status = table_statuses[0].replication_status.clone().unwrap();
+        assert!(replication_status.connected);
+        assert_eq!(replication_status.reconnect_attempts, 0);
+        assert_eq!(replication_status.reconnect_count, 1);
+    }
+
     /// Validates that `create_iceberg_snapshot` writes Iceberg metadata.
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     #[serial]
@@ -159,6 +745,12 @@ mod tests {
             commit_lsn: lsn,
             flush_lsn: Some(lsn),
             iceberg_warehouse_location: guard.tmp().unwrap().path().to_str().unwrap().to_string(),
+            initial_copy_progress: None,
+            replication_status: Some(ReplicationConnectionStatus {
+                connected: true,
+                ..Default::default()
+            }),
+            error: None,
         };
         assert_eq!(table_statuses, vec![expected_table_status]);
     }
@@ -375,4 +967,127 @@ mod tests {
         );
         assert_eq!(ids, HashSet::from([1, 2]));
     }
+
+    /// Testing scenario: create table with SQL catalog sharing database with metadata store, and check persisted catalog.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[serial]
+    #[cfg(feature = "catalog-sql")]
+    async fn test_create_table_with_sql_catalog() {
+        let (guard, client) = TestGuard::new(Some("sql_catalog")).await;
+        let backend = guard.backend();
+        backend.drop_table(guard.database_id, TABLE_ID).await;
+
+        let catalog_config = backend.get_sql_catalog_config("moonlink".to_string());
+        backend
+            .create_table_with_options(
+                guard.database_id,
+                TABLE_ID,
+                "public.sql_catalog".to_string(),
+                SRC_URI.to_string(),
+                TableOptions {
+                    iceberg_catalog_config: catalog_config.clone(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        client
+            .simple_query("INSERT INTO sql_catalog VALUES (1,'first');")
+            .await
+            .unwrap();
+        let lsn = current_wal_lsn(&client).await;
+        let ids = ids_from_state(
+            &backend
+                .scan_table(guard.database_id, TABLE_ID, Some(lsn))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(ids, HashSet::from([1]));
+        // Iceberg snapshot commits through SQL catalog.
+        backend
+            .create_snapshot(guard.database_id, TABLE_ID, lsn)
+            .await
+            .unwrap();
+
+        // Catalog is persisted, so it's kept across recovery.
+        let database_directory = guard.tmp().as_ref().unwrap().path().to_str().unwrap();
+        let metadata_store = SqliteMetadataStore::new_with_directory(database_directory)
+            .await
+            .unwrap();
+        let metadata_entries = metadata_store
+            .get_all_table_metadata_entries()
+            .await
+            .unwrap();
+        assert_eq!(metadata_entries.len(), 1);
+        assert_eq!(
+            metadata_entries[0]
+                .moonlink_table_config
+                .iceberg_table_config
+                .catalog,
+            catalog_config
+        );
+    }
+
+    /// Testing scenario: create table which persists row deletions as position delete files, and check persisted delete file format.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[serial]
+    async fn test_create_table_with_position_delete() {
+        let (guard, client) = TestGuard::new(Some("position_delete")).await;
+        let backend = guard.backend();
+        backend.drop_table(guard.database_id, TABLE_ID).await;
+
+        backend
+            .create_table_with_options(
+                guard.database_id,
+                TABLE_ID,
+                "public.position_delete".to_string(),
+                SRC_URI.to_string(),
+                TableOptions {
+                    iceberg_delete_file_format: IcebergDeleteFileFormat::PositionDelete,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        client
+            .simple_query(
+                "INSERT INTO position_delete VALUES (1,'a'),(2,'b');
+                 DELETE FROM position_delete WHERE id = 2;",
+            )
+            .await
+            .unwrap();
+        let lsn = current_wal_lsn(&client).await;
+        backend
+            .create_snapshot(guard.database_id, TABLE_ID, lsn)
+            .await
+            .unwrap();
+        let ids = ids_from_state_with_deletes(
+            &backend
+                .scan_table(guard.database_id, TABLE_ID, Some(lsn))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(ids, HashSet::from([1]));
+
+        // Delete file format is persisted, so it's kept across recovery.
+        let database_directory = guard.tmp().as_ref().unwrap().path().to_str().unwrap();
+        let metadata_store = SqliteMetadataStore::new_with_directory(database_directory)
+            .await
+            .unwrap();
+        let metadata_entries = metadata_store
+            .get_all_table_metadata_entries()
+            .await
+            .unwrap();
+        assert_eq!(metadata_entries.len(), 1);
+        assert_eq!(
+            metadata_entries[0]
+                .moonlink_table_config
+                .iceberg_table_config
+                .delete_file_format,
+            IcebergDeleteFileFormat::PositionDelete
+        );
+    }
 }
@@ -69,6 +69,18 @@ mod tests {
 
         assert_eq!(ids, HashSet::from([1, 2, 3]));
 
+        // Initial copy progress is reported as part of table status.
+        let progress = loop {
+            let table_statuses = backend.list_tables().await.unwrap();
+            let progress = table_statuses[0].initial_copy_progress.clone().unwrap();
+            if progress.finished {
+                break progress;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        };
+        assert_eq!(progress.rows_copied, 2);
+        assert_eq!(progress.estimated_total_rows, Some(2));
+
         // Manually drop the table we created
         initial_client
             .simple_query(&format!("DROP TABLE IF EXISTS {table_name};"))
@@ -603,4 +615,72 @@ mod tests {
             .unwrap();
         let _ = backend.drop_table(guard.database_id, TABLE_ID).await;
     }
+
+    /// Tables created together are copied within a single snapshot, so they're consistent with each other.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[serial]
+    async fn test_create_tables_in_single_snapshot() {
+        let (initial_client, connection) = connect(SRC_URI, NoTls).await.unwrap();
+        tokio::spawn(async move {
+            let _ = connection.await;
+        });
+        initial_client
+            .simple_query(
+                "DROP TABLE IF EXISTS batch_copy_a;
+                 DROP TABLE IF EXISTS batch_copy_b;
+                 CREATE TABLE batch_copy_a (id BIGINT PRIMARY KEY, name TEXT);
+                 CREATE TABLE batch_copy_b (id BIGINT PRIMARY KEY, name TEXT);
+                 INSERT INTO batch_copy_a VALUES (1,'a'),(2,'b');
+                 INSERT INTO batch_copy_b VALUES (10,'a');",
+            )
+            .await
+            .unwrap();
+
+        let (guard, _) = TestGuard::new(None).await;
+        let backend = guard.backend();
+        backend
+            .create_tables(
+                guard.database_id,
+                vec![
+                    (TABLE_ID, "public.batch_copy_a".to_string()),
+                    (TABLE_ID + 1, "public.batch_copy_b".to_string()),
+                ],
+                SRC_URI.to_string(),
+            )
+            .await
+            .unwrap();
+
+        // Changes to both tables after the snapshot are replicated through cdc.
+        initial_client
+            .simple_query(
+                "BEGIN;
+                 DELETE FROM batch_copy_a WHERE id = 1;
+                 INSERT INTO batch_copy_b VALUES (11,'b');
+                 COMMIT;",
+            )
+            .await
+            .unwrap();
+        let lsn = current_wal_lsn(&initial_client).await;
+
+        let ids_a = ids_from_state(
+            &backend
+                .scan_table(guard.database_id, TABLE_ID, Some(lsn))
+                .await
+                .unwrap(),
+        );
+        let ids_b = ids_from_state(
+            &backend
+                .scan_table(guard.database_id, TABLE_ID + 1, Some(lsn))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(ids_a, HashSet::from([2]));
+        assert_eq!(ids_b, HashSet::from([10, 11]));
+
+        backend.drop_table(guard.database_id, TABLE_ID + 1).await;
+        initial_client
+            .simple_query("DROP TABLE IF EXISTS batch_copy_a; DROP TABLE IF EXISTS batch_copy_b;")
+            .await
+            .unwrap();
+    }
 }
@@ -1,7 +1,9 @@
 use crate::pg_replicate::postgres_source::{
     CdcStreamError, PostgresSourceError, TableCopyStreamError,
 };
+use crate::replication_options::ReplicationOptionsError;
 use moonlink::Error as MoonlinkError;
+use moonlink_postgres_tls::PostgresTlsError;
 use std::result;
 use std::sync::Arc;
 use thiserror::Error;
@@ -15,6 +17,14 @@ pub enum Error {
     #[error("tokio postgres error: {source}")]
     TokioPostgres { source: Arc<TokioPostgresError> },
 
+    #[error("Postgres tls error: {source}")]
+    PostgresTls { source: Arc<PostgresTlsError> },
+
+    #[error("Replication options error: {source}")]
+    ReplicationOptions {
+        source: Arc<ReplicationOptionsError>,
+    },
+
     #[error("Postgres cdc stream error: {source}")]
     CdcStream { source: Arc<CdcStreamError> },
 
@@ -52,6 +62,22 @@ impl From<TokioPostgresError> for Error {
     }
 }
 
+impl From<PostgresTlsError> for Error {
+    fn from(source: PostgresTlsError) -> Self {
+        Error::PostgresTls {
+            source: Arc::new(source),
+        }
+    }
+}
+
+impl From<ReplicationOptionsError> for Error {
+    fn from(source: ReplicationOptionsError) -> Self {
+        Error::ReplicationOptions {
+            source: Arc::new(source),
+        }
+    }
+}
+
 impl From<CdcStreamError> for Error {
     fn from(source: CdcStreamError) -> Self {
         Error::CdcStream {
@@ -2,8 +2,16 @@ pub mod error;
 mod pg_replicate;
 mod replication_connection;
 mod replication_manager;
+mod replication_options;
 
 pub use error::*;
+pub use moonlink_postgres_tls::PostgresTlsError;
+pub use pg_replicate::dead_letter::{
+    ConversionErrorPolicy, DeadLetterRecord, RawValue, RowOperation,
+};
+pub use pg_replicate::moonlink_sink::LogicalMessage;
 pub use pg_replicate::postgres_source::PostgresSourceError;
+pub use pg_replicate::table_init::TableOptions;
 pub use replication_connection::ReplicationConnection;
 pub use replication_manager::ReplicationManager;
+pub use replication_options::{ReplicationOptions, ReplicationOptionsError};
@@ -3,15 +3,15 @@ use std::collections::{HashMap, HashSet};
 use crate::pg_replicate::conversions::text::TextFormatConverter;
 use crate::pg_replicate::table::{ColumnSchema, LookupKey, SrcTableId, TableName, TableSchema};
 use futures::future::err;
+use moonlink_postgres_tls::{parse_postgres_config, PostgresConnection, PostgresTlsError};
 use pg_escape::{quote_identifier, quote_literal};
-use postgres_replication::LogicalReplicationStream;
+use postgres_replication::ReplicationStream;
 use thiserror::Error;
 use tokio_postgres::{
     config::ReplicationMode,
     types::{Kind, PgLsn, Type},
-    Client as PostgresClient, Config, CopyOutStream, NoTls, SimpleQueryMessage, SimpleQueryRow,
+    Client as PostgresClient, CopyOutStream, SimpleQueryMessage, SimpleQueryRow,
 };
-use tokio_postgres::{tls::NoTlsStream, Connection, Socket};
 use tracing::Instrument;
 use tracing::{debug, info_span, warn};
 
@@ -19,6 +19,89 @@ pub struct SlotInfo {
     pub confirmed_flush_lsn: PgLsn,
 }
 
+/// Health of a replication slot, see [https://www.postgresql.org/docs/current/view-pg-replication-slots.html]
+pub struct SlotStatus {
+    /// One of `reserved`, `extended`, `unreserved` and `lost`, unassigned if the slot doesn't reserve WAL.
+    pub wal_status: Option<String>,
+    /// Unassigned once the slot has been invalidated.
+    pub restart_lsn: Option<PgLsn>,
+    pub confirmed_flush_lsn: Option<PgLsn>,
+    /// Number of WAL bytes between the current WAL LSN and the restart LSN.
+    pub retained_wal_bytes: Option<u64>,
+}
+
+/// Order to copy rows by a single integer key column, so an interrupted copy could resume after the last copied key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CopyKeyOrder {
+    pub key_column: String,
+    /// Only copy rows whose key is larger than it, if assigned.
+    pub start_after: Option<i64>,
+}
+
+/// Replica identity of a table, see `relreplident` in
+/// [https://www.postgresql.org/docs/current/catalog-pg-class.html]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplicaIdentity {
+    /// Old values of the primary key columns are logged.
+    Default,
+    /// No old values are logged.
+    Nothing,
+    /// Old values of all columns are logged.
+    Full,
+    /// Old values of the columns in the replica identity index are logged.
+    Index,
+}
+
+impl ReplicaIdentity {
+    pub fn from_relreplident(relreplident: &str) -> Option<Self> {
+        match relreplident {
+            "d" => Some(ReplicaIdentity::Default),
+            "n" => Some(ReplicaIdentity::Nothing),
+            "f" => Some(ReplicaIdentity::Full),
+            "i" => Some(ReplicaIdentity::Index),
+            _ => None,
+        }
+    }
+}
+
+/// Partition strategy of a partitioned table, see `partstrat` in
+/// [https://www.postgresql.org/docs/current/catalog-pg-partitioned-table.html]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PartitionStrategy {
+    Hash,
+    List,
+    Range,
+}
+
+impl PartitionStrategy {
+    pub fn from_partstrat(partstrat: &str) -> Option<Self> {
+        match partstrat {
+            "h" => Some(PartitionStrategy::Hash),
+            "l" => Some(PartitionStrategy::List),
+            "r" => Some(PartitionStrategy::Range),
+            _ => None,
+        }
+    }
+}
+
+/// Partition key of a table partitioned by a single column.
+#[derive(Clone, Debug)]
+pub struct TablePartitionKey {
+    pub strategy: PartitionStrategy,
+    /// Name of the partition key column.
+    pub column_name: String,
+    /// Bounds of the partitions, e.g. `FOR VALUES FROM ('2024-01-01') TO ('2024-02-01')`.
+    pub partition_bounds: Vec<String>,
+}
+
+/// Minimum server version (as `server_version_num`) which decodes prepared transactions at PREPARE time, with pgoutput
+/// protocol version 3 and the `two_phase` option.
+const TWO_PHASE_MIN_SERVER_VERSION_NUM: i32 = 150000;
+
+/// Session options which fix the text format of copied and replicated values, regardless of role or database defaults
+/// on the source. Intervals are only parsed in the `postgres` style, see [`crate::pg_replicate::conversions::interval`].
+const SESSION_OPTIONS: &str = "-c IntervalStyle=postgres";
+
 /// A client for Postgres logical replication
 pub struct ReplicationClient {
     postgres_client: PostgresClient,
@@ -30,15 +113,27 @@ pub enum ReplicationClientError {
     #[error("tokio_postgres error: {0}")]
     TokioPostgresError(#[from] tokio_postgres::Error),
 
+    #[error("postgres tls error: {0}")]
+    PostgresTlsError(#[from] PostgresTlsError),
+
     #[error("column {0} is missing from table {1}")]
     MissingColumn(String, String),
 
     #[error("publication {0} doesn't exist")]
     MissingPublication(String),
 
+    #[error("table {0} is not published by publication {1}")]
+    TableNotInPublication(TableName, String),
+
+    #[error("table {0} is already replicated through the replication connection")]
+    TableAlreadyReplicated(TableName),
+
     #[error("oid column is not a valid u32")]
     OidColumnNotU32,
 
+    #[error("attnum column is not a valid i16")]
+    AttnumColumnNotI16,
+
     #[error("replica identity '{0}' not supported")]
     ReplicaIdentityNotSupported(String),
 
@@ -59,21 +154,31 @@ pub enum ReplicationClientError {
 
     #[error("failed to create slot")]
     FailedToCreateSlot,
+
+    #[error("server_version_num is not a valid integer")]
+    InvalidServerVersionNum,
 }
 
 impl ReplicationClient {
-    /// Connect to a postgres database in logical replication mode without TLS
-    pub async fn connect_no_tls(
+    /// Connect to a postgres database, optionally in logical replication mode.
+    /// TLS is used according to the `sslmode` in the connection string.
+    pub async fn connect(
         uri: &str,
         replication_mode: bool,
-    ) -> Result<(ReplicationClient, Connection<Socket, NoTlsStream>), ReplicationClientError> {
+    ) -> Result<(ReplicationClient, PostgresConnection), ReplicationClientError> {
         debug!("connecting to postgres");
 
-        let mut config = uri.parse::<Config>()?;
+        let (mut config, tls) = parse_postgres_config(uri)?;
+        // Appended to user supplied options, so they take precedence.
+        let options = match config.get_options() {
+            Some(options) => format!("{options} {SESSION_OPTIONS}"),
+            None => SESSION_OPTIONS.to_string(),
+        };
+        config.options(&options);
         if replication_mode {
             config.replication_mode(ReplicationMode::Logical);
         }
-        let (postgres_client, connection) = config.connect(NoTls).await?;
+        let (postgres_client, connection) = config.connect(tls).await?;
 
         debug!("successfully connected to postgres");
 
@@ -86,6 +191,11 @@ impl ReplicationClient {
         ))
     }
 
+    /// Returns whether the underlying connection has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.postgres_client.is_closed()
+    }
+
     /// Starts a read-only trasaction with repeatable read isolation level
     pub async fn begin_readonly_transaction(&mut self) -> Result<(), ReplicationClientError> {
         // Now start the new read-only transaction
@@ -113,6 +223,31 @@ impl ReplicationClient {
         Ok(())
     }
 
+    /// Returns whether the server supports two-phase decoding.
+    async fn supports_two_phase(&self) -> Result<bool, ReplicationClientError> {
+        for msg in self
+            .postgres_client
+            .simple_query("SHOW server_version_num;")
+            .await?
+        {
+            if let SimpleQueryMessage::Row(row) = msg {
+                let server_version_num: i32 = row
+                    .get("server_version_num")
+                    .ok_or(ReplicationClientError::MissingColumn(
+                        "server_version_num".to_string(),
+                        "show".to_string(),
+                    ))?
+                    .parse()
+                    .map_err(|_| ReplicationClientError::InvalidServerVersionNum)?;
+                return Ok(server_version_num >= TWO_PHASE_MIN_SERVER_VERSION_NUM);
+            }
+        }
+        Err(ReplicationClientError::MissingColumn(
+            "server_version_num".to_string(),
+            "show".to_string(),
+        ))
+    }
+
     async fn rollback_txn(&mut self) -> Result<(), ReplicationClientError> {
         if self.in_txn {
             self.postgres_client.simple_query("rollback;").await?;
@@ -123,41 +258,156 @@ impl ReplicationClient {
 
     pub async fn add_table_to_publication(
         &mut self,
+        publication: &str,
         table_name: &TableName,
     ) -> Result<(), ReplicationClientError> {
         let query = format!(
-            "ALTER PUBLICATION moonlink_pub ADD TABLE {};",
+            "ALTER PUBLICATION {} ADD TABLE {};",
+            quote_identifier(publication),
             table_name.as_quoted_identifier()
         );
         self.postgres_client.simple_query(&query).await?;
         Ok(())
     }
 
+    /// Returns whether the table is published by the publication, either explicitly or via `FOR ALL TABLES` / `FOR TABLES IN SCHEMA`.
+    pub async fn is_table_in_publication(
+        &self,
+        publication: &str,
+        table_name: &TableName,
+    ) -> Result<bool, ReplicationClientError> {
+        let query = format!(
+            "select 1 from pg_publication_tables where pubname = {} and schemaname = {} and tablename = {};",
+            quote_literal(publication),
+            quote_literal(&table_name.schema),
+            quote_literal(&table_name.name),
+        );
+        for msg in self.postgres_client.simple_query(&query).await? {
+            if let SimpleQueryMessage::Row(_) = msg {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Returns the partition key of a partitioned table, along with bounds of its partitions.
+    /// Tables which aren't partitioned, or partitioned by expressions or multiple columns, have no partition key returned.
+    pub async fn get_partition_key(
+        &self,
+        table_id: SrcTableId,
+    ) -> Result<Option<TablePartitionKey>, ReplicationClientError> {
+        let partition_key_query = format!(
+            "select pt.partstrat, pt.partnatts, a.attname
+            from pg_partitioned_table pt
+            left join pg_attribute a on a.attrelid = pt.partrelid and a.attnum = pt.partattrs[0]
+            where pt.partrelid = {table_id};"
+        );
+        let mut partition_key = None;
+        for msg in self
+            .postgres_client
+            .simple_query(&partition_key_query)
+            .await?
+        {
+            if let SimpleQueryMessage::Row(row) = msg {
+                let strategy = row
+                    .try_get("partstrat")?
+                    .and_then(PartitionStrategy::from_partstrat)
+                    .ok_or(ReplicationClientError::MissingColumn(
+                        "partstrat".to_string(),
+                        "pg_partitioned_table".to_string(),
+                    ))?;
+                let partnatts = row.try_get("partnatts")?;
+                // Expression keys have no column attached.
+                let column_name = row.try_get("attname")?;
+                if let (Some("1"), Some(column_name)) = (partnatts, column_name) {
+                    partition_key = Some((strategy, column_name.to_string()));
+                }
+            }
+        }
+        let Some((strategy, column_name)) = partition_key else {
+            return Ok(None);
+        };
+
+        let partition_bound_query = format!(
+            "select pg_get_expr(c.relpartbound, c.oid) as partition_bound
+            from pg_inherits i
+            join pg_class c on c.oid = i.inhrelid
+            where i.inhparent = {table_id};"
+        );
+        let mut partition_bounds = vec![];
+        for msg in self
+            .postgres_client
+            .simple_query(&partition_bound_query)
+            .await?
+        {
+            if let SimpleQueryMessage::Row(row) = msg {
+                if let Some(partition_bound) = row.try_get("partition_bound")? {
+                    partition_bounds.push(partition_bound.to_string());
+                }
+            }
+        }
+
+        Ok(Some(TablePartitionKey {
+            strategy,
+            column_name,
+            partition_bounds,
+        }))
+    }
+
+    /// Returns the row filter (`WHERE` clause) of a table in the publication, if any.
+    pub async fn get_row_filter(
+        &self,
+        publication: &str,
+        table_id: SrcTableId,
+    ) -> Result<Option<String>, ReplicationClientError> {
+        let query = format!(
+            "select pg_get_expr(r.prqual, r.prrelid) as row_filter
+            from pg_publication_rel r
+            join pg_publication p on r.prpubid = p.oid
+            where p.pubname = {}
+            and r.prrelid = {}
+            and r.prqual is not null;",
+            quote_literal(publication),
+            table_id
+        );
+        for msg in self.postgres_client.simple_query(&query).await? {
+            if let SimpleQueryMessage::Row(row) = msg {
+                return Ok(row.try_get("row_filter")?.map(|s| s.to_string()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the number of rows in the table, which satisfy the row filter if given.
     pub async fn get_row_count(
         &mut self,
         table_name: &TableName,
+        row_filter: Option<&str>,
     ) -> Result<i64, ReplicationClientError> {
         let query = format!(
-            "SELECT COUNT(*) FROM {};",
-            table_name.as_quoted_identifier()
+            "SELECT COUNT(*) FROM {}{};",
+            table_name.as_quoted_identifier(),
+            Self::where_clause(row_filter)
         );
         let result = self.postgres_client.query_one(&query, &[]).await?;
         let row_count = result.get(0);
         Ok(row_count)
     }
 
-    /// Returns a [CopyOutStream] for a table
+    fn where_clause(row_filter: Option<&str>) -> String {
+        row_filter
+            .map(|row_filter| format!(" WHERE ({row_filter})"))
+            .unwrap_or_default()
+    }
+
+    /// Returns a [CopyOutStream] for a table, only including rows which satisfy the row filter if given.
     pub async fn get_table_copy_stream(
         &mut self,
         table_name: &TableName,
         column_schemas: &[ColumnSchema],
+        row_filter: Option<&str>,
+        key_order: Option<&CopyKeyOrder>,
     ) -> Result<(CopyOutStream, PgLsn), ReplicationClientError> {
-        let column_list = column_schemas
-            .iter()
-            .map(|col| quote_identifier(&col.name))
-            .collect::<Vec<_>>()
-            .join(", ");
-
         // start a transaction
         self.postgres_client.simple_query("BEGIN;").await?;
         self.in_txn = true;
@@ -165,13 +415,9 @@ impl ReplicationClient {
         // Get the current LSN before we start the copy
         let current_wal_lsn = self.get_current_wal_lsn().await?;
 
-        // TODO(nbiscaro): Use binary format instead of text.
-        let copy_query = format!(
-            r#"COPY {} ({column_list}) TO STDOUT WITH (FORMAT text);"#,
-            table_name.as_quoted_identifier(),
-        );
-
-        let stream = self.postgres_client.copy_out_simple(&copy_query).await?;
+        let stream = self
+            .copy_out_table(table_name, column_schemas, row_filter, key_order)
+            .await?;
 
         // Note that we keep the transaction open
         // Once we are done consuming the stream, we will commit the transaction
@@ -179,6 +425,79 @@ impl ReplicationClient {
         Ok((stream, current_wal_lsn))
     }
 
+    /// Returns a copy stream for the table within the given exported snapshot, along with the number of rows to copy,
+    /// which is counted within the same snapshot.
+    /// The transaction is kept open, and should be committed once the stream is consumed.
+    pub async fn get_table_copy_stream_in_snapshot(
+        &mut self,
+        table_name: &TableName,
+        column_schemas: &[ColumnSchema],
+        row_filter: Option<&str>,
+        key_order: Option<&CopyKeyOrder>,
+        snapshot_name: &str,
+    ) -> Result<(CopyOutStream, i64), ReplicationClientError> {
+        self.postgres_client
+            .simple_query(&format!(
+                "BEGIN READ ONLY ISOLATION LEVEL REPEATABLE READ; SET TRANSACTION SNAPSHOT {};",
+                quote_literal(snapshot_name)
+            ))
+            .await?;
+        self.in_txn = true;
+
+        let row_count = self.get_row_count(table_name, row_filter).await?;
+        let stream = self
+            .copy_out_table(table_name, column_schemas, row_filter, key_order)
+            .await?;
+        Ok((stream, row_count))
+    }
+
+    async fn copy_out_table(
+        &self,
+        table_name: &TableName,
+        column_schemas: &[ColumnSchema],
+        row_filter: Option<&str>,
+        key_order: Option<&CopyKeyOrder>,
+    ) -> Result<CopyOutStream, ReplicationClientError> {
+        let column_list = column_schemas
+            .iter()
+            .map(|col| quote_identifier(&col.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        // TODO(nbiscaro): Use binary format instead of text.
+        let copy_query = match (row_filter, key_order) {
+            (None, None) => format!(
+                r#"COPY {} ({column_list}) TO STDOUT WITH (FORMAT text);"#,
+                table_name.as_quoted_identifier(),
+            ),
+            _ => {
+                let mut predicates = vec![];
+                if let Some(row_filter) = row_filter {
+                    predicates.push(format!("({row_filter})"));
+                }
+                let mut order_by = String::new();
+                if let Some(key_order) = key_order {
+                    let key_column = quote_identifier(&key_order.key_column);
+                    if let Some(start_after) = key_order.start_after {
+                        predicates.push(format!("{key_column} > {start_after}"));
+                    }
+                    order_by = format!(" ORDER BY {key_column}");
+                }
+                let where_clause = if predicates.is_empty() {
+                    String::new()
+                } else {
+                    format!(" WHERE {}", predicates.join(" AND "))
+                };
+                format!(
+                    r#"COPY (SELECT {column_list} FROM {}{where_clause}{order_by}) TO STDOUT WITH (FORMAT text);"#,
+                    table_name.as_quoted_identifier(),
+                )
+            }
+        };
+
+        Ok(self.postgres_client.copy_out_simple(&copy_query).await?)
+    }
+
     /// Returns a vector of columns of a table, optionally filtered by a publication's column list
     pub async fn get_column_schemas(
         &self,
@@ -213,12 +532,17 @@ impl ReplicationClient {
         let column_info_query = format!(
             "{}
             select a.attname,
+                a.attnum,
                 a.atttypid,
                 a.atttypmod,
                 a.attnotnull,
                 a.attndims,
+                t.typname,
+                tn.nspname as typnamespace,
                 coalesce(i.indisprimary, false) as primary
             from pg_attribute a
+            join pg_type t on t.oid = a.atttypid
+            join pg_namespace tn on tn.oid = t.typnamespace
             left join pg_index i
                 on a.attrelid = i.indrelid
                 and a.attnum = any(i.indkey)
@@ -249,6 +573,15 @@ impl ReplicationClient {
                     ))?
                     .to_string();
 
+                let attnum = row
+                    .try_get("attnum")?
+                    .ok_or(ReplicationClientError::MissingColumn(
+                        "attnum".to_string(),
+                        "pg_attribute".to_string(),
+                    ))?
+                    .parse()
+                    .map_err(|_| ReplicationClientError::AttnumColumnNotI16)?;
+
                 let type_oid = row
                     .try_get("atttypid")?
                     .ok_or(ReplicationClientError::MissingColumn(
@@ -259,13 +592,20 @@ impl ReplicationClient {
                     .map_err(|_| ReplicationClientError::OidColumnNotU32)?;
 
                 // Fail fast on any type that we are not able to parse in try_from_str.
-                let typ = Type::from_oid(type_oid).ok_or_else(|| {
-                    ReplicationClientError::UnsupportedType(
-                        name.clone(),
-                        type_oid,
-                        table_name.to_string(),
-                    )
-                })?;
+                // Types from extensions don't have fixed oids, so they are resolved by name.
+                let typ = match Type::from_oid(type_oid) {
+                    Some(typ) => typ,
+                    None => {
+                        let typname = row.try_get("typname")?.unwrap_or_default();
+                        let typnamespace = row.try_get("typnamespace")?.unwrap_or_default();
+                        Type::new(
+                            typname.to_string(),
+                            type_oid,
+                            Kind::Simple,
+                            typnamespace.to_string(),
+                        )
+                    }
+                };
 
                 if !TextFormatConverter::is_supported_type(&typ) {
                     return Err(ReplicationClientError::UnsupportedType(
@@ -314,6 +654,7 @@ impl ReplicationClient {
                     typ,
                     modifier,
                     nullable,
+                    attnum,
                 })
             }
         }
@@ -321,14 +662,16 @@ impl ReplicationClient {
         Ok(column_schemas)
     }
 
+    /// Finds the first valid index accepted by `index_filter`, whose columns are all published.
     async fn fetch_lookup_key(
         &self,
         table_id: SrcTableId,
         published_column_names: HashSet<String>,
+        index_filter: impl Fn(&SimpleQueryRow) -> bool,
     ) -> Result<Option<LookupKey>, ReplicationClientError> {
         let index_rows = self.fetch_index_rows(table_id).await?;
 
-        for index_row in index_rows {
+        for index_row in index_rows.iter().filter(|row| index_filter(row)) {
             let index_name = match index_row.get("index_name") {
                 Some(name) => name.to_string(),
                 None => continue,
@@ -379,6 +722,7 @@ impl ReplicationClient {
                 i.indkey,
                 i.indisunique,
                 i.indisprimary,
+                i.indisreplident,
                 i.indpred IS NOT NULL AS is_partial,
                 COALESCE(con.condeferrable, false) AS is_deferrable
             FROM pg_index i
@@ -443,12 +787,60 @@ impl ReplicationClient {
     ) -> Result<LookupKey, ReplicationClientError> {
         let column_names: HashSet<String> =
             column_schemas.iter().map(|cs| cs.name.clone()).collect();
-        if let Some(unique_index_key) = self.fetch_lookup_key(src_table_id, column_names).await? {
-            return Ok(unique_index_key);
+        match self.get_replica_identity(src_table_id).await? {
+            // Old rows are fully logged, so any valid unique index works, and fallback to full row.
+            ReplicaIdentity::Full => {
+                let lookup_key = self
+                    .fetch_lookup_key(src_table_id, column_names, |_| true)
+                    .await?;
+                Ok(lookup_key.unwrap_or(LookupKey::FullRow))
+            }
+            // Only the primary key columns of old rows are logged.
+            ReplicaIdentity::Default => self
+                .fetch_lookup_key(src_table_id, column_names, |row| {
+                    row.get("indisprimary") == Some("t")
+                })
+                .await?
+                .ok_or(ReplicationClientError::ReplicaIdentityNotSupported(
+                    "default without a published primary key".to_string(),
+                )),
+            // Only the columns of the replica identity index of old rows are logged.
+            ReplicaIdentity::Index => self
+                .fetch_lookup_key(src_table_id, column_names, |row| {
+                    row.get("indisreplident") == Some("t")
+                })
+                .await?
+                .ok_or(ReplicationClientError::ReplicaIdentityNotSupported(
+                    "index with unpublished columns".to_string(),
+                )),
+            ReplicaIdentity::Nothing => Err(ReplicationClientError::ReplicaIdentityNotSupported(
+                "nothing".to_string(),
+            )),
         }
+    }
 
-        // default to full row
-        Ok(LookupKey::FullRow)
+    /// Returns the replica identity of the given table, which decides the columns of old rows logged for updates and deletes.
+    pub async fn get_replica_identity(
+        &self,
+        src_table_id: SrcTableId,
+    ) -> Result<ReplicaIdentity, ReplicationClientError> {
+        let query = format!("select relreplident from pg_class where oid = {src_table_id};");
+        for message in self.postgres_client.simple_query(&query).await? {
+            if let SimpleQueryMessage::Row(row) = message {
+                let replica_identity =
+                    row.try_get("relreplident")?
+                        .ok_or(ReplicationClientError::MissingColumn(
+                            "relreplident".to_string(),
+                            "pg_class".to_string(),
+                        ))?;
+                return ReplicaIdentity::from_relreplident(replica_identity).ok_or(
+                    ReplicationClientError::ReplicaIdentityNotSupported(
+                        replica_identity.to_string(),
+                    ),
+                );
+            }
+        }
+        Err(ReplicationClientError::MissingTableId(src_table_id))
     }
 
     pub async fn get_table_schema(
@@ -472,8 +864,6 @@ impl ReplicationClient {
     }
 
     /// Returns the src table id (called relation id in Postgres) of a table
-    /// Also checks whether the replica identity is default or full and
-    /// returns an error if not.
     pub async fn get_src_table_id(
         &self,
         table: &TableName,
@@ -482,8 +872,7 @@ impl ReplicationClient {
         let quoted_name = quote_literal(&table.name);
 
         let table_info_query = format!(
-            "select c.oid,
-                c.relreplident
+            "select c.oid
             from pg_class c
             join pg_namespace n
                 on (c.relnamespace = n.oid)
@@ -495,19 +884,6 @@ impl ReplicationClient {
 
         for message in self.postgres_client.simple_query(&table_info_query).await? {
             if let SimpleQueryMessage::Row(row) = message {
-                let replica_identity =
-                    row.try_get("relreplident")?
-                        .ok_or(ReplicationClientError::MissingColumn(
-                            "relreplident".to_string(),
-                            "pg_class".to_string(),
-                        ))?;
-
-                if !(replica_identity == "f") {
-                    return Err(ReplicationClientError::ReplicaIdentityNotSupported(
-                        replica_identity.to_string(),
-                    ));
-                }
-
                 let oid: u32 = row
                     .try_get("oid")?
                     .ok_or(ReplicationClientError::MissingColumn(
@@ -557,7 +933,10 @@ impl ReplicationClient {
 
     /// Returns the slot info of an existing slot. The slot info currently only has the
     /// confirmed_flush_lsn column of the pg_replication_slots table.
-    async fn get_slot(&self, slot_name: &str) -> Result<Option<SlotInfo>, ReplicationClientError> {
+    pub async fn get_slot(
+        &self,
+        slot_name: &str,
+    ) -> Result<Option<SlotInfo>, ReplicationClientError> {
         let query = format!(
             r#"select confirmed_flush_lsn from pg_replication_slots where slot_name = {};"#,
             quote_literal(slot_name)
@@ -585,16 +964,71 @@ impl ReplicationClient {
         Ok(None)
     }
 
+    /// Returns the health of the replication slot from the pg_replication_slots table,
+    /// or `None` if the slot doesn't exist.
+    pub async fn get_slot_status(
+        &self,
+        slot_name: &str,
+    ) -> Result<Option<SlotStatus>, ReplicationClientError> {
+        let query = format!(
+            r#"select wal_status, restart_lsn, confirmed_flush_lsn,
+                pg_wal_lsn_diff(pg_current_wal_lsn(), restart_lsn)::bigint as retained_wal_bytes
+            from pg_replication_slots where slot_name = {};"#,
+            quote_literal(slot_name)
+        );
+
+        let query_result = self.postgres_client.simple_query(&query).await?;
+
+        for res in &query_result {
+            if let SimpleQueryMessage::Row(row) = res {
+                let parse_lsn = |column: &str| -> Result<Option<PgLsn>, ReplicationClientError> {
+                    row.get(column)
+                        .map(|lsn| {
+                            lsn.parse()
+                                .map_err(|_| ReplicationClientError::InvalidPgLsn)
+                        })
+                        .transpose()
+                };
+                let retained_wal_bytes = row
+                    .get("retained_wal_bytes")
+                    .and_then(|bytes| bytes.parse::<i64>().ok())
+                    .map(|bytes| bytes.max(0) as u64);
+
+                return Ok(Some(SlotStatus {
+                    wal_status: row.get("wal_status").map(|s| s.to_string()),
+                    restart_lsn: parse_lsn("restart_lsn")?,
+                    confirmed_flush_lsn: parse_lsn("confirmed_flush_lsn")?,
+                    retained_wal_bytes,
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Creates a logical replication slot. This will only succeed if the postgres connection
     /// is in logical replication mode. Otherwise it will fail with the following error:
     /// `syntax error at or near "CREATE_REPLICATION_SLOT"``
     ///
+    /// The slot is created with two-phase decoding enabled if `two_phase`, which can't be enabled later before Postgres 17.
+    ///
     /// Returns the consistent_point column as slot info.
-    async fn create_slot(&self, slot_name: &str) -> Result<SlotInfo, ReplicationClientError> {
-        let query = format!(
-            r#"CREATE_REPLICATION_SLOT {} LOGICAL pgoutput USE_SNAPSHOT"#,
-            quote_identifier(slot_name)
-        );
+    async fn create_slot(
+        &self,
+        slot_name: &str,
+        two_phase: bool,
+    ) -> Result<SlotInfo, ReplicationClientError> {
+        let query = if two_phase {
+            format!(
+                r#"CREATE_REPLICATION_SLOT {} LOGICAL pgoutput (SNAPSHOT 'use', TWO_PHASE true)"#,
+                quote_identifier(slot_name)
+            )
+        } else {
+            format!(
+                r#"CREATE_REPLICATION_SLOT {} LOGICAL pgoutput USE_SNAPSHOT"#,
+                quote_identifier(slot_name)
+            )
+        };
         let results = self.postgres_client.simple_query(&query).await?;
 
         for result in results {
@@ -615,6 +1049,43 @@ impl ReplicationClient {
         Err(ReplicationClientError::FailedToCreateSlot)
     }
 
+    /// Creates a temporary logical replication slot which exports its snapshot, the slot is dropped when the session ends.
+    /// The exported snapshot could be imported by other sessions until the next command on this session.
+    ///
+    /// Returns the consistent_point and snapshot_name columns.
+    pub async fn create_snapshot_slot(
+        &self,
+        slot_name: &str,
+    ) -> Result<(PgLsn, String), ReplicationClientError> {
+        let query = format!(
+            r#"CREATE_REPLICATION_SLOT {} TEMPORARY LOGICAL pgoutput EXPORT_SNAPSHOT"#,
+            quote_identifier(slot_name)
+        );
+        let results = self.postgres_client.simple_query(&query).await?;
+
+        for result in results {
+            if let SimpleQueryMessage::Row(row) = result {
+                let consistent_point: PgLsn = row
+                    .get("consistent_point")
+                    .ok_or(ReplicationClientError::MissingColumn(
+                        "consistent_point".to_string(),
+                        "create_replication_slot".to_string(),
+                    ))?
+                    .parse()
+                    .map_err(|_| ReplicationClientError::InvalidPgLsn)?;
+                let snapshot_name = row
+                    .get("snapshot_name")
+                    .ok_or(ReplicationClientError::MissingColumn(
+                        "snapshot_name".to_string(),
+                        "create_replication_slot".to_string(),
+                    ))?
+                    .to_string();
+                return Ok((consistent_point, snapshot_name));
+            }
+        }
+        Err(ReplicationClientError::FailedToCreateSlot)
+    }
+
     /// Either return the slot info of an existing slot or creates a new
     /// slot and returns its slot info.
     pub async fn get_or_create_slot(
@@ -624,9 +1095,11 @@ impl ReplicationClient {
         if let Some(slot_info) = self.get_slot(slot_name).await? {
             Ok(slot_info)
         } else {
+            // Slot creation has to be the first command in the transaction which uses its snapshot.
+            let two_phase = self.supports_two_phase().await?;
             self.rollback_txn().await?;
             self.begin_readonly_transaction().await?;
-            Ok(self.create_slot(slot_name).await?)
+            Ok(self.create_slot(slot_name, two_phase).await?)
         }
     }
 
@@ -695,10 +1168,20 @@ impl ReplicationClient {
         publication: &str,
         slot_name: &str,
         start_lsn: PgLsn,
-    ) -> Result<LogicalReplicationStream, ReplicationClientError> {
+    ) -> Result<(ReplicationStream, u8), ReplicationClientError> {
+        // Protocol version 3 with two_phase enables decoding of prepared transactions at PREPARE time, which requires
+        // Postgres 15+; otherwise prepared transactions are decoded at COMMIT PREPARED like regular transactions.
+        // Older servers reject the two_phase option.
+        let (protocol_version, two_phase_option) = if self.supports_two_phase().await? {
+            (3, r#", "two_phase" 'on'"#)
+        } else {
+            (2, "")
+        };
         let options = format!(
-            r#"("proto_version" '2', "publication_names" {}, "streaming" 'on')"#,
+            r#"("proto_version" '{}', "publication_names" {}, "streaming" 'on', "messages" 'true'{})"#,
+            protocol_version,
             quote_literal(publication),
+            two_phase_option,
         );
 
         let query = format!(
@@ -713,8 +1196,9 @@ impl ReplicationClient {
             .copy_both_simple::<bytes::Bytes>(&query)
             .await?;
 
-        let stream = LogicalReplicationStream::new(copy_stream, Some(2));
+        // Records are decoded by the CDC stream, since some of them are decoded locally.
+        let stream = ReplicationStream::new(copy_stream);
 
-        Ok(stream)
+        Ok((stream, protocol_version))
     }
 }
@@ -1,13 +1,21 @@
 use std::fmt::Debug;
 
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use inet::PgInet;
+use interval::PgInterval;
 use numeric::PgNumeric;
+use range::PgRange;
 use uuid::Uuid;
 
 pub mod bool;
 pub mod cdc_event;
 pub mod hex;
+pub mod hstore;
+pub mod inet;
+pub mod interval;
 pub mod numeric;
+pub mod pgoutput;
+pub mod range;
 pub mod table_row;
 pub mod text;
 
@@ -31,6 +39,10 @@ pub enum Cell {
     Json(serde_json::Value),
     Bytes(Vec<u8>),
     Array(ArrayCell),
+    Interval(PgInterval),
+    Inet(PgInet),
+    Range(Box<PgRange>),
+    Hstore(Vec<(String, Option<String>)>),
 }
 
 #[derive(Debug, Clone)]
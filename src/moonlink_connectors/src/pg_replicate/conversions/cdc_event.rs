@@ -8,9 +8,14 @@ use postgres_replication::protocol::{
 };
 use thiserror::Error;
 
+use crate::pg_replicate::dead_letter::{DeadLetterRecord, RawValue, RowOperation};
 use crate::pg_replicate::table::{ColumnSchema, SrcTableId, TableSchema};
 
 use super::{
+    pgoutput::{
+        BeginPrepareBody, CommitPreparedBody, MessageBody, PgOutputMessage, PrepareBody,
+        RollbackPreparedBody,
+    },
     table_row::TableRow,
     text::{FromTextError, TextFormatConverter},
     Cell,
@@ -36,6 +41,9 @@ pub enum CdcEventConversionError {
     #[error("schema missing for table id {0}")]
     MissingSchema(SrcTableId),
 
+    #[error("tuple has {actual} columns, but table schema has {expected}")]
+    ColumnCountMismatch { expected: usize, actual: usize },
+
     #[error("from bytes error: {0}")]
     FromBytes(#[from] FromTextError),
 
@@ -50,10 +58,17 @@ impl CdcEventConverter {
         column_schemas: &[ColumnSchema],
         tuple_data: &[TupleData],
     ) -> Result<TableRow, CdcEventConversionError> {
+        // Tuples are decoded with the schema of the latest relation message, so a mismatch means the schema is out of sync.
+        if tuple_data.len() != column_schemas.len() {
+            return Err(CdcEventConversionError::ColumnCountMismatch {
+                expected: column_schemas.len(),
+                actual: tuple_data.len(),
+            });
+        }
         let mut values = Vec::with_capacity(column_schemas.len());
 
-        for (i, column_schema) in column_schemas.iter().enumerate() {
-            let cell = match &tuple_data[i] {
+        for (column_schema, tuple_data) in column_schemas.iter().zip(tuple_data) {
+            let cell = match tuple_data {
                 TupleData::Null => Cell::Null,
                 TupleData::UnchangedToast => TextFormatConverter::default_value(&column_schema.typ),
                 TupleData::Text(bytes) => {
@@ -67,10 +82,34 @@ impl CdcEventConverter {
         Ok(TableRow { values })
     }
 
+    /// Convert raw values persisted for a row which failed conversion, with the same semantics as tuple data.
+    pub(crate) fn try_from_raw_values(
+        column_schemas: &[ColumnSchema],
+        raw_values: &[RawValue],
+    ) -> Result<TableRow, CdcEventConversionError> {
+        let mut values = Vec::with_capacity(column_schemas.len());
+
+        for (i, column_schema) in column_schemas.iter().enumerate() {
+            let cell = match raw_values.get(i) {
+                None | Some(RawValue::Null) => Cell::Null,
+                Some(RawValue::UnchangedToast) => {
+                    TextFormatConverter::default_value(&column_schema.typ)
+                }
+                Some(RawValue::Text(bytes)) => {
+                    let str = str::from_utf8(&bytes[..])?;
+                    TextFormatConverter::try_from_str(&column_schema.typ, str)?
+                }
+            };
+            values.push(cell);
+        }
+
+        Ok(TableRow { values })
+    }
+
     fn try_from_insert_body(
         src_table_id: SrcTableId,
         column_schemas: &[ColumnSchema],
-        insert_body: InsertBody,
+        insert_body: &InsertBody,
     ) -> Result<CdcEvent, CdcEventConversionError> {
         let row =
             Self::try_from_tuple_data_slice(column_schemas, insert_body.tuple().tuple_data())?;
@@ -82,10 +121,12 @@ impl CdcEventConverter {
     fn try_from_update_body(
         src_table_id: SrcTableId,
         column_schemas: &[ColumnSchema],
-        update_body: UpdateBody,
+        update_body: &UpdateBody,
     ) -> Result<CdcEvent, CdcEventConversionError> {
+        // Old tuple is only present with FULL replica identity, and key tuple only when identity columns change.
         let old_row = update_body
             .old_tuple()
+            .or(update_body.key_tuple())
             .map(|tuple| Self::try_from_tuple_data_slice(column_schemas, tuple.tuple_data()))
             .transpose()?;
         let new_row =
@@ -102,7 +143,7 @@ impl CdcEventConverter {
     fn try_from_delete_body(
         src_table_id: SrcTableId,
         column_schemas: &[ColumnSchema],
-        delete_body: DeleteBody,
+        delete_body: &DeleteBody,
     ) -> Result<CdcEvent, CdcEventConversionError> {
         let tuple = delete_body
             .key_tuple()
@@ -115,79 +156,136 @@ impl CdcEventConverter {
     }
 
     pub fn try_from(
-        value: ReplicationMessage<LogicalReplicationMessage>,
+        value: ReplicationMessage<PgOutputMessage>,
         table_schemas: &HashMap<SrcTableId, TableSchema>,
     ) -> Result<CdcEvent, CdcEventConversionError> {
         match value {
-            ReplicationMessage::XLogData(xlog_data) => match xlog_data.into_data() {
-                LogicalReplicationMessage::Begin(begin_body) => Ok(CdcEvent::Begin(begin_body)),
-                LogicalReplicationMessage::Commit(commit_body) => Ok(CdcEvent::Commit(commit_body)),
-                LogicalReplicationMessage::Origin(_) => {
-                    Err(CdcEventConversionError::MessageNotSupported)
-                }
-                LogicalReplicationMessage::Relation(relation_body) => {
-                    Ok(CdcEvent::Relation(relation_body))
-                }
-                LogicalReplicationMessage::Type(type_body) => Ok(CdcEvent::Type(type_body)),
-                LogicalReplicationMessage::Insert(insert_body) => {
-                    let table_id = insert_body.rel_id();
-                    let column_schemas = &table_schemas
-                        .get(&table_id)
-                        .ok_or(CdcEventConversionError::MissingSchema(table_id))?
-                        .column_schemas;
-                    Ok(Self::try_from_insert_body(
-                        table_id,
-                        column_schemas,
-                        insert_body,
-                    )?)
-                }
-                LogicalReplicationMessage::Update(update_body) => {
-                    let table_id = update_body.rel_id();
-                    let column_schemas = &table_schemas
-                        .get(&table_id)
-                        .ok_or(CdcEventConversionError::MissingSchema(table_id))?
-                        .column_schemas;
-                    Ok(Self::try_from_update_body(
-                        table_id,
-                        column_schemas,
-                        update_body,
-                    )?)
-                }
-                LogicalReplicationMessage::Delete(delete_body) => {
-                    let table_id = delete_body.rel_id();
-                    let column_schemas = &table_schemas
-                        .get(&table_id)
-                        .ok_or(CdcEventConversionError::MissingSchema(table_id))?
-                        .column_schemas;
-                    Ok(Self::try_from_delete_body(
-                        table_id,
-                        column_schemas,
-                        delete_body,
-                    )?)
-                }
-                LogicalReplicationMessage::Truncate(_) => {
-                    Err(CdcEventConversionError::MessageNotSupported)
-                }
-                LogicalReplicationMessage::StreamStart(stream_start_body) => {
-                    Ok(CdcEvent::StreamStart(stream_start_body))
+            ReplicationMessage::XLogData(xlog_data) => {
+                let lsn = xlog_data.wal_start();
+                match xlog_data.into_data() {
+                    PgOutputMessage::Logical(message) => {
+                        Self::try_from_logical_replication_message(lsn, message, table_schemas)
+                    }
+                    PgOutputMessage::Message(message_body) => Ok(CdcEvent::Message(message_body)),
+                    PgOutputMessage::BeginPrepare(begin_prepare_body) => {
+                        Ok(CdcEvent::BeginPrepare(begin_prepare_body))
+                    }
+                    PgOutputMessage::Prepare(prepare_body) => Ok(CdcEvent::Prepare(prepare_body)),
+                    PgOutputMessage::CommitPrepared(commit_prepared_body) => {
+                        Ok(CdcEvent::CommitPrepared(commit_prepared_body))
+                    }
+                    PgOutputMessage::RollbackPrepared(rollback_prepared_body) => {
+                        Ok(CdcEvent::RollbackPrepared(rollback_prepared_body))
+                    }
+                    PgOutputMessage::StreamPrepare(stream_prepare_body) => {
+                        Ok(CdcEvent::StreamPrepare(stream_prepare_body))
+                    }
                 }
-                LogicalReplicationMessage::StreamStop(stream_stop_body) => {
-                    Ok(CdcEvent::StreamStop(stream_stop_body))
-                }
-                LogicalReplicationMessage::StreamCommit(stream_commit_body) => {
-                    Ok(CdcEvent::StreamCommit(stream_commit_body))
-                }
-                LogicalReplicationMessage::StreamAbort(stream_abort_body) => {
-                    Ok(CdcEvent::StreamAbort(stream_abort_body))
-                }
-                _ => Err(CdcEventConversionError::UnknownReplicationMessage),
-            },
+            }
             ReplicationMessage::PrimaryKeepAlive(primary_keepalive_body) => {
                 Ok(CdcEvent::PrimaryKeepAlive(primary_keepalive_body))
             }
             _ => Err(CdcEventConversionError::UnknownReplicationMessage),
         }
     }
+
+    fn try_from_logical_replication_message(
+        lsn: u64,
+        message: LogicalReplicationMessage,
+        table_schemas: &HashMap<SrcTableId, TableSchema>,
+    ) -> Result<CdcEvent, CdcEventConversionError> {
+        match message {
+            LogicalReplicationMessage::Begin(begin_body) => Ok(CdcEvent::Begin(begin_body)),
+            LogicalReplicationMessage::Commit(commit_body) => Ok(CdcEvent::Commit(commit_body)),
+            LogicalReplicationMessage::Origin(_) => {
+                Err(CdcEventConversionError::MessageNotSupported)
+            }
+            LogicalReplicationMessage::Relation(relation_body) => {
+                Ok(CdcEvent::Relation(relation_body))
+            }
+            LogicalReplicationMessage::Type(type_body) => Ok(CdcEvent::Type(type_body)),
+            LogicalReplicationMessage::Insert(insert_body) => {
+                let table_id = insert_body.rel_id();
+                let column_schemas = &table_schemas
+                    .get(&table_id)
+                    .ok_or(CdcEventConversionError::MissingSchema(table_id))?
+                    .column_schemas;
+                Self::try_from_insert_body(table_id, column_schemas, &insert_body).or_else(|e| {
+                    Ok(CdcEvent::ConversionFailure(DeadLetterRecord {
+                        src_table_id: table_id,
+                        lsn,
+                        xact_id: insert_body.xid(),
+                        operation: RowOperation::Insert,
+                        old_tuple: None,
+                        new_tuple: Some(RawValue::from_tuple_data(
+                            insert_body.tuple().tuple_data(),
+                        )),
+                        error: e.to_string(),
+                    }))
+                })
+            }
+            LogicalReplicationMessage::Update(update_body) => {
+                let table_id = update_body.rel_id();
+                let column_schemas = &table_schemas
+                    .get(&table_id)
+                    .ok_or(CdcEventConversionError::MissingSchema(table_id))?
+                    .column_schemas;
+                Self::try_from_update_body(table_id, column_schemas, &update_body).or_else(|e| {
+                    Ok(CdcEvent::ConversionFailure(DeadLetterRecord {
+                        src_table_id: table_id,
+                        lsn,
+                        xact_id: update_body.xid(),
+                        operation: RowOperation::Update,
+                        old_tuple: update_body
+                            .old_tuple()
+                            .or(update_body.key_tuple())
+                            .map(|tuple| RawValue::from_tuple_data(tuple.tuple_data())),
+                        new_tuple: Some(RawValue::from_tuple_data(
+                            update_body.new_tuple().tuple_data(),
+                        )),
+                        error: e.to_string(),
+                    }))
+                })
+            }
+            LogicalReplicationMessage::Delete(delete_body) => {
+                let table_id = delete_body.rel_id();
+                let column_schemas = &table_schemas
+                    .get(&table_id)
+                    .ok_or(CdcEventConversionError::MissingSchema(table_id))?
+                    .column_schemas;
+                Self::try_from_delete_body(table_id, column_schemas, &delete_body).or_else(|e| {
+                    Ok(CdcEvent::ConversionFailure(DeadLetterRecord {
+                        src_table_id: table_id,
+                        lsn,
+                        xact_id: delete_body.xid(),
+                        operation: RowOperation::Delete,
+                        old_tuple: delete_body
+                            .key_tuple()
+                            .or(delete_body.old_tuple())
+                            .map(|tuple| RawValue::from_tuple_data(tuple.tuple_data())),
+                        new_tuple: None,
+                        error: e.to_string(),
+                    }))
+                })
+            }
+            LogicalReplicationMessage::Truncate(_) => {
+                Err(CdcEventConversionError::MessageNotSupported)
+            }
+            LogicalReplicationMessage::StreamStart(stream_start_body) => {
+                Ok(CdcEvent::StreamStart(stream_start_body))
+            }
+            LogicalReplicationMessage::StreamStop(stream_stop_body) => {
+                Ok(CdcEvent::StreamStop(stream_stop_body))
+            }
+            LogicalReplicationMessage::StreamCommit(stream_commit_body) => {
+                Ok(CdcEvent::StreamCommit(stream_commit_body))
+            }
+            LogicalReplicationMessage::StreamAbort(stream_abort_body) => {
+                Ok(CdcEvent::StreamAbort(stream_abort_body))
+            }
+            _ => Err(CdcEventConversionError::UnknownReplicationMessage),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -204,4 +302,57 @@ pub enum CdcEvent {
     StreamStop(StreamStopBody),
     StreamCommit(StreamCommitBody),
     StreamAbort(StreamAbortBody),
+    /// Logical decoding message emitted by `pg_logical_emit_message`.
+    Message(MessageBody),
+    /// Two-phase commit messages, decoded for transactions prepared with `PREPARE TRANSACTION`.
+    BeginPrepare(BeginPrepareBody),
+    Prepare(PrepareBody),
+    CommitPrepared(CommitPreparedBody),
+    RollbackPrepared(RollbackPreparedBody),
+    StreamPrepare(PrepareBody),
+    /// Row change which fails conversion, handled according to the table's conversion error policy.
+    ConversionFailure(DeadLetterRecord),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use tokio_postgres::types::Type;
+
+    #[test]
+    fn test_try_from_tuple_data_slice_column_count_mismatch() {
+        let column_schemas = vec![ColumnSchema {
+            name: "id".to_string(),
+            typ: Type::INT4,
+            modifier: -1,
+            nullable: false,
+            attnum: 1,
+        }];
+        let row = CdcEventConverter::try_from_tuple_data_slice(
+            &column_schemas,
+            &[TupleData::Text(Bytes::from_static(b"1"))],
+        )
+        .unwrap();
+        assert!(matches!(row.values.as_slice(), [Cell::I32(1)]));
+
+        // Tuple with fewer or more columns than the schema returns error instead of panicking.
+        assert!(matches!(
+            CdcEventConverter::try_from_tuple_data_slice(&column_schemas, &[]),
+            Err(CdcEventConversionError::ColumnCountMismatch {
+                expected: 1,
+                actual: 0
+            })
+        ));
+        assert!(matches!(
+            CdcEventConverter::try_from_tuple_data_slice(
+                &column_schemas,
+                &[TupleData::Null, TupleData::Null]
+            ),
+            Err(CdcEventConversionError::ColumnCountMismatch {
+                expected: 1,
+                actual: 2
+            })
+        ));
+    }
 }
@@ -0,0 +1,56 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use thiserror::Error;
+use tokio_postgres::types::{Kind, Type};
+
+#[derive(Debug, Error)]
+pub enum HstoreParseError {
+    #[error("invalid hstore: {0}")]
+    InvalidInput(String),
+}
+
+/// Hstore comes from an extension, so it doesn't have a fixed oid and is identified by name.
+pub fn is_hstore_type(typ: &Type) -> bool {
+    typ.name() == "hstore" && matches!(typ.kind(), Kind::Simple)
+}
+
+/// Read a double quoted string, with backslash escapes.
+fn parse_quoted(chars: &mut Peekable<Chars>) -> Option<String> {
+    if chars.next() != Some('"') {
+        return None;
+    }
+    let mut val_str = String::new();
+    loop {
+        match chars.next()? {
+            '\\' => val_str.push(chars.next()?),
+            '"' => return Some(val_str),
+            c => val_str.push(c),
+        }
+    }
+}
+
+/// Parse hstore in the text format, e.g. `"a"=>"1", "b"=>NULL`, into key value pairs.
+pub fn parse_hstore(s: &str) -> Result<Vec<(String, Option<String>)>, HstoreParseError> {
+    let invalid_input = || HstoreParseError::InvalidInput(s.to_string());
+    let mut entries = vec![];
+    let mut chars = s.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+        if chars.peek().is_none() {
+            return Ok(entries);
+        }
+        let key = parse_quoted(&mut chars).ok_or_else(invalid_input)?;
+        if chars.next() != Some('=') || chars.next() != Some('>') {
+            return Err(invalid_input());
+        }
+        let value = if chars.peek() == Some(&'"') {
+            Some(parse_quoted(&mut chars).ok_or_else(invalid_input)?)
+        } else if chars.by_ref().take(4).collect::<String>() == "NULL" {
+            None
+        } else {
+            return Err(invalid_input());
+        };
+        entries.push((key, value));
+    }
+}
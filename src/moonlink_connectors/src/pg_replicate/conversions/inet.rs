@@ -0,0 +1,68 @@
+use std::net::{AddrParseError, IpAddr};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+/// A rust variant of the Postgres inet and cidr types, an IP address along with its network prefix length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PgInet {
+    pub address: IpAddr,
+    pub prefix_length: u8,
+}
+
+#[derive(Debug, Error)]
+pub enum ParseInetError {
+    #[error("invalid address: {0}")]
+    InvalidAddress(#[from] AddrParseError),
+
+    #[error("invalid prefix length: {0}")]
+    InvalidPrefixLength(String),
+
+    #[error("invalid mac address: {0}")]
+    InvalidMacAddr(String),
+}
+
+impl FromStr for PgInet {
+    type Err = ParseInetError;
+
+    /// Parses inet or cidr, prefix length is omitted for inet with a host address, e.g. `192.168.0.1`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, prefix_length) = match s.split_once('/') {
+            Some((address, prefix_length)) => (address, Some(prefix_length)),
+            None => (s, None),
+        };
+        let address = IpAddr::from_str(address)?;
+        let max_prefix_length = if address.is_ipv4() { 32 } else { 128 };
+        let prefix_length = match prefix_length {
+            Some(prefix_length) => prefix_length
+                .parse()
+                .ok()
+                .filter(|prefix_length| *prefix_length <= max_prefix_length)
+                .ok_or_else(|| ParseInetError::InvalidPrefixLength(s.to_string()))?,
+            None => max_prefix_length,
+        };
+        Ok(PgInet {
+            address,
+            prefix_length,
+        })
+    }
+}
+
+impl PgInet {
+    /// Get address octets in network order, 4 bytes for IPv4 and 16 bytes for IPv6.
+    pub fn address_octets(&self) -> Vec<u8> {
+        match self.address {
+            IpAddr::V4(address) => address.octets().to_vec(),
+            IpAddr::V6(address) => address.octets().to_vec(),
+        }
+    }
+}
+
+/// Parses macaddr or macaddr8, e.g. `08:00:2b:01:02:03`, into bytes.
+pub fn parse_macaddr(s: &str) -> Result<Vec<u8>, ParseInetError> {
+    s.split(':')
+        .map(|byte| {
+            u8::from_str_radix(byte, 16).map_err(|_| ParseInetError::InvalidMacAddr(s.to_string()))
+        })
+        .collect()
+}
@@ -0,0 +1,70 @@
+use std::str::FromStr;
+
+use thiserror::Error;
+
+/// A rust variant of the Postgres interval type, which keeps months, days and time separately,
+/// since their lengths vary.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PgInterval {
+    pub months: i32,
+    pub days: i32,
+    pub microseconds: i64,
+}
+
+#[derive(Debug, Error)]
+pub enum ParseIntervalError {
+    #[error("invalid interval: {0}")]
+    InvalidInput(String),
+}
+
+/// Parse the time part of an interval, e.g. `-04:05:06.789`, into microseconds.
+fn parse_interval_time(s: &str) -> Option<i64> {
+    let (sign, s) = match s.as_bytes().first() {
+        Some(b'-') => (-1, &s[1..]),
+        Some(b'+') => (1, &s[1..]),
+        _ => (1, s),
+    };
+    let mut parts = s.split(':');
+    let hours: i64 = parts.next()?.parse().ok()?;
+    let minutes: i64 = parts.next()?.parse().ok()?;
+    let (seconds, fraction) = match parts.next() {
+        Some(seconds) => seconds.split_once('.').unwrap_or((seconds, "")),
+        None => ("0", ""),
+    };
+    if parts.next().is_some() || fraction.len() > 6 {
+        return None;
+    }
+    let seconds: i64 = seconds.parse().ok()?;
+    let fraction: i64 = if fraction.is_empty() {
+        0
+    } else {
+        format!("{fraction:0<6}").parse().ok()?
+    };
+    Some(sign * (((hours * 60 + minutes) * 60 + seconds) * 1_000_000 + fraction))
+}
+
+impl FromStr for PgInterval {
+    type Err = ParseIntervalError;
+
+    /// Parses interval in the default `postgres` output style, e.g. `1 year 2 mons -3 days +04:05:06.789`.
+    /// Other styles are rejected; source connections always set `IntervalStyle` to `postgres`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid_input = || ParseIntervalError::InvalidInput(s.to_string());
+        let mut interval = PgInterval::default();
+        let mut tokens = s.split_whitespace();
+        while let Some(token) = tokens.next() {
+            if token.contains(':') {
+                interval.microseconds += parse_interval_time(token).ok_or_else(invalid_input)?;
+                continue;
+            }
+            let value: i32 = token.parse().map_err(|_| invalid_input())?;
+            match tokens.next() {
+                Some("year" | "years") => interval.months += value * 12,
+                Some("mon" | "mons") => interval.months += value,
+                Some("day" | "days") => interval.days += value,
+                _ => return Err(invalid_input()),
+            }
+        }
+        Ok(interval)
+    }
+}
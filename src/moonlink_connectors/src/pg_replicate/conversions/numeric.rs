@@ -101,6 +101,46 @@ impl Display for PgNumeric {
     }
 }
 
+/// Scale of the Postgres money type, which is determined by `lc_monetary` and is 2 for most locales.
+pub const MONEY_SCALE: i64 = 2;
+
+/// Parse money in the text format, e.g. `-$1,234.56` or `($1,234.56)`, currency symbols and
+/// group separators are dropped.
+///
+/// The format depends on `lc_monetary` of the source, which isn't known here. Only values with `.` as the decimal
+/// point and at most [`MONEY_SCALE`] fractional digits are accepted, so values formatted for other locales, e.g.
+/// `1.234,56 €`, fail conversion rather than being misread.
+pub fn parse_money(s: &str) -> Result<PgNumeric, ParseBigDecimalError> {
+    let unsupported = || {
+        ParseBigDecimalError::Other(format!(
+            "unsupported money format '{s}', expected `.` as decimal point and at most {MONEY_SCALE} fractional digits"
+        ))
+    };
+    let (integer_part, fraction_part) = s.rsplit_once('.').ok_or_else(unsupported)?;
+    let fraction_digits = fraction_part
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>();
+    let integer_digits = integer_part
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect::<String>();
+    if integer_part.contains('.')
+        || integer_digits.is_empty()
+        || fraction_digits.is_empty()
+        || fraction_digits.len() > MONEY_SCALE as usize
+        || fraction_part[fraction_digits.len()..]
+            .chars()
+            .any(|c| c.is_ascii_digit())
+    {
+        return Err(unsupported());
+    }
+    let negative = s.contains('-') || s.contains('(');
+    let value = BigDecimal::from_str(&format!("{integer_digits}.{fraction_digits}"))?
+        .with_scale(MONEY_SCALE);
+    Ok(PgNumeric::Value(if negative { -value } else { value }))
+}
+
 impl Default for PgNumeric {
     fn default() -> Self {
         PgNumeric::Value(BigDecimal::default())
@@ -0,0 +1,549 @@
+//! Decoding of pgoutput records which `postgres_replication` doesn't decode, parsed from the raw XLogData payload.
+//! Other records are delegated to `postgres_replication`.
+//!
+//! Reference: [https://www.postgresql.org/docs/current/protocol-logicalrep-message-formats.html]
+use byteorder::{BigEndian, ReadBytesExt};
+use bytes::Bytes;
+use postgres_replication::protocol::LogicalReplicationMessage;
+use std::io::{self, BufRead, Cursor, Read};
+
+/// Record tags, see the reference above.
+const MESSAGE_TAG: u8 = b'M';
+const STREAM_START_TAG: u8 = b'S';
+const STREAM_STOP_TAG: u8 = b'E';
+const BEGIN_PREPARE_TAG: u8 = b'b';
+const PREPARE_TAG: u8 = b'P';
+const COMMIT_PREPARED_TAG: u8 = b'K';
+const ROLLBACK_PREPARED_TAG: u8 = b'r';
+const STREAM_PREPARE_TAG: u8 = b'p';
+
+/// Latest protocol version whose records are decoded by `postgres_replication`; version 3 only adds two-phase
+/// records, which are decoded locally.
+const MAX_DELEGATED_PROTOCOL_VERSION: u8 = 2;
+
+/// A pgoutput record.
+#[derive(Debug)]
+pub enum PgOutputMessage {
+    /// Record decoded by `postgres_replication`.
+    Logical(LogicalReplicationMessage),
+    /// Logical decoding message emitted by `pg_logical_emit_message`.
+    Message(MessageBody),
+    /// Two-phase commit records, only sent with protocol version 3 and `two_phase` enabled.
+    BeginPrepare(BeginPrepareBody),
+    Prepare(PrepareBody),
+    CommitPrepared(CommitPreparedBody),
+    RollbackPrepared(RollbackPreparedBody),
+    StreamPrepare(PrepareBody),
+}
+
+/// Decoder for records of one replication stream.
+///
+/// Records within a streamed transaction (between Stream Start and Stream Stop) carry the transaction id, so the
+/// decoder tracks whether it's inside one.
+#[derive(Debug)]
+pub struct PgOutputDecoder {
+    /// Protocol version negotiated with `START_REPLICATION`.
+    protocol_version: u8,
+    /// Whether the current record belongs to a streamed transaction.
+    in_streamed_transaction: bool,
+}
+
+impl PgOutputDecoder {
+    pub fn new(protocol_version: u8) -> Self {
+        Self {
+            protocol_version,
+            in_streamed_transaction: false,
+        }
+    }
+
+    /// Decode the payload of one XLogData message.
+    pub fn decode(&mut self, buf: Bytes) -> io::Result<PgOutputMessage> {
+        let Some((&tag, body)) = buf.split_first() else {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "empty pgoutput record",
+            ));
+        };
+        let mut cursor = Cursor::new(body);
+        match tag {
+            MESSAGE_TAG => {
+                return MessageBody::parse(&mut cursor, self.in_streamed_transaction)
+                    .map(PgOutputMessage::Message);
+            }
+            BEGIN_PREPARE_TAG => {
+                return BeginPrepareBody::parse(&mut cursor).map(PgOutputMessage::BeginPrepare);
+            }
+            PREPARE_TAG => {
+                return PrepareBody::parse(&mut cursor).map(PgOutputMessage::Prepare);
+            }
+            COMMIT_PREPARED_TAG => {
+                return CommitPreparedBody::parse(&mut cursor).map(PgOutputMessage::CommitPrepared);
+            }
+            ROLLBACK_PREPARED_TAG => {
+                return RollbackPreparedBody::parse(&mut cursor)
+                    .map(PgOutputMessage::RollbackPrepared);
+            }
+            STREAM_PREPARE_TAG => {
+                return PrepareBody::parse(&mut cursor).map(PgOutputMessage::StreamPrepare);
+            }
+            STREAM_START_TAG => self.in_streamed_transaction = true,
+            STREAM_STOP_TAG => self.in_streamed_transaction = false,
+            _ => {}
+        }
+        LogicalReplicationMessage::parse(
+            &buf,
+            self.protocol_version.min(MAX_DELEGATED_PROTOCOL_VERSION),
+        )
+        .map(PgOutputMessage::Logical)
+    }
+}
+
+/// Logical decoding message record.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageBody {
+    xid: Option<u32>,
+    transactional: bool,
+    lsn: u64,
+    prefix: String,
+    content: Vec<u8>,
+}
+
+impl MessageBody {
+    /// Flag bit set for transactional messages.
+    const TRANSACTIONAL_FLAG: u8 = 1;
+
+    fn parse(cursor: &mut Cursor<&[u8]>, in_streamed_transaction: bool) -> io::Result<Self> {
+        let xid = if in_streamed_transaction {
+            Some(cursor.read_u32::<BigEndian>()?)
+        } else {
+            None
+        };
+        let flags = cursor.read_u8()?;
+        let lsn = cursor.read_u64::<BigEndian>()?;
+        let prefix = read_cstr(cursor)?;
+        let content_len = cursor.read_u32::<BigEndian>()? as usize;
+        let mut content = vec![0; content_len];
+        cursor.read_exact(&mut content)?;
+        Ok(Self {
+            xid,
+            transactional: flags & Self::TRANSACTIONAL_FLAG != 0,
+            lsn,
+            prefix,
+            content,
+        })
+    }
+
+    /// Transaction id, only present for streamed transactions.
+    pub fn xid(&self) -> Option<u32> {
+        self.xid
+    }
+
+    /// Whether the message is part of a transaction, otherwise it's emitted immediately.
+    pub fn transactional(&self) -> bool {
+        self.transactional
+    }
+
+    /// LSN of the message.
+    pub fn lsn(&self) -> u64 {
+        self.lsn
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+}
+
+/// Begin Prepare record, which starts a transaction being prepared.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BeginPrepareBody {
+    prepare_lsn: u64,
+    end_lsn: u64,
+    timestamp: i64,
+    xid: u32,
+    gid: String,
+}
+
+impl BeginPrepareBody {
+    fn parse(cursor: &mut Cursor<&[u8]>) -> io::Result<Self> {
+        Ok(Self {
+            prepare_lsn: cursor.read_u64::<BigEndian>()?,
+            end_lsn: cursor.read_u64::<BigEndian>()?,
+            timestamp: cursor.read_i64::<BigEndian>()?,
+            xid: cursor.read_u32::<BigEndian>()?,
+            gid: read_cstr(cursor)?,
+        })
+    }
+
+    pub fn prepare_lsn(&self) -> u64 {
+        self.prepare_lsn
+    }
+
+    pub fn end_lsn(&self) -> u64 {
+        self.end_lsn
+    }
+
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    pub fn xid(&self) -> u32 {
+        self.xid
+    }
+
+    pub fn gid(&self) -> &str {
+        &self.gid
+    }
+}
+
+/// Prepare record, also used for Stream Prepare which has the same layout.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrepareBody {
+    prepare_lsn: u64,
+    end_lsn: u64,
+    timestamp: i64,
+    xid: u32,
+    gid: String,
+}
+
+impl PrepareBody {
+    fn parse(cursor: &mut Cursor<&[u8]>) -> io::Result<Self> {
+        // Flags are currently unused.
+        let _flags = cursor.read_u8()?;
+        Ok(Self {
+            prepare_lsn: cursor.read_u64::<BigEndian>()?,
+            end_lsn: cursor.read_u64::<BigEndian>()?,
+            timestamp: cursor.read_i64::<BigEndian>()?,
+            xid: cursor.read_u32::<BigEndian>()?,
+            gid: read_cstr(cursor)?,
+        })
+    }
+
+    pub fn prepare_lsn(&self) -> u64 {
+        self.prepare_lsn
+    }
+
+    pub fn end_lsn(&self) -> u64 {
+        self.end_lsn
+    }
+
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    pub fn xid(&self) -> u32 {
+        self.xid
+    }
+
+    pub fn gid(&self) -> &str {
+        &self.gid
+    }
+}
+
+/// Commit Prepared record.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitPreparedBody {
+    commit_lsn: u64,
+    end_lsn: u64,
+    timestamp: i64,
+    xid: u32,
+    gid: String,
+}
+
+impl CommitPreparedBody {
+    fn parse(cursor: &mut Cursor<&[u8]>) -> io::Result<Self> {
+        // Flags are currently unused.
+        let _flags = cursor.read_u8()?;
+        Ok(Self {
+            commit_lsn: cursor.read_u64::<BigEndian>()?,
+            end_lsn: cursor.read_u64::<BigEndian>()?,
+            timestamp: cursor.read_i64::<BigEndian>()?,
+            xid: cursor.read_u32::<BigEndian>()?,
+            gid: read_cstr(cursor)?,
+        })
+    }
+
+    pub fn commit_lsn(&self) -> u64 {
+        self.commit_lsn
+    }
+
+    pub fn end_lsn(&self) -> u64 {
+        self.end_lsn
+    }
+
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    pub fn xid(&self) -> u32 {
+        self.xid
+    }
+
+    pub fn gid(&self) -> &str {
+        &self.gid
+    }
+}
+
+/// Rollback Prepared record.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RollbackPreparedBody {
+    prepare_end_lsn: u64,
+    rollback_end_lsn: u64,
+    prepare_timestamp: i64,
+    rollback_timestamp: i64,
+    xid: u32,
+    gid: String,
+}
+
+impl RollbackPreparedBody {
+    fn parse(cursor: &mut Cursor<&[u8]>) -> io::Result<Self> {
+        // Flags are currently unused.
+        let _flags = cursor.read_u8()?;
+        Ok(Self {
+            prepare_end_lsn: cursor.read_u64::<BigEndian>()?,
+            rollback_end_lsn: cursor.read_u64::<BigEndian>()?,
+            prepare_timestamp: cursor.read_i64::<BigEndian>()?,
+            rollback_timestamp: cursor.read_i64::<BigEndian>()?,
+            xid: cursor.read_u32::<BigEndian>()?,
+            gid: read_cstr(cursor)?,
+        })
+    }
+
+    pub fn prepare_end_lsn(&self) -> u64 {
+        self.prepare_end_lsn
+    }
+
+    pub fn rollback_end_lsn(&self) -> u64 {
+        self.rollback_end_lsn
+    }
+
+    pub fn prepare_timestamp(&self) -> i64 {
+        self.prepare_timestamp
+    }
+
+    pub fn rollback_timestamp(&self) -> i64 {
+        self.rollback_timestamp
+    }
+
+    pub fn xid(&self) -> u32 {
+        self.xid
+    }
+
+    pub fn gid(&self) -> &str {
+        &self.gid
+    }
+}
+
+/// Read a null-terminated UTF-8 string.
+fn read_cstr(cursor: &mut Cursor<&[u8]>) -> io::Result<String> {
+    let mut bytes = vec![];
+    cursor.read_until(0, &mut bytes)?;
+    if bytes.pop() != Some(0) {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "unterminated string",
+        ));
+    }
+    String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encode a logical decoding message record.
+    fn message_record(
+        xid: Option<u32>,
+        flags: u8,
+        lsn: u64,
+        prefix: &str,
+        content: &[u8],
+    ) -> Bytes {
+        let mut buf = vec![MESSAGE_TAG];
+        if let Some(xid) = xid {
+            buf.extend_from_slice(&xid.to_be_bytes());
+        }
+        buf.push(flags);
+        buf.extend_from_slice(&lsn.to_be_bytes());
+        buf.extend_from_slice(prefix.as_bytes());
+        buf.push(0);
+        buf.extend_from_slice(&(content.len() as u32).to_be_bytes());
+        buf.extend_from_slice(content);
+        Bytes::from(buf)
+    }
+
+    #[test]
+    fn test_decode_message() {
+        let mut decoder = PgOutputDecoder::new(/*protocol_version=*/ 2);
+        let record = message_record(
+            /*xid=*/ None,
+            /*flags=*/ 1,
+            /*lsn=*/ 0x16B3748,
+            "checkpoint",
+            b"batch-42",
+        );
+        let PgOutputMessage::Message(body) = decoder.decode(record).unwrap() else {
+            panic!("expects message record");
+        };
+        assert_eq!(body.xid(), None);
+        assert!(body.transactional());
+        assert_eq!(body.lsn(), 0x16B3748);
+        assert_eq!(body.prefix(), "checkpoint");
+        assert_eq!(body.content(), b"batch-42");
+
+        // Non-transactional message with empty content.
+        let record = message_record(
+            /*xid=*/ None, /*flags=*/ 0, /*lsn=*/ 10, "marker", b"",
+        );
+        let PgOutputMessage::Message(body) = decoder.decode(record).unwrap() else {
+            panic!("expects message record");
+        };
+        assert!(!body.transactional());
+        assert!(body.content().is_empty());
+    }
+
+    #[test]
+    fn test_decode_message_in_streamed_transaction() {
+        let mut decoder = PgOutputDecoder::new(/*protocol_version=*/ 2);
+        // Stream Start: xid 700, first segment.
+        let mut stream_start = vec![STREAM_START_TAG];
+        stream_start.extend_from_slice(&700_u32.to_be_bytes());
+        stream_start.push(1);
+        assert!(matches!(
+            decoder.decode(Bytes::from(stream_start)).unwrap(),
+            PgOutputMessage::Logical(LogicalReplicationMessage::StreamStart(_))
+        ));
+
+        let record = message_record(
+            /*xid=*/ Some(700),
+            /*flags=*/ 1,
+            /*lsn=*/ 20,
+            "checkpoint",
+            b"x",
+        );
+        let PgOutputMessage::Message(body) = decoder.decode(record).unwrap() else {
+            panic!("expects message record");
+        };
+        assert_eq!(body.xid(), Some(700));
+        assert_eq!(body.content(), b"x");
+
+        // Messages after Stream Stop carry no xid.
+        assert!(matches!(
+            decoder.decode(Bytes::from(vec![STREAM_STOP_TAG])).unwrap(),
+            PgOutputMessage::Logical(LogicalReplicationMessage::StreamStop(_))
+        ));
+        let record = message_record(
+            /*xid=*/ None, /*flags=*/ 0, /*lsn=*/ 30, "marker", b"y",
+        );
+        let PgOutputMessage::Message(body) = decoder.decode(record).unwrap() else {
+            panic!("expects message record");
+        };
+        assert_eq!(body.xid(), None);
+    }
+
+    /// Encode the fields shared by two-phase records, after the tag and flags.
+    fn two_phase_fields(
+        lsn_1: u64,
+        lsn_2: u64,
+        timestamps: &[i64],
+        xid: u32,
+        gid: &str,
+    ) -> Vec<u8> {
+        let mut buf = vec![];
+        buf.extend_from_slice(&lsn_1.to_be_bytes());
+        buf.extend_from_slice(&lsn_2.to_be_bytes());
+        for timestamp in timestamps {
+            buf.extend_from_slice(&timestamp.to_be_bytes());
+        }
+        buf.extend_from_slice(&xid.to_be_bytes());
+        buf.extend_from_slice(gid.as_bytes());
+        buf.push(0);
+        buf
+    }
+
+    #[test]
+    fn test_decode_two_phase_records() {
+        let mut decoder = PgOutputDecoder::new(/*protocol_version=*/ 3);
+
+        let mut record = vec![BEGIN_PREPARE_TAG];
+        record.extend(two_phase_fields(100, 200, &[7], 900, "gid-1"));
+        let PgOutputMessage::BeginPrepare(body) = decoder.decode(Bytes::from(record)).unwrap()
+        else {
+            panic!("expects begin prepare record");
+        };
+        assert_eq!(body.prepare_lsn(), 100);
+        assert_eq!(body.end_lsn(), 200);
+        assert_eq!(body.timestamp(), 7);
+        assert_eq!(body.xid(), 900);
+        assert_eq!(body.gid(), "gid-1");
+
+        let mut record = vec![PREPARE_TAG, 0];
+        record.extend(two_phase_fields(100, 200, &[7], 900, "gid-1"));
+        let PgOutputMessage::Prepare(body) = decoder.decode(Bytes::from(record)).unwrap() else {
+            panic!("expects prepare record");
+        };
+        assert_eq!(body.end_lsn(), 200);
+        assert_eq!(body.xid(), 900);
+        assert_eq!(body.gid(), "gid-1");
+
+        let mut record = vec![STREAM_PREPARE_TAG, 0];
+        record.extend(two_phase_fields(300, 400, &[8], 901, "gid-2"));
+        let PgOutputMessage::StreamPrepare(body) = decoder.decode(Bytes::from(record)).unwrap()
+        else {
+            panic!("expects stream prepare record");
+        };
+        assert_eq!(body.prepare_lsn(), 300);
+        assert_eq!(body.xid(), 901);
+        assert_eq!(body.gid(), "gid-2");
+
+        let mut record = vec![COMMIT_PREPARED_TAG, 0];
+        record.extend(two_phase_fields(500, 600, &[9], 900, "gid-1"));
+        let PgOutputMessage::CommitPrepared(body) = decoder.decode(Bytes::from(record)).unwrap()
+        else {
+            panic!("expects commit prepared record");
+        };
+        assert_eq!(body.commit_lsn(), 500);
+        assert_eq!(body.end_lsn(), 600);
+        assert_eq!(body.timestamp(), 9);
+        assert_eq!(body.gid(), "gid-1");
+
+        let mut record = vec![ROLLBACK_PREPARED_TAG, 0];
+        record.extend(two_phase_fields(400, 700, &[8, 10], 901, "gid-2"));
+        let PgOutputMessage::RollbackPrepared(body) = decoder.decode(Bytes::from(record)).unwrap()
+        else {
+            panic!("expects rollback prepared record");
+        };
+        assert_eq!(body.prepare_end_lsn(), 400);
+        assert_eq!(body.rollback_end_lsn(), 700);
+        assert_eq!(body.prepare_timestamp(), 8);
+        assert_eq!(body.rollback_timestamp(), 10);
+        assert_eq!(body.xid(), 901);
+        assert_eq!(body.gid(), "gid-2");
+
+        // Record without the GID.
+        let mut record = vec![COMMIT_PREPARED_TAG, 0];
+        record.extend(&two_phase_fields(500, 600, &[9], 900, "")[..28]);
+        assert!(decoder.decode(Bytes::from(record)).is_err());
+    }
+
+    #[test]
+    fn test_decode_truncated_message() {
+        let mut decoder = PgOutputDecoder::new(/*protocol_version=*/ 2);
+        let record = message_record(
+            /*xid=*/ None,
+            /*flags=*/ 1,
+            /*lsn=*/ 20,
+            "checkpoint",
+            b"content",
+        );
+        // Content is shorter than its length.
+        let truncated = record.slice(..record.len() - 1);
+        assert!(decoder.decode(truncated).is_err());
+        // Prefix isn't null-terminated.
+        let truncated = record.slice(..12);
+        assert!(decoder.decode(truncated).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use thiserror::Error;
+
+use super::{text::FromTextError, Cell};
+
+/// A rust variant of Postgres range types, bounds are `None` when unbounded.
+#[derive(Debug, Default, Clone)]
+pub struct PgRange {
+    pub lower: Option<Cell>,
+    pub upper: Option<Cell>,
+    pub lower_inclusive: bool,
+    pub upper_inclusive: bool,
+    pub empty: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum RangeParseError {
+    #[error("missing brackets")]
+    MissingBrackets,
+
+    #[error("expected two bounds")]
+    InvalidBounds,
+}
+
+/// Split the range body into lower and upper bound, unquoted empty bound means unbounded.
+fn split_range_bounds(s: &str) -> Result<[Option<String>; 2], RangeParseError> {
+    let mut bounds = vec![];
+    let mut val_str = String::new();
+    let mut val_quoted = false;
+    let mut in_quotes = false;
+    let mut chars = s.chars().peekable();
+    loop {
+        match chars.next() {
+            Some('\\') => {
+                if let Some(c) = chars.next() {
+                    val_str.push(c);
+                }
+            }
+            // Double quotes are escaped by doubling them inside of a quoted bound.
+            Some('"') if in_quotes && chars.peek() == Some(&'"') => {
+                val_str.push('"');
+                chars.next();
+            }
+            Some('"') => {
+                val_quoted = true;
+                in_quotes = !in_quotes;
+            }
+            Some(',') if !in_quotes => {
+                bounds.push((!val_str.is_empty() || val_quoted).then(|| val_str.clone()));
+                val_str.clear();
+                val_quoted = false;
+            }
+            Some(c) => val_str.push(c),
+            None => {
+                bounds.push((!val_str.is_empty() || val_quoted).then_some(val_str));
+                break;
+            }
+        }
+    }
+    bounds
+        .try_into()
+        .map_err(|_| RangeParseError::InvalidBounds)
+}
+
+/// Parse range in the text format, e.g. `[1,10)`, `(,"2024-01-01 00:00:00"]` or `empty`.
+pub fn parse_range<P>(s: &str, mut parse_bound: P) -> Result<PgRange, FromTextError>
+where
+    P: FnMut(&str) -> Result<Cell, FromTextError>,
+{
+    if s == "empty" {
+        return Ok(PgRange {
+            empty: true,
+            ..Default::default()
+        });
+    }
+    if s.len() < 2 {
+        return Err(RangeParseError::MissingBrackets.into());
+    }
+    let lower_inclusive = match s.as_bytes()[0] {
+        b'[' => true,
+        b'(' => false,
+        _ => return Err(RangeParseError::MissingBrackets.into()),
+    };
+    let upper_inclusive = match s.as_bytes()[s.len() - 1] {
+        b']' => true,
+        b')' => false,
+        _ => return Err(RangeParseError::MissingBrackets.into()),
+    };
+    let [lower, upper] = split_range_bounds(&s[1..s.len() - 1])?;
+    Ok(PgRange {
+        lower: lower.map(|lower| parse_bound(&lower)).transpose()?,
+        upper: upper.map(|upper| parse_bound(&upper)).transpose()?,
+        lower_inclusive,
+        upper_inclusive,
+        empty: false,
+    })
+}
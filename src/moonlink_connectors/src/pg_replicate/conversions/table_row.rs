@@ -9,7 +9,7 @@ use crate::pg_replicate::conversions::text::TextFormatConverter;
 
 use super::{text::FromTextError, Cell};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct TableRow {
     pub values: Vec<Cell>,
 }
@@ -139,12 +139,14 @@ mod tests {
                 typ: Type::INT4,
                 modifier: 0,
                 nullable: false,
+                attnum: 1,
             },
             ColumnSchema {
                 name: "text".into(),
                 typ: Type::TEXT,
                 modifier: 0,
                 nullable: false,
+                attnum: 2,
             },
         ];
         let row = b"42\thello\n";
@@ -162,12 +164,14 @@ mod tests {
                 typ: Type::TEXT,
                 modifier: 0,
                 nullable: true,
+                attnum: 3,
             },
             ColumnSchema {
                 name: "b".into(),
                 typ: Type::TEXT,
                 modifier: 0,
                 nullable: true,
+                attnum: 4,
             },
         ];
         // first column NULL, second column contains literal "\\N"
@@ -184,6 +188,7 @@ mod tests {
             typ: Type::INT4,
             modifier: 0,
             nullable: false,
+            attnum: 5,
         }];
         let err = TableRowConverter::try_from(b"1\t2", &schemas);
         assert!(matches!(
@@ -199,6 +204,7 @@ mod tests {
             typ: Type::INT4,
             modifier: 0,
             nullable: false,
+            attnum: 6,
         }];
         let err = TableRowConverter::try_from(b"1\t2\n", &schemas);
         assert!(matches!(
@@ -4,12 +4,28 @@ use std::num::{ParseFloatError, ParseIntError};
 use bigdecimal::ParseBigDecimalError;
 use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use thiserror::Error;
-use tokio_postgres::types::Type;
+use tokio_postgres::types::{Kind, Type};
 use uuid::Uuid;
 
-use crate::pg_replicate::conversions::{bool::parse_bool, hex};
-
-use super::{bool::ParseBoolError, hex::ByteaHexParseError, numeric::PgNumeric, ArrayCell, Cell};
+use crate::pg_replicate::conversions::{
+    bool::parse_bool,
+    hex,
+    hstore::{is_hstore_type, parse_hstore},
+    inet::parse_macaddr,
+    numeric::parse_money,
+    range::parse_range,
+};
+
+use super::{
+    bool::ParseBoolError,
+    hex::ByteaHexParseError,
+    hstore::HstoreParseError,
+    inet::ParseInetError,
+    interval::{ParseIntervalError, PgInterval},
+    numeric::PgNumeric,
+    range::RangeParseError,
+    ArrayCell, Cell,
+};
 
 #[derive(Debug, Error)]
 pub enum FromTextError {
@@ -43,6 +59,18 @@ pub enum FromTextError {
     #[error("invalid array: {0}")]
     InvalidArray(#[from] ArrayParseError),
 
+    #[error("invalid interval: {0}")]
+    InvalidInterval(#[from] ParseIntervalError),
+
+    #[error("invalid inet: {0}")]
+    InvalidInet(#[from] ParseInetError),
+
+    #[error("invalid range: {0}")]
+    InvalidRange(#[from] RangeParseError),
+
+    #[error("invalid hstore: {0}")]
+    InvalidHstore(#[from] HstoreParseError),
+
     #[error("row get error: {0:?}")]
     RowGetError(#[from] Box<dyn std::error::Error + Sync + Send>),
 }
@@ -60,51 +88,67 @@ pub enum ArrayParseError {
 
 impl TextFormatConverter {
     pub fn is_supported_type(typ: &Type) -> bool {
-        matches!(
-            *typ,
-            Type::BOOL
-                | Type::BOOL_ARRAY
-                | Type::CHAR
-                | Type::BPCHAR
-                | Type::VARCHAR
-                | Type::NAME
-                | Type::TEXT
-                | Type::CHAR_ARRAY
-                | Type::BPCHAR_ARRAY
-                | Type::VARCHAR_ARRAY
-                | Type::NAME_ARRAY
-                | Type::TEXT_ARRAY
-                | Type::INT2
-                | Type::INT2_ARRAY
-                | Type::INT4
-                | Type::INT4_ARRAY
-                | Type::INT8
-                | Type::INT8_ARRAY
-                | Type::FLOAT4
-                | Type::FLOAT4_ARRAY
-                | Type::FLOAT8
-                | Type::FLOAT8_ARRAY
-                | Type::NUMERIC
-                | Type::NUMERIC_ARRAY
-                | Type::BYTEA
-                | Type::BYTEA_ARRAY
-                | Type::DATE
-                | Type::DATE_ARRAY
-                | Type::TIME
-                | Type::TIME_ARRAY
-                | Type::TIMESTAMP
-                | Type::TIMESTAMP_ARRAY
-                | Type::TIMESTAMPTZ
-                | Type::TIMESTAMPTZ_ARRAY
-                | Type::UUID
-                | Type::UUID_ARRAY
-                | Type::JSON
-                | Type::JSON_ARRAY
-                | Type::JSONB
-                | Type::JSONB_ARRAY
-                | Type::OID
-                | Type::OID_ARRAY
-        )
+        is_hstore_type(typ)
+            || matches!(
+                *typ,
+                Type::BOOL
+                    | Type::BOOL_ARRAY
+                    | Type::CHAR
+                    | Type::BPCHAR
+                    | Type::VARCHAR
+                    | Type::NAME
+                    | Type::TEXT
+                    | Type::CHAR_ARRAY
+                    | Type::BPCHAR_ARRAY
+                    | Type::VARCHAR_ARRAY
+                    | Type::NAME_ARRAY
+                    | Type::TEXT_ARRAY
+                    | Type::INT2
+                    | Type::INT2_ARRAY
+                    | Type::INT4
+                    | Type::INT4_ARRAY
+                    | Type::INT8
+                    | Type::INT8_ARRAY
+                    | Type::FLOAT4
+                    | Type::FLOAT4_ARRAY
+                    | Type::FLOAT8
+                    | Type::FLOAT8_ARRAY
+                    | Type::NUMERIC
+                    | Type::NUMERIC_ARRAY
+                    | Type::BYTEA
+                    | Type::BYTEA_ARRAY
+                    | Type::DATE
+                    | Type::DATE_ARRAY
+                    | Type::TIME
+                    | Type::TIME_ARRAY
+                    | Type::TIMESTAMP
+                    | Type::TIMESTAMP_ARRAY
+                    | Type::TIMESTAMPTZ
+                    | Type::TIMESTAMPTZ_ARRAY
+                    | Type::UUID
+                    | Type::UUID_ARRAY
+                    | Type::JSON
+                    | Type::JSON_ARRAY
+                    | Type::JSONB
+                    | Type::JSONB_ARRAY
+                    | Type::OID
+                    | Type::OID_ARRAY
+                    | Type::INTERVAL
+                    | Type::INET
+                    | Type::CIDR
+                    | Type::MACADDR
+                    | Type::MACADDR8
+                    | Type::MONEY
+                    | Type::XML
+                    | Type::BIT
+                    | Type::VARBIT
+                    | Type::INT4_RANGE
+                    | Type::INT8_RANGE
+                    | Type::NUM_RANGE
+                    | Type::DATE_RANGE
+                    | Type::TS_RANGE
+                    | Type::TSTZ_RANGE
+            )
     }
 
     pub fn default_value(typ: &Type) -> Cell {
@@ -150,6 +194,11 @@ impl TextFormatConverter {
             Type::JSON_ARRAY | Type::JSONB_ARRAY => Cell::Array(ArrayCell::Json(Vec::default())),
             Type::OID => Cell::U32(u32::default()),
             Type::OID_ARRAY => Cell::Array(ArrayCell::U32(Vec::default())),
+            Type::INTERVAL => Cell::Interval(PgInterval::default()),
+            Type::MACADDR | Type::MACADDR8 => Cell::Bytes(Vec::default()),
+            Type::MONEY => Cell::Numeric(PgNumeric::default()),
+            Type::XML | Type::BIT | Type::VARBIT => Cell::String(String::default()),
+            _ if is_hstore_type(typ) => Cell::Hstore(Vec::default()),
             _ => Cell::Null,
         }
     }
@@ -305,6 +354,25 @@ impl TextFormatConverter {
             Type::OID_ARRAY => {
                 TextFormatConverter::parse_array(str, |str| Ok(Some(str.parse()?)), ArrayCell::U32)
             }
+            Type::INTERVAL => Ok(Cell::Interval(str.parse()?)),
+            Type::INET | Type::CIDR => Ok(Cell::Inet(str.parse()?)),
+            Type::MACADDR | Type::MACADDR8 => Ok(Cell::Bytes(parse_macaddr(str)?)),
+            Type::MONEY => Ok(Cell::Numeric(parse_money(str)?)),
+            Type::XML | Type::BIT | Type::VARBIT => Ok(Cell::String(str.to_string())),
+            Type::INT4_RANGE
+            | Type::INT8_RANGE
+            | Type::NUM_RANGE
+            | Type::DATE_RANGE
+            | Type::TS_RANGE
+            | Type::TSTZ_RANGE => {
+                let Kind::Range(subtype) = typ.kind() else {
+                    return Err(FromTextError::InvalidConversion());
+                };
+                let range =
+                    parse_range(str, |str| TextFormatConverter::try_from_str(subtype, str))?;
+                Ok(Cell::Range(Box::new(range)))
+            }
+            _ if is_hstore_type(typ) => Ok(Cell::Hstore(parse_hstore(str)?)),
             _ => Err(FromTextError::InvalidConversion()),
         }
     }
@@ -459,4 +527,129 @@ mod tests {
             _ => panic!("expected string array cell"),
         }
     }
+
+    #[test]
+    fn parse_interval_inet_and_money() {
+        match TextFormatConverter::try_from_str(
+            &Type::INTERVAL,
+            "1 year 2 mons -3 days +04:05:06.789",
+        )
+        .unwrap()
+        {
+            Cell::Interval(interval) => {
+                assert_eq!(interval.months, 14);
+                assert_eq!(interval.days, -3);
+                assert_eq!(
+                    interval.microseconds,
+                    ((4 * 60 + 5) * 60 + 6) * 1_000_000 + 789_000
+                );
+            }
+            _ => panic!("expected interval cell"),
+        }
+        match TextFormatConverter::try_from_str(&Type::INTERVAL, "-100:00:00").unwrap() {
+            Cell::Interval(interval) => {
+                assert_eq!(interval.microseconds, -100 * 3600 * 1_000_000)
+            }
+            _ => panic!("expected interval cell"),
+        }
+        assert!(TextFormatConverter::try_from_str(&Type::INTERVAL, "1 fortnight").is_err());
+
+        match TextFormatConverter::try_from_str(&Type::INET, "192.168.0.1").unwrap() {
+            Cell::Inet(inet) => {
+                assert_eq!(inet.address_octets(), vec![192, 168, 0, 1]);
+                assert_eq!(inet.prefix_length, 32);
+            }
+            _ => panic!("expected inet cell"),
+        }
+        match TextFormatConverter::try_from_str(&Type::CIDR, "2001:db8::/32").unwrap() {
+            Cell::Inet(inet) => {
+                assert_eq!(inet.address_octets().len(), 16);
+                assert_eq!(inet.prefix_length, 32);
+            }
+            _ => panic!("expected inet cell"),
+        }
+        assert!(TextFormatConverter::try_from_str(&Type::INET, "10.0.0.0/33").is_err());
+
+        match TextFormatConverter::try_from_str(&Type::MACADDR, "08:00:2b:01:02:03").unwrap() {
+            Cell::Bytes(bytes) => assert_eq!(bytes, vec![0x08, 0x00, 0x2b, 0x01, 0x02, 0x03]),
+            _ => panic!("expected bytes cell"),
+        }
+
+        match TextFormatConverter::try_from_str(&Type::MONEY, "-$1,234.5").unwrap() {
+            Cell::Numeric(PgNumeric::Value(value)) => {
+                assert_eq!(value.to_string(), "-1234.50")
+            }
+            _ => panic!("expected numeric cell"),
+        }
+        match TextFormatConverter::try_from_str(&Type::MONEY, "(₹12,34,567.89)").unwrap() {
+            Cell::Numeric(PgNumeric::Value(value)) => {
+                assert_eq!(value.to_string(), "-1234567.89")
+            }
+            _ => panic!("expected numeric cell"),
+        }
+        // Formats of other locales are rejected rather than misread.
+        for money in ["1.234,56 €", "￥1,234", "1.234", "BD1.234", "$1.2.3"] {
+            assert!(TextFormatConverter::try_from_str(&Type::MONEY, money).is_err());
+        }
+        // Intervals are only parsed in the `postgres` style.
+        for interval in ["P1Y2M3DT4H5M6S", "@ 1 year 2 mons"] {
+            assert!(TextFormatConverter::try_from_str(&Type::INTERVAL, interval).is_err());
+        }
+    }
+
+    #[test]
+    fn parse_range_text() {
+        match TextFormatConverter::try_from_str(&Type::INT4_RANGE, "[1,10)").unwrap() {
+            Cell::Range(range) => {
+                assert!(matches!(range.lower, Some(Cell::I32(1))));
+                assert!(matches!(range.upper, Some(Cell::I32(10))));
+                assert!(range.lower_inclusive);
+                assert!(!range.upper_inclusive);
+                assert!(!range.empty);
+            }
+            _ => panic!("expected range cell"),
+        }
+        match TextFormatConverter::try_from_str(&Type::TS_RANGE, "(,\"2024-01-01 00:00:00\"]")
+            .unwrap()
+        {
+            Cell::Range(range) => {
+                assert!(range.lower.is_none());
+                assert!(matches!(range.upper, Some(Cell::TimeStamp(_))));
+                assert!(!range.lower_inclusive);
+                assert!(range.upper_inclusive);
+            }
+            _ => panic!("expected range cell"),
+        }
+        match TextFormatConverter::try_from_str(&Type::INT8_RANGE, "empty").unwrap() {
+            Cell::Range(range) => assert!(range.empty),
+            _ => panic!("expected range cell"),
+        }
+        assert!(TextFormatConverter::try_from_str(&Type::INT4_RANGE, "1,10").is_err());
+    }
+
+    #[test]
+    fn parse_hstore_text() {
+        let hstore = Type::new(
+            "hstore".to_string(),
+            /*oid=*/ 16385,
+            Kind::Simple,
+            "public".to_string(),
+        );
+        assert!(TextFormatConverter::is_supported_type(&hstore));
+        match TextFormatConverter::try_from_str(&hstore, r#""a"=>"1", "b \"q\""=>NULL"#).unwrap() {
+            Cell::Hstore(entries) => assert_eq!(
+                entries,
+                vec![
+                    ("a".to_string(), Some("1".to_string())),
+                    ("b \"q\"".to_string(), None),
+                ]
+            ),
+            _ => panic!("expected hstore cell"),
+        }
+        match TextFormatConverter::try_from_str(&hstore, "").unwrap() {
+            Cell::Hstore(entries) => assert!(entries.is_empty()),
+            _ => panic!("expected hstore cell"),
+        }
+        assert!(TextFormatConverter::try_from_str(&hstore, r#""a"=>1"#).is_err());
+    }
 }
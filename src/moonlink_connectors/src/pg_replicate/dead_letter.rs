@@ -0,0 +1,312 @@
+use crate::pg_replicate::conversions::cdc_event::{CdcEventConversionError, CdcEventConverter};
+use crate::pg_replicate::conversions::table_row::TableRow;
+use crate::pg_replicate::table::{ColumnSchema, SrcTableId, TableSchema};
+use crate::pg_replicate::util::PostgresTableRow;
+use crate::Result;
+use moonlink::row::MoonlinkRow;
+use postgres_replication::protocol::TupleData;
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tokio::io::AsyncWriteExt;
+
+/// Policy for rows in the replication stream which fail conversion, for example an out-of-range numeric value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConversionErrorPolicy {
+    /// Fail the table, which stops its replication until it's dropped and added again.
+    #[default]
+    Fail,
+    /// Skip the row and log the error.
+    Skip,
+    /// Skip the row, and append it to the table's dead letter file, which could be inspected and replayed later.
+    DeadLetter,
+}
+
+impl FromStr for ConversionErrorPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "fail" => Ok(Self::Fail),
+            "skip" => Ok(Self::Skip),
+            "dead_letter" => Ok(Self::DeadLetter),
+            _ => Err(format!(
+                "invalid conversion error policy '{s}', expected one of `fail`, `skip` or `dead_letter`"
+            )),
+        }
+    }
+}
+
+/// Raw value of a column, as received from the replication stream.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RawValue {
+    Null,
+    /// TOASTed value which isn't changed, thus not sent.
+    UnchangedToast,
+    /// Value in text format, which may not be valid UTF-8.
+    Text(Vec<u8>),
+}
+
+impl RawValue {
+    pub(crate) fn from_tuple_data(tuple_data: &[TupleData]) -> Vec<Self> {
+        tuple_data
+            .iter()
+            .map(|data| match data {
+                TupleData::Null => RawValue::Null,
+                TupleData::UnchangedToast => RawValue::UnchangedToast,
+                TupleData::Text(bytes) => RawValue::Text(bytes.to_vec()),
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowOperation {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// A row which failed conversion, persisted as a JSON line in the dead letter file.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DeadLetterRecord {
+    pub src_table_id: SrcTableId,
+    /// WAL position of the change.
+    pub lsn: u64,
+    /// Transaction id, only assigned for streamed transactions.
+    pub xact_id: Option<u32>,
+    pub operation: RowOperation,
+    /// Old row for updates and deletes, either the full row or only identity columns, depending on replica identity.
+    pub old_tuple: Option<Vec<RawValue>>,
+    /// New row for inserts and updates.
+    pub new_tuple: Option<Vec<RawValue>>,
+    /// Conversion error.
+    pub error: String,
+}
+
+impl DeadLetterRecord {
+    /// Convert raw tuples with the given column schemas, into (old row, new row).
+    pub(crate) fn try_convert(
+        &self,
+        column_schemas: &[ColumnSchema],
+    ) -> std::result::Result<(Option<TableRow>, Option<TableRow>), CdcEventConversionError> {
+        let convert = |tuple: &Option<Vec<RawValue>>| {
+            tuple
+                .as_ref()
+                .map(|tuple| CdcEventConverter::try_from_raw_values(column_schemas, tuple))
+                .transpose()
+        };
+        Ok((convert(&self.old_tuple)?, convert(&self.new_tuple)?))
+    }
+
+    /// Lookup keys of the old and new rows, converted with the given table schema.
+    /// Rows whose key columns fail conversion are left out, so are all rows of tables identified by the full row.
+    pub(crate) fn keys(&self, table_schema: &TableSchema) -> Vec<MoonlinkRow> {
+        let Some(positions) = table_schema.lookup_key_positions() else {
+            return vec![];
+        };
+        let column_schemas: Vec<ColumnSchema> = positions
+            .iter()
+            .map(|position| table_schema.column_schemas[*position].clone())
+            .collect();
+        [&self.old_tuple, &self.new_tuple]
+            .into_iter()
+            .flatten()
+            .filter_map(|tuple| {
+                let raw_values: Vec<RawValue> = positions
+                    .iter()
+                    .map(|position| tuple.get(*position).cloned().unwrap_or(RawValue::Null))
+                    .collect();
+                CdcEventConverter::try_from_raw_values(&column_schemas, &raw_values)
+                    .ok()
+                    .map(|row| PostgresTableRow(row).into())
+            })
+            .collect()
+    }
+}
+
+/// Dead letter file of a table, which contains one [`DeadLetterRecord`] per line.
+pub struct DeadLetterStore {
+    path: PathBuf,
+}
+
+impl DeadLetterStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a record to the dead letter file, which is created if it doesn't exist.
+    pub async fn append(&self, record: &DeadLetterRecord) -> Result<()> {
+        let mut line = serde_json::to_vec(record)
+            .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+        line.push(b'\n');
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(&line).await?;
+        file.sync_data().await?;
+        Ok(())
+    }
+
+    /// Load all records from the dead letter file, return empty if it doesn't exist.
+    pub async fn load(&self) -> Result<Vec<DeadLetterRecord>> {
+        Self::load_from(&self.path).await
+    }
+
+    /// Load all records from the dead letter file at the given path, return empty if it doesn't exist.
+    pub async fn load_from(path: &Path) -> Result<Vec<DeadLetterRecord>> {
+        let content = match tokio::fs::read(path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+        let mut records = vec![];
+        for line in content
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+        {
+            let record = serde_json::from_slice(line)
+                .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    /// Overwrite the dead letter file with the given records atomically, delete the file if there's no record.
+    pub async fn rewrite(&self, records: &[DeadLetterRecord]) -> Result<()> {
+        if records.is_empty() {
+            return Self::delete(&self.path).await;
+        }
+        let mut content = vec![];
+        for record in records {
+            serde_json::to_writer(&mut content, record)
+                .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+            content.push(b'\n');
+        }
+        let tmp_path = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, content).await?;
+        tokio::fs::rename(&tmp_path, &self.path).await?;
+        Ok(())
+    }
+
+    /// Delete dead letter file at the given path, it's not an error if it doesn't exist.
+    pub async fn delete(path: &Path) -> Result<()> {
+        match tokio::fs::remove_file(path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pg_replicate::table::{LookupKey, TableName};
+    use moonlink::row::RowValue;
+    use tokio_postgres::types::Type;
+
+    fn get_test_record(lsn: u64) -> DeadLetterRecord {
+        DeadLetterRecord {
+            src_table_id: 1,
+            lsn,
+            xact_id: None,
+            operation: RowOperation::Update,
+            old_tuple: Some(vec![RawValue::Text(b"1".to_vec())]),
+            new_tuple: Some(vec![RawValue::Null, RawValue::UnchangedToast]),
+            error: "conversion error".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_store() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = DeadLetterStore::new(temp_dir.path().join("table.dead_letter.jsonl"));
+        assert!(store.load().await.unwrap().is_empty());
+
+        store.append(&get_test_record(10)).await.unwrap();
+        store.append(&get_test_record(20)).await.unwrap();
+        assert_eq!(
+            store.load().await.unwrap(),
+            vec![get_test_record(10), get_test_record(20)]
+        );
+
+        store.rewrite(&[get_test_record(20)]).await.unwrap();
+        assert_eq!(store.load().await.unwrap(), vec![get_test_record(20)]);
+
+        store.rewrite(&[]).await.unwrap();
+        assert!(!store.path().exists());
+    }
+
+    #[test]
+    fn test_dead_letter_keys() {
+        let column_schema = |name: &str, typ: Type, attnum: i16| ColumnSchema {
+            name: name.to_string(),
+            typ,
+            modifier: -1,
+            nullable: true,
+            attnum,
+        };
+        let mut table_schema = TableSchema {
+            table_name: TableName {
+                schema: "public".to_string(),
+                name: "t".to_string(),
+            },
+            src_table_id: 1,
+            column_schemas: vec![
+                column_schema("id", Type::INT8, 1),
+                column_schema("day", Type::DATE, 2),
+            ],
+            lookup_key: LookupKey::Key {
+                name: "t_pkey".to_string(),
+                columns: vec!["id".to_string()],
+            },
+        };
+        let record = DeadLetterRecord {
+            src_table_id: 1,
+            lsn: 10,
+            xact_id: None,
+            operation: RowOperation::Insert,
+            old_tuple: None,
+            new_tuple: Some(vec![
+                RawValue::Text(b"1".to_vec()),
+                RawValue::Text(b"infinity".to_vec()),
+            ]),
+            error: "conversion error".to_string(),
+        };
+        // Only the key column is converted, so the row which fails conversion still has a key.
+        assert_eq!(
+            record.keys(&table_schema),
+            vec![MoonlinkRow::new(vec![RowValue::Int64(1)])]
+        );
+
+        table_schema.lookup_key = LookupKey::FullRow;
+        assert!(record.keys(&table_schema).is_empty());
+    }
+
+    #[test]
+    fn test_parse_conversion_error_policy() {
+        assert_eq!(
+            "fail".parse::<ConversionErrorPolicy>().unwrap(),
+            ConversionErrorPolicy::Fail
+        );
+        assert_eq!(
+            "skip".parse::<ConversionErrorPolicy>().unwrap(),
+            ConversionErrorPolicy::Skip
+        );
+        assert_eq!(
+            "dead_letter".parse::<ConversionErrorPolicy>().unwrap(),
+            ConversionErrorPolicy::DeadLetter
+        );
+        assert!("unknown".parse::<ConversionErrorPolicy>().is_err());
+    }
+}
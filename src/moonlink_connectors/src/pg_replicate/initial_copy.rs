@@ -3,13 +3,25 @@ use crate::pg_replicate::table::{ColumnSchema, LookupKey, SrcTableId, TableName,
 use crate::pg_replicate::util::PostgresTableRow;
 use crate::Result;
 use futures::{pin_mut, Stream, StreamExt};
-use moonlink::TableEvent;
+use moonlink::row::MoonlinkRow;
+use moonlink::{InitialCopyPosition, InitialCopyProgress, TableEvent};
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc::Sender;
+use tokio::sync::watch;
 use tokio::task::JoinHandle;
 use tokio_postgres::types::PgLsn;
 use tokio_postgres::types::Type;
 
+/// Minimum interval between two progress reports.
+const PROGRESS_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of rows copied between two commits, for copies in key order.
+pub const INITIAL_COPY_CHECKPOINT_ROWS: u64 = 1_000_000;
+
 /// Represents progress information for an ongoing copy.
 #[derive(Debug)]
 pub struct CopyProgress {
@@ -17,25 +29,264 @@ pub struct CopyProgress {
     pub last_lsn: PgLsn,
     /// Number of rows copied so far.
     pub rows_copied: u64,
+    /// Number of bytes read from source so far.
+    pub bytes_copied: u64,
+}
+
+/// Checkpoint for an initial copy, persisted to local filesystem.
+///
+/// It lets recovery tell an unfinished copy apart from a finished one, it's only marked finished after copied rows
+/// have been persisted. Where an unfinished copy resumes from is decided by the [`InitialCopyPosition`] persisted
+/// with the mooncake table, the copy is redone from the source if there's none.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct InitialCopyCheckpoint {
+    /// `pg_current_wal_lsn` when the initial copy starts.
+    pub start_lsn: u64,
+    /// Number of rows copied so far, including rows not persisted yet.
+    pub rows_copied: u64,
+    /// Number of bytes read from source so far.
+    pub bytes_copied: u64,
+    /// Whether the initial copy has finished.
+    pub finished: bool,
+}
+
+impl InitialCopyCheckpoint {
+    /// Load checkpoint from the given path, return `None` if it doesn't exist.
+    pub async fn load(path: &Path) -> Result<Option<Self>> {
+        let content = match tokio::fs::read(path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let checkpoint = serde_json::from_slice(&content)
+            .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+        Ok(Some(checkpoint))
+    }
+
+    /// Persist checkpoint to the given path, which overwrites the old one atomically.
+    pub async fn persist(&self, path: &Path) -> Result<()> {
+        let content =
+            serde_json::to_vec(self).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, content).await?;
+        tokio::fs::rename(&tmp_path, path).await?;
+        Ok(())
+    }
+
+    /// Delete checkpoint at the given path, it's not an error if it doesn't exist.
+    pub async fn delete(path: &Path) -> Result<()> {
+        match tokio::fs::remove_file(path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Reports initial copy progress to table status, and checkpoints it periodically.
+pub struct CopyProgressReporter {
+    /// Sender to publish progress to table status reader.
+    progress_tx: watch::Sender<Option<InitialCopyProgress>>,
+    /// Local path to persist checkpoint, skip checkpointing if unassigned.
+    checkpoint_path: Option<PathBuf>,
+    /// Estimated total number of rows, taken from row count at copy start.
+    estimated_total_rows: Option<u64>,
+    /// Checkpoint for the current copy.
+    checkpoint: InitialCopyCheckpoint,
+    /// Error of the last failed copy attempt.
+    last_error: Option<String>,
+    /// Time when the copy starts.
+    start_time: Instant,
+    /// Time of the last report.
+    last_report_time: Instant,
+}
+
+impl CopyProgressReporter {
+    pub fn new(
+        progress_tx: watch::Sender<Option<InitialCopyProgress>>,
+        checkpoint_path: Option<PathBuf>,
+        estimated_total_rows: Option<u64>,
+    ) -> Self {
+        let now = Instant::now();
+        Self {
+            progress_tx,
+            checkpoint_path,
+            estimated_total_rows,
+            checkpoint: InitialCopyCheckpoint::default(),
+            last_error: None,
+            start_time: now,
+            last_report_time: now,
+        }
+    }
+
+    /// Set start LSN for the copy, and persist an initial checkpoint.
+    pub async fn start(&mut self, start_lsn: u64) -> Result<()> {
+        self.checkpoint.start_lsn = start_lsn;
+        self.report(/*force=*/ true).await
+    }
+
+    /// Record progress, it's reported at most once per [`PROGRESS_REPORT_INTERVAL`].
+    pub async fn update(&mut self, rows_copied: u64, bytes_copied: u64) -> Result<()> {
+        self.checkpoint.rows_copied = rows_copied;
+        self.checkpoint.bytes_copied = bytes_copied;
+        self.report(/*force=*/ false).await
+    }
+
+    /// Mark the copy as finished, and report final progress.
+    pub async fn finish(&mut self) -> Result<()> {
+        self.checkpoint.finished = true;
+        self.report(/*force=*/ true).await
+    }
+
+    /// Record a failed copy attempt, and report it along with progress.
+    pub async fn fail(&mut self, error: String) {
+        self.last_error = Some(error);
+        // Failure is reported regardless of checkpoint persistence, whose error is superseded by the copy error.
+        if let Err(e) = self.report(/*force=*/ true).await {
+            tracing::warn!(error = ?e, "failed to checkpoint initial copy");
+        }
+    }
+
+    /// Get the current progress.
+    pub fn get_progress(&self) -> InitialCopyProgress {
+        let rows_copied = self.checkpoint.rows_copied;
+        let eta_secs = if self.checkpoint.finished {
+            Some(0)
+        } else {
+            let elapsed_secs = self.start_time.elapsed().as_secs_f64();
+            match self.estimated_total_rows {
+                Some(total) if rows_copied > 0 && elapsed_secs > 0.0 => {
+                    let rows_per_sec = rows_copied as f64 / elapsed_secs;
+                    let remaining_rows = total.saturating_sub(rows_copied);
+                    Some((remaining_rows as f64 / rows_per_sec).ceil() as u64)
+                }
+                _ => None,
+            }
+        };
+        InitialCopyProgress {
+            rows_copied,
+            bytes_copied: self.checkpoint.bytes_copied,
+            estimated_total_rows: self.estimated_total_rows,
+            eta_secs,
+            finished: self.checkpoint.finished,
+            last_error: self.last_error.clone(),
+        }
+    }
+
+    async fn report(&mut self, force: bool) -> Result<()> {
+        if !force && self.last_report_time.elapsed() < PROGRESS_REPORT_INTERVAL {
+            return Ok(());
+        }
+        self.last_report_time = Instant::now();
+        // Receiver could be dropped if the table has been removed, which is not an error for copy.
+        let _ = self.progress_tx.send(Some(self.get_progress()));
+        if let Some(checkpoint_path) = &self.checkpoint_path {
+            self.checkpoint.persist(checkpoint_path).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Commits rows periodically for a copy in key order, along with the position to resume from.
+pub struct CopyCheckpointer {
+    /// Index of the key column which rows are copied in the order of.
+    key_index: usize,
+    /// LSN of the source snapshot which rows are copied from.
+    snapshot_lsn: u64,
+    /// Position to commit at the next checkpoint.
+    position: InitialCopyPosition,
+    /// Number of rows copied between two checkpoints.
+    checkpoint_rows: u64,
+    /// Number of rows copied since the last checkpoint.
+    rows_since_checkpoint: u64,
+}
+
+impl CopyCheckpointer {
+    /// Create a checkpointer, which resumes from the given persisted position.
+    /// [`CopyCheckpointer::restart`] has to be called with the source snapshot before rows are copied.
+    pub fn new(key_index: usize, position: InitialCopyPosition, checkpoint_rows: u64) -> Self {
+        Self {
+            key_index,
+            snapshot_lsn: 0,
+            position,
+            checkpoint_rows,
+            rows_since_checkpoint: 0,
+        }
+    }
+
+    /// Start a copy attempt from the source snapshot at the given LSN, which resumes after the last checkpoint.
+    /// Rows copied since the last checkpoint by a failed attempt are discarded, and copied again.
+    pub fn restart(&mut self, snapshot_lsn: u64) {
+        self.snapshot_lsn = snapshot_lsn;
+        self.rows_since_checkpoint = 0;
+    }
+
+    /// Get index of the key column which rows are copied in the order of.
+    pub fn key_index(&self) -> usize {
+        self.key_index
+    }
+
+    /// Get the last key committed by checkpoints, which the next copy attempt resumes after.
+    pub fn last_key(&self) -> Option<i64> {
+        self.position.last_key()
+    }
+
+    /// Get number of rows copied before the current copy attempt.
+    pub fn rows_copied_before(&self) -> u64 {
+        self.position.rows_copied
+    }
+
+    /// Record the copied row, and send a checkpoint event once enough rows have been copied since the last one.
+    pub async fn on_row_copied(
+        &mut self,
+        row: &MoonlinkRow,
+        rows_copied: u64,
+        event_sender: &Sender<TableEvent>,
+    ) {
+        self.rows_since_checkpoint += 1;
+        if self.rows_since_checkpoint < self.checkpoint_rows {
+            return;
+        }
+        // Key column is checked to hold integers when the copy starts, and primary keys are never NULL.
+        let Some(last_key) = InitialCopyPosition::get_key(row, self.key_index) else {
+            return;
+        };
+        self.position
+            .advance(last_key, self.snapshot_lsn, rows_copied);
+        self.rows_since_checkpoint = 0;
+        if let Err(e) = event_sender
+            .send(TableEvent::CheckpointInitialCopy {
+                position: self.position.clone(),
+            })
+            .await
+        {
+            tracing::warn!(error = ?e, "failed to send initial copy checkpoint event");
+        }
+    }
 }
 
 /// Reads rows from `stream` and sends them to the provided `event_sender`.
+/// If `checkpointer` is assigned, rows are expected in key order, and committed periodically.
 pub async fn copy_table_stream_impl(
     table_schema: TableSchema,
     mut stream: TableCopyStream,
     event_sender: &Sender<TableEvent>,
+    progress_reporter: &mut CopyProgressReporter,
+    mut checkpointer: Option<&mut CopyCheckpointer>,
 ) -> Result<CopyProgress> {
     // `TableEvent::Append` events. These events must be written to the
     // Mooncake table via `event_sender`.
-    // TODO: periodically report progress and
-    // support cancellation / restart.
+    // TODO: support cancellation.
     pin_mut!(stream);
-    let mut rows_copied = 0u64;
+    let mut rows_copied = checkpointer
+        .as_deref()
+        .map_or(0, CopyCheckpointer::rows_copied_before);
     while let Some(row) = stream.next().await {
-        let row = row?;
+        let row: MoonlinkRow = PostgresTableRow(row?).into();
+        let key_row = checkpointer.as_ref().map(|_| row.clone());
         if let Err(e) = event_sender
             .send(TableEvent::Append {
-                row: PostgresTableRow(row).into(),
+                row,
                 xact_id: None,
                 lsn: 0,
                 is_copied: true,
@@ -45,11 +296,21 @@ pub async fn copy_table_stream_impl(
             tracing::warn!(error = ?e, "failed to send copied row event");
         }
         rows_copied += 1;
+        if let (Some(checkpointer), Some(key_row)) = (checkpointer.as_deref_mut(), key_row.as_ref())
+        {
+            checkpointer
+                .on_row_copied(key_row, rows_copied, event_sender)
+                .await;
+        }
+        progress_reporter
+            .update(rows_copied, stream.bytes_copied())
+            .await?;
     }
 
     Ok(CopyProgress {
         last_lsn: PgLsn::from(0),
         rows_copied,
+        bytes_copied: stream.bytes_copied(),
     })
 }
 
@@ -59,20 +320,22 @@ pub async fn copy_table_stream<S>(
     table_schema: TableSchema,
     mut stream: S,
     event_sender: Sender<TableEvent>,
+    mut checkpointer: Option<CopyCheckpointer>,
 ) -> Result<CopyProgress>
 where
     S: Stream<Item = Result<crate::pg_replicate::conversions::table_row::TableRow>> + Unpin,
 {
     // `TableEvent::Append` events. These events must be written to the
     // Mooncake table via `event_sender`.
-    // TODO: periodically report progress and
-    // support cancellation / restart.
-    let mut rows_copied = 0u64;
+    let mut rows_copied = checkpointer
+        .as_ref()
+        .map_or(0, CopyCheckpointer::rows_copied_before);
     while let Some(row) = stream.next().await {
-        let row = row?;
+        let row: MoonlinkRow = PostgresTableRow(row?).into();
+        let key_row = checkpointer.as_ref().map(|_| row.clone());
         if let Err(e) = event_sender
             .send(TableEvent::Append {
-                row: PostgresTableRow(row).into(),
+                row,
                 xact_id: None,
                 lsn: 0,
                 is_copied: true,
@@ -82,11 +345,17 @@ where
             tracing::warn!(error = ?e, "failed to send copied row event");
         }
         rows_copied += 1;
+        if let (Some(checkpointer), Some(key_row)) = (checkpointer.as_mut(), key_row.as_ref()) {
+            checkpointer
+                .on_row_copied(key_row, rows_copied, &event_sender)
+                .await;
+        }
     }
 
     Ok(CopyProgress {
         last_lsn: PgLsn::from(0),
         rows_copied,
+        bytes_copied: 0,
     })
 }
 
@@ -117,6 +386,7 @@ mod tests {
                 typ: Type::INT4,
                 modifier: 0,
                 nullable: false,
+                attnum: 1,
             }],
             lookup_key: LookupKey::FullRow,
         }
@@ -154,7 +424,7 @@ mod tests {
         let (tx, mut rx) = mpsc::channel::<TableEvent>(8);
         let schema = make_test_schema("test");
 
-        let progress = copy_table_stream(schema, Box::pin(stream), tx)
+        let progress = copy_table_stream(schema, Box::pin(stream), tx, /*checkpointer=*/ None)
             .await
             .expect("copy failed");
 
@@ -193,7 +463,7 @@ mod tests {
         let (tx, mut rx) = mpsc::channel::<TableEvent>(1);
         let schema = make_test_schema("empty");
 
-        let progress = copy_table_stream(schema, Box::pin(stream), tx)
+        let progress = copy_table_stream(schema, Box::pin(stream), tx, /*checkpointer=*/ None)
             .await
             .expect("copy failed");
         assert_eq!(progress.rows_copied, 0);
@@ -211,11 +481,127 @@ mod tests {
         let (tx, _) = mpsc::channel::<TableEvent>(1);
         let schema = make_test_schema("broken");
 
-        let err = copy_table_stream(schema, Box::pin(stream), tx)
+        let err = copy_table_stream(schema, Box::pin(stream), tx, /*checkpointer=*/ None)
             .await
             .expect_err("expected failure");
 
         // Just verify we got an error - the exact format may vary
         assert!(err.to_string().contains("Postgres source error"));
     }
+
+    //----------------------------------------------------------------------
+    // 4. Copy in key order – rows are committed periodically with the position
+    //----------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn test_checkpoints_copy_in_key_order() {
+        // Resume after key 10, which has been copied from the source snapshot at LSN 50.
+        let mut persisted_position = InitialCopyPosition::default();
+        persisted_position.advance(
+            /*last_key=*/ 10, /*snapshot_lsn=*/ 50, /*rows_copied=*/ 10,
+        );
+        let mut checkpointer = CopyCheckpointer::new(
+            /*key_index=*/ 0,
+            persisted_position.clone(),
+            /*checkpoint_rows=*/ 2,
+        );
+        checkpointer.restart(/*snapshot_lsn=*/ 100);
+
+        let rows = (11..=15).map(|id| {
+            Ok(crate::pg_replicate::conversions::table_row::TableRow {
+                values: vec![crate::pg_replicate::conversions::Cell::I32(id)],
+            })
+        });
+        let (tx, mut rx) = mpsc::channel::<TableEvent>(16);
+        let progress = copy_table_stream(
+            make_test_schema("ordered"),
+            Box::pin(stream::iter(rows)),
+            tx,
+            Some(checkpointer),
+        )
+        .await
+        .expect("copy failed");
+        assert_eq!(progress.rows_copied, 15);
+
+        let mut checkpoints = vec![];
+        while let Some(evt) = rx.recv().await {
+            if let TableEvent::CheckpointInitialCopy { position } = evt {
+                checkpoints.push(position);
+            }
+        }
+        let mut expected_position = persisted_position;
+        expected_position.advance(
+            /*last_key=*/ 12, /*snapshot_lsn=*/ 100, /*rows_copied=*/ 12,
+        );
+        assert_eq!(checkpoints[0], expected_position);
+        expected_position.advance(
+            /*last_key=*/ 14, /*snapshot_lsn=*/ 100, /*rows_copied=*/ 14,
+        );
+        assert_eq!(checkpoints[1], expected_position);
+        assert_eq!(checkpoints.len(), 2);
+        assert_eq!(expected_position.copied_ranges.len(), 2);
+    }
+
+    //----------------------------------------------------------------------
+    // 5. Progress reporter – publishes progress and persists checkpoint
+    //----------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn test_progress_reporter_checkpoint() {
+        let checkpoint_path =
+            std::env::temp_dir().join(format!("{}.initial_copy.json", uuid::Uuid::new_v4()));
+        let (progress_tx, progress_rx) = watch::channel(None);
+        let mut reporter = CopyProgressReporter::new(
+            progress_tx,
+            Some(checkpoint_path.clone()),
+            /*estimated_total_rows=*/ Some(10),
+        );
+
+        // Starting the copy persists an unfinished checkpoint.
+        reporter.start(/*start_lsn=*/ 100).await.unwrap();
+        let checkpoint = InitialCopyCheckpoint::load(&checkpoint_path)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(checkpoint.start_lsn, 100);
+        assert!(!checkpoint.finished);
+
+        // Finishing the copy reports final progress.
+        reporter
+            .update(/*rows_copied=*/ 10, /*bytes_copied=*/ 50)
+            .await
+            .unwrap();
+        reporter.finish().await.unwrap();
+        let progress = progress_rx.borrow().clone().unwrap();
+        assert_eq!(progress.rows_copied, 10);
+        assert_eq!(progress.bytes_copied, 50);
+        assert_eq!(progress.estimated_total_rows, Some(10));
+        assert_eq!(progress.eta_secs, Some(0));
+        assert!(progress.finished);
+        let checkpoint = InitialCopyCheckpoint::load(&checkpoint_path)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            checkpoint,
+            InitialCopyCheckpoint {
+                start_lsn: 100,
+                rows_copied: 10,
+                bytes_copied: 50,
+                finished: true,
+            }
+        );
+
+        // Deleting the checkpoint is idempotent.
+        InitialCopyCheckpoint::delete(&checkpoint_path)
+            .await
+            .unwrap();
+        InitialCopyCheckpoint::delete(&checkpoint_path)
+            .await
+            .unwrap();
+        assert!(InitialCopyCheckpoint::load(&checkpoint_path)
+            .await
+            .unwrap()
+            .is_none());
+    }
 }
@@ -1,108 +1,615 @@
-use crate::pg_replicate::util::PostgresTableRow;
+use crate::pg_replicate::dead_letter::{
+    ConversionErrorPolicy, DeadLetterRecord, DeadLetterStore, RowOperation,
+};
+use crate::pg_replicate::util::{build_alter_table_request, PostgresTableRow};
 use crate::pg_replicate::{
     conversions::{cdc_event::CdcEvent, table_row::TableRow},
     replication_state::ReplicationState,
-    table::{SrcTableId, TableSchema},
+    table::{RelationColumn, SrcTableId, TableSchema},
 };
+use moonlink::row::MoonlinkRow;
 use moonlink::TableEvent;
 use postgres_replication::protocol::Column as ReplicationColumn;
 use std::collections::{HashMap, HashSet};
-use std::convert::Infallible;
 use std::sync::Arc;
 use tokio::sync::mpsc::Sender;
-use tokio::sync::watch;
+use tokio::sync::{broadcast, watch};
 use tokio_postgres::types::PgLsn;
-use tracing::{debug, warn};
+use tracing::{debug, error, info, warn};
+
+/// Transaction id used to replay dead letters as a transaction stream, which doesn't collide with in-progress
+/// non-streaming transactions of the table.
+/// Postgres xids cycle through the full 32-bit space, but xids below `FirstNormalTransactionId` (3) are reserved and skipped
+/// on wraparound, so the bootstrap xid is never assigned to a streamed transaction.
+const DEAD_LETTER_REPLAY_XACT_ID: u32 = 1;
+
+/// Logical decoding message emitted by `pg_logical_emit_message` on the source database.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogicalMessage {
+    /// LSN of the message.
+    pub lsn: u64,
+    /// Commit LSN of the enclosing transaction for transactional messages, which are only delivered after commit.
+    /// Data committed at this LSN is visible to table scans requested at the same LSN.
+    pub commit_lsn: Option<u64>,
+    pub prefix: String,
+    pub content: Vec<u8>,
+}
 
 #[derive(Default)]
 struct TransactionState {
     final_lsn: u64,
     touched_tables: HashSet<SrcTableId>,
+    /// Transactional logical messages, which are delivered on commit and discarded on abort.
+    messages: Vec<LogicalMessage>,
+    /// Dead letters whose lookup key is changed by the transaction, which are dropped on commit.
+    superseded_dead_letters: Vec<DeadLetterRecord>,
 }
 
-#[derive(Eq, PartialEq)]
-struct ColumnInfo {
-    name: String,
-    typ: u32,
-    modifier: i32,
+/// A non-streaming transaction which was interrupted by a disconnect, it will be replayed from its beginning after reconnect.
+struct InterruptedTransaction {
+    /// Final LSN of the interrupted transaction.
+    final_lsn: u64,
+    /// Number of row events already delivered to tables before disconnect.
+    delivered_events: usize,
 }
+
 pub struct Sink {
     event_senders: HashMap<SrcTableId, Sender<TableEvent>>,
     commit_lsn_txs: HashMap<SrcTableId, watch::Sender<u64>>,
     streaming_transactions_state: HashMap<u32, TransactionState>,
     transaction_state: TransactionState,
     replication_state: Arc<ReplicationState>,
-    relation_cache: HashMap<SrcTableId, Vec<ColumnInfo>>,
+    /// Latest schema of each table, used to detect and diff schema changes from relation messages.
+    relation_cache: HashMap<SrcTableId, TableSchema>,
+    /// Whether a non-streaming transaction is in progress.
+    in_transaction: bool,
+    /// Number of row events delivered for the current non-streaming transaction.
+    delivered_events: usize,
+    /// Number of replayed row events to skip for the current non-streaming transaction.
+    events_to_skip: usize,
+    /// Transaction interrupted by the last disconnect.
+    interrupted_transaction: Option<InterruptedTransaction>,
+    /// Sender for logical decoding messages, shared by all subscribers of the source.
+    logical_message_tx: broadcast::Sender<LogicalMessage>,
+    /// Stream position of the last delivered logical message, used to skip messages replayed after reconnect.
+    last_logical_message_position: u64,
+    /// Two-phase transaction being decoded before its PREPARE, as (xid, GID).
+    /// Its changes are sent as a transaction stream keyed by xid, so they're staged until COMMIT PREPARED.
+    preparing_transaction: Option<(u32, String)>,
+    /// Tables which have already prepared the transaction being decoded, if it's replayed after reconnect or restart.
+    /// Its changes to these tables are skipped.
+    replayed_prepared_tables: Option<HashSet<SrcTableId>>,
+    /// Prepared transactions, keyed by GID.
+    /// Tables persist prepared transactions, and rebuild them at restart (see `add_table`), so the confirmed flush LSN
+    /// advances past PREPARE once all touched tables have persisted it (see `has_pending_transactions`). Transactional
+    /// messages and superseded dead letters are only kept in memory, so transactions carrying them are replayed instead.
+    prepared_transactions: HashMap<String, TransactionState>,
+    /// GIDs of prepared transactions persisted by each table.
+    prepared_gids_rxs: HashMap<SrcTableId, watch::Receiver<HashSet<String>>>,
+    /// Policy for rows which fail conversion, keyed by table.
+    conversion_error_policies: HashMap<SrcTableId, ConversionErrorPolicy>,
+    /// Dead letter file for each table.
+    dead_letter_stores: HashMap<SrcTableId, DeadLetterStore>,
+    /// Dead letters of each table with their lookup keys under the current schema, used to find dead letters changed
+    /// by later rows. Loaded on first change of the table, and reset whenever the schema or the dead letter file changes.
+    dead_letter_keys: HashMap<SrcTableId, Vec<(MoonlinkRow, DeadLetterRecord)>>,
 }
 
 impl Sink {
-    pub fn new(replication_state: Arc<ReplicationState>) -> Self {
+    pub fn new(
+        replication_state: Arc<ReplicationState>,
+        logical_message_tx: broadcast::Sender<LogicalMessage>,
+    ) -> Self {
         Self {
             event_senders: HashMap::new(),
             commit_lsn_txs: HashMap::new(),
             streaming_transactions_state: HashMap::new(),
-            transaction_state: TransactionState {
-                final_lsn: 0,
-                touched_tables: HashSet::new(),
-            },
+            transaction_state: TransactionState::default(),
             replication_state,
             relation_cache: HashMap::new(),
+            in_transaction: false,
+            delivered_events: 0,
+            events_to_skip: 0,
+            interrupted_transaction: None,
+            logical_message_tx,
+            last_logical_message_position: 0,
+            preparing_transaction: None,
+            replayed_prepared_tables: None,
+            prepared_transactions: HashMap::new(),
+            prepared_gids_rxs: HashMap::new(),
+            conversion_error_policies: HashMap::new(),
+            dead_letter_stores: HashMap::new(),
+            dead_letter_keys: HashMap::new(),
         }
     }
 }
 
-pub struct SchemaChangeRequest(pub SrcTableId);
+/// Schema change of a table, with columns as of the relation message which announces it.
+pub struct SchemaChangeRequest {
+    pub src_table_id: SrcTableId,
+    pub relation_columns: Vec<RelationColumn>,
+}
 
 impl Sink {
+    /// Add a table to replicate, prepared transactions persisted by the table are tracked as prepared.
+    #[allow(clippy::too_many_arguments)]
     pub fn add_table(
         &mut self,
         src_table_id: SrcTableId,
         event_sender: Sender<TableEvent>,
         commit_lsn_tx: watch::Sender<u64>,
         table_schema: &TableSchema,
+        conversion_error_policy: ConversionErrorPolicy,
+        dead_letter_store: DeadLetterStore,
+        prepared_gids_rx: watch::Receiver<HashSet<String>>,
     ) {
         self.event_senders.insert(src_table_id, event_sender);
         self.commit_lsn_txs.insert(src_table_id, commit_lsn_tx);
-        let columns = table_schema
-            .column_schemas
-            .iter()
-            .map(|c| ColumnInfo {
-                name: c.name.clone(),
-                typ: c.typ.oid(),
-                modifier: c.modifier,
-            })
-            .collect();
-        self.relation_cache.insert(src_table_id, columns);
+        self.relation_cache
+            .insert(src_table_id, table_schema.clone());
+        self.conversion_error_policies
+            .insert(src_table_id, conversion_error_policy);
+        self.dead_letter_stores
+            .insert(src_table_id, dead_letter_store);
+        self.dead_letter_keys.remove(&src_table_id);
+        for gid in prepared_gids_rx.borrow().iter() {
+            self.prepared_transactions
+                .entry(gid.clone())
+                .or_default()
+                .touched_tables
+                .insert(src_table_id);
+        }
+        self.prepared_gids_rxs
+            .insert(src_table_id, prepared_gids_rx);
     }
+
+    /// Whether the columns in a relation message match the cached table schema, by position.
+    fn relation_matches_schema(columns: &[ReplicationColumn], table_schema: &TableSchema) -> bool {
+        columns.len() == table_schema.column_schemas.len()
+            && columns.iter().zip(table_schema.column_schemas.iter()).all(
+                |(column, column_schema)| {
+                    column.name().ok() == Some(column_schema.name.as_str())
+                        && column.type_id() as u32 == column_schema.typ.oid()
+                        && column.type_modifier() == column_schema.modifier
+                },
+            )
+    }
+
     pub fn drop_table(&mut self, src_table_id: SrcTableId) {
         self.event_senders.remove(&src_table_id).unwrap();
         self.commit_lsn_txs.remove(&src_table_id).unwrap();
+        self.conversion_error_policies.remove(&src_table_id);
+        self.dead_letter_stores.remove(&src_table_id);
+        self.dead_letter_keys.remove(&src_table_id);
+        self.prepared_gids_rxs.remove(&src_table_id);
     }
 
-    pub async fn alter_table(&mut self, src_table_id: SrcTableId, table_schema: &TableSchema) {
-        let new_columns: Vec<ColumnInfo> = table_schema
-            .column_schemas
-            .iter()
-            .map(|c| ColumnInfo {
-                name: c.name.clone(),
-                typ: c.typ.oid(),
-                modifier: c.modifier,
+    fn get_conversion_error_policy(&self, src_table_id: SrcTableId) -> ConversionErrorPolicy {
+        self.conversion_error_policies
+            .get(&src_table_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn set_conversion_error_policy(
+        &mut self,
+        src_table_id: SrcTableId,
+        policy: ConversionErrorPolicy,
+    ) {
+        if let Some(current_policy) = self.conversion_error_policies.get_mut(&src_table_id) {
+            *current_policy = policy;
+        }
+    }
+
+    /// Replay dead letters of the given table with its current schema, and return the number of replayed rows.
+    /// Rows which still fail conversion are kept in the dead letter file.
+    ///
+    /// Replayed rows are sent as a transaction stream, committed at the latest commit LSN of the table, so they
+    /// neither wait for nor expose an in-progress transaction.
+    ///
+    /// Dead letters whose row has been changed since are dropped when the change commits (see
+    /// `supersede_dead_letters`), so replay never overwrites a newer row.
+    pub async fn replay_dead_letters(&mut self, src_table_id: SrcTableId) -> crate::Result<usize> {
+        let (Some(event_sender), Some(store), Some(table_schema)) = (
+            self.event_senders.get(&src_table_id).cloned(),
+            self.dead_letter_stores.get(&src_table_id),
+            self.relation_cache.get(&src_table_id),
+        ) else {
+            return Ok(0);
+        };
+        let xact_id = Some(DEAD_LETTER_REPLAY_XACT_ID);
+        let mut failed_records: Vec<DeadLetterRecord> = vec![];
+        let mut events = vec![];
+        for mut record in store.load().await? {
+            let (old_row, new_row) = match record.try_convert(&table_schema.column_schemas) {
+                Ok(rows) => rows,
+                Err(e) => {
+                    record.error = e.to_string();
+                    failed_records.push(record);
+                    continue;
+                }
+            };
+            let lsn = record.lsn;
+            match record.operation {
+                RowOperation::Insert | RowOperation::Update => {
+                    let Some(new_row) = new_row else {
+                        continue;
+                    };
+                    if record.operation == RowOperation::Update {
+                        // Without old row, identity columns are unchanged, so the new row identifies the deleted row.
+                        let old_row = old_row.unwrap_or_else(|| new_row.clone());
+                        events.push(TableEvent::Delete {
+                            row: PostgresTableRow(old_row).into(),
+                            lsn,
+                            xact_id,
+                        });
+                    }
+                    events.push(TableEvent::Append {
+                        row: PostgresTableRow(new_row).into(),
+                        lsn,
+                        xact_id,
+                        is_copied: false,
+                    });
+                }
+                RowOperation::Delete => {
+                    if let Some(old_row) = old_row {
+                        events.push(TableEvent::Delete {
+                            row: PostgresTableRow(old_row).into(),
+                            lsn,
+                            xact_id,
+                        });
+                    }
+                }
+            }
+        }
+        let replayed_rows = events.len();
+        if !events.is_empty() {
+            let commit_lsn = self
+                .commit_lsn_txs
+                .get(&src_table_id)
+                .map(|commit_lsn_tx| *commit_lsn_tx.borrow())
+                .filter(|commit_lsn| *commit_lsn != 0)
+                .unwrap_or_else(|| self.replication_state.now());
+            events.push(TableEvent::Commit {
+                lsn: commit_lsn,
+                xact_id,
+            });
+            for event in events {
+                if let Err(e) = event_sender.send(event).await {
+                    warn!(error = ?e, "failed to send dead letter replay event");
+                }
+            }
+        }
+        store.rewrite(&failed_records).await?;
+        self.dead_letter_keys.remove(&src_table_id);
+        info!(
+            src_table_id,
+            replayed_rows,
+            failed_rows = failed_records.len(),
+            "replayed dead letters"
+        );
+        Ok(replayed_rows)
+    }
+
+    /// Load dead letters of the given table with their lookup keys, if not loaded yet.
+    async fn load_dead_letter_keys(&mut self, table_id: SrcTableId) -> crate::Result<()> {
+        if self.dead_letter_keys.contains_key(&table_id) {
+            return Ok(());
+        }
+        let (Some(store), Some(table_schema)) = (
+            self.dead_letter_stores.get(&table_id),
+            self.relation_cache.get(&table_id),
+        ) else {
+            return Ok(());
+        };
+        let mut dead_letter_keys = vec![];
+        for record in store.load().await? {
+            for key in record.keys(table_schema) {
+                dead_letter_keys.push((key, record.clone()));
+            }
+        }
+        self.dead_letter_keys.insert(table_id, dead_letter_keys);
+        Ok(())
+    }
+
+    /// Record dead letters whose lookup key matches any of the given rows, which are changed by the current
+    /// transaction. They're dropped when the transaction commits, since replaying them would overwrite the newer row.
+    async fn supersede_dead_letters(
+        &mut self,
+        table_id: SrcTableId,
+        rows: &[&TableRow],
+        xact_id: Option<u32>,
+    ) -> crate::Result<()> {
+        self.load_dead_letter_keys(table_id).await?;
+        let Some(dead_letter_keys) = self
+            .dead_letter_keys
+            .get(&table_id)
+            .filter(|dead_letter_keys| !dead_letter_keys.is_empty())
+        else {
+            return Ok(());
+        };
+        let Some(positions) = self
+            .relation_cache
+            .get(&table_id)
+            .and_then(|table_schema| table_schema.lookup_key_positions())
+        else {
+            return Ok(());
+        };
+        let mut superseded_dead_letters = vec![];
+        for row in rows {
+            let key: MoonlinkRow = PostgresTableRow(TableRow {
+                values: positions
+                    .iter()
+                    .map(|position| row.values[*position].clone())
+                    .collect(),
             })
-            .collect();
-        let old_columns = self.relation_cache.get(&src_table_id).unwrap();
-        let columns_to_drop = old_columns
+            .into();
+            for (dead_letter_key, record) in dead_letter_keys {
+                if *dead_letter_key == key && !superseded_dead_letters.contains(record) {
+                    superseded_dead_letters.push(record.clone());
+                }
+            }
+        }
+        let txn_state = match xact_id {
+            Some(xid) => self.streaming_transactions_state.entry(xid).or_default(),
+            None => &mut self.transaction_state,
+        };
+        txn_state
+            .superseded_dead_letters
+            .extend(superseded_dead_letters);
+        Ok(())
+    }
+
+    /// Drop dead letters superseded by a transaction before it's committed.
+    /// If it fails, the transaction isn't committed and is replayed after reconnect.
+    async fn drop_superseded_dead_letters(
+        &mut self,
+        superseded_dead_letters: Vec<DeadLetterRecord>,
+    ) -> crate::Result<()> {
+        let table_ids: HashSet<SrcTableId> = superseded_dead_letters
             .iter()
-            .filter(|c| !new_columns.contains(c))
-            .map(|c| c.name.clone())
+            .map(|record| record.src_table_id)
             .collect();
-        if let Some(event_sender) = self.event_senders.get_mut(&src_table_id) {
-            event_sender
-                .send(TableEvent::AlterTable { columns_to_drop })
-                .await
-                .unwrap();
+        for table_id in table_ids {
+            let Some(store) = self.dead_letter_stores.get(&table_id) else {
+                continue;
+            };
+            let records = store.load().await?;
+            let num_records = records.len();
+            let remaining_records: Vec<DeadLetterRecord> = records
+                .into_iter()
+                .filter(|record| !superseded_dead_letters.contains(record))
+                .collect();
+            store.rewrite(&remaining_records).await?;
+            self.dead_letter_keys.remove(&table_id);
+            info!(
+                src_table_id = table_id,
+                dropped_rows = num_records - remaining_records.len(),
+                "dropped dead letters whose rows have been changed since"
+            );
         }
-        self.relation_cache.insert(src_table_id, new_columns);
+        Ok(())
     }
+
+    /// Whether any received change could be lost at restart, i.e. a transaction is in progress, or prepared but not
+    /// persisted by all tables it touches.
+    fn has_pending_transactions(&self) -> bool {
+        self.in_transaction
+            || self.preparing_transaction.is_some()
+            || !self.streaming_transactions_state.is_empty()
+            || self
+                .prepared_transactions
+                .iter()
+                .any(|(gid, txn_state)| !self.is_prepared_transaction_persisted(gid, txn_state))
+    }
+
+    /// Whether the prepared transaction is recovered after restart, so the source no longer needs to replay it.
+    fn is_prepared_transaction_persisted(&self, gid: &str, txn_state: &TransactionState) -> bool {
+        txn_state.messages.is_empty()
+            && txn_state.superseded_dead_letters.is_empty()
+            && txn_state.touched_tables.iter().all(|table_id| {
+                self.prepared_gids_rxs
+                    .get(table_id)
+                    .is_none_or(|prepared_gids_rx| prepared_gids_rx.borrow().contains(gid))
+            })
+    }
+
+    /// Get the LSN which could be confirmed to the source for the given table, given its iceberg flush LSN.
+    /// A table with all commits flushed doesn't hold back the replication slot, so the current replication position
+    /// (which advances with keepalive messages even if no tracked table changes) could be confirmed instead.
+    pub fn get_confirmable_lsn(&self, src_table_id: SrcTableId, flush_lsn: u64) -> u64 {
+        // Nothing has been flushed yet, for example during initial copy.
+        if flush_lsn == 0 || self.has_pending_transactions() {
+            return flush_lsn;
+        }
+        let commit_lsn = self
+            .commit_lsn_txs
+            .get(&src_table_id)
+            .map(|commit_lsn_tx| *commit_lsn_tx.borrow())
+            .unwrap_or(0);
+        if commit_lsn > flush_lsn {
+            return flush_lsn;
+        }
+        flush_lsn.max(self.replication_state.now())
+    }
+
+    /// Apply the schema change of a table.
+    /// The table validates the change, and fails if it can't be applied without losing data (e.g. dropping an identity
+    /// column, or changing lookup keys of existing rows); the table then has to be resynced.
+    pub async fn alter_table(&mut self, src_table_id: SrcTableId, table_schema: &TableSchema) {
+        let old_table_schema = self.relation_cache.get(&src_table_id).unwrap();
+        let alter_table_request = build_alter_table_request(old_table_schema, table_schema);
+        if !alter_table_request.is_empty() {
+            if let Some(event_sender) = self.event_senders.get_mut(&src_table_id) {
+                if let Err(e) = event_sender
+                    .send(TableEvent::AlterTable {
+                        alter_table_request,
+                    })
+                    .await
+                {
+                    warn!(error = ?e, "failed to send alter table event");
+                }
+            }
+        }
+        self.relation_cache
+            .insert(src_table_id, table_schema.clone());
+        // Lookup keys of dead letters are converted with the new schema.
+        self.dead_letter_keys.remove(&src_table_id);
+    }
+
+    /// Reset transaction states after replication connection is lost, events will be replayed from the slot's confirmed flush LSN after reconnect.
+    ///
+    /// - Streaming transactions are aborted, since they're replayed in full.
+    /// - For a non-streaming transaction, rows already delivered can't be revoked, so record how many to skip when it's replayed.
+    pub async fn handle_disconnect(&mut self) {
+        for (xact_id, txn_state) in self.streaming_transactions_state.drain() {
+            for table_id in &txn_state.touched_tables {
+                if let Some(event_sender) = self.event_senders.get(table_id) {
+                    if let Err(e) = event_sender.send(TableEvent::StreamAbort { xact_id }).await {
+                        warn!(error = ?e, "failed to send stream abort event");
+                    }
+                }
+            }
+        }
+        if self.in_transaction {
+            self.interrupted_transaction = Some(InterruptedTransaction {
+                final_lsn: self.transaction_state.final_lsn,
+                delivered_events: self.delivered_events,
+            });
+        }
+        self.in_transaction = false;
+        self.delivered_events = 0;
+        self.events_to_skip = 0;
+        self.transaction_state.touched_tables.clear();
+        self.transaction_state.messages.clear();
+        self.transaction_state.superseded_dead_letters.clear();
+        // Transaction stream for the unprepared transaction has been aborted above, prepared ones are kept.
+        self.preparing_transaction = None;
+        self.replayed_prepared_tables = None;
+    }
+
+    /// Attach xid of the transaction being prepared to its row events.
+    fn attach_prepared_xact_id(&self, event: CdcEvent) -> CdcEvent {
+        let Some((prepared_xact_id, _)) = &self.preparing_transaction else {
+            return event;
+        };
+        let xact_id = Some(*prepared_xact_id);
+        match event {
+            CdcEvent::Insert((table_id, row, None)) => CdcEvent::Insert((table_id, row, xact_id)),
+            CdcEvent::Update((table_id, old_row, new_row, None)) => {
+                CdcEvent::Update((table_id, old_row, new_row, xact_id))
+            }
+            CdcEvent::Delete((table_id, row, None)) => CdcEvent::Delete((table_id, row, xact_id)),
+            CdcEvent::ConversionFailure(record) if record.xact_id.is_none() => {
+                CdcEvent::ConversionFailure(DeadLetterRecord { xact_id, ..record })
+            }
+            event => event,
+        }
+    }
+
+    /// Mark the transaction stream with the given xid as prepared, and notify all touched tables.
+    /// For a transaction replayed after reconnect or restart, tables which have already prepared it discard its
+    /// replayed changes, so only the other tables are notified.
+    async fn prepare_transaction(&mut self, xact_id: u32, gid: String) {
+        let txn_state = self
+            .streaming_transactions_state
+            .remove(&xact_id)
+            .unwrap_or_default();
+        let prepared_state = self.prepared_transactions.entry(gid.clone()).or_default();
+        let new_tables: Vec<SrcTableId> = txn_state
+            .touched_tables
+            .difference(&prepared_state.touched_tables)
+            .cloned()
+            .collect();
+        for table_id in &new_tables {
+            if let Some(event_sender) = self.event_senders.get(table_id) {
+                if let Err(e) = event_sender
+                    .send(TableEvent::PrepareTransaction {
+                        xact_id,
+                        gid: gid.clone(),
+                    })
+                    .await
+                {
+                    warn!(error = ?e, "failed to send prepare transaction event");
+                }
+            }
+        }
+        prepared_state.touched_tables.extend(new_tables);
+        // Kept from before reconnect if any, otherwise they're lost at restart and collected again from the replay.
+        if prepared_state.messages.is_empty() {
+            prepared_state.messages = txn_state.messages;
+        }
+        if prepared_state.superseded_dead_letters.is_empty() {
+            prepared_state.superseded_dead_letters = txn_state.superseded_dead_letters;
+        }
+    }
+
+    /// Send the event for a prepared transaction to all tables it touches.
+    /// If the transaction is unknown, e.g. prepared before the slot enabled two-phase decoding, send to all tables; tables which don't track the GID ignore it.
+    async fn send_prepared_transaction_event(
+        &self,
+        touched_tables: Option<&HashSet<SrcTableId>>,
+        event: TableEvent,
+    ) {
+        let table_ids: Vec<SrcTableId> = match touched_tables {
+            Some(touched_tables) => touched_tables.iter().cloned().collect(),
+            None => self.event_senders.keys().cloned().collect(),
+        };
+        for table_id in table_ids {
+            if let Some(event_sender) = self.event_senders.get(&table_id) {
+                if let Err(e) = event_sender.send(event.clone()).await {
+                    warn!(error = ?e, "failed to send prepared transaction event");
+                }
+            }
+        }
+    }
+
+    /// Deliver logical messages to subscribers, `position` is where they're located in the replication stream.
+    /// Messages at or before the last delivered position have been delivered before reconnect, and are skipped.
+    fn deliver_logical_messages(&mut self, messages: Vec<LogicalMessage>, position: u64) {
+        if messages.is_empty() {
+            return;
+        }
+        if position <= self.last_logical_message_position {
+            debug!(position, "skip logical messages replayed after reconnect");
+            return;
+        }
+        self.last_logical_message_position = position;
+        for message in messages {
+            // Error only indicates there's no subscriber.
+            let _ = self.logical_message_tx.send(message);
+        }
+    }
+
+    /// Whether the transaction with the given final LSN has already been committed to the table, which happens when events are replayed after reconnect.
+    fn is_committed(&self, table_id: SrcTableId, final_lsn: u64) -> bool {
+        self.commit_lsn_txs
+            .get(&table_id)
+            .is_some_and(|commit_lsn_tx| final_lsn < *commit_lsn_tx.borrow())
+    }
+
+    /// Whether the current row event should be skipped, because it has been delivered before disconnect.
+    fn should_skip_replayed_event(&mut self, table_id: SrcTableId, xact_id: Option<u32>) -> bool {
+        if self
+            .replayed_prepared_tables
+            .as_ref()
+            .is_some_and(|tables| tables.contains(&table_id))
+        {
+            return true;
+        }
+        if xact_id.is_some() {
+            return false;
+        }
+        if self.events_to_skip > 0 {
+            self.events_to_skip -= 1;
+            self.delivered_events += 1;
+            self.transaction_state.touched_tables.insert(table_id);
+            return true;
+        }
+        self.delivered_events += 1;
+        self.is_committed(table_id, self.transaction_state.final_lsn)
+    }
+
     /// Get final lsn for the current transaction.
     fn get_final_lsn(&mut self, table_id: SrcTableId, xact_id: Option<u32>) -> u64 {
         if let Some(xid) = xact_id {
@@ -124,17 +631,34 @@ impl Sink {
     pub async fn process_cdc_event(
         &mut self,
         event: CdcEvent,
-    ) -> Result<Option<SchemaChangeRequest>, Infallible> {
+    ) -> crate::Result<Option<SchemaChangeRequest>> {
+        let event = self.attach_prepared_xact_id(event);
         match event {
             CdcEvent::Begin(begin_body) => {
                 debug!(final_lsn = begin_body.final_lsn(), "begin transaction");
-                self.transaction_state.final_lsn = begin_body.final_lsn();
+                let final_lsn = begin_body.final_lsn();
+                self.transaction_state.final_lsn = final_lsn;
+                self.in_transaction = true;
+                self.delivered_events = 0;
+                self.events_to_skip = 0;
+                if let Some(interrupted) = &self.interrupted_transaction {
+                    if interrupted.final_lsn == final_lsn {
+                        self.events_to_skip = interrupted.delivered_events;
+                    }
+                    if interrupted.final_lsn <= final_lsn {
+                        self.interrupted_transaction = None;
+                    }
+                }
             }
             CdcEvent::StreamStart(stream_start_body) => {
                 debug!(stream_id = stream_start_body.xid(), "stream start");
             }
             CdcEvent::Commit(commit_body) => {
                 debug!(end_lsn = commit_body.end_lsn(), "commit transaction");
+                let superseded_dead_letters =
+                    std::mem::take(&mut self.transaction_state.superseded_dead_letters);
+                self.drop_superseded_dead_letters(superseded_dead_letters)
+                    .await?;
                 for table_id in &self.transaction_state.touched_tables {
                     let event_sender = self.event_senders.get(table_id).cloned();
                     if let Some(commit_lsn_tx) = self.commit_lsn_txs.get(table_id).cloned() {
@@ -155,6 +679,15 @@ impl Sink {
                     }
                 }
                 self.transaction_state.touched_tables.clear();
+                let messages = std::mem::take(&mut self.transaction_state.messages)
+                    .into_iter()
+                    .map(|message| LogicalMessage {
+                        commit_lsn: Some(commit_body.end_lsn()),
+                        ..message
+                    })
+                    .collect();
+                self.deliver_logical_messages(messages, commit_body.end_lsn());
+                self.in_transaction = false;
                 self.replication_state
                     .mark(PgLsn::from(commit_body.end_lsn()));
             }
@@ -165,9 +698,25 @@ impl Sink {
                     end_lsn = stream_commit_body.end_lsn(),
                     "stream commit"
                 );
-                if let Some(tables_in_txn) = self.streaming_transactions_state.get(&xact_id) {
+                if let Some(mut tables_in_txn) = self.streaming_transactions_state.remove(&xact_id)
+                {
+                    self.drop_superseded_dead_letters(std::mem::take(
+                        &mut tables_in_txn.superseded_dead_letters,
+                    ))
+                    .await?;
                     for table_id in &tables_in_txn.touched_tables {
                         let event_sender = self.event_senders.get(table_id).cloned();
+                        // Streaming transaction replayed after reconnect, which has already been committed.
+                        if self.is_committed(*table_id, stream_commit_body.commit_lsn()) {
+                            if let Some(event_sender) = event_sender {
+                                if let Err(e) =
+                                    event_sender.send(TableEvent::StreamAbort { xact_id }).await
+                                {
+                                    warn!(error = ?e, "failed to send stream abort event");
+                                }
+                            }
+                            continue;
+                        }
                         if let Some(commit_lsn_tx) = self.commit_lsn_txs.get(table_id).cloned() {
                             if let Err(e) = commit_lsn_tx.send(stream_commit_body.end_lsn()) {
                                 warn!(error = ?e, "failed to send stream commit lsn");
@@ -185,12 +734,25 @@ impl Sink {
                             }
                         }
                     }
-                    self.streaming_transactions_state.remove(&xact_id);
+                    let messages = tables_in_txn
+                        .messages
+                        .into_iter()
+                        .map(|message| LogicalMessage {
+                            commit_lsn: Some(stream_commit_body.end_lsn()),
+                            ..message
+                        })
+                        .collect();
+                    self.deliver_logical_messages(messages, stream_commit_body.end_lsn());
                 }
                 self.replication_state
                     .mark(PgLsn::from(stream_commit_body.end_lsn()));
             }
             CdcEvent::Insert((table_id, table_row, xact_id)) => {
+                self.supersede_dead_letters(table_id, &[&table_row], xact_id)
+                    .await?;
+                if self.should_skip_replayed_event(table_id, xact_id) {
+                    return Ok(None);
+                }
                 let final_lsn = self.get_final_lsn(table_id, xact_id);
                 let event_sender = self.event_senders.get(&table_id).cloned();
                 if let Some(event_sender) = event_sender {
@@ -217,12 +779,21 @@ impl Sink {
                 }
             }
             CdcEvent::Update((table_id, old_table_row, new_table_row, xact_id)) => {
+                let changed_rows: Vec<&TableRow> =
+                    old_table_row.iter().chain([&new_table_row]).collect();
+                self.supersede_dead_letters(table_id, &changed_rows, xact_id)
+                    .await?;
+                if self.should_skip_replayed_event(table_id, xact_id) {
+                    return Ok(None);
+                }
                 let final_lsn = self.get_final_lsn(table_id, xact_id);
                 let event_sender = self.event_senders.get(&table_id).cloned();
                 if let Some(event_sender) = event_sender {
+                    // Without old row, identity columns are unchanged, so the new row identifies the deleted row.
+                    let old_table_row = old_table_row.unwrap_or_else(|| new_table_row.clone());
                     if let Err(e) = event_sender
                         .send(TableEvent::Delete {
-                            row: PostgresTableRow(old_table_row.unwrap()).into(),
+                            row: PostgresTableRow(old_table_row).into(),
                             lsn: final_lsn,
                             xact_id,
                         })
@@ -244,6 +815,11 @@ impl Sink {
                 }
             }
             CdcEvent::Delete((table_id, table_row, xact_id)) => {
+                self.supersede_dead_letters(table_id, &[&table_row], xact_id)
+                    .await?;
+                if self.should_skip_replayed_event(table_id, xact_id) {
+                    return Ok(None);
+                }
                 let final_lsn = self.get_final_lsn(table_id, xact_id);
                 let event_sender = self.event_senders.get(&table_id).cloned();
                 if let Some(event_sender) = event_sender {
@@ -259,6 +835,72 @@ impl Sink {
                     }
                 }
             }
+            CdcEvent::ConversionFailure(record) => {
+                let table_id = record.src_table_id;
+                if self.should_skip_replayed_event(table_id, record.xact_id) {
+                    return Ok(None);
+                }
+                self.get_final_lsn(table_id, record.xact_id);
+                match self.get_conversion_error_policy(table_id) {
+                    // Replaying the row after reconnect fails again, so the table fails instead, which stops its
+                    // replication and no longer holds back the confirmed flush LSN.
+                    ConversionErrorPolicy::Fail => {
+                        error!(
+                            src_table_id = table_id,
+                            lsn = record.lsn,
+                            error = %record.error,
+                            "row conversion failed, failing the table"
+                        );
+                        if let Some(event_sender) = self.event_senders.get(&table_id) {
+                            if let Err(e) = event_sender
+                                .send(TableEvent::FailTable {
+                                    error: format!(
+                                        "row conversion failed at LSN {}: {}",
+                                        record.lsn, record.error
+                                    ),
+                                })
+                                .await
+                            {
+                                warn!(error = ?e, "failed to send fail table event");
+                            }
+                        }
+                    }
+                    ConversionErrorPolicy::Skip => {
+                        warn!(
+                            src_table_id = table_id,
+                            lsn = record.lsn,
+                            error = %record.error,
+                            "skip row which fails conversion"
+                        );
+                    }
+                    ConversionErrorPolicy::DeadLetter => {
+                        if let Some(store) = self.dead_letter_stores.get(&table_id) {
+                            warn!(
+                                src_table_id = table_id,
+                                lsn = record.lsn,
+                                error = %record.error,
+                                path = ?store.path(),
+                                "write row which fails conversion to dead letter file"
+                            );
+                            if let Err(e) = store.append(&record).await {
+                                // The row is delivered again when the transaction is replayed after reconnect.
+                                if record.xact_id.is_none() {
+                                    self.delivered_events -= 1;
+                                }
+                                return Err(e);
+                            }
+                            if let (Some(dead_letter_keys), Some(table_schema)) = (
+                                self.dead_letter_keys.get_mut(&table_id),
+                                self.relation_cache.get(&table_id),
+                            ) {
+                                for key in record.keys(table_schema) {
+                                    dead_letter_keys.push((key, record.clone()));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
             CdcEvent::Relation(relation_body) => {
                 debug!(
                     relation_id = relation_body.rel_id(),
@@ -266,13 +908,134 @@ impl Sink {
                     "Relation"
                 );
                 let src_table_id = relation_body.rel_id();
-                let cache_entry = self.relation_cache.get_mut(&src_table_id);
+                let cache_entry = self.relation_cache.get(&src_table_id);
                 if let Some(cache_entry) = cache_entry {
-                    if cache_entry.len() != relation_body.columns().len() {
-                        return Ok(Some(SchemaChangeRequest(src_table_id)));
+                    if !Self::relation_matches_schema(relation_body.columns(), cache_entry) {
+                        let relation_columns = relation_body
+                            .columns()
+                            .iter()
+                            .map(|column| {
+                                Ok(RelationColumn {
+                                    name: column.name()?.to_string(),
+                                    type_oid: column.type_id() as u32,
+                                    modifier: column.type_modifier(),
+                                })
+                            })
+                            .collect::<std::io::Result<Vec<_>>>()?;
+                        return Ok(Some(SchemaChangeRequest {
+                            src_table_id,
+                            relation_columns,
+                        }));
                     }
                 }
             }
+            CdcEvent::BeginPrepare(begin_prepare_body) => {
+                let xact_id = begin_prepare_body.xid();
+                let gid = begin_prepare_body.gid().to_string();
+                debug!(xact_id, gid, "begin prepare");
+                // Transaction has been prepared before reconnect or restart, and replayed since the confirmed flush LSN
+                // is before its PREPARE, e.g. it hasn't been persisted by all touched tables.
+                self.replayed_prepared_tables = self
+                    .prepared_transactions
+                    .get(&gid)
+                    .map(|txn_state| txn_state.touched_tables.clone());
+                self.preparing_transaction = Some((xact_id, gid));
+            }
+            CdcEvent::Prepare(prepare_body) => {
+                debug!(
+                    xact_id = prepare_body.xid(),
+                    end_lsn = prepare_body.end_lsn(),
+                    "prepare transaction"
+                );
+                self.replayed_prepared_tables = None;
+                if let Some((xact_id, gid)) = self.preparing_transaction.take() {
+                    self.prepare_transaction(xact_id, gid).await;
+                }
+            }
+            CdcEvent::StreamPrepare(stream_prepare_body) => {
+                let xact_id = stream_prepare_body.xid();
+                let gid = stream_prepare_body.gid().to_string();
+                debug!(xact_id, gid, "stream prepare");
+                self.prepare_transaction(xact_id, gid).await;
+            }
+            CdcEvent::CommitPrepared(commit_prepared_body) => {
+                let gid = commit_prepared_body.gid().to_string();
+                let end_lsn = commit_prepared_body.end_lsn();
+                debug!(gid, end_lsn, "commit prepared");
+                let mut txn_state = self.prepared_transactions.remove(&gid);
+                if let Some(txn_state) = &mut txn_state {
+                    self.drop_superseded_dead_letters(std::mem::take(
+                        &mut txn_state.superseded_dead_letters,
+                    ))
+                    .await?;
+                    for table_id in &txn_state.touched_tables {
+                        if let Some(commit_lsn_tx) = self.commit_lsn_txs.get(table_id) {
+                            if let Err(e) = commit_lsn_tx.send(end_lsn) {
+                                warn!(error = ?e, "failed to send commit prepared lsn");
+                            }
+                        }
+                    }
+                }
+                self.send_prepared_transaction_event(
+                    txn_state
+                        .as_ref()
+                        .map(|txn_state| &txn_state.touched_tables),
+                    TableEvent::CommitPrepared { gid, lsn: end_lsn },
+                )
+                .await;
+                if let Some(txn_state) = txn_state {
+                    let messages = txn_state
+                        .messages
+                        .into_iter()
+                        .map(|message| LogicalMessage {
+                            commit_lsn: Some(end_lsn),
+                            ..message
+                        })
+                        .collect();
+                    self.deliver_logical_messages(messages, end_lsn);
+                }
+                self.replication_state.mark(PgLsn::from(end_lsn));
+            }
+            CdcEvent::RollbackPrepared(rollback_prepared_body) => {
+                let gid = rollback_prepared_body.gid().to_string();
+                debug!(gid, "rollback prepared");
+                let txn_state = self.prepared_transactions.remove(&gid);
+                self.send_prepared_transaction_event(
+                    txn_state
+                        .as_ref()
+                        .map(|txn_state| &txn_state.touched_tables),
+                    TableEvent::RollbackPrepared { gid },
+                )
+                .await;
+            }
+            CdcEvent::Message(message_body) => {
+                let message = LogicalMessage {
+                    lsn: message_body.lsn(),
+                    commit_lsn: None,
+                    prefix: message_body.prefix().to_string(),
+                    content: message_body.content().to_vec(),
+                };
+                debug!(
+                    lsn = message.lsn,
+                    prefix = message.prefix,
+                    "logical message"
+                );
+                if !message_body.transactional() {
+                    self.deliver_logical_messages(vec![message], message_body.lsn());
+                } else if let Some(xact_id) = message_body.xid().or(self
+                    .preparing_transaction
+                    .as_ref()
+                    .map(|(xact_id, _)| *xact_id))
+                {
+                    self.streaming_transactions_state
+                        .entry(xact_id)
+                        .or_default()
+                        .messages
+                        .push(message);
+                } else {
+                    self.transaction_state.messages.push(message);
+                }
+            }
             CdcEvent::Type(type_body) => {
                 debug!(
                     type_id = type_body.id(),
@@ -309,3 +1072,495 @@ impl Sink {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pg_replicate::conversions::pgoutput::{PgOutputDecoder, PgOutputMessage};
+    use crate::pg_replicate::conversions::Cell;
+    use crate::pg_replicate::dead_letter::RawValue;
+    use crate::pg_replicate::table::{ColumnSchema, LookupKey, TableName};
+    use bytes::Bytes;
+    use moonlink::row::RowValue;
+    use postgres_replication::protocol::LogicalReplicationMessage;
+    use tempfile::TempDir;
+    use tokio::sync::mpsc;
+    use tokio_postgres::types::Type;
+
+    const SRC_TABLE_ID: SrcTableId = 1;
+
+    /// Row event or transaction boundary delivered to the table.
+    #[derive(Debug, PartialEq)]
+    enum Delivered {
+        Append { id: i32, xact_id: Option<u32> },
+        Commit { lsn: u64, xact_id: Option<u32> },
+        StreamAbort { xact_id: u32 },
+    }
+
+    /// Sink which replicates one table, with the receiving ends of the table's channels.
+    struct TestSink {
+        sink: Sink,
+        event_rx: mpsc::Receiver<TableEvent>,
+        /// Commit LSNs sent by the sink are only kept while there's a receiver.
+        _commit_lsn_rx: watch::Receiver<u64>,
+        _temp_dir: TempDir,
+    }
+
+    impl TestSink {
+        fn new() -> Self {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let (logical_message_tx, _) = broadcast::channel(16);
+            let mut sink = Sink::new(ReplicationState::new(), logical_message_tx);
+            let (event_tx, event_rx) = mpsc::channel(100);
+            let (commit_lsn_tx, commit_lsn_rx) = watch::channel(0);
+            let (_, prepared_gids_rx) = watch::channel(HashSet::new());
+            let table_schema = TableSchema {
+                table_name: TableName {
+                    schema: "public".to_string(),
+                    name: "t".to_string(),
+                },
+                src_table_id: SRC_TABLE_ID,
+                column_schemas: vec![ColumnSchema {
+                    name: "id".to_string(),
+                    typ: Type::INT4,
+                    modifier: -1,
+                    nullable: false,
+                    attnum: 1,
+                }],
+                lookup_key: LookupKey::Key {
+                    name: "t_pkey".to_string(),
+                    columns: vec!["id".to_string()],
+                },
+            };
+            sink.add_table(
+                SRC_TABLE_ID,
+                event_tx,
+                commit_lsn_tx,
+                &table_schema,
+                ConversionErrorPolicy::default(),
+                DeadLetterStore::new(temp_dir.path().join("t.dead_letter.jsonl")),
+                prepared_gids_rx,
+            );
+            Self {
+                sink,
+                event_rx,
+                _commit_lsn_rx: commit_lsn_rx,
+                _temp_dir: temp_dir,
+            }
+        }
+
+        async fn process(&mut self, events: Vec<CdcEvent>) {
+            for event in events {
+                self.sink.process_cdc_event(event).await.unwrap();
+            }
+        }
+
+        /// Take all events delivered to the table so far.
+        fn take_delivered(&mut self) -> Vec<Delivered> {
+            let mut delivered = vec![];
+            while let Ok(event) = self.event_rx.try_recv() {
+                delivered.push(match event {
+                    TableEvent::Append { row, xact_id, .. } => {
+                        let RowValue::Int32(id) = row.values[0] else {
+                            panic!("unexpected row {row:?}");
+                        };
+                        Delivered::Append { id, xact_id }
+                    }
+                    TableEvent::Commit { lsn, xact_id } => Delivered::Commit { lsn, xact_id },
+                    TableEvent::StreamAbort { xact_id } => Delivered::StreamAbort { xact_id },
+                    event => panic!("unexpected table event {event:?}"),
+                });
+            }
+            delivered
+        }
+    }
+
+    /// Decode a pgoutput record with `postgres_replication`.
+    fn decode(record: Vec<u8>) -> LogicalReplicationMessage {
+        let mut decoder = PgOutputDecoder::new(/*protocol_version=*/ 2);
+        let PgOutputMessage::Logical(message) = decoder.decode(Bytes::from(record)).unwrap() else {
+            panic!("expects logical replication message");
+        };
+        message
+    }
+
+    fn begin(final_lsn: u64) -> CdcEvent {
+        let mut record = vec![b'B'];
+        record.extend_from_slice(&final_lsn.to_be_bytes());
+        record.extend_from_slice(&0_i64.to_be_bytes());
+        record.extend_from_slice(&1_u32.to_be_bytes());
+        let LogicalReplicationMessage::Begin(body) = decode(record) else {
+            panic!("expects begin record");
+        };
+        CdcEvent::Begin(body)
+    }
+
+    fn commit(commit_lsn: u64, end_lsn: u64) -> CdcEvent {
+        let mut record = vec![b'C', 0];
+        record.extend_from_slice(&commit_lsn.to_be_bytes());
+        record.extend_from_slice(&end_lsn.to_be_bytes());
+        record.extend_from_slice(&0_i64.to_be_bytes());
+        let LogicalReplicationMessage::Commit(body) = decode(record) else {
+            panic!("expects commit record");
+        };
+        CdcEvent::Commit(body)
+    }
+
+    fn stream_commit(xact_id: u32, commit_lsn: u64, end_lsn: u64) -> CdcEvent {
+        let mut record = vec![b'c'];
+        record.extend_from_slice(&xact_id.to_be_bytes());
+        record.push(0);
+        record.extend_from_slice(&commit_lsn.to_be_bytes());
+        record.extend_from_slice(&end_lsn.to_be_bytes());
+        record.extend_from_slice(&0_i64.to_be_bytes());
+        let LogicalReplicationMessage::StreamCommit(body) = decode(record) else {
+            panic!("expects stream commit record");
+        };
+        CdcEvent::StreamCommit(body)
+    }
+
+    fn insert(id: i32, xact_id: Option<u32>) -> CdcEvent {
+        CdcEvent::Insert((
+            SRC_TABLE_ID,
+            TableRow {
+                values: vec![Cell::I32(id)],
+            },
+            xact_id,
+        ))
+    }
+
+    fn stream_prepare(xact_id: u32, prepare_lsn: u64, end_lsn: u64, gid: &str) -> CdcEvent {
+        let mut record = vec![b'p', 0];
+        record.extend_from_slice(&prepare_lsn.to_be_bytes());
+        record.extend_from_slice(&end_lsn.to_be_bytes());
+        record.extend_from_slice(&0_i64.to_be_bytes());
+        record.extend_from_slice(&xact_id.to_be_bytes());
+        record.extend_from_slice(gid.as_bytes());
+        record.push(0);
+        let mut decoder = PgOutputDecoder::new(/*protocol_version=*/ 3);
+        let PgOutputMessage::StreamPrepare(body) = decoder.decode(Bytes::from(record)).unwrap()
+        else {
+            panic!("expects stream prepare record");
+        };
+        CdcEvent::StreamPrepare(body)
+    }
+
+    /// Dead letters are replayed with a transaction stream of their own, even if a streamed transaction has the highest xid.
+    #[tokio::test]
+    async fn test_replay_dead_letters_with_reserved_xact_id() {
+        let mut test_sink = TestSink::new();
+        test_sink.process(vec![insert(1, Some(u32::MAX))]).await;
+        test_sink
+            .sink
+            .dead_letter_stores
+            .get(&SRC_TABLE_ID)
+            .unwrap()
+            .append(&DeadLetterRecord {
+                src_table_id: SRC_TABLE_ID,
+                lsn: 10,
+                xact_id: None,
+                operation: RowOperation::Insert,
+                old_tuple: None,
+                new_tuple: Some(vec![RawValue::Text(b"2".to_vec())]),
+                error: "failed to convert".to_string(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            test_sink
+                .sink
+                .replay_dead_letters(SRC_TABLE_ID)
+                .await
+                .unwrap(),
+            1
+        );
+        let delivered = test_sink.take_delivered();
+        assert_eq!(delivered.len(), 3);
+        assert_eq!(
+            delivered[0],
+            Delivered::Append {
+                id: 1,
+                xact_id: Some(u32::MAX)
+            }
+        );
+        assert_eq!(
+            delivered[1],
+            Delivered::Append {
+                id: 2,
+                xact_id: Some(DEAD_LETTER_REPLAY_XACT_ID)
+            }
+        );
+        assert!(matches!(
+            delivered[2],
+            Delivered::Commit {
+                xact_id: Some(DEAD_LETTER_REPLAY_XACT_ID),
+                ..
+            }
+        ));
+    }
+
+    /// Rows of a transaction delivered before disconnect are skipped when it's replayed, even across disconnects.
+    #[tokio::test]
+    async fn test_replay_partly_delivered_transaction() {
+        let mut test_sink = TestSink::new();
+
+        test_sink.process(vec![begin(100), insert(1, None)]).await;
+        test_sink.sink.handle_disconnect().await;
+        assert_eq!(
+            test_sink.take_delivered(),
+            vec![Delivered::Append {
+                id: 1,
+                xact_id: None
+            }]
+        );
+
+        // Disconnect again in the middle of the replay.
+        test_sink
+            .process(vec![begin(100), insert(1, None), insert(2, None)])
+            .await;
+        test_sink.sink.handle_disconnect().await;
+        assert_eq!(
+            test_sink.take_delivered(),
+            vec![Delivered::Append {
+                id: 2,
+                xact_id: None
+            }]
+        );
+
+        test_sink
+            .process(vec![
+                begin(100),
+                insert(1, None),
+                insert(2, None),
+                insert(3, None),
+                commit(100, 110),
+            ])
+            .await;
+        assert_eq!(
+            test_sink.take_delivered(),
+            vec![
+                Delivered::Append {
+                    id: 3,
+                    xact_id: None
+                },
+                Delivered::Commit {
+                    lsn: 110,
+                    xact_id: None
+                },
+            ]
+        );
+
+        // Later transactions are delivered in full.
+        test_sink
+            .process(vec![begin(200), insert(4, None), commit(200, 210)])
+            .await;
+        assert_eq!(
+            test_sink.take_delivered(),
+            vec![
+                Delivered::Append {
+                    id: 4,
+                    xact_id: None
+                },
+                Delivered::Commit {
+                    lsn: 210,
+                    xact_id: None
+                },
+            ]
+        );
+    }
+
+    /// Transaction committed to the table before disconnect is skipped when it's replayed.
+    #[tokio::test]
+    async fn test_replay_committed_transaction() {
+        let mut test_sink = TestSink::new();
+
+        test_sink
+            .process(vec![begin(100), insert(1, None), commit(100, 110)])
+            .await;
+        test_sink.sink.handle_disconnect().await;
+        assert_eq!(
+            test_sink.take_delivered(),
+            vec![
+                Delivered::Append {
+                    id: 1,
+                    xact_id: None
+                },
+                Delivered::Commit {
+                    lsn: 110,
+                    xact_id: None
+                },
+            ]
+        );
+
+        test_sink
+            .process(vec![
+                begin(100),
+                insert(1, None),
+                commit(100, 110),
+                begin(200),
+                insert(2, None),
+                commit(200, 210),
+            ])
+            .await;
+        assert_eq!(
+            test_sink.take_delivered(),
+            vec![
+                Delivered::Append {
+                    id: 2,
+                    xact_id: None
+                },
+                Delivered::Commit {
+                    lsn: 210,
+                    xact_id: None
+                },
+            ]
+        );
+    }
+
+    /// Streamed transaction committed to the table before disconnect is aborted when it's replayed, so its rows are
+    /// only committed once.
+    #[tokio::test]
+    async fn test_replay_committed_streamed_transaction() {
+        let mut test_sink = TestSink::new();
+        let xact_id = 700;
+
+        test_sink
+            .process(vec![
+                insert(1, Some(xact_id)),
+                stream_commit(xact_id, 100, 110),
+            ])
+            .await;
+        test_sink.sink.handle_disconnect().await;
+        assert_eq!(
+            test_sink.take_delivered(),
+            vec![
+                Delivered::Append {
+                    id: 1,
+                    xact_id: Some(xact_id)
+                },
+                Delivered::Commit {
+                    lsn: 110,
+                    xact_id: Some(xact_id)
+                },
+            ]
+        );
+
+        test_sink
+            .process(vec![
+                insert(1, Some(xact_id)),
+                stream_commit(xact_id, 100, 110),
+            ])
+            .await;
+        assert_eq!(
+            test_sink.take_delivered(),
+            vec![
+                Delivered::Append {
+                    id: 1,
+                    xact_id: Some(xact_id)
+                },
+                Delivered::StreamAbort { xact_id },
+            ]
+        );
+
+        // Streamed transaction in progress at disconnect is aborted, and replayed in full.
+        test_sink.process(vec![insert(2, Some(xact_id + 1))]).await;
+        test_sink.sink.handle_disconnect().await;
+        test_sink
+            .process(vec![
+                insert(2, Some(xact_id + 1)),
+                stream_commit(xact_id + 1, 200, 210),
+            ])
+            .await;
+        assert_eq!(
+            test_sink.take_delivered(),
+            vec![
+                Delivered::Append {
+                    id: 2,
+                    xact_id: Some(xact_id + 1)
+                },
+                Delivered::StreamAbort {
+                    xact_id: xact_id + 1
+                },
+                Delivered::Append {
+                    id: 2,
+                    xact_id: Some(xact_id + 1)
+                },
+                Delivered::Commit {
+                    lsn: 210,
+                    xact_id: Some(xact_id + 1)
+                },
+            ]
+        );
+    }
+
+    /// Nothing is confirmed before the table flushes, even if the replication position advances.
+    #[tokio::test]
+    async fn test_confirmable_lsn_before_flush() {
+        let test_sink = TestSink::new();
+        test_sink.sink.replication_state.mark(PgLsn::from(500));
+        assert_eq!(test_sink.sink.get_confirmable_lsn(SRC_TABLE_ID, 0), 0);
+    }
+
+    /// Open streamed transaction is replayed from the flush LSN after restart, so it's not passed.
+    #[tokio::test]
+    async fn test_confirmable_lsn_with_streamed_transaction() {
+        let mut test_sink = TestSink::new();
+        test_sink
+            .process(vec![begin(100), insert(1, None), commit(100, 110)])
+            .await;
+        test_sink.process(vec![insert(2, Some(700))]).await;
+        test_sink.sink.replication_state.mark(PgLsn::from(500));
+        assert_eq!(test_sink.sink.get_confirmable_lsn(SRC_TABLE_ID, 110), 110);
+    }
+
+    /// Prepared transaction which the table hasn't persisted is replayed from the flush LSN after restart, so it's
+    /// not passed.
+    #[tokio::test]
+    async fn test_confirmable_lsn_with_prepared_transaction() {
+        let mut test_sink = TestSink::new();
+        test_sink
+            .process(vec![begin(100), insert(1, None), commit(100, 110)])
+            .await;
+        test_sink
+            .process(vec![
+                insert(2, Some(700)),
+                stream_prepare(700, 200, 210, "gid-1"),
+            ])
+            .await;
+        test_sink.sink.replication_state.mark(PgLsn::from(500));
+        assert_eq!(test_sink.sink.get_confirmable_lsn(SRC_TABLE_ID, 110), 110);
+    }
+
+    /// Commits which haven't been flushed are replayed from the flush LSN after restart, so it's not passed.
+    #[tokio::test]
+    async fn test_confirmable_lsn_with_unflushed_commit() {
+        let mut test_sink = TestSink::new();
+        test_sink
+            .process(vec![
+                begin(100),
+                insert(1, None),
+                commit(100, 110),
+                begin(200),
+                insert(2, None),
+                commit(200, 210),
+            ])
+            .await;
+        test_sink.sink.replication_state.mark(PgLsn::from(500));
+        assert_eq!(test_sink.sink.get_confirmable_lsn(SRC_TABLE_ID, 110), 110);
+    }
+
+    /// Idle table with all commits flushed confirms the current replication position.
+    #[tokio::test]
+    async fn test_confirmable_lsn_for_idle_table() {
+        let mut test_sink = TestSink::new();
+        test_sink
+            .process(vec![begin(100), insert(1, None), commit(100, 110)])
+            .await;
+        assert_eq!(test_sink.sink.get_confirmable_lsn(SRC_TABLE_ID, 110), 110);
+
+        // Replication position advances with keepalive messages, while the table receives no changes.
+        test_sink.sink.replication_state.mark(PgLsn::from(500));
+        assert_eq!(test_sink.sink.get_confirmable_lsn(SRC_TABLE_ID, 110), 500);
+    }
+}
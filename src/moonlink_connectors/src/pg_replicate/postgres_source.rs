@@ -7,19 +7,23 @@ use std::{
 
 use futures::{ready, Stream};
 use pin_project_lite::pin_project;
-use postgres_replication::LogicalReplicationStream;
+use postgres_replication::protocol::ReplicationMessage;
+use postgres_replication::ReplicationStream;
 use thiserror::Error;
-use tokio_postgres::{tls::NoTlsStream, types::PgLsn, Connection, CopyOutStream, Socket};
+use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
+use tokio_postgres::{types::PgLsn, CopyOutStream};
 use tracing::{debug, error, info_span, warn, Instrument};
 
 use crate::pg_replicate::{
-    clients::postgres::{ReplicationClient, ReplicationClientError},
+    clients::postgres::{ReplicationClient, ReplicationClientError, SlotStatus, TablePartitionKey},
     conversions::{
         cdc_event::{CdcEvent, CdcEventConversionError, CdcEventConverter},
+        pgoutput::PgOutputDecoder,
         table_row::{TableRow, TableRowConversionError, TableRowConverter},
     },
     table::{ColumnSchema, SrcTableId, TableName, TableSchema},
 };
+use moonlink_postgres_tls::PostgresConnection;
 
 #[derive(Debug, Error)]
 pub enum PostgresSourceError {
@@ -29,6 +33,9 @@ pub enum PostgresSourceError {
     #[error("cdc stream can only be started with a slot_name")]
     MissingSlotName,
 
+    #[error("replication slot {0} doesn't exist")]
+    MissingSlot(String),
+
     #[error("replication client error: {0}")]
     ReplicationClient(#[from] ReplicationClientError),
 
@@ -48,6 +55,8 @@ pub struct PostgresSource {
     publication: Option<String>,
     confirmed_flush_lsn: PgLsn,
     uri: String,
+    /// Non-replication connection to monitor the replication slot, connected on first use and reconnected once closed.
+    monitor_client: Mutex<Option<ReplicationClient>>,
 }
 
 /// Configuration needed to create a CDC stream
@@ -58,6 +67,16 @@ pub struct CdcStreamConfig {
     pub confirmed_flush_lsn: PgLsn,
 }
 
+/// Snapshot exported by a temporary replication slot, which is consistent with the slot's consistent point.
+/// The snapshot could only be imported while it's alive, and the temporary slot is dropped along with it.
+pub struct ExportedSnapshot {
+    _replication_client: ReplicationClient,
+    /// Name of the snapshot, used to import the snapshot with `SET TRANSACTION SNAPSHOT`.
+    pub snapshot_name: String,
+    /// Changes committed before the consistent point are visible in the snapshot, and the ones after aren't.
+    pub consistent_point: PgLsn,
+}
+
 impl PostgresSource {
     pub async fn new(
         uri: &str,
@@ -68,7 +87,7 @@ impl PostgresSource {
         assert_eq!(replication_mode, slot_name.is_some());
         assert_eq!(replication_mode, publication.is_some());
         let (mut replication_client, connection) =
-            ReplicationClient::connect_no_tls(uri, replication_mode).await?;
+            ReplicationClient::connect(uri, replication_mode).await?;
         tokio::spawn(
             Self::drive_connection(connection).instrument(info_span!("postgres_client_monitor")),
         );
@@ -88,6 +107,7 @@ impl PostgresSource {
             slot_name,
             confirmed_flush_lsn,
             uri: uri.to_string(),
+            monitor_client: Mutex::new(None),
         })
     }
 
@@ -106,7 +126,7 @@ impl PostgresSource {
             .map_err(PostgresSourceError::ReplicationClient)
     }
 
-    async fn drive_connection(connection: Connection<Socket, NoTlsStream>) {
+    async fn drive_connection(connection: PostgresConnection) {
         if let Err(e) = connection.await {
             warn!("connection error: {}", e);
         }
@@ -114,19 +134,67 @@ impl PostgresSource {
 
     pub async fn add_table_to_publication(
         &mut self,
+        publication: &str,
         table_name: &TableName,
     ) -> Result<(), PostgresSourceError> {
         self.replication_client
-            .add_table_to_publication(table_name)
+            .add_table_to_publication(publication, table_name)
             .await?;
         Ok(())
     }
 
+    pub async fn is_table_in_publication(
+        &self,
+        publication: &str,
+        table_name: &TableName,
+    ) -> Result<bool, PostgresSourceError> {
+        let is_published = self
+            .replication_client
+            .is_table_in_publication(publication, table_name)
+            .await?;
+        Ok(is_published)
+    }
+
+    pub async fn publication_exists(&self, publication: &str) -> Result<bool, PostgresSourceError> {
+        let exists = self
+            .replication_client
+            .publication_exists(publication)
+            .await?;
+        Ok(exists)
+    }
+
+    pub async fn get_row_filter(
+        &self,
+        publication: &str,
+        src_table_id: SrcTableId,
+    ) -> Result<Option<String>, PostgresSourceError> {
+        let row_filter = self
+            .replication_client
+            .get_row_filter(publication, src_table_id)
+            .await?;
+        Ok(row_filter)
+    }
+
+    pub async fn get_partition_key(
+        &self,
+        src_table_id: SrcTableId,
+    ) -> Result<Option<TablePartitionKey>, PostgresSourceError> {
+        let partition_key = self
+            .replication_client
+            .get_partition_key(src_table_id)
+            .await?;
+        Ok(partition_key)
+    }
+
     pub async fn get_row_count(
         &mut self,
         table_name: &TableName,
+        row_filter: Option<&str>,
     ) -> Result<i64, PostgresSourceError> {
-        let row_count = self.replication_client.get_row_count(table_name).await?;
+        let row_count = self
+            .replication_client
+            .get_row_count(table_name, row_filter)
+            .await?;
         Ok(row_count)
     }
 
@@ -139,7 +207,7 @@ impl PostgresSource {
         assert!(src_table_id.is_some() || table_name.is_some());
         // Open new connection to get table schema
         let (mut replication_client, connection) =
-            ReplicationClient::connect_no_tls(&self.uri, false).await?;
+            ReplicationClient::connect(&self.uri, false).await?;
         tokio::spawn(
             Self::drive_connection(connection).instrument(info_span!("postgres_client_monitor")),
         );
@@ -170,16 +238,63 @@ impl PostgresSource {
         Ok(table_schema)
     }
 
+    /// Get the monitor connection, which is (re)connected if it's not connected yet or has been closed.
+    async fn get_monitor_client(
+        &self,
+    ) -> Result<MappedMutexGuard<'_, ReplicationClient>, PostgresSourceError> {
+        let mut monitor_client = self.monitor_client.lock().await;
+        if !matches!(monitor_client.as_ref(), Some(client) if !client.is_closed()) {
+            let (replication_client, connection) =
+                ReplicationClient::connect(&self.uri, false).await?;
+            tokio::spawn(
+                Self::drive_connection(connection)
+                    .instrument(info_span!("postgres_client_monitor")),
+            );
+            *monitor_client = Some(replication_client);
+        }
+        Ok(MutexGuard::map(monitor_client, |client| {
+            client.as_mut().unwrap()
+        }))
+    }
+
+    /// Fetch the confirmed flush LSN of the replication slot, through the monitor connection.
+    pub async fn fetch_confirmed_flush_lsn(&self) -> Result<PgLsn, PostgresSourceError> {
+        let slot_name = self
+            .slot_name()
+            .ok_or(PostgresSourceError::MissingSlotName)?;
+        let slot_info = self
+            .get_monitor_client()
+            .await?
+            .get_slot(slot_name)
+            .await?
+            .ok_or(PostgresSourceError::MissingSlot(slot_name.clone()))?;
+        Ok(slot_info.confirmed_flush_lsn)
+    }
+
+    /// Fetch the health of the replication slot through the monitor connection, `None` if the slot doesn't exist.
+    pub async fn fetch_slot_status(&self) -> Result<Option<SlotStatus>, PostgresSourceError> {
+        let slot_name = self
+            .slot_name()
+            .ok_or(PostgresSourceError::MissingSlotName)?;
+        Ok(self
+            .get_monitor_client()
+            .await?
+            .get_slot_status(slot_name)
+            .await?)
+    }
+
     pub async fn get_table_copy_stream(
         &mut self,
         table_name: &TableName,
         column_schemas: &[ColumnSchema],
+        row_filter: Option<&str>,
+        key_order: Option<&CopyKeyOrder>,
     ) -> Result<(TableCopyStream, PgLsn), PostgresSourceError> {
         debug!("starting table copy stream for table {table_name}");
 
         let (stream, start_lsn) = self
             .replication_client
-            .get_table_copy_stream(table_name, column_schemas)
+            .get_table_copy_stream(table_name, column_schemas, row_filter, key_order)
             .await
             .map_err(PostgresSourceError::ReplicationClient)?;
 
@@ -187,11 +302,68 @@ impl PostgresSource {
             TableCopyStream {
                 stream,
                 column_schemas: column_schemas.to_vec(),
+                bytes_copied: 0,
             },
             start_lsn,
         ))
     }
 
+    /// Export a snapshot through a temporary replication slot, named after the given slot name.
+    pub async fn export_snapshot(
+        uri: &str,
+        slot_name: &str,
+    ) -> Result<ExportedSnapshot, PostgresSourceError> {
+        let (replication_client, connection) = ReplicationClient::connect(uri, true).await?;
+        tokio::spawn(
+            Self::drive_connection(connection).instrument(info_span!("postgres_client_monitor")),
+        );
+        let (consistent_point, snapshot_name) = replication_client
+            .create_snapshot_slot(&format!("{slot_name}_snapshot"))
+            .await?;
+        debug!(
+            snapshot_name,
+            consistent_point = u64::from(consistent_point),
+            "exported snapshot"
+        );
+        Ok(ExportedSnapshot {
+            _replication_client: replication_client,
+            snapshot_name,
+            consistent_point,
+        })
+    }
+
+    /// Get table copy stream within the exported snapshot, along with the number of rows to copy.
+    pub async fn get_table_copy_stream_in_snapshot(
+        &mut self,
+        table_name: &TableName,
+        column_schemas: &[ColumnSchema],
+        row_filter: Option<&str>,
+        key_order: Option<&CopyKeyOrder>,
+        snapshot: &ExportedSnapshot,
+    ) -> Result<(TableCopyStream, i64), PostgresSourceError> {
+        debug!("starting table copy stream for table {table_name} in exported snapshot");
+
+        let (stream, row_count) = self
+            .replication_client
+            .get_table_copy_stream_in_snapshot(
+                table_name,
+                column_schemas,
+                row_filter,
+                key_order,
+                &snapshot.snapshot_name,
+            )
+            .await?;
+
+        Ok((
+            TableCopyStream {
+                stream,
+                column_schemas: column_schemas.to_vec(),
+                bytes_copied: 0,
+            },
+            row_count,
+        ))
+    }
+
     pub async fn commit_transaction(&mut self) -> Result<(), PostgresSourceError> {
         self.replication_client
             .commit_txn()
@@ -225,7 +397,7 @@ impl PostgresSource {
     ) -> Result<CdcStream, PostgresSourceError> {
         debug!("creating cdc stream");
 
-        let stream = replication_client
+        let (stream, protocol_version) = replication_client
             .get_logical_replication_stream(
                 &config.publication,
                 &config.slot_name,
@@ -239,6 +411,7 @@ impl PostgresSource {
 
         Ok(CdcStream {
             stream,
+            decoder: PgOutputDecoder::new(protocol_version),
             table_schemas: HashMap::new(),
             postgres_epoch,
         })
@@ -260,6 +433,14 @@ pin_project! {
         #[pin]
         stream: CopyOutStream,
         column_schemas: Vec<ColumnSchema>,
+        bytes_copied: u64,
+    }
+}
+
+impl TableCopyStream {
+    /// Number of bytes read from the source so far.
+    pub fn bytes_copied(&self) -> u64 {
+        self.bytes_copied
     }
 }
 
@@ -269,14 +450,17 @@ impl Stream for TableCopyStream {
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let this = self.project();
         match ready!(this.stream.poll_next(cx)) {
-            Some(Ok(row)) => match TableRowConverter::try_from(&row, this.column_schemas) {
-                Ok(row) => Poll::Ready(Some(Ok(row))),
-                Err(e) => {
-                    let e = TableCopyStreamError::ConversionError(e);
-                    error!(error = ?e, "failed to convert table row");
-                    Poll::Ready(Some(Err(e)))
+            Some(Ok(row)) => {
+                *this.bytes_copied += row.len() as u64;
+                match TableRowConverter::try_from(&row, this.column_schemas) {
+                    Ok(row) => Poll::Ready(Some(Ok(row))),
+                    Err(e) => {
+                        let e = TableCopyStreamError::ConversionError(e);
+                        error!(error = ?e, "failed to convert table row");
+                        Poll::Ready(Some(Err(e)))
+                    }
                 }
-            },
+            }
             Some(Err(e)) => {
                 error!(error = ?e, "table copy stream error");
                 Poll::Ready(Some(Err(e.into())))
@@ -293,13 +477,17 @@ pub enum CdcStreamError {
 
     #[error("cdc event conversion error: {0}")]
     CdcEventConversion(#[from] CdcEventConversionError),
+
+    #[error("pgoutput decoding error: {0}")]
+    PgOutputDecoding(#[from] std::io::Error),
 }
 
 pin_project! {
     #[must_use = "streams do nothing unless polled"]
     pub struct CdcStream {
         #[pin]
-        stream: LogicalReplicationStream,
+        stream: ReplicationStream,
+        decoder: PgOutputDecoder,
         table_schemas: HashMap<SrcTableId, TableSchema>,
         postgres_epoch: SystemTime,
     }
@@ -345,13 +533,27 @@ impl Stream for CdcStream {
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let this = self.project();
-        match ready!(this.stream.poll_next(cx)) {
-            Some(Ok(msg)) => match CdcEventConverter::try_from(msg, &this.table_schemas) {
-                Ok(row) => Poll::Ready(Some(Ok(row))),
-                Err(e) => Poll::Ready(Some(Err(e.into()))),
-            },
-            Some(Err(e)) => Poll::Ready(Some(Err(e.into()))),
-            None => Poll::Ready(None),
+        let msg = match ready!(this.stream.poll_next(cx)) {
+            Some(Ok(ReplicationMessage::XLogData(xlog_data))) => {
+                match xlog_data.map_data(|buf| this.decoder.decode(buf)) {
+                    Ok(xlog_data) => ReplicationMessage::XLogData(xlog_data),
+                    Err(e) => return Poll::Ready(Some(Err(e.into()))),
+                }
+            }
+            Some(Ok(ReplicationMessage::PrimaryKeepAlive(primary_keepalive_body))) => {
+                ReplicationMessage::PrimaryKeepAlive(primary_keepalive_body)
+            }
+            Some(Ok(_)) => {
+                return Poll::Ready(Some(Err(
+                    CdcEventConversionError::UnknownReplicationMessage.into(),
+                )))
+            }
+            Some(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
+            None => return Poll::Ready(None),
+        };
+        match CdcEventConverter::try_from(msg, this.table_schemas) {
+            Ok(row) => Poll::Ready(Some(Ok(row))),
+            Err(e) => Poll::Ready(Some(Err(e.into()))),
         }
     }
 }
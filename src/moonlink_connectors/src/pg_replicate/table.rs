@@ -3,7 +3,7 @@ use std::fmt::Display;
 use pg_escape::quote_identifier;
 use tokio_postgres::types::Type;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TableName {
     pub schema: String,
     pub name: String,
@@ -17,6 +17,22 @@ impl TableName {
         let name = tokens[1].to_string();
         (schema, name)
     }
+    /// Parse a table name given as `schema.name`, or `name` in the `public` schema.
+    /// Return `None` if the schema or table name is empty, or there're more parts.
+    pub fn try_parse(table_name: &str) -> Option<TableName> {
+        let (schema, name) = match table_name.split('.').collect::<Vec<_>>()[..] {
+            [name] => ("public", name),
+            [schema, name] => (schema, name),
+            _ => return None,
+        };
+        if schema.is_empty() || name.is_empty() {
+            return None;
+        }
+        Some(TableName {
+            schema: schema.to_string(),
+            name: name.to_string(),
+        })
+    }
     pub fn get_schema_name(&self) -> String {
         format!("{}.{}", self.schema, self.name)
     }
@@ -41,6 +57,16 @@ pub struct ColumnSchema {
     pub typ: Type,
     pub modifier: TypeModifier,
     pub nullable: bool,
+    /// Column number in Postgres, which stays the same across renames and type changes.
+    pub attnum: i16,
+}
+
+/// Column described by a relation message, as of its position in the replication stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelationColumn {
+    pub name: String,
+    pub type_oid: u32,
+    pub modifier: TypeModifier,
 }
 
 #[derive(Debug, Clone)]
@@ -59,4 +85,19 @@ pub struct TableSchema {
     pub lookup_key: LookupKey,
 }
 
-impl TableSchema {}
+impl TableSchema {
+    /// Positions of the lookup key columns, None if rows are identified by the full row.
+    pub(crate) fn lookup_key_positions(&self) -> Option<Vec<usize>> {
+        match &self.lookup_key {
+            LookupKey::Key { columns, .. } => columns
+                .iter()
+                .map(|column| {
+                    self.column_schemas
+                        .iter()
+                        .position(|column_schema| &column_schema.name == column)
+                })
+                .collect(),
+            LookupKey::FullRow => None,
+        }
+    }
+}
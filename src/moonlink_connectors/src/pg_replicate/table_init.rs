@@ -4,11 +4,13 @@ use crate::pg_replicate::util::postgres_schema_to_moonlink_schema;
 use crate::{Error, Result};
 use moonlink::event_sync::create_table_event_syncer;
 use moonlink::{
-    EventSyncReceiver, EventSyncSender, FileSystemAccessor, FileSystemConfig, IcebergTableConfig,
+    EventSyncReceiver, EventSyncSender, FileSystemAccessor, FileSystemConfig, IcebergCatalogConfig,
+    IcebergDeleteFileFormat, IcebergTableConfig, InitialCopyPosition, InitialCopyProgress,
     MooncakeTable, MooncakeTableConfig, MoonlinkSecretType, MoonlinkTableConfig,
-    MoonlinkTableSecret, ObjectStorageCache, ReadStateManager, TableEvent, TableEventManager,
-    TableHandler, TableStatusReader,
+    MoonlinkTableSecret, ObjectStorageCache, ParquetWriterConfig, PartitionSpec, ReadStateManager,
+    ReplicationConnectionStatus, TableEvent, TableEventManager, TableHandler, TableStatusReader,
 };
+use std::collections::HashSet;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -17,6 +19,19 @@ use tokio::sync::{broadcast, mpsc, mpsc::Sender, oneshot, watch};
 /// Default namespace for all iceberg tables.
 const DEFAULT_ICEBERG_NAMESPACE: &str = "default";
 
+/// Options to create mooncake table with, which are persisted along with the table config.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableOptions {
+    /// Filesystem to place iceberg table, fallback to local filesystem under base path if unassigned.
+    pub iceberg_filesystem_config: Option<FileSystemConfig>,
+    /// Catalog to track iceberg table metadata.
+    pub iceberg_catalog_config: IcebergCatalogConfig,
+    /// File format to persist row deletions, position delete files are readable by engines without deletion vector support.
+    pub iceberg_delete_file_format: IcebergDeleteFileFormat,
+    /// Config for parquet data files written at flush and compaction.
+    pub parquet_writer_config: ParquetWriterConfig,
+}
+
 /// Components required to replicate a single table.
 /// Components that the [`Sink`] needs for processing CDC events.
 pub struct TableComponents {
@@ -31,6 +46,13 @@ pub struct TableResources {
     pub table_status_reader: TableStatusReader,
     pub commit_lsn_tx: watch::Sender<u64>,
     pub flush_lsn_rx: watch::Receiver<u64>,
+    /// Error which stops replication for the table, unassigned if the table hasn't failed.
+    pub table_error_rx: watch::Receiver<Option<String>>,
+    /// GIDs of prepared transactions persisted by the table, which are recovered after restart.
+    pub prepared_gids_rx: watch::Receiver<HashSet<String>>,
+    pub initial_copy_progress_tx: watch::Sender<Option<InitialCopyProgress>>,
+    /// Initial copy position persisted with the table, unassigned if nothing has been persisted.
+    pub persisted_initial_copy_position: Option<InitialCopyPosition>,
 }
 
 /// Util function to delete and re-create the given directory.
@@ -59,22 +81,33 @@ pub async fn build_table_components(
     table_temp_files_directory: String,
     replication_state: &ReplicationState,
     object_storage_cache: ObjectStorageCache,
-    iceberg_filesystem_config: Option<FileSystemConfig>,
+    table_options: TableOptions,
+    partition_spec: Option<PartitionSpec>,
+    replication_status_rx: watch::Receiver<ReplicationConnectionStatus>,
 ) -> Result<(TableResources, MoonlinkTableConfig)> {
     let write_cache_path = PathBuf::from(base_path).join(&mooncake_table_id);
     recreate_directory(&write_cache_path).await?;
+    // Kept outside of the write cache directory, which is recreated at startup.
+    let prepared_transaction_wal_path =
+        PathBuf::from(base_path).join(format!("{mooncake_table_id}.prepared_transactions"));
     let (arrow_schema, identity) = postgres_schema_to_moonlink_schema(table_schema);
     let iceberg_filesystem_config =
-        iceberg_filesystem_config.unwrap_or(FileSystemConfig::FileSystem {
-            root_directory: base_path.to_string(),
-        });
+        table_options
+            .iceberg_filesystem_config
+            .unwrap_or(FileSystemConfig::FileSystem {
+                root_directory: base_path.to_string(),
+            });
 
     let iceberg_table_config = IcebergTableConfig {
         namespace: vec![DEFAULT_ICEBERG_NAMESPACE.to_string()],
         table_name: mooncake_table_id,
         filesystem_config: iceberg_filesystem_config.clone(),
+        catalog: table_options.iceberg_catalog_config,
+        delete_file_format: table_options.iceberg_delete_file_format,
     };
-    let mooncake_table_config = MooncakeTableConfig::new(table_temp_files_directory);
+    let mut mooncake_table_config = MooncakeTableConfig::new(table_temp_files_directory);
+    mooncake_table_config.partition_spec = partition_spec;
+    mooncake_table_config.parquet_writer_config = table_options.parquet_writer_config;
     let table = MooncakeTable::new(
         arrow_schema,
         table_schema.table_name.to_string(),
@@ -91,14 +124,22 @@ pub async fn build_table_components(
     let (commit_lsn_tx, commit_lsn_rx) = watch::channel(0u64);
     let read_state_manager =
         ReadStateManager::new(&table, replication_state.subscribe(), commit_lsn_rx);
-    let table_status_reader =
-        TableStatusReader::new(database_id, table_id, &iceberg_table_config, &table);
+    let (initial_copy_progress_tx, initial_copy_progress_rx) = watch::channel(None);
     let (event_sync_sender, event_sync_receiver) = create_table_event_syncer();
+    let table_error_rx = event_sync_receiver.table_error_rx.clone();
+    let prepared_gids_rx = event_sync_receiver.prepared_gids_rx.clone();
+    let table_status_reader =
+        TableStatusReader::new(database_id, table_id, &iceberg_table_config, &table)
+            .with_initial_copy_progress(initial_copy_progress_rx)
+            .with_replication_status(replication_status_rx)
+            .with_table_error(table_error_rx.clone());
+    let persisted_initial_copy_position = table.get_persisted_initial_copy_position().cloned();
     let table_handler = TableHandler::new(
         table,
         event_sync_sender,
         replication_state.subscribe(),
         /*event_replay_tx=*/ None,
+        Some(prepared_transaction_wal_path),
     )
     .await;
     let flush_lsn_rx = event_sync_receiver.flush_lsn_rx.clone();
@@ -113,6 +154,10 @@ pub async fn build_table_components(
         table_event_manager,
         commit_lsn_tx,
         flush_lsn_rx,
+        table_error_rx,
+        prepared_gids_rx,
+        initial_copy_progress_tx,
+        persisted_initial_copy_position,
     };
     let moonlink_table_config = MoonlinkTableConfig {
         mooncake_table_config,
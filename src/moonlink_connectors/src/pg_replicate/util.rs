@@ -1,13 +1,20 @@
 use crate::pg_replicate::{
-    conversions::{numeric::PgNumeric, table_row::TableRow, ArrayCell, Cell},
-    table::{LookupKey, TableSchema},
+    clients::postgres::{PartitionStrategy, TablePartitionKey},
+    conversions::{
+        hstore::is_hstore_type,
+        numeric::{PgNumeric, MONEY_SCALE},
+        table_row::TableRow,
+        ArrayCell, Cell,
+    },
+    table::{ColumnSchema, LookupKey, RelationColumn, TableSchema},
 };
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow_schema::extension::{ExtensionType, Json as ArrowJson, Uuid as ArrowUuid};
 use arrow_schema::{DECIMAL128_MAX_PRECISION, DECIMAL_DEFAULT_SCALE};
-use chrono::Timelike;
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
 use moonlink::row::RowValue;
 use moonlink::row::{IdentityProp, MoonlinkRow};
+use moonlink::{AlterTableRequest, PartitionField, PartitionSpec, PartitionTransform};
 use num_traits::cast::ToPrimitive;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -33,6 +40,17 @@ enum ArrowExtensionType {
     Json,
 }
 
+/// Max precision of the Postgres money type, which is stored as int64.
+const MONEY_PRECISION: u8 = 19;
+
+/// Create a nested field with field id assigned.
+fn new_field_with_id(name: &str, data_type: DataType, nullable: bool, field_id: &mut i32) -> Field {
+    let mut metadata = HashMap::new();
+    metadata.insert("PARQUET:field_id".to_string(), field_id.to_string());
+    *field_id += 1;
+    Field::new(name, data_type, nullable).with_metadata(metadata)
+}
+
 fn postgres_primitive_to_arrow_type(
     typ: &Type,
     modifier: i32,
@@ -83,6 +101,46 @@ fn postgres_primitive_to_arrow_type(
         Type::BYTEA => (DataType::Binary, None),
         // The type alias for postgres OID is uint32, but iceberg-rust doesn't support unsigned type, so use int64 instead.
         Type::OID => (DataType::Int64, None),
+        // Iceberg doesn't have an interval type, so keep its components separately.
+        Type::INTERVAL => (
+            DataType::Struct(
+                vec![
+                    new_field_with_id("months", DataType::Int32, true, field_id),
+                    new_field_with_id("days", DataType::Int32, true, field_id),
+                    new_field_with_id("microseconds", DataType::Int64, true, field_id),
+                ]
+                .into(),
+            ),
+            None,
+        ),
+        // Address is stored as octets in network order, 4 bytes for IPv4 and 16 bytes for IPv6.
+        Type::INET | Type::CIDR => (
+            DataType::Struct(
+                vec![
+                    new_field_with_id("address", DataType::Binary, true, field_id),
+                    new_field_with_id("prefix_length", DataType::Int32, true, field_id),
+                ]
+                .into(),
+            ),
+            None,
+        ),
+        Type::MACADDR | Type::MACADDR8 => (DataType::Binary, None),
+        Type::MONEY => (
+            DataType::Decimal128(MONEY_PRECISION, MONEY_SCALE as i8),
+            None,
+        ),
+        _ if is_hstore_type(typ) => {
+            let key = new_field_with_id("key", DataType::Utf8, false, field_id);
+            let value = new_field_with_id("value", DataType::Utf8, true, field_id);
+            // Entries are named the same as iceberg, so parquet column paths match.
+            (
+                DataType::Map(
+                    Arc::new(Field::new_struct("key_value", vec![key, value], false)),
+                    false,
+                ),
+                None,
+            )
+        }
         _ => (DataType::Utf8, None), // Default to string for unknown types
     };
 
@@ -143,6 +201,23 @@ fn postgres_type_to_arrow_type(
             Field::new_struct(name, fields, nullable)
         }
         Kind::Enum(_) => Field::new(name, DataType::Utf8, nullable),
+        // Bounds are null when unbounded.
+        Kind::Range(inner) => {
+            let fields = vec![
+                postgres_type_to_arrow_type(
+                    inner, /*modifier=*/ -1, /*name=*/ "lower", /*nullable=*/ true,
+                    field_id,
+                ),
+                postgres_type_to_arrow_type(
+                    inner, /*modifier=*/ -1, /*name=*/ "upper", /*nullable=*/ true,
+                    field_id,
+                ),
+                new_field_with_id("lower_inclusive", DataType::Boolean, true, field_id),
+                new_field_with_id("upper_inclusive", DataType::Boolean, true, field_id),
+                new_field_with_id("empty", DataType::Boolean, true, field_id),
+            ];
+            new_field_with_id(name, DataType::Struct(fields.into()), nullable, field_id)
+        }
         _ => {
             todo!("Unsupported type: {:?}", typ);
         }
@@ -185,10 +260,328 @@ pub fn postgres_schema_to_moonlink_schema(table_schema: &TableSchema) -> (Schema
     (Schema::new(fields), identity)
 }
 
+/// Convert a single column to arrow field, field ids are only placeholders and reassigned by moonlink.
+fn column_schema_to_arrow_field(column_schema: &ColumnSchema) -> Field {
+    let mut field_id = 0;
+    postgres_type_to_arrow_type(
+        &column_schema.typ,
+        column_schema.modifier,
+        &column_schema.name,
+        column_schema.nullable,
+        &mut field_id,
+    )
+}
+
+/// Build the schema change between two versions of a table, with columns matched by `attnum`.
+pub(crate) fn build_alter_table_request(
+    old_schema: &TableSchema,
+    new_schema: &TableSchema,
+) -> AlterTableRequest {
+    let mut alter_table_request = AlterTableRequest::default();
+    for old_column in old_schema.column_schemas.iter() {
+        let Some(new_column) = new_schema
+            .column_schemas
+            .iter()
+            .find(|c| c.attnum == old_column.attnum)
+        else {
+            alter_table_request
+                .dropped_columns
+                .push(old_column.name.clone());
+            continue;
+        };
+        if old_column.name != new_column.name {
+            alter_table_request
+                .renamed_columns
+                .push((old_column.name.clone(), new_column.name.clone()));
+        }
+        let old_field = column_schema_to_arrow_field(old_column);
+        let new_field = column_schema_to_arrow_field(new_column);
+        if old_field.data_type() != new_field.data_type()
+            || old_field.is_nullable() != new_field.is_nullable()
+        {
+            alter_table_request
+                .altered_columns
+                .push(Arc::new(new_field));
+        }
+    }
+    for new_column in new_schema.column_schemas.iter() {
+        if !old_schema
+            .column_schemas
+            .iter()
+            .any(|c| c.attnum == new_column.attnum)
+        {
+            alter_table_request
+                .new_columns
+                .push(Arc::new(column_schema_to_arrow_field(new_column)));
+        }
+    }
+    alter_table_request
+}
+
+/// Build the table schema as of a relation message, which could be decoded after later schema changes have been applied to
+/// the catalog. Columns come from the relation message; the catalog schema only provides nullability, column numbers and
+/// the lookup key. Columns missing from the catalog (renamed or dropped by a later change) keep their column number in the
+/// old schema if any, and are nullable.
+pub(crate) fn build_table_schema_from_relation(
+    relation_columns: &[RelationColumn],
+    old_schema: &TableSchema,
+    catalog_schema: &TableSchema,
+) -> TableSchema {
+    let find_column = |table_schema: &TableSchema, name: &str| {
+        table_schema
+            .column_schemas
+            .iter()
+            .find(|column_schema| column_schema.name == name)
+            .cloned()
+    };
+    let mut last_attnum = old_schema
+        .column_schemas
+        .iter()
+        .chain(catalog_schema.column_schemas.iter())
+        .map(|column_schema| column_schema.attnum)
+        .max()
+        .unwrap_or(0);
+    let column_schemas = relation_columns
+        .iter()
+        .map(|relation_column| {
+            let (nullable, attnum) = match (
+                find_column(catalog_schema, &relation_column.name),
+                find_column(old_schema, &relation_column.name),
+            ) {
+                (Some(catalog_column), _) => (catalog_column.nullable, catalog_column.attnum),
+                (None, Some(old_column)) => (true, old_column.attnum),
+                (None, None) => {
+                    last_attnum += 1;
+                    (true, last_attnum)
+                }
+            };
+            // Types from extensions don't have fixed oids, so they're resolved from known columns of the same type.
+            let typ = Type::from_oid(relation_column.type_oid)
+                .or_else(|| {
+                    catalog_schema
+                        .column_schemas
+                        .iter()
+                        .chain(old_schema.column_schemas.iter())
+                        .find(|column_schema| column_schema.typ.oid() == relation_column.type_oid)
+                        .map(|column_schema| column_schema.typ.clone())
+                })
+                .unwrap_or_else(|| {
+                    Type::new(
+                        relation_column.type_oid.to_string(),
+                        relation_column.type_oid,
+                        Kind::Simple,
+                        String::new(),
+                    )
+                });
+            ColumnSchema {
+                name: relation_column.name.clone(),
+                typ,
+                modifier: relation_column.modifier,
+                nullable,
+                attnum,
+            }
+        })
+        .collect();
+
+    // Lookup key columns could have been changed after the relation message, keep the old lookup key in that case.
+    let has_lookup_key_columns = |lookup_key: &LookupKey| match lookup_key {
+        LookupKey::Key { columns, .. } => columns.iter().all(|column| {
+            relation_columns
+                .iter()
+                .any(|relation_column| &relation_column.name == column)
+        }),
+        LookupKey::FullRow => true,
+    };
+    let lookup_key = if has_lookup_key_columns(&catalog_schema.lookup_key) {
+        catalog_schema.lookup_key.clone()
+    } else {
+        old_schema.lookup_key.clone()
+    };
+
+    TableSchema {
+        table_name: catalog_schema.table_name.clone(),
+        src_table_id: catalog_schema.src_table_id,
+        column_schemas,
+        lookup_key,
+    }
+}
+
 pub fn _table_schema_to_iceberg_schema(_table_schema: &TableSchema) -> Schema {
     todo!("Iceberg: convert postgres table schema to iceberg schema!")
 }
 
+/// Max number of truncate buckets a Postgres range partition could be split into, otherwise data files get too fragmented.
+const MAX_TRUNCATE_BUCKETS_PER_PARTITION: u64 = 16;
+
+/// Parse literal values out of a partition bound, e.g. `FOR VALUES FROM ('2024-01-01') TO ('2024-02-01')`.
+/// Unbounded values (`MINVALUE` and `MAXVALUE`) and nulls are skipped.
+fn parse_partition_bound_values(partition_bound: &str) -> Vec<String> {
+    let mut values = vec![];
+    let mut cur_value = String::new();
+    let mut in_parenthesis = false;
+    let mut in_quote = false;
+    let mut chars = partition_bound.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quote {
+            if c != '\'' {
+                cur_value.push(c);
+            } else if chars.peek() == Some(&'\'') {
+                cur_value.push(c);
+                chars.next();
+            } else {
+                in_quote = false;
+            }
+            continue;
+        }
+        match c {
+            '(' => {
+                in_parenthesis = true;
+                cur_value.clear();
+            }
+            '\'' if in_parenthesis => in_quote = true,
+            ',' | ')' if in_parenthesis => {
+                let value = cur_value.trim();
+                if !value.is_empty() && !["MINVALUE", "MAXVALUE", "NULL"].contains(&value) {
+                    values.push(value.to_string());
+                }
+                cur_value.clear();
+                in_parenthesis = c == ',';
+            }
+            _ if in_parenthesis => cur_value.push(c),
+            _ => {}
+        }
+    }
+    values
+}
+
+/// Parse a date or timestamp partition bound value, timestamps with time zone are converted to UTC.
+fn parse_partition_bound_datetime(value: &str) -> Option<NaiveDateTime> {
+    if let Ok(datetime) = DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f%#z") {
+        return Some(datetime.naive_utc());
+    }
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f") {
+        return Some(datetime);
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .map(|date| date.and_time(NaiveTime::MIN))
+}
+
+/// Get the coarsest time transform all range bounds align to, so no partition spans over multiple Postgres partitions.
+fn get_range_time_partition_transform(
+    partition_bounds: &[String],
+    has_time: bool,
+) -> PartitionTransform {
+    let bounds = partition_bounds
+        .iter()
+        .flat_map(|partition_bound| parse_partition_bound_values(partition_bound))
+        .filter_map(|value| parse_partition_bound_datetime(&value))
+        .collect::<Vec<_>>();
+    if bounds.is_empty() {
+        return PartitionTransform::Day;
+    }
+    let is_midnight = |datetime: &NaiveDateTime| datetime.time() == NaiveTime::MIN;
+    if bounds
+        .iter()
+        .all(|datetime| is_midnight(datetime) && datetime.ordinal() == 1)
+    {
+        PartitionTransform::Year
+    } else if bounds
+        .iter()
+        .all(|datetime| is_midnight(datetime) && datetime.day() == 1)
+    {
+        PartitionTransform::Month
+    } else if !has_time || bounds.iter().all(is_midnight) {
+        PartitionTransform::Day
+    } else {
+        PartitionTransform::Hour
+    }
+}
+
+/// Get the truncate width which all range bounds are multiple of, return `None` if it leads to too many partitions.
+fn get_range_truncate_width(partition_bounds: &[String]) -> Option<u32> {
+    let gcd = |mut a: u64, mut b: u64| {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    };
+    let mut width = 0;
+    let mut partition_sizes = vec![];
+    for partition_bound in partition_bounds.iter() {
+        let values = parse_partition_bound_values(partition_bound)
+            .iter()
+            .filter_map(|value| value.parse::<i64>().ok())
+            .collect::<Vec<_>>();
+        for value in values.iter() {
+            width = gcd(width, value.unsigned_abs());
+        }
+        if let [lower, upper] = values[..] {
+            partition_sizes.push(upper.abs_diff(lower));
+        }
+    }
+    if width == 0 || width > u32::MAX as u64 {
+        return None;
+    }
+    if partition_sizes
+        .iter()
+        .any(|size| size / width > MAX_TRUNCATE_BUCKETS_PER_PARTITION)
+    {
+        return None;
+    }
+    Some(width as u32)
+}
+
+/// Translate the partition key of a Postgres partitioned table into partition spec, so data files are partitioned in the same way.
+/// Only range and list partitioning on integer, date and timestamp columns are supported, others are replicated unpartitioned.
+pub(crate) fn postgres_partition_key_to_partition_spec(
+    table_schema: &TableSchema,
+    partition_key: &TablePartitionKey,
+) -> Option<PartitionSpec> {
+    let column_schema = table_schema
+        .column_schemas
+        .iter()
+        .find(|column_schema| column_schema.name == partition_key.column_name)?;
+    let transform = match (&column_schema.typ, partition_key.strategy) {
+        (_, PartitionStrategy::Hash) => None,
+        (&Type::INT2 | &Type::INT4 | &Type::INT8, PartitionStrategy::List) => {
+            Some(PartitionTransform::Identity)
+        }
+        (&Type::INT2 | &Type::INT4 | &Type::INT8, PartitionStrategy::Range) => {
+            get_range_truncate_width(&partition_key.partition_bounds)
+                .map(PartitionTransform::Truncate)
+        }
+        (&Type::DATE | &Type::TIMESTAMP | &Type::TIMESTAMPTZ, PartitionStrategy::List) => {
+            Some(PartitionTransform::Day)
+        }
+        (&Type::DATE, PartitionStrategy::Range) => Some(get_range_time_partition_transform(
+            &partition_key.partition_bounds,
+            /*has_time=*/ false,
+        )),
+        (&Type::TIMESTAMP | &Type::TIMESTAMPTZ, PartitionStrategy::Range) => {
+            Some(get_range_time_partition_transform(
+                &partition_key.partition_bounds,
+                /*has_time=*/ true,
+            ))
+        }
+        _ => None,
+    };
+    let Some(transform) = transform else {
+        warn!(
+            column_name = %partition_key.column_name,
+            strategy = ?partition_key.strategy,
+            "partition key is not supported, replicate table unpartitioned"
+        );
+        return None;
+    };
+    Some(PartitionSpec {
+        fields: vec![PartitionField {
+            source_column: partition_key.column_name.clone(),
+            transform,
+        }],
+    })
+}
+
 pub(crate) struct PostgresTableRow(pub TableRow);
 
 const ARROW_EPOCH: chrono::NaiveDate = chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
@@ -307,86 +700,100 @@ fn convert_array_cell(cell: ArrayCell) -> Vec<RowValue> {
     }
 }
 
-impl From<PostgresTableRow> for MoonlinkRow {
-    fn from(row: PostgresTableRow) -> Self {
-        let mut values = Vec::with_capacity(row.0.values.len());
-        for cell in row.0.values {
-            match cell {
-                Cell::I16(value) => {
-                    values.push(RowValue::Int32(value as i32));
-                }
-                Cell::I32(value) => {
-                    values.push(RowValue::Int32(value));
-                }
-                Cell::U32(value) => {
-                    values.push(RowValue::Int32(value as i32));
-                }
-                Cell::I64(value) => {
-                    values.push(RowValue::Int64(value));
-                }
-                Cell::F32(value) => {
-                    values.push(RowValue::Float32(value));
-                }
-                Cell::F64(value) => {
-                    values.push(RowValue::Float64(value));
-                }
-                Cell::Bool(value) => {
-                    values.push(RowValue::Bool(value));
-                }
-                Cell::String(value) => {
-                    values.push(RowValue::ByteArray(value.as_bytes().to_vec()));
-                }
-                Cell::Date(value) => {
-                    values.push(RowValue::Int32(
-                        value.signed_duration_since(ARROW_EPOCH).num_days() as i32,
-                    ));
-                }
-                Cell::Time(value) => {
-                    let seconds = value.num_seconds_from_midnight() as i64;
-                    let nanos = value.nanosecond() as i64;
-                    values.push(RowValue::Int64(seconds * 1_000_000 + nanos / 1_000))
-                }
-                Cell::TimeStamp(value) => {
-                    values.push(RowValue::Int64(value.and_utc().timestamp_micros()))
-                }
-                Cell::TimeStampTz(value) => values.push(RowValue::Int64(value.timestamp_micros())),
-                Cell::Uuid(value) => {
-                    values.push(RowValue::FixedLenByteArray(*value.as_bytes()));
-                }
-                Cell::Json(value) => {
-                    values.push(RowValue::ByteArray(value.to_string().as_bytes().to_vec()));
-                }
-                Cell::Bytes(value) => {
-                    values.push(RowValue::ByteArray(value));
-                }
-                Cell::Array(value) => {
-                    values.push(RowValue::Array(convert_array_cell(value)));
-                }
-                Cell::Numeric(value) => {
-                    match value {
-                        PgNumeric::Value(bigdecimal) => {
-                            let (int_val, _) = bigdecimal.into_bigint_and_exponent();
-                            values.push(RowValue::Decimal(int_val.to_i128().unwrap()));
-                        }
-                        _ => {
-                            // DevNote:
-                            // nan, inf, -inf will be converted to null
-                            values.push(RowValue::Null);
-                        }
-                    }
+/// Convert a range bound, numeric bounds come with their own scale and are rescaled to the default one of unconstrained numeric.
+fn convert_range_bound(bound: Option<Cell>) -> RowValue {
+    match bound {
+        Some(Cell::Numeric(PgNumeric::Value(bigdecimal))) => convert_cell(Cell::Numeric(
+            PgNumeric::Value(bigdecimal.with_scale(DECIMAL_DEFAULT_SCALE as i64)),
+        )),
+        Some(cell) => convert_cell(cell),
+        None => RowValue::Null,
+    }
+}
+
+fn convert_cell(cell: Cell) -> RowValue {
+    match cell {
+        Cell::I16(value) => RowValue::Int32(value as i32),
+        Cell::I32(value) => RowValue::Int32(value),
+        Cell::U32(value) => RowValue::Int32(value as i32),
+        Cell::I64(value) => RowValue::Int64(value),
+        Cell::F32(value) => RowValue::Float32(value),
+        Cell::F64(value) => RowValue::Float64(value),
+        Cell::Bool(value) => RowValue::Bool(value),
+        Cell::String(value) => RowValue::ByteArray(value.as_bytes().to_vec()),
+        Cell::Date(value) => {
+            RowValue::Int32(value.signed_duration_since(ARROW_EPOCH).num_days() as i32)
+        }
+        Cell::Time(value) => {
+            let seconds = value.num_seconds_from_midnight() as i64;
+            let nanos = value.nanosecond() as i64;
+            RowValue::Int64(seconds * 1_000_000 + nanos / 1_000)
+        }
+        Cell::TimeStamp(value) => RowValue::Int64(value.and_utc().timestamp_micros()),
+        Cell::TimeStampTz(value) => RowValue::Int64(value.timestamp_micros()),
+        Cell::Uuid(value) => RowValue::FixedLenByteArray(*value.as_bytes()),
+        Cell::Json(value) => RowValue::ByteArray(value.to_string().as_bytes().to_vec()),
+        Cell::Bytes(value) => RowValue::ByteArray(value),
+        Cell::Array(value) => RowValue::Array(convert_array_cell(value)),
+        Cell::Numeric(value) => {
+            match value {
+                PgNumeric::Value(bigdecimal) => {
+                    let (int_val, _) = bigdecimal.into_bigint_and_exponent();
+                    RowValue::Decimal(int_val.to_i128().unwrap())
                 }
-                Cell::Null => {
-                    values.push(RowValue::Null);
+                _ => {
+                    // DevNote:
+                    // nan, inf, -inf will be converted to null
+                    RowValue::Null
                 }
             }
         }
-        MoonlinkRow::new(values)
+        Cell::Interval(value) => RowValue::Struct(vec![
+            RowValue::Int32(value.months),
+            RowValue::Int32(value.days),
+            RowValue::Int64(value.microseconds),
+        ]),
+        Cell::Inet(value) => RowValue::Struct(vec![
+            RowValue::ByteArray(value.address_octets()),
+            RowValue::Int32(value.prefix_length as i32),
+        ]),
+        Cell::Range(value) => {
+            let range = *value;
+            RowValue::Struct(vec![
+                convert_range_bound(range.lower),
+                convert_range_bound(range.upper),
+                RowValue::Bool(range.lower_inclusive),
+                RowValue::Bool(range.upper_inclusive),
+                RowValue::Bool(range.empty),
+            ])
+        }
+        Cell::Hstore(value) => RowValue::Array(
+            value
+                .into_iter()
+                .map(|(key, value)| {
+                    RowValue::Struct(vec![
+                        RowValue::ByteArray(key.into_bytes()),
+                        value
+                            .map(|value| RowValue::ByteArray(value.into_bytes()))
+                            .unwrap_or(RowValue::Null),
+                    ])
+                })
+                .collect(),
+        ),
+        Cell::Null => RowValue::Null,
+    }
+}
+
+impl From<PostgresTableRow> for MoonlinkRow {
+    fn from(row: PostgresTableRow) -> Self {
+        MoonlinkRow::new(row.0.values.into_iter().map(convert_cell).collect())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pg_replicate::conversions::text::TextFormatConverter;
     use crate::pg_replicate::table::{ColumnSchema, LookupKey, TableName, TableSchema};
     use arrow::array::{Date32Array, StringArray, TimestampMicrosecondArray};
     use arrow::datatypes::DataType;
@@ -408,132 +815,154 @@ mod tests {
                     typ: Type::BOOL,
                     modifier: 0,
                     nullable: false,
+                    attnum: 1,
                 },
                 ColumnSchema {
                     name: "int2_field".to_string(),
                     typ: Type::INT2,
                     modifier: 0,
                     nullable: true,
+                    attnum: 2,
                 },
                 ColumnSchema {
                     name: "int4_field".to_string(),
                     typ: Type::INT4,
                     modifier: 0,
                     nullable: false,
+                    attnum: 3,
                 },
                 ColumnSchema {
                     name: "int8_field".to_string(),
                     typ: Type::INT8,
                     modifier: 0,
                     nullable: true,
+                    attnum: 4,
                 },
                 ColumnSchema {
                     name: "float4_field".to_string(),
                     typ: Type::FLOAT4,
                     modifier: 0,
                     nullable: true,
+                    attnum: 5,
                 },
                 ColumnSchema {
                     name: "float8_field".to_string(),
                     typ: Type::FLOAT8,
                     modifier: 0,
                     nullable: true,
+                    attnum: 6,
                 },
                 ColumnSchema {
                     name: "numeric_field".to_string(),
                     typ: Type::NUMERIC,
                     modifier: ((12 << 16) | 5) + 4, // NUMERIC(12,5)
                     nullable: true,
+                    attnum: 7,
                 },
                 ColumnSchema {
                     name: "varchar_field".to_string(),
                     typ: Type::VARCHAR,
                     modifier: 0,
                     nullable: true,
+                    attnum: 8,
                 },
                 ColumnSchema {
                     name: "text_field".to_string(),
                     typ: Type::TEXT,
                     modifier: 0,
                     nullable: true,
+                    attnum: 9,
                 },
                 ColumnSchema {
                     name: "bpchar_field".to_string(),
                     typ: Type::BPCHAR,
                     modifier: 0,
                     nullable: true,
+                    attnum: 10,
                 },
                 ColumnSchema {
                     name: "char_field".to_string(),
                     typ: Type::CHAR,
                     modifier: 0,
                     nullable: true,
+                    attnum: 11,
                 },
                 ColumnSchema {
                     name: "name_field".to_string(),
                     typ: Type::NAME,
                     modifier: 0,
                     nullable: true,
+                    attnum: 12,
                 },
                 ColumnSchema {
                     name: "date_field".to_string(),
                     typ: Type::DATE,
                     modifier: 0,
                     nullable: true,
+                    attnum: 13,
                 },
                 ColumnSchema {
                     name: "timestamp_field".to_string(),
                     typ: Type::TIMESTAMP,
                     modifier: 0,
                     nullable: true,
+                    attnum: 14,
                 },
                 ColumnSchema {
                     name: "timestamptz_field".to_string(),
                     typ: Type::TIMESTAMPTZ,
                     modifier: 0,
                     nullable: true,
+                    attnum: 15,
                 },
                 ColumnSchema {
                     name: "time_field".to_string(),
                     typ: Type::TIME,
                     modifier: 0,
                     nullable: true,
+                    attnum: 16,
                 },
                 ColumnSchema {
                     name: "timetz_field".to_string(),
                     typ: Type::TIMETZ,
                     modifier: 0,
                     nullable: true,
+                    attnum: 17,
                 },
                 ColumnSchema {
                     name: "uuid_field".to_string(),
                     typ: Type::UUID,
                     modifier: 0,
                     nullable: true,
+                    attnum: 18,
                 },
                 ColumnSchema {
                     name: "json_field".to_string(),
                     typ: Type::JSON,
                     modifier: 0,
                     nullable: true,
+                    attnum: 19,
                 },
                 ColumnSchema {
                     name: "jsonb_field".to_string(),
                     typ: Type::JSONB,
                     modifier: 0,
                     nullable: true,
+                    attnum: 20,
                 },
                 ColumnSchema {
                     name: "bytea_field".to_string(),
                     typ: Type::BYTEA,
                     modifier: 0,
                     nullable: true,
+                    attnum: 21,
                 },
                 ColumnSchema {
                     name: "oid_field".to_string(),
                     typ: Type::OID,
                     modifier: 0,
                     nullable: true,
+                    attnum: 22,
                 },
                 // Array type.
                 ColumnSchema {
@@ -541,6 +970,7 @@ mod tests {
                     typ: Type::BOOL_ARRAY,
                     modifier: 0,
                     nullable: true,
+                    attnum: 23,
                 },
                 // TODO(hjiang): Add composite type handling.
             ],
@@ -768,6 +1198,117 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_additional_types_to_moonlink_schema_and_row() {
+        let hstore = Type::new(
+            "hstore".to_string(),
+            /*oid=*/ 16385,
+            Kind::Simple,
+            "public".to_string(),
+        );
+        let column_schemas = vec![
+            (Type::INTERVAL, "1 mon 2 days 00:00:01"),
+            (Type::INET, "10.0.0.1/8"),
+            (Type::MONEY, "$12.34"),
+            (Type::NUM_RANGE, "[1.5,)"),
+            (hstore, "\"a\"=>\"1\", \"b\"=>NULL"),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (typ, _))| ColumnSchema {
+            name: format!("field_{idx}"),
+            typ,
+            modifier: -1,
+            nullable: true,
+            attnum: idx as i16 + 1,
+        })
+        .collect::<Vec<_>>();
+        let table_schema = TableSchema {
+            table_name: TableName {
+                schema: "public".to_string(),
+                name: "test_table".to_string(),
+            },
+            src_table_id: 1,
+            column_schemas: column_schemas.clone(),
+            lookup_key: LookupKey::FullRow,
+        };
+
+        let (arrow_schema, _) = postgres_schema_to_moonlink_schema(&table_schema);
+        assert!(matches!(
+            arrow_schema.field(0).data_type(),
+            DataType::Struct(fields) if fields.len() == 3
+        ));
+        assert!(matches!(
+            arrow_schema.field(1).data_type(),
+            DataType::Struct(fields) if fields.len() == 2
+        ));
+        assert_eq!(
+            arrow_schema.field(2).data_type(),
+            &DataType::Decimal128(19, 2)
+        );
+        assert!(matches!(
+            arrow_schema.field(3).data_type(),
+            DataType::Struct(fields) if fields.len() == 5
+        ));
+        assert!(matches!(
+            arrow_schema.field(4).data_type(),
+            DataType::Map(_, false)
+        ));
+        // All nested fields get field ids, so the schema could be converted to iceberg.
+        IcebergArrow::arrow_schema_to_schema(&arrow_schema).unwrap();
+
+        let values = [
+            "1 mon 2 days 00:00:01",
+            "10.0.0.1/8",
+            "$12.34",
+            "[1.5,)",
+            "\"a\"=>\"1\", \"b\"=>NULL",
+        ]
+        .iter()
+        .zip(column_schemas.iter())
+        .map(|(value, column_schema)| {
+            TextFormatConverter::try_from_str(&column_schema.typ, value).unwrap()
+        })
+        .collect();
+        let moonlink_row: MoonlinkRow = PostgresTableRow(TableRow { values }).into();
+        assert_eq!(
+            moonlink_row.values[0],
+            RowValue::Struct(vec![
+                RowValue::Int32(1),
+                RowValue::Int32(2),
+                RowValue::Int64(1_000_000),
+            ])
+        );
+        assert_eq!(
+            moonlink_row.values[1],
+            RowValue::Struct(vec![
+                RowValue::ByteArray(vec![10, 0, 0, 1]),
+                RowValue::Int32(8),
+            ])
+        );
+        assert_eq!(moonlink_row.values[2], RowValue::Decimal(1234));
+        assert_eq!(
+            moonlink_row.values[3],
+            RowValue::Struct(vec![
+                RowValue::Decimal(15_000_000_000),
+                RowValue::Null,
+                RowValue::Bool(true),
+                RowValue::Bool(false),
+                RowValue::Bool(false),
+            ])
+        );
+        assert_eq!(
+            moonlink_row.values[4],
+            RowValue::Array(vec![
+                RowValue::Struct(vec![
+                    RowValue::ByteArray(b"a".to_vec()),
+                    RowValue::ByteArray(b"1".to_vec()),
+                ]),
+                RowValue::Struct(vec![RowValue::ByteArray(b"b".to_vec()), RowValue::Null]),
+            ])
+        );
+    }
+
     #[test]
     fn test_postgres_array_to_moonlink_row() {
         let postgres_table_row = PostgresTableRow(TableRow {
@@ -868,4 +1409,246 @@ mod tests {
             RowValue::Int64(1704196800000000) // 2024-01-02 12:00:00 in microseconds
         );
     }
+
+    #[test]
+    fn test_build_alter_table_request() {
+        let column = |name: &str, typ: Type, nullable: bool, attnum: i16| ColumnSchema {
+            name: name.to_string(),
+            typ,
+            modifier: -1,
+            nullable,
+            attnum,
+        };
+        let table_schema = |column_schemas: Vec<ColumnSchema>| TableSchema {
+            table_name: TableName {
+                schema: "public".to_string(),
+                name: "test_table".to_string(),
+            },
+            src_table_id: 1,
+            column_schemas,
+            lookup_key: LookupKey::Key {
+                name: "id".to_string(),
+                columns: vec!["id".to_string()],
+            },
+        };
+        let old_schema = table_schema(vec![
+            column("id", Type::INT4, false, 1),
+            column("name", Type::TEXT, true, 2),
+            column("age", Type::INT4, true, 3),
+            column("score", Type::INT4, true, 4),
+        ]);
+        let new_schema = table_schema(vec![
+            column("id", Type::INT4, false, 1),
+            column("full_name", Type::TEXT, true, 2),
+            column("age", Type::INT8, true, 3),
+            column("email", Type::TEXT, true, 5),
+        ]);
+
+        let alter_table_request = build_alter_table_request(&old_schema, &new_schema);
+        assert_eq!(
+            alter_table_request.dropped_columns,
+            vec!["score".to_string()]
+        );
+        assert_eq!(
+            alter_table_request.renamed_columns,
+            vec![("name".to_string(), "full_name".to_string())]
+        );
+        assert_eq!(alter_table_request.altered_columns.len(), 1);
+        assert_eq!(alter_table_request.altered_columns[0].name(), "age");
+        assert_eq!(
+            alter_table_request.altered_columns[0].data_type(),
+            &DataType::Int64
+        );
+        assert_eq!(alter_table_request.new_columns.len(), 1);
+        assert_eq!(alter_table_request.new_columns[0].name(), "email");
+
+        assert!(build_alter_table_request(&old_schema, &old_schema).is_empty());
+    }
+
+    #[test]
+    fn test_build_table_schema_from_relation() {
+        let column = |name: &str, typ: Type, nullable: bool, attnum: i16| ColumnSchema {
+            name: name.to_string(),
+            typ,
+            modifier: -1,
+            nullable,
+            attnum,
+        };
+        let relation_column = |name: &str, typ: Type| RelationColumn {
+            name: name.to_string(),
+            type_oid: typ.oid(),
+            modifier: -1,
+        };
+        let table_schema = |column_schemas: Vec<ColumnSchema>| TableSchema {
+            table_name: TableName {
+                schema: "public".to_string(),
+                name: "test_table".to_string(),
+            },
+            src_table_id: 1,
+            column_schemas,
+            lookup_key: LookupKey::Key {
+                name: "id".to_string(),
+                columns: vec!["id".to_string()],
+            },
+        };
+        let old_schema = table_schema(vec![
+            column("id", Type::INT4, false, 1),
+            column("name", Type::TEXT, true, 2),
+        ]);
+        // Catalog has applied two schema changes: add "email" column, then rename "name" and add "phone" column.
+        let catalog_schema = table_schema(vec![
+            column("id", Type::INT4, false, 1),
+            column("full_name", Type::TEXT, true, 2),
+            column("email", Type::TEXT, false, 3),
+            column("phone", Type::TEXT, true, 4),
+        ]);
+
+        // Relation message of the first schema change only has the columns at that point.
+        let relation_columns = vec![
+            relation_column("id", Type::INT4),
+            relation_column("name", Type::TEXT),
+            relation_column("email", Type::TEXT),
+        ];
+        let new_schema =
+            build_table_schema_from_relation(&relation_columns, &old_schema, &catalog_schema);
+        let columns = new_schema
+            .column_schemas
+            .iter()
+            .map(|c| (c.name.as_str(), c.typ.clone(), c.nullable, c.attnum))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![
+                ("id", Type::INT4, false, 1),
+                ("name", Type::TEXT, true, 2),
+                ("email", Type::TEXT, false, 3),
+            ]
+        );
+        let alter_table_request = build_alter_table_request(&old_schema, &new_schema);
+        assert_eq!(alter_table_request.new_columns.len(), 1);
+        assert_eq!(alter_table_request.new_columns[0].name(), "email");
+        assert!(alter_table_request.renamed_columns.is_empty());
+
+        // Relation message of the second schema change matches the catalog.
+        let relation_columns = vec![
+            relation_column("id", Type::INT4),
+            relation_column("full_name", Type::TEXT),
+            relation_column("email", Type::TEXT),
+            relation_column("phone", Type::TEXT),
+        ];
+        let newer_schema =
+            build_table_schema_from_relation(&relation_columns, &new_schema, &catalog_schema);
+        let alter_table_request = build_alter_table_request(&new_schema, &newer_schema);
+        assert_eq!(
+            alter_table_request.renamed_columns,
+            vec![("name".to_string(), "full_name".to_string())]
+        );
+        assert_eq!(alter_table_request.new_columns.len(), 1);
+        assert_eq!(alter_table_request.new_columns[0].name(), "phone");
+    }
+
+    #[test]
+    fn test_postgres_partition_key_to_partition_spec() {
+        let table_schema = TableSchema {
+            table_name: TableName {
+                schema: "public".to_string(),
+                name: "events".to_string(),
+            },
+            src_table_id: 1,
+            column_schemas: vec![
+                ColumnSchema {
+                    name: "id".to_string(),
+                    typ: Type::INT8,
+                    modifier: 0,
+                    nullable: false,
+                    attnum: 1,
+                },
+                ColumnSchema {
+                    name: "created_at".to_string(),
+                    typ: Type::TIMESTAMPTZ,
+                    modifier: 0,
+                    nullable: false,
+                    attnum: 2,
+                },
+                ColumnSchema {
+                    name: "name".to_string(),
+                    typ: Type::TEXT,
+                    modifier: 0,
+                    nullable: true,
+                    attnum: 3,
+                },
+            ],
+            lookup_key: LookupKey::Key {
+                name: "id".to_string(),
+                columns: vec!["id".to_string()],
+            },
+        };
+        let get_transform = |column_name: &str, strategy, partition_bounds: &[&str]| {
+            let partition_key = TablePartitionKey {
+                strategy,
+                column_name: column_name.to_string(),
+                partition_bounds: partition_bounds.iter().map(|b| b.to_string()).collect(),
+            };
+            postgres_partition_key_to_partition_spec(&table_schema, &partition_key)
+                .map(|partition_spec| partition_spec.fields[0].transform)
+        };
+
+        // Monthly range partitions, with a default partition.
+        assert_eq!(
+            get_transform(
+                "created_at",
+                PartitionStrategy::Range,
+                &[
+                    "FOR VALUES FROM ('2024-01-01 00:00:00+00') TO ('2024-02-01 00:00:00+00')",
+                    "FOR VALUES FROM ('2024-02-01 00:00:00+00') TO ('2024-03-01 00:00:00+00')",
+                    "DEFAULT",
+                ],
+            ),
+            Some(PartitionTransform::Month)
+        );
+        assert_eq!(
+            get_transform(
+                "created_at",
+                PartitionStrategy::Range,
+                &["FOR VALUES FROM (MINVALUE) TO ('2024-01-01 06:00:00+00')"],
+            ),
+            Some(PartitionTransform::Hour)
+        );
+        assert_eq!(
+            get_transform(
+                "id",
+                PartitionStrategy::Range,
+                &[
+                    "FOR VALUES FROM ('0') TO ('1000')",
+                    "FOR VALUES FROM ('1000') TO ('3000')",
+                ],
+            ),
+            Some(PartitionTransform::Truncate(1000))
+        );
+        // Range bounds which don't share a reasonable width are left unpartitioned.
+        assert_eq!(
+            get_transform(
+                "id",
+                PartitionStrategy::Range,
+                &["FOR VALUES FROM ('1') TO ('1001')"],
+            ),
+            None
+        );
+        assert_eq!(
+            get_transform("id", PartitionStrategy::List, &["FOR VALUES IN ('1', '2')"]),
+            Some(PartitionTransform::Identity)
+        );
+        assert_eq!(
+            get_transform(
+                "id",
+                PartitionStrategy::Hash,
+                &["FOR VALUES WITH (modulus 4, remainder 0)"],
+            ),
+            None
+        );
+        assert_eq!(
+            get_transform("name", PartitionStrategy::List, &["FOR VALUES IN ('a')"]),
+            None
+        );
+    }
 }
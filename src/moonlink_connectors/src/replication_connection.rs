@@ -1,35 +1,66 @@
-use crate::pg_replicate::clients::postgres::ReplicationClient;
+use crate::pg_replicate::clients::postgres::{
+    CopyKeyOrder, ReplicationClient, ReplicationClientError,
+};
 use crate::pg_replicate::conversions::cdc_event::CdcEventConversionError;
-use crate::pg_replicate::initial_copy::copy_table_stream_impl;
-use crate::pg_replicate::moonlink_sink::{SchemaChangeRequest, Sink};
+use crate::pg_replicate::dead_letter::{ConversionErrorPolicy, DeadLetterRecord, DeadLetterStore};
+use crate::pg_replicate::initial_copy::{
+    copy_table_stream_impl, CopyCheckpointer, CopyProgressReporter, InitialCopyCheckpoint,
+    INITIAL_COPY_CHECKPOINT_ROWS,
+};
+use crate::pg_replicate::moonlink_sink::{LogicalMessage, SchemaChangeRequest, Sink};
 use crate::pg_replicate::postgres_source::{
-    CdcStreamConfig, CdcStreamError, PostgresSource, PostgresSourceError,
+    CdcStream, CdcStreamConfig, CdcStreamError, PostgresSource, PostgresSourceError,
+    TableCopyStream,
+};
+use crate::pg_replicate::table_init::{build_table_components, TableOptions};
+use crate::pg_replicate::util::{
+    build_table_schema_from_relation, postgres_partition_key_to_partition_spec,
 };
-use crate::pg_replicate::table_init::build_table_components;
+use crate::replication_options::ReplicationOptions;
 use crate::Result;
+use backon::{BackoffBuilder, ExponentialBuilder};
 use moonlink::{
-    FileSystemConfig, MoonlinkTableConfig, ObjectStorageCache, ReadStateManager, TableEventManager,
-    TableStatusReader,
+    InitialCopyPosition, InitialCopyProgress, MoonlinkTableConfig, ObjectStorageCache,
+    ReadStateManager, ReplicationConnectionStatus, ReplicationConnectionUsage,
+    ReplicationSlotStatus, TableEventManager, TableStatusReader,
 };
+use moonlink_postgres_tls::PostgresConnection;
+use pg_escape::{quote_identifier, quote_literal};
 use std::io::{Error, ErrorKind};
+use std::path::PathBuf;
+use std::pin::Pin;
 use std::sync::Arc;
 use tokio::pin;
 use tokio::time::Duration;
 use tokio_postgres::error::SqlState;
-use tokio_postgres::types::PgLsn;
-use tokio_postgres::{tls::NoTlsStream, Connection, Socket};
+use tokio_postgres::types::{PgLsn, Type};
 
 use crate::pg_replicate::replication_state::ReplicationState;
-use crate::pg_replicate::table::{SrcTableId, TableSchema};
+use crate::pg_replicate::table::{LookupKey, SrcTableId, TableName, TableSchema};
 use futures::StreamExt;
 use moonlink::TableEvent;
-use std::collections::HashMap;
-use tokio::sync::{mpsc, watch};
+use std::collections::{HashMap, HashSet};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
 use tokio::task::JoinHandle;
-use tokio_postgres::{connect, Client, Config, NoTls};
+use tokio_postgres::Client;
 use tracing::Instrument;
 use tracing::{debug, error, info_span, warn};
 
+/// Initial delay to reconnect replication connection.
+const RECONNECT_MIN_DELAY: Duration = Duration::from_millis(500);
+/// Max delay to reconnect replication connection.
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(60);
+/// Number of logical messages buffered for each subscriber, slow subscribers observe a lag error when exceeded.
+const LOGICAL_MESSAGE_CHANNEL_CAPACITY: usize = 1024;
+/// Interval to write heartbeats to the heartbeat table, if configured.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+/// Interval to check health of the replication slot.
+const SLOT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Number of attempts to copy a table, before the table fails.
+const INITIAL_COPY_MAX_ATTEMPTS: u32 = 3;
+/// Delay before retrying a failed initial copy.
+const INITIAL_COPY_RETRY_DELAY: Duration = Duration::from_secs(5);
+
 pub enum Command {
     AddTable {
         src_table_id: SrcTableId,
@@ -37,27 +68,68 @@ pub enum Command {
         event_sender: mpsc::Sender<TableEvent>,
         commit_lsn_tx: watch::Sender<u64>,
         flush_lsn_rx: watch::Receiver<u64>,
+        table_error_rx: watch::Receiver<Option<String>>,
+        prepared_gids_rx: watch::Receiver<HashSet<String>>,
+        conversion_error_policy: ConversionErrorPolicy,
+        dead_letter_path: PathBuf,
     },
     DropTable {
         src_table_id: SrcTableId,
     },
+    SetConversionErrorPolicy {
+        src_table_id: SrcTableId,
+        policy: ConversionErrorPolicy,
+    },
+    ReplayDeadLetters {
+        src_table_id: SrcTableId,
+        tx: oneshot::Sender<Result<usize>>,
+    },
     Shutdown,
 }
 
 struct TableState {
+    /// Id of the database which the table belongs to, tables of multiple databases could share a connection.
+    database_id: u32,
     schema: TableSchema,
     reader: ReadStateManager,
     event_manager: TableEventManager,
     status_reader: TableStatusReader,
+    /// Local path for the initial copy checkpoint.
+    initial_copy_checkpoint_path: PathBuf,
+    /// Local path for the dead letter file.
+    dead_letter_path: PathBuf,
+}
+/// Resources needed to perform the initial copy for a table.
+struct InitialCopyResources {
+    event_sender: mpsc::Sender<TableEvent>,
+    progress_tx: watch::Sender<Option<InitialCopyProgress>>,
+    /// Local path for the initial copy checkpoint.
+    checkpoint_path: PathBuf,
+    /// Initial copy position persisted with the table, unassigned if nothing has been persisted.
+    persisted_position: Option<InitialCopyPosition>,
+    /// Notified once the finished initial copy has been persisted.
+    completion_rx: watch::Receiver<bool>,
 }
-/// Manages replication for table(s) within a database.
+
+/// Manages replication for table(s) within a source database, which could belong to multiple moonlink databases.
 pub struct ReplicationConnection {
+    /// Source uri as given by user, including replication options.
+    src_uri: String,
+    /// Connection string used to connect to Postgres, with replication options removed.
     uri: String,
-    database_id: u32,
     table_base_path: String,
     table_temp_files_directory: String,
-    postgres_client: Client,
+    /// Control connection to the source database, shared with the heartbeat task.
+    postgres_client: Arc<Client>,
     handle: Option<JoinHandle<Result<()>>>,
+    /// Table to write heartbeats to, if configured.
+    heartbeat_table: Option<TableName>,
+    /// Conversion error policy for newly added tables.
+    default_conversion_error_policy: ConversionErrorPolicy,
+    /// Whether initial copies are checkpointed, for tables keyed by a single integer column.
+    initial_copy_checkpoint: bool,
+    /// Background tasks which run along with replication, i.e. slot health check and heartbeat.
+    background_handles: Vec<JoinHandle<()>>,
     table_states: HashMap<SrcTableId, TableState>,
     cmd_tx: mpsc::Sender<Command>,
     cmd_rx: Option<mpsc::Receiver<Command>>,
@@ -65,25 +137,33 @@ pub struct ReplicationConnection {
     source: Arc<PostgresSource>,
     replication_started: bool,
     slot_name: String,
+    /// Whether the replication slot is created by moonlink, and should be dropped at shutdown.
+    owns_slot: bool,
+    publication: String,
+    /// Whether the publication is created by moonlink; user-managed publications are never altered.
+    owns_publication: bool,
     /// Object storage cache.
     object_storage_cache: ObjectStorageCache,
     /// Background retry handles for drop operations.
     retry_handles: Vec<JoinHandle<Result<()>>>,
+    /// Replication connection status, shared with all tables' status readers.
+    replication_status_tx: watch::Sender<ReplicationConnectionStatus>,
+    /// Logical decoding messages decoded from the replication stream.
+    logical_message_tx: broadcast::Sender<LogicalMessage>,
 }
 
 impl ReplicationConnection {
     pub async fn new(
-        uri: String,
-        database_id: u32,
+        src_uri: String,
         table_base_path: String,
         table_temp_files_directory: String,
         object_storage_cache: ObjectStorageCache,
     ) -> Result<Self> {
-        debug!(%uri, "initializing replication connection");
+        debug!(%src_uri, "initializing replication connection");
 
-        let (postgres_client, connection) = connect(&uri, NoTls)
-            .await
-            .map_err(PostgresSourceError::from)?;
+        let (uri, replication_options) = ReplicationOptions::split_from_uri(&src_uri)?;
+        let (publication, owns_publication) = replication_options.publication();
+        let (postgres_client, connection) = moonlink_postgres_tls::connect(&uri).await?;
         tokio::spawn(
             async move {
                 if let Err(e) = connection.await {
@@ -95,43 +175,62 @@ impl ReplicationConnection {
         postgres_client
             .simple_query("SET lock_timeout = '100ms';")
             .await?;
-        postgres_client
-            .simple_query(
-                "DROP PUBLICATION IF EXISTS moonlink_pub; CREATE PUBLICATION moonlink_pub WITH (publish_via_partition_root = true);",
-            )
-            .await
-            .map_err(PostgresSourceError::from)?;
+        if owns_publication {
+            postgres_client
+                .simple_query(&format!(
+                    "DROP PUBLICATION IF EXISTS {0}; CREATE PUBLICATION {0} WITH (publish_via_partition_root = true);",
+                    quote_identifier(&publication)
+                ))
+                .await
+                .map_err(PostgresSourceError::from)?;
+        }
 
-        let db_name = uri
-            .parse::<Config>()
+        let db_name = moonlink_postgres_tls::parse_postgres_config(&uri)
             .ok()
-            .and_then(|c| c.get_dbname().map(|s| s.to_string()))
+            .and_then(|(c, _)| c.get_dbname().map(|s| s.to_string()))
             .unwrap_or_else(|| "".to_string());
-        let slot_name = if db_name.is_empty() {
-            "moonlink_slot".to_string()
-        } else {
-            format!("moonlink_slot_{db_name}")
-        };
+        let (slot_name, owns_slot) = replication_options.slot_name(&db_name);
 
         let postgres_source = PostgresSource::new(
             &uri,
             Some(slot_name.clone()),
-            Some("moonlink_pub".to_string()),
+            Some(publication.clone()),
             true,
         )
         .await?;
+        if !owns_publication && !postgres_source.publication_exists(&publication).await? {
+            return Err(
+                PostgresSourceError::from(ReplicationClientError::MissingPublication(publication))
+                    .into(),
+            );
+        }
 
         let (cmd_tx, cmd_rx) = mpsc::channel(8);
+        let (replication_status_tx, _) = watch::channel(ReplicationConnectionStatus {
+            usage: ReplicationConnectionUsage {
+                slot_name: slot_name.clone(),
+                publication: publication.clone(),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let (logical_message_tx, _) = broadcast::channel(LOGICAL_MESSAGE_CHANNEL_CAPACITY);
 
         debug!("replication connection ready");
 
         Ok(Self {
+            src_uri,
             uri,
-            database_id,
             table_base_path,
             table_temp_files_directory,
-            postgres_client,
+            postgres_client: Arc::new(postgres_client),
             handle: None,
+            heartbeat_table: replication_options.heartbeat_table,
+            default_conversion_error_policy: replication_options
+                .conversion_error_policy
+                .unwrap_or_default(),
+            initial_copy_checkpoint: replication_options.initial_copy_checkpoint,
+            background_handles: Vec::new(),
             table_states: HashMap::new(),
             cmd_tx,
             cmd_rx: Some(cmd_rx),
@@ -139,8 +238,13 @@ impl ReplicationConnection {
             source: Arc::new(postgres_source),
             replication_started: false,
             slot_name,
+            owns_slot,
+            publication,
+            owns_publication,
             object_storage_cache,
             retry_handles: Vec::new(),
+            replication_status_tx,
+            logical_message_tx,
         })
     }
 
@@ -149,7 +253,8 @@ impl ReplicationConnection {
     }
 
     /// Include full row in cdc stream (not just primary keys).
-    async fn alter_table_replica_identity(&self, table_name: &str) -> Result<()> {
+    /// Only applied with explicit opt-in, since it's an intrusive change on the source table, and inflates WAL.
+    async fn alter_table_replica_identity_full(&self, table_name: &str) -> Result<()> {
         self.postgres_client
             .simple_query(&format!("ALTER TABLE {table_name} REPLICA IDENTITY FULL;"))
             .await?;
@@ -161,9 +266,7 @@ impl ReplicationConnection {
         let uri = uri.to_string();
         let drop_query = drop_query.to_string();
         tokio::spawn(async move {
-            let (drop_client, _) = connect(&uri, NoTls)
-                .await
-                .map_err(PostgresSourceError::from)?;
+            let (drop_client, _) = moonlink_postgres_tls::connect(&uri).await?;
             drop_client
                 .simple_query(&drop_query)
                 .await
@@ -202,30 +305,49 @@ impl ReplicationConnection {
         Ok(())
     }
 
-    async fn remove_table_from_publication(&mut self, table_name: &str) -> Result<()> {
+    async fn remove_table_from_publication(&mut self, table_name: &TableName) -> Result<()> {
+        if !self.owns_publication {
+            return Ok(());
+        }
         self.attempt_drop_else_retry(&format!(
-            "ALTER PUBLICATION moonlink_pub DROP TABLE {table_name};"
+            "ALTER PUBLICATION {} DROP TABLE {};",
+            quote_identifier(&self.publication),
+            table_name.as_quoted_identifier()
         ))
         .await?;
         Ok(())
     }
 
+    /// Drop the publication if it's created by moonlink.
     pub async fn drop_publication(&mut self) -> Result<()> {
-        self.attempt_drop_else_retry("DROP PUBLICATION IF EXISTS moonlink_pub;")
-            .await?;
+        if !self.owns_publication {
+            return Ok(());
+        }
+        self.attempt_drop_else_retry(&format!(
+            "DROP PUBLICATION IF EXISTS {};",
+            quote_identifier(&self.publication)
+        ))
+        .await?;
         Ok(())
     }
 
+    /// Drop the replication slot if it's created by moonlink.
     pub async fn drop_replication_slot(&self) -> Result<()> {
+        if !self.owns_slot {
+            return Ok(());
+        }
         // First, terminate any active connections using this slot
         let terminate_query = format!(
-            "SELECT pg_terminate_backend(active_pid) FROM pg_replication_slots WHERE slot_name = '{}';",
-            self.slot_name
+            "SELECT pg_terminate_backend(active_pid) FROM pg_replication_slots WHERE slot_name = {};",
+            quote_literal(&self.slot_name)
         );
         let _ = self.postgres_client.simple_query(&terminate_query).await;
 
         // Then drop the replication slot
-        let drop_query = format!("SELECT pg_drop_replication_slot('{}');", self.slot_name);
+        let drop_query = format!(
+            "SELECT pg_drop_replication_slot({});",
+            quote_literal(&self.slot_name)
+        );
         self.postgres_client
             .simple_query(&drop_query)
             .await
@@ -249,10 +371,37 @@ impl ReplicationConnection {
             .collect::<Vec<_>>()
     }
 
+    /// Check replication slot health, and get the up-to-date replication connection status.
+    pub async fn get_replication_status(&self) -> Result<ReplicationConnectionStatus> {
+        refresh_slot_status(&self.source, &self.slot_name, &self.replication_status_tx).await?;
+        Ok(self.replication_status_tx.borrow().clone())
+    }
+
+    /// Subscribe to logical decoding messages emitted on the source database, after the subscription.
+    pub fn subscribe_logical_messages(&self) -> broadcast::Receiver<LogicalMessage> {
+        self.logical_message_tx.subscribe()
+    }
+
     pub fn table_count(&self) -> usize {
         self.table_states.len()
     }
 
+    /// Report tables and databases replicated through the connection in its status.
+    fn update_usage(&self) {
+        let mut database_ids = self
+            .table_states
+            .values()
+            .map(|table_state| table_state.database_id)
+            .collect::<Vec<_>>();
+        database_ids.sort_unstable();
+        database_ids.dedup();
+        let table_count = self.table_states.len() as u64;
+        self.replication_status_tx.send_modify(|status| {
+            status.usage.table_count = table_count;
+            status.usage.database_ids = database_ids;
+        });
+    }
+
     pub fn get_table_event_manager(&mut self, src_table_id: SrcTableId) -> &mut TableEventManager {
         &mut self
             .table_states
@@ -269,51 +418,76 @@ impl ReplicationConnection {
         let uri = self.uri.clone();
         let cfg = self.source.get_cdc_stream_config().unwrap();
         let source = self.source.clone();
+        let status_tx = self.replication_status_tx.clone();
+        let state = ReplicationTaskState {
+            sink,
+            cmd_rx,
+            table_schemas: HashMap::new(),
+            flush_lsn_rxs: HashMap::new(),
+            table_error_rxs: HashMap::new(),
+        };
 
-        tokio::spawn(async move {
-            let (client, connection) = ReplicationClient::connect_no_tls(&uri, true)
-                .await
-                .map_err(PostgresSourceError::from)?;
-
-            run_event_loop(client, cfg, connection, sink, cmd_rx, source).await
-        })
+        tokio::spawn(run_replication_supervisor(
+            uri, cfg, state, source, status_tx,
+        ))
     }
 
-    /// # Arguments
-    ///
-    /// * override_table_base_path: mooncake table directory, fallback to [`self.table_base_path`] if unassigned.
-    async fn add_table_to_replication<T: std::fmt::Display>(
+    /// Create components for the table, and register it to the replication task.
+    async fn register_table<T: std::fmt::Display>(
         &mut self,
         schema: &TableSchema,
         mooncake_table_id: &T,
+        database_id: u32,
         table_id: u32,
-        iceberg_filesystem_config: Option<FileSystemConfig>,
-        is_recovery: bool,
-    ) -> Result<MoonlinkTableConfig> {
+        table_options: TableOptions,
+    ) -> Result<(MoonlinkTableConfig, InitialCopyResources)> {
         let src_table_id = schema.src_table_id;
-        debug!(src_table_id, "adding table to replication");
+        // Data files are partitioned in the same way as the source table, if it's partitioned.
+        let partition_spec =
+            self.source
+                .get_partition_key(src_table_id)
+                .await?
+                .and_then(|partition_key| {
+                    postgres_partition_key_to_partition_spec(schema, &partition_key)
+                });
         let (table_resources, moonlink_table_config) = build_table_components(
             mooncake_table_id.to_string(),
-            self.database_id,
+            database_id,
             table_id,
             schema,
             &self.table_base_path,
             self.table_temp_files_directory.clone(),
             &self.replication_state,
             self.object_storage_cache.clone(),
-            iceberg_filesystem_config,
+            table_options,
+            partition_spec,
+            self.replication_status_tx.subscribe(),
         )
         .await?;
 
-        let event_sender_clone = table_resources.event_sender.clone();
+        let initial_copy_resources = InitialCopyResources {
+            event_sender: table_resources.event_sender.clone(),
+            progress_tx: table_resources.initial_copy_progress_tx,
+            checkpoint_path: PathBuf::from(&self.table_base_path)
+                .join(format!("{mooncake_table_id}.initial_copy.json")),
+            persisted_position: table_resources.persisted_initial_copy_position,
+            completion_rx: table_resources
+                .table_event_manager
+                .subscribe_initial_copy_completion(),
+        };
+        let dead_letter_path = PathBuf::from(&self.table_base_path)
+            .join(format!("{mooncake_table_id}.dead_letter.jsonl"));
 
         self.table_states.insert(
             src_table_id,
             TableState {
+                database_id,
                 schema: schema.clone(),
                 reader: table_resources.read_state_manager,
                 event_manager: table_resources.table_event_manager,
                 status_reader: table_resources.table_status_reader,
+                initial_copy_checkpoint_path: initial_copy_resources.checkpoint_path.clone(),
+                dead_letter_path: dead_letter_path.clone(),
             },
         );
         if let Err(e) = self
@@ -324,21 +498,115 @@ impl ReplicationConnection {
                 event_sender: table_resources.event_sender,
                 commit_lsn_tx: table_resources.commit_lsn_tx,
                 flush_lsn_rx: table_resources.flush_lsn_rx,
+                table_error_rx: table_resources.table_error_rx,
+                prepared_gids_rx: table_resources.prepared_gids_rx,
+                conversion_error_policy: self.default_conversion_error_policy,
+                dead_letter_path,
             })
             .await
         {
             error!(error = ?e, "failed to enqueue AddTable command");
         }
+        self.update_usage();
+
+        Ok((moonlink_table_config, initial_copy_resources))
+    }
 
-        // Create a dedicated source for the copy
+    /// # Arguments
+    ///
+    /// * override_table_base_path: mooncake table directory, fallback to [`self.table_base_path`] if unassigned.
+    async fn add_table_to_replication<T: std::fmt::Display>(
+        &mut self,
+        schema: &TableSchema,
+        mooncake_table_id: &T,
+        database_id: u32,
+        table_id: u32,
+        table_options: TableOptions,
+        is_recovery: bool,
+    ) -> Result<MoonlinkTableConfig> {
+        let src_table_id = schema.src_table_id;
+        debug!(src_table_id, "adding table to replication");
+        let (moonlink_table_config, initial_copy_resources) = self
+            .register_table(
+                schema,
+                mooncake_table_id,
+                database_id,
+                table_id,
+                table_options,
+            )
+            .await?;
+        let InitialCopyResources {
+            event_sender: event_sender_clone,
+            progress_tx: initial_copy_progress_tx,
+            checkpoint_path: initial_copy_checkpoint_path,
+            persisted_position,
+            completion_rx,
+        } = initial_copy_resources;
+
+        // Create a dedicated source to count rows and publish the table, the copy opens its own.
         let mut copy_source = PostgresSource::new(&self.uri, None, None, false).await?;
 
+        // Only copy rows published by the publication's row filter, if any.
+        let row_filter = self
+            .source
+            .get_row_filter(&self.publication, src_table_id)
+            .await?;
+
         // Check if there are existing rows
-        let row_count = copy_source.get_row_count(&schema.table_name).await?;
+        let row_count = copy_source
+            .get_row_count(&schema.table_name, row_filter.as_deref())
+            .await?;
+
+        // Copied rows are committed along with the copy position at checkpoints and when the copy finishes, and the local
+        // checkpoint is only marked finished after the finished copy has been persisted. So an unfinished checkpoint at
+        // recovery means the copy has to be resumed from the persisted position, or redone if nothing has been persisted.
+        let checkpoint = if is_recovery {
+            InitialCopyCheckpoint::load(&initial_copy_checkpoint_path).await?
+        } else {
+            None
+        };
+        // Checkpointed copies are copied in key order, which are resumed regardless of whether checkpointing is still enabled.
+        let copy_key_index = get_copy_key_index(schema);
+        let mut unfinished_copy = false;
+        let mut resume_position = None;
+        if let Some(mut checkpoint) = checkpoint.filter(|checkpoint| !checkpoint.finished) {
+            match persisted_position {
+                // Finished copy has been persisted right before restart, only the checkpoint is left behind.
+                Some(position) if position.is_finished() => {
+                    checkpoint.finished = true;
+                    checkpoint.persist(&initial_copy_checkpoint_path).await?;
+                }
+                Some(position) if position.last_key().is_some() => {
+                    if copy_key_index.is_none() {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!(
+                                "initial copy of table {} can't resume, its key is no longer a single integer column",
+                                schema.table_name
+                            ),
+                        )
+                        .into());
+                    }
+                    warn!(
+                        src_table_id,
+                        last_key = position.last_key(),
+                        "initial copy didn't finish before restart, resume the copy"
+                    );
+                    unfinished_copy = true;
+                    resume_position = Some(position);
+                }
+                _ => {
+                    warn!(
+                        src_table_id,
+                        "initial copy didn't finish before restart, redo the copy"
+                    );
+                    unfinished_copy = true;
+                }
+            }
+        }
 
-        // Only perform initial copy for new tables, not during recovery.
-        // Early return if there are no rows to copy.
-        if !is_recovery && row_count > 0 {
+        // Only perform initial copy for new tables with rows to copy, or tables whose initial copy hasn't finished.
+        if (!is_recovery && row_count > 0) || unfinished_copy {
             if let Err(e) = event_sender_clone.send(TableEvent::StartInitialCopy).await {
                 error!(error = ?e, "failed to send StartInitialCopy event");
             }
@@ -346,41 +614,46 @@ impl ReplicationConnection {
             // Alter the publication to add the table.
             // Add table to publication first to begin accumulating any cdc events.
             // We can check where our initial copy started from and discard any rows we have already seen.
-            copy_source
-                .add_table_to_publication(&schema.table_name)
-                .await?;
-
-            let schema_clone = schema.clone();
-            tokio::spawn(async move {
-                let (stream, start_lsn) = copy_source
-                    .get_table_copy_stream(&schema_clone.table_name, &schema_clone.column_schemas)
-                    .await
-                    .expect("failed to get table copy stream");
-                let res = copy_table_stream_impl(schema_clone, stream, &event_sender_clone).await;
-
-                if let Err(e) = res {
-                    error!(error = ?e, table_id = src_table_id, "failed to copy table");
-                }
-                // Commit the transaction
+            if self.owns_publication {
                 copy_source
-                    .commit_transaction()
-                    .await
-                    .expect("failed to commit transaction");
+                    .add_table_to_publication(&self.publication, &schema.table_name)
+                    .await?;
+            }
 
-                if let Err(e) = event_sender_clone
-                    .send(TableEvent::FinishInitialCopy {
-                        start_lsn: start_lsn.into(),
-                    })
-                    .await
-                {
-                    error!(error = ?e, table_id = src_table_id, "failed to send FinishTableCopy command");
-                }
-            });
+            let progress_reporter = CopyProgressReporter::new(
+                initial_copy_progress_tx,
+                Some(initial_copy_checkpoint_path),
+                /*estimated_total_rows=*/ Some(row_count as u64),
+            );
+            // Resume after the last copied key, from a new source snapshot.
+            let checkpointer = copy_key_index
+                .filter(|_| self.initial_copy_checkpoint || resume_position.is_some())
+                .map(|key_index| {
+                    CopyCheckpointer::new(
+                        key_index,
+                        resume_position.unwrap_or_default(),
+                        INITIAL_COPY_CHECKPOINT_ROWS,
+                    )
+                });
+            tokio::spawn(run_initial_copy(
+                schema.clone(),
+                /*attempt=*/ None,
+                CopyStreamSource {
+                    uri: self.uri.clone(),
+                    row_filter,
+                },
+                event_sender_clone,
+                progress_reporter,
+                checkpointer,
+                completion_rx,
+            ));
         } else {
             // If there are no rows to copy, we still need to add the table to publication.
-            copy_source
-                .add_table_to_publication(&schema.table_name)
-                .await?;
+            if self.owns_publication {
+                copy_source
+                    .add_table_to_publication(&self.publication, &schema.table_name)
+                    .await?;
+            }
         }
 
         debug!(table_id, "table added to replication");
@@ -391,14 +664,20 @@ impl ReplicationConnection {
     async fn remove_table_from_replication(&mut self, src_table_id: SrcTableId) -> Result<()> {
         debug!(src_table_id, "removing table from replication");
         let TableState {
-            mut event_manager, ..
+            mut event_manager,
+            initial_copy_checkpoint_path,
+            dead_letter_path,
+            ..
         } = self.table_states.remove(&src_table_id).unwrap();
         // Notify the table handler to clean up cache, mooncake and iceberg table state.
         debug!(src_table_id, "drop table from table handler");
         event_manager.drop_table().await?;
+        InitialCopyCheckpoint::delete(&initial_copy_checkpoint_path).await?;
+        DeadLetterStore::delete(&dead_letter_path).await?;
         if let Err(e) = self.cmd_tx.send(Command::DropTable { src_table_id }).await {
             error!(error = ?e, "failed to enqueue DropTable command");
         }
+        self.update_usage();
 
         debug!(src_table_id, "table removed from replication");
 
@@ -408,9 +687,30 @@ impl ReplicationConnection {
     pub async fn start_replication(&mut self) -> Result<()> {
         debug!("starting replication");
 
-        let sink = Sink::new(self.replication_state.clone());
+        let sink = Sink::new(
+            self.replication_state.clone(),
+            self.logical_message_tx.clone(),
+        );
         let receiver = self.cmd_rx.take().unwrap();
         self.handle = Some(self.spawn_replication_task(sink, receiver).await);
+        self.background_handles.push(tokio::spawn(run_slot_monitor(
+            self.source.clone(),
+            self.slot_name.clone(),
+            self.replication_status_tx.clone(),
+        )));
+        if let Some(heartbeat_table) = &self.heartbeat_table {
+            self.postgres_client
+                .simple_query(&format!(
+                    "CREATE TABLE IF NOT EXISTS {} (id int PRIMARY KEY, last_heartbeat timestamptz NOT NULL);",
+                    heartbeat_table.as_quoted_identifier()
+                ))
+                .await
+                .map_err(PostgresSourceError::from)?;
+            self.background_handles.push(tokio::spawn(run_heartbeat(
+                self.postgres_client.clone(),
+                heartbeat_table.clone(),
+            )));
+        }
 
         self.replication_started = true;
 
@@ -423,24 +723,24 @@ impl ReplicationConnection {
         &mut self,
         table_name: &str,
         mooncake_table_id: &T,
+        database_id: u32,
         table_id: u32,
-        iceberg_filesystem_config: Option<FileSystemConfig>,
+        table_options: TableOptions,
         is_recovery: bool,
+        allow_replica_identity_full: bool,
     ) -> Result<(SrcTableId, MoonlinkTableConfig)> {
         debug!(table_name, "adding table");
-        // TODO: We should not naively alter the replica identity of a table. We should only do this if we are sure that the table does not already have a FULL replica identity. [https://github.com/Mooncake-Labs/moonlink/issues/104]
-        self.alter_table_replica_identity(table_name).await?;
         let table_schema = self
-            .source
-            .fetch_table_schema(None, Some(table_name), None)
+            .fetch_table_schema_to_add(table_name, allow_replica_identity_full)
             .await?;
 
         let moonlink_table_config = self
             .add_table_to_replication(
                 &table_schema,
                 mooncake_table_id,
+                database_id,
                 table_id,
-                iceberg_filesystem_config,
+                table_options,
                 is_recovery,
             )
             .await?;
@@ -450,6 +750,249 @@ impl ReplicationConnection {
         Ok((table_schema.src_table_id, moonlink_table_config))
     }
 
+    /// Add tables together, which are copied within a single exported snapshot, and replicated from the snapshot's
+    /// consistent point; so reads across these tables are consistent right after the initial copy.
+    ///
+    /// # Arguments
+    ///
+    /// * database_id: id of the database which all tables belong to.
+    /// * tables: table name, mooncake table id and table id of each table to add.
+    pub async fn add_tables<T: std::fmt::Display>(
+        &mut self,
+        database_id: u32,
+        tables: &[(String, T, u32)],
+        table_options: TableOptions,
+        allow_replica_identity_full: bool,
+    ) -> Result<Vec<(SrcTableId, MoonlinkTableConfig)>> {
+        debug!(table_count = tables.len(), "adding tables");
+        let mut table_schemas = Vec::with_capacity(tables.len());
+        for (table_name, _, _) in tables {
+            table_schemas.push(
+                self.fetch_table_schema_to_add(table_name, allow_replica_identity_full)
+                    .await?,
+            );
+        }
+
+        // Tables registered or published so far, which are rolled back if any table fails to be added.
+        let mut registered_tables = Vec::with_capacity(tables.len());
+        let mut published_tables = Vec::with_capacity(tables.len());
+        let res = self
+            .add_tables_impl(
+                database_id,
+                tables,
+                table_schemas,
+                table_options,
+                &mut registered_tables,
+                &mut published_tables,
+            )
+            .await;
+        if let Err(e) = &res {
+            warn!(error = ?e, "failed to add tables, rolling back");
+            for table_name in published_tables {
+                if let Err(e) = self.remove_table_from_publication(&table_name).await {
+                    warn!(error = ?e, %table_name, "failed to remove table from publication");
+                }
+            }
+            for src_table_id in registered_tables {
+                if let Err(e) = self.remove_table_from_replication(src_table_id).await {
+                    warn!(error = ?e, src_table_id, "failed to remove table from replication");
+                }
+            }
+        }
+        let added_tables = res?;
+
+        debug!(table_count = added_tables.len(), "tables added");
+
+        Ok(added_tables)
+    }
+
+    /// Register tables and start their initial copies within one exported snapshot.
+    /// Tables are recorded to `registered_tables` and `published_tables` once registered and published, so they
+    /// could be rolled back on failure; initial copies are only started after all tables are set up.
+    async fn add_tables_impl<T: std::fmt::Display>(
+        &mut self,
+        database_id: u32,
+        tables: &[(String, T, u32)],
+        table_schemas: Vec<TableSchema>,
+        table_options: TableOptions,
+        registered_tables: &mut Vec<SrcTableId>,
+        published_tables: &mut Vec<TableName>,
+    ) -> Result<Vec<(SrcTableId, MoonlinkTableConfig)>> {
+        let mut added_tables = Vec::with_capacity(tables.len());
+        let mut initial_copies = Vec::with_capacity(tables.len());
+        for ((_, mooncake_table_id, table_id), schema) in tables.iter().zip(&table_schemas) {
+            let (moonlink_table_config, initial_copy_resources) = self
+                .register_table(
+                    schema,
+                    mooncake_table_id,
+                    database_id,
+                    *table_id,
+                    table_options.clone(),
+                )
+                .await?;
+            registered_tables.push(schema.src_table_id);
+            // Changes streamed after the table is published are buffered until its initial copy finishes.
+            if let Err(e) = initial_copy_resources
+                .event_sender
+                .send(TableEvent::StartInitialCopy)
+                .await
+            {
+                error!(error = ?e, "failed to send StartInitialCopy event");
+            }
+            added_tables.push((schema.src_table_id, moonlink_table_config));
+            initial_copies.push(initial_copy_resources);
+        }
+
+        // Tables have to be published before the snapshot is exported, so all changes after its consistent point are streamed.
+        if self.owns_publication {
+            let mut publication_source = PostgresSource::new(&self.uri, None, None, false).await?;
+            for schema in &table_schemas {
+                publication_source
+                    .add_table_to_publication(&self.publication, &schema.table_name)
+                    .await?;
+                published_tables.push(schema.table_name.clone());
+            }
+        }
+
+        let snapshot = PostgresSource::export_snapshot(&self.uri, &self.slot_name).await?;
+        let mut copies = Vec::with_capacity(table_schemas.len());
+        for (schema, initial_copy_resources) in table_schemas.into_iter().zip(initial_copies) {
+            let mut copy_source = PostgresSource::new(&self.uri, None, None, false).await?;
+            let row_filter = self
+                .source
+                .get_row_filter(&self.publication, schema.src_table_id)
+                .await?;
+            // Snapshot has to be imported while it's still exported, so open the copy stream before spawning the copy.
+            let copy_key_index =
+                get_copy_key_index(&schema).filter(|_| self.initial_copy_checkpoint);
+            let key_order = copy_key_index.map(|key_index| CopyKeyOrder {
+                key_column: schema.column_schemas[key_index].name.clone(),
+                start_after: None,
+            });
+            let (stream, row_count) = copy_source
+                .get_table_copy_stream_in_snapshot(
+                    &schema.table_name,
+                    &schema.column_schemas,
+                    row_filter.as_deref(),
+                    key_order.as_ref(),
+                    &snapshot,
+                )
+                .await?;
+            let progress_reporter = CopyProgressReporter::new(
+                initial_copy_resources.progress_tx,
+                Some(initial_copy_resources.checkpoint_path),
+                /*estimated_total_rows=*/ Some(row_count as u64),
+            );
+            let checkpointer = copy_key_index.map(|key_index| {
+                CopyCheckpointer::new(
+                    key_index,
+                    InitialCopyPosition::default(),
+                    INITIAL_COPY_CHECKPOINT_ROWS,
+                )
+            });
+            let stream_source = CopyStreamSource {
+                uri: self.uri.clone(),
+                row_filter,
+            };
+            copies.push((
+                copy_source,
+                schema,
+                stream,
+                stream_source,
+                initial_copy_resources.event_sender,
+                progress_reporter,
+                checkpointer,
+                initial_copy_resources.completion_rx,
+            ));
+        }
+        // All copies have imported the snapshot, release the temporary slot.
+        let consistent_point = snapshot.consistent_point;
+        drop(snapshot);
+
+        for (
+            copy_source,
+            schema,
+            stream,
+            stream_source,
+            event_sender,
+            progress_reporter,
+            checkpointer,
+            completion_rx,
+        ) in copies
+        {
+            // Retried copies open a new source snapshot, so the table is no longer consistent with the others.
+            let attempt = CopyAttempt {
+                copy_source,
+                stream,
+                start_lsn: consistent_point,
+            };
+            tokio::spawn(run_initial_copy(
+                schema,
+                Some(attempt),
+                stream_source,
+                event_sender,
+                progress_reporter,
+                checkpointer,
+                completion_rx,
+            ));
+        }
+
+        Ok(added_tables)
+    }
+
+    /// Fetch schema of the table to add, and check the table is published by the publication.
+    async fn fetch_table_schema_to_add(
+        &self,
+        table_name: &str,
+        allow_replica_identity_full: bool,
+    ) -> Result<TableSchema> {
+        // Respect the existing replica identity if it identifies rows, only fallback to FULL with opt-in.
+        // FULL is the most verbose replica identity, so it never downgrades the source table.
+        let table_schema = match self
+            .source
+            .fetch_table_schema(None, Some(table_name), Some(&self.publication))
+            .await
+        {
+            Err(PostgresSourceError::ReplicationClient(
+                ReplicationClientError::ReplicaIdentityNotSupported(replica_identity),
+            )) if allow_replica_identity_full => {
+                warn!(
+                    table_name,
+                    replica_identity, "replica identity has no usable key, altering to FULL"
+                );
+                self.alter_table_replica_identity_full(table_name).await?;
+                self.source
+                    .fetch_table_schema(None, Some(table_name), Some(&self.publication))
+                    .await?
+            }
+            res => res?,
+        };
+        // Replicated tables are keyed by source table id, even if they belong to different databases.
+        if self.table_states.contains_key(&table_schema.src_table_id) {
+            return Err(
+                PostgresSourceError::from(ReplicationClientError::TableAlreadyReplicated(
+                    table_schema.table_name,
+                ))
+                .into(),
+            );
+        }
+        if !self.owns_publication
+            && !self
+                .source
+                .is_table_in_publication(&self.publication, &table_schema.table_name)
+                .await?
+        {
+            return Err(
+                PostgresSourceError::from(ReplicationClientError::TableNotInPublication(
+                    table_schema.table_name,
+                    self.publication.clone(),
+                ))
+                .into(),
+            );
+        }
+        Ok(table_schema)
+    }
+
     /// Remove the given table from connection.
     pub async fn drop_table(&mut self, src_table_id: u32) -> Result<()> {
         debug!(src_table_id, "dropping table");
@@ -459,7 +1002,7 @@ impl ReplicationConnection {
             .unwrap()
             .schema
             .table_name
-            .to_string();
+            .clone();
 
         // Remove table from publication as the first step, to prevent further events.
         self.remove_table_from_publication(&table_name).await?;
@@ -469,8 +1012,51 @@ impl ReplicationConnection {
         Ok(())
     }
 
-    pub fn check_table_belongs_to_source(&self, uri: &str) -> bool {
-        self.uri == uri
+    /// Set policy for rows of the given table which fail conversion, effective for subsequent changes.
+    pub async fn set_conversion_error_policy(
+        &self,
+        src_table_id: SrcTableId,
+        policy: ConversionErrorPolicy,
+    ) -> Result<()> {
+        self.cmd_tx
+            .send(Command::SetConversionErrorPolicy {
+                src_table_id,
+                policy,
+            })
+            .await
+            .map_err(|e| Error::new(ErrorKind::BrokenPipe, e.to_string()))?;
+        Ok(())
+    }
+
+    /// Read rows of the given table which failed conversion and are kept in its dead letter file.
+    pub async fn read_dead_letters(
+        &self,
+        src_table_id: SrcTableId,
+    ) -> Result<Vec<DeadLetterRecord>> {
+        let table_state = self.table_states.get(&src_table_id).ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("table {src_table_id} is not replicated"),
+            )
+        })?;
+        DeadLetterStore::load_from(&table_state.dead_letter_path).await
+    }
+
+    /// Replay dead letters of the given table with its current schema, e.g. after the column type has been fixed.
+    /// Returns number of replayed row events, rows which still fail conversion are kept.
+    pub async fn replay_dead_letters(&self, src_table_id: SrcTableId) -> Result<usize> {
+        let (tx, rx) = oneshot::channel();
+        self.cmd_tx
+            .send(Command::ReplayDeadLetters { src_table_id, tx })
+            .await
+            .map_err(|e| Error::new(ErrorKind::BrokenPipe, e.to_string()))?;
+        rx.await
+            .map_err(|e| Error::new(ErrorKind::BrokenPipe, e.to_string()))?
+    }
+
+    /// Connection string the replication connection is created with.
+    pub fn src_uri(&self) -> &str {
+        &self.src_uri
     }
 
     /// Wait for all pending retry operations to complete.
@@ -490,6 +1076,9 @@ impl ReplicationConnection {
     pub fn shutdown(mut self) -> JoinHandle<Result<()>> {
         tokio::spawn(async move {
             debug!("shutting down replication connection");
+            for handle in self.background_handles.drain(..) {
+                handle.abort();
+            }
             if self.replication_started {
                 if let Err(e) = self.cmd_tx.send(Command::Shutdown).await {
                     warn!(error = ?e, "failed to send shutdown command");
@@ -512,17 +1101,433 @@ impl ReplicationConnection {
     }
 }
 
+/// State of the replication task, which is kept across reconnects.
+struct ReplicationTaskState {
+    sink: Sink,
+    cmd_rx: mpsc::Receiver<Command>,
+    /// Schemas for all replicated tables, used to re-register tables to a new cdc stream.
+    table_schemas: HashMap<SrcTableId, TableSchema>,
+    flush_lsn_rxs: HashMap<SrcTableId, watch::Receiver<u64>>,
+    /// Failed tables no longer flush, so they don't hold back the confirmed flush LSN.
+    table_error_rxs: HashMap<SrcTableId, watch::Receiver<Option<String>>>,
+}
+
+impl ReplicationTaskState {
+    /// Get the LSN which could be confirmed to the source, i.e. the min confirmable LSN over tables which haven't failed.
+    fn get_confirmed_lsn(&self) -> Option<u64> {
+        self.flush_lsn_rxs
+            .iter()
+            .filter(|(src_table_id, _)| {
+                self.table_error_rxs
+                    .get(src_table_id)
+                    .is_none_or(|rx| rx.borrow().is_none())
+            })
+            .map(|(src_table_id, rx)| self.sink.get_confirmable_lsn(*src_table_id, *rx.borrow()))
+            .min()
+    }
+
+    /// Apply the given command, and return whether it's a shutdown request.
+    async fn handle_command(&mut self, cmd: Command, stream: Option<Pin<&mut CdcStream>>) -> bool {
+        match cmd {
+            Command::AddTable {
+                src_table_id,
+                schema,
+                event_sender,
+                commit_lsn_tx,
+                flush_lsn_rx,
+                table_error_rx,
+                prepared_gids_rx,
+                conversion_error_policy,
+                dead_letter_path,
+            } => {
+                self.sink.add_table(
+                    src_table_id,
+                    event_sender,
+                    commit_lsn_tx,
+                    &schema,
+                    conversion_error_policy,
+                    DeadLetterStore::new(dead_letter_path),
+                    prepared_gids_rx,
+                );
+                self.flush_lsn_rxs.insert(src_table_id, flush_lsn_rx);
+                self.table_error_rxs.insert(src_table_id, table_error_rx);
+                if let Some(stream) = stream {
+                    stream.add_table_schema(schema.clone());
+                }
+                self.table_schemas.insert(src_table_id, schema);
+            }
+            Command::DropTable { src_table_id } => {
+                self.sink.drop_table(src_table_id);
+                self.flush_lsn_rxs.remove(&src_table_id);
+                self.table_error_rxs.remove(&src_table_id);
+                if let Some(stream) = stream {
+                    stream.remove_table_schema(src_table_id);
+                }
+                self.table_schemas.remove(&src_table_id);
+            }
+            Command::SetConversionErrorPolicy {
+                src_table_id,
+                policy,
+            } => {
+                self.sink.set_conversion_error_policy(src_table_id, policy);
+            }
+            Command::ReplayDeadLetters { src_table_id, tx } => {
+                let _ = tx.send(self.sink.replay_dead_letters(src_table_id).await);
+            }
+            Command::Shutdown => {
+                debug!("received shutdown command");
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Opened copy stream for an initial copy attempt.
+struct CopyAttempt {
+    copy_source: PostgresSource,
+    stream: TableCopyStream,
+    /// LSN of the source snapshot which rows are copied from.
+    start_lsn: PgLsn,
+}
+
+/// Source to open copy streams from, when a failed initial copy is retried.
+struct CopyStreamSource {
+    uri: String,
+    /// Row filter of the publication for the table, if any.
+    row_filter: Option<String>,
+}
+
+impl CopyStreamSource {
+    /// Open a copy stream from a new source snapshot, which resumes after the last checkpointed key if checkpointed.
+    async fn open(
+        &self,
+        schema: &TableSchema,
+        checkpointer: Option<&CopyCheckpointer>,
+    ) -> Result<CopyAttempt> {
+        let mut copy_source = PostgresSource::new(&self.uri, None, None, false).await?;
+        let key_order = checkpointer.map(|checkpointer| CopyKeyOrder {
+            key_column: schema.column_schemas[checkpointer.key_index()].name.clone(),
+            start_after: checkpointer.last_key(),
+        });
+        let (stream, start_lsn) = copy_source
+            .get_table_copy_stream(
+                &schema.table_name,
+                &schema.column_schemas,
+                self.row_filter.as_deref(),
+                key_order.as_ref(),
+            )
+            .await?;
+        Ok(CopyAttempt {
+            copy_source,
+            stream,
+            start_lsn,
+        })
+    }
+}
+
+/// Copy the table, starting with the given copy attempt if any, and finish the initial copy at its start LSN.
+/// A failed copy is retried from the last checkpoint, or redone if there's none; the table fails once all attempts
+/// fail, which is reported through table status.
+async fn run_initial_copy(
+    schema: TableSchema,
+    mut attempt: Option<CopyAttempt>,
+    stream_source: CopyStreamSource,
+    event_sender: mpsc::Sender<TableEvent>,
+    mut progress_reporter: CopyProgressReporter,
+    mut checkpointer: Option<CopyCheckpointer>,
+    completion_rx: watch::Receiver<bool>,
+) {
+    let src_table_id = schema.src_table_id;
+    let mut attempt_count = 0;
+    loop {
+        attempt_count += 1;
+        let res = match attempt.take() {
+            Some(attempt) => Ok(attempt),
+            None => stream_source.open(&schema, checkpointer.as_ref()).await,
+        };
+        let res = match res {
+            Ok(attempt) => {
+                copy_table(
+                    &schema,
+                    attempt,
+                    &event_sender,
+                    &mut progress_reporter,
+                    checkpointer.as_mut(),
+                    completion_rx.clone(),
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        let Err(e) = res else {
+            return;
+        };
+        error!(error = ?e, table_id = src_table_id, attempt = attempt_count, "failed to copy table");
+        progress_reporter.fail(e.to_string()).await;
+        if attempt_count >= INITIAL_COPY_MAX_ATTEMPTS {
+            if let Err(e) = event_sender
+                .send(TableEvent::FailTable {
+                    error: format!("initial copy failed after {attempt_count} attempts: {e}"),
+                })
+                .await
+            {
+                error!(error = ?e, table_id = src_table_id, "failed to send FailTable event");
+            }
+            return;
+        }
+        // Sender is dropped if the table is dropped, which needs no more copy.
+        if event_sender
+            .send(TableEvent::AbortInitialCopy)
+            .await
+            .is_err()
+        {
+            warn!(
+                table_id = src_table_id,
+                "table dropped before initial copy finishes"
+            );
+            return;
+        }
+        tokio::time::sleep(INITIAL_COPY_RETRY_DELAY).await;
+    }
+}
+
+/// Copy rows from the stream, and mark the copy finished once copied rows have been persisted.
+/// An error is only returned before the copy finishes, so the copy could be retried.
+async fn copy_table(
+    schema: &TableSchema,
+    attempt: CopyAttempt,
+    event_sender: &mpsc::Sender<TableEvent>,
+    progress_reporter: &mut CopyProgressReporter,
+    mut checkpointer: Option<&mut CopyCheckpointer>,
+    mut completion_rx: watch::Receiver<bool>,
+) -> Result<()> {
+    let CopyAttempt {
+        mut copy_source,
+        stream,
+        start_lsn,
+    } = attempt;
+    let src_table_id = schema.src_table_id;
+    if let Err(e) = progress_reporter.start(start_lsn.into()).await {
+        warn!(error = ?e, table_id = src_table_id, "failed to checkpoint initial copy");
+    }
+    if let Some(checkpointer) = checkpointer.as_deref_mut() {
+        checkpointer.restart(start_lsn.into());
+    }
+    copy_table_stream_impl(
+        schema.clone(),
+        stream,
+        event_sender,
+        progress_reporter,
+        checkpointer,
+    )
+    .await?;
+    copy_source.commit_transaction().await?;
+
+    if let Err(e) = event_sender
+        .send(TableEvent::FinishInitialCopy {
+            start_lsn: start_lsn.into(),
+        })
+        .await
+    {
+        error!(error = ?e, table_id = src_table_id, "failed to send FinishTableCopy command");
+        return Ok(());
+    }
+    // Sender is dropped if the table is dropped before the copy gets persisted.
+    if completion_rx
+        .wait_for(|persisted| *persisted)
+        .await
+        .is_err()
+    {
+        warn!(
+            table_id = src_table_id,
+            "table dropped before initial copy is persisted"
+        );
+        return Ok(());
+    }
+    // Finished copy is persisted with the table, which recovery checks if the local checkpoint is left unfinished.
+    if let Err(e) = progress_reporter.finish().await {
+        warn!(error = ?e, table_id = src_table_id, "failed to checkpoint finished initial copy");
+    }
+    Ok(())
+}
+
+/// Get index of the key column to copy rows in the order of, so an interrupted copy could resume after the last copied
+/// key; only tables keyed by a single integer column are copied in key order, if initial copy checkpointing is enabled.
+fn get_copy_key_index(schema: &TableSchema) -> Option<usize> {
+    let LookupKey::Key { columns, .. } = &schema.lookup_key else {
+        return None;
+    };
+    let [key_column] = columns.as_slice() else {
+        return None;
+    };
+    schema.column_schemas.iter().position(|column| {
+        &column.name == key_column
+            && (column.typ == Type::INT2 || column.typ == Type::INT4 || column.typ == Type::INT8)
+    })
+}
+
+/// Periodically check health of the replication slot, and publish it as part of replication status.
+#[tracing::instrument(name = "replication_slot_monitor", skip_all)]
+async fn run_slot_monitor(
+    postgres_source: Arc<PostgresSource>,
+    slot_name: String,
+    status_tx: watch::Sender<ReplicationConnectionStatus>,
+) {
+    let mut interval = tokio::time::interval_at(
+        tokio::time::Instant::now() + SLOT_HEALTH_CHECK_INTERVAL,
+        SLOT_HEALTH_CHECK_INTERVAL,
+    );
+    loop {
+        interval.tick().await;
+        if let Err(e) = refresh_slot_status(&postgres_source, &slot_name, &status_tx).await {
+            warn!(error = ?e, "failed to check replication slot health");
+        }
+    }
+}
+
+/// Periodically write to the heartbeat table, so the source sends keepalives with advancing WAL position
+/// even if the replicated tables are idle.
+#[tracing::instrument(name = "replication_heartbeat", skip_all)]
+async fn run_heartbeat(postgres_client: Arc<Client>, heartbeat_table: TableName) {
+    let query = format!(
+        "INSERT INTO {} (id, last_heartbeat) VALUES (1, now()) \
+         ON CONFLICT (id) DO UPDATE SET last_heartbeat = excluded.last_heartbeat;",
+        heartbeat_table.as_quoted_identifier()
+    );
+    let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = postgres_client.simple_query(&query).await {
+            warn!(error = ?e, "failed to write heartbeat");
+        }
+    }
+}
+
+/// Fetch health of the replication slot, and publish it as part of replication status.
+async fn refresh_slot_status(
+    postgres_source: &PostgresSource,
+    slot_name: &str,
+    status_tx: &watch::Sender<ReplicationConnectionStatus>,
+) -> Result<()> {
+    let slot_status = match postgres_source.fetch_slot_status().await? {
+        Some(slot_status) => ReplicationSlotStatus {
+            slot_name: slot_name.to_string(),
+            exists: true,
+            wal_status: slot_status.wal_status,
+            restart_lsn: slot_status.restart_lsn.map(u64::from),
+            confirmed_flush_lsn: slot_status.confirmed_flush_lsn.map(u64::from),
+            retained_wal_bytes: slot_status.retained_wal_bytes,
+        },
+        None => ReplicationSlotStatus {
+            slot_name: slot_name.to_string(),
+            ..Default::default()
+        },
+    };
+    if slot_status.is_lost() {
+        error!(
+            slot_name,
+            wal_status = ?slot_status.wal_status,
+            "replication slot is lost, replicated tables need to be resynced"
+        );
+    }
+    status_tx.send_modify(|status| status.slot_status = Some(slot_status));
+    Ok(())
+}
+
+/// Reason for the replication event loop to exit.
+enum EventLoopExit {
+    /// Shutdown requested.
+    Shutdown,
+    /// Replication connection or stream terminated, with the reason.
+    Disconnected(String),
+}
+
+/// Run replication, and reconnect with exponential backoff whenever the event loop terminates unexpectedly.
+/// After reconnect, cdc stream resumes from the slot's confirmed flush LSN, and all tables are re-registered.
+#[tracing::instrument(name = "replication_supervisor", skip_all)]
+async fn run_replication_supervisor(
+    uri: String,
+    mut cfg: CdcStreamConfig,
+    mut state: ReplicationTaskState,
+    postgres_source: Arc<PostgresSource>,
+    status_tx: watch::Sender<ReplicationConnectionStatus>,
+) -> Result<()> {
+    let backoff_builder = ExponentialBuilder::default()
+        .with_min_delay(RECONNECT_MIN_DELAY)
+        .with_max_delay(RECONNECT_MAX_DELAY)
+        .with_jitter()
+        .without_max_times();
+    let mut backoff = backoff_builder.build();
+
+    loop {
+        let res = match ReplicationClient::connect(&uri, true).await {
+            Ok((client, connection)) => {
+                run_event_loop(
+                    client,
+                    cfg.clone(),
+                    connection,
+                    &mut state,
+                    postgres_source.clone(),
+                    &status_tx,
+                )
+                .await
+            }
+            Err(e) => Err(PostgresSourceError::from(e).into()),
+        };
+        let reason = match res {
+            Ok(EventLoopExit::Shutdown) => return Ok(()),
+            Ok(EventLoopExit::Disconnected(reason)) => reason,
+            Err(e) => e.to_string(),
+        };
+
+        // Start a new round of backoff if the last attempt managed to connect.
+        if status_tx.borrow().connected {
+            backoff = backoff_builder.build();
+        }
+        state.sink.handle_disconnect().await;
+        status_tx.send_modify(|status| {
+            status.connected = false;
+            status.reconnect_attempts += 1;
+            status.last_error = Some(reason.clone());
+        });
+        let delay = backoff.next().unwrap_or(RECONNECT_MAX_DELAY);
+        warn!(%reason, ?delay, "replication disconnected, reconnecting");
+
+        // Keep serving commands while waiting to reconnect.
+        let sleep = tokio::time::sleep(delay);
+        pin!(sleep);
+        loop {
+            tokio::select! {
+                _ = &mut sleep => break,
+                Some(cmd) = state.cmd_rx.recv() => {
+                    if state.handle_command(cmd, None).await {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        // Resume from the slot's confirmed flush LSN.
+        match postgres_source.fetch_confirmed_flush_lsn().await {
+            Ok(lsn) => cfg.confirmed_flush_lsn = lsn,
+            Err(e) => warn!(error = ?e, "failed to fetch confirmed flush lsn"),
+        }
+    }
+}
+
 #[tracing::instrument(name = "replication_event_loop", skip_all)]
 async fn run_event_loop(
     client: ReplicationClient,
     cfg: CdcStreamConfig,
-    connection: Connection<Socket, NoTlsStream>,
-    mut sink: Sink,
-    mut cmd_rx: mpsc::Receiver<Command>,
+    connection: PostgresConnection,
+    state: &mut ReplicationTaskState,
     postgres_source: Arc<PostgresSource>,
-) -> Result<()> {
+    status_tx: &watch::Sender<ReplicationConnectionStatus>,
+) -> Result<EventLoopExit> {
     pin!(connection);
 
+    let publication = cfg.publication.clone();
     // Create stream while driving connection
     let stream = tokio::select! {
         s = PostgresSource::create_cdc_stream(client, cfg) => s?,
@@ -534,23 +1539,26 @@ async fn run_event_loop(
     // Now run the main event loop
     pin!(stream);
 
+    // Register all known tables to the new stream.
+    for schema in state.table_schemas.values() {
+        stream.as_mut().add_table_schema(schema.clone());
+    }
+    status_tx.send_modify(|status| {
+        if status.reconnect_attempts > 0 {
+            status.reconnect_count += 1;
+        }
+        status.connected = true;
+        status.reconnect_attempts = 0;
+    });
+
     debug!("replication event loop started");
 
     let mut status_interval = tokio::time::interval(Duration::from_secs(10));
-    let mut flush_lsn_rxs: HashMap<SrcTableId, watch::Receiver<u64>> = HashMap::new();
 
     loop {
         tokio::select! {
             _ = status_interval.tick() => {
-                let mut confirmed_lsn: Option<u64> = None;
-                for rx in flush_lsn_rxs.values() {
-                    let lsn = *rx.borrow();
-                    confirmed_lsn = Some(match confirmed_lsn {
-                        Some(v) => v.min(lsn),
-                        None => lsn,
-                    });
-                }
-                let lsn_to_send = confirmed_lsn.map(PgLsn::from).unwrap_or(PgLsn::from(0));
+                let lsn_to_send = state.get_confirmed_lsn().map(PgLsn::from).unwrap_or(PgLsn::from(0));
                 if let Err(e) = stream
                     .as_mut()
                     .send_status_update(lsn_to_send)
@@ -559,26 +1567,15 @@ async fn run_event_loop(
                     error!(error = ?e, "failed to send status update");
                 }
             },
-            Some(cmd) = cmd_rx.recv() => match cmd {
-                Command::AddTable { src_table_id, schema, event_sender, commit_lsn_tx, flush_lsn_rx } => {
-                    sink.add_table(src_table_id, event_sender, commit_lsn_tx, &schema);
-                    flush_lsn_rxs.insert(src_table_id, flush_lsn_rx);
-                    stream.as_mut().add_table_schema(schema);
-                }
-                Command::DropTable { src_table_id } => {
-                    sink.drop_table(src_table_id);
-                    flush_lsn_rxs.remove(&src_table_id);
-                    stream.as_mut().remove_table_schema(src_table_id);
-                }
-                Command::Shutdown => {
-                    debug!("received shutdown command");
-                    break;
+            Some(cmd) = state.cmd_rx.recv() => {
+                if state.handle_command(cmd, Some(stream.as_mut())).await {
+                    return Ok(EventLoopExit::Shutdown);
                 }
             },
             event = StreamExt::next(&mut stream) => {
                 let Some(event_result) = event else {
                     error!("replication stream ended unexpectedly");
-                    break;
+                    return Ok(EventLoopExit::Disconnected("replication stream ended unexpectedly".to_string()));
                 };
 
                 match event_result {
@@ -592,25 +1589,32 @@ async fn run_event_loop(
                     }
                     Err(e) => {
                         error!(error = ?e, "cdc stream error");
-                        break;
+                        return Ok(EventLoopExit::Disconnected(e.to_string()));
                     }
                     Ok(event) => {
-                        let res = sink.process_cdc_event(event).await.unwrap();
-                        if let Some(SchemaChangeRequest(src_table_id)) = res {
-                            let table_schema = postgres_source.fetch_table_schema(Some(src_table_id), None, None).await?;
-                            sink.alter_table(src_table_id, &table_schema).await;
-                            stream.as_mut().add_table_schema(table_schema);
+                        // Holds back the replication slot, so the event is replayed after reconnect.
+                        let res = match state.sink.process_cdc_event(event).await {
+                            Ok(res) => res,
+                            Err(e) => {
+                                error!(error = %e, "failed to process cdc event");
+                                return Ok(EventLoopExit::Disconnected(e.to_string()));
+                            }
+                        };
+                        if let Some(SchemaChangeRequest { src_table_id, relation_columns }) = res {
+                            // The catalog could already reflect later schema changes than rows being decoded, so columns come from the relation message.
+                            let catalog_schema = postgres_source.fetch_table_schema(Some(src_table_id), None, Some(&publication)).await?;
+                            let table_schema = build_table_schema_from_relation(&relation_columns, &state.table_schemas[&src_table_id], &catalog_schema);
+                            state.sink.alter_table(src_table_id, &table_schema).await;
+                            stream.as_mut().add_table_schema(table_schema.clone());
+                            state.table_schemas.insert(src_table_id, table_schema);
                         }
                     }
                 }
             }
             _ = &mut connection => {
                 error!("replication connection closed");
-                break;
+                return Ok(EventLoopExit::Disconnected("replication connection closed".to_string()));
             }
         }
     }
-
-    debug!("replication event loop stopped");
-    Ok(())
 }
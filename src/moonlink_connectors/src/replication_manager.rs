@@ -1,11 +1,15 @@
+use crate::pg_replicate::dead_letter::{ConversionErrorPolicy, DeadLetterRecord};
+use crate::pg_replicate::moonlink_sink::LogicalMessage;
 use crate::pg_replicate::table::SrcTableId;
+use crate::pg_replicate::table_init::TableOptions;
 use crate::ReplicationConnection;
+use crate::ReplicationOptions;
 use crate::Result;
-use moonlink::FileSystemConfig;
-use moonlink::TableStatusReader;
 use moonlink::{MoonlinkTableConfig, ObjectStorageCache, ReadStateManager, TableEventManager};
+use moonlink::{ReplicationConnectionStatus, TableStatusReader};
 use std::collections::HashMap;
 use std::hash::Hash;
+use tokio::sync::broadcast;
 use tokio::task::JoinHandle;
 use tracing::debug;
 
@@ -15,10 +19,13 @@ use tracing::debug;
 /// provides a single entry point to add new tables to a running
 /// replication. A new replication will automatically be started when a
 /// table is added for a URI that is not currently being replicated.
+///
+/// URIs with `share_slot` enabled, which resolve to the same source database, share one replication connection,
+/// so tables of multiple databases are decoded by a single walsender; see [`ReplicationOptions::connection_key`].
 pub struct ReplicationManager<T: Clone + Eq + Hash + std::fmt::Display> {
-    /// Maps from uri to replication connection.
+    /// Maps from connection key to replication connection.
     connections: HashMap<String, ReplicationConnection>,
-    /// Maps from mooncake table id to (uri, source table id).
+    /// Maps from mooncake table id to (connection key, source table id).
     table_info: HashMap<T, (String, SrcTableId)>,
     /// Base directory for mooncake tables.
     table_base_path: String,
@@ -53,7 +60,10 @@ impl<T: Clone + Eq + Hash + std::fmt::Display> ReplicationManager<T> {
     ///
     /// # Arguments
     ///
-    /// * secret_entry: secret necessary to access object storage, use local filesystem if not assigned.
+    /// * table_options: options to create mooncake table with, i.e. filesystem and catalog of the iceberg table.
+    ///
+    /// Source table replica identity is only altered to FULL, if the existing one doesn't identify rows and
+    /// `allow_replica_identity_full` is enabled in `src_uri`; see [`ReplicationOptions`].
     #[allow(clippy::too_many_arguments)]
     pub async fn add_table(
         &mut self,
@@ -62,50 +72,119 @@ impl<T: Clone + Eq + Hash + std::fmt::Display> ReplicationManager<T> {
         database_id: u32,
         table_id: u32,
         table_name: &str,
-        filesystem_config: Option<FileSystemConfig>,
+        table_options: TableOptions,
         is_recovery: bool,
     ) -> Result<MoonlinkTableConfig> {
         debug!(%src_uri, table_name, "adding table through manager");
-        if !self.connections.contains_key(src_uri) {
-            debug!(%src_uri, "creating replication connection");
-            // Lazily create the directory that will hold all tables.
-            // This will not overwrite any existing directory.
-            tokio::fs::create_dir_all(&self.table_base_path).await?;
-            let base_path = tokio::fs::canonicalize(&self.table_base_path).await?;
-            let replication_connection = ReplicationConnection::new(
-                src_uri.to_string(),
-                database_id,
-                base_path.to_str().unwrap().to_string(),
-                self.table_temp_files_directory.clone(),
-                self.object_storage_cache.clone(),
-            )
-            .await?;
-            self.connections
-                .insert(src_uri.to_string(), replication_connection);
-        }
-        let replication_connection = self.connections.get_mut(src_uri).unwrap();
+        let (_, replication_options) = ReplicationOptions::split_from_uri(src_uri)?;
+        let connection_key = Self::connection_key(src_uri);
+        let replication_connection = self.get_or_create_connection(src_uri).await?;
 
         let (src_table_id, moonlink_table_config) = replication_connection
             .add_table(
                 table_name,
                 &mooncake_table_id,
+                database_id,
                 table_id,
-                filesystem_config,
+                table_options,
                 is_recovery,
+                replication_options.allow_replica_identity_full,
             )
             .await?;
         self.table_info
-            .insert(mooncake_table_id, (src_uri.to_string(), src_table_id));
+            .insert(mooncake_table_id, (connection_key, src_table_id));
 
         debug!(src_table_id, "table added through manager");
 
         Ok(moonlink_table_config)
     }
 
+    /// Add tables of the same source together, they're copied within a single snapshot so reads across them are consistent.
+    ///
+    /// # Arguments
+    ///
+    /// * tables: mooncake table id, table id and source table name of each table to add.
+    pub async fn add_tables(
+        &mut self,
+        src_uri: &str,
+        database_id: u32,
+        tables: Vec<(T, u32, String)>,
+        table_options: TableOptions,
+    ) -> Result<Vec<MoonlinkTableConfig>> {
+        debug!(%src_uri, table_count = tables.len(), "adding tables through manager");
+        let (_, replication_options) = ReplicationOptions::split_from_uri(src_uri)?;
+        let connection_key = Self::connection_key(src_uri);
+        let replication_connection = self.get_or_create_connection(src_uri).await?;
+
+        let tables = tables
+            .into_iter()
+            .map(|(mooncake_table_id, table_id, table_name)| {
+                (table_name, mooncake_table_id, table_id)
+            })
+            .collect::<Vec<_>>();
+        let added_tables = replication_connection
+            .add_tables(
+                database_id,
+                &tables,
+                table_options,
+                replication_options.allow_replica_identity_full,
+            )
+            .await?;
+
+        let mut moonlink_table_configs = Vec::with_capacity(added_tables.len());
+        for ((_, mooncake_table_id, _), (src_table_id, moonlink_table_config)) in
+            tables.into_iter().zip(added_tables)
+        {
+            self.table_info
+                .insert(mooncake_table_id, (connection_key.clone(), src_table_id));
+            moonlink_table_configs.push(moonlink_table_config);
+        }
+
+        debug!("tables added through manager");
+
+        Ok(moonlink_table_configs)
+    }
+
+    /// Get key of the replication connection for the source, fallback to the URI itself if it cannot be parsed,
+    /// which fails at connection creation.
+    fn connection_key(src_uri: &str) -> String {
+        ReplicationOptions::connection_key(src_uri).unwrap_or_else(|_| src_uri.to_string())
+    }
+
+    /// Get the replication connection for the source, create one if it doesn't exist.
+    async fn get_or_create_connection(
+        &mut self,
+        src_uri: &str,
+    ) -> Result<&mut ReplicationConnection> {
+        let connection_key = Self::connection_key(src_uri);
+        if !self.connections.contains_key(&connection_key) {
+            debug!(%src_uri, "creating replication connection");
+            // Lazily create the directory that will hold all tables.
+            // This will not overwrite any existing directory.
+            tokio::fs::create_dir_all(&self.table_base_path).await?;
+            let base_path = tokio::fs::canonicalize(&self.table_base_path).await?;
+            let replication_connection = ReplicationConnection::new(
+                src_uri.to_string(),
+                base_path.to_str().unwrap().to_string(),
+                self.table_temp_files_directory.clone(),
+                self.object_storage_cache.clone(),
+            )
+            .await?;
+            self.connections
+                .insert(connection_key.clone(), replication_connection);
+        }
+        let replication_connection = self.connections.get_mut(&connection_key).unwrap();
+        if replication_connection.src_uri() != src_uri {
+            ReplicationOptions::check_shared_connection(replication_connection.src_uri(), src_uri)?;
+        }
+        Ok(replication_connection)
+    }
+
     pub async fn start_replication(&mut self, src_uri: &str) -> Result<()> {
-        assert!(self.connections.contains_key(src_uri));
+        let connection_key = Self::connection_key(src_uri);
+        assert!(self.connections.contains_key(&connection_key));
 
-        let connection = self.connections.get_mut(src_uri).unwrap();
+        let connection = self.connections.get_mut(&connection_key).unwrap();
         if !connection.replication_started() {
             connection.start_replication().await?;
         }
@@ -116,18 +195,19 @@ impl<T: Clone + Eq + Hash + std::fmt::Display> ReplicationManager<T> {
     /// If the table is not tracked, logs a message and returns successfully.
     /// Return whether the table is tracked by moonlink.
     pub async fn drop_table(&mut self, mooncake_table_id: T) -> Result<bool> {
-        let (table_uri, src_table_id) = match self.table_info.get(&mooncake_table_id) {
+        let (connection_key, src_table_id) = match self.table_info.get(&mooncake_table_id) {
             Some(info) => info.clone(),
             None => {
                 debug!("attempted to drop table that is not tracked by moonlink - table may already be dropped");
                 return Ok(false);
             }
         };
-        debug!(src_table_id, %table_uri, "dropping table through manager");
-        let repl_conn = self.connections.get_mut(&table_uri).unwrap();
+        debug!(src_table_id, %connection_key, "dropping table through manager");
+        let repl_conn = self.connections.get_mut(&connection_key).unwrap();
         repl_conn.drop_table(src_table_id).await?;
+        self.table_info.remove(&mooncake_table_id);
         if repl_conn.table_count() == 0 {
-            self.shutdown_connection(&table_uri);
+            self.shutdown_connection_by_key(&connection_key);
         }
 
         debug!(src_table_id, "table dropped through manager");
@@ -144,6 +224,51 @@ impl<T: Clone + Eq + Hash + std::fmt::Display> ReplicationManager<T> {
         connection.get_table_status_reader(src_table_id)
     }
 
+    /// Subscribe to logical decoding messages of the replication source identified by `src_uri`.
+    /// Return `None` if the source is not being replicated.
+    pub fn subscribe_logical_messages(
+        &self,
+        src_uri: &str,
+    ) -> Option<broadcast::Receiver<LogicalMessage>> {
+        self.connections
+            .get(&Self::connection_key(src_uri))
+            .map(|connection| connection.subscribe_logical_messages())
+    }
+
+    /// Get the replication connection status of the source identified by `src_uri`, with up-to-date slot health.
+    /// Return `None` if the source is not being replicated.
+    pub async fn get_replication_status(
+        &self,
+        src_uri: &str,
+    ) -> Option<Result<ReplicationConnectionStatus>> {
+        let connection = self.connections.get(&Self::connection_key(src_uri))?;
+        Some(connection.get_replication_status().await)
+    }
+
+    /// Set policy for rows of the given table which fail conversion.
+    pub async fn set_conversion_error_policy(
+        &self,
+        mooncake_table_id: &T,
+        policy: ConversionErrorPolicy,
+    ) -> Result<()> {
+        let (src_table_id, connection) = self.get_replication_connection(mooncake_table_id);
+        connection
+            .set_conversion_error_policy(src_table_id, policy)
+            .await
+    }
+
+    /// Read dead letters of the given table.
+    pub async fn read_dead_letters(&self, mooncake_table_id: &T) -> Result<Vec<DeadLetterRecord>> {
+        let (src_table_id, connection) = self.get_replication_connection(mooncake_table_id);
+        connection.read_dead_letters(src_table_id).await
+    }
+
+    /// Replay dead letters of the given table, and return the number of replayed row events.
+    pub async fn replay_dead_letters(&self, mooncake_table_id: &T) -> Result<usize> {
+        let (src_table_id, connection) = self.get_replication_connection(mooncake_table_id);
+        connection.replay_dead_letters(src_table_id).await
+    }
+
     pub fn get_table_status_readers(&self) -> Vec<&TableStatusReader> {
         let mut table_state_readers = vec![];
         for (_, cur_repl_conn) in self.connections.iter() {
@@ -165,14 +290,19 @@ impl<T: Clone + Eq + Hash + std::fmt::Display> ReplicationManager<T> {
     }
 
     /// Gracefully shutdown a replication connection by its URI.
+    /// A shared connection is shut down for all URIs sharing it.
     pub fn shutdown_connection(&mut self, uri: &str) {
+        self.shutdown_connection_by_key(&Self::connection_key(uri));
+    }
+
+    fn shutdown_connection_by_key(&mut self, connection_key: &str) {
         // Clean up completed shutdown handles first
         self.cleanup_completed_shutdowns();
 
-        if let Some(conn) = self.connections.remove(uri) {
+        if let Some(conn) = self.connections.remove(connection_key) {
             let shutdown_handle = conn.shutdown();
             self.shutdown_handles.push(shutdown_handle);
-            self.table_info.retain(|_, (u, _)| u != uri);
+            self.table_info.retain(|_, (key, _)| key != connection_key);
         }
     }
 
@@ -30,6 +30,9 @@ pub struct TableMetadataEntry {
 
 #[async_trait]
 pub trait MetadataStoreTrait: Send + Sync {
+    /// Get uri of the database which backs metadata storage, which could be shared with iceberg SQL catalog.
+    fn get_database_uri(&self) -> &str;
+
     /// Return whether metadata table exists.
     #[allow(async_fn_in_trait)]
     async fn metadata_table_exists(&self) -> Result<bool>;
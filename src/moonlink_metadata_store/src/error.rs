@@ -1,3 +1,4 @@
+use moonlink_postgres_tls::PostgresTlsError;
 use serde_json::Error as SerdeJsonError;
 use std::sync::Arc;
 use thiserror::Error;
@@ -8,6 +9,9 @@ pub enum Error {
     #[error("tokio postgres error: {source}")]
     TokioPostgres { source: Arc<TokioPostgresError> },
 
+    #[error("postgres tls error: {source}")]
+    PostgresTls { source: Arc<PostgresTlsError> },
+
     #[error("metadata operation row number doesn't match {0} vs {1}")]
     PostgresRowCountError(u32, u32),
 
@@ -43,6 +47,14 @@ impl From<TokioPostgresError> for Error {
     }
 }
 
+impl From<PostgresTlsError> for Error {
+    fn from(source: PostgresTlsError) -> Self {
+        Error::PostgresTls {
+            source: Arc::new(source),
+        }
+    }
+}
+
 impl From<sqlx::Error> for Error {
     fn from(source: sqlx::Error) -> Self {
         Error::Sqlx {
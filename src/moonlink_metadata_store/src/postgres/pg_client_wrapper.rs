@@ -1,4 +1,4 @@
-use tokio_postgres::{connect, Client, NoTls};
+use tokio_postgres::Client;
 
 use crate::error::Result;
 
@@ -12,7 +12,7 @@ pub(super) struct PgClientWrapper {
 
 impl PgClientWrapper {
     pub(super) async fn new(uri: &str) -> Result<Self> {
-        let (postgres_client, connection) = connect(uri, NoTls).await?;
+        let (postgres_client, connection) = moonlink_postgres_tls::connect(uri).await?;
 
         // Spawn connection driver in background to keep eventloop alive.
         let _pg_connection = tokio::spawn(async move {
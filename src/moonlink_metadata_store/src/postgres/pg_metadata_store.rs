@@ -24,6 +24,10 @@ pub struct PgMetadataStore {
 
 #[async_trait]
 impl MetadataStoreTrait for PgMetadataStore {
+    fn get_database_uri(&self) -> &str {
+        &self.uri
+    }
+
     async fn metadata_table_exists(&self) -> Result<bool> {
         let pg_client = PgClientWrapper::new(&self.uri).await?;
         utils::table_exists(&pg_client.postgres_client, MOONLINK_METADATA_TABLE).await
@@ -51,31 +55,44 @@ impl MetadataStoreTrait for PgMetadataStore {
                 LEFT JOIN secrets s
                     ON t.database_id = s.database_id
                     AND t.table_id = s.table_id
+                ORDER BY t.database_id, t.table_id, s.id
                 ",
                 &[],
             )
             .await?;
 
+        // A table could have multiple secrets, rows are ordered so secrets of the same table are adjacent.
         let mut metadata_entries = Vec::with_capacity(rows.len());
-        for row in rows {
+        let mut row_idx = 0;
+        while row_idx < rows.len() {
+            let row = &rows[row_idx];
             let database_id: u32 = row.get("database_id");
             let table_id: u32 = row.get("table_id");
             let src_table_name: String = row.get("table_name");
             let src_table_uri: String = row.get("uri");
             let serialized_config: serde_json::Value = row.get("config");
-            let secret_type: Option<String> = row.get("secret_type");
-            let secret_entry: Option<MoonlinkTableSecret> = {
-                secret_type.map(|secret_type| MoonlinkTableSecret {
-                    secret_type: MoonlinkTableSecret::convert_secret_type(&secret_type),
-                    key_id: row.get("key_id"),
-                    secret: row.get("secret"),
-                    endpoint: row.get("endpoint"),
-                    region: row.get("region"),
-                    project: row.get("project"),
-                })
-            };
+
+            let mut secret_entries = vec![];
+            while row_idx < rows.len()
+                && rows[row_idx].get::<_, u32>("database_id") == database_id
+                && rows[row_idx].get::<_, u32>("table_id") == table_id
+            {
+                let row = &rows[row_idx];
+                let secret_type: Option<String> = row.get("secret_type");
+                if let Some(secret_type) = secret_type {
+                    secret_entries.push(MoonlinkTableSecret {
+                        secret_type: MoonlinkTableSecret::convert_secret_type(&secret_type),
+                        key_id: row.get("key_id"),
+                        secret: row.get("secret"),
+                        endpoint: row.get("endpoint"),
+                        region: row.get("region"),
+                        project: row.get("project"),
+                    });
+                }
+                row_idx += 1;
+            }
             let moonlink_table_config =
-                config_utils::deserialze_moonlink_table_config(serialized_config, secret_entry)?;
+                config_utils::deserialze_moonlink_table_config(serialized_config, secret_entries)?;
 
             let metadata_entry = TableMetadataEntry {
                 database_id,
@@ -99,7 +116,7 @@ impl MetadataStoreTrait for PgMetadataStore {
         moonlink_table_config: MoonlinkTableConfig,
     ) -> Result<()> {
         let pg_client = PgClientWrapper::new(&self.uri).await?;
-        let (serialized_config, moonlink_table_secret) =
+        let (serialized_config, moonlink_table_secrets) =
             config_utils::parse_moonlink_table_config(moonlink_table_config)?;
 
         // Create metadata table if not exist.
@@ -142,7 +159,7 @@ impl MetadataStoreTrait for PgMetadataStore {
         }
 
         // Persist table secrets.
-        if let Some(table_secret) = moonlink_table_secret {
+        for table_secret in moonlink_table_secrets {
             let rows_affected = pg_client
                 .postgres_client
                 .execute(
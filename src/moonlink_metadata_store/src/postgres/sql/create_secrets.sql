@@ -5,7 +5,7 @@ CREATE TABLE secrets (
     id SERIAL PRIMARY KEY,          -- Unique row identifier
     database_id oid,                -- Database OID.
     table_id oid,                   -- Table OID.
-    secret_type TEXT,               -- One of (S3, GCS)
+    secret_type TEXT,               -- One of (S3, GCS, iceberg REST catalog, iceberg SQL catalog)
     key_id TEXT,        
     secret TEXT,        
     project TEXT,          -- (optional)  
@@ -3,7 +3,7 @@ CREATE TABLE secrets (
     id SERIAL PRIMARY KEY,          -- unique row identifier
     database_id INTEGER,            -- Database id.
     table_id INTEGER,               -- Table id.
-    secret_type TEXT,               -- One of (S3, GCS)
+    secret_type TEXT,               -- One of (S3, GCS, iceberg REST catalog, iceberg SQL catalog)
     key_id TEXT,
     secret TEXT,
     project TEXT,          -- (optional)  
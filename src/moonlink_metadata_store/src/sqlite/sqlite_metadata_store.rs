@@ -26,6 +26,10 @@ pub struct SqliteMetadataStore {
 
 #[async_trait]
 impl MetadataStoreTrait for SqliteMetadataStore {
+    fn get_database_uri(&self) -> &str {
+        &self.database_uri
+    }
+
     async fn metadata_table_exists(&self) -> Result<bool> {
         let sqlite_conn = SqliteConnWrapper::new(&self.database_uri).await?;
         utils::table_exists(&sqlite_conn.pool, MOONLINK_SCHEMA, MOONLINK_METADATA_TABLE).await
@@ -51,13 +55,17 @@ impl MetadataStoreTrait for SqliteMetadataStore {
             LEFT JOIN secrets s
                 ON t.database_id = s.database_id
                 AND t.table_id = s.table_id
+            ORDER BY t.database_id, t.table_id, s.id
             "#,
         )
         .fetch_all(&sqlite_conn.pool)
         .await?;
 
+        // A table could have multiple secrets, rows are ordered so secrets of the same table are adjacent.
         let mut metadata_entries = Vec::with_capacity(rows.len());
-        for row in rows {
+        let mut row_idx = 0;
+        while row_idx < rows.len() {
+            let row = &rows[row_idx];
             let database_id: u32 = row.get("database_id");
             let table_id: u32 = row.get("table_id");
             let src_table_name: String = row.get("table_name");
@@ -65,19 +73,28 @@ impl MetadataStoreTrait for SqliteMetadataStore {
             let serialized_config: String = row.get("config");
             let json_value: serde_json::Value = serde_json::from_str(&serialized_config)?;
 
-            let secret_type: Option<String> = row.get("secret_type");
-            let secret_entry: Option<MoonlinkTableSecret> =
-                secret_type.map(|secret_type| MoonlinkTableSecret {
-                    secret_type: MoonlinkTableSecret::convert_secret_type(&secret_type),
-                    key_id: row.get("key_id"),
-                    secret: row.get("secret"),
-                    endpoint: row.get("endpoint"),
-                    region: row.get("region"),
-                    project: row.get("project"),
-                });
+            let mut secret_entries = vec![];
+            while row_idx < rows.len()
+                && rows[row_idx].get::<u32, _>("database_id") == database_id
+                && rows[row_idx].get::<u32, _>("table_id") == table_id
+            {
+                let row = &rows[row_idx];
+                let secret_type: Option<String> = row.get("secret_type");
+                if let Some(secret_type) = secret_type {
+                    secret_entries.push(MoonlinkTableSecret {
+                        secret_type: MoonlinkTableSecret::convert_secret_type(&secret_type),
+                        key_id: row.get("key_id"),
+                        secret: row.get("secret"),
+                        endpoint: row.get("endpoint"),
+                        region: row.get("region"),
+                        project: row.get("project"),
+                    });
+                }
+                row_idx += 1;
+            }
 
             let moonlink_table_config =
-                config_utils::deserialze_moonlink_table_config(json_value, secret_entry)?;
+                config_utils::deserialze_moonlink_table_config(json_value, secret_entries)?;
 
             metadata_entries.push(TableMetadataEntry {
                 database_id,
@@ -99,7 +116,7 @@ impl MetadataStoreTrait for SqliteMetadataStore {
         table_uri: &str,
         moonlink_table_config: MoonlinkTableConfig,
     ) -> Result<()> {
-        let (serialized_config, moonlink_table_secret) =
+        let (serialized_config, moonlink_table_secrets) =
             config_utils::parse_moonlink_table_config(moonlink_table_config)?;
         let serialized_config = serde_json::to_string(&serialized_config)?;
 
@@ -143,8 +160,8 @@ impl MetadataStoreTrait for SqliteMetadataStore {
             return Err(Error::SqliteRowCountError(1, rows_affected as u32));
         }
 
-        // Insert into mooncake_secrets if present
-        if let Some(secret) = moonlink_table_secret {
+        // Insert into secrets table if any.
+        for secret in moonlink_table_secrets {
             let rows_affected = sqlx::query(
                 r#"
                 INSERT INTO secrets (database_id, table_id, secret_type, key_id, secret, endpoint, region, project)
@@ -62,6 +62,7 @@ pub(crate) fn get_moonlink_table_config() -> MoonlinkTableConfig {
             namespace: vec!["namespace".to_string()],
             table_name: "table".to_string(),
             filesystem_config: get_filesystem_config(),
+            ..Default::default()
         },
         ..Default::default()
     }
@@ -11,6 +11,7 @@ pub(crate) fn get_moonlink_table_config() -> MoonlinkTableConfig {
             filesystem_config: FileSystemConfig::FileSystem {
                 root_directory: "/tmp/test_warehouse_uri".to_string(),
             },
+            ..Default::default()
         },
         ..Default::default()
     }